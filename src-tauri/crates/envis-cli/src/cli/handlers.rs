@@ -1,6 +1,30 @@
 use envis_core::manager::app_config_manager::AppConfigManager;
+use envis_core::manager::env_serv_data_manager::EnvServDataManager;
 use envis_core::manager::environment_manager::EnvironmentManager;
-use envis_core::types::EnvironmentStatus;
+use envis_core::manager::project_manifest::{ManifestApplyAction, ProjectManifest};
+use envis_core::manager::service_manager::ServiceManager;
+use envis_core::manager::services::mongodb::MongodbService;
+use envis_core::manager::services::{DownloadStatus, ServiceRegistry};
+use envis_core::manager::system_info_manager::SystemInfoManager;
+use envis_core::types::{EnvironmentStatus, ServiceStatus, ServiceType};
+use indicatif::{ProgressBar, ProgressStyle};
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::time::Duration;
+
+/// `install` 命令支持的服务名
+const INSTALLABLE_SERVICES: &[(&str, ServiceType)] = &[
+    ("nodejs", ServiceType::Nodejs),
+    ("mongodb", ServiceType::Mongodb),
+    ("mysql", ServiceType::Mysql),
+    ("mariadb", ServiceType::Mariadb),
+    ("postgresql", ServiceType::Postgresql),
+    ("nginx", ServiceType::Nginx),
+    ("python", ServiceType::Python),
+];
+
+/// 轮询下载进度的间隔
+const INSTALL_POLL_INTERVAL: Duration = Duration::from_millis(200);
 
 fn persist_last_used_environment_ids(active_environment_ids: Vec<String>) -> Result<(), String> {
     let manager = AppConfigManager::global();
@@ -80,6 +104,15 @@ pub fn handle_use_early(target_str: &str) {
                     );
                 }
                 println!("✓ 成功激活环境: {}", target_str);
+                for hook in res.hook_results.iter().filter(|h| !h.success) {
+                    eprintln!(
+                        "警告: 钩子执行失败: {} ({})",
+                        hook.command,
+                        hook.error
+                            .clone()
+                            .unwrap_or_else(|| format!("退出码 {}", hook.exit_code.unwrap_or(-1)))
+                    );
+                }
             } else {
                 eprintln!("错误: {}", res.message);
                 std::process::exit(1);
@@ -144,3 +177,642 @@ pub fn handle_complete_use() {
         }
     }
 }
+
+/// 处理 `env` 命令: `env create <name>` / `env list` / `env activate <name_or_id>`
+pub fn handle_env(args: &[String]) {
+    match args.first().map(String::as_str) {
+        Some("create") => match args.get(1) {
+            Some(name) => handle_env_create(name),
+            None => {
+                eprintln!("错误: 必须指定环境名称");
+                eprintln!("用法: envis env create <name>");
+                std::process::exit(1);
+            }
+        },
+        Some("list") | Some("ls") => handle_env_list(),
+        // 与 `use` 行为一致：停用其他活跃环境、激活目标环境并写入 shell 配置
+        Some("activate") => match args.get(1) {
+            Some(target) => handle_use_early(target),
+            None => {
+                eprintln!("错误: 必须指定环境名称或 ID");
+                eprintln!("用法: envis env activate <name_or_id>");
+                std::process::exit(1);
+            }
+        },
+        Some(other) => {
+            eprintln!("未知的 env 子命令: {}", other);
+            eprintln!("用法: envis env <create|list|activate>");
+            std::process::exit(1);
+        }
+        None => {
+            eprintln!("用法: envis env <create|list|activate>");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_env_create(name: &str) {
+    let name = name.trim();
+    if name.is_empty() {
+        eprintln!("错误: 环境名称不能为空");
+        std::process::exit(1);
+    }
+
+    let manager = EnvironmentManager::global();
+    let manager = manager.lock().unwrap();
+
+    // `use` 按名称查找环境，重名会导致无法区分
+    match manager.get_all_environments() {
+        Ok(envs) if envs.iter().any(|e| e.name == name) => {
+            eprintln!("错误: 名称为 '{}' 的环境已存在", name);
+            std::process::exit(1);
+        }
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("错误: 无法获取环境列表: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    match manager.create_environment(name.to_string(), None) {
+        Ok(res) if res.success => {
+            let id = res
+                .data
+                .as_ref()
+                .and_then(|data| data["environment"]["id"].as_str())
+                .unwrap_or_default();
+            println!("✓ 成功创建环境: {}", name);
+            println!("ID: {}", id);
+        }
+        Ok(res) => {
+            eprintln!("错误: {}", res.message);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("错误: 创建环境失败: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// `env list`：输出包含完整 ID、名称与状态的表格
+fn handle_env_list() {
+    let manager = EnvironmentManager::global();
+    let manager = manager.lock().unwrap();
+    let envs = match manager.get_all_environments() {
+        Ok(envs) => envs,
+        Err(e) => {
+            eprintln!("错误: 获取环境列表失败: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if envs.is_empty() {
+        println!("(无环境)");
+        return;
+    }
+
+    let id_width = envs
+        .iter()
+        .map(|env| env.id.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("ID".len());
+    let name_width = envs
+        .iter()
+        .map(|env| env.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("NAME".len());
+
+    println!(
+        "{:<id_width$}  {:<name_width$}  STATUS",
+        "ID",
+        "NAME",
+        id_width = id_width,
+        name_width = name_width
+    );
+    for env in envs {
+        let status = match env.status {
+            EnvironmentStatus::Active => "active",
+            EnvironmentStatus::Inactive => "inactive",
+        };
+        println!(
+            "{:<id_width$}  {:<name_width$}  {}",
+            env.id,
+            env.name,
+            status,
+            id_width = id_width,
+            name_width = name_width
+        );
+    }
+}
+
+/// `status` 表格中的一行
+struct StatusRow {
+    service: String,
+    version: String,
+    environment: String,
+    status: Option<ServiceStatus>,
+    port: String,
+}
+
+/// 处理 `status` 命令: `status [--env <name_or_id>] [--no-color]`
+///
+/// 默认列出所有已激活环境中的服务，无运行进程的服务（如 Node.js）状态显示为 `-`。
+pub fn handle_status(args: &[String]) {
+    const USAGE: &str = "用法: envis status [--env <name_or_id>] [--no-color]";
+
+    let mut target = None;
+    let mut no_color = false;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--no-color" => no_color = true,
+            "--env" => match iter.next() {
+                Some(value) => target = Some(value.clone()),
+                None => {
+                    eprintln!("错误: 参数 '--env' 缺少值");
+                    eprintln!("{}", USAGE);
+                    std::process::exit(1);
+                }
+            },
+            other => {
+                eprintln!("错误: 未知参数 '{}'", other);
+                eprintln!("{}", USAGE);
+                std::process::exit(1);
+            }
+        }
+    }
+    let color = !no_color && std::io::stdout().is_terminal();
+
+    let environments = {
+        let manager = EnvironmentManager::global();
+        let manager = manager.lock().unwrap();
+        match manager.get_all_environments() {
+            Ok(envs) => envs,
+            Err(e) => {
+                eprintln!("错误: 获取环境列表失败: {}", e);
+                std::process::exit(1);
+            }
+        }
+    };
+    let environments: Vec<_> = match &target {
+        Some(target) => {
+            let found = environments
+                .iter()
+                .find(|e| &e.id == target)
+                .or_else(|| environments.iter().find(|e| &e.name == target))
+                .cloned();
+            match found {
+                Some(env) => vec![env],
+                None => {
+                    eprintln!("错误: 未找到名称或 ID 为 '{}' 的环境", target);
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => environments
+            .into_iter()
+            .filter(|env| env.status == EnvironmentStatus::Active)
+            .collect(),
+    };
+    if environments.is_empty() {
+        println!("(无已激活的环境)");
+        return;
+    }
+
+    // 正在运行的服务的监听端口：服务数据 ID -> 端口
+    let ports: HashMap<String, Vec<u16>> = ServiceManager::global()
+        .get_managed_process_stats()
+        .unwrap_or_default()
+        .into_values()
+        .flat_map(|by_type| by_type.into_values())
+        .flatten()
+        .map(|stat| (stat.service_id, stat.ports))
+        .collect();
+
+    let mut rows = Vec::new();
+    for environment in &environments {
+        let service_datas = {
+            let manager = EnvServDataManager::global();
+            let manager = manager.lock().unwrap();
+            match manager.get_environment_all_service_datas(&environment.id) {
+                Ok(service_datas) => service_datas,
+                Err(e) => {
+                    eprintln!("警告: 获取环境 {} 的服务失败: {}", environment.name, e);
+                    continue;
+                }
+            }
+        };
+        for service_data in service_datas {
+            let status =
+                ServiceManager::global().get_service_running_status(&environment.id, &service_data);
+            let port = ports
+                .get(&service_data.id)
+                .filter(|ports| !ports.is_empty())
+                .map(|ports| {
+                    ports
+                        .iter()
+                        .map(|p| p.to_string())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                })
+                .unwrap_or_else(|| "-".to_string());
+            rows.push(StatusRow {
+                service: service_data.service_type.default_name(),
+                version: service_data.version,
+                environment: environment.name.clone(),
+                status,
+                port,
+            });
+        }
+    }
+
+    if rows.is_empty() {
+        println!("(无服务)");
+        return;
+    }
+
+    let width = |header: &str, value: fn(&StatusRow) -> &str| {
+        rows.iter()
+            .map(|row| value(row).chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(header.len())
+    };
+    let service_width = width("SERVICE", |row| &row.service);
+    let version_width = width("VERSION", |row| &row.version);
+    let env_width = width("ENVIRONMENT", |row| &row.environment);
+    let status_width = width("STATUS", |row| status_label(row.status.as_ref()));
+
+    println!(
+        "{:<service_width$}  {:<version_width$}  {:<env_width$}  {:<status_width$}  PORT",
+        "SERVICE", "VERSION", "ENVIRONMENT", "STATUS"
+    );
+    for row in &rows {
+        let label = status_label(row.status.as_ref());
+        let padded = format!("{:<status_width$}", label);
+        let status = match (color, row.status.as_ref()) {
+            (true, Some(ServiceStatus::Running)) => format!("\x1b[32m{}\x1b[0m", padded),
+            (true, Some(ServiceStatus::Stopped)) => format!("\x1b[31m{}\x1b[0m", padded),
+            (true, Some(ServiceStatus::Degraded)) => format!("\x1b[33m{}\x1b[0m", padded),
+            _ => padded,
+        };
+        println!(
+            "{:<service_width$}  {:<version_width$}  {:<env_width$}  {}  {}",
+            row.service, row.version, row.environment, status, row.port
+        );
+    }
+}
+
+fn status_label(status: Option<&ServiceStatus>) -> &'static str {
+    match status {
+        Some(ServiceStatus::Running) => "Running",
+        Some(ServiceStatus::Stopped) => "Stopped",
+        Some(ServiceStatus::Degraded) => "Degraded",
+        Some(ServiceStatus::Error) => "Error",
+        Some(ServiceStatus::Unknown) => "Unknown",
+        None => "-",
+    }
+}
+
+/// 处理 `mongodump` 命令: `mongodump --env <id> --service <id> --output <dir>`
+pub fn handle_mongodump(args: &[String]) {
+    const USAGE: &str =
+        "用法: envis mongodump --env <env_id> --service <service_data_id> --output <dir>";
+
+    let mut environment_id = None;
+    let mut service_data_id = None;
+    let mut output_dir = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let slot = match arg.as_str() {
+            "--env" => &mut environment_id,
+            "--service" => &mut service_data_id,
+            "--output" => &mut output_dir,
+            other => {
+                eprintln!("错误: 未知参数 '{}'", other);
+                eprintln!("{}", USAGE);
+                std::process::exit(1);
+            }
+        };
+        match iter.next() {
+            Some(value) => *slot = Some(value.clone()),
+            None => {
+                eprintln!("错误: 参数 '{}' 缺少值", arg);
+                eprintln!("{}", USAGE);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let (Some(environment_id), Some(service_data_id), Some(output_dir)) =
+        (environment_id, service_data_id, output_dir)
+    else {
+        eprintln!("错误: 必须同时指定 --env、--service 和 --output");
+        eprintln!("{}", USAGE);
+        std::process::exit(1);
+    };
+
+    let service_data = {
+        let manager = EnvServDataManager::global();
+        let manager = manager.lock().unwrap();
+        match manager.get_service_data(&environment_id, &service_data_id) {
+            Ok(service_data) => service_data,
+            Err(e) => {
+                eprintln!("错误: {}", e);
+                std::process::exit(1);
+            }
+        }
+    };
+
+    match MongodbService::global().dump_to_directory(&environment_id, &service_data, &output_dir) {
+        Ok(res) if res.success => println!("✓ {}", res.message),
+        Ok(res) => {
+            eprintln!("错误: {}", res.message);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("错误: 备份 MongoDB 失败: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// 处理 `install` 命令: `install <service> <version>`
+pub fn handle_install(args: &[String]) {
+    const USAGE: &str = "用法: envis install <service> <version>";
+
+    let (Some(service_name), Some(version)) = (args.first(), args.get(1)) else {
+        eprintln!("错误: 必须指定服务名称和版本");
+        eprintln!("{}", USAGE);
+        std::process::exit(1);
+    };
+
+    let Some((_, service_type)) = INSTALLABLE_SERVICES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(service_name))
+    else {
+        let names: Vec<&str> = INSTALLABLE_SERVICES.iter().map(|(name, _)| *name).collect();
+        eprintln!("错误: 不支持的服务 '{}'", service_name);
+        eprintln!("支持的服务: {}", names.join(", "));
+        std::process::exit(1);
+    };
+
+    if let Some(warning) = SystemInfoManager::global().missing_dependencies_warning(service_type) {
+        eprintln!("警告: {}", warning);
+    }
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("错误: 创建异步运行时失败: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match runtime.block_on(install_service(service_type, version)) {
+        Ok(message) => println!("✓ {}", message),
+        Err(e) => {
+            eprintln!("错误: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// 处理 `apply` 命令：读取当前目录的 .envisrc，让环境中的服务与之一致并安装缺失的版本
+pub fn handle_apply(args: &[String]) {
+    const USAGE: &str = "用法: envis apply [--env <name_or_id>] [--no-download]";
+
+    let mut target = None;
+    let mut download = true;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--no-download" => download = false,
+            "--env" => match iter.next() {
+                Some(value) => target = Some(value.clone()),
+                None => {
+                    eprintln!("错误: 参数 '--env' 缺少值");
+                    eprintln!("{}", USAGE);
+                    std::process::exit(1);
+                }
+            },
+            other => {
+                eprintln!("错误: 未知参数 '{}'", other);
+                eprintln!("{}", USAGE);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let cwd = match std::env::current_dir() {
+        Ok(cwd) => cwd,
+        Err(e) => {
+            eprintln!("错误: 获取当前目录失败: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let manifest = match ProjectManifest::load(&cwd) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            eprintln!("错误: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let environments = {
+        let manager = EnvironmentManager::global();
+        let manager = manager.lock().unwrap();
+        match manager.get_all_environments() {
+            Ok(envs) => envs,
+            Err(e) => {
+                eprintln!("错误: 获取环境列表失败: {}", e);
+                std::process::exit(1);
+            }
+        }
+    };
+    // 未指定 --env 时使用唯一的已激活环境
+    let environment = match &target {
+        Some(target) => environments
+            .iter()
+            .find(|e| &e.id == target)
+            .or_else(|| environments.iter().find(|e| &e.name == target)),
+        None => {
+            let active: Vec<_> = environments
+                .iter()
+                .filter(|env| env.status == EnvironmentStatus::Active)
+                .collect();
+            if active.len() > 1 {
+                eprintln!("错误: 有多个已激活的环境，请使用 --env 指定目标环境");
+                eprintln!("{}", USAGE);
+                std::process::exit(1);
+            }
+            active.into_iter().next()
+        }
+    };
+    let Some(environment) = environment else {
+        match &target {
+            Some(target) => eprintln!("错误: 未找到名称或 ID 为 '{}' 的环境", target),
+            None => eprintln!("错误: 没有已激活的环境，请使用 --env 指定目标环境"),
+        }
+        std::process::exit(1);
+    };
+
+    let applied = match manifest.apply(&environment.id, false) {
+        Ok(applied) => applied,
+        Err(e) => {
+            eprintln!("错误: 应用项目清单失败: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    println!(
+        "按 {} 更新环境 {}:",
+        manifest.path.display(),
+        environment.name
+    );
+    let mut failed = false;
+    for entry in &applied.entries {
+        let name = format!("{} {}", entry.service_type.default_name(), entry.version);
+        match entry.action {
+            ManifestApplyAction::Created => println!("  + {} 已添加", name),
+            ManifestApplyAction::Updated => println!(
+                "  ~ {} 已从 {} 切换",
+                name,
+                entry.from_version.as_deref().unwrap_or("-")
+            ),
+            ManifestApplyAction::Unchanged => println!("  = {} 无需修改", name),
+            ManifestApplyAction::Skipped => {
+                failed = true;
+                println!(
+                    "  ! {} 已跳过: {}",
+                    name,
+                    entry.message.as_deref().unwrap_or("-")
+                );
+            }
+        }
+    }
+
+    let missing: Vec<_> = applied.entries.iter().filter(|e| !e.installed).collect();
+    if !missing.is_empty() {
+        if download {
+            let runtime = match tokio::runtime::Runtime::new() {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    eprintln!("错误: 创建异步运行时失败: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            for entry in missing {
+                match runtime.block_on(install_service(&entry.service_type, &entry.version)) {
+                    Ok(message) => println!("✓ {}", message),
+                    Err(e) => {
+                        failed = true;
+                        eprintln!("错误: {}", e);
+                    }
+                }
+            }
+        } else {
+            for entry in missing {
+                println!(
+                    "  {} {} 尚未安装，去掉 --no-download 可自动安装",
+                    entry.service_type.default_name(),
+                    entry.version
+                );
+            }
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+/// 下载并安装服务版本，轮询下载任务并渲染进度条直到安装结束
+async fn install_service(service_type: &ServiceType, version: &str) -> Result<String, String> {
+    let service = ServiceRegistry::get(service_type);
+    let display_name = format!("{} {}", service_type.default_name(), version);
+    if service.is_installed(version) {
+        return Ok(format!("{} 已经安装", display_name));
+    }
+
+    let progress_bar = ProgressBar::new(0);
+    progress_bar.set_style(
+        ProgressStyle::with_template(
+            "{msg} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})",
+        )
+        .unwrap_or_else(|_| ProgressStyle::default_bar())
+        .progress_chars("=> "),
+    );
+    progress_bar.set_message(format!("下载 {}", display_name));
+
+    // 下载在后台任务中进行，下载完成后由服务的回调继续解压安装
+    let mut download = Some({
+        let service = service.clone();
+        let version = version.to_string();
+        tokio::spawn(async move { service.download_and_install(&version).await })
+    });
+
+    loop {
+        let task = service.get_download_progress(version);
+        if let Some(task) = &task {
+            if task.total_size > 0 {
+                progress_bar.set_length(task.total_size);
+            }
+            progress_bar.set_position(task.downloaded_size);
+
+            match task.status {
+                DownloadStatus::Installing => {
+                    progress_bar.set_message(format!("安装 {}", display_name));
+                }
+                DownloadStatus::Installed => {
+                    progress_bar.finish_and_clear();
+                    return Ok(format!("{} 安装成功", display_name));
+                }
+                DownloadStatus::Failed | DownloadStatus::Cancelled | DownloadStatus::Paused => {
+                    progress_bar.abandon();
+                    return Err(format!(
+                        "安装 {} 未完成: {}",
+                        display_name,
+                        task.error_message
+                            .clone()
+                            .unwrap_or_else(|| format!("{:?}", task.status))
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        if download.as_ref().is_some_and(|handle| handle.is_finished()) {
+            let result = match download.take().unwrap().await {
+                Ok(Ok(result)) => result,
+                Ok(Err(e)) => {
+                    progress_bar.abandon();
+                    return Err(format!("下载 {} 失败: {}", display_name, e));
+                }
+                Err(e) => {
+                    progress_bar.abandon();
+                    return Err(format!("下载任务异常退出: {}", e));
+                }
+            };
+            if !result.success {
+                progress_bar.abandon();
+                return Err(result.message);
+            }
+        }
+
+        // 下载已结束但找不到任务时，以安装结果为准
+        if download.is_none() && task.is_none() {
+            progress_bar.finish_and_clear();
+            return if service.is_installed(version) {
+                Ok(format!("{} 安装成功", display_name))
+            } else {
+                Err(format!("找不到 {} 的下载任务", display_name))
+            };
+        }
+
+        tokio::time::sleep(INSTALL_POLL_INTERVAL).await;
+    }
+}
@@ -1,7 +1,9 @@
 mod handlers;
 
 use envis_core::manager::app_config_manager::initialize_config_manager;
+use envis_core::manager::env_serv_data_manager::initialize_env_serv_data_manager;
 use envis_core::manager::environment_manager::initialize_environment_manager;
+use envis_core::manager::service_manager::initialize_service_manager;
 use envis_core::manager::shell_manamger::initialize_shell_manager;
 
 /// 提前处理 CLI 参数（不依赖 Tauri 应用）
@@ -52,6 +54,52 @@ pub fn handle_cli(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
         std::process::exit(0);
     }
 
+    // ── env：create / list / activate，activate 需要写 shell 配置 ──────
+    if args[1] == "env" {
+        initialize_config_manager()?;
+        initialize_shell_manager()?;
+        initialize_environment_manager()?;
+        handlers::handle_env(&args[2..]);
+        std::process::exit(0);
+    }
+
+    // ── status：只读取服务状态，不涉及 shell 配置 ─────────────────────
+    if args[1] == "status" {
+        initialize_config_manager()?;
+        initialize_environment_manager()?;
+        initialize_env_serv_data_manager()?;
+        initialize_service_manager()?;
+        handlers::handle_status(&args[2..]);
+        std::process::exit(0);
+    }
+
+    // ── mongodump：由定时备份（crontab / 计划任务）调用，不涉及 shell 配置 ─
+    if args[1] == "mongodump" {
+        initialize_config_manager()?;
+        initialize_environment_manager()?;
+        initialize_env_serv_data_manager()?;
+        handlers::handle_mongodump(&args[2..]);
+        std::process::exit(0);
+    }
+
+    // ── install：只需 AppConfigManager 确定安装目录 ────────────────────
+    if args[1] == "install" {
+        initialize_config_manager()?;
+        handlers::handle_install(&args[2..]);
+        std::process::exit(0);
+    }
+
+    // ── apply：按当前目录的 .envisrc 更新环境服务，激活的服务会改写 shell 配置 ─
+    if args[1] == "apply" {
+        initialize_config_manager()?;
+        initialize_shell_manager()?;
+        initialize_environment_manager()?;
+        initialize_env_serv_data_manager()?;
+        initialize_service_manager()?;
+        handlers::handle_apply(&args[2..]);
+        std::process::exit(0);
+    }
+
     // ── --complete-use：输出环境名供 shell tab 补全使用（静默，不报错）─
     if args[1] == "--complete-use" {
         let _ = initialize_config_manager();
@@ -81,8 +129,18 @@ SUBCOMMANDS:
     list             List all environments
     ls               List all environments
     use              Activate an environment
+    env create       Create a new environment
+    env list         List all environments with ID, name and status
+    env activate     Activate an environment (same as use)
+    status           Show services of active environments and whether they are running
+                     (--env <name_or_id> to show one environment, --no-color to disable colors)
     rs               Reload shell configuration (alias of refresh)
     refresh          Reload shell configuration (source ~/.zshrc or ~/.bash_profile)
+    mongodump        Dump a MongoDB service to a directory (used by scheduled backups)
+    install          Download and install a service version
+                     (nodejs, mongodb, mysql, mariadb, postgresql, nginx, python)
+    apply            Sync services of an environment with .envisrc in the current directory
+                     (--env <name_or_id> to choose the environment, --no-download to skip installs)
 
 EXAMPLES:
     # List all environments
@@ -91,6 +149,21 @@ EXAMPLES:
     # Activate an environment by name
     envis use my-env
 
+    # Create an environment
+    envis env create my-env
+
+    # Show service status of one environment
+    envis status --env my-env
+
+    # Install a service version
+    envis install nodejs 20.11.0
+
+    # Apply .envisrc of the current project to the active environment
+    envis apply
+
+    # Dump a MongoDB service
+    envis mongodump --env <env_id> --service <service_data_id> --output ~/backups
+
     # Activate an environment by ID
     envis use 0389cccc-1ed7-4d59-8be0-0c1baec26e5eenv
 
@@ -11,6 +11,19 @@ pub const ENVIS_DIR: &str = ".envis";
 pub const SERVICES_FOLDER: &str = "services";
 pub const ENVS_FOLDER: &str = "envs";
 
+/// 下载等网络请求使用的代理模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyMode {
+    /// 不使用代理
+    None,
+    /// 使用系统代理（HTTP_PROXY / HTTPS_PROXY / ALL_PROXY 环境变量及系统设置）
+    #[default]
+    System,
+    /// 使用 proxy_url 指定的代理
+    Manual,
+}
+
 /// 配置文件结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -28,12 +41,56 @@ pub struct AppConfig {
     pub show_environment_name_on_terminal_open: bool,
     #[serde(default)]
     pub show_service_info_on_terminal_open: bool,
+    /// 启动应用时自动启动已激活环境中的服务
+    #[serde(default)]
+    pub auto_start_services: bool,
+    /// 自动重启服务的健康检查间隔（秒）
+    #[serde(default = "default_service_supervision_interval_secs")]
+    pub service_supervision_interval_secs: u64,
+    /// 服务崩溃后的最大自动重启次数
+    #[serde(default = "default_service_auto_restart_max_retries")]
+    pub service_auto_restart_max_retries: u32,
+    /// Windows 下同时将 PATH 与环境变量写入用户注册表（HKCU\Environment），
+    /// 使不读取 PowerShell/CMD 配置的 GUI 程序也能看到
+    #[serde(default)]
+    pub windows_use_user_environment: bool,
+    /// 每个 Shell 配置文件保留的自动备份（.envbak{timestamp}）数量
+    #[serde(default = "default_shell_config_backup_retention")]
+    pub shell_config_backup_retention: u32,
+    /// 下载代理模式
+    #[serde(default)]
+    pub proxy_mode: ProxyMode,
+    /// 手动代理地址，支持 http / https / socks5 / socks5h，可包含 user:password@ 认证信息
+    #[serde(default)]
+    pub proxy_url: Option<String>,
+    /// 不使用代理的主机列表（如 localhost、.example.com）
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+    /// 同时进行的最大下载任务数，超出的任务排队等待
+    #[serde(default = "default_max_concurrent_downloads")]
+    pub max_concurrent_downloads: u32,
 }
 
 fn default_true() -> bool {
     true
 }
 
+fn default_service_supervision_interval_secs() -> u64 {
+    10
+}
+
+fn default_service_auto_restart_max_retries() -> u32 {
+    3
+}
+
+fn default_shell_config_backup_retention() -> u32 {
+    5
+}
+
+fn default_max_concurrent_downloads() -> u32 {
+    3
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         let home_dir = dirs::home_dir().expect("无法获取用户主目录");
@@ -49,6 +106,15 @@ impl Default for AppConfig {
             deactivate_other_environments_on_activate: true,
             show_environment_name_on_terminal_open: true,
             show_service_info_on_terminal_open: false,
+            auto_start_services: false,
+            service_supervision_interval_secs: default_service_supervision_interval_secs(),
+            service_auto_restart_max_retries: default_service_auto_restart_max_retries(),
+            windows_use_user_environment: false,
+            shell_config_backup_retention: default_shell_config_backup_retention(),
+            proxy_mode: ProxyMode::default(),
+            proxy_url: None,
+            no_proxy: vec![],
+            max_concurrent_downloads: default_max_concurrent_downloads(),
         }
     }
 }
@@ -265,6 +331,11 @@ impl AppConfigManager {
         Ok(config_dir.to_string_lossy().to_string())
     }
 
+    /// 获取配置文件路径
+    pub fn get_app_config_file_path(&self) -> String {
+        self.app_config_path.to_string_lossy().to_string()
+    }
+
     /// 保存配置到文件
     fn save_app_config(&self) -> Result<()> {
         let app_config_content =
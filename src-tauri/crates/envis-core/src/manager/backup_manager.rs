@@ -0,0 +1,443 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{Local, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use crate::manager::env_serv_data_manager::{EnvServDataManager, ServiceDataResult};
+use crate::manager::services::{
+    MariadbService, MongodbService, MysqlService, PostgresqlService, ServiceRegistry,
+};
+use crate::types::{ServiceData, ServiceType};
+
+/// 备份记录在 metadata 中的键
+const BACKUPS_METADATA_KEY: &str = "SERVICE_BACKUPS";
+/// 备份文件所在的子目录
+const BACKUPS_DIR_NAME: &str = "backups";
+/// 恢复时等待服务就绪的最长时间
+const RESTORE_READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 备份文件信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub path: String,
+    pub timestamp: String,
+    pub size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_version: Option<String>,
+}
+
+/// 全局备份管理器单例
+static BACKUP_MANAGER: OnceLock<Arc<BackupManager>> = OnceLock::new();
+
+/// 数据库备份管理器（MySQL / MariaDB / MongoDB / PostgreSQL）
+pub struct BackupManager {}
+
+impl BackupManager {
+    /// 获取全局备份管理器实例
+    pub fn global() -> Arc<BackupManager> {
+        BACKUP_MANAGER
+            .get_or_init(|| {
+                let manager = Self::new();
+                Arc::new(manager)
+            })
+            .clone()
+    }
+
+    /// 创建新的备份管理器
+    fn new() -> Self {
+        Self {}
+    }
+
+    /// 备份服务数据到 `<service_data_folder>/backups/`，`retention` 为保留的最大份数
+    pub fn backup_service_data(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        retention: Option<usize>,
+    ) -> Result<ServiceDataResult> {
+        let mut command = self.build_backup_command(environment_id, service_data)?;
+        let tool_version = Self::get_tool_version(&command);
+
+        let backups_dir = self.get_backups_dir(environment_id, service_data)?;
+        fs::create_dir_all(&backups_dir).context("创建备份目录失败")?;
+
+        let timestamp = Local::now().format("%Y%m%d-%H%M%S").to_string();
+        let file_name = format!(
+            "{}-{}.{}",
+            service_data.service_type.dir_name(),
+            timestamp,
+            Self::backup_extension(&service_data.service_type)
+        );
+        let backup_path = backups_dir.join(&file_name);
+
+        log::info!("开始备份服务 {} 到 {:?}", service_data.name, backup_path);
+
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("启动备份工具失败")?;
+
+        // stderr 单独读取，避免管道写满导致子进程阻塞
+        let stderr_handle = child.stderr.take().map(Self::spawn_stderr_reader);
+
+        let write_result = (|| -> Result<()> {
+            let mut stdout = child.stdout.take().ok_or_else(|| anyhow!("无法读取备份输出"))?;
+            let file = File::create(&backup_path).context("创建备份文件失败")?;
+            let mut encoder = GzEncoder::new(file, Compression::default());
+            io::copy(&mut stdout, &mut encoder).context("写入备份文件失败")?;
+            encoder.finish().context("压缩备份文件失败")?;
+            Ok(())
+        })();
+
+        let status = child.wait().context("等待备份工具退出失败")?;
+        let stderr = stderr_handle
+            .and_then(|h| h.join().ok())
+            .unwrap_or_default();
+
+        if let Err(e) = write_result {
+            let _ = fs::remove_file(&backup_path);
+            return Err(anyhow!("{}: {}", e, stderr.trim()));
+        }
+        if !status.success() {
+            let _ = fs::remove_file(&backup_path);
+            return Err(anyhow!("备份失败: {}", stderr.trim()));
+        }
+
+        let size = fs::metadata(&backup_path).map(|m| m.len()).unwrap_or(0);
+        let info = BackupInfo {
+            file_name,
+            path: backup_path.to_string_lossy().to_string(),
+            timestamp: Utc::now().to_rfc3339(),
+            size,
+            tool_version,
+        };
+
+        // 记录到 metadata 并清理超出保留份数的旧备份
+        let mut records = Self::get_backup_records(service_data);
+        records.push(info.clone());
+        let pruned = match retention {
+            Some(keep) => Self::prune_backups(&mut records, keep),
+            None => Vec::new(),
+        };
+        let mut service_data = service_data.clone();
+        EnvServDataManager::global().lock().unwrap().set_metadata(
+            environment_id,
+            &mut service_data,
+            BACKUPS_METADATA_KEY,
+            serde_json::to_value(&records)?,
+        )?;
+
+        log::info!("服务 {} 备份完成: {}", service_data.name, info.path);
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "备份成功".to_string(),
+            data: Some(serde_json::json!({
+                "backup": info,
+                "pruned": pruned,
+                "stderr": stderr,
+            })),
+        })
+    }
+
+    /// 列出服务的所有备份（按时间倒序）
+    pub fn list_service_backups(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let backups_dir = self.get_backups_dir(environment_id, service_data)?;
+        let records = Self::get_backup_records(service_data);
+
+        let mut backups: Vec<BackupInfo> = Vec::new();
+        if backups_dir.exists() {
+            for entry in fs::read_dir(&backups_dir).context("读取备份目录失败")? {
+                let entry = entry?;
+                let path = entry.path();
+                if !path.is_file() {
+                    continue;
+                }
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+
+                // 优先使用 metadata 中记录的信息
+                let backup = match records.iter().find(|r| r.file_name == file_name) {
+                    Some(record) => BackupInfo {
+                        size,
+                        ..record.clone()
+                    },
+                    None => BackupInfo {
+                        timestamp: entry
+                            .metadata()
+                            .and_then(|m| m.modified())
+                            .map(|t| chrono::DateTime::<Utc>::from(t).to_rfc3339())
+                            .unwrap_or_default(),
+                        file_name,
+                        path: path.to_string_lossy().to_string(),
+                        size,
+                        tool_version: None,
+                    },
+                };
+                backups.push(backup);
+            }
+        }
+        backups.sort_by(|a, b| b.file_name.cmp(&a.file_name));
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "获取备份列表成功".to_string(),
+            data: Some(serde_json::json!({ "backups": backups })),
+        })
+    }
+
+    /// 从备份恢复服务数据，服务运行中时拒绝执行
+    pub fn restore_service_backup(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        backup_file: &str,
+    ) -> Result<ServiceDataResult> {
+        if self.is_service_running(environment_id, service_data)? {
+            return Ok(ServiceDataResult {
+                success: false,
+                message: "服务正在运行，请先停止服务再恢复备份".to_string(),
+                data: None,
+            });
+        }
+
+        let backups_dir = self.get_backups_dir(environment_id, service_data)?;
+        let backup_path = Self::resolve_backup_path(&backups_dir, backup_file)?;
+
+        // 恢复需要服务在线，临时启动并在结束后停止
+        let service = ServiceRegistry::get(&service_data.service_type);
+        let start_result = service.start_service(environment_id, service_data)?;
+        if !start_result.success {
+            return Err(anyhow!("启动服务失败: {}", start_result.message));
+        }
+
+        let restore_result = self
+            .wait_until_running(environment_id, service_data)
+            .and_then(|_| self.run_restore(environment_id, service_data, &backup_path));
+
+        if let Err(e) = service.stop_service(environment_id, service_data) {
+            log::warn!("恢复完成后停止服务失败: {}", e);
+        }
+
+        let stderr = restore_result?;
+        log::info!("服务 {} 已从 {:?} 恢复", service_data.name, backup_path);
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "恢复备份成功".to_string(),
+            data: Some(serde_json::json!({
+                "path": backup_path.to_string_lossy(),
+                "stderr": stderr,
+            })),
+        })
+    }
+
+    /// 执行恢复命令，解压备份文件写入其 stdin，返回 stderr 输出
+    fn run_restore(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        backup_path: &Path,
+    ) -> Result<String> {
+        let mut command = self.build_restore_command(environment_id, service_data)?;
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("启动恢复工具失败")?;
+
+        let stderr_handle = child.stderr.take().map(Self::spawn_stderr_reader);
+
+        let write_result = (|| -> Result<()> {
+            let mut stdin = child.stdin.take().ok_or_else(|| anyhow!("无法写入恢复输入"))?;
+            let file = File::open(backup_path).context("打开备份文件失败")?;
+            let mut decoder = GzDecoder::new(file);
+            io::copy(&mut decoder, &mut stdin).context("写入恢复数据失败")?;
+            Ok(())
+        })();
+
+        let status = child.wait().context("等待恢复工具退出失败")?;
+        let stderr = stderr_handle
+            .and_then(|h| h.join().ok())
+            .unwrap_or_default();
+
+        if let Err(e) = write_result {
+            return Err(anyhow!("{}: {}", e, stderr.trim()));
+        }
+        if !status.success() {
+            return Err(anyhow!("恢复失败: {}", stderr.trim()));
+        }
+        Ok(stderr)
+    }
+
+    fn build_backup_command(&self, environment_id: &str, service_data: &ServiceData) -> Result<Command> {
+        match service_data.service_type {
+            ServiceType::Mysql => MysqlService::global().build_backup_command(environment_id, service_data),
+            ServiceType::Mariadb => MariadbService::global().build_backup_command(environment_id, service_data),
+            ServiceType::Mongodb => MongodbService::global().build_backup_command(environment_id, service_data),
+            ServiceType::Postgresql => PostgresqlService::global().build_backup_command(environment_id, service_data),
+            _ => Err(anyhow!("服务类型 {:?} 不支持备份", service_data.service_type)),
+        }
+    }
+
+    fn build_restore_command(&self, environment_id: &str, service_data: &ServiceData) -> Result<Command> {
+        match service_data.service_type {
+            ServiceType::Mysql => MysqlService::global().build_restore_command(environment_id, service_data),
+            ServiceType::Mariadb => MariadbService::global().build_restore_command(environment_id, service_data),
+            ServiceType::Mongodb => MongodbService::global().build_restore_command(environment_id, service_data),
+            ServiceType::Postgresql => PostgresqlService::global().build_restore_command(environment_id, service_data),
+            _ => Err(anyhow!("服务类型 {:?} 不支持恢复", service_data.service_type)),
+        }
+    }
+
+    fn is_service_running(&self, environment_id: &str, service_data: &ServiceData) -> Result<bool> {
+        let status =
+            ServiceRegistry::get(&service_data.service_type).get_service_status(environment_id, service_data)?;
+        Ok(status
+            .data
+            .as_ref()
+            .and_then(|d| d.get("isRunning"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false))
+    }
+
+    /// 轮询等待服务就绪
+    fn wait_until_running(&self, environment_id: &str, service_data: &ServiceData) -> Result<()> {
+        let start = std::time::Instant::now();
+        while start.elapsed() < RESTORE_READY_TIMEOUT {
+            if self.is_service_running(environment_id, service_data)? {
+                // 进程已启动，再给一点时间完成监听
+                std::thread::sleep(Duration::from_millis(1000));
+                return Ok(());
+            }
+            std::thread::sleep(Duration::from_millis(500));
+        }
+        Err(anyhow!("等待服务启动超时"))
+    }
+
+    fn get_backups_dir(&self, environment_id: &str, service_data: &ServiceData) -> Result<PathBuf> {
+        let (_, _, _, _, service_data_folder, _) = EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .build_service_paths(environment_id, service_data)?;
+        Ok(service_data_folder.join(BACKUPS_DIR_NAME))
+    }
+
+    /// 仅允许恢复备份目录下的文件
+    fn resolve_backup_path(backups_dir: &Path, backup_file: &str) -> Result<PathBuf> {
+        let file_name = Path::new(backup_file)
+            .file_name()
+            .ok_or_else(|| anyhow!("无效的备份文件: {}", backup_file))?;
+        let backup_path = backups_dir.join(file_name);
+        if !backup_path.is_file() {
+            return Err(anyhow!("备份文件不存在: {}", backup_path.display()));
+        }
+        Ok(backup_path)
+    }
+
+    fn backup_extension(service_type: &ServiceType) -> &'static str {
+        match service_type {
+            ServiceType::Mongodb => "archive.gz",
+            _ => "sql.gz",
+        }
+    }
+
+    fn get_backup_records(service_data: &ServiceData) -> Vec<BackupInfo> {
+        service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get(BACKUPS_METADATA_KEY))
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// 删除超出保留份数的旧备份，返回被删除的文件名
+    ///
+    /// 保留份数至少为 1，避免删除刚写入的备份。
+    fn prune_backups(records: &mut Vec<BackupInfo>, keep: usize) -> Vec<String> {
+        records.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+        let excess = records.len().saturating_sub(keep.max(1));
+        records
+            .drain(..excess)
+            .map(|record| {
+                if let Err(e) = fs::remove_file(&record.path) {
+                    log::warn!("删除旧备份失败 {}: {}", record.path, e);
+                }
+                record.file_name
+            })
+            .collect()
+    }
+
+    /// 获取备份工具版本（`<tool> --version` 的首行）
+    fn get_tool_version(command: &Command) -> Option<String> {
+        let output = crate::utils::create_command(command.get_program())
+            .arg("--version")
+            .envs(command.get_envs().filter_map(|(k, v)| v.map(|v| (k, v))))
+            .output()
+            .ok()?;
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+    }
+
+    fn spawn_stderr_reader<R: Read + Send + 'static>(mut stderr: R) -> std::thread::JoinHandle<String> {
+        std::thread::spawn(move || {
+            let mut buf = String::new();
+            let _ = stderr.read_to_string(&mut buf);
+            buf
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prune_backups_keeps_newest_when_retention_is_zero() {
+        let dir = tempfile::tempdir().unwrap();
+        let older = "mysql-20260101-000000.sql.gz";
+        let newest = "mysql-20260102-000000.sql.gz";
+        let mut records: Vec<BackupInfo> = [older, newest]
+            .iter()
+            .map(|name| {
+                let path = dir.path().join(name);
+                fs::write(&path, b"backup").unwrap();
+                BackupInfo {
+                    file_name: name.to_string(),
+                    path: path.to_string_lossy().to_string(),
+                    timestamp: String::new(),
+                    size: 6,
+                    tool_version: None,
+                }
+            })
+            .collect();
+
+        let pruned = BackupManager::prune_backups(&mut records, 0);
+
+        assert_eq!(pruned, vec![older.to_string()]);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].file_name, newest);
+        assert!(dir.path().join(newest).exists());
+        assert!(!dir.path().join(older).exists());
+    }
+}
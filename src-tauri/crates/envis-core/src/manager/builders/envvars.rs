@@ -1,7 +1,11 @@
-use crate::types::ServiceType;
-use anyhow::Result;
+use crate::manager::services::vault::DEFAULT_VAULT_ADDR;
+use crate::types::{Environment, ServiceType};
+use anyhow::{anyhow, Result};
 use std::collections::HashMap;
 
+/// 环境自定义环境变量在 Environment.metadata 中的键
+pub const ENVIRONMENT_ENV_VARS_KEY: &str = "envVars";
+
 /// 环境变量构建器
 /// 负责为不同服务类型构建环境变量配置
 pub struct EnvVarBuilder;
@@ -60,6 +64,24 @@ impl EnvVarBuilder {
             ServiceType::Nasm => {
                 // NASM 服务不需要额外环境变量
             }
+            ServiceType::Php => {
+                Self::build_php_env_vars(&mut env_vars, service_folder)?;
+            }
+            ServiceType::Vault => {
+                Self::build_vault_env_vars(&mut env_vars)?;
+            }
+            ServiceType::Opensearch => {
+                // OpenSearch 服务不需要环境变量
+            }
+            ServiceType::Kafka => {
+                // Kafka 服务不需要环境变量
+            }
+            ServiceType::Rabbitmq => {
+                // RabbitMQ 服务不需要环境变量
+            }
+            ServiceType::Compose => {
+                // Compose 服务不需要环境变量
+            }
         }
 
         Ok(env_vars)
@@ -151,4 +173,71 @@ impl EnvVarBuilder {
 
         Ok(())
     }
+
+    /// 构建 PHP 服务的环境变量
+    fn build_php_env_vars(
+        env_vars: &mut HashMap<String, String>,
+        service_folder: &std::path::Path,
+    ) -> Result<()> {
+        // PHPRC 默认指向安装目录，metadata 中会覆盖为环境目录下生成的 php.ini
+        env_vars.insert(
+            "PHPRC".to_string(),
+            service_folder.to_string_lossy().to_string(),
+        );
+
+        Ok(())
+    }
+
+    /// 构建 Vault 服务的环境变量
+    fn build_vault_env_vars(env_vars: &mut HashMap<String, String>) -> Result<()> {
+        env_vars.insert("VAULT_ADDR".to_string(), DEFAULT_VAULT_ADDR.to_string());
+        // root token 在创建服务数据时随机生成，实际值由 metadata 覆盖
+        env_vars.insert("VAULT_TOKEN".to_string(), String::new());
+
+        Ok(())
+    }
+
+    /// 读取环境 metadata 中用户配置的环境变量
+    pub fn build_env_vars_for_environment(environment: &Environment) -> HashMap<String, String> {
+        environment
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get(ENVIRONMENT_ENV_VARS_KEY))
+            .and_then(|v| v.as_object())
+            .map(|vars| {
+                vars.iter()
+                    .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), v.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 校验环境变量名是否为合法的 shell 标识符
+    pub fn validate_env_var_key(key: &str) -> Result<()> {
+        let mut chars = key.chars();
+        let valid = match chars.next() {
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+                chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+            }
+            _ => false,
+        };
+        if !valid {
+            return Err(anyhow!(
+                "无效的环境变量名: {}（只能包含字母、数字和下划线，且不能以数字开头）",
+                key
+            ));
+        }
+        if key.eq_ignore_ascii_case("PATH") {
+            return Err(anyhow!("PATH 由服务自动管理，不能直接设置"));
+        }
+        Ok(())
+    }
+
+    /// 校验环境变量值（shell 配置按行解析，不支持换行）
+    pub fn validate_env_var_value(value: &str) -> Result<()> {
+        if value.contains('\n') || value.contains('\r') {
+            return Err(anyhow!("环境变量值不能包含换行"));
+        }
+        Ok(())
+    }
 }
@@ -1,11 +1,58 @@
 use crate::manager::app_config_manager::AppConfigManager;
+use crate::manager::environment_manager::EnvironmentManager;
+use crate::manager::services::compose::COMPOSE_PROJECT_NAME_KEY;
 use crate::manager::services::java::{JavaService, MavenService};
+use crate::manager::services::kafka::{
+    DEFAULT_KAFKA_CONTROLLER_PORT, DEFAULT_KAFKA_HEAP_MB, DEFAULT_KAFKA_PORT,
+};
+use crate::manager::services::opensearch::{DEFAULT_OPENSEARCH_HEAP_MB, DEFAULT_OPENSEARCH_PORT};
+use crate::manager::services::postgresql::{PostgresqlService, DEFAULT_POSTGRESQL_PORT};
+use crate::manager::services::rabbitmq::{DEFAULT_RABBITMQ_MANAGEMENT_PORT, DEFAULT_RABBITMQ_PORT};
+use crate::manager::services::vault::DEFAULT_VAULT_ADDR;
+use crate::manager::services::{ComposeService, PhpService, RustService};
 use crate::types::{ServiceData, ServiceType};
 use crate::utils::path::to_unix_path_string;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// 数据库服务初始化状态与凭据对应的 metadata 键
+#[derive(Debug, Clone, Copy)]
+pub struct DatabaseMetadataKeys {
+    /// 配置文件路径
+    pub config: &'static str,
+    pub port: &'static str,
+    /// MySQL / MariaDB 固定使用 root，不单独保存用户名
+    pub username: Option<&'static str>,
+    pub password: &'static str,
+    /// 初始化是否完成，重置开始时写入 false，成功后写入 true
+    pub initialized: &'static str,
+}
+
+pub const MYSQL_METADATA_KEYS: DatabaseMetadataKeys = DatabaseMetadataKeys {
+    config: "MYSQL_CONFIG",
+    port: "MYSQL_PORT",
+    username: None,
+    password: "MYSQL_ROOT_PASSWORD",
+    initialized: "MYSQL_INITIALIZED",
+};
+
+pub const MARIADB_METADATA_KEYS: DatabaseMetadataKeys = DatabaseMetadataKeys {
+    config: "MARIADB_CONFIG",
+    port: "MARIADB_PORT",
+    username: None,
+    password: "MARIADB_ROOT_PASSWORD",
+    initialized: "MARIADB_INITIALIZED",
+};
+
+pub const MONGODB_METADATA_KEYS: DatabaseMetadataKeys = DatabaseMetadataKeys {
+    config: "MONGODB_CONFIG",
+    port: "MONGODB_PORT",
+    username: Some("MONGODB_ADMIN_USERNAME"),
+    password: "MONGODB_ADMIN_PASSWORD",
+    initialized: "MONGODB_INITIALIZED",
+};
 
 /// 元数据构建器
 /// 负责为不同服务类型构建默认的 metadata 配置
@@ -62,7 +109,7 @@ impl MetadataBuilder {
             }
             ServiceType::Rust => {
                 // 为 Rust 服务创建默认配置
-                Self::build_rust_default_metadata(service_data, &mut metadata)?;
+                Self::build_rust_default_metadata(environment_id, service_data, &mut metadata)?;
             }
             ServiceType::Custom => {
                 // 为自定义服务创建默认配置
@@ -83,11 +130,77 @@ impl MetadataBuilder {
             ServiceType::Nasm => {
                 // NASM 暂无默认 metadata
             }
+            ServiceType::Php => {
+                // 为 PHP 服务生成 php.ini / php-fpm.conf
+                Self::build_php_default_metadata(environment_id, service_data, &mut metadata)?;
+            }
+            ServiceType::Vault => {
+                // 为 Vault 服务生成监听地址与 dev 模式 root token
+                Self::build_vault_default_metadata(environment_id, service_data, &mut metadata)?;
+            }
+            ServiceType::Opensearch => {
+                // 为 OpenSearch 服务设置默认端口与堆大小
+                Self::build_opensearch_default_metadata(
+                    environment_id,
+                    service_data,
+                    &mut metadata,
+                );
+            }
+            ServiceType::Kafka => {
+                // 为 Kafka 服务设置默认端口与堆大小
+                Self::build_kafka_default_metadata(environment_id, service_data, &mut metadata);
+            }
+            ServiceType::Rabbitmq => {
+                // 为 RabbitMQ 服务设置默认 AMQP 与管理端口
+                Self::build_rabbitmq_default_metadata(environment_id, service_data, &mut metadata);
+            }
+            ServiceType::Compose => {
+                // 为 Compose 服务设置默认项目名，compose 文件由用户指定
+                Self::build_compose_default_metadata(environment_id, &mut metadata);
+            }
         }
 
         Ok(metadata)
     }
 
+    /// 数据库初始化成功后写入的 metadata：配置文件、端口、凭据与初始化完成标记
+    pub fn build_database_init_metadata(
+        keys: &DatabaseMetadataKeys,
+        config_path: &Path,
+        port: &str,
+        username: &str,
+        password: &str,
+    ) -> HashMap<String, serde_json::Value> {
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            keys.config.to_string(),
+            serde_json::Value::String(config_path.to_string_lossy().to_string()),
+        );
+        metadata.insert(
+            keys.port.to_string(),
+            serde_json::Value::String(port.to_string()),
+        );
+        if let Some(username_key) = keys.username {
+            metadata.insert(
+                username_key.to_string(),
+                serde_json::Value::String(username.to_string()),
+            );
+        }
+        metadata.insert(
+            keys.password.to_string(),
+            serde_json::Value::String(password.to_string()),
+        );
+        metadata.insert(keys.initialized.to_string(), serde_json::Value::Bool(true));
+        metadata
+    }
+
+    /// 数据库重置开始时写入的 metadata，初始化成功前状态检查视为未完成
+    pub fn build_database_reset_metadata(
+        keys: &DatabaseMetadataKeys,
+    ) -> HashMap<String, serde_json::Value> {
+        HashMap::from([(keys.initialized.to_string(), serde_json::Value::Bool(false))])
+    }
+
     /// 构建 Node.js 服务的默认 metadata
     fn build_nodejs_default_metadata(
         environment_id: &str,
@@ -277,7 +390,19 @@ http {{
         service_data: &ServiceData,
         metadata: &mut HashMap<String, serde_json::Value>,
     ) -> Result<()> {
-        let _ = metadata; // 当前无默认键，保持签名一致
+        let envs_folder = {
+            let app_config_manager = AppConfigManager::global();
+            let app_config_manager = app_config_manager.lock().unwrap();
+            app_config_manager.get_envs_folder()
+        };
+        let data_dir = PathBuf::from(envs_folder)
+            .join(environment_id)
+            .join("postgresql")
+            .join(&service_data.version)
+            .join("data");
+
+        Self::insert_postgresql_metadata(&data_dir, metadata);
+
         log::debug!(
             "已为 PostgreSQL 服务 {} {} (env: {}) 创建默认 metadata",
             service_data.name,
@@ -287,6 +412,49 @@ http {{
         Ok(())
     }
 
+    /// 写入 PostgreSQL 数据目录相关的 metadata
+    fn insert_postgresql_metadata(
+        data_dir: &Path,
+        metadata: &mut HashMap<String, serde_json::Value>,
+    ) {
+        let config_path = data_dir.join("postgresql.conf");
+        // 已初始化的数据目录以 postgresql.conf 中的端口为准
+        let port =
+            PostgresqlService::read_config_port(&config_path).unwrap_or(DEFAULT_POSTGRESQL_PORT);
+
+        // 数据目录由 initdb 创建，这里只记录路径
+        metadata.insert(
+            "POSTGRESQL_DATA".to_string(),
+            serde_json::Value::String(data_dir.to_string_lossy().to_string()),
+        );
+        metadata.insert(
+            "POSTGRESQL_CONFIG".to_string(),
+            serde_json::Value::String(config_path.to_string_lossy().to_string()),
+        );
+        metadata.insert(
+            "POSTGRESQL_LOG".to_string(),
+            serde_json::Value::String(
+                data_dir
+                    .join("postgresql.log")
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+        );
+        metadata.insert(
+            "POSTGRESQL_PORT".to_string(),
+            serde_json::Value::String(port.to_string()),
+        );
+        metadata.insert(
+            "POSTGRESQL_SUPERUSER".to_string(),
+            serde_json::Value::String("postgres".to_string()),
+        );
+        // 密码在初始化时设置，这里先写入空值
+        metadata.insert(
+            "POSTGRESQL_PASSWORD".to_string(),
+            serde_json::Value::String(String::new()),
+        );
+    }
+
     /// 构建 Python 服务的默认 metadata
     fn build_python_default_metadata(
         environment_id: &str,
@@ -314,8 +482,9 @@ http {{
         Ok(())
     }
 
-    /// 构建 Java 服务的默认 metadata
+    /// 构建 Rust 服务的默认 metadata
     fn build_rust_default_metadata(
+        environment_id: &str,
         service_data: &ServiceData,
         metadata: &mut HashMap<String, serde_json::Value>,
     ) -> Result<()> {
@@ -334,8 +503,9 @@ http {{
             serde_json::Value::String(install_path.to_string_lossy().to_string()),
         );
 
-        // CARGO_HOME = 安装目录下的 cargo 子文件夹
-        let cargo_home = install_path.join("cargo");
+        // CARGO_HOME = 环境目录下的 cargo 子文件夹，cargo install 的工具按环境隔离
+        let cargo_home =
+            RustService::global().get_default_cargo_home(environment_id, &service_data.version);
         metadata.insert(
             "CARGO_HOME".to_string(),
             serde_json::Value::String(cargo_home.to_string_lossy().to_string()),
@@ -417,6 +587,12 @@ http {{
             serde_json::Value::Object(serde_json::Map::new()),
         );
 
+        // 设置默认空的命令列表
+        metadata.insert("commands".to_string(), serde_json::Value::Array(vec![]));
+
+        // 设置默认空的已注册工具目录列表
+        metadata.insert("binaries".to_string(), serde_json::Value::Array(vec![]));
+
         // 默认关闭终端自动跳转目录
         metadata.insert(
             "autoChdirEnabled".to_string(),
@@ -552,4 +728,195 @@ port=5353
 
         Ok(())
     }
+
+    /// 构建 PHP 服务的默认 metadata
+    fn build_php_default_metadata(
+        environment_id: &str,
+        service_data: &ServiceData,
+        metadata: &mut HashMap<String, serde_json::Value>,
+    ) -> Result<()> {
+        let phprc = PhpService::global().ensure_config_files(environment_id, &service_data.version)?;
+
+        // PHPRC 指向环境目录，php 命令行与 php-fpm 共用生成的 php.ini
+        metadata.insert(
+            "PHPRC".to_string(),
+            serde_json::Value::String(phprc.to_string_lossy().to_string()),
+        );
+
+        log::debug!(
+            "已为 PHP 服务 {} {} (env: {}) 创建默认 metadata",
+            service_data.name,
+            service_data.version,
+            environment_id
+        );
+        Ok(())
+    }
+
+    /// 构建 Vault 服务的默认 metadata
+    fn build_vault_default_metadata(
+        environment_id: &str,
+        service_data: &ServiceData,
+        metadata: &mut HashMap<String, serde_json::Value>,
+    ) -> Result<()> {
+        metadata.insert(
+            "VAULT_ADDR".to_string(),
+            serde_json::Value::String(DEFAULT_VAULT_ADDR.to_string()),
+        );
+        // dev 模式以该 token 作为 -dev-root-token-id 启动，每个服务数据独立生成
+        metadata.insert(
+            "VAULT_TOKEN".to_string(),
+            serde_json::Value::String(uuid::Uuid::new_v4().to_string()),
+        );
+
+        log::debug!(
+            "已为 Vault 服务 {} {} (env: {}) 创建默认 metadata",
+            service_data.name,
+            service_data.version,
+            environment_id
+        );
+        Ok(())
+    }
+
+    /// 构建 OpenSearch 服务的默认 metadata
+    fn build_opensearch_default_metadata(
+        environment_id: &str,
+        service_data: &ServiceData,
+        metadata: &mut HashMap<String, serde_json::Value>,
+    ) {
+        metadata.insert(
+            "OPENSEARCH_PORT".to_string(),
+            serde_json::Value::String(DEFAULT_OPENSEARCH_PORT.to_string()),
+        );
+        metadata.insert(
+            "OPENSEARCH_HEAP_MB".to_string(),
+            serde_json::Value::String(DEFAULT_OPENSEARCH_HEAP_MB.to_string()),
+        );
+
+        log::debug!(
+            "已为 OpenSearch 服务 {} {} (env: {}) 创建默认 metadata",
+            service_data.name,
+            service_data.version,
+            environment_id
+        );
+    }
+
+    /// 构建 Kafka 服务的默认 metadata
+    fn build_kafka_default_metadata(
+        environment_id: &str,
+        service_data: &ServiceData,
+        metadata: &mut HashMap<String, serde_json::Value>,
+    ) {
+        metadata.insert(
+            "KAFKA_PORT".to_string(),
+            serde_json::Value::String(DEFAULT_KAFKA_PORT.to_string()),
+        );
+        metadata.insert(
+            "KAFKA_CONTROLLER_PORT".to_string(),
+            serde_json::Value::String(DEFAULT_KAFKA_CONTROLLER_PORT.to_string()),
+        );
+        metadata.insert(
+            "KAFKA_HEAP_MB".to_string(),
+            serde_json::Value::String(DEFAULT_KAFKA_HEAP_MB.to_string()),
+        );
+
+        log::debug!(
+            "已为 Kafka 服务 {} {} (env: {}) 创建默认 metadata",
+            service_data.name,
+            service_data.version,
+            environment_id
+        );
+    }
+
+    /// 构建 RabbitMQ 服务的默认 metadata
+    fn build_rabbitmq_default_metadata(
+        environment_id: &str,
+        service_data: &ServiceData,
+        metadata: &mut HashMap<String, serde_json::Value>,
+    ) {
+        metadata.insert(
+            "RABBITMQ_PORT".to_string(),
+            serde_json::Value::String(DEFAULT_RABBITMQ_PORT.to_string()),
+        );
+        metadata.insert(
+            "RABBITMQ_MANAGEMENT_PORT".to_string(),
+            serde_json::Value::String(DEFAULT_RABBITMQ_MANAGEMENT_PORT.to_string()),
+        );
+
+        log::debug!(
+            "已为 RabbitMQ 服务 {} {} (env: {}) 创建默认 metadata",
+            service_data.name,
+            service_data.version,
+            environment_id
+        );
+    }
+
+    /// 构建 Compose 服务的默认 metadata：项目名默认为环境名称，
+    /// 环境名称不能作为项目名时不设置，运行时使用环境 ID
+    fn build_compose_default_metadata(
+        environment_id: &str,
+        metadata: &mut HashMap<String, serde_json::Value>,
+    ) {
+        if let Some(project_name) = EnvironmentManager::read_environment_name(environment_id)
+            .and_then(|name| ComposeService::normalize_project_name(&name))
+        {
+            metadata.insert(
+                COMPOSE_PROJECT_NAME_KEY.to_string(),
+                serde_json::Value::String(project_name),
+            );
+        }
+    }
+}
+
+/// 读取数值型 metadata，兼容字符串与数字两种写法
+pub fn metadata_number(service_data: &ServiceData, key: &str) -> Option<u64> {
+    let value = service_data.metadata.as_ref()?.get(key)?;
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+/// 读取端口 metadata，未设置时返回 None，超出范围或为 0 时报错
+pub fn metadata_port(service_data: &ServiceData, key: &str) -> Result<Option<u16>> {
+    metadata_number(service_data, key)
+        .map(|port| {
+            u16::try_from(port)
+                .ok()
+                .filter(|port| *port > 0)
+                .ok_or_else(|| anyhow!("{} 无效: {}", key, port))
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata_value<'a>(metadata: &'a HashMap<String, serde_json::Value>, key: &str) -> &'a str {
+        metadata.get(key).and_then(|v| v.as_str()).unwrap()
+    }
+
+    #[test]
+    fn test_postgresql_metadata_port_and_password() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // 尚未初始化时使用默认端口
+        let mut metadata = HashMap::new();
+        MetadataBuilder::insert_postgresql_metadata(dir.path(), &mut metadata);
+        assert_eq!(
+            metadata_value(&metadata, "POSTGRESQL_PORT"),
+            DEFAULT_POSTGRESQL_PORT.to_string()
+        );
+        assert_eq!(metadata_value(&metadata, "POSTGRESQL_PASSWORD"), "");
+
+        // 已有 postgresql.conf 时以其中的端口为准
+        fs::write(
+            dir.path().join("postgresql.conf"),
+            "listen_addresses = '127.0.0.1'\nport = 5433  # 自定义端口\n",
+        )
+        .unwrap();
+        let mut metadata = HashMap::new();
+        MetadataBuilder::insert_postgresql_metadata(dir.path(), &mut metadata);
+        assert_eq!(metadata_value(&metadata, "POSTGRESQL_PORT"), "5433");
+        assert!(metadata.contains_key("POSTGRESQL_PASSWORD"));
+    }
 }
@@ -9,5 +9,8 @@ pub mod envvars;
 pub mod metadata;
 
 pub use envpaths::EnvPathBuilder;
-pub use envvars::EnvVarBuilder;
-pub use metadata::MetadataBuilder;
+pub use envvars::{EnvVarBuilder, ENVIRONMENT_ENV_VARS_KEY};
+pub use metadata::{
+    metadata_number, metadata_port, DatabaseMetadataKeys, MetadataBuilder,
+    MARIADB_METADATA_KEYS, MONGODB_METADATA_KEYS, MYSQL_METADATA_KEYS,
+};
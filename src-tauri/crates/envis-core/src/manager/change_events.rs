@@ -0,0 +1,112 @@
+use serde::Serialize;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+
+use crate::types::{Environment, ServiceData};
+
+/// 环境创建 / 修改 / 删除 / 激活 / 停用事件
+pub const ENVIRONMENT_CHANGED_EVENT: &str = "environment-changed";
+/// 服务数据创建 / 修改 / 删除 / 激活 / 停用事件
+pub const SERVICE_DATA_CHANGED_EVENT: &str = "service-data-changed";
+/// 状态检测发现服务启动 / 停止事件
+pub const SERVICE_STATUS_CHANGED_EVENT: &str = "service-status-changed";
+
+/// 变更事件回调：事件名 + payload
+pub type ChangeEventCallback = Arc<dyn Fn(&str, serde_json::Value) + Send + Sync>;
+
+/// 变更类型
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeOperation {
+    Created,
+    Updated,
+    Deleted,
+    Activated,
+    Deactivated,
+}
+
+type QueuedEvent = (&'static str, serde_json::Value);
+
+/// 事件队列发送端，未启动分发线程时为空，此时不产生事件（如 CLI）
+static EVENT_SENDER: OnceLock<Mutex<Sender<QueuedEvent>>> = OnceLock::new();
+
+/// 启动变更事件分发线程，重复调用时忽略
+///
+/// 管理器在持有自身锁时产生事件，事件先放入队列，由分发线程调用回调推送，
+/// 避免前端收到事件后立即查询状态时等待尚未释放的锁。
+pub fn start(event_callback: ChangeEventCallback) {
+    let (sender, receiver) = mpsc::channel::<QueuedEvent>();
+    if EVENT_SENDER.set(Mutex::new(sender)).is_err() {
+        return;
+    }
+
+    let spawned = thread::Builder::new()
+        .name("change-events".to_string())
+        .spawn(move || {
+            for (event, payload) in receiver {
+                event_callback(event, payload);
+            }
+        });
+    if let Err(e) = spawned {
+        log::error!("启动变更事件分发线程失败: {}", e);
+    }
+}
+
+fn publish(event: &'static str, payload: serde_json::Value) {
+    let Some(sender) = EVENT_SENDER.get() else {
+        return;
+    };
+    if let Ok(sender) = sender.lock() {
+        if sender.send((event, payload)).is_err() {
+            log::warn!("变更事件 {} 入队失败，分发线程已退出", event);
+        }
+    }
+}
+
+/// 环境发生变更，删除时 environment 为删除前的记录
+pub fn environment_changed(operation: ChangeOperation, environment: &Environment) {
+    publish(
+        ENVIRONMENT_CHANGED_EVENT,
+        serde_json::json!({
+            "operation": operation,
+            "environmentId": environment.id,
+            "environment": environment,
+        }),
+    );
+}
+
+/// 服务数据发生变更，删除时 service_data 为删除前的记录
+pub fn service_data_changed(
+    operation: ChangeOperation,
+    environment_id: &str,
+    service_data: &ServiceData,
+) {
+    publish(
+        SERVICE_DATA_CHANGED_EVENT,
+        serde_json::json!({
+            "operation": operation,
+            "environmentId": environment_id,
+            "serviceId": service_data.id,
+            "serviceData": service_data,
+        }),
+    );
+}
+
+/// 服务运行状态发生变化，status 为 "running" / "stopped" 等
+pub fn service_status_changed(
+    environment_id: &str,
+    service_id: &str,
+    previous: &str,
+    status: &str,
+) {
+    publish(
+        SERVICE_STATUS_CHANGED_EVENT,
+        serde_json::json!({
+            "environmentId": environment_id,
+            "serviceId": service_id,
+            "previousStatus": previous,
+            "status": status,
+        }),
+    );
+}
@@ -10,11 +10,14 @@ use uuid::Uuid;
 
 use crate::manager::app_config_manager::AppConfigManager;
 use crate::manager::builders::MetadataBuilder;
+use crate::manager::change_events::{self, ChangeOperation};
 use crate::manager::host_manager::HostManager;
-use crate::manager::services::{
-    CustomService, HostService, JavaService, NodejsService, RustService, ServiceLifecycle, StandardService,
+use crate::manager::service_manager::ServiceManager;
+use crate::manager::services::{JavaService, ServiceRegistry};
+use crate::types::{
+    Environment, ServiceData, ServiceDataStatus, ServiceType, UpdateServiceDataRequest,
 };
-use crate::types::{ServiceData, ServiceDataStatus, ServiceType, UpdateServiceDataRequest};
+use crate::utils::path::to_unix_path_string;
 
 const ENV_SERVICE_CONFIG_FILE_NAME: &str = "service.json";
 
@@ -26,6 +29,24 @@ pub struct ServiceDataResult {
     pub data: Option<serde_json::Value>,
 }
 
+/// 单个服务数据的完整性检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceDataIntegrity {
+    pub service_data_id: String,
+    pub service_type: ServiceType,
+    pub version: String,
+    pub issues: Vec<String>,
+}
+
+/// 跨环境搜索服务数据的单条结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceDataSearchResult {
+    pub environment: Environment,
+    pub service_data: ServiceData,
+}
+
 /// 全局环境服务数据管理器单例
 static ENV_SERV_DATA_MANAGER: OnceLock<Arc<Mutex<EnvServDataManager>>> = OnceLock::new();
 
@@ -192,6 +213,68 @@ impl EnvServDataManager {
         Ok(service_datas)
     }
 
+    /// 在给定环境中搜索服务数据：名称或版本包含 query（不区分大小写），可按类型和状态过滤
+    ///
+    /// 环境列表由调用方从 EnvironmentManager 获取，避免持有本管理器锁时再获取其锁
+    pub fn search_service_data(
+        &self,
+        environments: &[Environment],
+        query: &str,
+        service_type: Option<ServiceType>,
+        status: Option<ServiceDataStatus>,
+    ) -> Result<Vec<ServiceDataSearchResult>> {
+        let query = query.trim().to_lowercase();
+        let mut results = Vec::new();
+
+        for environment in environments {
+            let service_datas = match self.get_environment_all_service_datas(&environment.id) {
+                Ok(service_datas) => service_datas,
+                Err(e) => {
+                    log::warn!("读取环境 {} 的服务数据失败: {}", environment.id, e);
+                    continue;
+                }
+            };
+
+            results.extend(
+                service_datas
+                    .into_iter()
+                    .filter(|sd| {
+                        query.is_empty()
+                            || format!("{} {}", sd.name, sd.version)
+                                .to_lowercase()
+                                .contains(&query)
+                    })
+                    .filter(|sd| service_type.as_ref().is_none_or(|t| *t == sd.service_type))
+                    .filter(|sd| status.as_ref().is_none_or(|s| *s == sd.status))
+                    .map(|service_data| ServiceDataSearchResult {
+                        environment: environment.clone(),
+                        service_data,
+                    }),
+            );
+        }
+
+        Ok(results)
+    }
+
+    /// 将多个 metadata 键值合并到文件中的服务数据并保存，返回保存后的服务数据
+    ///
+    /// 以文件中的记录为准，避免调用方持有的旧副本覆盖其他字段
+    pub fn merge_metadata(
+        &self,
+        environment_id: &str,
+        service_id: &str,
+        entries: HashMap<String, serde_json::Value>,
+    ) -> Result<ServiceData> {
+        let mut service_data = self.get_service_data(environment_id, service_id)?;
+        service_data
+            .metadata
+            .get_or_insert_with(HashMap::new)
+            .extend(entries);
+        service_data.updated_at = Utc::now().to_rfc3339();
+        self.save_service_data(environment_id, &service_data)?;
+        Ok(service_data)
+    }
+
     /// 获取指定环境的单个服务数据（从文件读取）
     pub fn get_service_data(&self, environment_id: &str, service_id: &str) -> Result<ServiceData> {
         let service_datas = self.get_environment_all_service_datas(environment_id)?;
@@ -209,10 +292,7 @@ impl EnvServDataManager {
         version: String,
     ) -> Result<ServiceDataResult> {
         // 1. 生成 ID (uuid v4 前8位 + 时间戳)
-        let uuid = Uuid::new_v4().to_string();
-        let short_id = &uuid[0..8];
-        let timestamp = Utc::now().timestamp();
-        let id = format!("{}-{}", short_id, timestamp);
+        let id = Self::generate_service_data_id();
 
         // 2. 生成名称
         let name = service_type.default_name();
@@ -250,7 +330,12 @@ impl EnvServDataManager {
         }
 
         // 5. 保存
-        self.save_service_data(environment_id, &service_data)?;
+        self.write_service_data(environment_id, &service_data)?;
+        change_events::service_data_changed(
+            ChangeOperation::Created,
+            environment_id,
+            &service_data,
+        );
 
         Ok(ServiceDataResult {
             success: true,
@@ -271,7 +356,96 @@ impl EnvServDataManager {
             .find(|sd| sd.id == request.id)
             .context("找不到指定的服务数据")?;
 
-        // 应用更新
+        Self::apply_update_request(&mut target_service, request);
+
+        // 直接保存，不再处理路径变化（因为 type/version 不变，路径就不会变）
+        self.save_service_data(environment_id, &target_service)?;
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "服务更新成功".to_string(),
+            data: Some(serde_json::json!({ "serviceData": target_service })),
+        })
+    }
+
+    /// 批量更新服务数据（如拖拽排序）
+    ///
+    /// 先校验所有 ID 并在内存中应用更新，任一 ID 不存在则不写入任何文件；
+    /// 写入时先全部写到临时文件，再统一替换，避免只更新了一部分记录。
+    pub fn bulk_update_service_data(
+        &self,
+        environment_id: &str,
+        requests: Vec<UpdateServiceDataRequest>,
+    ) -> Result<ServiceDataResult> {
+        let mut service_datas: HashMap<String, ServiceData> = self
+            .get_environment_all_service_datas(environment_id)?
+            .into_iter()
+            .map(|sd| (sd.id.clone(), sd))
+            .collect();
+
+        let mut updated_ids: Vec<String> = Vec::new();
+        for request in requests {
+            let id = request.id.clone();
+            let target = service_datas
+                .get_mut(&id)
+                .with_context(|| format!("找不到指定的服务数据: {}", id))?;
+            Self::apply_update_request(target, request);
+            if !updated_ids.contains(&id) {
+                updated_ids.push(id);
+            }
+        }
+
+        // 先写临时文件，全部成功后再替换
+        let mut pending = Vec::new();
+        for id in &updated_ids {
+            let service_data = &service_datas[id];
+            let (_, _, _, _, service_data_folder, service_config_path) =
+                self.build_service_paths(environment_id, service_data)?;
+            if !service_data_folder.exists() {
+                fs::create_dir_all(&service_data_folder).context("创建服务数据文件夹失败")?;
+            }
+            let tmp_path = service_config_path.with_extension("json.tmp");
+            let written = serde_json::to_string_pretty(service_data)
+                .context("序列化服务数据失败")
+                .and_then(|content| {
+                    fs::write(&tmp_path, content).context("写入服务配置临时文件失败")
+                });
+            if let Err(e) = written {
+                for (tmp, _) in &pending {
+                    let _ = fs::remove_file(tmp);
+                }
+                let _ = fs::remove_file(&tmp_path);
+                return Err(e);
+            }
+            pending.push((tmp_path, service_config_path));
+        }
+        for (tmp_path, config_path) in &pending {
+            fs::rename(tmp_path, config_path).context("替换服务配置文件失败")?;
+        }
+        for id in &updated_ids {
+            change_events::service_data_changed(
+                ChangeOperation::Updated,
+                environment_id,
+                &service_datas[id],
+            );
+        }
+
+        log::info!(
+            "已批量更新 {} 个服务数据 (env: {})",
+            updated_ids.len(),
+            environment_id
+        );
+
+        let updated: Vec<&ServiceData> = updated_ids.iter().map(|id| &service_datas[id]).collect();
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("已更新 {} 个服务", updated.len()),
+            data: Some(serde_json::json!({ "serviceDatas": updated })),
+        })
+    }
+
+    /// 将更新请求应用到服务数据（不落盘）
+    fn apply_update_request(target_service: &mut ServiceData, request: UpdateServiceDataRequest) {
         if let Some(name) = request.name {
             target_service.name = name;
         }
@@ -288,15 +462,6 @@ impl EnvServDataManager {
         }
 
         target_service.updated_at = Utc::now().to_rfc3339();
-
-        // 直接保存，不再处理路径变化（因为 type/version 不变，路径就不会变）
-        self.save_service_data(environment_id, &target_service)?;
-
-        Ok(ServiceDataResult {
-            success: true,
-            message: "服务更新成功".to_string(),
-            data: Some(serde_json::json!({ "serviceData": target_service })),
-        })
     }
 
     /// 根据 ID 删除服务数据
@@ -311,7 +476,149 @@ impl EnvServDataManager {
             .find(|sd| sd.id == service_id)
             .context("找不到指定的服务数据")?;
 
-        self.remove_service_directories(environment_id, &target_service)
+        let result = self.remove_service_directories(environment_id, &target_service)?;
+        change_events::service_data_changed(
+            ChangeOperation::Deleted,
+            environment_id,
+            &target_service,
+        );
+        Ok(result)
+    }
+
+    /// 复制服务数据到同一或另一个环境：复制数据目录与 metadata，并将其中指向
+    /// 源环境目录的路径改写为目标环境目录，新服务数据为 Inactive 状态。
+    ///
+    /// 服务数据目录按 类型/版本 存放，目标环境中已有同类型同版本的服务数据时无法复制。
+    pub fn duplicate_service_data(
+        &self,
+        src_environment_id: &str,
+        src_service_id: &str,
+        dest_environment_id: &str,
+        new_name: Option<String>,
+    ) -> Result<ServiceDataResult> {
+        let source = self.get_service_data(src_environment_id, src_service_id)?;
+        let (_, src_env_folder, _, _, src_data_folder, _) =
+            self.build_service_paths(src_environment_id, &source)?;
+        let (_, dest_env_folder, _, _, dest_data_folder, _) =
+            self.build_service_paths(dest_environment_id, &source)?;
+
+        if !dest_env_folder.exists() {
+            return Err(anyhow::anyhow!("找不到目标环境: {}", dest_environment_id));
+        }
+        if dest_data_folder.exists() {
+            return Err(anyhow::anyhow!(
+                "目标环境中已存在 {} {} 的服务数据",
+                source.service_type.default_name(),
+                source.version
+            ));
+        }
+
+        let service_datas = self.get_environment_all_service_datas(dest_environment_id)?;
+        let min_sort = service_datas
+            .iter()
+            .filter_map(|sd| sd.sort)
+            .min()
+            .unwrap_or(0);
+        let now = Utc::now().to_rfc3339();
+        let name = new_name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| source.name.clone());
+
+        let mut service_data = ServiceData {
+            id: Self::generate_service_data_id(),
+            name,
+            service_type: source.service_type.clone(),
+            version: source.version.clone(),
+            status: ServiceDataStatus::Inactive,
+            sort: Some(min_sort - 1),
+            metadata: source.metadata.clone(),
+            created_at: now.clone(),
+            updated_at: now,
+        };
+
+        let replacements = Self::build_path_replacements(&[(&src_env_folder, &dest_env_folder)]);
+        if let Some(metadata) = service_data.metadata.as_mut() {
+            for value in metadata.values_mut() {
+                Self::rewrite_json_paths(value, &replacements);
+            }
+        }
+
+        let copied =
+            Self::copy_service_data_folder(&src_data_folder, &dest_data_folder).and_then(|_| {
+                Self::rewrite_config_file_paths(&dest_data_folder, &replacements);
+                self.write_service_data(dest_environment_id, &service_data)
+            });
+        if let Err(e) = copied {
+            // 复制失败时清理不完整的目标目录
+            let _ = fs::remove_dir_all(&dest_data_folder);
+            return Err(e);
+        }
+
+        change_events::service_data_changed(
+            ChangeOperation::Created,
+            dest_environment_id,
+            &service_data,
+        );
+        log::info!(
+            "已复制服务数据 {} ({}) 到环境 {}: {}",
+            source.name,
+            source.id,
+            dest_environment_id,
+            service_data.id
+        );
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("服务 {} 复制成功", service_data.name),
+            data: Some(serde_json::json!({ "serviceData": service_data })),
+        })
+    }
+
+    /// 复制服务数据目录，跳过服务配置文件与 pid / lock / socket 等运行时文件
+    fn copy_service_data_folder(from: &Path, to: &Path) -> Result<()> {
+        const RUNTIME_EXTENSIONS: &[&str] = &["pid", "lock", "sock"];
+
+        fs::create_dir_all(to).context("创建服务数据文件夹失败")?;
+        if !from.exists() {
+            return Ok(());
+        }
+
+        for entry in walkdir::WalkDir::new(from)
+            .min_depth(1)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            let src_path = entry.path();
+            let rel = src_path.strip_prefix(from)?;
+            let target = to.join(rel);
+
+            if entry.file_type().is_dir() {
+                fs::create_dir_all(&target).context("创建服务数据子目录失败")?;
+                continue;
+            }
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let skip = rel == Path::new(ENV_SERVICE_CONFIG_FILE_NAME)
+                || src_path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .map(|ext| RUNTIME_EXTENSIONS.contains(&ext))
+                    .unwrap_or(false);
+            if skip {
+                continue;
+            }
+            fs::copy(src_path, &target)
+                .with_context(|| format!("复制文件失败: {}", src_path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// 生成服务数据 ID（uuid v4 前8位 + 时间戳）
+    fn generate_service_data_id() -> String {
+        let uuid = Uuid::new_v4().to_string();
+        format!("{}-{}", &uuid[0..8], Utc::now().timestamp())
     }
 
     /// 保存服务数据到环境
@@ -320,6 +627,18 @@ impl EnvServDataManager {
         environment_id: &str,
         service_data: &ServiceData,
     ) -> Result<ServiceDataResult> {
+        self.write_service_data(environment_id, service_data)?;
+        change_events::service_data_changed(ChangeOperation::Updated, environment_id, service_data);
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "服务保存成功".to_string(),
+            data: None,
+        })
+    }
+
+    /// 写入服务配置文件，不推送变更事件
+    fn write_service_data(&self, environment_id: &str, service_data: &ServiceData) -> Result<()> {
         let (_, _, _, _, service_data_folder, service_config_path) =
             self.build_service_paths(environment_id, service_data)?;
 
@@ -338,12 +657,7 @@ impl EnvServDataManager {
             service_data.version,
             service_data.id
         );
-
-        Ok(ServiceDataResult {
-            success: true,
-            message: "服务保存成功".to_string(),
-            data: None,
-        })
+        Ok(())
     }
 
     /// 删除服务数据文件夹 (底层实现)
@@ -380,7 +694,12 @@ impl EnvServDataManager {
         password: Option<String>,
     ) -> Result<ServiceDataResult> {
         // 需要下载安装的服务，若安装目录不存在则静默跳过，保持 Inactive 状态
-        if service_data.service_type.needs_download() {
+        // （Java 服务的 JAVA_HOME 指向外部 JDK 时无需下载）
+        let uses_external_jdk = service_data.service_type == ServiceType::Java
+            && JavaService::global()
+                .external_java_home(service_data)
+                .is_some();
+        if service_data.service_type.needs_download() && !uses_external_jdk {
             let app_config_manager = AppConfigManager::global();
             let services_folder = {
                 let manager = app_config_manager.lock().unwrap();
@@ -407,14 +726,7 @@ impl EnvServDataManager {
             }
         }
 
-        let handler: Arc<dyn ServiceLifecycle> = match service_data.service_type {
-            ServiceType::Host => HostService::global(),
-            ServiceType::Custom => CustomService::global(),
-            ServiceType::Nodejs => NodejsService::global(),
-            ServiceType::Java => JavaService::global(),
-            ServiceType::Rust => RustService::global(),
-            _ => StandardService::global(),
-        };
+        let handler = ServiceRegistry::get(&service_data.service_type);
 
         handler.active(environment_id, service_data, password)?;
 
@@ -424,7 +736,12 @@ impl EnvServDataManager {
             service_data.status = ServiceDataStatus::Active;
         }
         service_data.updated_at = Utc::now().to_rfc3339();
-        self.save_service_data(environment_id, service_data)?;
+        self.write_service_data(environment_id, service_data)?;
+        change_events::service_data_changed(
+            ChangeOperation::Activated,
+            environment_id,
+            service_data,
+        );
 
         Ok(ServiceDataResult {
             success: true,
@@ -440,14 +757,7 @@ impl EnvServDataManager {
         service_data: &mut ServiceData,
         password: Option<String>,
     ) -> Result<ServiceDataResult> {
-        let handler: Arc<dyn ServiceLifecycle> = match service_data.service_type {
-            ServiceType::Host => HostService::global(),
-            ServiceType::Custom => CustomService::global(),
-            ServiceType::Nodejs => NodejsService::global(),
-            ServiceType::Java => JavaService::global(),
-            ServiceType::Rust => RustService::global(),
-            _ => StandardService::global(),
-        };
+        let handler = ServiceRegistry::get(&service_data.service_type);
 
         handler.deactive(environment_id, service_data, password)?;
 
@@ -457,7 +767,12 @@ impl EnvServDataManager {
             service_data.status = ServiceDataStatus::Inactive;
         }
         service_data.updated_at = Utc::now().to_rfc3339();
-        self.save_service_data(environment_id, service_data)?;
+        self.write_service_data(environment_id, service_data)?;
+        change_events::service_data_changed(
+            ChangeOperation::Deactivated,
+            environment_id,
+            service_data,
+        );
 
         Ok(ServiceDataResult {
             success: true,
@@ -466,6 +781,244 @@ impl EnvServDataManager {
         })
     }
 
+    /// 修改服务数据的版本（仅供版本升级使用，UpdateServiceDataRequest 不允许修改版本）
+    ///
+    /// 服务数据目录按版本存放，这里会把目录移动到新版本下，并将 metadata 和配置文件中
+    /// 指向旧数据目录、旧安装目录的路径改写为新版本的路径。
+    pub(crate) fn change_service_data_version(
+        &self,
+        environment_id: &str,
+        service_data: &mut ServiceData,
+        target_version: &str,
+    ) -> Result<()> {
+        let (_, _, _, old_service_folder, old_data_folder, _) =
+            self.build_service_paths(environment_id, service_data)?;
+        let mut upgraded = service_data.clone();
+        upgraded.version = target_version.to_string();
+        let (_, _, _, new_service_folder, new_data_folder, _) =
+            self.build_service_paths(environment_id, &upgraded)?;
+
+        if new_data_folder.exists() {
+            return Err(anyhow::anyhow!(
+                "目标版本的服务数据目录已存在: {}",
+                new_data_folder.display()
+            ));
+        }
+        if old_data_folder.exists() {
+            if let Some(parent) = new_data_folder.parent() {
+                fs::create_dir_all(parent).context("创建服务数据文件夹失败")?;
+            }
+            fs::rename(&old_data_folder, &new_data_folder).context("移动服务数据文件夹失败")?;
+        }
+
+        let replacements = Self::build_path_replacements(&[
+            (&old_data_folder, &new_data_folder),
+            (&old_service_folder, &new_service_folder),
+        ]);
+        if let Some(metadata) = upgraded.metadata.as_mut() {
+            for value in metadata.values_mut() {
+                Self::rewrite_json_paths(value, &replacements);
+            }
+        }
+        Self::rewrite_config_file_paths(&new_data_folder, &replacements);
+
+        upgraded.updated_at = Utc::now().to_rfc3339();
+        self.save_service_data(environment_id, &upgraded)?;
+        *service_data = upgraded;
+        Ok(())
+    }
+
+    /// 生成路径替换表，同时包含系统原生路径与 `/` 分隔的路径写法
+    fn build_path_replacements(pairs: &[(&Path, &Path)]) -> Vec<(String, String)> {
+        let mut replacements = Vec::new();
+        for (old, new) in pairs {
+            let native = (
+                old.to_string_lossy().to_string(),
+                new.to_string_lossy().to_string(),
+            );
+            let unix = (to_unix_path_string(old), to_unix_path_string(new));
+            if native != unix {
+                replacements.push(unix);
+            }
+            replacements.push(native);
+        }
+        replacements
+    }
+
+    /// 替换文本中的路径前缀，只匹配完整的路径段（避免 6.0.1 误匹配 6.0.10）
+    fn replace_path_prefix(text: &str, old: &str, new: &str) -> String {
+        if old.is_empty() || !text.contains(old) {
+            return text.to_string();
+        }
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(pos) = rest.find(old) {
+            let after = &rest[pos + old.len()..];
+            let at_boundary = after.chars().next().is_none_or(|c| {
+                !(c.is_alphanumeric() || matches!(c, '.' | '-' | '_'))
+            });
+            result.push_str(&rest[..pos]);
+            result.push_str(if at_boundary { new } else { old });
+            rest = after;
+        }
+        result.push_str(rest);
+        result
+    }
+
+    fn rewrite_json_paths(value: &mut serde_json::Value, replacements: &[(String, String)]) {
+        match value {
+            serde_json::Value::String(s) => {
+                for (old, new) in replacements {
+                    *s = Self::replace_path_prefix(s, old, new);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    Self::rewrite_json_paths(item, replacements);
+                }
+            }
+            serde_json::Value::Object(map) => {
+                for item in map.values_mut() {
+                    Self::rewrite_json_paths(item, replacements);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 改写服务数据目录下配置文件中的路径（只处理浅层的小型文本配置文件，不触碰数据文件）
+    fn rewrite_config_file_paths(data_folder: &Path, replacements: &[(String, String)]) {
+        const CONFIG_EXTENSIONS: &[&str] =
+            &["conf", "cnf", "ini", "yaml", "yml", "toml", "properties"];
+        const MAX_CONFIG_SIZE: u64 = 1024 * 1024;
+
+        for entry in walkdir::WalkDir::new(data_folder)
+            .max_depth(2)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+        {
+            let path = entry.path();
+            let is_config = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| CONFIG_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+                .unwrap_or(false);
+            let small = entry
+                .metadata()
+                .map(|m| m.len() <= MAX_CONFIG_SIZE)
+                .unwrap_or(false);
+            if !is_config || !small {
+                continue;
+            }
+
+            let Ok(content) = fs::read_to_string(path) else {
+                continue;
+            };
+            let updated = replacements
+                .iter()
+                .fold(content.clone(), |acc, (old, new)| {
+                    Self::replace_path_prefix(&acc, old, new)
+                });
+            if updated != content {
+                match fs::write(path, updated) {
+                    Ok(_) => log::info!("已更新配置文件中的路径: {}", path.display()),
+                    Err(e) => log::warn!("更新配置文件 {} 失败: {}", path.display(), e),
+                }
+            }
+        }
+    }
+
+    /// 检查环境中服务数据的完整性：服务是否已安装、metadata 中的路径是否存在、配置文件能否解析
+    pub fn validate_service_data_integrity(
+        &self,
+        environment_id: &str,
+    ) -> Result<Vec<ServiceDataIntegrity>> {
+        let service_datas = self.get_environment_all_service_datas(environment_id)?;
+        let service_manager = ServiceManager::global();
+
+        Ok(service_datas
+            .iter()
+            .map(|service_data| {
+                let mut issues = Vec::new();
+
+                if !service_manager
+                    .is_service_installed(&service_data.service_type, &service_data.version)
+                {
+                    issues.push(format!(
+                        "{} {} 未安装",
+                        service_data.service_type.default_name(),
+                        service_data.version
+                    ));
+                }
+
+                let mut metadata: Vec<_> = service_data.metadata.iter().flatten().collect();
+                metadata.sort_by(|a, b| a.0.cmp(b.0));
+                for (key, value) in metadata {
+                    let Some(value) = value.as_str().filter(|v| !v.is_empty()) else {
+                        continue;
+                    };
+                    if let Some(issue) = Self::check_metadata_path(key, Path::new(value)) {
+                        issues.push(issue);
+                    }
+                }
+
+                ServiceDataIntegrity {
+                    service_data_id: service_data.id.clone(),
+                    service_type: service_data.service_type.clone(),
+                    version: service_data.version.clone(),
+                    issues,
+                }
+            })
+            .collect())
+    }
+
+    /// 检查 metadata 中的路径项，非路径项返回 None
+    fn check_metadata_path(key: &str, path: &Path) -> Option<String> {
+        const PATH_KEY_SUFFIXES: [&str; 8] = [
+            "_CONFIG", "_CONF", "_DATA", "_LOG", "_HOME", "_PATH", "_PREFIX", "_DIR",
+        ];
+        let is_config = key.ends_with("_CONFIG") || key.ends_with("_CONF") || key == "PHPRC";
+        if !is_config && !PATH_KEY_SUFFIXES.iter().any(|suffix| key.ends_with(suffix)) {
+            return None;
+        }
+        if !path.is_absolute() {
+            return None;
+        }
+
+        // 日志文件在服务首次启动前可能尚未生成，只要求其所在目录存在
+        if key.ends_with("_LOG") && !path.exists() {
+            let parent = path.parent().filter(|p| !p.exists())?;
+            return Some(format!("{} 所在目录不存在: {}", key, parent.display()));
+        }
+        if !path.exists() {
+            return Some(format!("{} 指向的路径不存在: {}", key, path.display()));
+        }
+        if !is_config || !path.is_file() {
+            return None;
+        }
+
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => return Some(format!("{} 配置文件无法读取: {}", key, e)),
+        };
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        let parse_error = match extension.as_str() {
+            "json" => serde_json::from_str::<serde_json::Value>(&content)
+                .err()
+                .map(|e| e.to_string()),
+            "yaml" | "yml" => serde_yaml::from_str::<serde_yaml::Value>(&content)
+                .err()
+                .map(|e| e.to_string()),
+            _ => None,
+        };
+        parse_error.map(|e| format!("{} 配置文件解析失败: {}", key, e))
+    }
+
     /// 从文件加载服务数据配置
     fn load_service_data_from_file(&self, config_path: &Path) -> Result<ServiceData> {
         let config_content = fs::read_to_string(config_path).context("读取服务配置文件失败")?;
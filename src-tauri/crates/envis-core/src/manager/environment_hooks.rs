@@ -0,0 +1,349 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::manager::environment_manager::EnvironmentResult;
+use crate::manager::shell_manamger::ShellManager;
+use crate::types::Environment;
+use crate::utils::output_tail;
+
+/// 激活环境后执行的命令在 Environment.metadata 中的键
+pub const ON_ACTIVATE_METADATA_KEY: &str = "onActivate";
+/// 停用环境后执行的命令在 Environment.metadata 中的键
+pub const ON_DEACTIVATE_METADATA_KEY: &str = "onDeactivate";
+/// 单个钩子超时时间（秒）在 Environment.metadata 中的键
+pub const HOOK_TIMEOUT_METADATA_KEY: &str = "hookTimeoutSecs";
+/// 钩子执行失败事件
+pub const ENVIRONMENT_HOOK_FAILED_EVENT: &str = "environment-hook-failed";
+/// 单个钩子的默认超时时间（秒）
+pub const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 60;
+
+/// 单个钩子允许的最长超时时间（秒）
+const MAX_HOOK_TIMEOUT_SECS: u64 = 3600;
+/// 执行结果中 stdout / stderr 保留的最大字符数（保留末尾）
+const OUTPUT_SNIPPET_CHARS: usize = 2000;
+/// 钩子执行结果在操作结果 data 中的键
+const HOOK_RESULTS_DATA_KEY: &str = "hookResults";
+
+/// 钩子触发时机
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvironmentHookPhase {
+    Activate,
+    Deactivate,
+}
+
+/// 环境激活 / 停用钩子配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentHooks {
+    #[serde(default)]
+    pub on_activate: Vec<String>,
+    #[serde(default)]
+    pub on_deactivate: Vec<String>,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_HOOK_TIMEOUT_SECS
+}
+
+/// 单个钩子的执行结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentHookResult {
+    pub environment_id: String,
+    pub phase: EnvironmentHookPhase,
+    pub command: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    /// 命令无法执行或超时时的错误信息
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+impl EnvironmentHooks {
+    /// 读取环境 metadata 中的钩子配置
+    pub fn from_environment(environment: &Environment) -> Self {
+        let metadata = environment.metadata.as_ref();
+        let commands = |key: &str| -> Vec<String> {
+            metadata
+                .and_then(|m| m.get(key))
+                .and_then(|v| v.as_array())
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter_map(|item| item.as_str())
+                        .map(|item| item.to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        Self {
+            on_activate: commands(ON_ACTIVATE_METADATA_KEY),
+            on_deactivate: commands(ON_DEACTIVATE_METADATA_KEY),
+            timeout_secs: metadata
+                .and_then(|m| m.get(HOOK_TIMEOUT_METADATA_KEY))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(DEFAULT_HOOK_TIMEOUT_SECS),
+        }
+    }
+
+    /// 去除空命令并校验超时时间
+    pub fn normalized(self) -> Result<Self> {
+        if self.timeout_secs == 0 || self.timeout_secs > MAX_HOOK_TIMEOUT_SECS {
+            return Err(anyhow!(
+                "钩子超时时间必须在 1 ~ {} 秒之间",
+                MAX_HOOK_TIMEOUT_SECS
+            ));
+        }
+        let clean = |commands: Vec<String>| -> Vec<String> {
+            commands
+                .into_iter()
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+                .collect()
+        };
+
+        Ok(Self {
+            on_activate: clean(self.on_activate),
+            on_deactivate: clean(self.on_deactivate),
+            timeout_secs: self.timeout_secs,
+        })
+    }
+
+    /// 写入 metadata 的键值，未配置的键写入 null 以删除
+    pub fn to_metadata(&self) -> Vec<(&'static str, serde_json::Value)> {
+        let commands = |commands: &[String]| {
+            if commands.is_empty() {
+                serde_json::Value::Null
+            } else {
+                serde_json::json!(commands)
+            }
+        };
+
+        vec![
+            (ON_ACTIVATE_METADATA_KEY, commands(&self.on_activate)),
+            (ON_DEACTIVATE_METADATA_KEY, commands(&self.on_deactivate)),
+            (
+                HOOK_TIMEOUT_METADATA_KEY,
+                if self.timeout_secs == DEFAULT_HOOK_TIMEOUT_SECS {
+                    serde_json::Value::Null
+                } else {
+                    serde_json::json!(self.timeout_secs)
+                },
+            ),
+        ]
+    }
+
+    /// 是否为钩子配置使用的 metadata 键
+    pub fn is_hook_metadata_key(key: &str) -> bool {
+        matches!(
+            key,
+            ON_ACTIVATE_METADATA_KEY | ON_DEACTIVATE_METADATA_KEY | HOOK_TIMEOUT_METADATA_KEY
+        )
+    }
+
+    /// 依次执行环境的钩子，单个钩子失败不影响后续钩子
+    ///
+    /// 命令在 login shell 中执行，可以读取到刚写入 Shell 配置的 PATH 与环境变量。
+    pub fn run(
+        environment: &Environment,
+        phase: EnvironmentHookPhase,
+    ) -> Vec<EnvironmentHookResult> {
+        let hooks = Self::from_environment(environment);
+        let commands = match phase {
+            EnvironmentHookPhase::Activate => &hooks.on_activate,
+            EnvironmentHookPhase::Deactivate => &hooks.on_deactivate,
+        };
+        if commands.is_empty() {
+            return Vec::new();
+        }
+
+        let timeout = Duration::from_secs(hooks.timeout_secs.clamp(1, MAX_HOOK_TIMEOUT_SECS));
+        let env_vars = HashMap::from([
+            ("ENVIS_ENVIRONMENT_ID".to_string(), environment.id.clone()),
+            (
+                "ENVIS_ENVIRONMENT_NAME".to_string(),
+                environment.name.clone(),
+            ),
+        ]);
+
+        commands
+            .iter()
+            .map(|command| {
+                log::info!(
+                    "执行环境 {} 的 {:?} 钩子: {}",
+                    environment.name,
+                    phase,
+                    command
+                );
+                let started = Instant::now();
+                let output =
+                    ShellManager::execute_command_with_env_vars(command, &env_vars, Some(timeout));
+                let duration_ms = started.elapsed().as_millis() as u64;
+
+                let result = match output {
+                    Ok((stdout, stderr, exit_code)) => EnvironmentHookResult {
+                        environment_id: environment.id.clone(),
+                        phase,
+                        command: command.clone(),
+                        success: exit_code == 0,
+                        exit_code: Some(exit_code),
+                        stdout: output_tail(&stdout, OUTPUT_SNIPPET_CHARS),
+                        stderr: output_tail(&stderr, OUTPUT_SNIPPET_CHARS),
+                        error: None,
+                        duration_ms,
+                    },
+                    Err(e) => EnvironmentHookResult {
+                        environment_id: environment.id.clone(),
+                        phase,
+                        command: command.clone(),
+                        success: false,
+                        exit_code: None,
+                        stdout: String::new(),
+                        stderr: String::new(),
+                        error: Some(e.to_string()),
+                        duration_ms,
+                    },
+                };
+                if !result.success {
+                    log::warn!(
+                        "环境 {} 的钩子执行失败: {} ({})",
+                        environment.name,
+                        command,
+                        result
+                            .error
+                            .clone()
+                            .unwrap_or_else(|| format!("退出码 {:?}", result.exit_code))
+                    );
+                }
+                result
+            })
+            .collect()
+    }
+
+    /// 将钩子执行结果附加到激活 / 停用结果：data 中写入 hookResults，有失败时在 message 中说明
+    ///
+    /// 钩子失败不改变操作本身的 success。
+    pub fn attach_results(
+        mut result: EnvironmentResult,
+        results: Vec<EnvironmentHookResult>,
+    ) -> EnvironmentResult {
+        if results.is_empty() {
+            return result;
+        }
+
+        let failed: Vec<&str> = results
+            .iter()
+            .filter(|r| !r.success)
+            .map(|r| r.command.as_str())
+            .collect();
+        if !failed.is_empty() {
+            result.message = format!(
+                "{}，但 {} 个钩子执行失败: {}",
+                result.message,
+                failed.len(),
+                failed.join("; ")
+            );
+        }
+
+        let results = serde_json::json!(results);
+        match result.data.as_mut() {
+            Some(serde_json::Value::Object(data)) => {
+                data.insert(HOOK_RESULTS_DATA_KEY.to_string(), results);
+            }
+            _ => {
+                result.data = Some(serde_json::json!({ HOOK_RESULTS_DATA_KEY: results }));
+            }
+        }
+        result
+    }
+
+    /// 从激活 / 停用结果的 data 中取出钩子执行结果
+    pub fn results_from_data(data: Option<&serde_json::Value>) -> Vec<EnvironmentHookResult> {
+        data.and_then(|d| d.get(HOOK_RESULTS_DATA_KEY))
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook_result(command: &str, success: bool) -> EnvironmentHookResult {
+        EnvironmentHookResult {
+            environment_id: "env-1".to_string(),
+            phase: EnvironmentHookPhase::Activate,
+            command: command.to_string(),
+            success,
+            exit_code: Some(if success { 0 } else { 1 }),
+            stdout: String::new(),
+            stderr: String::new(),
+            error: None,
+            duration_ms: 10,
+        }
+    }
+
+    fn environment_result(data: Option<serde_json::Value>) -> EnvironmentResult {
+        EnvironmentResult {
+            success: true,
+            message: "环境已激活".to_string(),
+            data,
+        }
+    }
+
+    #[test]
+    fn test_attach_results_without_hooks() {
+        let result = EnvironmentHooks::attach_results(environment_result(None), Vec::new());
+        assert!(result.success);
+        assert_eq!(result.message, "环境已激活");
+        assert!(result.data.is_none());
+    }
+
+    #[test]
+    fn test_attach_results_merges_into_data() {
+        let data = serde_json::json!({ "environment": { "id": "env-1" } });
+        let result = EnvironmentHooks::attach_results(
+            environment_result(Some(data)),
+            vec![hook_result("echo ok", true)],
+        );
+        assert_eq!(result.message, "环境已激活");
+        let data = result.data.as_ref().unwrap();
+        assert_eq!(data["environment"]["id"], "env-1");
+
+        let results = EnvironmentHooks::results_from_data(result.data.as_ref());
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].command, "echo ok");
+    }
+
+    #[test]
+    fn test_attach_results_reports_failures() {
+        let result = EnvironmentHooks::attach_results(
+            environment_result(None),
+            vec![
+                hook_result("echo ok", true),
+                hook_result("false", false),
+                hook_result("exit 2", false),
+            ],
+        );
+        // 钩子失败不改变操作本身的结果
+        assert!(result.success);
+        assert_eq!(
+            result.message,
+            "环境已激活，但 2 个钩子执行失败: false; exit 2"
+        );
+        assert_eq!(
+            EnvironmentHooks::results_from_data(result.data.as_ref()).len(),
+            3
+        );
+    }
+}
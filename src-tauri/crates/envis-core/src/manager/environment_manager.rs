@@ -7,10 +7,16 @@ use std::path::Path;
 use std::sync::{Arc, Mutex, OnceLock};
 
 use crate::manager::app_config_manager::AppConfigManager;
+use crate::manager::builders::{EnvVarBuilder, ENVIRONMENT_ENV_VARS_KEY};
+use crate::manager::change_events::{self, ChangeOperation};
 use crate::manager::env_serv_data_manager::EnvServDataManager;
+use crate::manager::environment_hooks::{
+    EnvironmentHookPhase, EnvironmentHookResult, EnvironmentHooks,
+};
 use crate::manager::host_manager::HostManager;
-use crate::manager::shell_manamger::ShellManager;
-use crate::types::{Environment, EnvironmentStatus, ServiceType};
+use crate::manager::scheduler::SCHEDULED_TASKS_METADATA_KEY;
+use crate::manager::shell_manamger::{EnvBlockIntegrity, ShellConfigReport, ShellManager};
+use crate::types::{Environment, EnvironmentStatus, ServiceDataStatus, ServiceType};
 
 const ENV_CONFIG_FILE_NAME: &str = "environment.json";
 
@@ -31,6 +37,9 @@ pub struct SwitchEnvironmentResult {
     pub activated_environment_id: String,
     /// 本次被停用的其他环境 ID 列表
     pub deactivated_environment_ids: Vec<String>,
+    /// 停用 / 激活过程中执行的环境钩子结果
+    #[serde(default)]
+    pub hook_results: Vec<EnvironmentHookResult>,
 }
 
 /// 活跃环境信息
@@ -172,7 +181,8 @@ impl EnvironmentManager {
         };
 
         // 保存环境配置
-        self.save_environment(&environment)?;
+        self.write_environment(&environment)?;
+        change_events::environment_changed(ChangeOperation::Created, &environment);
 
         log::info!("环境已创建: {} ({})", environment.name, environment.id);
 
@@ -185,6 +195,18 @@ impl EnvironmentManager {
 
     /// 保存环境
     pub fn save_environment(&self, environment: &Environment) -> Result<EnvironmentResult> {
+        self.write_environment(environment)?;
+        change_events::environment_changed(ChangeOperation::Updated, environment);
+
+        Ok(EnvironmentResult {
+            success: true,
+            message: "环境配置已保存".to_string(),
+            data: Some(serde_json::to_value(environment).context("环境序列化到 data 失败")?),
+        })
+    }
+
+    /// 写入环境配置文件，不推送变更事件
+    fn write_environment(&self, environment: &Environment) -> Result<()> {
         let envs_folder = {
             let app_config_manager = AppConfigManager::global();
             let app_config_manager = app_config_manager.lock().unwrap();
@@ -203,12 +225,7 @@ impl EnvironmentManager {
         fs::write(&env_config_path, json_content).context("写入环境配置文件失败")?;
 
         log::info!("环境配置已保存: {} ({})", environment.name, environment.id);
-
-        Ok(EnvironmentResult {
-            success: true,
-            message: "环境配置已保存".to_string(),
-            data: Some(serde_json::to_value(environment).context("环境序列化到 data 失败")?),
-        })
+        Ok(())
     }
 
     /// 删除环境
@@ -235,6 +252,7 @@ impl EnvironmentManager {
             fs::remove_dir_all(&env_path).context("删除环境文件夹失败")?;
             log::info!("环境文件夹已删除: {}", environment.id);
         }
+        change_events::environment_changed(ChangeOperation::Deleted, environment);
 
         Ok(EnvironmentResult {
             success: true,
@@ -257,8 +275,18 @@ impl EnvironmentManager {
         Ok(env_config_path.exists())
     }
 
-    /// 激活环境（仅更新状态和Shell环境块，不激活服务）
+    /// 激活环境（仅更新状态和Shell环境块，不激活服务），随后执行 onActivate 钩子
     pub fn activate_environment(&self, environment: &mut Environment) -> Result<EnvironmentResult> {
+        let result = self.apply_environment_activation(environment)?;
+        let hook_results = EnvironmentHooks::run(environment, EnvironmentHookPhase::Activate);
+        Ok(EnvironmentHooks::attach_results(result, hook_results))
+    }
+
+    /// 更新环境状态和 Shell 环境块，不执行钩子
+    fn apply_environment_activation(
+        &self,
+        environment: &mut Environment,
+    ) -> Result<EnvironmentResult> {
         let environment_name = environment.name.clone();
         let environment_id = environment.id.clone();
 
@@ -289,22 +317,24 @@ impl EnvironmentManager {
         {
             let shell_manager = ShellManager::global();
             let shell_manager = shell_manager.lock().unwrap();
-            shell_manager
-                .clear_shell_environment_block_content()
-                .context("清除shell环境块失败")?;
+            let mut update = shell_manager.begin_update();
+            update.clear_block();
 
             // 添加 echo 信息到对应的 block（global 或 active）
             if app_config.show_environment_name_on_terminal_open {
-                shell_manager
-                    .add_echo_environment(&environment_name, &environment_id)
-                    .context("添加echo环境信息失败")?;
+                update.add_echo_environment(&environment_name, &environment_id);
             }
 
             if let Some(info) = services_info {
-                shell_manager
-                    .add_echo_services(info)
-                    .context("添加服务信息的Echo失败")?;
+                update.add_echo_services(info);
+            }
+
+            // 导出环境自定义的环境变量
+            for (key, value) in EnvVarBuilder::build_env_vars_for_environment(environment) {
+                update.add_literal_export(&key, &value);
             }
+
+            update.commit().context("更新shell环境块失败")?;
         }
 
         // 更新环境状态和时间戳
@@ -312,7 +342,8 @@ impl EnvironmentManager {
         environment.updated_at = Utc::now().to_rfc3339();
 
         // 保存环境配置
-        self.save_environment(environment)?;
+        self.write_environment(environment)?;
+        change_events::environment_changed(ChangeOperation::Activated, environment);
 
         Ok(EnvironmentResult {
             success: true,
@@ -321,14 +352,14 @@ impl EnvironmentManager {
         })
     }
 
-    /// 激活环境和所有服务
+    /// 激活环境和所有服务，服务激活完成后执行 onActivate 钩子
     pub fn activate_environment_and_services(
         &self,
         environment: &mut Environment,
         password: Option<String>,
     ) -> Result<EnvironmentResult> {
         // 1. 先激活环境本身
-        let result = self.activate_environment(environment)?;
+        let result = self.apply_environment_activation(environment)?;
 
         // 2. 激活所有服务
         let environment_id = environment.id.clone();
@@ -355,37 +386,54 @@ impl EnvironmentManager {
             }
         }
 
+        // 3. 服务的 PATH 也已写入 Shell 配置，执行激活钩子
+        let hook_results = EnvironmentHooks::run(environment, EnvironmentHookPhase::Activate);
+
         if !activation_failures.is_empty() {
-            return Ok(EnvironmentResult {
-                success: false,
-                message: anyhow!(
-                    "环境已激活，但以下服务激活失败: {}",
-                    activation_failures.join("; ")
-                )
-                .to_string(),
-                data: result.data,
-            });
+            return Ok(EnvironmentHooks::attach_results(
+                EnvironmentResult {
+                    success: false,
+                    message: anyhow!(
+                        "环境已激活，但以下服务激活失败: {}",
+                        activation_failures.join("; ")
+                    )
+                    .to_string(),
+                    data: result.data,
+                },
+                hook_results,
+            ));
         }
 
-        Ok(result)
+        Ok(EnvironmentHooks::attach_results(result, hook_results))
     }
 
-    /// 停用环境（仅更新状态和Shell环境块，不停用服务）
+    /// 停用环境（仅更新状态和Shell环境块，不停用服务），随后执行 onDeactivate 钩子
     pub fn deactivate_environment(
         &self,
         environment: &mut Environment,
+    ) -> Result<EnvironmentResult> {
+        let result = self.apply_environment_deactivation(environment)?;
+        let hook_results = EnvironmentHooks::run(environment, EnvironmentHookPhase::Deactivate);
+        Ok(EnvironmentHooks::attach_results(result, hook_results))
+    }
+
+    /// 更新环境状态并移除 Shell 环境块中的环境信息，不执行钩子
+    fn apply_environment_deactivation(
+        &self,
+        environment: &mut Environment,
     ) -> Result<EnvironmentResult> {
         // 移除当前活跃环境的 echo 信息（限制锁的作用域）
         {
             let shell_manager = ShellManager::global();
             let shell_manager = shell_manager.lock().unwrap();
-            shell_manager
-                .remove_echo_environment()
-                .context("移除echo环境信息失败")?;
+            let mut update = shell_manager.begin_update();
+            update.remove_echo_environment().remove_echo_services();
 
-            shell_manager
-                .remove_echo_services()
-                .context("移除服务echo信息失败")?;
+            for key in EnvVarBuilder::build_env_vars_for_environment(environment).keys() {
+                update.delete_export(key);
+            }
+
+            update.commit().context("移除shell环境块信息失败")?;
         }
 
         // 更新环境状态和时间戳
@@ -393,7 +441,8 @@ impl EnvironmentManager {
         environment.updated_at = Utc::now().to_rfc3339();
 
         // 保存环境配置
-        self.save_environment(environment)?;
+        self.write_environment(environment)?;
+        change_events::environment_changed(ChangeOperation::Deactivated, environment);
 
         Ok(EnvironmentResult {
             success: true,
@@ -473,16 +522,19 @@ impl EnvironmentManager {
             })?;
 
         let mut deactivated_environment_ids: Vec<String> = Vec::new();
+        let mut hook_results = Vec::new();
 
         // 2. 停用其他活跃环境（按需）
         if deactivate_others {
             for (i, env) in all_environments.iter_mut().enumerate() {
                 if i != target_idx && env.status == EnvironmentStatus::Active {
                     let env_id = env.id.clone();
-                    if let Err(e) =
-                        self.deactivate_environment_and_services(env, password.clone())
-                    {
-                        log::warn!("切换环境时停用环境 {} 失败（非致命）: {}", env_id, e);
+                    match self.deactivate_environment_and_services(env, password.clone()) {
+                        Ok(result) => hook_results
+                            .extend(EnvironmentHooks::results_from_data(result.data.as_ref())),
+                        Err(e) => {
+                            log::warn!("切换环境时停用环境 {} 失败（非致命）: {}", env_id, e)
+                        }
                     }
                     // 不管 success 字段，只要没有 Err 说明状态已改变
                     deactivated_environment_ids.push(env_id);
@@ -493,15 +545,233 @@ impl EnvironmentManager {
         // 3. 激活目标环境
         let mut target_env = all_environments[target_idx].clone();
         let activate_result = self.activate_environment_and_services(&mut target_env, password)?;
+        hook_results.extend(EnvironmentHooks::results_from_data(
+            activate_result.data.as_ref(),
+        ));
 
         Ok(SwitchEnvironmentResult {
             success: activate_result.success,
             message: activate_result.message,
             activated_environment_id: target_environment_id.to_string(),
             deactivated_environment_ids,
+            hook_results,
+        })
+    }
+
+    /// 获取环境自定义的环境变量
+    pub fn get_environment_env_vars(&self, environment_id: &str) -> Result<HashMap<String, String>> {
+        let environment = self.load_environment(environment_id)?;
+        Ok(EnvVarBuilder::build_env_vars_for_environment(&environment))
+    }
+
+    /// 设置环境变量；环境已激活时同步写入 shell 环境块
+    pub fn set_environment_env_var(
+        &self,
+        environment_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<EnvironmentResult> {
+        EnvVarBuilder::validate_env_var_key(key)?;
+        EnvVarBuilder::validate_env_var_value(value)?;
+
+        let mut environment = self.load_environment(environment_id)?;
+        let mut env_vars = EnvVarBuilder::build_env_vars_for_environment(&environment);
+        env_vars.insert(key.to_string(), value.to_string());
+        self.save_environment_env_vars(&mut environment, &env_vars)?;
+
+        if environment.status == EnvironmentStatus::Active {
+            let shell_manager = ShellManager::global();
+            let shell_manager = shell_manager.lock().unwrap();
+            shell_manager.add_literal_export(key, value)?;
+        }
+
+        Ok(EnvironmentResult {
+            success: true,
+            message: format!("已设置环境变量 {}", key),
+            data: Some(serde_json::json!({ "envVars": env_vars })),
+        })
+    }
+
+    /// 删除环境变量；环境已激活时同步从 shell 环境块移除
+    pub fn delete_environment_env_var(
+        &self,
+        environment_id: &str,
+        key: &str,
+    ) -> Result<EnvironmentResult> {
+        let mut environment = self.load_environment(environment_id)?;
+        let mut env_vars = EnvVarBuilder::build_env_vars_for_environment(&environment);
+        if env_vars.remove(key).is_none() {
+            return Ok(EnvironmentResult {
+                success: false,
+                message: format!("环境变量 {} 不存在", key),
+                data: None,
+            });
+        }
+        self.save_environment_env_vars(&mut environment, &env_vars)?;
+
+        if environment.status == EnvironmentStatus::Active {
+            let shell_manager = ShellManager::global();
+            let shell_manager = shell_manager.lock().unwrap();
+            shell_manager.delete_export(key)?;
+        }
+
+        Ok(EnvironmentResult {
+            success: true,
+            message: format!("已删除环境变量 {}", key),
+            data: Some(serde_json::json!({ "envVars": env_vars })),
+        })
+    }
+
+    /// 读取环境 metadata 中的单个键，不存在时返回 None
+    pub fn get_environment_metadata(
+        &self,
+        environment_id: &str,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>> {
+        let environment = self.load_environment(environment_id)?;
+        Ok(environment.metadata.and_then(|mut m| m.remove(key)))
+    }
+
+    /// 设置环境 metadata 中的单个键，value 为 null 时删除该键
+    ///
+    /// 环境变量由 set_environment_env_var 维护、计划任务由 TaskScheduler 维护，不能通过此方法修改
+    pub fn set_environment_metadata(
+        &self,
+        environment_id: &str,
+        key: &str,
+        value: serde_json::Value,
+    ) -> Result<EnvironmentResult> {
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(anyhow!("metadata 键不能为空"));
+        }
+        if key == ENVIRONMENT_ENV_VARS_KEY {
+            return Err(anyhow!(
+                "{} 由环境变量设置维护，请使用环境变量相关命令",
+                key
+            ));
+        }
+        if key == SCHEDULED_TASKS_METADATA_KEY {
+            return Err(anyhow!("{} 由计划任务维护，请使用计划任务相关命令", key));
+        }
+        if EnvironmentHooks::is_hook_metadata_key(key) {
+            return Err(anyhow!(
+                "{} 由环境钩子设置维护，请使用环境钩子相关命令",
+                key
+            ));
+        }
+
+        self.write_environment_metadata(environment_id, key, value.clone())?;
+
+        Ok(EnvironmentResult {
+            success: true,
+            message: format!("已设置环境 metadata {}", key),
+            data: Some(serde_json::json!({ "key": key, "value": value })),
         })
     }
 
+    /// 获取环境的激活 / 停用钩子
+    pub fn get_environment_hooks(&self, environment_id: &str) -> Result<EnvironmentResult> {
+        let environment = self.load_environment(environment_id)?;
+        let hooks = EnvironmentHooks::from_environment(&environment);
+        Ok(EnvironmentResult {
+            success: true,
+            message: "获取环境钩子成功".to_string(),
+            data: Some(serde_json::json!({ "hooks": hooks })),
+        })
+    }
+
+    /// 设置环境的激活 / 停用钩子，整体替换已有配置
+    pub fn set_environment_hooks(
+        &self,
+        environment_id: &str,
+        hooks: EnvironmentHooks,
+    ) -> Result<EnvironmentResult> {
+        let hooks = hooks.normalized()?;
+        let mut environment = self.load_environment(environment_id)?;
+        let metadata = environment.metadata.get_or_insert_with(HashMap::new);
+        for (key, value) in hooks.to_metadata() {
+            if value.is_null() {
+                metadata.remove(key);
+            } else {
+                metadata.insert(key.to_string(), value);
+            }
+        }
+        if metadata.is_empty() {
+            environment.metadata = None;
+        }
+        environment.updated_at = Utc::now().to_rfc3339();
+        self.save_environment(&environment)?;
+
+        Ok(EnvironmentResult {
+            success: true,
+            message: "环境钩子已保存".to_string(),
+            data: Some(serde_json::json!({ "hooks": hooks })),
+        })
+    }
+
+    /// 写入环境 metadata 中的单个键，不校验保留键，value 为 null 时删除该键
+    pub(crate) fn write_environment_metadata(
+        &self,
+        environment_id: &str,
+        key: &str,
+        value: serde_json::Value,
+    ) -> Result<()> {
+        let mut environment = self.load_environment(environment_id)?;
+        let metadata = environment.metadata.get_or_insert_with(HashMap::new);
+        if value.is_null() {
+            metadata.remove(key);
+        } else {
+            metadata.insert(key.to_string(), value);
+        }
+        if metadata.is_empty() {
+            environment.metadata = None;
+        }
+        environment.updated_at = Utc::now().to_rfc3339();
+        self.save_environment(&environment)?;
+        Ok(())
+    }
+
+    /// 将环境变量写回 metadata 并保存
+    fn save_environment_env_vars(
+        &self,
+        environment: &mut Environment,
+        env_vars: &HashMap<String, String>,
+    ) -> Result<()> {
+        environment
+            .metadata
+            .get_or_insert_with(HashMap::new)
+            .insert(ENVIRONMENT_ENV_VARS_KEY.to_string(), serde_json::to_value(env_vars)?);
+        environment.updated_at = Utc::now().to_rfc3339();
+        self.save_environment(environment)?;
+        Ok(())
+    }
+
+    /// 通过环境 ID 读取环境配置
+    fn load_environment(&self, environment_id: &str) -> Result<Environment> {
+        let envs_folder = {
+            let app_config_manager = AppConfigManager::global();
+            let app_config_manager = app_config_manager.lock().unwrap();
+            app_config_manager.get_envs_folder()
+        };
+
+        let env_config_path = Path::new(&envs_folder)
+            .join(environment_id)
+            .join(ENV_CONFIG_FILE_NAME);
+        if !env_config_path.exists() {
+            return Err(anyhow!("环境配置文件不存在: {}", environment_id));
+        }
+        self.load_environment_from_file(&env_config_path)
+    }
+
+    /// 读取环境名称，不经过全局锁，可在持有其他管理器的锁时调用
+    pub(crate) fn read_environment_name(environment_id: &str) -> Option<String> {
+        Self::new()
+            .load_environment(environment_id)
+            .ok()
+            .map(|environment| environment.name)
+    }
+
     /// 从文件加载环境配置
     fn load_environment_from_file(&self, config_path: &Path) -> Result<Environment> {
         let config_content = fs::read_to_string(config_path).context("读取环境配置文件失败")?;
@@ -510,6 +780,138 @@ impl EnvironmentManager {
         Ok(environment)
     }
 
+    /// 检查 Shell 配置文件中的 Envis 环境块，repair 为 true 时备份并按当前激活状态重建
+    pub fn verify_and_repair_shell_config(&self, repair: bool) -> Result<ShellConfigReport> {
+        let files = {
+            let shell_manager = ShellManager::global();
+            let shell_manager = shell_manager.lock().unwrap();
+            shell_manager.diagnose_config_files()
+        };
+        let healthy = files.iter().all(|file| file.issues.is_empty());
+
+        if !repair {
+            return Ok(ShellConfigReport {
+                healthy,
+                repaired: false,
+                files,
+                after_repair: None,
+            });
+        }
+
+        let backups = {
+            let shell_manager = ShellManager::global();
+            let shell_manager = shell_manager.lock().unwrap();
+            shell_manager
+                .rebuild_env_blocks()
+                .context("重建shell环境块失败")?
+        };
+
+        self.reapply_active_environments()?;
+
+        let after_repair = {
+            let shell_manager = ShellManager::global();
+            let shell_manager = shell_manager.lock().unwrap();
+            shell_manager.diagnose_config_files()
+        };
+
+        let files = files
+            .into_iter()
+            .map(|mut file| {
+                file.backup_path = backups.get(&file.path).cloned();
+                file
+            })
+            .collect();
+
+        Ok(ShellConfigReport {
+            healthy,
+            repaired: true,
+            files,
+            after_repair: Some(after_repair),
+        })
+    }
+
+    /// 修复 Shell 配置文件中损坏的环境块标记，有文件被修复时按当前激活状态重新写入
+    ///
+    /// 返回修复前存在问题的文件及其状态
+    pub fn repair_shell_env_blocks(&self) -> Result<Vec<EnvBlockIntegrity>> {
+        let repaired = {
+            let shell_manager = ShellManager::global();
+            let shell_manager = shell_manager.lock().unwrap();
+            shell_manager.repair_env_block()?
+        };
+
+        if !repaired.is_empty() {
+            self.reapply_active_environments()?;
+        }
+        Ok(repaired)
+    }
+
+    /// 从备份恢复单个 Shell 配置文件，并按当前激活状态重新写入 Envis 环境块
+    pub fn restore_shell_config_backup(&self, config_path: &str, tag: &str) -> Result<String> {
+        let restored = {
+            let shell_manager = ShellManager::global();
+            let shell_manager = shell_manager.lock().unwrap();
+            shell_manager.restore_config_backup(config_path, tag)?
+        };
+
+        self.reapply_active_environments()?;
+        Ok(restored)
+    }
+
+    /// 按激活先后顺序重新写入已激活环境及其服务的 Shell 配置
+    fn reapply_active_environments(&self) -> Result<()> {
+        let last_used_environment_ids = {
+            let app_config_manager = AppConfigManager::global();
+            let app_config_manager = app_config_manager.lock().unwrap();
+            app_config_manager.get_app_config().last_used_environment_ids
+        };
+
+        let mut environments: Vec<Environment> = self
+            .get_all_environments()?
+            .into_iter()
+            .filter(|env| env.status == EnvironmentStatus::Active)
+            .collect();
+        environments.sort_by_key(|env| {
+            last_used_environment_ids
+                .iter()
+                .position(|id| id == &env.id)
+                .map(|pos| pos + 1)
+                .unwrap_or(0)
+        });
+
+        for mut environment in environments {
+            // 仅恢复 Shell 配置，不是用户触发的激活，不执行钩子
+            self.apply_environment_activation(&mut environment)?;
+
+            let service_datas = {
+                let env_serv_data_manager = EnvServDataManager::global();
+                let env_serv_data_manager = env_serv_data_manager.lock().unwrap();
+                env_serv_data_manager
+                    .get_environment_all_service_datas(&environment.id)
+                    .unwrap_or_default()
+            };
+
+            for mut service_data in service_datas {
+                // Host 服务修改的是 hosts 文件，与 Shell 配置无关
+                if !matches!(service_data.status, ServiceDataStatus::Active)
+                    || service_data.service_type == ServiceType::Host
+                {
+                    continue;
+                }
+
+                let env_serv_data_manager = EnvServDataManager::global();
+                let env_serv_data_manager = env_serv_data_manager.lock().unwrap();
+                if let Err(e) =
+                    env_serv_data_manager.active_service_data(&environment.id, &mut service_data, None)
+                {
+                    log::warn!("重新写入服务 {} 的 Shell 配置失败: {}", service_data.name, e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// 通过环境 ID 获取单个环境
     pub fn get_environment(&self, environment_id: &str) -> Result<EnvironmentResult> {
         let envs_folder = {
@@ -2,6 +2,8 @@ use anyhow::{anyhow, Context, Result};
 
 use crate::manager::app_config_manager::AppConfigManager;
 use crate::manager::environment_manager::EnvironmentManager;
+use crate::manager::scheduler::TaskScheduler;
+use crate::manager::service_supervisor::ServiceSupervisor;
 use crate::manager::shell_manamger::ShellManager;
 use crate::types::EnvironmentStatus;
 
@@ -21,6 +23,10 @@ fn persist_active_environment_ids(active_environment_ids: Vec<String>) -> Result
 pub fn cleanup_on_app_close() -> Result<bool> {
     log::info!("cleanup_on_app_close 开始执行");
 
+    // 先停止服务监控，避免退出时停止的服务被自动重启
+    ServiceSupervisor::global().shutdown();
+    TaskScheduler::global().shutdown();
+
     let app_config = {
         let manager = AppConfigManager::global();
         let manager = manager
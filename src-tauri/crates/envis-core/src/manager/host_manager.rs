@@ -14,6 +14,16 @@ pub struct HostEntry {
     pub enabled: bool,
 }
 
+/// 从 hosts 格式文件批量导入的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostImportResult {
+    pub imported: u32,
+    pub skipped: u32,
+    pub errors: Vec<String>,
+    /// 实际导入的条目
+    pub entries: Vec<HostEntry>,
+}
+
 /// 全局 Host 管理器单例
 static HOST_MANAGER: OnceLock<Arc<Mutex<HostManager>>> = OnceLock::new();
 
@@ -568,6 +578,140 @@ impl HostManager {
         Ok(())
     }
 
+    /// 从 /etc/hosts 格式的文件批量导入条目（写操作，需要权限）
+    ///
+    /// overwrite 为 false 时跳过主机名已存在的条目；为 true 时替换同主机名的已有条目
+    pub fn bulk_import_hosts(
+        &self,
+        file_path: &str,
+        overwrite: bool,
+        password: &str,
+    ) -> Result<HostImportResult> {
+        let file_content = fs::read_to_string(file_path).context("读取 hosts 文件失败")?;
+        let (parsed, errors) = Self::parse_etc_hosts_content(&file_content);
+
+        let existing = self.get_hosts()?;
+        let mut seen = std::collections::HashSet::new();
+        let mut replaced_hostnames = std::collections::HashSet::new();
+        let mut entries = Vec::new();
+        let mut skipped = 0;
+
+        for entry in parsed {
+            // 同一文件内重复的主机名只取第一条
+            if !seen.insert(entry.hostname.clone()) {
+                skipped += 1;
+                continue;
+            }
+            if existing.iter().any(|e| e.hostname == entry.hostname) {
+                if !overwrite {
+                    skipped += 1;
+                    continue;
+                }
+                replaced_hostnames.insert(entry.hostname.clone());
+            }
+            entries.push(entry);
+        }
+
+        if !entries.is_empty() {
+            let content = self.read_hosts_file()?;
+            let content = if !content.contains(ENVIS_HOSTS_BLOCK_START) {
+                self.create_envis_block(&content)
+            } else {
+                content
+            };
+
+            let block_content = self.extract_envis_block(&content)?;
+            let mut new_block_content = String::new();
+            for line in block_content.lines() {
+                let replaced = self
+                    .parse_host_entry(line)
+                    .is_some_and(|e| replaced_hostnames.contains(&e.hostname));
+                if !replaced {
+                    new_block_content.push_str(&format!("{}\n", line));
+                }
+            }
+            for entry in &entries {
+                new_block_content.push_str(&format!("{}\n", self.format_host_entry(entry)));
+            }
+
+            let new_content = self.replace_envis_block(&content, &new_block_content)?;
+            self.write_hosts_file(&new_content, password)?;
+        }
+
+        Ok(HostImportResult {
+            imported: entries.len() as u32,
+            skipped,
+            errors,
+            entries,
+        })
+    }
+
+    /// 解析 /etc/hosts 格式的内容：`<ip> <hostname> [<aliases>...]`，别名各自生成一个条目
+    fn parse_etc_hosts_content(content: &str) -> (Vec<HostEntry>, Vec<String>) {
+        let mut entries = Vec::new();
+        let mut errors = Vec::new();
+
+        for (index, raw_line) in content.lines().enumerate() {
+            let (line, comment) = match raw_line.split_once('#') {
+                Some((line, comment)) => (line.trim(), Some(comment.trim())),
+                None => (raw_line.trim(), None),
+            };
+            // 跳过空行和注释行
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let ip = parts.next().unwrap_or_default();
+            let hostnames: Vec<&str> = parts.collect();
+            if ip.parse::<std::net::IpAddr>().is_err() {
+                errors.push(format!("第 {} 行: 无效的 IP 地址 {}", index + 1, ip));
+                continue;
+            }
+            if hostnames.is_empty() {
+                errors.push(format!("第 {} 行: 缺少主机名", index + 1));
+                continue;
+            }
+
+            let comment = comment.filter(|c| !c.is_empty()).map(|c| c.to_string());
+            for hostname in hostnames {
+                entries.push(HostEntry {
+                    id: format!("{}_{}", ip, hostname),
+                    ip: ip.to_string(),
+                    hostname: hostname.to_string(),
+                    comment: comment.clone(),
+                    enabled: true,
+                });
+            }
+        }
+
+        (entries, errors)
+    }
+
+    /// 将所有启用的 host 条目以 /etc/hosts 格式导出到文件，返回导出的条目数
+    pub fn export_hosts_as_etc_format(&self, dest_path: &str) -> Result<usize> {
+        let entries: Vec<HostEntry> = self
+            .get_hosts()?
+            .into_iter()
+            .filter(|e| e.enabled)
+            .collect();
+
+        let mut content = String::from("# Hosts exported from Envis\n");
+        for entry in &entries {
+            content.push_str(&format!("{}\n", self.format_host_entry(entry)));
+        }
+
+        let dest_path = PathBuf::from(dest_path);
+        if let Some(parent) = dest_path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent).context("创建导出目录失败")?;
+            }
+        }
+        fs::write(&dest_path, content).context("写入导出文件失败")?;
+
+        Ok(entries.len())
+    }
+
     /// 清空所有 Envis 管理的 host 条目（写操作，需要权限）
     pub fn clear_hosts(&self, password: &str) -> Result<()> {
         let content = self.read_hosts_file()?;
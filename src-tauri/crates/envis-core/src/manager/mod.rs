@@ -1,12 +1,21 @@
 pub mod app_config_manager;
+pub mod backup_manager;
 pub mod builders;
+pub mod change_events;
 pub mod env_serv_data_manager;
+pub mod environment_hooks;
 pub mod environment_manager;
 pub mod exit_cleanup_manager;
 pub mod export_import;
 pub mod file_manager;
 pub mod host_manager;
+pub mod project_manifest;
+pub mod scheduler;
 pub mod service_manager;
+pub mod service_supervisor;
 pub mod services;
 pub mod shell_manamger;
+pub mod startup_manager;
 pub mod system_info_manager;
+pub mod templates;
+pub mod windows_user_env;
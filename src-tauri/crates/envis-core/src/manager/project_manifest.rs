@@ -0,0 +1,501 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use crate::manager::env_serv_data_manager::EnvServDataManager;
+use crate::manager::environment_manager::EnvironmentManager;
+use crate::manager::service_manager::ServiceManager;
+use crate::manager::services::{DownloadManager, DownloadStatus, ServiceRegistry};
+use crate::types::{EnvironmentStatus, ServiceData, ServiceDataStatus, ServiceType};
+
+/// 项目目录中声明所需服务版本的文件名
+pub const PROJECT_MANIFEST_FILE: &str = ".envisrc";
+
+/// 可以在清单中声明版本的服务类型
+const MANIFEST_SERVICE_TYPES: &[ServiceType] = &[
+    ServiceType::Nodejs,
+    ServiceType::Python,
+    ServiceType::Java,
+    ServiceType::Rust,
+    ServiceType::Php,
+    ServiceType::Nasm,
+    ServiceType::Mongodb,
+    ServiceType::Mysql,
+    ServiceType::Mariadb,
+    ServiceType::Postgresql,
+    ServiceType::Redis,
+    ServiceType::Nginx,
+    ServiceType::Dnsmasq,
+    ServiceType::Vault,
+    ServiceType::Opensearch,
+    ServiceType::Kafka,
+    ServiceType::Rabbitmq,
+];
+
+/// 服务名的常用别名
+const SERVICE_ALIASES: &[(&str, ServiceType)] = &[
+    ("node", ServiceType::Nodejs),
+    ("mongo", ServiceType::Mongodb),
+    ("postgres", ServiceType::Postgresql),
+];
+
+/// 清单文件格式，按内容判断：以 `{` 开头为 JSON，否则为 TOML
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ManifestFormat {
+    Toml,
+    Json,
+}
+
+/// 清单中的一条服务版本声明
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntry {
+    pub service_type: ServiceType,
+    pub version: String,
+}
+
+/// 项目清单（.envisrc）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectManifest {
+    pub path: PathBuf,
+    pub format: ManifestFormat,
+    pub entries: Vec<ManifestEntry>,
+}
+
+/// 单条声明的检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntryCheck {
+    pub service_type: ServiceType,
+    pub version: String,
+    pub installed: bool,
+    /// 已激活环境中同类型已激活服务的版本
+    pub active_versions: Vec<String>,
+    /// 已激活环境中存在该版本的已激活服务
+    pub satisfied: bool,
+}
+
+/// 清单的检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestCheck {
+    pub path: PathBuf,
+    pub format: ManifestFormat,
+    pub entries: Vec<ManifestEntryCheck>,
+    pub satisfied: bool,
+}
+
+/// 应用清单时对单条声明执行的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ManifestApplyAction {
+    /// 新建服务数据
+    Created,
+    /// 已有同类型服务数据，切换到声明的版本
+    Updated,
+    /// 已有相同版本的服务数据
+    Unchanged,
+    /// 无法处理（如服务正在运行），见 message
+    Skipped,
+}
+
+/// 单条声明的应用结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestApplyEntry {
+    pub service_type: ServiceType,
+    pub version: String,
+    pub action: ManifestApplyAction,
+    pub from_version: Option<String>,
+    pub service_data: Option<ServiceData>,
+    pub installed: bool,
+    /// 为缺失版本发起的下载任务 ID
+    pub download_task_id: Option<String>,
+    pub message: Option<String>,
+}
+
+/// 清单的应用结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestApplyResult {
+    pub environment_id: String,
+    pub path: PathBuf,
+    pub entries: Vec<ManifestApplyEntry>,
+    pub download_task_ids: Vec<String>,
+}
+
+impl ProjectManifest {
+    /// 读取清单，path 为目录时读取其中的 .envisrc
+    pub fn load(path: &Path) -> Result<Self> {
+        let path = if path.is_dir() {
+            path.join(PROJECT_MANIFEST_FILE)
+        } else {
+            path.to_path_buf()
+        };
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("读取项目清单失败: {}", path.display()))?;
+        let (format, entries) = Self::parse(&content)
+            .map_err(|e| anyhow!("项目清单 {} 无效:\n{}", path.display(), e))?;
+        Ok(Self {
+            path,
+            format,
+            entries,
+        })
+    }
+
+    /// 解析清单内容，所有条目的错误一并返回
+    ///
+    /// 服务版本可以写在顶层，也可以写在 services 表中：
+    /// `nodejs = "20.18.0"` 或 `[services]` 下的 `nodejs = "20.18.0"`。
+    fn parse(content: &str) -> Result<(ManifestFormat, Vec<ManifestEntry>)> {
+        let (format, root) = if content.trim_start().starts_with('{') {
+            let root: Value =
+                serde_json::from_str(content).map_err(|e| anyhow!("JSON 格式错误: {}", e))?;
+            (ManifestFormat::Json, root)
+        } else {
+            let root: toml::Table =
+                toml::from_str(content).map_err(|e| anyhow!("TOML 格式错误: {}", e))?;
+            (ManifestFormat::Toml, serde_json::to_value(root)?)
+        };
+
+        let Value::Object(root) = root else {
+            return Err(anyhow!("顶层必须是对象"));
+        };
+        let services = match root.get("services") {
+            Some(Value::Object(services)) => {
+                if let Some(key) = root.keys().find(|key| *key != "services") {
+                    return Err(anyhow!("未知字段 \"{}\"，服务版本需写在 services 中", key));
+                }
+                services.clone()
+            }
+            Some(_) => return Err(anyhow!("services 必须是 服务名 = \"版本\" 形式的表")),
+            None => root,
+        };
+        if services.is_empty() {
+            return Err(anyhow!("没有声明任何服务"));
+        }
+
+        let mut entries: Vec<ManifestEntry> = Vec::new();
+        let mut errors = Vec::new();
+        for (name, value) in &services {
+            match Self::parse_entry(name, value) {
+                Ok(entry) if entries.iter().any(|e| e.service_type == entry.service_type) => {
+                    errors.push(format!(
+                        "  - {}: 重复声明了 {}",
+                        name,
+                        entry.service_type.default_name()
+                    ));
+                }
+                Ok(entry) => entries.push(entry),
+                Err(e) => errors.push(format!("  - {}: {}", name, e)),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(anyhow!("{}", errors.join("\n")));
+        }
+        Ok((format, entries))
+    }
+
+    fn parse_entry(name: &str, value: &Value) -> std::result::Result<ManifestEntry, String> {
+        let service_type = Self::parse_service_type(name)?;
+        let version = match value {
+            Value::String(version) => version.trim().to_string(),
+            Value::Number(number) => {
+                return Err(format!("版本号必须是字符串，请加上引号，如 \"{}\"", number))
+            }
+            _ => return Err("版本号必须是字符串，如 \"20.18.0\"".to_string()),
+        };
+        if version.is_empty() {
+            return Err("版本号不能为空".to_string());
+        }
+        // 版本号会作为安装目录名，只允许常见的版本字符
+        if version.starts_with('.')
+            || !version
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+'))
+        {
+            return Err(format!("版本号 \"{}\" 包含非法字符", version));
+        }
+        Ok(ManifestEntry {
+            service_type,
+            version,
+        })
+    }
+
+    fn parse_service_type(name: &str) -> std::result::Result<ServiceType, String> {
+        let name = name.trim().to_ascii_lowercase();
+        if let Some((_, service_type)) = SERVICE_ALIASES.iter().find(|(alias, _)| *alias == name) {
+            return Ok(service_type.clone());
+        }
+        if let Some(service_type) = MANIFEST_SERVICE_TYPES.iter().find(|t| t.dir_name() == name) {
+            return Ok(service_type.clone());
+        }
+        let supported: Vec<&str> = MANIFEST_SERVICE_TYPES
+            .iter()
+            .map(|t| t.dir_name())
+            .collect();
+        Err(format!(
+            "不支持的服务类型，可用的服务: {}",
+            supported.join(", ")
+        ))
+    }
+
+    /// 检查每条声明的版本是否已安装，以及已激活的环境是否满足
+    pub fn check(&self) -> Result<ManifestCheck> {
+        let environments = {
+            let environment_manager = EnvironmentManager::global();
+            let environment_manager = environment_manager.lock().unwrap();
+            environment_manager.get_all_environments()?
+        }; // 锁在这里被释放
+
+        let active_service_datas: Vec<ServiceData> = {
+            let env_serv_data_manager = EnvServDataManager::global();
+            let env_serv_data_manager = env_serv_data_manager.lock().unwrap();
+            let mut service_datas = Vec::new();
+            for environment in environments
+                .iter()
+                .filter(|e| e.status == EnvironmentStatus::Active)
+            {
+                service_datas.extend(
+                    env_serv_data_manager
+                        .get_environment_all_service_datas(&environment.id)?
+                        .into_iter()
+                        .filter(|sd| matches!(sd.status, ServiceDataStatus::Active)),
+                );
+            }
+            service_datas
+        };
+
+        let entries: Vec<ManifestEntryCheck> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let active_versions: Vec<String> = active_service_datas
+                    .iter()
+                    .filter(|sd| sd.service_type == entry.service_type)
+                    .map(|sd| sd.version.clone())
+                    .collect();
+                ManifestEntryCheck {
+                    service_type: entry.service_type.clone(),
+                    version: entry.version.clone(),
+                    installed: ServiceRegistry::get(&entry.service_type)
+                        .is_installed(&entry.version),
+                    satisfied: active_versions.contains(&entry.version),
+                    active_versions,
+                }
+            })
+            .collect();
+
+        Ok(ManifestCheck {
+            path: self.path.clone(),
+            format: self.format,
+            satisfied: entries.iter().all(|e| e.satisfied),
+            entries,
+        })
+    }
+
+    /// 让目标环境的服务数据与清单一致：缺少的服务新建，版本不同的切换版本
+    ///
+    /// download_missing 为 true 时在后台下载未安装的版本，返回的任务 ID 可用于查询进度。
+    pub fn apply(
+        &self,
+        environment_id: &str,
+        download_missing: bool,
+    ) -> Result<ManifestApplyResult> {
+        {
+            let environment_manager = EnvironmentManager::global();
+            let environment_manager = environment_manager.lock().unwrap();
+            if !environment_manager
+                .get_all_environments()?
+                .iter()
+                .any(|e| e.id == environment_id)
+            {
+                return Err(anyhow!("未找到环境: {}", environment_id));
+            }
+        } // 锁在这里被释放
+
+        let mut entries = Vec::new();
+        for entry in &self.entries {
+            let mut applied = Self::apply_entry(environment_id, entry)?;
+            if download_missing && !applied.installed {
+                applied.download_task_id = Some(Self::start_download(entry)?);
+            }
+            entries.push(applied);
+        }
+
+        log::info!(
+            "已将项目清单 {} 应用到环境 {}",
+            self.path.display(),
+            environment_id
+        );
+        Ok(ManifestApplyResult {
+            environment_id: environment_id.to_string(),
+            path: self.path.clone(),
+            download_task_ids: entries
+                .iter()
+                .filter_map(|e| e.download_task_id.clone())
+                .collect(),
+            entries,
+        })
+    }
+
+    fn apply_entry(environment_id: &str, entry: &ManifestEntry) -> Result<ManifestApplyEntry> {
+        let same_type: Vec<ServiceData> = EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .get_environment_all_service_datas(environment_id)?
+            .into_iter()
+            .filter(|sd| sd.service_type == entry.service_type)
+            .collect();
+
+        let mut applied = ManifestApplyEntry {
+            service_type: entry.service_type.clone(),
+            version: entry.version.clone(),
+            action: ManifestApplyAction::Unchanged,
+            from_version: None,
+            service_data: None,
+            installed: ServiceRegistry::get(&entry.service_type).is_installed(&entry.version),
+            download_task_id: None,
+            message: None,
+        };
+
+        if let Some(service_data) = same_type.iter().find(|sd| sd.version == entry.version) {
+            applied.service_data = Some(service_data.clone());
+            return Ok(applied);
+        }
+
+        let Some(mut service_data) = same_type.into_iter().next() else {
+            let result = EnvServDataManager::global()
+                .lock()
+                .unwrap()
+                .create_service_data(
+                    environment_id,
+                    entry.service_type.clone(),
+                    entry.version.clone(),
+                )?;
+            applied.action = ManifestApplyAction::Created;
+            applied.service_data = result
+                .data
+                .and_then(|data| data.get("serviceData").cloned())
+                .and_then(|value| serde_json::from_value(value).ok());
+            return Ok(applied);
+        };
+
+        applied.from_version = Some(service_data.version.clone());
+        if ServiceManager::is_daemon_service(&entry.service_type)
+            && ServiceManager::global()
+                .is_service_running(environment_id, &service_data)
+                .unwrap_or(false)
+        {
+            applied.action = ManifestApplyAction::Skipped;
+            applied.message = Some(format!(
+                "{} {} 正在运行，请先停止后再切换到 {}",
+                service_data.name, service_data.version, entry.version
+            ));
+            applied.service_data = Some(service_data);
+            return Ok(applied);
+        }
+
+        // 与版本升级相同：先停用旧版本的 PATH 与环境变量，切换后再按新版本激活
+        let env_serv_data_manager = EnvServDataManager::global();
+        let env_serv_data_manager = env_serv_data_manager.lock().unwrap();
+        let was_active = matches!(service_data.status, ServiceDataStatus::Active);
+        if was_active {
+            env_serv_data_manager.deactive_service_data(environment_id, &mut service_data, None)?;
+        }
+        env_serv_data_manager.change_service_data_version(
+            environment_id,
+            &mut service_data,
+            &entry.version,
+        )?;
+        if was_active {
+            env_serv_data_manager.active_service_data(environment_id, &mut service_data, None)?;
+        }
+        applied.action = ManifestApplyAction::Updated;
+        applied.service_data = Some(service_data);
+        Ok(applied)
+    }
+
+    /// 在后台下载安装声明的版本，已有进行中的任务时直接复用
+    fn start_download(entry: &ManifestEntry) -> Result<String> {
+        let runtime = tokio::runtime::Handle::try_current()
+            .map_err(|_| anyhow!("当前没有可用的异步运行时，无法开始下载"))?;
+        let task_id = format!("{}-{}", entry.service_type.dir_name(), entry.version);
+        let in_progress = DownloadManager::global()
+            .get_task_status(&task_id)
+            .map(|task| {
+                matches!(
+                    task.status,
+                    DownloadStatus::Pending
+                        | DownloadStatus::Downloading
+                        | DownloadStatus::Resuming
+                        | DownloadStatus::Downloaded
+                        | DownloadStatus::Installing
+                )
+            })
+            .unwrap_or(false);
+        if !in_progress {
+            let service = ServiceRegistry::get(&entry.service_type);
+            let version = entry.version.clone();
+            runtime.spawn(async move {
+                match service.download_and_install(&version).await {
+                    Ok(result) if !result.success => {
+                        log::warn!("下载 {} 失败: {}", version, result.message)
+                    }
+                    Err(e) => log::warn!("下载 {} 失败: {}", version, e),
+                    _ => {}
+                }
+            });
+        }
+        Ok(task_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest() {
+        let (format, entries) = ProjectManifest::parse(
+            "nodejs = \"20.18.0\"\npython = \"3.13.1\"\nmongo = \"7.0.15\"\n",
+        )
+        .unwrap();
+        assert_eq!(format, ManifestFormat::Toml);
+        assert_eq!(entries.len(), 3);
+        assert!(entries.contains(&ManifestEntry {
+            service_type: ServiceType::Mongodb,
+            version: "7.0.15".to_string(),
+        }));
+
+        let (format, entries) =
+            ProjectManifest::parse(r#"{ "services": { "nodejs": "20.18.0" } }"#).unwrap();
+        assert_eq!(format, ManifestFormat::Json);
+        assert_eq!(entries[0].service_type, ServiceType::Nodejs);
+
+        let (_, entries) = ProjectManifest::parse("[services]\npostgresql = \"16.4\"\n").unwrap();
+        assert_eq!(entries[0].version, "16.4");
+    }
+
+    #[test]
+    fn test_parse_manifest_errors() {
+        let err = ProjectManifest::parse("python = 3.13\nfoo = \"1.0\"\nnodejs = \"../x\"\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("python: 版本号必须是字符串"));
+        assert!(err.contains("foo: 不支持的服务类型"));
+        assert!(err.contains("nodejs: 版本号 \"../x\" 包含非法字符"));
+
+        let err = ProjectManifest::parse("node = \"20\"\nnodejs = \"22\"\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("重复声明了 Node.js"));
+
+        assert!(ProjectManifest::parse("nodejs = ").is_err());
+        assert!(ProjectManifest::parse("").is_err());
+        assert!(ProjectManifest::parse("name = \"x\"\n[services]\nnodejs = \"20\"\n").is_err());
+    }
+}
@@ -0,0 +1,679 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, Utc};
+use cron::Schedule;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::manager::env_serv_data_manager::EnvServDataManager;
+use crate::manager::environment_manager::{EnvironmentManager, EnvironmentResult};
+use crate::manager::shell_manamger::ShellManager;
+use crate::types::ServiceDataStatus;
+use crate::utils::output_tail;
+
+/// 计划任务在 Environment.metadata 中的键
+pub const SCHEDULED_TASKS_METADATA_KEY: &str = "scheduledTasks";
+/// 计划任务执行完成事件
+pub const SCHEDULED_TASK_FINISHED_EVENT: &str = "scheduled-task-finished";
+
+/// 每个任务保留的执行记录条数
+const HISTORY_LIMIT: usize = 10;
+/// 执行记录中 stdout / stderr 保留的最大字符数（保留末尾）
+const OUTPUT_SNIPPET_CHARS: usize = 2000;
+/// 检查到期任务的间隔，也是调度的最小粒度
+const TICK_INTERVAL: Duration = Duration::from_secs(10);
+/// 调度线程检查退出标记的间隔
+const SHUTDOWN_POLL: Duration = Duration::from_millis(200);
+
+/// 调度事件回调：事件名 + 事件数据
+pub type SchedulerEventCallback = Arc<dyn Fn(&str, serde_json::Value) + Send + Sync>;
+
+/// 任务触发方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ScheduledTaskTrigger {
+    /// 按 cron 表达式到期执行
+    Schedule,
+    /// 手动立即执行
+    Manual,
+    /// 启动时补执行应用关闭期间错过的调度
+    CatchUp,
+}
+
+/// 任务执行结果
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduledTaskStatus {
+    Success,
+    Failed,
+}
+
+/// 单次执行记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTaskRun {
+    pub started_at: String,
+    pub finished_at: String,
+    pub trigger: ScheduledTaskTrigger,
+    pub status: ScheduledTaskStatus,
+    pub exit_code: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// 环境计划任务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTask {
+    pub id: String,
+    pub name: String,
+    /// cron 表达式，支持 5 段（分 时 日 月 周）、6 / 7 段（含秒、年）及 @daily 等简写
+    pub cron_expr: String,
+    pub command: String,
+    pub enabled: bool,
+    /// 应用关闭期间错过调度时，启动后补执行一次
+    #[serde(default)]
+    pub catch_up: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_run: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_status: Option<ScheduledTaskStatus>,
+    /// 最近的执行记录，最新的在前
+    #[serde(default)]
+    pub history: Vec<ScheduledTaskRun>,
+    pub created_at: String,
+}
+
+/// 新建 / 修改计划任务的参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTaskInput {
+    pub name: String,
+    pub cron_expr: String,
+    pub command: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub catch_up: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 全局计划任务调度器单例
+static TASK_SCHEDULER: OnceLock<Arc<TaskScheduler>> = OnceLock::new();
+
+/// 计划任务调度器：应用运行期间按 cron 表达式执行各环境的计划任务
+pub struct TaskScheduler {
+    /// 串行化任务列表的读改写，避免执行结果覆盖用户的修改
+    store_lock: Mutex<()>,
+    /// 正在执行的任务，同一任务不会并发执行
+    running: Mutex<HashSet<String>>,
+    /// 本次运行中各任务最近一次触发或结束的时间
+    last_fired: Mutex<HashMap<String, DateTime<Utc>>>,
+    /// 调度线程启动时间，未开启 catch_up 的任务不补执行此前错过的调度
+    started_at: Mutex<Option<DateTime<Utc>>>,
+    event_callback: Mutex<Option<SchedulerEventCallback>>,
+    stop_flag: AtomicBool,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl TaskScheduler {
+    /// 获取全局调度器实例
+    pub fn global() -> Arc<TaskScheduler> {
+        TASK_SCHEDULER.get_or_init(|| Arc::new(Self::new())).clone()
+    }
+
+    /// 创建新的调度器
+    fn new() -> Self {
+        Self {
+            store_lock: Mutex::new(()),
+            running: Mutex::new(HashSet::new()),
+            last_fired: Mutex::new(HashMap::new()),
+            started_at: Mutex::new(None),
+            event_callback: Mutex::new(None),
+            stop_flag: AtomicBool::new(false),
+            handle: Mutex::new(None),
+        }
+    }
+
+    /// 启动调度线程，重复调用时忽略
+    pub fn start(&self, event_callback: SchedulerEventCallback) {
+        let mut handle = self.handle.lock().unwrap();
+        if handle.is_some() {
+            return;
+        }
+        self.stop_flag.store(false, Ordering::SeqCst);
+        *self.event_callback.lock().unwrap() = Some(event_callback);
+        self.started_at.lock().unwrap().get_or_insert_with(Utc::now);
+
+        let spawned = thread::Builder::new()
+            .name("task-scheduler".to_string())
+            .spawn(|| TaskScheduler::global().run());
+        match spawned {
+            Ok(h) => *handle = Some(h),
+            Err(e) => log::error!("启动计划任务线程失败: {}", e),
+        }
+    }
+
+    /// 停止调度线程并等待其退出，正在执行的任务不会被中断
+    pub fn shutdown(&self) {
+        self.stop_flag.store(true, Ordering::SeqCst);
+        let handle = self.handle.lock().unwrap().take();
+        if let Some(handle) = handle {
+            if handle.join().is_err() {
+                log::error!("计划任务线程异常退出");
+            }
+            log::info!("计划任务调度已停止");
+        }
+    }
+
+    /// 列出环境的计划任务
+    pub fn list_tasks(&self, environment_id: &str) -> Result<EnvironmentResult> {
+        let tasks = Self::load_tasks(environment_id)?;
+        Ok(EnvironmentResult {
+            success: true,
+            message: "获取计划任务成功".to_string(),
+            data: Some(serde_json::json!({ "tasks": tasks })),
+        })
+    }
+
+    /// 新增计划任务
+    pub fn add_task(
+        &self,
+        environment_id: &str,
+        input: ScheduledTaskInput,
+    ) -> Result<EnvironmentResult> {
+        let input = Self::validate_input(input)?;
+        let task = ScheduledTask {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: input.name,
+            cron_expr: input.cron_expr,
+            command: input.command,
+            enabled: input.enabled,
+            catch_up: input.catch_up,
+            last_run: None,
+            last_status: None,
+            history: Vec::new(),
+            created_at: Utc::now().to_rfc3339(),
+        };
+
+        {
+            let _guard = self.store_lock.lock().unwrap();
+            let mut tasks = Self::load_tasks(environment_id)?;
+            tasks.push(task.clone());
+            Self::save_tasks(environment_id, &tasks)?;
+        }
+
+        Ok(EnvironmentResult {
+            success: true,
+            message: format!("已添加计划任务 {}", task.name),
+            data: Some(serde_json::json!({ "task": task })),
+        })
+    }
+
+    /// 修改计划任务，执行记录保持不变
+    pub fn update_task(
+        &self,
+        environment_id: &str,
+        task_id: &str,
+        input: ScheduledTaskInput,
+    ) -> Result<EnvironmentResult> {
+        let input = Self::validate_input(input)?;
+        let task = {
+            let _guard = self.store_lock.lock().unwrap();
+            let mut tasks = Self::load_tasks(environment_id)?;
+            let task = tasks
+                .iter_mut()
+                .find(|t| t.id == task_id)
+                .ok_or_else(|| anyhow!("计划任务不存在: {}", task_id))?;
+            // 重新启用或修改调度时间时，从现在开始计算下一次执行
+            if (input.enabled && !task.enabled) || input.cron_expr != task.cron_expr {
+                self.last_fired
+                    .lock()
+                    .unwrap()
+                    .insert(task.id.clone(), Utc::now());
+            }
+            task.name = input.name;
+            task.cron_expr = input.cron_expr;
+            task.command = input.command;
+            task.enabled = input.enabled;
+            task.catch_up = input.catch_up;
+            let task = task.clone();
+            Self::save_tasks(environment_id, &tasks)?;
+            task
+        };
+
+        Ok(EnvironmentResult {
+            success: true,
+            message: format!("已更新计划任务 {}", task.name),
+            data: Some(serde_json::json!({ "task": task })),
+        })
+    }
+
+    /// 删除计划任务，正在执行的任务会执行完毕，但不再记录结果
+    pub fn delete_task(&self, environment_id: &str, task_id: &str) -> Result<EnvironmentResult> {
+        {
+            let _guard = self.store_lock.lock().unwrap();
+            let mut tasks = Self::load_tasks(environment_id)?;
+            let len = tasks.len();
+            tasks.retain(|t| t.id != task_id);
+            if tasks.len() == len {
+                return Err(anyhow!("计划任务不存在: {}", task_id));
+            }
+            Self::save_tasks(environment_id, &tasks)?;
+        }
+        self.last_fired.lock().unwrap().remove(task_id);
+
+        Ok(EnvironmentResult {
+            success: true,
+            message: "已删除计划任务".to_string(),
+            data: Some(serde_json::json!({ "taskId": task_id })),
+        })
+    }
+
+    /// 立即执行一次计划任务（不论是否启用），等待执行结束后返回执行记录
+    pub fn run_task_now(&self, environment_id: &str, task_id: &str) -> Result<EnvironmentResult> {
+        let task = Self::load_tasks(environment_id)?
+            .into_iter()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| anyhow!("计划任务不存在: {}", task_id))?;
+        let run = self
+            .execute_task(environment_id, &task, ScheduledTaskTrigger::Manual)
+            .ok_or_else(|| anyhow!("计划任务 {} 正在执行中", task.name))?;
+
+        Ok(EnvironmentResult {
+            success: run.status == ScheduledTaskStatus::Success,
+            message: match run.status {
+                ScheduledTaskStatus::Success => format!("计划任务 {} 执行成功", task.name),
+                ScheduledTaskStatus::Failed => {
+                    format!("计划任务 {} 执行失败: 退出码 {}", task.name, run.exit_code)
+                }
+            },
+            data: Some(serde_json::json!({ "run": run })),
+        })
+    }
+
+    /// 解析 cron 表达式
+    ///
+    /// 5 段表达式按 Unix crontab 处理：补齐秒字段，星期 0 / 7 表示周日
+    pub fn parse_cron_expr(expr: &str) -> Result<Schedule> {
+        let expr = expr.trim();
+        if expr.is_empty() {
+            return Err(anyhow!("cron 表达式不能为空"));
+        }
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let normalized = if fields.len() == 5 {
+            let day_of_week = Self::convert_unix_day_of_week(fields[4])?;
+            format!("0 {} {}", fields[..4].join(" "), day_of_week)
+        } else {
+            expr.to_string()
+        };
+        Schedule::from_str(&normalized).map_err(|e| anyhow!("无效的 cron 表达式 {}: {}", expr, e))
+    }
+
+    /// 将 Unix crontab 的星期字段（0-7，0 / 7 为周日）转为 cron 库的 1-7（1 为周日）
+    fn convert_unix_day_of_week(field: &str) -> Result<String> {
+        let convert = |value: &str| -> Result<u32> {
+            match value.parse::<u32>() {
+                Ok(n @ 0..=7) => Ok(n % 7 + 1),
+                _ => Err(anyhow!("无效的星期字段: {}", field)),
+            }
+        };
+        let is_numeric = |value: &str| value.chars().all(|c| c.is_ascii_digit());
+
+        let mut parts = Vec::new();
+        for part in field.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => (range, Some(step)),
+                None => (part, None),
+            };
+            let converted = match range.split_once('-') {
+                Some((start, end)) if is_numeric(start) && is_numeric(end) && end == "7" => {
+                    // 以周日（7）结尾的范围转换后会跨过 7，带步长时展开成逐个取值，如 1-7/2 转为 2,4,6,1
+                    if let Some(step) = step {
+                        let step = match step.parse::<usize>() {
+                            Ok(n) if n > 0 => n,
+                            _ => return Err(anyhow!("无效的星期字段: {}", field)),
+                        };
+                        convert(start)?;
+                        for value in (start.parse::<u32>()?..=7).step_by(step) {
+                            let day = convert(&value.to_string())?.to_string();
+                            if !parts.contains(&day) {
+                                parts.push(day);
+                            }
+                        }
+                        continue;
+                    }
+                    // 不带步长时拆成两段，如 1-7 转为 2-7,1
+                    match convert(start)? {
+                        1 => "1-7".to_string(),
+                        start => {
+                            parts.push(format!("{}-7", start));
+                            "1".to_string()
+                        }
+                    }
+                }
+                Some((start, end)) if is_numeric(start) && is_numeric(end) => {
+                    format!("{}-{}", convert(start)?, convert(end)?)
+                }
+                None if is_numeric(range) => convert(range)?.to_string(),
+                _ => range.to_string(),
+            };
+            parts.push(match step {
+                Some(step) => format!("{}/{}", converted, step),
+                None => converted,
+            });
+        }
+        Ok(parts.join(","))
+    }
+
+    /// 调度主循环
+    fn run(&self) {
+        log::info!("计划任务调度已启动");
+        while !self.stop_flag.load(Ordering::SeqCst) {
+            if let Err(e) = self.dispatch_due_tasks() {
+                log::error!("检查计划任务失败: {}", e);
+            }
+
+            let deadline = Instant::now() + TICK_INTERVAL;
+            while Instant::now() < deadline {
+                if self.stop_flag.load(Ordering::SeqCst) {
+                    return;
+                }
+                thread::sleep(SHUTDOWN_POLL);
+            }
+        }
+    }
+
+    /// 检查所有环境中已到期的任务，每个任务在独立线程中执行
+    fn dispatch_due_tasks(&self) -> Result<()> {
+        let environments = EnvironmentManager::global()
+            .lock()
+            .unwrap()
+            .get_all_environments()?;
+        let now = Utc::now();
+        let started_at = self.started_at.lock().unwrap().unwrap_or(now);
+
+        for environment in environments {
+            let tasks = match Self::load_tasks(&environment.id) {
+                Ok(tasks) => tasks,
+                Err(e) => {
+                    log::warn!("读取环境 {} 的计划任务失败: {}", environment.name, e);
+                    continue;
+                }
+            };
+            for task in tasks.into_iter().filter(|t| t.enabled) {
+                let Some(trigger) = self.due_trigger(&task, started_at, now) else {
+                    continue;
+                };
+                self.last_fired.lock().unwrap().insert(task.id.clone(), now);
+
+                let environment_id = environment.id.clone();
+                let spawned = thread::Builder::new()
+                    .name(format!("scheduled-task-{}", task.id))
+                    .spawn(move || {
+                        TaskScheduler::global().execute_task(&environment_id, &task, trigger);
+                    });
+                if let Err(e) = spawned {
+                    log::error!("启动计划任务线程失败: {}", e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 判断任务是否到期，返回本次的触发方式
+    ///
+    /// 以上一次执行时间为起点计算下一次调度；错过的多次调度只补执行一次。
+    fn due_trigger(
+        &self,
+        task: &ScheduledTask,
+        started_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Option<ScheduledTaskTrigger> {
+        let schedule = match Self::parse_cron_expr(&task.cron_expr) {
+            Ok(schedule) => schedule,
+            Err(e) => {
+                log::warn!("计划任务 {} 的 cron 表达式无效: {}", task.name, e);
+                return None;
+            }
+        };
+
+        let persisted = task
+            .last_run
+            .as_deref()
+            .unwrap_or(&task.created_at)
+            .parse::<DateTime<Utc>>()
+            .ok();
+        let last_fired = self.last_fired.lock().unwrap().get(&task.id).copied();
+        let mut since = persisted.max(last_fired).unwrap_or(started_at);
+        if !task.catch_up {
+            since = since.max(started_at);
+        }
+
+        let next = schedule.after(&since.with_timezone(&Local)).next()?;
+        if next.with_timezone(&Utc) > now {
+            return None;
+        }
+        // 到期时间早于调度启动时间，说明是应用关闭期间错过的调度
+        if next.with_timezone(&Utc) < started_at && last_fired.is_none() {
+            Some(ScheduledTaskTrigger::CatchUp)
+        } else {
+            Some(ScheduledTaskTrigger::Schedule)
+        }
+    }
+
+    /// 执行任务并记录结果；任务正在执行时返回 None
+    fn execute_task(
+        &self,
+        environment_id: &str,
+        task: &ScheduledTask,
+        trigger: ScheduledTaskTrigger,
+    ) -> Option<ScheduledTaskRun> {
+        if !self.running.lock().unwrap().insert(task.id.clone()) {
+            log::warn!("计划任务 {} 仍在执行，跳过本次调度", task.name);
+            return None;
+        }
+
+        log::info!(
+            "执行计划任务 {} ({:?}): {}",
+            task.name,
+            trigger,
+            task.command
+        );
+        let started_at = Utc::now();
+        let result = Self::build_env_vars(environment_id).and_then(|env_vars| {
+            ShellManager::execute_command_with_env_vars(&task.command, &env_vars, None)
+        });
+        let (stdout, stderr, exit_code) = match result {
+            Ok(output) => output,
+            Err(e) => (String::new(), e.to_string(), -1),
+        };
+        let finished_at = Utc::now();
+
+        let run = ScheduledTaskRun {
+            started_at: started_at.to_rfc3339(),
+            finished_at: finished_at.to_rfc3339(),
+            trigger,
+            status: if exit_code == 0 {
+                ScheduledTaskStatus::Success
+            } else {
+                ScheduledTaskStatus::Failed
+            },
+            exit_code,
+            stdout: output_tail(&stdout, OUTPUT_SNIPPET_CHARS),
+            stderr: output_tail(&stderr, OUTPUT_SNIPPET_CHARS),
+        };
+
+        // 执行期间错过的调度不再补执行
+        self.last_fired
+            .lock()
+            .unwrap()
+            .insert(task.id.clone(), finished_at);
+        if let Err(e) = self.record_run(environment_id, &task.id, &run) {
+            log::error!("保存计划任务 {} 的执行记录失败: {}", task.name, e);
+        }
+        self.running.lock().unwrap().remove(&task.id);
+
+        let callback = self.event_callback.lock().unwrap().clone();
+        if let Some(callback) = callback {
+            callback(
+                SCHEDULED_TASK_FINISHED_EVENT,
+                serde_json::json!({
+                    "environmentId": environment_id,
+                    "taskId": task.id,
+                    "taskName": task.name,
+                    "run": run,
+                }),
+            );
+        }
+        Some(run)
+    }
+
+    /// 写入执行记录，只保留最近 HISTORY_LIMIT 条
+    fn record_run(
+        &self,
+        environment_id: &str,
+        task_id: &str,
+        run: &ScheduledTaskRun,
+    ) -> Result<()> {
+        let _guard = self.store_lock.lock().unwrap();
+        let mut tasks = Self::load_tasks(environment_id)?;
+        let Some(task) = tasks.iter_mut().find(|t| t.id == task_id) else {
+            // 任务已在执行期间被删除
+            return Ok(());
+        };
+        task.last_run = Some(run.started_at.clone());
+        task.last_status = Some(run.status);
+        task.history.insert(0, run.clone());
+        task.history.truncate(HISTORY_LIMIT);
+        Self::save_tasks(environment_id, &tasks)
+    }
+
+    /// 构建任务的环境变量：已激活服务的 envVars / paths，再由环境变量覆盖
+    fn build_env_vars(environment_id: &str) -> Result<HashMap<String, String>> {
+        let service_datas = EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .get_environment_all_service_datas(environment_id)?;
+
+        let mut env_vars = HashMap::new();
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for service_data in service_datas
+            .iter()
+            .filter(|sd| matches!(sd.status, ServiceDataStatus::Active))
+        {
+            let Some(metadata) = service_data.metadata.as_ref() else {
+                continue;
+            };
+            if let Some(serde_json::Value::Object(vars)) = metadata.get("envVars") {
+                for (key, value) in vars {
+                    if let Some(value) = value.as_str() {
+                        env_vars.insert(key.clone(), value.to_string());
+                    }
+                }
+            }
+            if let Some(serde_json::Value::Array(entries)) = metadata.get("paths") {
+                paths.extend(entries.iter().filter_map(|p| p.as_str()).map(PathBuf::from));
+            }
+        }
+
+        if !paths.is_empty() {
+            if let Some(current) = std::env::var_os("PATH") {
+                paths.extend(std::env::split_paths(&current));
+            }
+            if let Ok(joined) = std::env::join_paths(paths) {
+                env_vars.insert("PATH".to_string(), joined.to_string_lossy().to_string());
+            }
+        }
+
+        env_vars.extend(
+            EnvironmentManager::global()
+                .lock()
+                .unwrap()
+                .get_environment_env_vars(environment_id)?,
+        );
+        Ok(env_vars)
+    }
+
+    /// 校验并规范化任务参数
+    fn validate_input(input: ScheduledTaskInput) -> Result<ScheduledTaskInput> {
+        let name = input.name.trim().to_string();
+        if name.is_empty() {
+            return Err(anyhow!("任务名称不能为空"));
+        }
+        let command = input.command.trim().to_string();
+        if command.is_empty() {
+            return Err(anyhow!("任务命令不能为空"));
+        }
+        let cron_expr = input.cron_expr.trim().to_string();
+        Self::parse_cron_expr(&cron_expr)?;
+
+        Ok(ScheduledTaskInput {
+            name,
+            cron_expr,
+            command,
+            ..input
+        })
+    }
+
+    /// 读取环境的计划任务列表
+    fn load_tasks(environment_id: &str) -> Result<Vec<ScheduledTask>> {
+        let value = EnvironmentManager::global()
+            .lock()
+            .unwrap()
+            .get_environment_metadata(environment_id, SCHEDULED_TASKS_METADATA_KEY)?;
+        match value {
+            Some(value) => Ok(serde_json::from_value(value)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// 保存环境的计划任务列表，列表为空时删除该键
+    fn save_tasks(environment_id: &str, tasks: &[ScheduledTask]) -> Result<()> {
+        let value = if tasks.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::to_value(tasks)?
+        };
+        EnvironmentManager::global()
+            .lock()
+            .unwrap()
+            .write_environment_metadata(environment_id, SCHEDULED_TASKS_METADATA_KEY, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_unix_day_of_week() {
+        let convert = |field: &str| TaskScheduler::convert_unix_day_of_week(field).unwrap();
+        assert_eq!(convert("0"), "1");
+        assert_eq!(convert("7"), "1");
+        assert_eq!(convert("1-5"), "2-6");
+        assert_eq!(convert("1-7"), "2-7,1");
+        assert_eq!(convert("0-7"), "1-7");
+        assert_eq!(convert("*/2"), "*/2");
+        assert_eq!(convert("1-7/2"), "2,4,6,1");
+        assert_eq!(convert("0-7/2"), "1,3,5,7");
+        assert_eq!(convert("MON-FRI"), "MON-FRI");
+        assert!(TaskScheduler::convert_unix_day_of_week("8").is_err());
+        assert!(TaskScheduler::convert_unix_day_of_week("1-7/0").is_err());
+    }
+
+    #[test]
+    fn test_parse_cron_expr_with_sunday_range_step() {
+        assert!(TaskScheduler::parse_cron_expr("0 9 * * 1-7/2").is_ok());
+        assert!(TaskScheduler::parse_cron_expr("0 9 * * 0-7").is_ok());
+        assert!(TaskScheduler::parse_cron_expr("0 9 * * 1-5").is_ok());
+    }
+}
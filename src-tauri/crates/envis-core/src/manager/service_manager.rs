@@ -1,11 +1,20 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, OnceLock};
 
 use crate::manager::app_config_manager::AppConfigManager;
-use crate::types::ServiceType;
+use crate::manager::env_serv_data_manager::{EnvServDataManager, ServiceDataResult};
+use crate::manager::environment_manager::EnvironmentManager;
+use crate::manager::services::download_manager::DownloadResult;
+use crate::manager::services::{
+    install_callback, DnsmasqService, DownloadManager, DownloadStatus, ServiceRegistry,
+};
+use crate::manager::system_info_manager::{ManagedProcessStats, SystemInfoManager};
+use crate::types::{Environment, ServiceData, ServiceDataStatus, ServiceStatus, ServiceType};
 
 /// 服务信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +31,19 @@ pub struct Service {
     pub installed: Option<bool>,
 }
 
+/// 环境中单个服务的运行状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceStatusSummary {
+    pub service_data_id: String,
+    #[serde(rename = "type")]
+    pub service_type: ServiceType,
+    pub version: String,
+    pub status: ServiceStatus,
+    /// 状态检查返回的数据（端口、健康检查等），检查失败或超时时为 { error }
+    pub detail: serde_json::Value,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceCheckData {
     pub installed: bool,
@@ -46,6 +68,75 @@ pub struct ServiceResult {
     pub data: Option<serde_json::Value>,
 }
 
+/// 服务更新检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceUpdateInfo {
+    pub service_type: ServiceType,
+    pub current_version: String,
+    pub latest_version: String,
+    pub has_update: bool,
+}
+
+/// 环境中的服务数据及其安装、运行状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledServiceData {
+    #[serde(flatten)]
+    pub service_data: ServiceData,
+    pub is_installed: bool,
+    /// 无守护进程的服务（如 Node.js、SSL）为 None
+    pub service_status: Option<ServiceStatus>,
+}
+
+/// 服务文件夹中条目的分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ServicesFolderEntryKind {
+    /// 有效安装（预期的可执行文件存在）
+    Installed,
+    /// 版本目录存在但缺少预期的可执行文件
+    Orphaned,
+    /// 残留的下载压缩包
+    Archive,
+    /// 安装过程中遗留的临时目录
+    Temp,
+}
+
+/// 服务文件夹扫描条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServicesFolderEntry {
+    pub kind: ServicesFolderEntryKind,
+    pub service_type: ServiceType,
+    pub version: Option<String>,
+    pub path: String,
+    pub size: u64,
+    pub size_formatted: String,
+    /// 所属版本被服务数据引用，清理时不会删除
+    pub referenced: bool,
+}
+
+/// 服务主进程的资源占用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceResourceUsage {
+    /// 未运行时为 None
+    pub pid: Option<u32>,
+    /// CPU 使用率 (%)，基于与上一次采样之间的差值
+    pub cpu_percent: f64,
+    /// 常驻内存 (KB)
+    pub memory_rss_kb: u64,
+}
+
+/// 升级服务数据时等待目标版本安装完成的最长时间
+const INSTALL_WAIT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30 * 60);
+
+/// 离线安装支持的压缩包格式，具体服务能否解压由其 extract_and_install 决定
+const OFFLINE_ARCHIVE_EXTENSIONS: &[&str] = &[".tar.gz", ".tgz", ".tar.xz", ".zip", ".dmg"];
+/// 汇总环境服务状态时单个服务检查的超时时间，避免某个卡住的 lsof 拖慢整个结果
+const SERVICE_STATUS_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
 /// 全局服务管理器单例
 static SERVICE_MANAGER: OnceLock<Arc<ServiceManager>> = OnceLock::new();
 
@@ -144,6 +235,980 @@ impl ServiceManager {
         })
     }
 
+    /// 检查环境中已激活服务是否有可用更新（同一主版本内的更高版本）
+    pub fn check_for_service_updates(&self, environment_id: &str) -> Result<ServiceResult> {
+        let service_datas = {
+            let env_serv_data_manager = EnvServDataManager::global();
+            let env_serv_data_manager = env_serv_data_manager.lock().unwrap();
+            env_serv_data_manager.get_environment_all_service_datas(environment_id)?
+        }; // 锁在这里被释放
+
+        let mut updates = Vec::new();
+        for service_data in service_datas
+            .iter()
+            .filter(|sd| matches!(sd.status, ServiceDataStatus::Active))
+        {
+            let available = match self.get_available_version_strings(&service_data.service_type) {
+                Some(versions) => versions,
+                None => continue, // 该类型没有可下载的版本列表（如 Custom、Host）
+            };
+
+            let current = match Self::parse_lenient_version(&service_data.version) {
+                Some(v) => v,
+                None => continue,
+            };
+
+            // 仅比较同一主版本，避免提示跨主版本升级
+            let latest = available
+                .iter()
+                .filter_map(|v| Self::parse_lenient_version(v).map(|parsed| (parsed, v)))
+                .filter(|(parsed, _)| parsed.major == current.major)
+                .max_by(|(a, _), (b, _)| a.cmp(b));
+
+            let (latest_version, has_update) = match latest {
+                Some((parsed, raw)) => (raw.clone(), parsed > current),
+                None => (service_data.version.clone(), false),
+            };
+
+            updates.push(ServiceUpdateInfo {
+                service_type: service_data.service_type.clone(),
+                current_version: service_data.version.clone(),
+                latest_version,
+                has_update,
+            });
+        }
+
+        Ok(ServiceResult {
+            success: true,
+            message: "检查服务更新成功".to_string(),
+            data: Some(serde_json::json!({ "updates": updates })),
+        })
+    }
+
+    /// 列出环境中的服务数据，并附带是否已安装和运行状态
+    pub fn list_installed_services_for_environment(
+        &self,
+        environment_id: &str,
+    ) -> Result<Vec<InstalledServiceData>> {
+        let service_datas = {
+            let env_serv_data_manager = EnvServDataManager::global();
+            let env_serv_data_manager = env_serv_data_manager.lock().unwrap();
+            env_serv_data_manager.get_environment_all_service_datas(environment_id)?
+        }; // 锁在这里被释放
+
+        Ok(service_datas
+            .into_iter()
+            .map(|service_data| {
+                let is_installed =
+                    self.is_service_installed(&service_data.service_type, &service_data.version);
+                let service_status = if is_installed {
+                    self.get_service_running_status(environment_id, &service_data)
+                } else {
+                    None
+                };
+                InstalledServiceData {
+                    service_data,
+                    is_installed,
+                    service_status,
+                }
+            })
+            .collect())
+    }
+
+    /// 检查指定版本的服务是否已安装（Custom、Host、SSL 无需安装，视为已安装）
+    pub fn is_service_installed(&self, service_type: &ServiceType, version: &str) -> bool {
+        ServiceRegistry::get(service_type).is_installed(version)
+    }
+
+    /// 从本地压缩包离线安装指定版本
+    ///
+    /// 压缩包复制到下载时的存放目录后作为下载任务处理，进度与下载历史和在线下载一致；
+    /// 解压后仍找不到预期的可执行文件时任务标记为失败。
+    pub async fn install_service_from_file(
+        &self,
+        service_type: &ServiceType,
+        version: &str,
+        archive_path: &str,
+        sha256: Option<&str>,
+    ) -> Result<DownloadResult> {
+        let version = version.trim();
+        if version.is_empty() {
+            return Err(anyhow!("版本号不能为空"));
+        }
+        let name = service_type.default_name();
+        let service = ServiceRegistry::get(service_type);
+        if service.is_installed(version) {
+            return Ok(DownloadResult::success(
+                format!("{} {} 已经安装", name, version),
+                None,
+            ));
+        }
+        let archive_dir = service
+            .archive_dir(version)
+            .ok_or_else(|| anyhow!("{} 不支持离线安装", name))?;
+
+        let source = PathBuf::from(archive_path);
+        if !source.is_file() {
+            return Err(anyhow!("压缩包不存在: {}", archive_path));
+        }
+        let filename = source
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if !OFFLINE_ARCHIVE_EXTENSIONS
+            .iter()
+            .any(|ext| filename.ends_with(ext))
+        {
+            return Err(anyhow!(
+                "不支持的压缩包格式: {}，支持 {}",
+                filename,
+                OFFLINE_ARCHIVE_EXTENSIONS.join(" / ")
+            ));
+        }
+
+        if let Some(expected) = sha256.map(str::trim).filter(|s| !s.is_empty()) {
+            let path = source.clone();
+            let actual = tokio::task::spawn_blocking(move || Self::file_sha256(&path)).await??;
+            if !actual.eq_ignore_ascii_case(expected) {
+                return Err(anyhow!(
+                    "SHA-256 校验失败: 期望 {}，实际 {}",
+                    expected,
+                    actual
+                ));
+            }
+        }
+
+        let task_id = format!("{}-{}", service_type.dir_name(), version);
+        let service_type_for_callback = service_type.clone();
+        let version_for_callback = version.to_string();
+        let success_callback = install_callback(format!("{} {}", name, version), move |task| {
+            let service_type = service_type_for_callback.clone();
+            let version = version_for_callback.clone();
+            async move {
+                let service = ServiceRegistry::get(&service_type);
+                service.extract_and_install(&task, &version).await?;
+                if !service.is_installed(&version) {
+                    return Err(anyhow!(
+                        "解压完成但未找到 {} {} 的可执行文件，请确认压缩包与当前系统和架构匹配",
+                        service_type.default_name(),
+                        version
+                    ));
+                }
+                Ok(())
+            }
+        });
+
+        let download_manager = DownloadManager::global();
+        download_manager
+            .import_local_archive(
+                task_id.clone(),
+                &source,
+                archive_dir,
+                Some(success_callback),
+            )
+            .await?;
+        Ok(DownloadResult::success(
+            format!("{} {} 压缩包已导入，正在安装", name, version),
+            download_manager.get_task_status(&task_id),
+        ))
+    }
+
+    /// 扫描服务文件夹，将条目分为有效安装、孤立目录、残留压缩包和临时目录
+    pub fn scan_services_folder(&self) -> Result<Vec<ServicesFolderEntry>> {
+        let services_folder = {
+            let app_config_manager = AppConfigManager::global();
+            let app_config_manager = app_config_manager.lock().unwrap();
+            PathBuf::from(app_config_manager.get_services_folder())
+        }; // 锁在这里被释放
+
+        let mut entries = Vec::new();
+        if !services_folder.exists() {
+            return Ok(entries);
+        }
+
+        let referenced = self.get_referenced_service_versions()?;
+        // 正在下载或安装的文件不参与扫描，避免清理时破坏进行中的任务
+        let active_paths: Vec<PathBuf> = DownloadManager::global()
+            .get_all_tasks()
+            .into_iter()
+            .filter(|task| {
+                !matches!(
+                    task.status,
+                    DownloadStatus::Installed | DownloadStatus::Failed | DownloadStatus::Cancelled
+                )
+            })
+            .map(|task| task.target_path)
+            .collect();
+        let is_active = |path: &Path| active_paths.iter().any(|p| p.starts_with(path));
+
+        for service_type in Self::installable_service_types() {
+            let service = ServiceRegistry::get(&service_type);
+            let type_path = services_folder.join(service_type.dir_name());
+            let Ok(type_entries) = fs::read_dir(&type_path) else {
+                continue;
+            };
+
+            for type_entry in type_entries.flatten() {
+                let path = type_entry.path();
+                if is_active(&path) {
+                    continue;
+                }
+                let name = type_entry.file_name().to_string_lossy().to_string();
+
+                if path.is_file() {
+                    if Self::is_archive_name(&name) {
+                        entries.push(self.build_folder_entry(
+                            ServicesFolderEntryKind::Archive,
+                            &service_type,
+                            None,
+                            &path,
+                            false,
+                        ));
+                    }
+                    continue;
+                }
+                if !path.is_dir() {
+                    continue;
+                }
+                if Self::is_temp_name(&name) {
+                    entries.push(self.build_folder_entry(
+                        ServicesFolderEntryKind::Temp,
+                        &service_type,
+                        None,
+                        &path,
+                        false,
+                    ));
+                    continue;
+                }
+
+                // 版本目录
+                let version = name;
+                let is_referenced = referenced.contains(&(service_type.clone(), version.clone()));
+                let kind = if service.is_installed(&version) {
+                    ServicesFolderEntryKind::Installed
+                } else {
+                    ServicesFolderEntryKind::Orphaned
+                };
+                entries.push(self.build_folder_entry(
+                    kind,
+                    &service_type,
+                    Some(&version),
+                    &path,
+                    is_referenced,
+                ));
+
+                // 版本目录中残留的压缩包和临时目录（如 temp_mongosh）
+                let Ok(version_entries) = fs::read_dir(&path) else {
+                    continue;
+                };
+                for version_entry in version_entries.flatten() {
+                    let sub_path = version_entry.path();
+                    let sub_name = version_entry.file_name().to_string_lossy().to_string();
+                    let kind = if sub_path.is_file() && Self::is_archive_name(&sub_name) {
+                        ServicesFolderEntryKind::Archive
+                    } else if sub_path.is_dir() && Self::is_temp_name(&sub_name) {
+                        ServicesFolderEntryKind::Temp
+                    } else {
+                        continue;
+                    };
+                    entries.push(self.build_folder_entry(
+                        kind,
+                        &service_type,
+                        Some(&version),
+                        &sub_path,
+                        is_referenced,
+                    ));
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// 删除扫描结果中指定分类的条目（有效安装和被服务数据引用的版本不会删除）
+    pub fn cleanup_services_folder(
+        &self,
+        kinds: &[ServicesFolderEntryKind],
+    ) -> Result<ServiceResult> {
+        let entries = self.scan_services_folder()?;
+
+        let mut removed_orphaned = 0;
+        let mut removed_archives = 0;
+        let mut removed_temp = 0;
+        let mut reclaimed_bytes: u64 = 0;
+        let mut failed = Vec::new();
+
+        for entry in entries.iter().filter(|e| {
+            kinds.contains(&e.kind) && e.kind != ServicesFolderEntryKind::Installed && !e.referenced
+        }) {
+            let path = Path::new(&entry.path);
+            if !path.exists() {
+                // 已随孤立的版本目录一起删除
+                continue;
+            }
+            let result = if path.is_dir() {
+                fs::remove_dir_all(path)
+            } else {
+                fs::remove_file(path)
+            };
+            match result {
+                Ok(_) => {
+                    log::info!("已清理服务文件夹条目: {}", entry.path);
+                    reclaimed_bytes += entry.size;
+                    match entry.kind {
+                        ServicesFolderEntryKind::Orphaned => removed_orphaned += 1,
+                        ServicesFolderEntryKind::Archive => removed_archives += 1,
+                        ServicesFolderEntryKind::Temp => removed_temp += 1,
+                        ServicesFolderEntryKind::Installed => {}
+                    }
+                }
+                Err(e) => {
+                    log::warn!("清理服务文件夹条目失败 {}: {}", entry.path, e);
+                    failed.push(entry.path.clone());
+                }
+            }
+        }
+
+        Ok(ServiceResult {
+            success: failed.is_empty(),
+            message: if failed.is_empty() {
+                format!("清理完成，释放 {}", self.format_file_size(reclaimed_bytes))
+            } else {
+                format!("清理完成，{} 项删除失败", failed.len())
+            },
+            data: Some(serde_json::json!({
+                "removed": {
+                    "orphaned": removed_orphaned,
+                    "archive": removed_archives,
+                    "temp": removed_temp,
+                },
+                "reclaimedBytes": reclaimed_bytes,
+                "reclaimedFormatted": self.format_file_size(reclaimed_bytes),
+                "failed": failed,
+            })),
+        })
+    }
+
+    /// 所有需要下载安装的服务类型
+    fn installable_service_types() -> Vec<ServiceType> {
+        vec![
+            ServiceType::Mongodb,
+            ServiceType::Mysql,
+            ServiceType::Mariadb,
+            ServiceType::Postgresql,
+            ServiceType::Redis,
+            ServiceType::Nginx,
+            ServiceType::Nodejs,
+            ServiceType::Python,
+            ServiceType::Java,
+            ServiceType::Rust,
+            ServiceType::Dnsmasq,
+            ServiceType::Nasm,
+            ServiceType::Php,
+            ServiceType::Vault,
+            ServiceType::Opensearch,
+            ServiceType::Kafka,
+            ServiceType::Rabbitmq,
+        ]
+    }
+
+    /// 收集所有环境的服务数据引用的服务版本
+    fn get_referenced_service_versions(&self) -> Result<HashSet<(ServiceType, String)>> {
+        let environments = EnvironmentManager::global()
+            .lock()
+            .unwrap()
+            .get_all_environments()?;
+        let env_serv_data_manager = EnvServDataManager::global();
+        let env_serv_data_manager = env_serv_data_manager.lock().unwrap();
+
+        let mut referenced = HashSet::new();
+        for environment in environments {
+            for service_data in
+                env_serv_data_manager.get_environment_all_service_datas(&environment.id)?
+            {
+                referenced.insert((service_data.service_type, service_data.version));
+            }
+        }
+        Ok(referenced)
+    }
+
+    fn build_folder_entry(
+        &self,
+        kind: ServicesFolderEntryKind,
+        service_type: &ServiceType,
+        version: Option<&str>,
+        path: &Path,
+        referenced: bool,
+    ) -> ServicesFolderEntry {
+        let size = self.get_folder_size(path).unwrap_or(0);
+        ServicesFolderEntry {
+            kind,
+            service_type: service_type.clone(),
+            version: version.map(|v| v.to_string()),
+            path: path.to_string_lossy().to_string(),
+            size,
+            size_formatted: self.format_file_size(size),
+            referenced,
+        }
+    }
+
+    fn is_archive_name(name: &str) -> bool {
+        let name = name.to_lowercase();
+        name.ends_with(".tgz")
+            || name.ends_with(".zip")
+            || name.ends_with(".tar")
+            || name.contains(".tar.")
+    }
+
+    fn is_temp_name(name: &str) -> bool {
+        let name = name.to_lowercase();
+        name == "temp" || name == "tmp" || name.starts_with("temp_") || name.starts_with("tmp_")
+    }
+
+    /// 获取服务运行状态，无守护进程的服务返回 None
+    pub fn get_service_running_status(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Option<ServiceStatus> {
+        if service_data.service_type == ServiceType::Dnsmasq {
+            return DnsmasqService::global()
+                .get_service_status(service_data)
+                .ok();
+        }
+        if !Self::is_daemon_service(&service_data.service_type) {
+            return None;
+        }
+        Some(match self.is_service_running(environment_id, service_data) {
+            Ok(true) => ServiceStatus::Running,
+            Ok(false) => ServiceStatus::Stopped,
+            Err(_) => ServiceStatus::Unknown,
+        })
+    }
+
+    /// 并发获取环境中所有服务的运行状态，单个服务检查失败或超时不影响整体结果
+    pub async fn get_environment_services_status(
+        &self,
+        environment_id: &str,
+    ) -> Result<Vec<ServiceStatusSummary>> {
+        let service_datas = EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .get_environment_all_service_datas(environment_id)?;
+
+        let checks = service_datas.into_iter().map(|service_data| {
+            let environment_id = environment_id.to_string();
+            async move {
+                let task = tokio::task::spawn_blocking({
+                    let service_data = service_data.clone();
+                    move || Self::check_service_status(&environment_id, &service_data)
+                });
+                // 超时只是不再等待结果，阻塞的检查仍会在后台线程中执行完
+                let (status, detail) = match tokio::time::timeout(SERVICE_STATUS_TIMEOUT, task).await
+                {
+                    Ok(Ok(result)) => result,
+                    Ok(Err(e)) => (
+                        ServiceStatus::Error,
+                        serde_json::json!({ "error": format!("状态检查任务失败: {}", e) }),
+                    ),
+                    Err(_) => (
+                        ServiceStatus::Unknown,
+                        serde_json::json!({
+                            "error": format!("状态检查超时（{} 秒）", SERVICE_STATUS_TIMEOUT.as_secs())
+                        }),
+                    ),
+                };
+                ServiceStatusSummary {
+                    service_data_id: service_data.id,
+                    service_type: service_data.service_type,
+                    version: service_data.version,
+                    status,
+                    detail,
+                }
+            }
+        });
+
+        Ok(futures_util::future::join_all(checks).await)
+    }
+
+    /// 检查单个服务的运行状态，无守护进程的服务返回 Unknown
+    fn check_service_status(
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> (ServiceStatus, serde_json::Value) {
+        let error = |message: String| {
+            (
+                ServiceStatus::Error,
+                serde_json::json!({ "error": message }),
+            )
+        };
+
+        if service_data.service_type == ServiceType::Dnsmasq {
+            return match DnsmasqService::global().get_service_status(service_data) {
+                Ok(status) => (status, serde_json::json!({})),
+                Err(e) => error(e.to_string()),
+            };
+        }
+        if !Self::is_daemon_service(&service_data.service_type) {
+            return (ServiceStatus::Unknown, serde_json::json!({}));
+        }
+
+        match ServiceRegistry::get(&service_data.service_type)
+            .get_service_status(environment_id, service_data)
+        {
+            Ok(result) if result.success => {
+                let data = result.data.unwrap_or_else(|| serde_json::json!({}));
+                let status = data
+                    .get("status")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                    .or_else(|| {
+                        data.get("isRunning")
+                            .and_then(|v| v.as_bool())
+                            .map(|running| {
+                                if running {
+                                    ServiceStatus::Running
+                                } else {
+                                    ServiceStatus::Stopped
+                                }
+                            })
+                    })
+                    .unwrap_or(ServiceStatus::Unknown);
+                (status, data)
+            }
+            Ok(result) => error(result.message),
+            Err(e) => error(e.to_string()),
+        }
+    }
+
+    /// 获取服务类型对应的可用版本号列表
+    fn get_available_version_strings(&self, service_type: &ServiceType) -> Option<Vec<String>> {
+        if !service_type.needs_download() {
+            return None;
+        }
+        Some(ServiceRegistry::get(service_type).available_versions())
+    }
+
+    /// 宽松解析版本号：去掉 v 前缀和非数字后缀，不足三段时补 0（如 "8" -> 8.0.0）
+    pub(crate) fn parse_lenient_version(version: &str) -> Option<semver::Version> {
+        let numeric: String = version
+            .trim()
+            .trim_start_matches('v')
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == '.')
+            .collect();
+        let mut parts: Vec<&str> = numeric.split('.').filter(|s| !s.is_empty()).take(3).collect();
+        if parts.is_empty() {
+            return None;
+        }
+        while parts.len() < 3 {
+            parts.push("0");
+        }
+        semver::Version::parse(&parts.join(".")).ok()
+    }
+
+    /// 是否为需要启动进程的常驻服务（数据库 / Nginx / PHP-FPM / Vault / OpenSearch / Kafka / RabbitMQ / Compose 项目）
+    pub fn is_daemon_service(service_type: &ServiceType) -> bool {
+        matches!(
+            service_type,
+            ServiceType::Mongodb
+                | ServiceType::Mysql
+                | ServiceType::Mariadb
+                | ServiceType::Postgresql
+                | ServiceType::Redis
+                | ServiceType::Nginx
+                | ServiceType::Php
+                | ServiceType::Vault
+                | ServiceType::Opensearch
+                | ServiceType::Kafka
+                | ServiceType::Rabbitmq
+                | ServiceType::Compose
+        )
+    }
+
+    /// 统计所有环境中常驻服务（含 Dnsmasq）正在运行的进程资源占用
+    pub fn get_managed_process_stats(&self) -> Result<ManagedProcessStats> {
+        let (services_folder, envs_folder) = Self::get_services_and_envs_folders();
+
+        let environments = EnvironmentManager::global()
+            .lock()
+            .unwrap()
+            .get_all_environments()?;
+        let mut targets = Vec::new();
+        for environment in environments {
+            let service_datas = EnvServDataManager::global()
+                .lock()
+                .unwrap()
+                .get_environment_all_service_datas(&environment.id)?;
+            targets.extend(
+                service_datas
+                    .into_iter()
+                    .filter(|sd| {
+                        Self::is_daemon_service(&sd.service_type)
+                            || matches!(sd.service_type, ServiceType::Dnsmasq)
+                    })
+                    .map(|sd| (environment.id.clone(), sd)),
+            );
+        }
+
+        SystemInfoManager::global().get_managed_process_stats(
+            &targets,
+            &services_folder,
+            &envs_folder,
+        )
+    }
+
+    /// 获取服务主进程的 CPU 与内存占用
+    ///
+    /// 优先使用服务状态中返回的 PID；状态未提供 PID 时按安装目录匹配服务进程，
+    /// 返回主进程 PID 及主进程与子进程的合计占用。找不到进程时 pid 为 None。
+    pub fn get_service_resource_usage(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceResourceUsage> {
+        if !Self::is_daemon_service(&service_data.service_type)
+            && service_data.service_type != ServiceType::Dnsmasq
+        {
+            return Err(anyhow!(
+                "服务类型 {:?} 不是常驻服务",
+                service_data.service_type
+            ));
+        }
+
+        let status_pid = if Self::is_daemon_service(&service_data.service_type) {
+            ServiceRegistry::get(&service_data.service_type)
+                .get_service_status(environment_id, service_data)?
+                .data
+                .as_ref()
+                .and_then(|d| d.get("pid"))
+                .and_then(|v| v.as_u64())
+                .map(|pid| pid as u32)
+        } else {
+            None
+        };
+
+        let system_info_manager = SystemInfoManager::global();
+        if let Some(pid) = status_pid {
+            if let Some((cpu_usage, memory_bytes)) = system_info_manager.get_process_usage(pid)? {
+                return Ok(ServiceResourceUsage {
+                    pid: Some(pid),
+                    cpu_percent: cpu_usage as f64,
+                    memory_rss_kb: memory_bytes / 1024,
+                });
+            }
+        }
+
+        let (services_folder, envs_folder) = Self::get_services_and_envs_folders();
+        let stats = system_info_manager.get_managed_process_stats(
+            &[(environment_id.to_string(), service_data.clone())],
+            &services_folder,
+            &envs_folder,
+        )?;
+        let stat = stats
+            .values()
+            .flat_map(|by_type| by_type.values())
+            .flatten()
+            .next();
+
+        Ok(match stat {
+            Some(stat) => ServiceResourceUsage {
+                pid: Some(stat.pid),
+                cpu_percent: stat.cpu_usage as f64,
+                memory_rss_kb: stat.memory_bytes / 1024,
+            },
+            None => ServiceResourceUsage {
+                pid: None,
+                cpu_percent: 0.0,
+                memory_rss_kb: 0,
+            },
+        })
+    }
+
+    fn get_services_and_envs_folders() -> (PathBuf, PathBuf) {
+        let app_config_manager = AppConfigManager::global();
+        let app_config_manager = app_config_manager.lock().unwrap();
+        (
+            PathBuf::from(app_config_manager.get_services_folder()),
+            PathBuf::from(app_config_manager.get_envs_folder()),
+        )
+    }
+
+    /// 检查常驻服务进程是否在运行（Degraded 视为仍在运行）
+    pub fn is_service_running(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<bool> {
+        Self::ensure_daemon_service(service_data)?;
+        let result = ServiceRegistry::get(&service_data.service_type)
+            .get_service_status(environment_id, service_data)?;
+
+        Ok(result
+            .data
+            .as_ref()
+            .and_then(|d| d.get("isRunning"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false))
+    }
+
+    /// 启动常驻服务
+    pub fn start_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        Self::ensure_daemon_service(service_data)?;
+        ServiceRegistry::get(&service_data.service_type).start_service(environment_id, service_data)
+    }
+
+    /// 停止常驻服务
+    pub fn stop_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        Self::ensure_daemon_service(service_data)?;
+        ServiceRegistry::get(&service_data.service_type).stop_service(environment_id, service_data)
+    }
+
+    fn ensure_daemon_service(service_data: &ServiceData) -> Result<()> {
+        if !Self::is_daemon_service(&service_data.service_type) {
+            return Err(anyhow!(
+                "服务类型 {:?} 不是常驻服务",
+                service_data.service_type
+            ));
+        }
+        Ok(())
+    }
+
+    /// 将服务数据升级到目标版本
+    ///
+    /// 按需下载安装目标版本；运行中的服务先停止，已激活的服务先停用，
+    /// 迁移数据目录与 metadata 后再重新激活并重启。数据库跨大版本升级需要 allow_major。
+    pub async fn upgrade_service_data(
+        &self,
+        environment_id: &str,
+        service_data_id: &str,
+        target_version: &str,
+        allow_major: bool,
+    ) -> Result<ServiceDataResult> {
+        let mut service_data = EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .get_service_data(environment_id, service_data_id)?;
+        let service_type = service_data.service_type.clone();
+        let from_version = service_data.version.clone();
+        let target_version = target_version.trim();
+
+        if !service_type.needs_download() {
+            return Err(anyhow!(
+                "{} 服务没有版本，无法升级",
+                service_type.default_name()
+            ));
+        }
+        if target_version.is_empty() || target_version == from_version {
+            return Err(anyhow!("目标版本与当前版本相同"));
+        }
+
+        let (from, to) = match (
+            Self::parse_lenient_version(&from_version),
+            Self::parse_lenient_version(target_version),
+        ) {
+            (Some(from), Some(to)) => (from, to),
+            _ => {
+                return Err(anyhow!(
+                    "无法解析版本号: {} -> {}",
+                    from_version,
+                    target_version
+                ))
+            }
+        };
+        if to < from {
+            return Err(anyhow!(
+                "不支持降级: {} -> {}",
+                from_version,
+                target_version
+            ));
+        }
+
+        let caveat = if to.major != from.major {
+            Self::major_upgrade_caveat(&service_type)
+        } else {
+            None
+        };
+        if let Some(caveat) = caveat {
+            if !allow_major {
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: format!(
+                        "{} {} -> {} 为跨大版本升级，确认后需指定 allowMajor。{}",
+                        service_type.default_name(),
+                        from_version,
+                        target_version,
+                        caveat
+                    ),
+                    data: Some(serde_json::json!({
+                        "fromVersion": from_version,
+                        "toVersion": target_version,
+                        "requiresAllowMajor": true,
+                        "caveat": caveat,
+                    })),
+                });
+            }
+        }
+
+        self.install_service_version(&service_type, target_version)
+            .await?;
+
+        // 停止运行中的服务
+        let was_running = Self::is_daemon_service(&service_type)
+            && self
+                .is_service_running(environment_id, &service_data)
+                .unwrap_or(false);
+        if was_running {
+            let stopped = self.stop_service(environment_id, &service_data)?;
+            if !stopped.success {
+                return Err(anyhow!("停止服务失败: {}", stopped.message));
+            }
+        }
+
+        let was_active = matches!(service_data.status, ServiceDataStatus::Active);
+        {
+            let env_serv_data_manager = EnvServDataManager::global();
+            let env_serv_data_manager = env_serv_data_manager.lock().unwrap();
+
+            // 先停用旧版本的 PATH 与环境变量，迁移后再按新版本激活
+            if was_active {
+                env_serv_data_manager.deactive_service_data(
+                    environment_id,
+                    &mut service_data,
+                    None,
+                )?;
+            }
+            env_serv_data_manager.change_service_data_version(
+                environment_id,
+                &mut service_data,
+                target_version,
+            )?;
+            if was_active {
+                env_serv_data_manager.active_service_data(
+                    environment_id,
+                    &mut service_data,
+                    None,
+                )?;
+            }
+        } // 锁在这里被释放
+        log::info!(
+            "服务数据 {} 已从 {} 升级到 {}",
+            service_data.id,
+            from_version,
+            target_version
+        );
+
+        let mut message = format!(
+            "{} 已从 {} 升级到 {}",
+            service_data.name, from_version, target_version
+        );
+        let mut restarted = false;
+        if was_running {
+            match self.start_service(environment_id, &service_data) {
+                Ok(result) if result.success => restarted = true,
+                Ok(result) => message = format!("{}，但重启失败: {}", message, result.message),
+                Err(e) => message = format!("{}，但重启失败: {}", message, e),
+            }
+        }
+
+        Ok(ServiceDataResult {
+            success: true,
+            message,
+            data: Some(serde_json::json!({
+                "serviceData": service_data,
+                "fromVersion": from_version,
+                "toVersion": target_version,
+                "reactivated": was_active,
+                "wasRunning": was_running,
+                "restarted": restarted,
+                "caveat": caveat,
+            })),
+        })
+    }
+
+    /// 数据库跨大版本升级的迁移注意事项，非数据库服务返回 None
+    fn major_upgrade_caveat(service_type: &ServiceType) -> Option<&'static str> {
+        match service_type {
+            ServiceType::Mongodb => Some(
+                "MongoDB 只支持逐个大版本升级（如 6.0 -> 7.0 -> 8.0），升级前需将 featureCompatibilityVersion 设置为当前版本，并建议先用 mongodump 备份数据。",
+            ),
+            ServiceType::Mysql => Some(
+                "MySQL 跨大版本升级前请先备份数据，新版本首次启动时会自动升级数据字典，升级后无法回退到旧版本。",
+            ),
+            ServiceType::Mariadb => Some(
+                "MariaDB 升级后需运行 mariadb-upgrade 更新系统表，升级前请先备份数据。",
+            ),
+            ServiceType::Postgresql => Some(
+                "PostgreSQL 不同大版本的数据目录格式不兼容，需使用 pg_dump/pg_restore 或 pg_upgrade 迁移数据，新版本无法直接启动旧数据目录。",
+            ),
+            ServiceType::Redis => Some(
+                "Redis 新版本可以读取旧版本的 RDB/AOF 文件，但新版本写入的文件无法被旧版本读取。",
+            ),
+            _ => None,
+        }
+    }
+
+    /// 下载并安装指定版本，等待后台解压安装完成
+    async fn install_service_version(
+        &self,
+        service_type: &ServiceType,
+        version: &str,
+    ) -> Result<()> {
+        if self.is_service_installed(service_type, version) {
+            return Ok(());
+        }
+
+        let result = ServiceRegistry::get(service_type)
+            .download_and_install(version)
+            .await?;
+        if !result.success {
+            return Err(anyhow!(
+                "下载 {} {} 失败: {}",
+                service_type.default_name(),
+                version,
+                result.message
+            ));
+        }
+
+        // 下载完成后由回调在后台解压安装，轮询任务状态直到安装结束
+        let task_id = format!("{}-{}", service_type.dir_name(), version);
+        let deadline = std::time::Instant::now() + INSTALL_WAIT_TIMEOUT;
+        loop {
+            match DownloadManager::global().get_task_status(&task_id) {
+                Some(task) => match task.status {
+                    DownloadStatus::Installed => return Ok(()),
+                    DownloadStatus::Failed | DownloadStatus::Cancelled | DownloadStatus::Paused => {
+                        return Err(anyhow!(
+                            "安装 {} {} 未完成: {}",
+                            service_type.default_name(),
+                            version,
+                            task.error_message
+                                .unwrap_or_else(|| format!("{:?}", task.status))
+                        ));
+                    }
+                    _ => {}
+                },
+                None if self.is_service_installed(service_type, version) => return Ok(()),
+                None => return Err(anyhow!("找不到下载任务: {}", task_id)),
+            }
+            if std::time::Instant::now() >= deadline {
+                return Err(anyhow!(
+                    "等待 {} {} 安装超时",
+                    service_type.default_name(),
+                    version
+                ));
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        }
+    }
+
     /// 获取单个服务的文件夹大小
     pub fn get_service_size(
         &self,
@@ -174,22 +1239,30 @@ impl ServiceManager {
 
         let size = self.get_folder_size(&service_path)?;
         let size_formatted = self.format_file_size(size);
+        // 目录存在但缺少可执行文件时为残缺安装
+        let installed = ServiceRegistry::get(service_type).is_installed(version);
 
         Ok(ServiceResult {
             success: true,
             message: "获取服务大小成功".to_string(),
             data: Some(serde_json::json!({
                 "size": size,
-                "sizeFormatted": size_formatted
+                "sizeFormatted": size_formatted,
+                "installed": installed
             })),
         })
     }
 
     /// 删除已安装的服务
+    ///
+    /// 仍被服务数据引用时，除非 force 为 true（同时停用这些服务数据），否则拒绝删除；
+    /// 存在从安装目录启动的进程时，除非 stop_running 为 true（先结束这些进程），否则拒绝删除。
     pub fn delete_service(
         &self,
         service_type: &ServiceType,
         version: &str,
+        force: bool,
+        stop_running: bool,
     ) -> Result<ServiceResult> {
         let services_folder = {
             let app_config_manager = AppConfigManager::global();
@@ -210,6 +1283,94 @@ impl ServiceManager {
             });
         }
 
+        let references = self.find_service_data_references(service_type, version)?;
+        if !references.is_empty() && !force {
+            let mut environment_names: Vec<&str> = references
+                .iter()
+                .map(|(env, _)| env.name.as_str())
+                .collect();
+            environment_names.dedup();
+            return Ok(ServiceResult {
+                success: false,
+                message: format!(
+                    "{} {} 仍被以下环境使用: {}",
+                    service_type_str,
+                    version,
+                    environment_names.join("、")
+                ),
+                data: Some(serde_json::json!({
+                    "references": references
+                        .iter()
+                        .map(|(env, sd)| Self::reference_json(env, sd))
+                        .collect::<Vec<_>>(),
+                })),
+            });
+        }
+
+        let system_info_manager = SystemInfoManager::global();
+        let processes = system_info_manager.find_processes_by_exe_dir(&service_path)?;
+        if !processes.is_empty() && !stop_running {
+            let pids: Vec<String> = processes.iter().map(|p| p.pid.to_string()).collect();
+            return Ok(ServiceResult {
+                success: false,
+                message: format!(
+                    "{} {} 正在运行（PID: {}），请先停止",
+                    service_type_str,
+                    version,
+                    pids.join(", ")
+                ),
+                data: Some(serde_json::json!({ "processes": processes })),
+            });
+        }
+
+        // 结束从安装目录启动的进程，并等待其退出
+        for process in &processes {
+            system_info_manager.kill_process(process.pid)?;
+        }
+        if !processes.is_empty() {
+            let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+            while !system_info_manager
+                .find_processes_by_exe_dir(&service_path)?
+                .is_empty()
+            {
+                if std::time::Instant::now() >= deadline {
+                    return Err(anyhow!("结束 {} {} 的进程超时", service_type_str, version));
+                }
+                std::thread::sleep(std::time::Duration::from_millis(200));
+            }
+            log::info!(
+                "已结束 {} {} 的 {} 个进程",
+                service_type_str,
+                version,
+                processes.len()
+            );
+        }
+
+        // 停用引用该版本的服务数据
+        let mut unlinked = Vec::new();
+        if !references.is_empty() {
+            let env_serv_data_manager = EnvServDataManager::global();
+            let env_serv_data_manager = env_serv_data_manager.lock().unwrap();
+            for (environment, mut service_data) in references {
+                if matches!(service_data.status, ServiceDataStatus::Active) {
+                    if let Err(e) = env_serv_data_manager.deactive_service_data(
+                        &environment.id,
+                        &mut service_data,
+                        None,
+                    ) {
+                        log::warn!(
+                            "停用服务数据 {} 失败，直接标记为未激活: {}",
+                            service_data.id,
+                            e
+                        );
+                        service_data.status = ServiceDataStatus::Inactive;
+                        env_serv_data_manager.save_service_data(&environment.id, &service_data)?;
+                    }
+                }
+                unlinked.push(Self::reference_json(&environment, &service_data));
+            }
+        }
+
         // 删除服务文件夹
         fs::remove_dir_all(&service_path).context("删除服务文件夹失败")?;
 
@@ -226,7 +1387,45 @@ impl ServiceManager {
         Ok(ServiceResult {
             success: true,
             message: format!("{} {} 删除成功", service_type_str, version),
-            data: None,
+            data: Some(serde_json::json!({
+                "stopped": processes,
+                "unlinked": unlinked,
+            })),
+        })
+    }
+
+    /// 查找所有环境中引用指定服务版本的服务数据
+    fn find_service_data_references(
+        &self,
+        service_type: &ServiceType,
+        version: &str,
+    ) -> Result<Vec<(Environment, ServiceData)>> {
+        let environments = EnvironmentManager::global()
+            .lock()
+            .unwrap()
+            .get_all_environments()?;
+        let env_serv_data_manager = EnvServDataManager::global();
+        let env_serv_data_manager = env_serv_data_manager.lock().unwrap();
+
+        let mut references = Vec::new();
+        for environment in environments {
+            for service_data in
+                env_serv_data_manager.get_environment_all_service_datas(&environment.id)?
+            {
+                if &service_data.service_type == service_type && service_data.version == version {
+                    references.push((environment.clone(), service_data));
+                }
+            }
+        }
+        Ok(references)
+    }
+
+    fn reference_json(environment: &Environment, service_data: &ServiceData) -> serde_json::Value {
+        serde_json::json!({
+            "environmentId": environment.id,
+            "environmentName": environment.name,
+            "serviceDataId": service_data.id,
+            "serviceDataName": service_data.name,
         })
     }
 
@@ -248,6 +1447,12 @@ impl ServiceManager {
             ServiceType::SSL => "ssl".to_string(),
             ServiceType::Dnsmasq => "dnsmasq".to_string(),
             ServiceType::Nasm => "nasm".to_string(),
+            ServiceType::Php => "php".to_string(),
+            ServiceType::Vault => "vault".to_string(),
+            ServiceType::Opensearch => "opensearch".to_string(),
+            ServiceType::Kafka => "kafka".to_string(),
+            ServiceType::Rabbitmq => "rabbitmq".to_string(),
+            ServiceType::Compose => "compose".to_string(),
         }
     }
 
@@ -268,6 +1473,12 @@ impl ServiceManager {
             "ssl" => Some(ServiceType::SSL),
             "dnsmasq" => Some(ServiceType::Dnsmasq),
             "nasm" => Some(ServiceType::Nasm),
+            "php" => Some(ServiceType::Php),
+            "vault" => Some(ServiceType::Vault),
+            "opensearch" => Some(ServiceType::Opensearch),
+            "kafka" => Some(ServiceType::Kafka),
+            "rabbitmq" => Some(ServiceType::Rabbitmq),
+            "compose" => Some(ServiceType::Compose),
             _ => None,
         }
     }
@@ -311,6 +1522,14 @@ impl ServiceManager {
             format!("{:.2} {}", size, UNITS[unit_index])
         }
     }
+
+    /// 流式计算文件的 SHA-256（小写十六进制）
+    fn file_sha256(path: &Path) -> Result<String> {
+        let mut file = fs::File::open(path)?;
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut file, &mut hasher)?;
+        Ok(format!("{:x}", hasher.finalize()))
+    }
 }
 
 /// 初始化服务管理器
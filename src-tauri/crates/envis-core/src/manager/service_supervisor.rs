@@ -0,0 +1,481 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::manager::app_config_manager::AppConfigManager;
+use crate::manager::env_serv_data_manager::{EnvServDataManager, ServiceDataResult};
+use crate::manager::environment_manager::EnvironmentManager;
+use crate::manager::service_manager::ServiceManager;
+use crate::types::{EnvironmentStatus, ServiceData, ServiceDataStatus, ServiceType};
+
+/// 开启自动重启的 metadata 键
+pub const AUTO_RESTART_METADATA_KEY: &str = "AUTO_RESTART";
+/// 服务意外停止事件
+pub const SERVICE_CRASHED_EVENT: &str = "service-crashed";
+/// 服务被自动重启事件
+pub const SERVICE_RESTARTED_EVENT: &str = "service-restarted";
+
+/// 退避的基础等待时间，第 n 次重启前等待 base * 2^n
+const BACKOFF_BASE: Duration = Duration::from_secs(2);
+/// 退避等待的上限
+const BACKOFF_MAX: Duration = Duration::from_secs(300);
+/// 服务持续运行超过该时间后清零重启次数
+const STABLE_RESET: Duration = Duration::from_secs(60);
+/// 监控线程检查退出标记的间隔
+const SHUTDOWN_POLL: Duration = Duration::from_millis(200);
+
+/// 监控事件回调：事件名 + 事件数据
+pub type SupervisionEventCallback = Arc<dyn Fn(&str, serde_json::Value) + Send + Sync>;
+
+/// 单个被监控服务的状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupervisedService {
+    pub environment_id: String,
+    pub service_id: String,
+    pub service_name: String,
+    pub service_type: ServiceType,
+    pub running: bool,
+    pub restart_attempts: u32,
+    pub gave_up: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_crash_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_restart_at: Option<String>,
+    #[serde(skip)]
+    running_since: Option<Instant>,
+    #[serde(skip)]
+    next_retry_at: Option<Instant>,
+}
+
+/// 监控整体状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupervisionStatus {
+    pub running: bool,
+    pub interval_secs: u64,
+    pub max_retries: u32,
+    pub services: Vec<SupervisedService>,
+}
+
+#[derive(Default)]
+struct SupervisorState {
+    /// 通过 stop_service 主动停止的服务，不视为崩溃
+    intentional_stops: HashSet<String>,
+    services: HashMap<String, SupervisedService>,
+}
+
+/// 全局服务监控器单例
+static SERVICE_SUPERVISOR: OnceLock<Arc<ServiceSupervisor>> = OnceLock::new();
+
+/// 服务监控器：对开启 AUTO_RESTART 的服务定期检查，意外停止时自动重启
+pub struct ServiceSupervisor {
+    state: Mutex<SupervisorState>,
+    stop_flag: AtomicBool,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl ServiceSupervisor {
+    /// 获取全局服务监控器实例
+    pub fn global() -> Arc<ServiceSupervisor> {
+        SERVICE_SUPERVISOR
+            .get_or_init(|| {
+                let supervisor = Self::new();
+                Arc::new(supervisor)
+            })
+            .clone()
+    }
+
+    /// 创建新的服务监控器
+    fn new() -> Self {
+        Self {
+            state: Mutex::new(SupervisorState::default()),
+            stop_flag: AtomicBool::new(false),
+            handle: Mutex::new(None),
+        }
+    }
+
+    /// 启动监控线程，重复调用时忽略
+    pub fn start(&self, event_callback: SupervisionEventCallback) {
+        let mut handle = self.handle.lock().unwrap();
+        if handle.is_some() {
+            return;
+        }
+        self.stop_flag.store(false, Ordering::SeqCst);
+
+        let spawned = thread::Builder::new()
+            .name("service-supervisor".to_string())
+            .spawn(move || {
+                let supervisor = ServiceSupervisor::global();
+                supervisor.run(event_callback);
+            });
+        match spawned {
+            Ok(h) => *handle = Some(h),
+            Err(e) => log::error!("启动服务监控线程失败: {}", e),
+        }
+    }
+
+    /// 停止监控线程并等待其退出
+    pub fn shutdown(&self) {
+        self.stop_flag.store(true, Ordering::SeqCst);
+        let handle = self.handle.lock().unwrap().take();
+        if let Some(handle) = handle {
+            if handle.join().is_err() {
+                log::error!("服务监控线程异常退出");
+            }
+            log::info!("服务监控已停止");
+        }
+    }
+
+    /// 记录主动停止的服务，监控不会将其视为崩溃
+    pub fn mark_intentional_stop(&self, service_id: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.intentional_stops.insert(service_id.to_string());
+        if let Some(service) = state.services.get_mut(service_id) {
+            service.next_retry_at = None;
+        }
+    }
+
+    /// 服务重新启动后清除主动停止标记
+    pub fn clear_intentional_stop(&self, service_id: &str) {
+        self.state
+            .lock()
+            .unwrap()
+            .intentional_stops
+            .remove(service_id);
+    }
+
+    /// 服务是否开启了自动重启
+    pub fn is_auto_restart_enabled(service_data: &ServiceData) -> bool {
+        service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get(AUTO_RESTART_METADATA_KEY))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// 开启或关闭服务的自动重启
+    pub fn set_auto_restart(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        enabled: bool,
+    ) -> Result<ServiceDataResult> {
+        if !ServiceManager::is_daemon_service(&service_data.service_type) {
+            return Err(anyhow!(
+                "服务类型 {:?} 不支持自动重启",
+                service_data.service_type
+            ));
+        }
+
+        let mut service_data = service_data.clone();
+        let result = EnvServDataManager::global().lock().unwrap().set_metadata(
+            environment_id,
+            &mut service_data,
+            AUTO_RESTART_METADATA_KEY,
+            serde_json::Value::Bool(enabled),
+        )?;
+
+        if !enabled {
+            self.state.lock().unwrap().services.remove(&service_data.id);
+        }
+
+        Ok(ServiceDataResult {
+            success: result.success,
+            message: if enabled {
+                "已开启自动重启".to_string()
+            } else {
+                "已关闭自动重启".to_string()
+            },
+            data: result.data,
+        })
+    }
+
+    /// 获取监控状态
+    pub fn get_status(&self) -> SupervisionStatus {
+        let (interval_secs, max_retries) = Self::get_settings();
+        let mut services: Vec<SupervisedService> = self
+            .state
+            .lock()
+            .unwrap()
+            .services
+            .values()
+            .cloned()
+            .collect();
+        services.sort_by(|a, b| a.service_name.cmp(&b.service_name));
+
+        SupervisionStatus {
+            running: self.handle.lock().unwrap().is_some()
+                && !self.stop_flag.load(Ordering::SeqCst),
+            interval_secs,
+            max_retries,
+            services,
+        }
+    }
+
+    /// 监控主循环
+    fn run(&self, event_callback: SupervisionEventCallback) {
+        log::info!("服务监控已启动");
+        while !self.stop_flag.load(Ordering::SeqCst) {
+            if let Err(e) = self.check_services(&event_callback) {
+                log::error!("服务监控检查失败: {}", e);
+            }
+
+            let (interval_secs, _) = Self::get_settings();
+            let deadline = Instant::now() + Duration::from_secs(interval_secs.max(1));
+            while Instant::now() < deadline {
+                if self.stop_flag.load(Ordering::SeqCst) {
+                    return;
+                }
+                thread::sleep(SHUTDOWN_POLL);
+            }
+        }
+    }
+
+    /// 检查所有开启自动重启的服务
+    fn check_services(&self, event_callback: &SupervisionEventCallback) -> Result<()> {
+        let (_, max_retries) = Self::get_settings();
+        let targets = Self::collect_supervised_services()?;
+
+        // 移除已不再受监控的服务
+        {
+            let ids: HashSet<&str> = targets.iter().map(|(_, sd)| sd.id.as_str()).collect();
+            self.state
+                .lock()
+                .unwrap()
+                .services
+                .retain(|id, _| ids.contains(id.as_str()));
+        }
+
+        for (environment_id, service_data) in targets {
+            if self.stop_flag.load(Ordering::SeqCst) {
+                break;
+            }
+            let running = match ServiceManager::global().is_service_running(&environment_id, &service_data) {
+                Ok(running) => running,
+                Err(e) => {
+                    log::warn!("检查服务 {} 状态失败: {}", service_data.name, e);
+                    continue;
+                }
+            };
+            self.update_service(
+                &environment_id,
+                &service_data,
+                running,
+                max_retries,
+                event_callback,
+            );
+        }
+        Ok(())
+    }
+
+    /// 根据本次检查结果更新服务状态，必要时执行重启
+    fn update_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        running: bool,
+        max_retries: u32,
+        event_callback: &SupervisionEventCallback,
+    ) {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        let intentional = state.intentional_stops.contains(&service_data.id);
+        let first_seen = !state.services.contains_key(&service_data.id);
+        let service = state
+            .services
+            .entry(service_data.id.clone())
+            .or_insert_with(|| SupervisedService {
+                environment_id: environment_id.to_string(),
+                service_id: service_data.id.clone(),
+                service_name: service_data.name.clone(),
+                service_type: service_data.service_type.clone(),
+                running,
+                restart_attempts: 0,
+                gave_up: false,
+                last_crash_at: None,
+                last_restart_at: None,
+                running_since: running.then_some(now),
+                next_retry_at: None,
+            });
+
+        if running {
+            service.next_retry_at = None;
+            let since = *service.running_since.get_or_insert(now);
+            if now.duration_since(since) >= STABLE_RESET {
+                service.restart_attempts = 0;
+                service.gave_up = false;
+            }
+            service.running = true;
+            return;
+        }
+
+        let was_running = service.running;
+        service.running = false;
+        service.running_since = None;
+        if intentional {
+            service.next_retry_at = None;
+            return;
+        }
+
+        // 由运行转为停止，或首次检查时已停止且非主动停止，视为崩溃
+        if was_running || first_seen {
+            service.last_crash_at = Some(Utc::now().to_rfc3339());
+            service.gave_up = service.restart_attempts >= max_retries;
+            if !service.gave_up {
+                service.next_retry_at = Some(now + Self::backoff(service.restart_attempts));
+            }
+            log::warn!("检测到服务 {} 意外停止", service.service_name);
+            event_callback(
+                SERVICE_CRASHED_EVENT,
+                serde_json::json!({
+                    "environmentId": environment_id,
+                    "serviceId": service.service_id,
+                    "serviceName": service.service_name,
+                    "serviceType": service.service_type,
+                    "restartAttempts": service.restart_attempts,
+                    "willRestart": !service.gave_up,
+                }),
+            );
+            return;
+        }
+
+        match service.next_retry_at {
+            Some(at) if now >= at => {}
+            _ => return,
+        }
+
+        service.restart_attempts += 1;
+        service.last_restart_at = Some(Utc::now().to_rfc3339());
+        let attempt = service.restart_attempts;
+        let service_name = service.service_name.clone();
+        drop(state);
+
+        // 重启期间不持有锁，避免阻塞 stop_service 中的标记
+        log::info!("正在自动重启服务 {}（第 {} 次）", service_name, attempt);
+        let (success, message) = match ServiceManager::global().start_service(environment_id, service_data) {
+            Ok(res) => (res.success, res.message),
+            Err(e) => (false, e.to_string()),
+        };
+
+        let mut state = self.state.lock().unwrap();
+        if let Some(service) = state.services.get_mut(&service_data.id) {
+            if success {
+                service.next_retry_at = None;
+            } else if attempt >= max_retries {
+                service.next_retry_at = None;
+                service.gave_up = true;
+            } else {
+                service.next_retry_at = Some(Instant::now() + Self::backoff(attempt));
+            }
+            // 启动成功但下次检查仍未运行时，按崩溃处理
+            service.running = success;
+            if success {
+                service.running_since = Some(Instant::now());
+            }
+        }
+        drop(state);
+
+        event_callback(
+            SERVICE_RESTARTED_EVENT,
+            serde_json::json!({
+                "environmentId": environment_id,
+                "serviceId": service_data.id,
+                "serviceName": service_name,
+                "serviceType": service_data.service_type,
+                "attempt": attempt,
+                "success": success,
+                "message": message,
+            }),
+        );
+    }
+
+    /// 第 n 次重启前的退避时间
+    fn backoff(attempts: u32) -> Duration {
+        BACKOFF_BASE
+            .saturating_mul(2u32.saturating_pow(attempts))
+            .min(BACKOFF_MAX)
+    }
+
+    /// 读取监控间隔与最大重试次数
+    fn get_settings() -> (u64, u32) {
+        let app_config = AppConfigManager::global().lock().unwrap().get_app_config();
+        (
+            app_config.service_supervision_interval_secs,
+            app_config.service_auto_restart_max_retries,
+        )
+    }
+
+    /// 收集已激活环境中开启自动重启且处于激活状态的服务
+    fn collect_supervised_services() -> Result<Vec<(String, ServiceData)>> {
+        let environments = EnvironmentManager::global()
+            .lock()
+            .unwrap()
+            .get_all_environments()?;
+
+        let mut targets = Vec::new();
+        for environment in environments
+            .into_iter()
+            .filter(|e| matches!(e.status, EnvironmentStatus::Active))
+        {
+            let service_datas = EnvServDataManager::global()
+                .lock()
+                .unwrap()
+                .get_environment_all_service_datas(&environment.id)?;
+            targets.extend(
+                service_datas
+                    .into_iter()
+                    .filter(|sd| matches!(sd.status, ServiceDataStatus::Active))
+                    .filter(|sd| ServiceManager::is_daemon_service(&sd.service_type))
+                    .filter(Self::is_auto_restart_enabled)
+                    .map(|sd| (environment.id.clone(), sd)),
+            );
+        }
+        Ok(targets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_seen_stopped_service_schedules_restart() {
+        let supervisor = ServiceSupervisor::new();
+        let service_data = ServiceData {
+            id: "svc-1".to_string(),
+            name: "redis".to_string(),
+            service_type: ServiceType::Redis,
+            version: "7.2.0".to_string(),
+            status: ServiceDataStatus::Active,
+            sort: None,
+            metadata: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        let callback: SupervisionEventCallback = Arc::new(move |event, data| {
+            recorded.lock().unwrap().push((event.to_string(), data));
+        });
+
+        supervisor.update_service("env-1", &service_data, false, 3, &callback);
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, SERVICE_CRASHED_EVENT);
+        assert_eq!(events[0].1["willRestart"], true);
+
+        let state = supervisor.state.lock().unwrap();
+        let service = &state.services["svc-1"];
+        assert!(service.last_crash_at.is_some());
+        assert!(service.next_retry_at.is_some());
+        assert!(!service.gave_up);
+    }
+}
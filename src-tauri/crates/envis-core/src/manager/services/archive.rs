@@ -0,0 +1,23 @@
+use crate::utils::create_command;
+use anyhow::{anyhow, Result};
+use std::path::Path;
+
+/// 使用系统 tar 解压压缩包，并去掉包内唯一的顶层目录（如 kafka_2.13-3.7.0/）
+///
+/// tar 按内容识别 gz / xz 等压缩格式，Windows 10+ 自带的 bsdtar 同样支持
+pub fn extract_tar_strip_root(archive_path: &Path, target_dir: &Path) -> Result<()> {
+    let output = create_command("tar")
+        .arg("-xf")
+        .arg(archive_path)
+        .arg("-C")
+        .arg(target_dir)
+        .arg("--strip-components=1")
+        .output()?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "解压失败: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,457 @@
+use crate::manager::env_serv_data_manager::{EnvServDataManager, ServiceDataResult};
+use crate::manager::services::traits::{status_result, ServiceLifecycle};
+use crate::types::{ServiceData, ServiceStatus};
+use crate::utils::create_command;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::{Arc, OnceLock};
+
+/// compose 文件路径的 metadata 键
+pub const COMPOSE_FILE_KEY: &str = "COMPOSE_FILE";
+/// compose 项目名的 metadata 键，创建服务时默认为环境名称
+pub const COMPOSE_PROJECT_NAME_KEY: &str = "COMPOSE_PROJECT_NAME";
+/// 按顺序探测的容器运行时，只使用系统已安装的，不提供下载
+const RUNTIMES: [&str; 2] = ["docker", "podman"];
+
+/// 检测到的容器运行时
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerRuntime {
+    /// docker 或 podman
+    pub binary: String,
+    pub compose_version: String,
+}
+
+/// compose ps 输出中单个容器的状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComposeContainer {
+    pub name: String,
+    pub service: String,
+    /// running、exited、restarting 等
+    pub state: String,
+    /// 可读的状态描述，如 "Up 2 minutes"
+    pub status: String,
+    pub health: Option<String>,
+    pub exit_code: Option<i64>,
+}
+
+static GLOBAL_COMPOSE_SERVICE: OnceLock<Arc<ComposeService>> = OnceLock::new();
+
+/// Docker Compose 服务：通过系统中的 docker / podman 管理环境内的 compose 项目，
+/// 环境激活时启动、停用时停止
+pub struct ComposeService {}
+
+impl ComposeService {
+    pub fn global() -> Arc<ComposeService> {
+        GLOBAL_COMPOSE_SERVICE
+            .get_or_init(|| Arc::new(ComposeService::new()))
+            .clone()
+    }
+
+    fn new() -> Self {
+        Self {}
+    }
+
+    /// 依次探测 docker、podman 的 compose 子命令
+    pub fn detect_runtime(&self) -> Result<ContainerRuntime> {
+        let mut compose_errors = Vec::new();
+        for binary in RUNTIMES {
+            // 可执行文件不存在时继续探测下一个
+            let Ok(output) = create_command(binary)
+                .args(["compose", "version", "--short"])
+                .output()
+            else {
+                continue;
+            };
+            if output.status.success() {
+                return Ok(ContainerRuntime {
+                    binary: binary.to_string(),
+                    compose_version: String::from_utf8_lossy(&output.stdout).trim().to_string(),
+                });
+            }
+            compose_errors.push(format!(
+                "{} compose 不可用: {}",
+                binary,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        if compose_errors.is_empty() {
+            Err(anyhow!(
+                "未检测到 docker 或 podman，请先安装 Docker Desktop 或 Podman"
+            ))
+        } else {
+            Err(anyhow!("{}", compose_errors.join("; ")))
+        }
+    }
+
+    /// 检查容器运行时的守护进程是否可用
+    fn ensure_daemon_running(&self, runtime: &ContainerRuntime) -> Result<()> {
+        let output = create_command(&runtime.binary)
+            .arg("info")
+            .output()
+            .map_err(|e| anyhow!("执行 {} info 失败: {}", runtime.binary, e))?;
+        if output.status.success() {
+            return Ok(());
+        }
+
+        let detail = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if runtime.binary == "podman" {
+            Err(anyhow!(
+                "Podman 服务未运行，请先执行 podman machine start: {}",
+                detail
+            ))
+        } else {
+            Err(anyhow!(
+                "Docker 守护进程未运行，请先启动 Docker Desktop 或 dockerd: {}",
+                detail
+            ))
+        }
+    }
+
+    /// 读取 metadata 中的 compose 文件路径
+    fn get_compose_file(&self, service_data: &ServiceData) -> Result<PathBuf> {
+        let compose_file = service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get(COMPOSE_FILE_KEY))
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from)
+            .ok_or_else(|| anyhow!("未设置 compose 文件，请先指定 {}", COMPOSE_FILE_KEY))?;
+        if !compose_file.is_file() {
+            return Err(anyhow!("compose 文件不存在: {}", compose_file.display()));
+        }
+        Ok(compose_file)
+    }
+
+    /// 读取 metadata 中的项目名，未设置时使用环境 ID
+    fn get_project_name(&self, environment_id: &str, service_data: &ServiceData) -> String {
+        service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get(COMPOSE_PROJECT_NAME_KEY))
+            .and_then(|v| v.as_str())
+            .and_then(Self::normalize_project_name)
+            .or_else(|| Self::normalize_project_name(environment_id))
+            .unwrap_or_else(|| "envis".to_string())
+    }
+
+    /// compose 项目名只能包含小写字母、数字、连字符和下划线，且以字母或数字开头；
+    /// 不含任何可用字符（如纯中文名称）时返回 None
+    pub fn normalize_project_name(name: &str) -> Option<String> {
+        let normalized: String = name
+            .trim()
+            .to_lowercase()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let normalized = normalized.trim_matches(['-', '_']);
+        if normalized.is_empty() {
+            None
+        } else {
+            Some(normalized.to_string())
+        }
+    }
+
+    /// 设置 compose 文件和项目名
+    pub fn set_compose_file(
+        &self,
+        environment_id: &str,
+        service_data: &mut ServiceData,
+        compose_file: &str,
+        project_name: Option<&str>,
+    ) -> Result<ServiceDataResult> {
+        let compose_file = PathBuf::from(compose_file.trim());
+        if !compose_file.is_file() {
+            return Err(anyhow!("compose 文件不存在: {}", compose_file.display()));
+        }
+        let compose_file = compose_file.to_string_lossy().to_string();
+
+        let env_serv_data_manager = EnvServDataManager::global();
+        let env_serv_data_manager = env_serv_data_manager.lock().unwrap();
+        env_serv_data_manager.set_metadata(
+            environment_id,
+            service_data,
+            COMPOSE_FILE_KEY,
+            serde_json::Value::String(compose_file.clone()),
+        )?;
+        if let Some(project_name) = project_name.filter(|s| !s.trim().is_empty()) {
+            let normalized = Self::normalize_project_name(project_name)
+                .ok_or_else(|| anyhow!("无效的 compose 项目名: {}", project_name))?;
+            env_serv_data_manager.set_metadata(
+                environment_id,
+                service_data,
+                COMPOSE_PROJECT_NAME_KEY,
+                serde_json::Value::String(normalized),
+            )?;
+        }
+        drop(env_serv_data_manager);
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "compose 文件设置成功".to_string(),
+            data: Some(serde_json::json!({
+                "composeFile": compose_file,
+                "projectName": self.get_project_name(environment_id, service_data),
+            })),
+        })
+    }
+
+    /// 生成 `<runtime> compose -f <file> -p <project>` 命令
+    fn compose_command(
+        &self,
+        runtime: &ContainerRuntime,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<Command> {
+        let compose_file = self.get_compose_file(service_data)?;
+        let mut command = create_command(&runtime.binary);
+        command
+            .arg("compose")
+            .arg("-f")
+            .arg(&compose_file)
+            .arg("-p")
+            .arg(self.get_project_name(environment_id, service_data));
+        if let Some(dir) = compose_file.parent() {
+            command.current_dir(dir);
+        }
+        Ok(command)
+    }
+
+    /// 检测运行时与守护进程后执行 compose 子命令
+    fn run_compose(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        args: &[&str],
+    ) -> Result<(ContainerRuntime, Output)> {
+        let runtime = self.detect_runtime()?;
+        self.ensure_daemon_running(&runtime)?;
+        let output = self
+            .compose_command(&runtime, environment_id, service_data)?
+            .args(args)
+            .output()
+            .map_err(|e| anyhow!("执行 {} compose 失败: {}", runtime.binary, e))?;
+        Ok((runtime, output))
+    }
+
+    fn command_result(
+        runtime: &ContainerRuntime,
+        args: &[&str],
+        output: &Output,
+    ) -> Option<ServiceDataResult> {
+        if output.status.success() {
+            return None;
+        }
+        Some(ServiceDataResult {
+            success: false,
+            message: format!(
+                "{} compose {} 失败: {}",
+                runtime.binary,
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            data: None,
+        })
+    }
+
+    /// 启动 compose 项目（up -d）
+    pub fn start_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let args = ["up", "-d"];
+        let (runtime, output) = self.run_compose(environment_id, service_data, &args)?;
+        if let Some(failed) = Self::command_result(&runtime, &args, &output) {
+            return Ok(failed);
+        }
+
+        let project_name = self.get_project_name(environment_id, service_data);
+        log::info!("Compose 项目 {} 已启动", project_name);
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("Compose 项目 {} 已启动", project_name),
+            data: Some(serde_json::json!({
+                "projectName": project_name,
+                "runtime": runtime,
+            })),
+        })
+    }
+
+    /// 停止并移除 compose 项目的容器（down）
+    pub fn stop_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let args = ["down"];
+        let (runtime, output) = self.run_compose(environment_id, service_data, &args)?;
+        if let Some(failed) = Self::command_result(&runtime, &args, &output) {
+            return Ok(failed);
+        }
+
+        let project_name = self.get_project_name(environment_id, service_data);
+        log::info!("Compose 项目 {} 已停止", project_name);
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("Compose 项目 {} 已停止", project_name),
+            data: None,
+        })
+    }
+
+    /// 重启 compose 项目（先 down 再 up，使 compose 文件的修改生效）
+    pub fn restart_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let stopped = self.stop_service(environment_id, service_data)?;
+        if !stopped.success {
+            return Ok(stopped);
+        }
+        self.start_service(environment_id, service_data)
+    }
+
+    /// 获取 compose 项目状态，data 中的 containers 为各容器状态：
+    /// 全部运行为 Running，部分运行为 Degraded，没有运行中的容器为 Stopped
+    pub fn get_service_status(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let args = ["ps", "--all", "--format", "json"];
+        let (runtime, output) = self.run_compose(environment_id, service_data, &args)?;
+        if let Some(failed) = Self::command_result(&runtime, &args, &output) {
+            return Ok(failed);
+        }
+
+        let containers = Self::parse_ps_output(&String::from_utf8_lossy(&output.stdout))?;
+        let running = containers.iter().filter(|c| c.state == "running").count();
+        let status = if running == 0 {
+            ServiceStatus::Stopped
+        } else if running == containers.len() {
+            ServiceStatus::Running
+        } else {
+            ServiceStatus::Degraded
+        };
+
+        let mut result = status_result(status);
+        if let Some(data) = result.data.as_mut().and_then(|d| d.as_object_mut()) {
+            data.insert(
+                "projectName".to_string(),
+                serde_json::json!(self.get_project_name(environment_id, service_data)),
+            );
+            data.insert("containers".to_string(), serde_json::json!(containers));
+        }
+        Ok(result)
+    }
+
+    /// 解析 compose ps --format json 的输出：新版本每行一个 JSON 对象，旧版本为 JSON 数组
+    fn parse_ps_output(stdout: &str) -> Result<Vec<ComposeContainer>> {
+        let stdout = stdout.trim();
+        let values: Vec<serde_json::Value> = if stdout.is_empty() {
+            Vec::new()
+        } else if stdout.starts_with('[') {
+            serde_json::from_str(stdout)?
+        } else {
+            stdout
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str)
+                .collect::<std::result::Result<_, _>>()?
+        };
+
+        let field = |value: &serde_json::Value, key: &str| {
+            value
+                .get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        Ok(values
+            .iter()
+            .map(|value| ComposeContainer {
+                name: field(value, "Name"),
+                service: field(value, "Service"),
+                state: field(value, "State").to_lowercase(),
+                status: field(value, "Status"),
+                health: Some(field(value, "Health")).filter(|h| !h.is_empty()),
+                exit_code: value.get("ExitCode").and_then(|v| v.as_i64()),
+            })
+            .collect())
+    }
+}
+
+impl ServiceLifecycle for ComposeService {
+    /// 激活时启动 compose 项目
+    fn active(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        _password: Option<String>,
+    ) -> Result<()> {
+        let result = ComposeService::start_service(self, environment_id, service_data)?;
+        if !result.success {
+            return Err(anyhow!(result.message));
+        }
+        Ok(())
+    }
+
+    /// 停用时停止 compose 项目
+    fn deactive(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        _password: Option<String>,
+    ) -> Result<()> {
+        let result = ComposeService::stop_service(self, environment_id, service_data)?;
+        if !result.success {
+            return Err(anyhow!(result.message));
+        }
+        Ok(())
+    }
+
+    fn start_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        ComposeService::start_service(self, environment_id, service_data)
+    }
+
+    fn stop_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        ComposeService::stop_service(self, environment_id, service_data)
+    }
+
+    fn restart_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        ComposeService::restart_service(self, environment_id, service_data)
+    }
+
+    fn get_service_status(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        ComposeService::get_service_status(self, environment_id, service_data)
+    }
+}
@@ -1,14 +1,98 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
 use serde_json;
-use std::sync::{Arc, OnceLock};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Stdio};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
 
+use crate::manager::env_serv_data_manager::{EnvServDataManager, ServiceDataResult};
 use crate::manager::services::traits::ServiceLifecycle;
 use crate::manager::shell_manamger::ShellManager;
-use crate::types::ServiceData;
+use crate::types::{ServiceData, ServiceDataStatus};
+use crate::utils::{create_command, kill_process_tree};
 
 static GLOBAL_CUSTOM_SERVICE: OnceLock<Arc<CustomService>> = OnceLock::new();
 
-pub struct CustomService;
+/// 自定义命令在 metadata 中的键
+const COMMANDS_METADATA_KEY: &str = "commands";
+/// 已注册本地工具目录在 metadata 中的键
+const BINARIES_METADATA_KEY: &str = "binaries";
+/// 轮询子进程退出状态的间隔
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 自定义服务中的可执行命令（metadata.commands 的条目）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomCommand {
+    pub name: String,
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// 工作目录，为空时继承 Envis 进程的工作目录
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// 额外环境变量，优先级高于自定义服务的 envVars
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+/// 自定义命令的执行结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomCommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// 被信号终止或超时被杀时为 -1
+    pub exit_code: i32,
+    pub timed_out: bool,
+    pub duration_ms: u64,
+}
+
+/// 逐行输出回调，参数为 (stream, line)，stream 为 "stdout" 或 "stderr"
+pub type CommandOutputCallback = Arc<dyn Fn(&str, &str) + Send + Sync>;
+
+/// 注册到自定义服务中的本地工具目录（metadata.binaries 的条目）
+///
+/// 同名工具可注册多个版本，同一时间只有一个版本处于选中状态并加入 PATH。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomBinary {
+    pub name: String,
+    pub version: String,
+    pub folder: String,
+    /// 加入 PATH 的子目录（如 bin），为空时使用 folder 本身
+    #[serde(default)]
+    pub bin_dir: Option<String>,
+    #[serde(default)]
+    pub active: bool,
+}
+
+impl CustomBinary {
+    /// 实际加入 PATH 的目录
+    pub fn path_entry(&self) -> String {
+        match self
+            .bin_dir
+            .as_deref()
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+        {
+            Some(bin_dir) => Path::new(&self.folder)
+                .join(bin_dir)
+                .to_string_lossy()
+                .to_string(),
+            None => self.folder.clone(),
+        }
+    }
+}
+
+pub struct CustomService {
+    /// 正在执行的命令，key 为 run id
+    running: Mutex<HashMap<String, Arc<Mutex<Child>>>>,
+}
 
 impl CustomService {
     pub fn global() -> Arc<Self> {
@@ -18,7 +102,506 @@ impl CustomService {
     }
 
     pub fn new() -> Self {
-        Self
+        Self {
+            running: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 读取服务 metadata 中的命令列表
+    pub fn get_commands(service_data: &ServiceData) -> Result<Vec<CustomCommand>> {
+        match service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get(COMMANDS_METADATA_KEY))
+        {
+            Some(value) if !value.is_null() => {
+                serde_json::from_value(value.clone()).context("解析自定义命令列表失败")
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// 获取命令列表
+    pub fn list_commands(&self, service_data: &ServiceData) -> Result<ServiceDataResult> {
+        let commands = Self::get_commands(service_data)?;
+        Ok(ServiceDataResult {
+            success: true,
+            message: "获取自定义命令列表成功".to_string(),
+            data: Some(serde_json::json!({ "commands": commands })),
+        })
+    }
+
+    /// 新增命令，名称不可重复
+    pub fn add_command(
+        &self,
+        environment_id: &str,
+        service_data: &mut ServiceData,
+        command: CustomCommand,
+    ) -> Result<ServiceDataResult> {
+        if let Err(message) = validate_command(&command) {
+            return Ok(error_result(message));
+        }
+        let mut commands = Self::get_commands(service_data)?;
+        if commands.iter().any(|c| c.name == command.name) {
+            return Ok(error_result(format!("命令 {} 已存在", command.name)));
+        }
+        commands.push(command);
+        self.save_commands(environment_id, service_data, &commands)
+    }
+
+    /// 按名称更新命令，允许同时修改名称
+    pub fn update_command(
+        &self,
+        environment_id: &str,
+        service_data: &mut ServiceData,
+        name: &str,
+        command: CustomCommand,
+    ) -> Result<ServiceDataResult> {
+        if let Err(message) = validate_command(&command) {
+            return Ok(error_result(message));
+        }
+        let mut commands = Self::get_commands(service_data)?;
+        if command.name != name && commands.iter().any(|c| c.name == command.name) {
+            return Ok(error_result(format!("命令 {} 已存在", command.name)));
+        }
+        let Some(target) = commands.iter_mut().find(|c| c.name == name) else {
+            return Ok(error_result(format!("命令 {} 不存在", name)));
+        };
+        *target = command;
+        self.save_commands(environment_id, service_data, &commands)
+    }
+
+    /// 按名称删除命令
+    pub fn remove_command(
+        &self,
+        environment_id: &str,
+        service_data: &mut ServiceData,
+        name: &str,
+    ) -> Result<ServiceDataResult> {
+        let mut commands = Self::get_commands(service_data)?;
+        let before = commands.len();
+        commands.retain(|c| c.name != name);
+        if commands.len() == before {
+            return Ok(error_result(format!("命令 {} 不存在", name)));
+        }
+        self.save_commands(environment_id, service_data, &commands)
+    }
+
+    fn save_commands(
+        &self,
+        environment_id: &str,
+        service_data: &mut ServiceData,
+        commands: &[CustomCommand],
+    ) -> Result<ServiceDataResult> {
+        let value = serde_json::to_value(commands)?;
+        let result = EnvServDataManager::global()
+            .lock()
+            .map_err(|e| anyhow!("获取服务数据管理器锁失败: {}", e))?
+            .set_metadata(environment_id, service_data, COMMANDS_METADATA_KEY, value)?;
+        if !result.success {
+            return Ok(result);
+        }
+        Ok(ServiceDataResult {
+            success: true,
+            message: "自定义命令已保存".to_string(),
+            data: Some(serde_json::json!({ "commands": commands })),
+        })
+    }
+
+    /// 读取服务 metadata 中已注册的工具目录
+    pub fn get_binaries(service_data: &ServiceData) -> Result<Vec<CustomBinary>> {
+        match service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get(BINARIES_METADATA_KEY))
+        {
+            Some(value) if !value.is_null() => {
+                serde_json::from_value(value.clone()).context("解析已注册工具目录失败")
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// 注册本地工具目录为一个版本
+    ///
+    /// 同名工具尚无选中版本时自动选中，服务已激活则立即加入 PATH。
+    pub fn register_binary(
+        &self,
+        environment_id: &str,
+        service_data: &mut ServiceData,
+        name: &str,
+        version: &str,
+        folder: &str,
+        bin_dir: Option<String>,
+    ) -> Result<ServiceDataResult> {
+        if name.trim().is_empty() || version.trim().is_empty() {
+            return Ok(error_result("名称和版本不能为空".to_string()));
+        }
+        if !Path::new(folder).is_dir() {
+            return Ok(error_result(format!("目录不存在: {}", folder)));
+        }
+
+        let mut binaries = Self::get_binaries(service_data)?;
+        if binaries
+            .iter()
+            .any(|b| b.name == name && b.version == version)
+        {
+            return Ok(error_result(format!("{} {} 已注册", name, version)));
+        }
+
+        let mut binary = CustomBinary {
+            name: name.to_string(),
+            version: version.to_string(),
+            folder: folder.to_string(),
+            bin_dir,
+            active: false,
+        };
+        let path_entry = binary.path_entry();
+        if !Path::new(&path_entry).is_dir() {
+            return Ok(error_result(format!("目录不存在: {}", path_entry)));
+        }
+
+        binary.active = !binaries.iter().any(|b| b.name == name && b.active);
+        if binary.active && is_service_active(service_data) {
+            sync_binary_paths(None, Some(&path_entry))?;
+        }
+        binaries.push(binary);
+        self.save_binaries(environment_id, service_data, &binaries)
+    }
+
+    /// 取消注册，选中的版本会同时从 PATH 中移除
+    pub fn unregister_binary(
+        &self,
+        environment_id: &str,
+        service_data: &mut ServiceData,
+        name: &str,
+        version: &str,
+    ) -> Result<ServiceDataResult> {
+        let mut binaries = Self::get_binaries(service_data)?;
+        let Some(index) = binaries
+            .iter()
+            .position(|b| b.name == name && b.version == version)
+        else {
+            return Ok(error_result(format!("{} {} 未注册", name, version)));
+        };
+
+        let removed = binaries.remove(index);
+        if removed.active && is_service_active(service_data) {
+            sync_binary_paths(Some(&removed.path_entry()), None)?;
+        }
+        self.save_binaries(environment_id, service_data, &binaries)
+    }
+
+    /// 切换同名工具的选中版本：移除旧版本路径，加入新版本路径
+    pub fn switch_binary(
+        &self,
+        environment_id: &str,
+        service_data: &mut ServiceData,
+        name: &str,
+        version: &str,
+    ) -> Result<ServiceDataResult> {
+        let mut binaries = Self::get_binaries(service_data)?;
+        let Some(target) = binaries
+            .iter()
+            .find(|b| b.name == name && b.version == version)
+        else {
+            return Ok(error_result(format!("{} {} 未注册", name, version)));
+        };
+        let new_path = target.path_entry();
+        let old_path = binaries
+            .iter()
+            .find(|b| b.name == name && b.active && b.version != version)
+            .map(|b| b.path_entry());
+
+        if is_service_active(service_data) {
+            sync_binary_paths(old_path.as_deref(), Some(&new_path))?;
+        }
+        for binary in binaries.iter_mut().filter(|b| b.name == name) {
+            binary.active = binary.version == version;
+        }
+        self.save_binaries(environment_id, service_data, &binaries)
+    }
+
+    fn save_binaries(
+        &self,
+        environment_id: &str,
+        service_data: &mut ServiceData,
+        binaries: &[CustomBinary],
+    ) -> Result<ServiceDataResult> {
+        let value = serde_json::to_value(binaries)?;
+        let result = EnvServDataManager::global()
+            .lock()
+            .map_err(|e| anyhow!("获取服务数据管理器锁失败: {}", e))?
+            .set_metadata(environment_id, service_data, BINARIES_METADATA_KEY, value)?;
+        if !result.success {
+            return Ok(result);
+        }
+        Ok(ServiceDataResult {
+            success: true,
+            message: "已注册工具目录已保存".to_string(),
+            data: Some(serde_json::json!({ "binaries": binaries })),
+        })
+    }
+
+    /// 生成一次命令执行的 run id
+    pub fn generate_run_id() -> String {
+        uuid::Uuid::new_v4().to_string()
+    }
+
+    /// 执行命令并等待退出
+    ///
+    /// 环境变量按 Envis 进程环境 → 服务 envVars / paths → 命令 env 的顺序合并。
+    /// 超时后结束整个进程树；提供 `on_output` 时逐行回调输出，结果中不再累积 stdout/stderr。
+    pub fn execute_command(
+        &self,
+        run_id: &str,
+        service_data: &ServiceData,
+        name: &str,
+        timeout: Option<Duration>,
+        on_output: Option<CommandOutputCallback>,
+    ) -> Result<CustomCommandOutput> {
+        let command = Self::get_commands(service_data)?
+            .into_iter()
+            .find(|c| c.name == name)
+            .ok_or_else(|| anyhow!("命令 {} 不存在", name))?;
+
+        let mut cmd = if cfg!(target_os = "windows") {
+            // 通过 cmd 执行，以便解析 npm.cmd 等批处理脚本
+            let mut cmd = create_command("cmd");
+            cmd.arg("/C").arg(&command.program);
+            cmd
+        } else {
+            create_command(&command.program)
+        };
+        cmd.args(&command.args)
+            .envs(build_command_env(service_data, &command))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        if let Some(cwd) = command.cwd.as_deref().filter(|c| !c.trim().is_empty()) {
+            if !Path::new(cwd).is_dir() {
+                return Err(anyhow!("工作目录不存在: {}", cwd));
+            }
+            cmd.current_dir(cwd);
+        }
+
+        // 独立进程组，超时或停止时可以连同子进程一起结束
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+
+        log::info!(
+            "执行自定义命令 {} ({}): {} {}",
+            name,
+            run_id,
+            command.program,
+            command.args.join(" ")
+        );
+
+        let started = Instant::now();
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("启动命令 {} 失败", command.program))?;
+        let stdout_reader = child
+            .stdout
+            .take()
+            .map(|out| spawn_output_reader(out, "stdout", on_output.clone()));
+        let stderr_reader = child
+            .stderr
+            .take()
+            .map(|err| spawn_output_reader(err, "stderr", on_output.clone()));
+
+        let child = Arc::new(Mutex::new(child));
+        self.running
+            .lock()
+            .map_err(|e| anyhow!("获取运行列表锁失败: {}", e))?
+            .insert(run_id.to_string(), child.clone());
+
+        let wait_result = wait_with_timeout(&child, timeout);
+
+        if let Ok(mut running) = self.running.lock() {
+            running.remove(run_id);
+        }
+        let (status, timed_out) = wait_result?;
+
+        let stdout = stdout_reader
+            .and_then(|h| h.join().ok())
+            .unwrap_or_default();
+        let stderr = stderr_reader
+            .and_then(|h| h.join().ok())
+            .unwrap_or_default();
+        let exit_code = if timed_out {
+            -1
+        } else {
+            status.code().unwrap_or(-1)
+        };
+
+        log::info!(
+            "自定义命令 {} ({}) 结束: 退出码 {}{}",
+            name,
+            run_id,
+            exit_code,
+            if timed_out { "，已超时" } else { "" }
+        );
+
+        Ok(CustomCommandOutput {
+            stdout,
+            stderr,
+            exit_code,
+            timed_out,
+            duration_ms: started.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// 结束正在执行的命令
+    pub fn stop_command(&self, run_id: &str) -> Result<ServiceDataResult> {
+        let child = self
+            .running
+            .lock()
+            .map_err(|e| anyhow!("获取运行列表锁失败: {}", e))?
+            .get(run_id)
+            .cloned();
+        let Some(child) = child else {
+            return Ok(error_result(format!("命令未在运行: {}", run_id)));
+        };
+
+        let mut child = child
+            .lock()
+            .map_err(|e| anyhow!("获取子进程锁失败: {}", e))?;
+        kill_process_tree(&mut child);
+        Ok(ServiceDataResult {
+            success: true,
+            message: "已停止命令".to_string(),
+            data: Some(serde_json::json!({ "runId": run_id })),
+        })
+    }
+}
+
+fn error_result(message: String) -> ServiceDataResult {
+    ServiceDataResult {
+        success: false,
+        message,
+        data: None,
+    }
+}
+
+fn is_service_active(service_data: &ServiceData) -> bool {
+    matches!(service_data.status, ServiceDataStatus::Active)
+}
+
+/// 将工具目录的变化同步到 shell 配置
+fn sync_binary_paths(old_path: Option<&str>, new_path: Option<&str>) -> Result<()> {
+    let shell_manager = ShellManager::global();
+    let shell_manager = shell_manager
+        .lock()
+        .map_err(|e| anyhow!("获取 Shell 管理器锁失败: {}", e))?;
+    let mut update = shell_manager.begin_update();
+    if let Some(path) = old_path {
+        update.delete_path(path);
+    }
+    if let Some(path) = new_path {
+        update.add_path(path);
+    }
+    update.commit().context("更新工具目录 PATH 失败")
+}
+
+fn validate_command(command: &CustomCommand) -> std::result::Result<(), String> {
+    if command.name.trim().is_empty() {
+        return Err("命令名称不能为空".to_string());
+    }
+    if command.program.trim().is_empty() {
+        return Err("执行程序不能为空".to_string());
+    }
+    Ok(())
+}
+
+/// 合并自定义服务的 envVars、paths 与命令自身的 env
+fn build_command_env(
+    service_data: &ServiceData,
+    command: &CustomCommand,
+) -> HashMap<String, String> {
+    let mut env = HashMap::new();
+    let metadata = service_data.metadata.as_ref();
+
+    if let Some(serde_json::Value::Object(vars)) = metadata.and_then(|m| m.get("envVars")) {
+        for (key, value) in vars {
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                _ => value.to_string().trim_matches('"').to_string(),
+            };
+            env.insert(key.clone(), value);
+        }
+    }
+
+    if let Some(serde_json::Value::Array(paths)) = metadata.and_then(|m| m.get("paths")) {
+        let mut entries: Vec<PathBuf> = paths
+            .iter()
+            .filter_map(|p| p.as_str())
+            .map(PathBuf::from)
+            .collect();
+        if let Some(current) = std::env::var_os("PATH") {
+            entries.extend(std::env::split_paths(&current));
+        }
+        if let Ok(joined) = std::env::join_paths(entries) {
+            env.insert("PATH".to_string(), joined.to_string_lossy().to_string());
+        }
+    }
+
+    env.extend(command.env.clone());
+    env
+}
+
+/// 在后台线程中逐行读取输出；有回调时转发，否则累积后返回
+fn spawn_output_reader<R: Read + Send + 'static>(
+    source: R,
+    stream: &'static str,
+    on_output: Option<CommandOutputCallback>,
+) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(source);
+        let mut collected = String::new();
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let line = String::from_utf8_lossy(&buf);
+                    match &on_output {
+                        Some(callback) => callback(stream, line.trim_end_matches(['\r', '\n'])),
+                        None => collected.push_str(&line),
+                    }
+                }
+            }
+        }
+        collected
+    })
+}
+
+/// 等待子进程退出，超时则结束进程树；返回 (退出状态, 是否超时)
+fn wait_with_timeout(
+    child: &Arc<Mutex<Child>>,
+    timeout: Option<Duration>,
+) -> Result<(std::process::ExitStatus, bool)> {
+    let started = Instant::now();
+    loop {
+        {
+            let mut child = child
+                .lock()
+                .map_err(|e| anyhow!("获取子进程锁失败: {}", e))?;
+            if let Some(status) = child.try_wait().context("等待命令退出失败")? {
+                return Ok((status, false));
+            }
+            if timeout.is_some_and(|t| started.elapsed() >= t) {
+                kill_process_tree(&mut child);
+                let status = child.wait().context("等待命令退出失败")?;
+                return Ok((status, true));
+            }
+        }
+        thread::sleep(WAIT_POLL_INTERVAL);
     }
 }
 
@@ -35,6 +618,8 @@ impl ServiceLifecycle for CustomService {
             .map_err(|e| anyhow::anyhow!("获取 Shell 管理器锁失败: {}", e))?;
 
         if let Some(metadata) = &service_data.metadata {
+            let mut update = shell_manager.begin_update();
+
             // 处理自定义环境变量
             if let Some(env_vars_value) = metadata.get("envVars") {
                 if let serde_json::Value::Object(env_vars_obj) = env_vars_value {
@@ -43,9 +628,7 @@ impl ServiceLifecycle for CustomService {
                             serde_json::Value::String(s) => s.clone(),
                             _ => value.to_string().trim_matches('"').to_string(),
                         };
-                        shell_manager
-                            .add_export(key, &value_str)
-                            .with_context(|| format!("设置自定义环境变量 {} 失败", key))?;
+                        update.add_export(key, &value_str);
                         log::debug!("已设置自定义环境变量: {}={}", key, value_str);
                     }
                 }
@@ -57,15 +640,27 @@ impl ServiceLifecycle for CustomService {
                     for path_value in paths_array {
                         if let serde_json::Value::String(path_str) = path_value {
                             // 即使路径不存在也添加到 PATH
-                            shell_manager.add_path(path_str).with_context(|| {
-                                format!("添加自定义路径到 PATH 失败: {}", path_str)
-                            })?;
+                            update.add_path(path_str);
                             log::debug!("已添加自定义路径到 PATH: {}", path_str);
                         }
                     }
                 }
             }
 
+            // 处理已注册工具目录（仅选中的版本）
+            for binary in Self::get_binaries(service_data)?
+                .iter()
+                .filter(|b| b.active)
+            {
+                update.add_path(&binary.path_entry());
+                log::debug!(
+                    "已添加工具目录到 PATH: {} {} -> {}",
+                    binary.name,
+                    binary.version,
+                    binary.path_entry()
+                );
+            }
+
             // 处理自定义 Alias
             if let Some(aliases_value) = metadata.get("aliases") {
                 if let serde_json::Value::Object(aliases_obj) = aliases_value {
@@ -74,14 +669,16 @@ impl ServiceLifecycle for CustomService {
                             serde_json::Value::String(s) => s.clone(),
                             _ => value.to_string().trim_matches('"').to_string(),
                         };
-                        shell_manager
-                            .add_alias(key, &value_str)
-                            .with_context(|| format!("设置自定义 Alias {} 失败", key))?;
+                        update.add_alias(key, &value_str);
                         log::debug!("已设置自定义 Alias: {}={}", key, value_str);
                     }
                 }
             }
 
+            update
+                .commit()
+                .context("设置自定义环境变量、路径和 Alias 失败")?;
+
             // 处理自动跳转目录（auto chdir）
             let auto_chdir_enabled = metadata
                 .get("autoChdirEnabled")
@@ -116,13 +713,13 @@ impl ServiceLifecycle for CustomService {
             .map_err(|e| anyhow::anyhow!("获取 Shell 管理器锁失败: {}", e))?;
 
         if let Some(metadata) = &service_data.metadata {
+            let mut update = shell_manager.begin_update();
+
             // 移除自定义环境变量
             if let Some(env_vars_value) = metadata.get("envVars") {
                 if let serde_json::Value::Object(env_vars_obj) = env_vars_value {
                     for key in env_vars_obj.keys() {
-                        shell_manager
-                            .delete_export(key)
-                            .with_context(|| format!("移除自定义环境变量 {} 失败", key))?;
+                        update.delete_export(key);
                         log::debug!("已移除自定义环境变量: {}", key);
                     }
                 }
@@ -133,27 +730,36 @@ impl ServiceLifecycle for CustomService {
                 if let serde_json::Value::Array(paths_array) = paths_value {
                     for path_value in paths_array {
                         if let serde_json::Value::String(path_str) = path_value {
-                            shell_manager.delete_path(path_str).with_context(|| {
-                                format!("从 PATH 移除自定义路径失败: {}", path_str)
-                            })?;
+                            update.delete_path(path_str);
                             log::debug!("已从 PATH 移除自定义路径: {}", path_str);
                         }
                     }
                 }
             }
 
+            // 移除已注册工具目录
+            for binary in Self::get_binaries(service_data)?
+                .iter()
+                .filter(|b| b.active)
+            {
+                update.delete_path(&binary.path_entry());
+                log::debug!("已从 PATH 移除工具目录: {}", binary.path_entry());
+            }
+
             // 移除自定义 Alias
             if let Some(aliases_value) = metadata.get("aliases") {
                 if let serde_json::Value::Object(aliases_obj) = aliases_value {
                     for key in aliases_obj.keys() {
-                        shell_manager
-                            .delete_alias(key)
-                            .with_context(|| format!("移除自定义 Alias {} 失败", key))?;
+                        update.delete_alias(key);
                         log::debug!("已移除自定义 Alias: {}", key);
                     }
                 }
             }
 
+            update
+                .commit()
+                .context("移除自定义环境变量、路径和 Alias 失败")?;
+
             // 移除自动跳转目录（如果有配置）
             let auto_chdir_enabled = metadata
                 .get("autoChdirEnabled")
@@ -1,10 +1,16 @@
 use crate::manager::app_config_manager::AppConfigManager;
 use crate::manager::env_serv_data_manager::ServiceDataResult;
-use crate::manager::services::{DownloadManager, DownloadResult, DownloadStatus, DownloadTask};
+use crate::manager::services::launch::{wait_for_early_exit, OutputCapture};
+use crate::manager::services::traits::{status_result, ServiceLifecycle};
+use crate::manager::services::{
+    install_callback, remove_partial_install, DownloadManager, DownloadResult, DownloadTask,
+};
 use crate::types::{ServiceData, ServiceStatus};
 use crate::utils::create_command;
 use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
 use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
 use std::path::PathBuf;
 use std::sync::{Arc, OnceLock};
 
@@ -16,6 +22,11 @@ pub struct DnsmasqVersion {
     pub date: String,
 }
 
+/// 上游 DNS 服务器配置项前缀
+const SERVER_PREFIX: &str = "server=";
+/// 设置上游 DNS 时确保存在的指令：不转发私有地址的反向解析、不读取 resolv.conf
+const UPSTREAM_DIRECTIVES: [&str; 2] = ["bogus-priv", "no-resolv"];
+
 /// 全局 Dnsmasq 服务管理器单例
 static GLOBAL_DNSMASQ_SERVICE: OnceLock<Arc<DnsmasqService>> = OnceLock::new();
 
@@ -94,42 +105,13 @@ impl DnsmasqService {
         let download_manager = DownloadManager::global();
 
         let version_for_callback = version.to_string();
-        let success_callback = Arc::new(move |task: &DownloadTask| {
-            let dnsmasq_service = DnsmasqService::global();
-            // 在异步上下文中执行解压
-            let task_clone = task.clone();
-            let version_clone = version_for_callback.clone();
-            let task_id = task.id.clone();
-
-            tokio::spawn(async move {
-                let download_manager = DownloadManager::global();
-                // 更新状态为 Installing
-                let _ =
-                    download_manager.update_task_status(&task_id, DownloadStatus::Installing, None);
-
-                match dnsmasq_service
-                    .extract_and_install(&task_clone, &version_clone)
+        let success_callback = install_callback(format!("Dnsmasq {}", version), move |task| {
+            let version = version_for_callback.clone();
+            async move {
+                DnsmasqService::global()
+                    .extract_and_install(&task, &version)
                     .await
-                {
-                    Ok(_) => {
-                        // 更新状态为 Installed
-                        let _ = download_manager.update_task_status(
-                            &task_id,
-                            DownloadStatus::Installed,
-                            None,
-                        );
-                    }
-                    Err(e) => {
-                        log::error!("安装 Dnsmasq 失败: {}", e);
-                        // 更新状态为 Failed
-                        let _ = download_manager.update_task_status(
-                            &task_id,
-                            DownloadStatus::Failed,
-                            Some(e.to_string()),
-                        );
-                    }
-                }
-            });
+            }
         });
 
         match download_manager
@@ -154,6 +136,16 @@ impl DnsmasqService {
 
     /// 解压和安装 Dnsmasq
     pub async fn extract_and_install(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let result = self.install_from_archive(task, version).await;
+        if result.is_err() {
+            // 解压安装失败时清理残缺的安装目录
+            remove_partial_install(&self.get_install_path(version));
+        }
+        result
+    }
+
+    /// 将下载的压缩包解压到安装目录
+    async fn install_from_archive(&self, task: &DownloadTask, version: &str) -> Result<()> {
         let archive_path = &task.target_path;
         let install_path = self.get_install_path(version);
 
@@ -294,6 +286,141 @@ impl DnsmasqService {
         }
     }
 
+    /// 读取配置文件中的上游 DNS 服务器（不含 server=/domain/ 形式的按域名转发规则）
+    pub fn get_upstream_dns(&self, service_data: &ServiceData) -> Result<Vec<String>> {
+        let conf_path = self
+            .get_config_path(service_data)
+            .ok_or_else(|| anyhow!("未找到配置文件路径"))?;
+        let content = std::fs::read_to_string(&conf_path)?;
+
+        Ok(content
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix(SERVER_PREFIX))
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty() && !value.starts_with('/'))
+            .map(|value| value.to_string())
+            .collect())
+    }
+
+    /// 设置上游 DNS 服务器：替换原有的 server= 行，并确保 bogus-priv、no-resolv 存在
+    ///
+    /// 服务器格式为 IP 或 IP#端口，运行中的服务会重启以加载新配置。
+    pub fn set_upstream_dns(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+        servers: &[String],
+    ) -> Result<ServiceDataResult> {
+        let servers: Vec<String> = servers
+            .iter()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        if servers.is_empty() {
+            return Err(anyhow!("至少需要一个上游 DNS 服务器"));
+        }
+        for server in &servers {
+            Self::validate_upstream_server(server)?;
+        }
+
+        let conf_path = self
+            .get_config_path(service_data)
+            .ok_or_else(|| anyhow!("未找到配置文件路径"))?;
+        let content = std::fs::read_to_string(&conf_path)?;
+        let mut lines = Self::remove_upstream_lines(&content);
+        for directive in UPSTREAM_DIRECTIVES {
+            if !lines.iter().any(|line| line.trim() == directive) {
+                lines.push(directive.to_string());
+            }
+        }
+        lines.extend(servers.iter().map(|s| format!("{}{}", SERVER_PREFIX, s)));
+        std::fs::write(&conf_path, lines.join("\n") + "\n")?;
+        log::info!(
+            "已将 {} 的上游 DNS 设置为 {}",
+            conf_path.display(),
+            servers.join(", ")
+        );
+
+        let restarted = self.reload_if_running(service_data)?;
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("上游 DNS 已设置为 {}", servers.join(", ")),
+            data: Some(serde_json::json!({
+                "configPath": conf_path.to_string_lossy().to_string(),
+                "servers": servers,
+                "restarted": restarted,
+            })),
+        })
+    }
+
+    /// 移除上游 DNS 服务器与 no-resolv，恢复为使用系统 resolv.conf 中的服务器
+    pub fn reset_upstream_dns(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let conf_path = self
+            .get_config_path(service_data)
+            .ok_or_else(|| anyhow!("未找到配置文件路径"))?;
+        let content = std::fs::read_to_string(&conf_path)?;
+        let lines: Vec<String> = Self::remove_upstream_lines(&content)
+            .into_iter()
+            .filter(|line| line.trim() != "no-resolv")
+            .collect();
+        std::fs::write(&conf_path, lines.join("\n") + "\n")?;
+        log::info!("已清除 {} 中的上游 DNS 配置", conf_path.display());
+
+        let restarted = self.reload_if_running(service_data)?;
+        Ok(ServiceDataResult {
+            success: true,
+            message: "上游 DNS 已重置".to_string(),
+            data: Some(serde_json::json!({
+                "configPath": conf_path.to_string_lossy().to_string(),
+                "restarted": restarted,
+            })),
+        })
+    }
+
+    /// 去掉上游 DNS 的 server= 行，保留按域名转发的规则与注释
+    fn remove_upstream_lines(content: &str) -> Vec<String> {
+        content
+            .lines()
+            .filter(|line| match line.trim().strip_prefix(SERVER_PREFIX) {
+                Some(value) => value.trim_start().starts_with('/'),
+                None => true,
+            })
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    fn validate_upstream_server(server: &str) -> Result<()> {
+        let (ip, port) = match server.split_once('#') {
+            Some((ip, port)) => (ip, Some(port)),
+            None => (server, None),
+        };
+        if ip.parse::<IpAddr>().is_err() {
+            return Err(anyhow!("无效的上游 DNS 地址: {}", server));
+        }
+        if port.is_some_and(|p| !p.parse::<u16>().is_ok_and(|p| p != 0)) {
+            return Err(anyhow!("无效的上游 DNS 端口: {}", server));
+        }
+        Ok(())
+    }
+
+    /// 服务运行中时重启以加载新配置，返回是否已重启
+    ///
+    /// dnsmasq 收到 SIGHUP 只会重新读取 hosts 与 resolv 文件，不会重新读取 dnsmasq.conf。
+    fn reload_if_running(&self, service_data: &ServiceData) -> Result<bool> {
+        if !matches!(
+            self.get_service_status(service_data)?,
+            ServiceStatus::Running
+        ) {
+            return Ok(false);
+        }
+        self.restart_service(service_data)?;
+        Ok(true)
+    }
+
     /// 启动 Dnsmasq 服务
     pub fn start_service(&self, service_data: &ServiceData) -> Result<ServiceDataResult> {
         let version = &service_data.version;
@@ -316,25 +443,39 @@ impl DnsmasqService {
         // PID 文件路径
         let pid_file = install_path.join("dnsmasq.pid");
 
+        // 输出写入配置目录下的 logs/dnsmasq-stdout.log
+        let capture = OutputCapture::new(conf_path.parent().unwrap_or(&install_path), "dnsmasq")?;
+
         // 构建命令
         let mut cmd = create_command(&dnsmasq_bin);
-        cmd.arg("-C").arg(conf_path);
+        cmd.arg("-C").arg(&conf_path);
         cmd.arg("--pid-file").arg(&pid_file);
         // 不使用 -k，让它后台运行（daemonize），这样它会自己 fork 并写入 pid 文件
+        capture.attach(&mut cmd)?;
 
-        let output = cmd
-            .output()
+        let mut child = cmd
+            .spawn()
             .map_err(|e| anyhow!("启动 Dnsmasq 失败: {}", e))?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow!("启动 Dnsmasq 失败: {}", stderr));
+        // 配置错误时 dnsmasq 在后台化之前就会以非零状态退出
+        if let Some(status) = wait_for_early_exit(&mut child) {
+            let detail = capture.failure_detail(&status);
+            log::error!("Dnsmasq 启动失败: {}", detail);
+            return Ok(ServiceDataResult {
+                success: false,
+                message: format!("Dnsmasq 启动失败: {}", detail),
+                data: Some(serde_json::json!({
+                    "outputLog": capture.path().to_string_lossy().to_string(),
+                })),
+            });
         }
 
         Ok(ServiceDataResult {
             success: true,
             message: "Dnsmasq 启动成功".to_string(),
-            data: None,
+            data: Some(serde_json::json!({
+                "outputLog": capture.path().to_string_lossy().to_string(),
+            })),
         })
     }
 
@@ -390,7 +531,10 @@ impl DnsmasqService {
         self.stop_service(service_data)?;
         // 等待一小段时间确保进程完全退出
         std::thread::sleep(std::time::Duration::from_millis(500));
-        self.start_service(service_data)?;
+        let result = self.start_service(service_data)?;
+        if !result.success {
+            return Err(anyhow!(result.message));
+        }
         Ok(())
     }
 
@@ -431,3 +575,88 @@ impl DnsmasqService {
         Ok(ServiceStatus::Stopped)
     }
 }
+
+impl ServiceLifecycle for DnsmasqService {
+    fn is_installed(&self, version: &str) -> bool {
+        DnsmasqService::is_installed(self, version)
+    }
+
+    fn available_versions(&self) -> Vec<String> {
+        self.get_available_versions()
+            .into_iter()
+            .map(|v| v.version)
+            .collect()
+    }
+
+    fn download_and_install<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<DownloadResult>> {
+        Box::pin(DnsmasqService::download_and_install(self, version))
+    }
+
+    fn cancel_download(&self, version: &str) -> Result<()> {
+        DnsmasqService::cancel_download(self, version)
+    }
+
+    fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        DnsmasqService::get_download_progress(self, version)
+    }
+
+    fn archive_dir(&self, version: &str) -> Option<PathBuf> {
+        self.get_install_path(version)
+            .parent()
+            .map(|parent| parent.join("temp"))
+    }
+
+    fn extract_and_install<'a>(
+        &'a self,
+        task: &'a DownloadTask,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(DnsmasqService::extract_and_install(self, task, version))
+    }
+
+    fn start_service(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        DnsmasqService::start_service(self, service_data)
+    }
+
+    fn stop_service(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        DnsmasqService::stop_service(self, service_data)?;
+        Ok(ServiceDataResult {
+            success: true,
+            message: "停止 Dnsmasq 成功".to_string(),
+            data: None,
+        })
+    }
+
+    fn restart_service(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        DnsmasqService::restart_service(self, service_data)?;
+        Ok(ServiceDataResult {
+            success: true,
+            message: "重启 Dnsmasq 成功".to_string(),
+            data: None,
+        })
+    }
+
+    fn get_service_status(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let status = DnsmasqService::get_service_status(self, service_data)?;
+        Ok(status_result(status))
+    }
+}
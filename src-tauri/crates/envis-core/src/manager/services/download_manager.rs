@@ -1,12 +1,18 @@
+use crate::manager::app_config_manager::AppConfigManager;
+use crate::manager::services::ServiceRegistry;
+use crate::types::ServiceType;
+use crate::utils::http_client_builder;
 use anyhow::{anyhow, Result};
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::io::ErrorKind;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, OnceLock};
-use tokio::fs::File;
-use tokio::io::AsyncWriteExt;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::{Semaphore, SemaphorePermit};
 
 /// 下载状态
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,8 +25,56 @@ pub enum DownloadStatus {
     Installed,
     Failed,
     Cancelled,
+    Paused,
+    Resuming,
 }
 
+/// 预估所需磁盘空间时的倍数（压缩包本身 + 解压后的内容）
+const DISK_SPACE_MULTIPLIER: u64 = 3;
+
+/// 下载历史记录文件，位于服务目录下
+const DOWNLOAD_HISTORY_FILE: &str = "download_history.json";
+
+/// 并发下载数上限的允许范围
+const MIN_CONCURRENT_DOWNLOADS: u32 = 1;
+const MAX_CONCURRENT_DOWNLOADS: u32 = 10;
+
+/// 下载前检测到目标磁盘空间不足
+#[derive(Debug, Clone)]
+pub struct DiskSpaceInsufficient {
+    pub path: PathBuf,
+    pub required: u64,
+    pub available: u64,
+}
+
+impl std::fmt::Display for DiskSpaceInsufficient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "磁盘空间不足: {} 所在磁盘需要 {} 字节，可用 {} 字节",
+            self.path.display(),
+            self.required,
+            self.available
+        )
+    }
+}
+
+impl std::error::Error for DiskSpaceInsufficient {}
+
+/// 下载在传输过程中被暂停，已下载部分保留在磁盘上，恢复后继续
+#[derive(Debug, Clone)]
+pub struct DownloadPaused {
+    pub id: String,
+}
+
+impl std::fmt::Display for DownloadPaused {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "下载已暂停: {}", self.id)
+    }
+}
+
+impl std::error::Error for DownloadPaused {}
+
 /// 下载任务信息
 #[derive(Clone, Serialize, Deserialize)]
 pub struct DownloadTask {
@@ -36,6 +90,8 @@ pub struct DownloadTask {
     pub progress: f64,
     pub error_message: Option<String>,
     pub failed_urls: Vec<String>, // 记录失败的URLs
+    #[serde(default)]
+    pub finished_at: Option<String>, // 安装完成或失败的时间，写入下载历史时记录
     #[serde(skip)]
     pub success_callback: Option<SuccessCallback>, // 下载成功后的回调函数
 }
@@ -56,6 +112,7 @@ impl std::fmt::Debug for DownloadTask {
             .field("progress", &self.progress)
             .field("error_message", &self.error_message)
             .field("failed_urls", &self.failed_urls)
+            .field("finished_at", &self.finished_at)
             .field("success_callback", &self.success_callback.is_some())
             .finish()
     }
@@ -83,6 +140,7 @@ impl DownloadTask {
             progress: 0.0,
             error_message: None,
             failed_urls: Vec::new(),
+            finished_at: None,
             success_callback,
         }
     }
@@ -130,13 +188,26 @@ impl DownloadTask {
 /// 下载成功回调函数类型
 pub type SuccessCallback = Arc<dyn Fn(&DownloadTask) + Send + Sync>;
 
+/// 并发下载上限的当前状态
+struct DownloadLimit {
+    limit: u32,
+    /// 调低上限时仍被进行中的下载占用、需在其完成后回收的许可数
+    pending_reduction: u32,
+}
+
 /// 全局下载管理器单例
 static GLOBAL_DOWNLOAD_MANAGER: OnceLock<Arc<DownloadManager>> = OnceLock::new();
 
 /// 下载管理器
 pub struct DownloadManager {
     pub(crate) tasks: Arc<Mutex<HashMap<String, DownloadTask>>>,
-    client: reqwest::Client,
+    /// 已安装或失败的任务记录，持久化到 download_history.json，应用重启后仍可查询
+    history: Arc<Mutex<HashMap<String, DownloadTask>>>,
+    /// 每个任务开始传输前获取一个许可，限制同时进行的下载数量
+    max_concurrent_downloads: Arc<Semaphore>,
+    download_limit: Mutex<DownloadLimit>,
+    /// 每个任务一把锁，下载循环运行期间持有，保证同一任务不会有两个循环同时写入文件
+    running: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
 }
 
 impl DownloadManager {
@@ -149,14 +220,143 @@ impl DownloadManager {
 
     /// 创建新的下载管理器实例（内部使用）
     fn new() -> Self {
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(1800)) // 30分钟超时
-            .build()
-            .expect("Failed to create HTTP client");
-
+        let limit = {
+            let app_config_manager = AppConfigManager::global();
+            let app_config_manager = app_config_manager.lock().unwrap();
+            app_config_manager
+                .get_app_config()
+                .max_concurrent_downloads
+                .clamp(MIN_CONCURRENT_DOWNLOADS, MAX_CONCURRENT_DOWNLOADS)
+        };
         Self {
             tasks: Arc::new(Mutex::new(HashMap::new())),
-            client,
+            history: Arc::new(Mutex::new(Self::load_history())),
+            max_concurrent_downloads: Arc::new(Semaphore::new(limit as usize)),
+            download_limit: Mutex::new(DownloadLimit {
+                limit,
+                pending_reduction: 0,
+            }),
+            running: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 获取当前并发下载上限
+    pub fn get_concurrent_download_limit(&self) -> u32 {
+        self.download_limit.lock().unwrap().limit
+    }
+
+    /// 设置并发下载上限并写入应用配置
+    pub fn set_concurrent_download_limit(&self, limit: u32) -> Result<()> {
+        if !(MIN_CONCURRENT_DOWNLOADS..=MAX_CONCURRENT_DOWNLOADS).contains(&limit) {
+            return Err(anyhow!(
+                "并发下载数需在 {} 到 {} 之间",
+                MIN_CONCURRENT_DOWNLOADS,
+                MAX_CONCURRENT_DOWNLOADS
+            ));
+        }
+        {
+            let app_config_manager = AppConfigManager::global();
+            let mut app_config_manager = app_config_manager.lock().unwrap();
+            let mut app_config = app_config_manager.get_app_config();
+            app_config.max_concurrent_downloads = limit;
+            app_config_manager.set_app_config(app_config)?;
+        }
+        self.apply_concurrent_download_limit(limit);
+        Ok(())
+    }
+
+    /// 调整信号量许可数；调低时先回收空闲许可，其余在进行中的下载完成后回收
+    pub fn apply_concurrent_download_limit(&self, limit: u32) {
+        let limit = limit.clamp(MIN_CONCURRENT_DOWNLOADS, MAX_CONCURRENT_DOWNLOADS);
+        let mut state = self.download_limit.lock().unwrap();
+        if limit > state.limit {
+            let increase = limit - state.limit;
+            let cancelled = increase.min(state.pending_reduction);
+            state.pending_reduction -= cancelled;
+            self.max_concurrent_downloads
+                .add_permits((increase - cancelled) as usize);
+        } else if limit < state.limit {
+            let decrease = (state.limit - limit) as usize;
+            let forgotten = self.max_concurrent_downloads.forget_permits(decrease);
+            state.pending_reduction += (decrease - forgotten) as u32;
+        } else {
+            return;
+        }
+        log::info!("并发下载上限调整为 {}", limit);
+        state.limit = limit;
+    }
+
+    /// 等待下载许可，达到并发上限时排队
+    async fn acquire_download_permit(&self, id: &str) -> Result<SemaphorePermit<'_>> {
+        if let Ok(permit) = self.max_concurrent_downloads.try_acquire() {
+            return Ok(permit);
+        }
+        log::info!("已达到并发下载上限，等待其他下载完成: {}", id);
+        self.max_concurrent_downloads
+            .acquire()
+            .await
+            .map_err(|e| anyhow!("获取下载许可失败: {}", e))
+    }
+
+    /// 归还下载许可；上限调低后尚未回收的许可在这里丢弃
+    fn release_download_permit(&self, permit: SemaphorePermit<'_>) {
+        let mut state = self.download_limit.lock().unwrap();
+        if state.pending_reduction > 0 {
+            state.pending_reduction -= 1;
+            permit.forget();
+        }
+    }
+
+    /// 每次下载时按当前代理设置创建 HTTP 客户端，修改设置后无需重启即可生效
+    fn client() -> Result<reqwest::Client> {
+        Ok(http_client_builder()?
+            .timeout(std::time::Duration::from_secs(1800)) // 30分钟超时
+            .build()?)
+    }
+
+    fn get_history_path() -> PathBuf {
+        let app_config_manager = AppConfigManager::global();
+        let app_config_manager = app_config_manager.lock().unwrap();
+        PathBuf::from(app_config_manager.get_services_folder()).join(DOWNLOAD_HISTORY_FILE)
+    }
+
+    /// 读取下载历史，文件不存在或内容损坏时从空记录开始
+    fn load_history() -> HashMap<String, DownloadTask> {
+        let history_path = Self::get_history_path();
+        let content = match fs::read_to_string(&history_path) {
+            Ok(content) => content,
+            Err(e) => {
+                if e.kind() != ErrorKind::NotFound {
+                    log::warn!("读取下载历史失败 {:?}: {}", history_path, e);
+                }
+                return HashMap::new();
+            }
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            log::warn!("解析下载历史失败 {:?}: {}", history_path, e);
+            HashMap::new()
+        })
+    }
+
+    /// 先写临时文件再替换，避免写入中断时损坏历史文件
+    fn save_history(history: &HashMap<String, DownloadTask>) -> Result<()> {
+        let history_path = Self::get_history_path();
+        if let Some(parent) = history_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp_path = history_path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(history)?)?;
+        fs::rename(&tmp_path, &history_path)?;
+        Ok(())
+    }
+
+    /// 任务进入 Installed 或 Failed 后写入下载历史，同一任务 ID 只保留最近一次结果
+    fn record_history(&self, mut task: DownloadTask) {
+        task.finished_at = Some(Utc::now().to_rfc3339());
+        let mut history = self.history.lock().unwrap();
+        history.insert(task.id.clone(), task);
+        if let Err(e) = Self::save_history(&history) {
+            log::warn!("保存下载历史失败: {}", e);
         }
     }
 
@@ -179,6 +379,9 @@ impl DownloadManager {
             fs::create_dir_all(&target_dir)?;
         }
 
+        // 检查磁盘空间，避免解压到一半失败
+        self.check_disk_space(&urls[0], &target_dir).await?;
+
         // 如果需要覆盖，先清理现有文件
         let target_path = target_dir.join(&filename);
         if overwrite_existing && target_path.exists() {
@@ -210,8 +413,76 @@ impl DownloadManager {
         self.download_with_fallback(&id).await
     }
 
-    /// 支持备用URL的下载方法
+    /// 根据 Content-Length 预估所需空间，并与目标目录所在磁盘的可用空间比较
+    async fn check_disk_space(&self, url: &str, target_dir: &Path) -> Result<()> {
+        let content_length = match Self::client()?
+            .head(url)
+            .timeout(std::time::Duration::from_secs(15))
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => response
+                .headers()
+                .get(reqwest::header::CONTENT_LENGTH)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok()),
+            Ok(response) => {
+                log::debug!(
+                    "HEAD 请求返回 {}，跳过磁盘空间检查: {}",
+                    response.status(),
+                    url
+                );
+                None
+            }
+            Err(e) => {
+                log::debug!("HEAD 请求失败，跳过磁盘空间检查: {}", e);
+                None
+            }
+        };
+        let Some(content_length) = content_length else {
+            return Ok(());
+        };
+
+        let Some(available) = available_space(target_dir) else {
+            log::debug!("无法获取 {:?} 所在磁盘的可用空间，跳过检查", target_dir);
+            return Ok(());
+        };
+
+        let required = content_length.saturating_mul(DISK_SPACE_MULTIPLIER);
+        if available < required {
+            return Err(DiskSpaceInsufficient {
+                path: target_dir.to_path_buf(),
+                required,
+                available,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// 获取任务的运行锁
+    fn running_lock(&self, id: &str) -> Arc<tokio::sync::Mutex<()>> {
+        let mut running = self.running.lock().unwrap();
+        running.entry(id.to_string()).or_default().clone()
+    }
+
+    /// 支持备用URL的下载方法，同一任务的上一个下载循环退出后才开始
     pub async fn download_with_fallback(&self, id: &str) -> Result<()> {
+        let running_lock = self.running_lock(id);
+        let _running = running_lock.lock().await;
+        self.download_with_permit(id).await
+    }
+
+    /// 获取并发下载许可后依次尝试下载地址，调用方需持有任务的运行锁
+    async fn download_with_permit(&self, id: &str) -> Result<()> {
+        let permit = self.acquire_download_permit(id).await?;
+        let result = self.download_urls_in_order(id).await;
+        self.release_download_permit(permit);
+        result
+    }
+
+    /// 依次尝试任务的下载地址，失败时切换到备用URL
+    async fn download_urls_in_order(&self, id: &str) -> Result<()> {
         loop {
             let current_task = {
                 let tasks = self.tasks.lock().unwrap();
@@ -222,10 +493,13 @@ impl DownloadManager {
                 return Err(anyhow!("未找到下载任务: {}", id));
             };
 
-            // 检查任务是否被取消
+            // 检查任务是否被取消或暂停
             if matches!(task.status, DownloadStatus::Cancelled) {
                 return Err(anyhow!("下载已取消"));
             }
+            if matches!(task.status, DownloadStatus::Paused) {
+                return Err(DownloadPaused { id: id.to_string() }.into());
+            }
 
             // 尝试下载当前URL
             let result = self.download_file(&mut task).await;
@@ -258,6 +532,7 @@ impl DownloadManager {
                 }
                 Err(e) => {
                     // 下载失败，尝试切换到下一个URL
+                    let mut failed_task = None;
                     let should_retry = {
                         let mut tasks = self.tasks.lock().unwrap();
                         if let Some(stored_task) = tasks.get_mut(id) {
@@ -265,6 +540,9 @@ impl DownloadManager {
                             if matches!(stored_task.status, DownloadStatus::Cancelled) {
                                 log::info!("检测到任务已取消，停止重试: {}", id);
                                 false
+                            } else if matches!(stored_task.status, DownloadStatus::Paused) {
+                                log::info!("检测到任务已暂停，保留已下载部分: {}", id);
+                                false
                             } else if stored_task.switch_to_next_url() {
                                 log::warn!(
                                     "下载失败，切换到备用URL: {} -> {}",
@@ -279,6 +557,7 @@ impl DownloadManager {
                                     "所有下载地址都失败了。失败的URLs: {:?}。最后错误: {}",
                                     stored_task.failed_urls, e
                                 ));
+                                failed_task = Some(stored_task.clone());
                                 false
                             }
                         } else {
@@ -286,6 +565,9 @@ impl DownloadManager {
                         }
                     };
 
+                    if let Some(task) = failed_task {
+                        self.record_history(task);
+                    }
                     if !should_retry {
                         return Err(e);
                     }
@@ -299,9 +581,22 @@ impl DownloadManager {
     async fn download_file(&self, task: &mut DownloadTask) -> Result<()> {
         log::info!("开始下载文件: {} -> {:?}", task.url, task.target_path);
 
+        // 断点续传：已下载部分仍在磁盘上时，从该位置继续请求
+        let resume_from = match fs::metadata(&task.target_path) {
+            Ok(meta) if meta.is_file() && task.downloaded_size > 0 => {
+                task.downloaded_size.min(meta.len())
+            }
+            _ => 0,
+        };
+
         // 发送HTTP请求
         log::info!("正在连接下载服务器...");
-        let response = self.client.get(&task.url).send().await?;
+        let mut request = Self::client()?.get(&task.url);
+        if resume_from > 0 {
+            log::info!("从 {} 字节处继续下载", resume_from);
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", resume_from));
+        }
+        let response = request.send().await?;
 
         if !response.status().is_success() {
             let error_msg = format!("下载失败，状态码: {}", response.status());
@@ -322,13 +617,25 @@ impl DownloadManager {
             ));
         }
 
+        // 服务器返回 206 时追加写入，否则（不支持 Range）从头下载
+        let resumed = resume_from > 0 && response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+        let mut downloaded = if resumed { resume_from } else { 0 };
+
         // 更新任务状态和文件大小到全局存储
-        let total_size = response.content_length().unwrap_or(0);
+        let total_size = response
+            .content_length()
+            .map(|len| len + downloaded)
+            .unwrap_or(0);
         {
             let mut tasks = self.tasks.lock().unwrap();
             if let Some(stored_task) = tasks.get_mut(&task.id) {
+                if matches!(stored_task.status, DownloadStatus::Paused) {
+                    return Err(DownloadPaused { id: task.id.clone() }.into());
+                }
                 stored_task.status = DownloadStatus::Downloading;
                 stored_task.total_size = total_size;
+                stored_task.downloaded_size = downloaded;
+                stored_task.calculate_progress();
             }
         }
 
@@ -341,8 +648,11 @@ impl DownloadManager {
         );
 
         // 创建输出文件
-        let mut file = File::create(&task.target_path).await?;
-        let mut downloaded = 0u64;
+        let mut file = if resumed {
+            OpenOptions::new().append(true).open(&task.target_path).await?
+        } else {
+            File::create(&task.target_path).await?
+        };
         let mut last_log_time = std::time::Instant::now();
 
         // 读取响应流并写入文件
@@ -353,15 +663,23 @@ impl DownloadManager {
         while let Some(chunk_result) = stream.next().await {
             let chunk = chunk_result?;
 
-            // 检查任务是否被取消
-            {
+            // 检查任务是否被取消或暂停
+            let paused = {
                 let tasks = self.tasks.lock().unwrap();
-                if let Some(current_task) = tasks.get(&task.id) {
-                    if matches!(current_task.status, DownloadStatus::Cancelled) {
+                match tasks.get(&task.id) {
+                    Some(current_task) if matches!(current_task.status, DownloadStatus::Cancelled) => {
                         log::info!("下载已取消: {}", task.id);
                         return Err(anyhow!("下载已取消"));
                     }
+                    Some(current_task) => matches!(current_task.status, DownloadStatus::Paused),
+                    None => false,
                 }
+            };
+            if paused {
+                // 落盘已下载部分，恢复时从 downloaded_size 继续
+                file.flush().await?;
+                log::info!("下载已暂停: {} ({} 字节)", task.id, downloaded);
+                return Err(DownloadPaused { id: task.id.clone() }.into());
             }
 
             // 写入文件
@@ -395,6 +713,116 @@ impl DownloadManager {
         Ok(())
     }
 
+    /// 将本地压缩包作为下载任务导入：复制到 target_dir 并更新进度，完成后与下载一样调用成功回调
+    pub async fn import_local_archive(
+        &self,
+        id: String,
+        source: &Path,
+        target_dir: PathBuf,
+        success_callback: Option<SuccessCallback>,
+    ) -> Result<()> {
+        let filename = source
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| anyhow!("无效的压缩包路径: {}", source.display()))?;
+        let total_size = fs::metadata(source)?.len();
+
+        fs::create_dir_all(&target_dir)?;
+        if let Some(available) = available_space(&target_dir) {
+            let required = total_size.saturating_mul(DISK_SPACE_MULTIPLIER);
+            if available < required {
+                return Err(DiskSpaceInsufficient {
+                    path: target_dir,
+                    required,
+                    available,
+                }
+                .into());
+            }
+        }
+
+        let target_path = target_dir.join(&filename);
+        let mut task = DownloadTask::new(
+            id.clone(),
+            vec![source.to_string_lossy().to_string()],
+            target_path.clone(),
+            filename,
+            success_callback,
+        );
+        task.total_size = total_size;
+        task.status = DownloadStatus::Downloading;
+        {
+            let mut tasks = self.tasks.lock().unwrap();
+            if let Some(existing) = tasks.get(&id) {
+                if matches!(
+                    existing.status,
+                    DownloadStatus::Pending
+                        | DownloadStatus::Downloading
+                        | DownloadStatus::Resuming
+                        | DownloadStatus::Installing
+                ) {
+                    return Err(anyhow!("任务正在进行中: {}", id));
+                }
+            }
+            tasks.insert(id.clone(), task);
+        }
+
+        if let Err(e) = self.copy_with_progress(&id, source, &target_path).await {
+            let failed_task = {
+                let mut tasks = self.tasks.lock().unwrap();
+                tasks.get_mut(&id).map(|task| {
+                    task.status = DownloadStatus::Failed;
+                    task.error_message = Some(format!("导入压缩包失败: {}", e));
+                    task.clone()
+                })
+            };
+            if let Some(task) = failed_task {
+                self.record_history(task);
+            }
+            let _ = fs::remove_file(&target_path);
+            return Err(e);
+        }
+
+        let task_for_callback = {
+            let mut tasks = self.tasks.lock().unwrap();
+            tasks.get_mut(&id).map(|task| {
+                task.status = DownloadStatus::Downloaded;
+                task.progress = 100.0;
+                task.clone()
+            })
+        };
+        if let Some(task) = task_for_callback {
+            if let Some(callback) = task.success_callback.clone() {
+                callback(&task);
+            }
+        }
+        log::info!("本地压缩包已导入: {:?} -> {:?}", source, target_path);
+        Ok(())
+    }
+
+    /// 分块复制本地文件，并同步更新任务的已复制大小与进度
+    async fn copy_with_progress(&self, id: &str, source: &Path, target: &Path) -> Result<()> {
+        let mut reader = File::open(source).await?;
+        let mut writer = File::create(target).await?;
+        let mut buffer = vec![0u8; 1024 * 1024];
+        let mut copied: u64 = 0;
+        loop {
+            let read = reader.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+            writer.write_all(&buffer[..read]).await?;
+            copied += read as u64;
+
+            let mut tasks = self.tasks.lock().unwrap();
+            if let Some(task) = tasks.get_mut(id) {
+                task.downloaded_size = copied;
+                task.calculate_progress();
+            }
+        }
+        writer.flush().await?;
+        Ok(())
+    }
+
     /// 取消下载任务
     pub fn cancel_download(&self, id: &str) -> Result<()> {
         let mut tasks = self.tasks.lock().unwrap();
@@ -431,6 +859,65 @@ impl DownloadManager {
         }
     }
 
+    /// 暂停下载任务，保留已下载的部分文件
+    pub fn pause_download(&self, id: &str) -> Result<()> {
+        let mut tasks = self.tasks.lock().unwrap();
+        let task = tasks
+            .get_mut(id)
+            .ok_or_else(|| anyhow!("未找到下载任务: {}", id))?;
+
+        match task.status {
+            DownloadStatus::Pending | DownloadStatus::Downloading | DownloadStatus::Resuming => {
+                task.status = DownloadStatus::Paused;
+                log::info!("暂停下载任务: {}", id);
+                Ok(())
+            }
+            DownloadStatus::Paused => Ok(()),
+            _ => Err(anyhow!("当前状态无法暂停: {:?}", task.status)),
+        }
+    }
+
+    /// 恢复已暂停的下载任务，使用 Range 请求从 downloaded_size 处继续
+    ///
+    /// 暂停只在收到下一个数据块时生效，新的下载循环会等上一个循环退出后再开始
+    pub fn resume_download(&self, id: &str) -> Result<()> {
+        let runtime = tokio::runtime::Handle::try_current()
+            .map_err(|_| anyhow!("当前没有可用的异步运行时，无法恢复下载"))?;
+
+        {
+            let mut tasks = self.tasks.lock().unwrap();
+            let task = tasks
+                .get_mut(id)
+                .ok_or_else(|| anyhow!("未找到下载任务: {}", id))?;
+            if !matches!(task.status, DownloadStatus::Paused) {
+                return Err(anyhow!("下载任务未处于暂停状态: {:?}", task.status));
+            }
+            task.status = DownloadStatus::Resuming;
+            task.error_message = None;
+        }
+
+        log::info!("恢复下载任务: {}", id);
+        let id = id.to_string();
+        runtime.spawn(async move {
+            let download_manager = DownloadManager::global();
+            let running_lock = download_manager.running_lock(&id);
+            let _running = running_lock.lock().await;
+
+            // 上一个循环可能在看到暂停前就已继续下载直到结束，或期间又被暂停/取消
+            let still_resuming = download_manager
+                .get_task_status(&id)
+                .is_some_and(|task| matches!(task.status, DownloadStatus::Resuming));
+            if !still_resuming {
+                log::info!("下载任务已不需要恢复: {}", id);
+                return;
+            }
+            if let Err(e) = download_manager.download_with_permit(&id).await {
+                log::warn!("恢复下载结束: {} - {}", id, e);
+            }
+        });
+        Ok(())
+    }
+
     /// 获取下载任务状态
     pub fn get_task_status(&self, id: &str) -> Option<DownloadTask> {
         let tasks = self.tasks.lock().unwrap();
@@ -443,23 +930,48 @@ impl DownloadManager {
         tasks.values().cloned().collect()
     }
 
-    /// 更新任务状态
+    /// 更新任务状态，进入 Installed 或 Failed 时写入下载历史
     pub fn update_task_status(
         &self,
         id: &str,
         status: DownloadStatus,
         error_message: Option<String>,
     ) -> Result<()> {
-        let mut tasks = self.tasks.lock().unwrap();
-
-        if let Some(task) = tasks.get_mut(id) {
+        let finished_task = {
+            let mut tasks = self.tasks.lock().unwrap();
+            let task = tasks
+                .get_mut(id)
+                .ok_or_else(|| anyhow!("未找到下载任务: {}", id))?;
+            let finished = matches!(status, DownloadStatus::Installed | DownloadStatus::Failed);
             task.status = status;
             if let Some(message) = error_message {
                 task.error_message = Some(message);
             }
-            Ok(())
-        } else {
-            Err(anyhow!("未找到下载任务: {}", id))
+            finished.then(|| task.clone())
+        };
+
+        if let Some(task) = finished_task {
+            self.record_history(task);
+        }
+        Ok(())
+    }
+
+    /// 获取所有下载历史，按完成时间倒序
+    pub fn get_all_download_history(&self) -> Vec<DownloadTask> {
+        let history = self.history.lock().unwrap();
+        let mut records: Vec<DownloadTask> = history.values().cloned().collect();
+        records.sort_by(|a, b| b.finished_at.cmp(&a.finished_at));
+        records
+    }
+
+    /// 清空下载历史并删除历史文件
+    pub fn clear_download_history(&self) -> Result<()> {
+        let mut history = self.history.lock().unwrap();
+        history.clear();
+        match fs::remove_file(Self::get_history_path()) {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(anyhow!("删除下载历史文件失败: {}", e)),
         }
     }
 
@@ -471,7 +983,7 @@ impl DownloadManager {
             .filter(|task| {
                 matches!(
                     task.status,
-                    DownloadStatus::Pending | DownloadStatus::Downloading
+                    DownloadStatus::Pending | DownloadStatus::Downloading | DownloadStatus::Resuming
                 )
             })
             .count()
@@ -502,4 +1014,137 @@ impl DownloadResult {
             task: None,
         }
     }
+
+    /// 下载被暂停，task 的状态为 Paused，恢复后在后台继续下载并安装
+    pub fn paused(message: String, task: Option<DownloadTask>) -> Self {
+        Self {
+            success: true,
+            message,
+            task,
+        }
+    }
+}
+
+/// 获取路径所在磁盘的可用空间（取挂载点最长匹配的磁盘），路径不存在时使用最近的已存在上级目录
+pub fn available_space(path: &Path) -> Option<u64> {
+    let path = path.ancestors().find_map(|p| fs::canonicalize(p).ok())?;
+    // Windows 下 canonicalize 会带上 \\?\ 前缀，需去掉后再与挂载点比较
+    let path = PathBuf::from(path.to_string_lossy().trim_start_matches(r"\\?\"));
+
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+/// 解压安装失败时移除未完成的安装目录，避免 is_installed 将残缺版本识别为已安装
+pub fn remove_partial_install(install_dir: &Path) {
+    if !install_dir.exists() {
+        return;
+    }
+    match fs::remove_dir_all(install_dir) {
+        Ok(_) => log::info!("已清理未完成的安装目录: {:?}", install_dir),
+        Err(e) => log::warn!("清理未完成的安装目录失败 {:?}: {}", install_dir, e),
+    }
+}
+
+/// 构建下载完成回调：在后台执行 install，并将任务状态依次更新为 Installing、Installed/Failed
+///
+/// label 用于日志，如 "Redis 7.2.4"
+pub fn install_callback<F, Fut>(label: String, install: F) -> SuccessCallback
+where
+    F: Fn(DownloadTask) -> Fut + Send + Sync + 'static,
+    Fut: std::future::Future<Output = Result<()>> + Send + 'static,
+{
+    Arc::new(move |task: &DownloadTask| {
+        log::info!("{} 下载完成: {} - {}", label, task.id, task.filename);
+        let task_id = task.id.clone();
+        let label = label.clone();
+        let install = install(task.clone());
+
+        tokio::spawn(async move {
+            let download_manager = DownloadManager::global();
+            if let Err(e) =
+                download_manager.update_task_status(&task_id, DownloadStatus::Installing, None)
+            {
+                log::error!("更新任务状态失败: {}", e);
+            } else {
+                log::info!("{} 开始安装", label);
+            }
+
+            match install.await {
+                Ok(_) => {
+                    if let Err(e) = download_manager.update_task_status(
+                        &task_id,
+                        DownloadStatus::Installed,
+                        None,
+                    ) {
+                        log::error!("更新任务状态失败: {}", e);
+                    } else {
+                        log::info!("{} 安装成功", label);
+                    }
+                }
+                Err(e) => {
+                    if let Err(update_err) = download_manager.update_task_status(
+                        &task_id,
+                        DownloadStatus::Failed,
+                        Some(format!("安装失败: {}", e)),
+                    ) {
+                        log::error!("更新任务状态失败: {}", update_err);
+                    }
+                    log::error!("{} 安装失败: {}", label, e);
+                }
+            }
+        });
+    })
+}
+
+/// 下载服务安装包，下载完成后在后台执行该服务的 extract_and_install
+///
+/// 任务 ID 为 `<服务目录名>-<版本>`，如 "vault-1.20.4"
+pub async fn download_service_package(
+    service_type: ServiceType,
+    version: &str,
+    urls: Vec<String>,
+    install_path: PathBuf,
+    filename: String,
+) -> Result<DownloadResult> {
+    let name = service_type.default_name();
+    let task_id = format!("{}-{}", service_type.dir_name(), version);
+    let download_manager = DownloadManager::global();
+
+    let version_for_callback = version.to_string();
+    let success_callback = install_callback(format!("{} {}", name, version), move |task| {
+        let service = ServiceRegistry::get(&service_type);
+        let version = version_for_callback.clone();
+        async move { service.extract_and_install(&task, &version).await }
+    });
+
+    match download_manager
+        .start_download(
+            task_id.clone(),
+            urls,
+            install_path,
+            filename,
+            true,
+            Some(success_callback),
+        )
+        .await
+    {
+        Ok(_) => match download_manager.get_task_status(&task_id) {
+            Some(task) => Ok(DownloadResult::success(
+                format!("{} {} 下载完成", name, version),
+                Some(task),
+            )),
+            None => Ok(DownloadResult::error("无法获取下载任务状态".to_string())),
+        },
+        Err(e) if e.is::<DownloadPaused>() => Ok(DownloadResult::paused(
+            format!("{} {} 下载已暂停", name, version),
+            download_manager.get_task_status(&task_id),
+        )),
+        Err(e) => Ok(DownloadResult::error(format!("下载失败: {}", e))),
+    }
 }
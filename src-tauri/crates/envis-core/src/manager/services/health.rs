@@ -0,0 +1,218 @@
+use crate::manager::builders::DatabaseMetadataKeys;
+use crate::types::{ServiceData, ServiceStatus};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+/// 健康检查的连接超时
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 服务健康检查结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceHealth {
+    /// 进程是否存在
+    pub process: bool,
+    /// 端口是否可连接
+    pub port_open: bool,
+    /// 服务是否正常响应（SELECT 1 / ping / HTTP 等）
+    pub responding: bool,
+    /// 响应耗时（毫秒）
+    pub latency_ms: Option<u64>,
+}
+
+impl ServiceHealth {
+    /// 根据检查结果推导服务状态：进程存在但未响应时为 Degraded
+    pub fn status(&self) -> ServiceStatus {
+        if self.responding {
+            ServiceStatus::Running
+        } else if self.process || self.port_open {
+            ServiceStatus::Degraded
+        } else {
+            ServiceStatus::Stopped
+        }
+    }
+
+    /// 将健康检查字段写入状态数据（覆盖 status）
+    pub fn merge_into(&self, data: &mut serde_json::Value) {
+        if let Some(map) = data.as_object_mut() {
+            map.insert("process".to_string(), self.process.into());
+            map.insert("portOpen".to_string(), self.port_open.into());
+            map.insert("responding".to_string(), self.responding.into());
+            map.insert("latencyMs".to_string(), serde_json::json!(self.latency_ms));
+            map.insert("status".to_string(), serde_json::json!(self.status()));
+        }
+    }
+}
+
+/// 数据库服务初始化状态
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InitializationState {
+    Initialized,
+    /// 目录已存在，但初始化未完成或保存的凭据不可用
+    PartiallyInitialized,
+    NotInitialized,
+}
+
+/// 数据库服务初始化状态检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializationCheck {
+    pub state: InitializationState,
+    pub reason: String,
+    /// 服务运行中且已用保存的凭据登录成功
+    pub credentials_verified: bool,
+}
+
+impl InitializationCheck {
+    /// 根据目录、metadata 与凭据验证结果判断初始化状态
+    ///
+    /// verify_credentials 在服务运行时使用保存的凭据登录，未运行时返回 None。
+    /// 早于初始化标记写入的服务没有该键，只要凭据齐全即视为已初始化。
+    pub fn evaluate(
+        files_exist: bool,
+        service_data: &ServiceData,
+        keys: &DatabaseMetadataKeys,
+        verify_credentials: impl FnOnce() -> Option<bool>,
+    ) -> Self {
+        let partial = |reason: String| Self {
+            state: InitializationState::PartiallyInitialized,
+            reason,
+            credentials_verified: false,
+        };
+
+        if !files_exist {
+            return Self {
+                state: InitializationState::NotInitialized,
+                reason: "配置文件或数据目录不存在".to_string(),
+                credentials_verified: false,
+            };
+        }
+
+        let metadata = service_data.metadata.as_ref();
+        let flag = metadata
+            .and_then(|m| m.get(keys.initialized))
+            .and_then(|v| v.as_bool());
+        if flag == Some(false) {
+            return partial("上次初始化未完成".to_string());
+        }
+
+        let missing: Vec<&str> = keys
+            .username
+            .into_iter()
+            .chain([keys.password])
+            .filter(|key| {
+                metadata
+                    .and_then(|m| m.get(*key))
+                    .and_then(|v| v.as_str())
+                    .filter(|v| !v.is_empty())
+                    .is_none()
+            })
+            .collect();
+        if !missing.is_empty() {
+            return partial(format!("metadata 中缺少凭据: {}", missing.join(", ")));
+        }
+
+        match verify_credentials() {
+            Some(true) => Self {
+                state: InitializationState::Initialized,
+                reason: "已使用保存的凭据登录成功".to_string(),
+                credentials_verified: true,
+            },
+            Some(false) => partial("服务运行中，但使用保存的凭据无法登录".to_string()),
+            None => Self {
+                state: InitializationState::Initialized,
+                reason: "服务未运行，未验证凭据".to_string(),
+                credentials_verified: false,
+            },
+        }
+    }
+}
+
+/// 将监听地址转换为可连接的地址（0.0.0.0、* 等视为本机）
+pub fn connect_host(bind_address: &str) -> String {
+    let first = bind_address
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .find(|s| !s.is_empty())
+        .unwrap_or("");
+    match first.trim_matches(|c| c == '\'' || c == '"') {
+        "" | "0.0.0.0" | "*" | "::" | "localhost" => "127.0.0.1".to_string(),
+        host => host.to_string(),
+    }
+}
+
+fn resolve(host: &str, port: u16) -> Option<SocketAddr> {
+    (connect_host(host).as_str(), port)
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+}
+
+/// 检查 TCP 端口是否可连接，返回连接耗时（毫秒）
+pub fn check_tcp_port(host: &str, port: u16) -> Option<u64> {
+    let addr = resolve(host, port)?;
+    let start = Instant::now();
+    TcpStream::connect_timeout(&addr, HEALTH_CHECK_TIMEOUT)
+        .ok()
+        .map(|_| start.elapsed().as_millis() as u64)
+}
+
+/// 发送 HTTP HEAD 请求，收到 HTTP 响应即视为正常，返回耗时（毫秒）
+pub fn check_http(host: &str, port: u16) -> Option<u64> {
+    let addr = resolve(host, port)?;
+    let start = Instant::now();
+    let mut stream = TcpStream::connect_timeout(&addr, HEALTH_CHECK_TIMEOUT).ok()?;
+    stream.set_read_timeout(Some(HEALTH_CHECK_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(HEALTH_CHECK_TIMEOUT)).ok()?;
+    stream
+        .write_all(b"HEAD / HTTP/1.0\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .ok()?;
+
+    let mut buf = [0u8; 5];
+    stream.read_exact(&mut buf).ok()?;
+    if &buf == b"HTTP/" {
+        Some(start.elapsed().as_millis() as u64)
+    } else {
+        None
+    }
+}
+
+/// 发送 HTTP GET 请求，返回 2xx 响应的响应体与耗时（毫秒）
+pub fn http_get(host: &str, port: u16, path: &str) -> Option<(String, u64)> {
+    let addr = resolve(host, port)?;
+    let start = Instant::now();
+    let mut stream = TcpStream::connect_timeout(&addr, HEALTH_CHECK_TIMEOUT).ok()?;
+    stream.set_read_timeout(Some(HEALTH_CHECK_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(HEALTH_CHECK_TIMEOUT)).ok()?;
+    let request = format!(
+        "GET {} HTTP/1.0\r\nHost: localhost\r\nAccept: application/json\r\nConnection: close\r\n\r\n",
+        path
+    );
+    stream.write_all(request.as_bytes()).ok()?;
+
+    // HTTP/1.0 + Connection: close，服务端写完响应后关闭连接
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).ok()?;
+    let latency = start.elapsed().as_millis() as u64;
+
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response.split_once("\r\n\r\n")?;
+    let status_code = head.lines().next()?.split_whitespace().nth(1)?;
+    if status_code.starts_with('2') {
+        Some((body.to_string(), latency))
+    } else {
+        None
+    }
+}
+
+/// 执行探测函数并计时，探测成功时返回耗时（毫秒）
+pub fn timed(probe: impl FnOnce() -> bool) -> Option<u64> {
+    let start = Instant::now();
+    if probe() {
+        Some(start.elapsed().as_millis() as u64)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,38 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::time::Duration;
+
+/// 调用服务 HTTP API 的超时时间
+const API_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 调用本机服务 HTTP API 的客户端
+pub fn api_client() -> Result<reqwest::Client> {
+    Ok(reqwest::Client::builder().timeout(API_TIMEOUT).build()?)
+}
+
+/// 解析 JSON 响应，空响应体视为 null
+///
+/// 非 2xx 时用 error_reason 从响应体中取出错误信息，取不到时使用原始响应文本；
+/// api_name 用于错误消息，如 "Vault API"
+pub async fn parse_json_response(
+    response: reqwest::Response,
+    api_name: &str,
+    error_reason: impl FnOnce(&Value) -> Option<String>,
+) -> Result<Value> {
+    let status = response.status();
+    let text = response.text().await?;
+    let body: Value = if text.trim().is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_str(&text).unwrap_or(Value::Null)
+    };
+
+    if !status.is_success() {
+        let reason = error_reason(&body)
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| text.trim().to_string());
+        return Err(anyhow!("{} 返回错误状态 {}: {}", api_name, status, reason));
+    }
+
+    Ok(body)
+}
@@ -1,5 +1,7 @@
 use crate::manager::app_config_manager::AppConfigManager;
-use crate::manager::services::{DownloadManager, DownloadResult, DownloadStatus, DownloadTask};
+use crate::manager::services::{
+    install_callback, remove_partial_install, DownloadManager, DownloadResult, DownloadTask,
+};
 use crate::manager::shell_manamger::ShellManager;
 use crate::types::ServiceData;
 use anyhow::{anyhow, Result};
@@ -128,52 +130,15 @@ impl GradleService {
         let task_id = self.get_gradle_task_id(java_version);
         let download_manager = DownloadManager::global();
         let java_version_for_callback = java_version.to_string();
-
-        let success_callback = Arc::new(move |task: &DownloadTask| {
-            log::info!("Gradle 下载完成: {}", task.filename);
-
-            let task_for_spawn = task.clone();
-            let service_for_spawn = GradleService::global();
-            let java_version_for_spawn = java_version_for_callback.clone();
-
-            tokio::spawn(async move {
-                let download_manager = DownloadManager::global();
-                if let Err(e) = download_manager.update_task_status(
-                    &task_for_spawn.id,
-                    DownloadStatus::Installing,
-                    None,
-                ) {
-                    log::error!("更新任务状态失败: {}", e);
-                }
-
-                match service_for_spawn
-                    .extract_and_install_gradle(&task_for_spawn, &java_version_for_spawn)
-                    .await
-                {
-                    Ok(_) => {
-                        if let Err(e) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            DownloadStatus::Installed,
-                            None,
-                        ) {
-                            log::error!("更新任务状态失败: {}", e);
-                        } else {
-                            log::info!("Gradle 安装成功");
-                        }
-                    }
-                    Err(e) => {
-                        if let Err(update_err) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            DownloadStatus::Failed,
-                            Some(format!("安装失败: {}", e)),
-                        ) {
-                            log::error!("更新任务状态失败: {}", update_err);
-                        }
-                        log::error!("Gradle 安装失败: {}", e);
-                    }
+        let success_callback =
+            install_callback(format!("Gradle (Java {})", java_version), move |task| {
+                let java_version = java_version_for_callback.clone();
+                async move {
+                    GradleService::global()
+                        .extract_and_install_gradle(&task, &java_version)
+                        .await
                 }
             });
-        });
 
         match download_manager
             .start_download(
@@ -207,6 +172,20 @@ impl GradleService {
         &self,
         task: &DownloadTask,
         java_version: &str,
+    ) -> Result<()> {
+        let result = self.install_gradle_from_archive(task, java_version).await;
+        if result.is_err() {
+            // 解压安装失败时清理残缺的安装目录
+            remove_partial_install(&self.get_gradle_install_path(java_version));
+        }
+        result
+    }
+
+    /// 将下载的 Gradle 压缩包解压到安装目录
+    async fn install_gradle_from_archive(
+        &self,
+        task: &DownloadTask,
+        java_version: &str,
     ) -> Result<()> {
         let archive_path = &task.target_path;
         let install_dir = self.get_gradle_install_path(java_version);
@@ -240,15 +219,18 @@ impl GradleService {
         let shell_manager = shell_manager.lock().unwrap();
 
         if let Some(metadata) = &service_data.metadata {
+            let mut update = shell_manager.begin_update();
+
             if let Some(gradle_home) = metadata
                 .get("GRADLE_HOME")
                 .and_then(|v| v.as_str())
                 .map(|v| v.trim())
                 .filter(|v| !v.is_empty())
             {
-                shell_manager.add_export("GRADLE_HOME", gradle_home)?;
                 let gradle_bin = format!("{}/bin", gradle_home);
-                shell_manager.add_path(&gradle_bin)?;
+                update
+                    .add_export("GRADLE_HOME", gradle_home)
+                    .add_path(&gradle_bin);
             }
 
             if let Some(gradle_user_home) = metadata
@@ -257,8 +239,10 @@ impl GradleService {
                 .map(|v| v.trim())
                 .filter(|v| !v.is_empty())
             {
-                shell_manager.add_export("GRADLE_USER_HOME", gradle_user_home)?;
+                update.add_export("GRADLE_USER_HOME", gradle_user_home);
             }
+
+            update.commit()?;
         }
 
         Ok(())
@@ -278,13 +262,16 @@ impl GradleService {
             .filter(|value| !value.is_empty())
             .map(|value| value.to_string());
 
+        let mut update = shell_manager.begin_update();
         if let Some(gradle_home) = metadata_gradle_home {
             let gradle_bin = format!("{}/bin", gradle_home);
-            shell_manager.delete_path(&gradle_bin)?;
+            update.delete_path(&gradle_bin);
         }
 
-        shell_manager.delete_export("GRADLE_HOME")?;
-        shell_manager.delete_export("GRADLE_USER_HOME")?;
+        update
+            .delete_export("GRADLE_HOME")
+            .delete_export("GRADLE_USER_HOME")
+            .commit()?;
 
         Ok(())
     }
@@ -1,14 +1,20 @@
 use crate::manager::app_config_manager::AppConfigManager;
+use crate::manager::env_serv_data_manager::{EnvServDataManager, ServiceDataResult};
 use crate::manager::services::traits::ServiceLifecycle;
-use crate::manager::services::{DownloadManager, DownloadResult, DownloadStatus, DownloadTask};
+use crate::manager::services::{
+    install_callback, remove_partial_install, DownloadManager, DownloadResult, DownloadTask,
+};
 use crate::manager::shell_manamger::ShellManager;
-use crate::types::ServiceData;
+use crate::types::{ServiceData, ServiceDataStatus, ServiceType};
+use crate::utils::http_client_builder;
 use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, OnceLock};
 
-use super::{GradleService, MavenService};
+use super::{GradleService, MavenService, MavenVersion};
 
 /// Java 版本信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +24,14 @@ pub struct JavaVersion {
     pub date: String,
 }
 
+/// 系统中已安装的 JDK
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemJavaInstallation {
+    pub home: String,
+    pub version: Option<String>,
+}
+
 /// 全局 Java 服务管理器单例
 static GLOBAL_JAVA_SERVICE: OnceLock<Arc<JavaService>> = OnceLock::new();
 
@@ -70,13 +84,40 @@ impl JavaService {
 
     /// 检查 Java 是否已安装
     pub fn is_installed(&self, version: &str) -> bool {
-        let install_path = self.get_install_path(version);
-        let java_binary = if cfg!(target_os = "windows") {
-            install_path.join("bin").join("java.exe")
+        Self::java_binary(&self.get_install_path(version)).exists()
+    }
+
+    /// JAVA_HOME 下的 java 可执行文件路径
+    fn java_binary(java_home: &Path) -> PathBuf {
+        if cfg!(target_os = "windows") {
+            java_home.join("bin").join("java.exe")
         } else {
-            install_path.join("bin").join("java")
-        };
-        java_binary.exists()
+            java_home.join("bin").join("java")
+        }
+    }
+
+    /// metadata 中 JAVA_HOME 指向 Envis 安装目录以外的有效 JDK 时返回该路径
+    pub fn external_java_home(&self, service_data: &ServiceData) -> Option<PathBuf> {
+        let java_home = service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("JAVA_HOME"))
+            .and_then(|v| v.as_str())
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)?;
+        if java_home == self.get_install_path(&service_data.version)
+            || !Self::java_binary(&java_home).exists()
+        {
+            return None;
+        }
+        Some(java_home)
+    }
+
+    /// 服务实际使用的 JAVA_HOME：优先使用外部 JDK，否则为 Envis 下载的安装目录
+    fn resolve_java_home(&self, service_data: &ServiceData) -> PathBuf {
+        self.external_java_home(service_data)
+            .unwrap_or_else(|| self.get_install_path(&service_data.version))
     }
 
     /// 获取 Java 安装路径
@@ -127,6 +168,46 @@ impl JavaService {
         Ok((urls, filename))
     }
 
+    /// 通过 Adoptium API 查询当前平台对应的 JDK 下载地址
+    async fn fetch_adoptium_download_url(&self, version: &str) -> Result<String> {
+        let os_name = match std::env::consts::OS {
+            "macos" => "mac",
+            "linux" => "linux",
+            "windows" => "windows",
+            platform => return Err(anyhow!("不支持的平台: {}", platform)),
+        };
+        let arch_name = match std::env::consts::ARCH {
+            "x86_64" => "x64",
+            "aarch64" => "aarch64",
+            arch => return Err(anyhow!("不支持的架构: {}", arch)),
+        };
+
+        let api_url = format!(
+            "https://api.adoptium.net/v3/assets/latest/{}/hotspot?architecture={}&image_type=jdk&os={}&vendor=eclipse",
+            version, arch_name, os_name
+        );
+
+        let client = http_client_builder()?
+            .timeout(std::time::Duration::from_secs(15))
+            .build()?;
+        let response = client.get(&api_url).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Adoptium API 返回错误状态: {}", response.status()));
+        }
+        let body = response.text().await?;
+        let assets: serde_json::Value = serde_json::from_str(&body)?;
+
+        // 只选取 tar.gz / zip 格式的安装包（排除 msi、pkg 等安装器）
+        assets
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|asset| asset.pointer("/binary/package/link").and_then(|v| v.as_str()))
+            .find(|link| link.ends_with(".tar.gz") || link.ends_with(".zip"))
+            .map(|link| link.to_string())
+            .ok_or_else(|| anyhow!("未找到 Java {} 的 {}-{} 安装包", version, os_name, arch_name))
+    }
+
     /// 下载并安装 Java
     pub async fn download_and_install(&self, version: &str) -> Result<DownloadResult> {
         if self.is_installed(version) {
@@ -136,56 +217,24 @@ impl JavaService {
             ));
         }
 
-        let (urls, filename) = self.build_download_info(version)?;
+        let (mut urls, filename) = self.build_download_info(version)?;
+        // 优先使用 Adoptium 官方地址，失败时回退到归档镜像
+        match self.fetch_adoptium_download_url(version).await {
+            Ok(url) => urls.insert(0, url),
+            Err(e) => log::warn!("查询 Adoptium Java {} 下载地址失败，使用备用地址: {}", version, e),
+        }
         let install_path = self.get_install_path(version);
         let task_id = format!("java-{}", version);
         let download_manager = DownloadManager::global();
 
         let version_for_callback = version.to_string();
-        let success_callback = Arc::new(move |task: &DownloadTask| {
-            log::info!("Java {} 下载完成: {}", version_for_callback, task.filename);
-
-            let task_for_spawn = task.clone();
-            let version_for_spawn = version_for_callback.clone();
-            let service_for_spawn = JavaService::global();
-
-            tokio::spawn(async move {
-                let download_manager = DownloadManager::global();
-                if let Err(e) = download_manager.update_task_status(
-                    &task_for_spawn.id,
-                    DownloadStatus::Installing,
-                    None,
-                ) {
-                    log::error!("更新任务状态失败: {}", e);
-                }
-
-                match service_for_spawn
-                    .extract_and_install(&task_for_spawn, &version_for_spawn)
+        let success_callback = install_callback(format!("Java {}", version), move |task| {
+            let version = version_for_callback.clone();
+            async move {
+                JavaService::global()
+                    .extract_and_install(&task, &version)
                     .await
-                {
-                    Ok(_) => {
-                        if let Err(e) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            DownloadStatus::Installed,
-                            None,
-                        ) {
-                            log::error!("更新任务状态失败: {}", e);
-                        } else {
-                            log::info!("Java {} 安装成功", version_for_spawn);
-                        }
-                    }
-                    Err(e) => {
-                        if let Err(update_err) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            DownloadStatus::Failed,
-                            Some(format!("安装失败: {}", e)),
-                        ) {
-                            log::error!("更新任务状态失败: {}", update_err);
-                        }
-                        log::error!("Java {} 安装失败: {}", version_for_spawn, e);
-                    }
-                }
-            });
+            }
         });
 
         match download_manager
@@ -215,6 +264,16 @@ impl JavaService {
 
     /// 解压和安装 Java
     pub async fn extract_and_install(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let result = self.install_from_archive(task, version).await;
+        if result.is_err() {
+            // 解压安装失败时清理残缺的安装目录
+            remove_partial_install(&self.get_install_path(version));
+        }
+        result
+    }
+
+    /// 将下载的压缩包解压到安装目录
+    async fn install_from_archive(&self, task: &DownloadTask, version: &str) -> Result<()> {
         let archive_path = &task.target_path;
         let install_dir = self.get_install_path(version);
 
@@ -231,6 +290,9 @@ impl JavaService {
             return Err(anyhow!("不支持的压缩格式"));
         }
 
+        // macOS 的 JDK 包结构为 Contents/Home/bin，将 Home 目录内容提升到安装目录
+        flatten_macos_jdk_home(&install_dir)?;
+
         #[cfg(not(target_os = "windows"))]
         set_executable_permissions(&install_dir)?;
 
@@ -255,9 +317,9 @@ impl JavaService {
 
     /// 激活服务
     pub fn activate_service(&self, service_data: &ServiceData) -> Result<()> {
-        let install_path = self.get_install_path(&service_data.version);
+        let install_path = self.resolve_java_home(service_data);
 
-        if !self.is_installed(&service_data.version) {
+        if !Self::java_binary(&install_path).exists() {
             return Err(anyhow!("Java {} 未安装", service_data.version));
         }
 
@@ -270,15 +332,17 @@ impl JavaService {
             
             let bin_path = install_path.join("bin").to_string_lossy().to_string();
 
-            shell_manager.add_export("JAVA_HOME", &java_home)?;
-            shell_manager.add_path(&bin_path)?;
+            let mut update = shell_manager.begin_update();
+            update.add_export("JAVA_HOME", &java_home).add_path(&bin_path);
 
             // 设置 JAVA_OPTS
             if let Some(metadata) = &service_data.metadata {
                 if let Some(java_opts) = metadata.get("JAVA_OPTS").and_then(|v| v.as_str()) {
-                    shell_manager.add_export("JAVA_OPTS", java_opts)?;
+                    update.add_export("JAVA_OPTS", java_opts);
                 }
             }
+
+            update.commit()?;
         } // shell_manager 锁在这里释放
 
         // 激活 Maven 服务（不再持有 shell_manager 锁）
@@ -295,7 +359,7 @@ impl JavaService {
 
     /// 取消激活服务
     pub fn deactivate_service(&self, service_data: &ServiceData) -> Result<()> {
-        let install_path = self.get_install_path(&service_data.version);
+        let install_path = self.resolve_java_home(service_data);
 
         // 先取消激活子服务（不持有 shell_manager 锁）
         let maven_service = MavenService::global();
@@ -309,16 +373,21 @@ impl JavaService {
             let shell_manager = ShellManager::global();
             let shell_manager = shell_manager.lock().unwrap();
 
-            // Windows 平台 java.exe 直接在根目录，其他平台在 bin 子文件夹
-            let bin_path = if cfg!(target_os = "windows") {
-                install_path.to_string_lossy().to_string()
-            } else {
-                install_path.join("bin").to_string_lossy().to_string()
-            };
-
-            shell_manager.delete_path(&bin_path)?;
-            shell_manager.delete_export("JAVA_HOME")?;
-            shell_manager.delete_export("JAVA_OPTS")?;
+            // 与激活时一致，移除 JAVA_HOME/bin；同时清理 Envis 安装目录的 bin，避免切换 JAVA_HOME 后残留
+            let bin_path = install_path.join("bin").to_string_lossy().to_string();
+            let default_bin_path = self
+                .get_install_path(&service_data.version)
+                .join("bin")
+                .to_string_lossy()
+                .to_string();
+
+            shell_manager
+                .begin_update()
+                .delete_path(&bin_path)
+                .delete_path(&default_bin_path)
+                .delete_export("JAVA_HOME")
+                .delete_export("JAVA_OPTS")
+                .commit()?;
         } // shell_manager 锁在这里释放
 
         log::info!("Java {} 服务已取消激活", service_data.version);
@@ -327,18 +396,13 @@ impl JavaService {
 
     /// 获取 Java 版本信息
     pub fn get_java_info(&self, service_data: &ServiceData) -> Result<serde_json::Value> {
-        let install_path = self.get_install_path(&service_data.version);
+        let install_path = self.resolve_java_home(service_data);
+        let java_binary = Self::java_binary(&install_path);
 
-        if !self.is_installed(&service_data.version) {
+        if !java_binary.exists() {
             return Err(anyhow!("Java {} 未安装", service_data.version));
         }
 
-        let java_binary = if cfg!(target_os = "windows") {
-            install_path.join("bin").join("java.exe")
-        } else {
-            install_path.join("bin").join("java")
-        };
-
         let output = crate::utils::create_command(java_binary.to_str().unwrap_or("java"))
             .arg("-version")
             .output()?;
@@ -372,6 +436,171 @@ impl JavaService {
         }))
     }
 
+    /// 获取服务当前使用的 JAVA_HOME
+    pub fn get_java_home(
+        &self,
+        environment_id: &str,
+        service_data_id: &str,
+    ) -> Result<ServiceDataResult> {
+        let service_data = EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .get_service_data(environment_id, service_data_id)?;
+        let java_home = self.resolve_java_home(&service_data);
+        let external = self.external_java_home(&service_data).is_some();
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "获取 JAVA_HOME 成功".to_string(),
+            data: Some(serde_json::json!({
+                "javaHome": java_home.to_string_lossy(),
+                "external": external,
+                "installed": Self::java_binary(&java_home).exists(),
+            })),
+        })
+    }
+
+    /// 将 JAVA_HOME 指向已有的 JDK 安装目录，服务已激活时同步更新终端配置
+    pub fn set_java_home(
+        &self,
+        environment_id: &str,
+        service_data_id: &str,
+        java_home_path: String,
+    ) -> Result<ServiceDataResult> {
+        let java_home = PathBuf::from(java_home_path.trim());
+        if !Self::java_binary(&java_home).exists() {
+            return Err(anyhow!(
+                "{} 不是有效的 JDK 目录：未找到 {}",
+                java_home.to_string_lossy(),
+                Self::java_binary(Path::new("")).to_string_lossy()
+            ));
+        }
+
+        let env_serv_data_manager = EnvServDataManager::global();
+        let env_serv_data_manager = env_serv_data_manager.lock().unwrap();
+        let mut service_data =
+            env_serv_data_manager.get_service_data(environment_id, service_data_id)?;
+        if service_data.service_type != ServiceType::Java {
+            return Err(anyhow!("服务 {} 不是 Java 服务", service_data.name));
+        }
+
+        let old_java_home = self.resolve_java_home(&service_data);
+        env_serv_data_manager.set_metadata(
+            environment_id,
+            &mut service_data,
+            "JAVA_HOME",
+            serde_json::Value::String(java_home.to_string_lossy().to_string()),
+        )?;
+
+        if matches!(service_data.status, ServiceDataStatus::Active) {
+            let old_bin_path = old_java_home.join("bin").to_string_lossy().to_string();
+            let bin_path = java_home.join("bin").to_string_lossy().to_string();
+            let shell_manager = ShellManager::global();
+            let shell_manager = shell_manager.lock().unwrap();
+            shell_manager
+                .begin_update()
+                .delete_path(&old_bin_path)
+                .add_export("JAVA_HOME", &java_home.to_string_lossy())
+                .add_path(&bin_path)
+                .commit()?;
+        }
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "JAVA_HOME 设置成功".to_string(),
+            data: Some(serde_json::json!({
+                "javaHome": java_home.to_string_lossy(),
+                "version": Self::detect_java_version(&java_home),
+            })),
+        })
+    }
+
+    /// 扫描常见安装位置中的 JDK（不包含 Envis 下载的版本）
+    pub fn detect_system_java_installations(&self) -> Vec<SystemJavaInstallation> {
+        let services_java_folder = self.get_install_path("");
+        let mut candidates: Vec<PathBuf> = Vec::new();
+
+        if let Ok(java_home) = std::env::var("JAVA_HOME") {
+            candidates.push(PathBuf::from(java_home));
+        }
+        for parent in Self::java_search_dirs() {
+            let Ok(entries) = std::fs::read_dir(&parent) else {
+                continue;
+            };
+            for entry in entries.filter_map(|e| e.ok()) {
+                let path = entry.path();
+                // macOS 的 JDK 位于 <name>.jdk/Contents/Home
+                let mac_home = path.join("Contents").join("Home");
+                candidates.push(if mac_home.is_dir() { mac_home } else { path });
+            }
+        }
+
+        let mut seen = HashSet::new();
+        let mut installations: Vec<SystemJavaInstallation> = candidates
+            .into_iter()
+            .filter(|home| Self::java_binary(home).exists())
+            .filter(|home| !home.starts_with(&services_java_folder))
+            .filter(|home| seen.insert(std::fs::canonicalize(home).unwrap_or(home.clone())))
+            .map(|home| SystemJavaInstallation {
+                version: Self::detect_java_version(&home),
+                home: home.to_string_lossy().to_string(),
+            })
+            .collect();
+        installations.sort_by(|a, b| a.home.cmp(&b.home));
+        installations
+    }
+
+    /// 各平台包管理器与安装程序的常见 JDK 安装目录
+    fn java_search_dirs() -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        if cfg!(target_os = "windows") {
+            for program_files in ["ProgramFiles", "ProgramW6432"] {
+                if let Ok(root) = std::env::var(program_files) {
+                    let root = PathBuf::from(root);
+                    for vendor in [
+                        "Java",
+                        "Eclipse Adoptium",
+                        "Microsoft",
+                        "Zulu",
+                        "Amazon Corretto",
+                        "BellSoft",
+                    ] {
+                        dirs.push(root.join(vendor));
+                    }
+                }
+            }
+        } else if cfg!(target_os = "macos") {
+            dirs.push(PathBuf::from("/Library/Java/JavaVirtualMachines"));
+            if let Some(home) = dirs::home_dir() {
+                dirs.push(home.join("Library/Java/JavaVirtualMachines"));
+            }
+        } else {
+            dirs.push(PathBuf::from("/usr/lib/jvm"));
+            dirs.push(PathBuf::from("/usr/java"));
+            dirs.push(PathBuf::from("/opt/java"));
+        }
+        if let Some(home) = dirs::home_dir() {
+            // SDKMAN! 与 IntelliJ IDEA 下载的 JDK
+            dirs.push(home.join(".sdkman").join("candidates").join("java"));
+            dirs.push(home.join(".jdks"));
+        }
+        dirs
+    }
+
+    /// 执行 `java -version` 并解析版本号
+    fn detect_java_version(java_home: &Path) -> Option<String> {
+        let output = crate::utils::create_command(Self::java_binary(java_home))
+            .arg("-version")
+            .output()
+            .ok()?;
+        // java -version 输出到 stderr
+        String::from_utf8_lossy(&output.stderr)
+            .lines()
+            .find(|line| line.contains("version"))
+            .and_then(|line| line.split('"').nth(1))
+            .map(|v| v.to_string())
+    }
+
     // ─── Maven 代理方法 ───────────────────────────────────────────────────────
 
     /// 检查 Maven 是否已安装（代理方法）
@@ -384,6 +613,21 @@ impl JavaService {
         MavenService::global().get_maven_home(java_version)
     }
 
+    /// 获取各 Java 版本对应的 Maven 版本及安装状态
+    pub fn get_maven_versions(&self) -> Vec<MavenVersion> {
+        let maven_service = MavenService::global();
+        self.get_available_versions()
+            .into_iter()
+            .map(|java| MavenVersion {
+                version: maven_service
+                    .get_maven_version_for_java(&java.version)
+                    .to_string(),
+                installed: maven_service.is_maven_installed(&java.version),
+                java_version: java.version,
+            })
+            .collect()
+    }
+
     /// 下载并安装 Maven（代理方法）
     pub async fn download_and_install_maven(&self, java_version: &str) -> Result<DownloadResult> {
         MavenService::global()
@@ -453,6 +697,44 @@ impl ServiceLifecycle for JavaService {
     ) -> Result<()> {
         self.deactivate_service(service_data)
     }
+
+    fn is_installed(&self, version: &str) -> bool {
+        JavaService::is_installed(self, version)
+    }
+
+    fn available_versions(&self) -> Vec<String> {
+        self.get_available_versions()
+            .into_iter()
+            .map(|v| v.version)
+            .collect()
+    }
+
+    fn download_and_install<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<DownloadResult>> {
+        Box::pin(JavaService::download_and_install(self, version))
+    }
+
+    fn cancel_download(&self, version: &str) -> Result<()> {
+        JavaService::cancel_download(self, version)
+    }
+
+    fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        JavaService::get_download_progress(self, version)
+    }
+
+    fn archive_dir(&self, version: &str) -> Option<PathBuf> {
+        Some(self.get_install_path(version))
+    }
+
+    fn extract_and_install<'a>(
+        &'a self,
+        task: &'a DownloadTask,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(JavaService::extract_and_install(self, task, version))
+    }
 }
 
 // ─── 共享工具方法 ───────────────────────────────────────────────────────────
@@ -516,6 +798,29 @@ pub(crate) fn flatten_single_subdir(target_dir: &PathBuf) -> Result<()> {
     Ok(())
 }
 
+/// 若存在 macOS JDK 的 Contents/Home 结构，则将 Home 中的内容提升到 install_dir
+fn flatten_macos_jdk_home(install_dir: &Path) -> Result<()> {
+    let home_dir = install_dir.join("Contents").join("Home");
+    if !home_dir.join("bin").exists() {
+        return Ok(());
+    }
+
+    for entry in std::fs::read_dir(&home_dir)
+        .map_err(|e| anyhow!("读取 JDK Home 目录失败: {}", e))?
+    {
+        let entry = entry.map_err(|e| anyhow!("读取目录条目失败: {}", e))?;
+        let src = entry.path();
+        let dest = install_dir.join(entry.file_name());
+        std::fs::rename(&src, &dest)
+            .map_err(|e| anyhow!("移动 {:?} 到 {:?} 失败: {}", src, dest, e))?;
+    }
+
+    std::fs::remove_dir_all(install_dir.join("Contents"))
+        .map_err(|e| anyhow!("删除 Contents 目录失败: {}", e))?;
+
+    Ok(())
+}
+
 /// 解压 zip 格式文件
 pub(crate) async fn extract_zip(archive_path: &PathBuf, target_dir: &PathBuf) -> Result<()> {
     use std::fs::File;
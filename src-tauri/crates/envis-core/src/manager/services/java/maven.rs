@@ -1,12 +1,24 @@
 use crate::manager::app_config_manager::AppConfigManager;
-use crate::manager::services::{DownloadManager, DownloadResult, DownloadStatus, DownloadTask};
+use crate::manager::services::{
+    install_callback, remove_partial_install, DownloadManager, DownloadResult, DownloadTask,
+};
 use crate::manager::shell_manamger::ShellManager;
 use crate::types::ServiceData;
 use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::{Arc, OnceLock};
 use xmltree::{Element, EmitterConfig, XMLNode};
 
+/// Maven 版本信息（Maven 版本由 Java 版本决定）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MavenVersion {
+    pub java_version: String,
+    pub version: String,
+    pub installed: bool,
+}
+
 /// 全局 Maven 服务管理器单例
 static GLOBAL_MAVEN_SERVICE: OnceLock<Arc<MavenService>> = OnceLock::new();
 
@@ -42,7 +54,8 @@ impl MavenService {
         17
     }
 
-    fn get_maven_version_for_java(&self, java_version: &str) -> &'static str {
+    /// 获取与 Java 版本匹配的 Maven 版本
+    pub fn get_maven_version_for_java(&self, java_version: &str) -> &'static str {
         let major = self.parse_java_major_version(java_version);
         if major <= 8 {
             Self::MAVEN_VERSION_FOR_JAVA_8
@@ -325,52 +338,15 @@ impl MavenService {
         let task_id = self.get_maven_task_id(java_version);
         let download_manager = DownloadManager::global();
         let java_version_for_callback = java_version.to_string();
-
-        let success_callback = Arc::new(move |task: &DownloadTask| {
-            log::info!("Maven 下载完成: {}", task.filename);
-
-            let task_for_spawn = task.clone();
-            let service_for_spawn = MavenService::global();
-            let java_version_for_spawn = java_version_for_callback.clone();
-
-            tokio::spawn(async move {
-                let download_manager = DownloadManager::global();
-                if let Err(e) = download_manager.update_task_status(
-                    &task_for_spawn.id,
-                    DownloadStatus::Installing,
-                    None,
-                ) {
-                    log::error!("更新任务状态失败: {}", e);
-                }
-
-                match service_for_spawn
-                    .extract_and_install_maven(&task_for_spawn, &java_version_for_spawn)
-                    .await
-                {
-                    Ok(_) => {
-                        if let Err(e) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            DownloadStatus::Installed,
-                            None,
-                        ) {
-                            log::error!("更新任务状态失败: {}", e);
-                        } else {
-                            log::info!("Maven 安装成功");
-                        }
-                    }
-                    Err(e) => {
-                        if let Err(update_err) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            DownloadStatus::Failed,
-                            Some(format!("安装失败: {}", e)),
-                        ) {
-                            log::error!("更新任务状态失败: {}", update_err);
-                        }
-                        log::error!("Maven 安装失败: {}", e);
-                    }
+        let success_callback =
+            install_callback(format!("Maven (Java {})", java_version), move |task| {
+                let java_version = java_version_for_callback.clone();
+                async move {
+                    MavenService::global()
+                        .extract_and_install_maven(&task, &java_version)
+                        .await
                 }
             });
-        });
 
         match download_manager
             .start_download(
@@ -404,6 +380,20 @@ impl MavenService {
         &self,
         task: &DownloadTask,
         java_version: &str,
+    ) -> Result<()> {
+        let result = self.install_maven_from_archive(task, java_version).await;
+        if result.is_err() {
+            // 解压安装失败时清理残缺的安装目录
+            remove_partial_install(&self.get_maven_install_path(java_version));
+        }
+        result
+    }
+
+    /// 将下载的 Maven 压缩包解压到安装目录
+    async fn install_maven_from_archive(
+        &self,
+        task: &DownloadTask,
+        java_version: &str,
     ) -> Result<()> {
         let archive_path = &task.target_path;
         let install_dir = self.get_maven_install_path(java_version);
@@ -442,14 +432,7 @@ impl MavenService {
 
     /// 激活 Maven 服务（设置环境变量）
     pub fn activate(&self, java_version: &str, service_data: &ServiceData) -> Result<()> {
-        let metadata_maven_home = service_data
-            .metadata
-            .as_ref()
-            .and_then(|metadata| metadata.get("MAVEN_HOME"))
-            .and_then(|value| value.as_str())
-            .map(|value| value.trim())
-            .filter(|value| !value.is_empty())
-            .map(|value| value.to_string());
+        let metadata_maven_home = self.resolve_maven_home(java_version, service_data);
 
         let metadata_maven_repo_url = service_data
             .metadata
@@ -474,21 +457,26 @@ impl MavenService {
             let shell_manager = ShellManager::global();
             let shell_manager = shell_manager.lock().unwrap();
 
+            let mut update = shell_manager.begin_update();
+
             // 设置 MAVEN_HOME
             if let Some(maven_home) = &metadata_maven_home {
-                shell_manager.add_export("MAVEN_HOME", maven_home)?;
-                shell_manager.add_export("M2_HOME", maven_home)?;
                 let maven_bin = format!("{}/bin", maven_home);
-                shell_manager.add_path(&maven_bin)?;
+                update
+                    .add_export("MAVEN_HOME", maven_home)
+                    .add_export("M2_HOME", maven_home)
+                    .add_path(&maven_bin);
             }
 
             // 设置 MAVEN_REPO_URL
-            shell_manager.add_export("MAVEN_REPO_URL", metadata_maven_repo_url)?;
+            update.add_export("MAVEN_REPO_URL", metadata_maven_repo_url);
 
             // 设置 MAVEN_LOCAL_REPO
             if let Some(local_repo) = &metadata_maven_local_repo {
-                shell_manager.add_export("MAVEN_LOCAL_REPO", local_repo)?;
+                update.add_export("MAVEN_LOCAL_REPO", local_repo);
             }
+
+            update.commit()?;
         } // shell_manager 锁在这里释放
 
         // 统一设置 settings.xml 中的环境变量占位符（文件操作，不需要锁）
@@ -499,29 +487,58 @@ impl MavenService {
         Ok(())
     }
 
-    /// 取消激活 Maven 服务（删除环境变量）
-    pub fn deactivate(&self, service_data: &ServiceData) -> Result<()> {
+    /// 获取 Maven 仓库地址，metadata 中未设置时使用默认地址
+    pub fn get_maven_repository(&self, service_data: &ServiceData) -> String {
+        service_data
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get("MAVEN_REPO_URL"))
+            .and_then(|value| value.as_str())
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .unwrap_or(Self::DEFAULT_MAVEN_REPO_URL)
+            .to_string()
+    }
+
+    /// 更新已激活服务的 MAVEN_REPO_URL 环境变量（settings.xml 通过占位符引用）
+    pub fn apply_maven_repository(&self, repository_url: &str) -> Result<()> {
         let shell_manager = ShellManager::global();
         let shell_manager = shell_manager.lock().unwrap();
+        shell_manager.add_export("MAVEN_REPO_URL", repository_url)
+    }
 
-        let metadata_maven_home = service_data
+    /// MAVEN_HOME：优先使用 metadata 中的设置，否则使用随 Java 安装的 Maven
+    fn resolve_maven_home(&self, java_version: &str, service_data: &ServiceData) -> Option<String> {
+        service_data
             .metadata
             .as_ref()
             .and_then(|metadata| metadata.get("MAVEN_HOME"))
             .and_then(|value| value.as_str())
             .map(|value| value.trim())
             .filter(|value| !value.is_empty())
-            .map(|value| value.to_string());
+            .map(|value| value.to_string())
+            .or_else(|| self.get_maven_home(java_version))
+    }
+
+    /// 取消激活 Maven 服务（删除环境变量）
+    pub fn deactivate(&self, service_data: &ServiceData) -> Result<()> {
+        let shell_manager = ShellManager::global();
+        let shell_manager = shell_manager.lock().unwrap();
+
+        let metadata_maven_home = self.resolve_maven_home(&service_data.version, service_data);
 
+        let mut update = shell_manager.begin_update();
         if let Some(maven_home) = metadata_maven_home {
             let maven_bin = format!("{}/bin", maven_home);
-            shell_manager.delete_path(&maven_bin)?;
+            update.delete_path(&maven_bin);
         }
 
-        shell_manager.delete_export("MAVEN_HOME")?;
-        shell_manager.delete_export("M2_HOME")?;
-        shell_manager.delete_export("MAVEN_REPO_URL")?;
-        shell_manager.delete_export("MAVEN_LOCAL_REPO")?;
+        update
+            .delete_export("MAVEN_HOME")
+            .delete_export("M2_HOME")
+            .delete_export("MAVEN_REPO_URL")
+            .delete_export("MAVEN_LOCAL_REPO")
+            .commit()?;
 
         Ok(())
     }
@@ -4,4 +4,4 @@ pub mod maven;
 
 pub use gradle::GradleService;
 pub use java::{JavaService, JavaVersion};
-pub use maven::MavenService;
+pub use maven::{MavenService, MavenVersion};
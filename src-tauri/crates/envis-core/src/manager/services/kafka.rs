@@ -0,0 +1,857 @@
+use crate::manager::app_config_manager::AppConfigManager;
+use crate::manager::builders::{metadata_number, metadata_port};
+use crate::manager::env_serv_data_manager::ServiceDataResult;
+use crate::manager::service_manager::ServiceManager;
+use crate::manager::service_supervisor::ServiceSupervisor;
+use crate::manager::services::archive::extract_tar_strip_root;
+use crate::manager::services::health::{self, ServiceHealth};
+use crate::manager::services::launch::OutputCapture;
+use crate::manager::services::traits::ServiceLifecycle;
+use crate::manager::services::{
+    download_service_package, remove_partial_install, shutdown, DownloadManager, DownloadResult,
+    DownloadTask,
+};
+use crate::types::{ServiceData, ServiceType};
+use crate::utils::create_command;
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KafkaVersion {
+    pub version: String,
+    pub date: String,
+}
+
+/// Kafka topic 分区信息（来自 `kafka-topics --describe`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaPartition {
+    pub partition: u32,
+    pub leader: Option<i64>,
+    pub replicas: Vec<i64>,
+    pub isr: Vec<i64>,
+}
+
+/// Kafka topic 详情
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaTopic {
+    pub topic: String,
+    pub topic_id: Option<String>,
+    pub partition_count: Option<u32>,
+    pub replication_factor: Option<u32>,
+    pub configs: String,
+    pub partitions: Vec<KafkaPartition>,
+}
+
+/// 默认 broker 端口
+pub const DEFAULT_KAFKA_PORT: u16 = 9092;
+/// 默认 KRaft controller 端口
+pub const DEFAULT_KAFKA_CONTROLLER_PORT: u16 = 9093;
+/// 默认 JVM 堆大小（MB）
+pub const DEFAULT_KAFKA_HEAP_MB: u32 = 512;
+/// 发行包使用的 Scala 版本
+const KAFKA_SCALA_VERSION: &str = "2.13";
+/// 仅监听本机
+const KAFKA_HOST: &str = "127.0.0.1";
+/// 启动后等待 broker 端口就绪的最长时间
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+static GLOBAL_KAFKA_SERVICE: OnceLock<Arc<KafkaService>> = OnceLock::new();
+
+/// Kafka 服务：以 KRaft 模式运行单节点（broker + controller），无需 ZooKeeper
+pub struct KafkaService {}
+
+impl KafkaService {
+    pub fn global() -> Arc<KafkaService> {
+        GLOBAL_KAFKA_SERVICE
+            .get_or_init(|| Arc::new(KafkaService::new()))
+            .clone()
+    }
+
+    fn new() -> Self {
+        Self {}
+    }
+
+    pub fn get_available_versions(&self) -> Vec<KafkaVersion> {
+        vec![
+            KafkaVersion {
+                version: "4.0.0".to_string(),
+                date: "2025-03-18".to_string(),
+            },
+            KafkaVersion {
+                version: "3.9.1".to_string(),
+                date: "2025-05-21".to_string(),
+            },
+            KafkaVersion {
+                version: "3.8.1".to_string(),
+                date: "2024-10-29".to_string(),
+            },
+        ]
+    }
+
+    pub fn is_installed(&self, version: &str) -> bool {
+        self.get_script_path(version, "kafka-server-start").exists()
+    }
+
+    fn get_install_path(&self, version: &str) -> PathBuf {
+        let services_folder = {
+            let app_config_manager = AppConfigManager::global();
+            let app_config_manager = app_config_manager.lock().unwrap();
+            PathBuf::from(app_config_manager.get_services_folder())
+        };
+        services_folder.join("kafka").join(version)
+    }
+
+    fn get_service_data_folder(&self, environment_id: &str, version: &str) -> PathBuf {
+        let app_config_manager = AppConfigManager::global();
+        let app_config_manager = app_config_manager.lock().unwrap();
+        let envs_folder = app_config_manager.get_envs_folder();
+
+        PathBuf::from(envs_folder)
+            .join(environment_id)
+            .join("kafka")
+            .join(version)
+    }
+
+    /// 发行包自带的脚本：Unix 为 bin/<name>.sh，Windows 为 bin/windows/<name>.bat
+    fn get_script_path(&self, version: &str, name: &str) -> PathBuf {
+        let bin_dir = self.get_install_path(version).join("bin");
+        if cfg!(target_os = "windows") {
+            bin_dir.join("windows").join(format!("{}.bat", name))
+        } else {
+            bin_dir.join(format!("{}.sh", name))
+        }
+    }
+
+    fn build_download_info(&self, version: &str) -> Result<(Vec<String>, String)> {
+        let filename = format!("kafka_{}-{}.tgz", KAFKA_SCALA_VERSION, version);
+        // downloads.apache.org 只保留最新版本，旧版本从 archive 下载
+        let urls = vec![
+            format!(
+                "https://downloads.apache.org/kafka/{}/{}",
+                version, filename
+            ),
+            format!(
+                "https://archive.apache.org/dist/kafka/{}/{}",
+                version, filename
+            ),
+        ];
+
+        Ok((urls, filename))
+    }
+
+    pub async fn download_and_install(&self, version: &str) -> Result<DownloadResult> {
+        if self.is_installed(version) {
+            return Ok(DownloadResult::success(
+                format!("Kafka {} 已经安装", version),
+                None,
+            ));
+        }
+
+        let (urls, filename) = self.build_download_info(version)?;
+        download_service_package(
+            ServiceType::Kafka,
+            version,
+            urls,
+            self.get_install_path(version),
+            filename,
+        )
+        .await
+    }
+
+    pub async fn extract_and_install(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let result = self.install_from_archive(task, version);
+        if result.is_err() {
+            // 解压安装失败时清理残缺的安装目录
+            remove_partial_install(&self.get_install_path(version));
+        }
+        result
+    }
+
+    /// 将下载的 tgz 解压到安装目录（去掉顶层的 kafka_<scala>-<version> 目录）
+    fn install_from_archive(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let archive_path = &task.target_path;
+        let install_dir = self.get_install_path(version);
+        fs::create_dir_all(&install_dir)?;
+
+        extract_tar_strip_root(archive_path, &install_dir)?;
+
+        if !self.is_installed(version) {
+            return Err(anyhow!("未找到 kafka-server-start 启动脚本"));
+        }
+
+        if archive_path.exists() {
+            fs::remove_file(archive_path)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn cancel_download(&self, version: &str) -> Result<()> {
+        let task_id = format!("kafka-{}", version);
+        DownloadManager::global().cancel_download(&task_id)
+    }
+
+    pub fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        let task_id = format!("kafka-{}", version);
+        DownloadManager::global().get_task_status(&task_id)
+    }
+
+    pub fn get_service_status(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let config = self.get_runtime_config(environment_id, service_data)?;
+        let health = self.check_health(&config);
+        let running = health.process || health.port_open;
+
+        let mut data = serde_json::json!({
+            "isRunning": running,
+            "port": config.port,
+            "controllerPort": config.controller_port,
+            "heapMb": config.heap_mb,
+            "pid": shutdown::read_running_pid(&config.pid_path),
+            "configPath": config.config_path,
+            "dataPath": config.data_dir,
+            "logPath": config.logs_dir,
+        });
+        health.merge_into(&mut data);
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "获取 Kafka 状态成功".to_string(),
+            data: Some(data),
+        })
+    }
+
+    /// 生成 server.properties，首次启动前格式化 KRaft 存储目录，然后以前台方式启动：
+    /// `KAFKA_HEAP_OPTS=... LOG_DIR=<env>/logs kafka-server-start <env>/config/server.properties`
+    pub fn start_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        ServiceSupervisor::global().clear_intentional_stop(&service_data.id);
+        let version = &service_data.version;
+        let start_script = self.get_script_path(version, "kafka-server-start");
+
+        if !start_script.exists() {
+            return Ok(ServiceDataResult {
+                success: false,
+                message: "kafka-server-start 启动脚本不存在".to_string(),
+                data: None,
+            });
+        }
+
+        let config = self.get_runtime_config(environment_id, service_data)?;
+
+        if let Some(pid) = shutdown::read_running_pid(&config.pid_path) {
+            return Ok(ServiceDataResult {
+                success: true,
+                message: "Kafka 已在运行".to_string(),
+                data: Some(serde_json::json!({
+                    "port": config.port,
+                    "pid": pid,
+                    "alreadyRunning": true
+                })),
+            });
+        }
+
+        for port in [config.port, config.controller_port] {
+            if health::check_tcp_port(KAFKA_HOST, port).is_some() {
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: format!("端口 {} 已被占用", port),
+                    data: None,
+                });
+            }
+        }
+
+        self.write_config_file(&config)?;
+        if let Err(e) = self.format_storage(version, &config) {
+            return Ok(ServiceDataResult {
+                success: false,
+                message: e.to_string(),
+                data: None,
+            });
+        }
+        if let Some(parent) = config.pid_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let service_data_folder = self.get_service_data_folder(environment_id, version);
+        let capture = OutputCapture::new(&service_data_folder, "kafka")?;
+        let mut command = self.script_command(version, "kafka-server-start")?;
+        command
+            .arg(&config.config_path)
+            .env(
+                "KAFKA_HEAP_OPTS",
+                format!("-Xms{0}m -Xmx{0}m", config.heap_mb),
+            )
+            .env("LOG_DIR", &config.logs_dir)
+            .current_dir(self.get_install_path(version));
+        capture.attach(&mut command)?;
+
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: format!("启动失败: {}", e),
+                    data: None,
+                })
+            }
+        };
+        let pid = child.id();
+        fs::write(&config.pid_path, pid.to_string())?;
+
+        // 等待 broker 端口就绪或进程退出
+        let deadline = Instant::now() + STARTUP_TIMEOUT;
+        while Instant::now() < deadline {
+            if health::check_tcp_port(KAFKA_HOST, config.port).is_some() {
+                log::info!("Kafka 已启动，PID: {}", pid);
+                return Ok(ServiceDataResult {
+                    success: true,
+                    message: "Kafka 启动成功".to_string(),
+                    data: Some(serde_json::json!({
+                        "port": config.port,
+                        "pid": pid,
+                    })),
+                });
+            }
+            if let Ok(Some(status)) = child.try_wait() {
+                let _ = fs::remove_file(&config.pid_path);
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: format!("Kafka 启动失败: {}", capture.failure_detail(&status)),
+                    data: Some(serde_json::json!({
+                        "logPath": capture.path(),
+                    })),
+                });
+            }
+            std::thread::sleep(Duration::from_millis(500));
+        }
+
+        Ok(ServiceDataResult {
+            success: false,
+            message: format!(
+                "Kafka 进程已启动，但端口 {} 尚未就绪，请检查日志: {}",
+                config.port,
+                capture.path().to_string_lossy()
+            ),
+            data: Some(serde_json::json!({
+                "port": config.port,
+                "pid": pid,
+                "logPath": capture.path(),
+            })),
+        })
+    }
+
+    pub fn stop_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        ServiceSupervisor::global().mark_intentional_stop(&service_data.id);
+        let config = self.get_runtime_config(environment_id, service_data)?;
+
+        // broker 关闭时需要刷盘并通知 controller，等待时间比普通进程长
+        Ok(shutdown::stop_pid_file_process(
+            "Kafka",
+            &config.pid_path,
+            "TERM",
+            Duration::from_secs(10),
+        ))
+    }
+
+    pub fn restart_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let _ = self.stop_service(environment_id, service_data);
+        std::thread::sleep(Duration::from_millis(300));
+        self.start_service(environment_id, service_data)
+    }
+
+    /// 创建 topic
+    pub fn create_kafka_topic(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        topic: &str,
+        partitions: u32,
+        replication_factor: u32,
+    ) -> Result<ServiceDataResult> {
+        let topic = Self::validate_topic_name(topic)?;
+        if partitions == 0 || replication_factor == 0 {
+            return Err(anyhow!("分区数和副本数必须大于 0"));
+        }
+
+        self.run_topics_command(
+            environment_id,
+            service_data,
+            &[
+                "--create",
+                "--topic",
+                &topic,
+                "--partitions",
+                &partitions.to_string(),
+                "--replication-factor",
+                &replication_factor.to_string(),
+            ],
+        )?;
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("已创建 topic {}", topic),
+            data: Some(serde_json::json!({
+                "topic": topic,
+                "partitions": partitions,
+                "replicationFactor": replication_factor,
+            })),
+        })
+    }
+
+    /// 列出所有 topic（包含内部 topic，如 __consumer_offsets），按名称排序
+    pub fn list_kafka_topics(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let stdout = self.run_topics_command(environment_id, service_data, &["--list"])?;
+        let mut topics: Vec<String> = stdout
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(|line| line.to_string())
+            .collect();
+        topics.sort();
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "获取 Kafka topic 列表成功".to_string(),
+            data: Some(serde_json::json!({ "topics": topics })),
+        })
+    }
+
+    /// 删除单个 topic，不允许正则匹配批量删除
+    pub fn delete_kafka_topic(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        topic: &str,
+    ) -> Result<ServiceDataResult> {
+        let topic = Self::validate_topic_name(topic)?;
+        self.run_topics_command(
+            environment_id,
+            service_data,
+            &["--delete", "--topic", &topic],
+        )?;
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("已删除 topic {}", topic),
+            data: Some(serde_json::json!({ "topic": topic })),
+        })
+    }
+
+    /// 查看 topic 的分区、leader 与副本信息
+    pub fn describe_kafka_topic(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        topic: &str,
+    ) -> Result<ServiceDataResult> {
+        let topic = Self::validate_topic_name(topic)?;
+        let stdout = self.run_topics_command(
+            environment_id,
+            service_data,
+            &["--describe", "--topic", &topic],
+        )?;
+        let described = Self::parse_describe_output(&topic, &stdout)
+            .ok_or_else(|| anyhow!("topic {} 不存在", topic))?;
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "获取 topic 详情成功".to_string(),
+            data: Some(serde_json::json!({ "topic": described })),
+        })
+    }
+
+    /// 解析 `kafka-topics --describe` 的输出：
+    /// 首行为 `Topic: t  TopicId: ...  PartitionCount: 1  ReplicationFactor: 1  Configs: ...`，
+    /// 之后每个分区一行 `Topic: t  Partition: 0  Leader: 1  Replicas: 1  Isr: 1`
+    fn parse_describe_output(topic: &str, output: &str) -> Option<KafkaTopic> {
+        let mut described: Option<KafkaTopic> = None;
+        for line in output.lines() {
+            let fields: Vec<(&str, &str)> = line
+                .split('\t')
+                .filter_map(|field| field.split_once(':'))
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .collect();
+            let get = |key: &str| fields.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+            if get("Topic") != Some(topic) {
+                continue;
+            }
+
+            if let Some(partition) = get("Partition").and_then(|v| v.parse().ok()) {
+                let ids = |key: &str| -> Vec<i64> {
+                    get(key)
+                        .unwrap_or_default()
+                        .split(',')
+                        .filter_map(|id| id.trim().parse().ok())
+                        .collect()
+                };
+                if let Some(described) = described.as_mut() {
+                    described.partitions.push(KafkaPartition {
+                        partition,
+                        leader: get("Leader").and_then(|v| v.parse().ok()),
+                        replicas: ids("Replicas"),
+                        isr: ids("Isr"),
+                    });
+                }
+            } else {
+                described = Some(KafkaTopic {
+                    topic: topic.to_string(),
+                    topic_id: get("TopicId").map(|v| v.to_string()),
+                    partition_count: get("PartitionCount").and_then(|v| v.parse().ok()),
+                    replication_factor: get("ReplicationFactor").and_then(|v| v.parse().ok()),
+                    configs: get("Configs").unwrap_or_default().to_string(),
+                    partitions: Vec::new(),
+                });
+            }
+        }
+        described
+    }
+
+    /// 执行 `kafka-topics --bootstrap-server 127.0.0.1:<port> <args>`，失败时返回 stderr
+    fn run_topics_command(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        args: &[&str],
+    ) -> Result<String> {
+        let config = self.get_runtime_config(environment_id, service_data)?;
+        if health::check_tcp_port(KAFKA_HOST, config.port).is_none() {
+            return Err(anyhow!("Kafka 服务未运行，请先启动服务"));
+        }
+
+        let output = self
+            .script_command(&service_data.version, "kafka-topics")?
+            .arg("--bootstrap-server")
+            .arg(format!("{}:{}", KAFKA_HOST, config.port))
+            .args(args)
+            .output()?;
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            // 只取错误行，去掉 Java 堆栈
+            let reason = stderr
+                .lines()
+                .find(|line| line.contains("Error") || line.contains("Exception"))
+                .unwrap_or(stderr.trim());
+            return Err(anyhow!("kafka-topics 执行失败: {}", reason.trim()));
+        }
+        Ok(stdout)
+    }
+
+    /// topic 名只能包含字母、数字、`.`、`_`、`-`，长度不超过 249
+    fn validate_topic_name(topic: &str) -> Result<String> {
+        let topic = topic.trim();
+        if topic.is_empty() {
+            return Err(anyhow!("topic 名不能为空"));
+        }
+        if topic == "."
+            || topic == ".."
+            || topic.len() > 249
+            || !topic
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "._-".contains(c))
+        {
+            return Err(anyhow!("topic 名无效: {}", topic));
+        }
+        Ok(topic.to_string())
+    }
+
+    /// 构建发行包脚本命令，并设置运行 Kafka 所需的 JAVA_HOME
+    fn script_command(&self, version: &str, name: &str) -> Result<Command> {
+        let script = self.get_script_path(version, name);
+        if !script.exists() {
+            return Err(anyhow!("未找到 {} 脚本", name));
+        }
+        let mut command = create_command(&script);
+        match Self::find_java_home() {
+            Some(java_home) => {
+                command.env("JAVA_HOME", java_home);
+            }
+            None if !Self::has_java_on_path() => {
+                return Err(anyhow!(
+                    "Kafka 需要 Java 运行环境，请先安装 Java 服务或设置 JAVA_HOME"
+                ));
+            }
+            None => {}
+        }
+        Ok(command)
+    }
+
+    /// 查找 JDK：优先使用 JAVA_HOME，其次为 Envis 已安装的最高版本 Java
+    fn find_java_home() -> Option<PathBuf> {
+        let java_bin = |home: &Path| {
+            if cfg!(target_os = "windows") {
+                home.join("bin").join("java.exe")
+            } else {
+                home.join("bin").join("java")
+            }
+        };
+
+        if let Some(java_home) = std::env::var_os("JAVA_HOME").map(PathBuf::from) {
+            if java_bin(&java_home).exists() {
+                return Some(java_home);
+            }
+        }
+
+        let java_folder = {
+            let app_config_manager = AppConfigManager::global();
+            let app_config_manager = app_config_manager.lock().unwrap();
+            PathBuf::from(app_config_manager.get_services_folder()).join("java")
+        };
+        fs::read_dir(java_folder)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|home| java_bin(home).exists())
+            .filter_map(|home| {
+                let name = home.file_name()?.to_string_lossy().to_string();
+                Some((ServiceManager::parse_lenient_version(&name)?, home))
+            })
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, home)| home)
+    }
+
+    fn has_java_on_path() -> bool {
+        create_command("java")
+            .arg("-version")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    /// KRaft 模式启动前必须格式化存储目录，已格式化（存在 meta.properties）时跳过
+    fn format_storage(&self, version: &str, config: &KafkaRuntimeConfig) -> Result<()> {
+        if config.data_dir.join("meta.properties").exists() {
+            return Ok(());
+        }
+
+        let output = self
+            .script_command(version, "kafka-storage")?
+            .arg("random-uuid")
+            .output()?;
+        let cluster_id = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.trim())
+            .rfind(|line| !line.is_empty())
+            .map(|line| line.to_string())
+            .filter(|_| output.status.success())
+            .ok_or_else(|| {
+                anyhow!(
+                    "生成 Kafka 集群 ID 失败: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                )
+            })?;
+
+        let output = self
+            .script_command(version, "kafka-storage")?
+            .args(["format", "-t", &cluster_id, "-c"])
+            .arg(&config.config_path)
+            .output()?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "格式化 Kafka 存储目录失败: {}{}",
+                String::from_utf8_lossy(&output.stderr),
+                String::from_utf8_lossy(&output.stdout)
+            ));
+        }
+        log::info!("Kafka 存储目录已格式化，集群 ID: {}", cluster_id);
+        Ok(())
+    }
+
+    /// 检查进程与 broker 端口；Kafka 没有 HTTP 接口，端口可连接即视为正常响应
+    fn check_health(&self, config: &KafkaRuntimeConfig) -> ServiceHealth {
+        let process = shutdown::read_running_pid(&config.pid_path).is_some();
+        let port_latency = health::check_tcp_port(KAFKA_HOST, config.port);
+        ServiceHealth {
+            process,
+            port_open: port_latency.is_some(),
+            responding: port_latency.is_some(),
+            latency_ms: port_latency,
+        }
+    }
+
+    fn get_runtime_config(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<KafkaRuntimeConfig> {
+        let service_data_folder =
+            self.get_service_data_folder(environment_id, &service_data.version);
+
+        let port = metadata_port(service_data, "KAFKA_PORT")?.unwrap_or(DEFAULT_KAFKA_PORT);
+        let controller_port = metadata_port(service_data, "KAFKA_CONTROLLER_PORT")?
+            .unwrap_or(DEFAULT_KAFKA_CONTROLLER_PORT);
+        if port == controller_port {
+            return Err(anyhow!(
+                "KAFKA_PORT 与 KAFKA_CONTROLLER_PORT 不能相同: {}",
+                port
+            ));
+        }
+        let heap_mb = metadata_number(service_data, "KAFKA_HEAP_MB")
+            .map(|heap| {
+                u32::try_from(heap)
+                    .ok()
+                    .filter(|heap| *heap >= 128)
+                    .ok_or_else(|| anyhow!("KAFKA_HEAP_MB 不能小于 128: {}", heap))
+            })
+            .transpose()?
+            .unwrap_or(DEFAULT_KAFKA_HEAP_MB);
+
+        Ok(KafkaRuntimeConfig {
+            port,
+            controller_port,
+            heap_mb,
+            config_path: service_data_folder.join("config").join("server.properties"),
+            data_dir: service_data_folder.join("data"),
+            logs_dir: service_data_folder.join("logs"),
+            pid_path: service_data_folder.join("run").join("kafka.pid"),
+        })
+    }
+
+    /// 每次启动时按 metadata 重新生成单节点 KRaft 配置
+    fn write_config_file(&self, config: &KafkaRuntimeConfig) -> Result<()> {
+        if let Some(config_dir) = config.config_path.parent() {
+            fs::create_dir_all(config_dir)?;
+        }
+        fs::create_dir_all(&config.data_dir)?;
+        fs::create_dir_all(&config.logs_dir)?;
+
+        let server_properties = format!(
+            "# 由 Envis 生成，启动时会按服务配置覆盖\n\
+             process.roles=broker,controller\n\
+             node.id=1\n\
+             controller.quorum.voters=1@{host}:{controller_port}\n\
+             listeners=PLAINTEXT://{host}:{port},CONTROLLER://{host}:{controller_port}\n\
+             advertised.listeners=PLAINTEXT://{host}:{port}\n\
+             controller.listener.names=CONTROLLER\n\
+             inter.broker.listener.name=PLAINTEXT\n\
+             listener.security.protocol.map=CONTROLLER:PLAINTEXT,PLAINTEXT:PLAINTEXT\n\
+             log.dirs={data_dir}\n\
+             num.partitions=1\n\
+             # 单节点集群，内部 topic 只能有一个副本\n\
+             offsets.topic.replication.factor=1\n\
+             transaction.state.log.replication.factor=1\n\
+             transaction.state.log.min.isr=1\n",
+            host = KAFKA_HOST,
+            port = config.port,
+            controller_port = config.controller_port,
+            data_dir = Self::properties_path(&config.data_dir),
+        );
+        fs::write(&config.config_path, server_properties)?;
+
+        Ok(())
+    }
+
+    /// properties 文件中反斜杠是转义符，统一使用正斜杠
+    fn properties_path(path: &Path) -> String {
+        path.to_string_lossy().replace('\\', "/")
+    }
+}
+
+struct KafkaRuntimeConfig {
+    port: u16,
+    controller_port: u16,
+    heap_mb: u32,
+    config_path: PathBuf,
+    data_dir: PathBuf,
+    logs_dir: PathBuf,
+    pid_path: PathBuf,
+}
+
+impl ServiceLifecycle for KafkaService {
+    fn is_installed(&self, version: &str) -> bool {
+        KafkaService::is_installed(self, version)
+    }
+
+    fn available_versions(&self) -> Vec<String> {
+        self.get_available_versions()
+            .into_iter()
+            .map(|v| v.version)
+            .collect()
+    }
+
+    fn download_and_install<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<DownloadResult>> {
+        Box::pin(KafkaService::download_and_install(self, version))
+    }
+
+    fn cancel_download(&self, version: &str) -> Result<()> {
+        KafkaService::cancel_download(self, version)
+    }
+
+    fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        KafkaService::get_download_progress(self, version)
+    }
+
+    fn archive_dir(&self, version: &str) -> Option<PathBuf> {
+        Some(self.get_install_path(version))
+    }
+
+    fn extract_and_install<'a>(
+        &'a self,
+        task: &'a DownloadTask,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(KafkaService::extract_and_install(self, task, version))
+    }
+
+    fn start_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        KafkaService::start_service(self, environment_id, service_data)
+    }
+
+    fn stop_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        KafkaService::stop_service(self, environment_id, service_data)
+    }
+
+    fn restart_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        KafkaService::restart_service(self, environment_id, service_data)
+    }
+
+    fn get_service_status(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        KafkaService::get_service_status(self, environment_id, service_data)
+    }
+}
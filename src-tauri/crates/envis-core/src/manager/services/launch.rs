@@ -0,0 +1,121 @@
+use anyhow::Result;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+/// 启动后等待进程提前退出的时间
+const EARLY_EXIT_WAIT: Duration = Duration::from_secs(2);
+/// 轮询子进程状态的间隔
+const EARLY_EXIT_POLL: Duration = Duration::from_millis(100);
+/// 输出日志超过该大小时转存为 .old 后重新写入
+const MAX_OUTPUT_LOG_SIZE: u64 = 5 * 1024 * 1024;
+/// 失败消息中最多附带的输出字节数
+const MAX_OUTPUT_TAIL: u64 = 4 * 1024;
+
+/// 服务进程的 stdout/stderr 输出日志：<service_data_folder>/logs/<service>-stdout.log
+pub struct OutputCapture {
+    path: PathBuf,
+    /// 本次启动写入前的文件长度，用于只读取本次启动的输出
+    offset: u64,
+}
+
+impl OutputCapture {
+    /// 准备输出日志文件，超过大小上限时先转存
+    pub fn new(service_data_folder: &Path, service_name: &str) -> Result<Self> {
+        let logs_dir = service_data_folder.join("logs");
+        fs::create_dir_all(&logs_dir)?;
+        let path = logs_dir.join(format!("{}-stdout.log", service_name));
+
+        if fs::metadata(&path)
+            .map(|m| m.len() > MAX_OUTPUT_LOG_SIZE)
+            .unwrap_or(false)
+        {
+            let old_path = logs_dir.join(format!("{}-stdout.log.old", service_name));
+            if let Err(e) = fs::rename(&path, &old_path) {
+                log::warn!("转存输出日志 {} 失败: {}", path.display(), e);
+                File::create(&path)?;
+            }
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        writeln!(
+            file,
+            "==== {} 启动 {} ====",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+            service_name
+        )?;
+        let offset = file.metadata()?.len();
+        Ok(Self { path, offset })
+    }
+
+    /// 输出日志路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 将命令的 stdout/stderr 追加写入输出日志，stdin 置空
+    pub fn attach(&self, command: &mut Command) -> Result<()> {
+        let stdout = OpenOptions::new().append(true).open(&self.path)?;
+        let stderr = stdout.try_clone()?;
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::from(stdout))
+            .stderr(Stdio::from(stderr));
+        Ok(())
+    }
+
+    /// 读取本次启动写入的输出（只保留末尾部分）
+    pub fn read_output(&self) -> String {
+        let read = || -> Result<String> {
+            let mut file = File::open(&self.path)?;
+            let len = file.metadata()?.len();
+            let start = self.offset.max(len.saturating_sub(MAX_OUTPUT_TAIL));
+            file.seek(SeekFrom::Start(start.min(len)))?;
+            let mut buf = Vec::new();
+            file.read_to_end(&mut buf)?;
+            Ok(String::from_utf8_lossy(&buf).trim().to_string())
+        };
+        read().unwrap_or_else(|e| {
+            log::warn!("读取输出日志 {} 失败: {}", self.path.display(), e);
+            String::new()
+        })
+    }
+
+    /// 进程异常退出时的失败说明：退出码 + 捕获到的输出
+    pub fn failure_detail(&self, status: &ExitStatus) -> String {
+        let code = status
+            .code()
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| "未知".to_string());
+        let output = self.read_output();
+        if output.is_empty() {
+            format!(
+                "进程已退出（退出码 {}），未输出错误信息，详见 {}",
+                code,
+                self.path.display()
+            )
+        } else {
+            format!("进程已退出（退出码 {}）: {}", code, output)
+        }
+    }
+}
+
+/// 等待子进程一小段时间，若以非零状态提前退出则返回退出状态。
+/// 以 0 退出视为已转入后台（如 fork 守护进程），仍在运行视为启动成功。
+pub fn wait_for_early_exit(child: &mut Child) -> Option<ExitStatus> {
+    let deadline = Instant::now() + EARLY_EXIT_WAIT;
+    while Instant::now() < deadline {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return None,
+            Ok(Some(status)) => return Some(status),
+            Ok(None) => std::thread::sleep(EARLY_EXIT_POLL),
+            Err(e) => {
+                log::warn!("检查子进程状态失败: {}", e);
+                return None;
+            }
+        }
+    }
+    None
+}
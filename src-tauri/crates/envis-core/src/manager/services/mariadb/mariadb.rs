@@ -1,15 +1,30 @@
 ﻿use crate::manager::app_config_manager::AppConfigManager;
-use crate::manager::env_serv_data_manager::ServiceDataResult;
-use crate::manager::services::{DownloadManager, DownloadResult, DownloadTask};
-use crate::types::{ServiceData, ServiceStatus};
-use crate::utils::create_command;
+use crate::manager::builders::{MetadataBuilder, MARIADB_METADATA_KEYS};
+use crate::manager::env_serv_data_manager::{EnvServDataManager, ServiceDataResult};
+use crate::manager::service_supervisor::ServiceSupervisor;
+use crate::manager::services::health::{self, InitializationCheck, ServiceHealth};
+use crate::manager::services::launch::{wait_for_early_exit, OutputCapture};
+use crate::manager::services::shutdown::{self, GracefulStop};
+use crate::manager::services::traits::ServiceLifecycle;
+use crate::manager::services::{
+    install_callback, remove_partial_install, DownloadManager, DownloadResult, DownloadTask,
+};
+use crate::types::ServiceData;
+use crate::manager::services::mysql::{
+    escape_sql_string, grant_statements, mysqld_config_value, mysqld_port, normalize_privileges,
+    parse_database_grant, quote_identifier, user_account,
+};
+use crate::utils::{create_command, find_free_port, output_tail};
 use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
 use serde::{Deserialize, Serialize};
 use std::fs::File;
-use std::io::copy;
-use std::path::PathBuf;
+use std::io::{copy, Read, Write};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
 use std::sync::{Arc, OnceLock};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// MariaDB 版本信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +34,34 @@ pub struct MariadbVersion {
     pub release_type: String,
 }
 
+/// 导出 / 导入进度回调，参数为 (已处理字节数, 预计总字节数)
+pub type TransferProgressCallback = Arc<dyn Fn(u64, Option<u64>) + Send + Sync>;
+
+/// 导出 / 导入时读写文件的块大小
+const TRANSFER_CHUNK_SIZE: usize = 64 * 1024;
+
+/// 初始化时临时服务器可用的端口范围
+const INIT_TEMP_PORT_RANGE: RangeInclusive<u16> = 3307..=3400;
+/// 等待临时服务器可以登录的最长时间
+const INIT_READY_TIMEOUT: Duration = Duration::from_secs(30);
+/// 等待临时服务器正常关闭的最长时间
+const INIT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+const INIT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// 启动失败时附带的错误日志字符数
+const INIT_LOG_TAIL_CHARS: usize = 2000;
+
+/// MariaDB 初始化参数
+#[derive(Debug, Clone, Default)]
+pub struct MariadbInitOptions {
+    pub root_password: String,
+    /// 为空时使用配置中的端口
+    pub port: Option<String>,
+    /// 为空时使用配置中的监听地址
+    pub bind_address: Option<String>,
+    /// 清空已有数据目录后重新初始化
+    pub reset: bool,
+}
+
 /// 全局 MariaDB 服务管理器单例
 static GLOBAL_MARIADB_SERVICE: OnceLock<Arc<MariadbService>> = OnceLock::new();
 
@@ -180,56 +223,13 @@ impl MariadbService {
         let download_manager = DownloadManager::global();
 
         let version_for_callback = version.to_string();
-        let success_callback = Arc::new(move |task: &DownloadTask| {
-            log::info!(
-                "MariaDB {} 下载完成: {} - {}",
-                version_for_callback,
-                task.id,
-                task.filename
-            );
-            let task_for_spawn = task.clone();
-            let version_for_spawn = version_for_callback.clone();
-            let service_for_spawn = MariadbService::global();
-
-            tokio::spawn(async move {
-                let download_manager = DownloadManager::global();
-                if let Err(e) = download_manager.update_task_status(
-                    &task_for_spawn.id,
-                    crate::manager::services::DownloadStatus::Installing,
-                    None,
-                ) {
-                    log::error!("更新任务状态失败: {}", e);
-                } else {
-                    log::info!("MariaDB {} 开始安装", version_for_spawn);
-                }
-
-                match service_for_spawn
-                    .extract_and_install(&task_for_spawn, &version_for_spawn)
+        let success_callback = install_callback(format!("MariaDB {}", version), move |task| {
+            let version = version_for_callback.clone();
+            async move {
+                MariadbService::global()
+                    .extract_and_install(&task, &version)
                     .await
-                {
-                    Ok(_) => {
-                        if let Err(e) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            crate::manager::services::DownloadStatus::Installed,
-                            None,
-                        ) {
-                            log::error!("更新任务状态失败: {}", e);
-                        } else {
-                            log::info!("MariaDB {} 安装成功", version_for_spawn);
-                        }
-                    }
-                    Err(e) => {
-                        if let Err(update_err) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            crate::manager::services::DownloadStatus::Failed,
-                            Some(format!("安装失败: {}", e)),
-                        ) {
-                            log::error!("更新任务状态失败: {}", update_err);
-                        }
-                        log::error!("MariaDB {} 安装失败: {}", version_for_spawn, e);
-                    }
-                }
-            });
+            }
         });
 
         match download_manager
@@ -259,6 +259,16 @@ impl MariadbService {
 
     /// 解压并安装 MariaDB
     pub async fn extract_and_install(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let result = self.install_from_archive(task, version).await;
+        if result.is_err() {
+            // 解压安装失败时清理残缺的安装目录
+            remove_partial_install(&self.get_install_path(version));
+        }
+        result
+    }
+
+    /// 将下载的压缩包解压到安装目录
+    async fn install_from_archive(&self, task: &DownloadTask, version: &str) -> Result<()> {
         let archive_path = &task.target_path;
         let install_dir = self.get_install_path(version);
         std::fs::create_dir_all(&install_dir)?;
@@ -406,6 +416,12 @@ impl MariadbService {
             }
         }
 
+        let mysqld_path = self
+            .get_install_path(version)
+            .join("bin")
+            .join("mysqld")
+            .to_string_lossy()
+            .to_string();
         let running = if cfg!(target_os = "windows") {
             let output = create_command("tasklist")
                 .arg("/FI")
@@ -433,7 +449,7 @@ impl MariadbService {
                         false
                     } else {
                         // lsof 无输出时回退到 pgrep
-                        let output = create_command("pgrep").arg("-x").arg("mysqld").output();
+                        let output = create_command("pgrep").arg("-f").arg(&mysqld_path).output();
                         match output {
                             Ok(o2) => {
                                 let stdout2 = String::from_utf8_lossy(&o2.stdout);
@@ -445,7 +461,7 @@ impl MariadbService {
                 }
                 Err(_) => {
                     // lsof 不可用，回退到 pgrep
-                    let output = create_command("pgrep").arg("-x").arg("mysqld").output();
+                    let output = create_command("pgrep").arg("-f").arg(&mysqld_path).output();
                     match output {
                         Ok(o) => {
                             let stdout = String::from_utf8_lossy(&o.stdout);
@@ -457,18 +473,26 @@ impl MariadbService {
             }
         };
 
-        let status = if running {
-            ServiceStatus::Running
-        } else {
-            ServiceStatus::Stopped
+        // 健康检查：端口连通后执行 SELECT 1
+        let port_latency = port
+            .parse::<u16>()
+            .ok()
+            .and_then(|p| health::check_tcp_port(&bind_address, p));
+        let query_latency = port_latency.and_then(|_| self.ping_server(service_data, &port));
+        let health = ServiceHealth {
+            process: running,
+            port_open: port_latency.is_some(),
+            responding: query_latency.is_some(),
+            latency_ms: query_latency.or(port_latency),
         };
-        let data = serde_json::json!({
+
+        let mut data = serde_json::json!({
             "isRunning": running,
-            "status": status,
             "port": port,
             "bindAddress": bind_address,
             "configPath": config_path.to_string_lossy().to_string(),
         });
+        health.merge_into(&mut data);
         Ok(ServiceDataResult {
             success: true,
             message: "获取状态成功".to_string(),
@@ -482,6 +506,7 @@ impl MariadbService {
         environment_id: &str,
         service_data: &ServiceData,
     ) -> Result<ServiceDataResult> {
+        ServiceSupervisor::global().clear_intentional_stop(&service_data.id);
         let version = &service_data.version;
         let install_path = self.get_install_path(version);
         let service_data_folder = self.getservice_data_folder(environment_id, version);
@@ -508,38 +533,37 @@ impl MariadbService {
             });
         }
 
-        let child_res = if cfg!(target_os = "windows") {
-            create_command(&mysqld)
-                .arg(format!("--defaults-file={}", config_path.to_string_lossy()))
-                .spawn()
-        } else if cfg!(target_os = "macos") {
-            // macOS: 后台运行，重定向 stdio，防止进程随终端关闭
-            create_command(&mysqld)
-                .arg(format!("--defaults-file={}", config_path.to_string_lossy()))
-                .stdin(std::process::Stdio::null())
-                .stdout(std::process::Stdio::null())
-                .stderr(std::process::Stdio::null())
-                .spawn()
-        } else {
-            // Linux: mysqld 自身支持 daemonize 配置
-            create_command(&mysqld)
-                .arg(format!("--defaults-file={}", config_path.to_string_lossy()))
-                .spawn()
-        };
+        // 子进程输出写入 logs/mariadb-stdout.log，启动失败时可直接返回错误原因
+        let capture = OutputCapture::new(&service_data_folder, "mariadb")?;
+        let mut command = create_command(&mysqld);
+        command.arg(format!("--defaults-file={}", config_path.to_string_lossy()));
+        capture.attach(&mut command)?;
 
-        match child_res {
-            Ok(child) => {
+        match command.spawn() {
+            Ok(mut child) => {
                 log::info!("MariaDB 进程已启动，PID: {:?}", child.id());
-                // 等待服务完成初始化
-                std::thread::sleep(Duration::from_millis(500));
+                // 配置错误时 mysqld 会立即退出，等待一小段时间检查
+                if let Some(status) = wait_for_early_exit(&mut child) {
+                    let detail = capture.failure_detail(&status);
+                    log::error!("MariaDB 启动后立即退出: {}", detail);
+                    return Ok(ServiceDataResult {
+                        success: false,
+                        message: format!("MariaDB 启动失败: {}", detail),
+                        data: Some(serde_json::json!({
+                            "configPath": config_path.to_string_lossy().to_string(),
+                            "outputLog": capture.path().to_string_lossy().to_string(),
+                        })),
+                    });
+                }
                 Ok(ServiceDataResult {
                     success: true,
                     message: format!(
-                        "MariaDB 启动命令已发送（使用配置文件: {}）",
+                        "MariaDB 启动成功（使用配置文件: {}）",
                         config_path.display()
                     ),
                     data: Some(serde_json::json!({
                         "configPath": config_path.to_string_lossy().to_string(),
+                        "outputLog": capture.path().to_string_lossy().to_string(),
                     })),
                 })
             }
@@ -551,53 +575,58 @@ impl MariadbService {
         }
     }
 
+    /// 停止 MariaDB 服务：使用 mariadb-admin shutdown 正常关闭，超时后强制结束 pid 文件中的进程
     pub fn stop_service(
         &self,
         environment_id: &str,
         service_data: &ServiceData,
     ) -> Result<ServiceDataResult> {
-        let res = if cfg!(target_os = "windows") {
-            create_command("taskkill")
-                .args(&["/IM", "mysqld.exe", "/F"])
-                .output()
-        } else {
-            // 使用 -x 精确匹配进程名，避免误杀其他包含 mysqld 的进程
-            create_command("pkill").args(&["-x", "mysqld"]).output()
-        };
-
-        match res {
-            Ok(o) => {
-                let exit_code = o.status.code().unwrap_or(-1);
-                // exit code 0 = 成功停止，1 = 进程不存在（也算成功）
-                if exit_code == 0 || exit_code == 1 {
-                    Ok(ServiceDataResult {
-                        success: true,
-                        message: "停止 MariaDB 成功".to_string(),
-                        data: None,
-                    })
-                } else {
-                    Ok(ServiceDataResult {
-                        success: false,
-                        message: format!("停止失败(exit {}): {}", exit_code, String::from_utf8_lossy(&o.stderr)),
-                        data: None,
-                    })
-                }
-            }
-            Err(e) => Ok(ServiceDataResult {
-                success: false,
-                message: format!("停止命令失败: {}", e),
-                data: None,
-            }),
+        ServiceSupervisor::global().mark_intentional_stop(&service_data.id);
+        let config_path = self
+            .getservice_data_folder(environment_id, &service_data.version)
+            .join("my.cnf");
+        let pid = mysqld_config_value(&config_path, "pid-file")
+            .and_then(|pid_file| shutdown::read_pid_file(Path::new(&pid_file)));
+
+        Ok(GracefulStop {
+            service_name: "MariaDB",
+            method: "mariadb-admin shutdown",
+            command: self.shutdown_command(service_data, &config_path),
+            pid,
+            port: mysqld_port(&config_path),
+            timeout: shutdown::stop_timeout(service_data),
         }
+        .run())
     }
 
+    /// 重启 MariaDB 服务，等待端口释放后再启动
     pub fn restart_service(
         &self,
         environment_id: &str,
         service_data: &ServiceData,
     ) -> Result<ServiceDataResult> {
-        let _ = self.stop_service(environment_id, service_data);
-        std::thread::sleep(Duration::from_millis(300));
+        let stop_res = self.stop_service(environment_id, service_data)?;
+        if !stop_res.success {
+            return Ok(stop_res);
+        }
+
+        let config_path = self
+            .getservice_data_folder(environment_id, &service_data.version)
+            .join("my.cnf");
+        if let Some(port) = mysqld_port(&config_path) {
+            let timeout = shutdown::stop_timeout(service_data);
+            if !shutdown::wait_for_port_release(port, timeout) {
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: format!(
+                        "端口 {} 在 {} 秒内未释放，无法重启 MariaDB",
+                        port,
+                        timeout.as_secs()
+                    ),
+                    data: None,
+                });
+            }
+        }
         self.start_service(environment_id, service_data)
     }
 
@@ -790,21 +819,84 @@ impl MariadbService {
         config_path.exists() && data_dir.exists()
     }
 
+    /// 检查初始化状态：目录、metadata 中的凭据，服务运行时用保存的 root 密码执行 SELECT 1
+    ///
+    /// metadata 以文件中的记录为准，初始化流程保存的凭据不依赖调用方传入的副本
+    pub fn check_initialization(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> InitializationCheck {
+        let service_data = EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .get_service_data(environment_id, &service_data.id)
+            .unwrap_or_else(|_| service_data.clone());
+        InitializationCheck::evaluate(
+            self.is_initialized(environment_id, &service_data),
+            &service_data,
+            &MARIADB_METADATA_KEYS,
+            || self.verify_root_credentials(&service_data),
+        )
+    }
+
+    /// 端口可连接时使用保存的 root 密码执行 SELECT 1，端口不通时返回 None
+    fn verify_root_credentials(&self, service_data: &ServiceData) -> Option<bool> {
+        let (root_password, port) = Self::get_root_credentials(service_data).ok()?;
+        health::check_tcp_port("127.0.0.1", port.parse().ok()?)?;
+        let output = self
+            .client_command(service_data, &port, &root_password)
+            .ok()?
+            .arg("--connect-timeout=2")
+            .arg("-e")
+            .arg("SELECT 1")
+            .output()
+            .ok()?;
+        Some(output.status.success())
+    }
+
     /// 初始化 MariaDB
     pub fn initialize_mariadb(
         &self,
         environment_id: &str,
         service_data: &ServiceData,
-        root_password: String,
-        port: Option<String>,
-        bind_address: Option<String>,
-        reset: bool,
+        options: MariadbInitOptions,
     ) -> Result<ServiceDataResult> {
+        self.initialize_mariadb_with_progress_events(
+            |_, _| {},
+            environment_id,
+            service_data,
+            options,
+        )
+    }
+
+    /// 初始化 MariaDB，每个步骤通过 progress_callback(step, message) 报告进度
+    /// 步骤与 MongoDB 初始化的进度事件格式一致
+    pub fn initialize_mariadb_with_progress_events(
+        &self,
+        progress_callback: impl Fn(&str, &str),
+        environment_id: &str,
+        service_data: &ServiceData,
+        options: MariadbInitOptions,
+    ) -> Result<ServiceDataResult> {
+        let MariadbInitOptions {
+            root_password,
+            port,
+            bind_address,
+            reset,
+        } = options;
+        // 辅助函数：发送进度事件
+        let emit_progress = |step: &str, message: &str| {
+            progress_callback(step, message);
+            log::info!("[MariaDB 初始化进度] {}: {}", step, message);
+        };
+
         let version = &service_data.version;
         let install_path = self.get_install_path(version);
         let service_data_folder = self.getservice_data_folder(environment_id, version);
 
         // 检查 MariaDB 是否已安装
+        emit_progress("mariadb_check_installation", "检查安装状态...");
         let mysql_install_db = if cfg!(target_os = "windows") {
             install_path.join("bin").join("mysql_install_db.exe")
         } else {
@@ -818,16 +910,18 @@ impl MariadbService {
         };
 
         if !mysqld.exists() {
+            emit_progress("mariadb_check_installation", "未安装");
             return Ok(ServiceDataResult {
                 success: false,
                 message: format!("MariaDB {} 未安装，请先下载并安装", version),
                 data: None,
             });
         }
+        emit_progress("mariadb_check_installation", "已安装");
 
         // 如果是重置,先清理现有数据
         if reset && service_data_folder.exists() {
-            log::info!("重置模式：清理现有数据...");
+            emit_progress("mariadb_reset", "清理现有数据...");
             std::fs::read_dir(&service_data_folder)?.for_each(|entry_res| {
                 if let Ok(entry) = entry_res {
                     let path = entry.path();
@@ -844,10 +938,23 @@ impl MariadbService {
                     };
                 }
             });
+            emit_progress("mariadb_reset", "数据清理完成");
+        }
+
+        // 重置时先标记为未完成，初始化失败后状态检查不会误报为已初始化
+        if reset {
+            if let Err(e) = EnvServDataManager::global().lock().unwrap().merge_metadata(
+                environment_id,
+                &service_data.id,
+                MetadataBuilder::build_database_reset_metadata(&MARIADB_METADATA_KEYS),
+            ) {
+                log::warn!("标记 MariaDB 重置状态失败: {}", e);
+            }
         }
 
         // 检查是否已初始化（非重置模式）
         if !reset && self.is_initialized(environment_id, service_data) {
+            emit_progress("mariadb_check_existing", "已初始化");
             return Ok(ServiceDataResult {
                 success: false,
                 message: "MariaDB 已经初始化，如需重新初始化请使用重置功能".to_string(),
@@ -855,37 +962,77 @@ impl MariadbService {
             });
         }
 
-        let port = port.unwrap_or_else(|| "3306".to_string());
+        // 未指定时使用 metadata 中的端口（如按模板创建时分配的端口）
+        let port = port
+            .or_else(|| {
+                service_data
+                    .metadata
+                    .as_ref()
+                    .and_then(|m| m.get("MARIADB_PORT"))
+                    .and_then(|v| {
+                        v.as_str()
+                            .map(|s| s.to_string())
+                            .or_else(|| v.as_u64().map(|n| n.to_string()))
+                    })
+            })
+            .unwrap_or_else(|| "3306".to_string());
         let bind_address = bind_address.unwrap_or_else(|| "127.0.0.1".to_string());
 
-        // 创建目录结构
-        log::info!("创建目录结构...");
-        std::fs::create_dir_all(&service_data_folder)?;
-
+        // 初始化失败时清理本次创建的数据目录与配置文件，已存在的不删除
         let data_dir = service_data_folder.join("data");
-        std::fs::create_dir_all(&data_dir)?;
+        let config_path = service_data_folder.join("my.cnf");
+        let data_dir_existed = data_dir.exists();
+        let config_existed = config_path.exists();
+        let fail = |step: &str, message: String| {
+            emit_progress(step, &message);
+            if !data_dir_existed {
+                let _ = std::fs::remove_dir_all(&data_dir);
+            }
+            if !config_existed {
+                let _ = std::fs::remove_file(&config_path);
+            }
+            Ok(ServiceDataResult {
+                success: false,
+                message: message.clone(),
+                data: Some(serde_json::json!({
+                    "step": step,
+                    "error": message,
+                })),
+            })
+        };
 
+        // 创建目录结构
+        emit_progress("mariadb_create_directories", "创建目录结构...");
         let log_dir = service_data_folder.join("logs");
-        std::fs::create_dir_all(&log_dir)?;
-
         let tmp_dir = service_data_folder.join("tmp");
-        std::fs::create_dir_all(&tmp_dir)?;
+        if let Err(e) = [&data_dir, &log_dir, &tmp_dir]
+            .iter()
+            .try_for_each(std::fs::create_dir_all)
+        {
+            return fail("mariadb_create_directories", format!("创建目录失败: {}", e));
+        }
+        emit_progress("mariadb_create_directories", "目录结构创建完成");
 
         // 创建配置文件
-        log::info!("创建配置文件...");
-        let config_path = service_data_folder.join("my.cnf");
-        self.create_default_config(
+        emit_progress("mariadb_create_config", "创建配置文件...");
+        if let Err(e) = self.create_default_config(
             &config_path,
             &data_dir,
             &log_dir,
             &tmp_dir,
             &port,
             &bind_address,
-        )?;
+        ) {
+            return fail("mariadb_create_config", format!("创建配置文件失败: {}", e));
+        }
+        emit_progress("mariadb_create_config", "配置文件创建完成");
 
         // 初始化数据目录
-        log::info!("初始化数据目录...");
         let init_output = if mysql_install_db.exists() {
+            emit_progress(
+                "mariadb_init_data",
+                "执行 mysql_install_db 初始化数据目录...",
+            );
             // 使用 mysql_install_db（旧版本）
             // --auth-root-authentication-method=normal：在 MariaDB 10.4+ 中禁用 unix_socket 插件，
             // 改为密码认证，确保后续通过 socket 以空密码连接设置 root 密码时不因 OS 用户不匹配而被拒绝
@@ -893,42 +1040,69 @@ impl MariadbService {
                 .arg(format!("--datadir={}", data_dir.display()))
                 .arg(format!("--basedir={}", install_path.display()))
                 .arg("--auth-root-authentication-method=normal")
-                .output()?
+                .output()
         } else {
             // 使用 mysqld --initialize-insecure（新版本）
+            emit_progress(
+                "mariadb_init_data",
+                "执行 mysqld --initialize-insecure 初始化数据目录...",
+            );
             create_command(&mysqld)
                 .arg("--initialize-insecure")
                 .arg(format!("--datadir={}", data_dir.display()))
                 .arg(format!("--basedir={}", install_path.display()))
-                .output()?
+                .output()
         };
 
-        if !init_output.status.success() {
-            let error = String::from_utf8_lossy(&init_output.stderr);
-            return Err(anyhow!("初始化数据目录失败: {}", error));
+        match init_output {
+            Ok(output) if output.status.success() => {}
+            Ok(output) => {
+                let error = String::from_utf8_lossy(&output.stderr);
+                return fail(
+                    "mariadb_init_data",
+                    format!("初始化数据目录失败: {}", error),
+                );
+            }
+            Err(e) => {
+                return fail("mariadb_init_data", format!("执行初始化命令失败: {}", e));
+            }
         }
+        emit_progress("mariadb_init_data", "数据目录初始化完成");
 
         // 启动临时服务器设置 root 密码
         // 注意：不使用 --skip-grant-tables，因为 MariaDB 10.4+ 该选项会隐式启用
         // --skip-networking，导致 TCP 连接被拒绝；--initialize-insecure 已创建 root@localhost（无密码）
-        log::info!("启动临时服务器设置 root 密码...");
-        let temp_port = "3307";
-        let temp_socket = tmp_dir.join("mysql_init.sock");
-        let mut mysqld_process = create_command(&mysqld)
-            .arg(format!("--defaults-file={}", config_path.display()))
-            .arg(format!("--port={}", temp_port))
-            .arg(format!("--socket={}", temp_socket.display()))
-            .spawn()?;
-
-        // 等待服务器启动
-        std::thread::sleep(Duration::from_secs(3));
-
-        // 设置 root 密码
         let mysql_client = if cfg!(target_os = "windows") {
             install_path.join("bin").join("mysql.exe")
         } else {
             install_path.join("bin").join("mysql")
         };
+        // 临时服务器使用空闲端口，避免与已运行的 MariaDB / MySQL 冲突
+        let temp_port = match find_free_port(INIT_TEMP_PORT_RANGE, "临时 MariaDB 服务器") {
+            Ok(port) => port.to_string(),
+            Err(e) => return fail("mariadb_start_temp_server", e.to_string()),
+        };
+        let temp_server = InitServer {
+            mysqld: &mysqld,
+            mysql_client: &mysql_client,
+            config_path: &config_path,
+            service_data_folder: &service_data_folder,
+            error_log: &log_dir.join("error.log"),
+            port: &temp_port,
+            socket: &tmp_dir.join("mysql_init.sock"),
+        };
+
+        emit_progress("mariadb_start_temp_server", "启动临时服务器...");
+        let mut mysqld_process = match temp_server.start("") {
+            Ok(child) => child,
+            Err(e) => {
+                return fail(
+                    "mariadb_start_temp_server",
+                    format!("启动临时服务器失败: {}", e),
+                )
+            }
+        };
+        emit_progress("mariadb_start_temp_server", "临时服务器已启动");
 
         // 使用 SET PASSWORD 兼容所有 MariaDB 版本，并切换认证方式为 mysql_native_password
         // 连接时不传密码（--initialize-insecure 后 root 无密码）
@@ -939,51 +1113,61 @@ impl MariadbService {
              GRANT ALL PRIVILEGES ON *.* TO 'root'@'127.0.0.1' IDENTIFIED BY '{pw}' WITH GRANT OPTION; \
              GRANT ALL PRIVILEGES ON *.* TO 'root'@'::1' IDENTIFIED BY '{pw}' WITH GRANT OPTION; \
              FLUSH PRIVILEGES;",
-            pw = root_password
+            pw = escape_sql_string(&root_password)
         );
 
-        // Unix 下优先走 socket，Windows 下走 TCP
-        let password_output = if cfg!(target_os = "windows") {
-            create_command(&mysql_client)
-                .arg(format!("--port={}", temp_port))
-                .arg("--host=127.0.0.1")
-                .arg("-u").arg("root")
-                .arg("--password=")
-                .arg("-e")
-                .arg(&set_password_cmd)
-                .output()
-        } else {
-            create_command(&mysql_client)
-                .arg(format!("--socket={}", temp_socket.display()))
-                .arg("-u").arg("root")
-                .arg("--password=")
-                .arg("-e")
-                .arg(&set_password_cmd)
-                .output()
-        };
-
-        // 等待密码设置完成并写入磁盘
-        log::info!("等待密码数据写入磁盘 (2秒)...");
-        std::thread::sleep(Duration::from_secs(2));
+        emit_progress("mariadb_set_root_password", "设置 root 密码...");
+        if let Err(e) = temp_server.execute("", &set_password_cmd, cfg!(target_os = "windows")) {
+            temp_server.stop(&mut mysqld_process, "");
+            return fail(
+                "mariadb_set_root_password",
+                format!("设置 root 密码失败: {}", e),
+            );
+        }
+        emit_progress("mariadb_set_root_password", "root 密码设置完成");
 
-        // 停止临时服务器
-        let _ = mysqld_process.kill();
-        let _ = mysqld_process.wait();
+        // 正常关闭临时服务器，确保授权表写入磁盘
+        emit_progress("mariadb_stop_temp_server", "停止临时服务器...");
+        temp_server.stop(&mut mysqld_process, &root_password);
+        emit_progress("mariadb_stop_temp_server", "临时服务器已停止");
 
-        // 检查密码设置是否成功
-        match password_output {
-            Ok(output) if output.status.success() => {
-                log::info!("root 密码设置成功");
-            }
-            Ok(output) => {
-                let error = String::from_utf8_lossy(&output.stderr);
-                log::warn!("设置 root 密码可能失败: {}", error);
-            }
+        // 重新启动并通过 TCP 使用新密码登录，确认密码实际生效后才返回成功
+        emit_progress("mariadb_verify_password", "重启临时服务器验证 root 密码...");
+        let mut verify_process = match temp_server.start(&root_password) {
+            Ok(child) => child,
             Err(e) => {
-                log::warn!("执行密码设置命令失败: {}", e);
+                return fail(
+                    "mariadb_verify_password",
+                    format!("验证 root 密码失败: {}", e),
+                )
             }
+        };
+        // 后续操作均通过 TCP --host=127.0.0.1 连接，需单独验证
+        let tcp_login = temp_server.execute(&root_password, "SELECT 1", true);
+        temp_server.stop(&mut verify_process, &root_password);
+        if let Err(e) = tcp_login {
+            return fail(
+                "mariadb_verify_password",
+                format!("通过 TCP 使用新密码登录失败: {}", e),
+            );
         }
+        emit_progress("mariadb_verify_password", "root 密码验证通过");
+
+        // 保存配置文件、端口与 root 密码，并标记初始化完成
+        let metadata = MetadataBuilder::build_database_init_metadata(
+            &MARIADB_METADATA_KEYS,
+            &config_path,
+            &port,
+            "root",
+            &root_password,
+        );
+        EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .merge_metadata(environment_id, &service_data.id, metadata.clone())
+            .map_err(|e| anyhow!("保存初始化 metadata 失败: {}", e))?;
 
+        emit_progress("mariadb_complete", "初始化完成！");
         log::info!("MariaDB 初始化完成！");
 
         Ok(ServiceDataResult {
@@ -996,6 +1180,7 @@ impl MariadbService {
                 "rootPassword": root_password,
                 "port": port,
                 "bindAddress": bind_address,
+                "metadata": metadata,
             })),
         })
     }
@@ -1309,7 +1494,7 @@ default-character-set = utf8mb4
             .arg("-u").arg("root")
             .arg(format!("--password={}", root_password))
             .arg("-e")
-            .arg("SELECT User, Host FROM mysql.user WHERE Host = 'localhost' AND User != '' ORDER BY User")
+            .arg("SELECT User, Host FROM mysql.user WHERE User != '' AND User != 'root' AND User NOT IN ('mysql.sys', 'mysql.infoschema', 'mysql.session', 'mariadb.sys') ORDER BY User, Host")
             .arg("--batch")
             .arg("--skip-column-names")
             .output()?;
@@ -1337,7 +1522,7 @@ default-character-set = utf8mb4
                 .arg("-u").arg("root")
                 .arg(format!("--password={}", root_password))
                 .arg("-e")
-                .arg(format!("SHOW GRANTS FOR '{}'@'{}'", username, host))
+                .arg(format!("SHOW GRANTS FOR {}", user_account(username, host)))
                 .arg("--batch")
                 .arg("--skip-column-names")
                 .output()?;
@@ -1346,26 +1531,12 @@ default-character-set = utf8mb4
             if grants_output.status.success() {
                 let grants_str = String::from_utf8_lossy(&grants_output.stdout);
                 for grant_line in grants_str.lines().filter(|l| !l.is_empty()) {
-                    // 解析形如: GRANT SELECT ON `dbname`.* TO 'user'@'host'
-                    // 或: GRANT ALL PRIVILEGES ON `dbname`.* TO 'user'@'host'
-                    if let Some(on_pos) = grant_line.find(" ON ") {
-                        let priv_part = &grant_line[6..on_pos]; // 跳过 "GRANT "
-                        let rest = &grant_line[on_pos + 4..];
-                        if let Some(dot_pos) = rest.find(".*") {
-                            let db_part = &rest[..dot_pos];
-                            let db_name = db_part.trim_matches('`').trim_matches('\'');
-                            if db_name != "*" && db_name != "mysql" {
-                                let privilege = if priv_part.contains("ALL PRIVILEGES") {
-                                    "ALL PRIVILEGES"
-                                } else {
-                                    "SELECT"
-                                };
-                                grants.push(serde_json::json!({
-                                    "database": db_name,
-                                    "privilege": privilege,
-                                }));
-                            }
-                        }
+                    if let Some((db_name, privileges)) = parse_database_grant(grant_line) {
+                        grants.push(serde_json::json!({
+                            "database": db_name,
+                            "privilege": privileges.join(", "),
+                            "privileges": privileges,
+                        }));
                     }
                 }
             }
@@ -1392,6 +1563,7 @@ default-character-set = utf8mb4
         username: String,
         password: String,
         grants: Vec<serde_json::Value>,
+        host: Option<String>,
     ) -> Result<ServiceDataResult> {
         log::info!("创建 MariaDB 用户: {}", username);
 
@@ -1430,19 +1602,11 @@ default-character-set = utf8mb4
         }
 
         // 构建 SQL 语句
+        let account = user_account(&username, host.as_deref().unwrap_or("localhost"));
         let mut sql_parts = vec![
-            format!("CREATE USER '{}'@'localhost' IDENTIFIED BY '{}'", username, password),
+            format!("CREATE USER {} IDENTIFIED BY '{}'", account, escape_sql_string(&password)),
         ];
-        for grant in &grants {
-            let database = grant.get("database").and_then(|v| v.as_str()).unwrap_or("");
-            let privilege = grant.get("privilege").and_then(|v| v.as_str()).unwrap_or("SELECT");
-            if !database.is_empty() {
-                sql_parts.push(format!(
-                    "GRANT {} ON `{}`.* TO '{}'@'localhost'",
-                    privilege, database, username
-                ));
-            }
-        }
+        sql_parts.extend(grant_statements(&grants, &account)?);
         sql_parts.push("FLUSH PRIVILEGES".to_string());
         let sql = sql_parts.join("; ");
 
@@ -1473,6 +1637,7 @@ default-character-set = utf8mb4
         _environment_id: &str,
         service_data: &ServiceData,
         username: String,
+        host: Option<String>,
     ) -> Result<ServiceDataResult> {
         log::info!("删除 MariaDB 用户: {}", username);
 
@@ -1509,8 +1674,8 @@ default-character-set = utf8mb4
         }
 
         let sql = format!(
-            "DROP USER IF EXISTS '{}'@'localhost'; FLUSH PRIVILEGES",
-            username
+            "DROP USER IF EXISTS {}; FLUSH PRIVILEGES",
+            user_account(&username, host.as_deref().unwrap_or("localhost"))
         );
 
         let output = create_command(&mysql_client)
@@ -1541,6 +1706,7 @@ default-character-set = utf8mb4
         service_data: &ServiceData,
         username: String,
         grants: Vec<serde_json::Value>,
+        host: Option<String>,
     ) -> Result<ServiceDataResult> {
         log::info!("更新 MariaDB 用户权限: {}", username);
 
@@ -1577,22 +1743,11 @@ default-character-set = utf8mb4
         }
 
         // 先撤销所有权限，再重新授予
+        let account = user_account(&username, host.as_deref().unwrap_or("localhost"));
         let mut sql_parts = vec![
-            format!(
-                "REVOKE ALL PRIVILEGES, GRANT OPTION FROM '{}'@'localhost'",
-                username
-            ),
+            format!("REVOKE ALL PRIVILEGES, GRANT OPTION FROM {}", account),
         ];
-        for grant in &grants {
-            let database = grant.get("database").and_then(|v| v.as_str()).unwrap_or("");
-            let privilege = grant.get("privilege").and_then(|v| v.as_str()).unwrap_or("SELECT");
-            if !database.is_empty() {
-                sql_parts.push(format!(
-                    "GRANT {} ON `{}`.* TO '{}'@'localhost'",
-                    privilege, database, username
-                ));
-            }
-        }
+        sql_parts.extend(grant_statements(&grants, &account)?);
         sql_parts.push("FLUSH PRIVILEGES".to_string());
         let sql = sql_parts.join("; ");
 
@@ -1733,4 +1888,817 @@ default-character-set = utf8mb4
     fn escape_applescript_string(value: &str) -> String {
         value.replace('\\', "\\\\").replace('"', "\\\"")
     }
+
+    /// 为用户授予指定数据库的权限（privileges 如 ["SELECT","INSERT"] 或 ["ALL"]，database 为 "*" 时表示全部数据库）
+    pub fn grant_privileges(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+        username: String,
+        database: String,
+        privileges: Vec<String>,
+        host: Option<String>,
+    ) -> Result<ServiceDataResult> {
+        log::info!("授予 MariaDB 用户 {} 数据库 {} 权限: {:?}", username, database, privileges);
+
+        if username.is_empty() {
+            return Err(anyhow!("用户名不能为空"));
+        }
+        if username.to_lowercase() == "root" {
+            return Err(anyhow!("不能修改 root 用户权限"));
+        }
+        if database.is_empty() {
+            return Err(anyhow!("数据库名不能为空"));
+        }
+
+        let privilege_list = normalize_privileges(&privileges)?;
+        let target = if database == "*" {
+            "*.*".to_string()
+        } else {
+            format!("{}.*", quote_identifier(&database))
+        };
+        let account = user_account(&username, host.as_deref().unwrap_or("localhost"));
+        let sql = format!(
+            "GRANT {} ON {} TO {}; FLUSH PRIVILEGES",
+            privilege_list, target, account
+        );
+
+        let output = self
+            .root_client_command(service_data)?
+            .arg("-e")
+            .arg(&sql)
+            .output()?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("授予权限失败: {}", error));
+        }
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("已授予用户 '{}' 数据库 '{}' 的权限", username, database),
+            data: Some(serde_json::json!({
+                "username": username,
+                "database": database,
+                "privileges": privilege_list,
+            })),
+        })
+    }
+
+    /// 修改 root 密码
+    ///
+    /// 使用当前密码执行 ALTER USER，再用新密码重新连接验证，验证通过后才写入 metadata。
+    pub fn change_root_password(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        new_password: String,
+    ) -> Result<ServiceDataResult> {
+        if new_password.is_empty() {
+            return Err(anyhow!("新密码不能为空"));
+        }
+
+        let (_, port) = Self::get_root_credentials(service_data)?;
+
+        // 初始化时可能同时创建了 root@127.0.0.1 / root@::1，这里一并修改，不存在的账号会被跳过
+        let password = escape_sql_string(&new_password);
+        let accounts: Vec<String> = ["localhost", "127.0.0.1", "::1"]
+            .iter()
+            .map(|host| {
+                format!(
+                    "{} IDENTIFIED BY '{}'",
+                    user_account("root", host),
+                    password
+                )
+            })
+            .collect();
+        let sql = format!(
+            "ALTER USER IF EXISTS {}; FLUSH PRIVILEGES;",
+            accounts.join(", ")
+        );
+
+        // SQL 中包含新密码，通过 stdin 传入，避免出现在进程参数中
+        let mut child = self
+            .root_client_command(service_data)?
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(sql.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("执行 ALTER USER 失败，root 密码未修改: {}", error));
+        }
+
+        // 用新密码重新连接，确认修改生效
+        let verify = self
+            .client_command(service_data, &port, &new_password)?
+            .arg("-e")
+            .arg("SELECT 1")
+            .output()?;
+        if !verify.status.success() {
+            let error = String::from_utf8_lossy(&verify.stderr);
+            log::warn!(
+                "MariaDB root 新密码验证失败，metadata 保持原密码: {}",
+                error
+            );
+            return Err(anyhow!(
+                "ALTER USER 已执行成功，但使用新密码重新连接失败，metadata 仍保留原密码: {}",
+                error
+            ));
+        }
+
+        let mut service_data = service_data.clone();
+        EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .set_metadata(
+                environment_id,
+                &mut service_data,
+                "MARIADB_ROOT_PASSWORD",
+                serde_json::Value::String(new_password),
+            )
+            .map_err(|e| anyhow!("数据库中的 root 密码已修改，但保存到 metadata 失败: {}", e))?;
+
+        log::info!("MariaDB root 密码已修改");
+        Ok(ServiceDataResult {
+            success: true,
+            message: "root 密码修改成功".to_string(),
+            data: None,
+        })
+    }
+
+    /// 导出单个数据库为 SQL 文件
+    ///
+    /// 预计总大小取自 information_schema 中的数据与索引大小，仅用于估算进度。
+    pub fn dump_database(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+        database_name: String,
+        output_path: String,
+        on_progress: Option<TransferProgressCallback>,
+    ) -> Result<ServiceDataResult> {
+        log::info!("导出 MariaDB 数据库 {} 到 {}", database_name, output_path);
+
+        if database_name.is_empty() {
+            return Err(anyhow!("数据库名称不能为空"));
+        }
+        let (root_password, port) = Self::get_root_credentials(service_data)?;
+        let dump_bin = self.get_dump_bin(&service_data.version)?;
+
+        let output_file = Path::new(&output_path);
+        if let Some(parent) = output_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let estimated_size = self.estimate_database_size(service_data, &database_name);
+
+        let mut child = create_command(&dump_bin)
+            .arg(format!("--port={}", port))
+            .arg("--host=127.0.0.1")
+            .arg("-u")
+            .arg("root")
+            .arg(format!("--password={}", root_password))
+            .arg("--single-transaction")
+            .arg("--default-character-set=utf8mb4")
+            .arg("--routines")
+            .arg("--events")
+            .arg(&database_name)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow!("启动 mariadb-dump 失败: {}", e))?;
+
+        let stderr_reader = child.stderr.take().map(spawn_stderr_reader);
+        let mut stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow!("无法读取 mariadb-dump 输出"))?;
+
+        let copy_result = File::create(output_file)
+            .map_err(|e| anyhow!("创建导出文件失败: {}", e))
+            .and_then(|mut file| {
+                transfer_with_progress(&mut stdout, &mut file, estimated_size, on_progress.as_ref())
+            });
+        // 写文件失败时关闭管道，让 mariadb-dump 退出
+        drop(stdout);
+        let status = child.wait()?;
+        let stderr = stderr_reader
+            .and_then(|h| h.join().ok())
+            .unwrap_or_default();
+
+        let written = match copy_result {
+            Ok(written) if status.success() => written,
+            Ok(_) => {
+                let _ = std::fs::remove_file(output_file);
+                return Err(anyhow!("导出数据库失败: {}", stderr.trim()));
+            }
+            Err(e) => {
+                let _ = std::fs::remove_file(output_file);
+                return Err(e);
+            }
+        };
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("数据库 '{}' 导出成功", database_name),
+            data: Some(serde_json::json!({
+                "database": database_name,
+                "outputPath": output_path,
+                "size": written,
+            })),
+        })
+    }
+
+    /// 从 SQL 文件导入数据库，数据库不存在时自动创建
+    pub fn restore_database(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+        database_name: String,
+        sql_path: String,
+        on_progress: Option<TransferProgressCallback>,
+    ) -> Result<ServiceDataResult> {
+        log::info!("从 {} 导入 MariaDB 数据库 {}", sql_path, database_name);
+
+        if database_name.is_empty() {
+            return Err(anyhow!("数据库名称不能为空"));
+        }
+        let mut sql_file =
+            File::open(&sql_path).map_err(|e| anyhow!("打开 SQL 文件失败: {}", e))?;
+        let total_size = sql_file.metadata().map(|m| m.len()).ok();
+
+        let output = self
+            .root_client_command(service_data)?
+            .arg("-e")
+            .arg(format!(
+                "CREATE DATABASE IF NOT EXISTS {}",
+                quote_identifier(&database_name)
+            ))
+            .output()?;
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("创建数据库失败: {}", error));
+        }
+
+        let mut child = self
+            .root_client_command(service_data)?
+            .arg("--default-character-set=utf8mb4")
+            .arg(&database_name)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow!("启动 mysql 客户端失败: {}", e))?;
+
+        let stderr_reader = child.stderr.take().map(spawn_stderr_reader);
+        let copy_result = match child.stdin.take() {
+            // stdin 在块结束时关闭，mysql 客户端随之退出
+            Some(mut stdin) => {
+                transfer_with_progress(&mut sql_file, &mut stdin, total_size, on_progress.as_ref())
+            }
+            None => Err(anyhow!("无法写入 mysql 客户端输入")),
+        };
+        let status = child.wait()?;
+        let stderr = stderr_reader
+            .and_then(|h| h.join().ok())
+            .unwrap_or_default();
+
+        if !status.success() {
+            return Err(anyhow!("导入数据库失败: {}", stderr.trim()));
+        }
+        let imported = copy_result?;
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("数据库 '{}' 导入成功", database_name),
+            data: Some(serde_json::json!({
+                "database": database_name,
+                "sqlPath": sql_path,
+                "size": imported,
+            })),
+        })
+    }
+
+    /// 估算数据库大小（数据 + 索引），失败时返回 None
+    fn estimate_database_size(
+        &self,
+        service_data: &ServiceData,
+        database_name: &str,
+    ) -> Option<u64> {
+        let output = self
+            .root_client_command(service_data)
+            .ok()?
+            .arg("-N")
+            .arg("-B")
+            .arg("-e")
+            .arg(format!(
+                "SELECT COALESCE(SUM(data_length + index_length), 0) FROM information_schema.tables WHERE table_schema = '{}'",
+                escape_sql_string(database_name)
+            ))
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .filter(|size| *size > 0)
+    }
+
+    /// 获取导出工具路径（优先 mariadb-dump，回退 mysqldump）
+    fn get_dump_bin(&self, version: &str) -> Result<PathBuf> {
+        let bin_dir = self.get_install_path(version).join("bin");
+        let exe = if cfg!(target_os = "windows") { ".exe" } else { "" };
+        ["mariadb-dump", "mysqldump"]
+            .iter()
+            .map(|name| bin_dir.join(format!("{}{}", name, exe)))
+            .find(|p| p.exists())
+            .ok_or_else(|| anyhow!("mariadb-dump 未安装"))
+    }
+
+    /// 导出所有用户及其权限为可重放的 SQL 脚本（类似 pt-show-grants）
+    ///
+    /// 数据库备份不包含用户与授权，该脚本用于灾难恢复时重建账号。
+    /// root、系统账号与角色不导出，避免恢复时覆盖目标实例的 root 密码。
+    pub fn export_users_sql(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+        output_path: String,
+    ) -> Result<ServiceDataResult> {
+        log::info!("导出 MariaDB 用户与权限到 {}", output_path);
+
+        // --raw 避免 batch 模式对反斜杠转义，保证输出可直接执行
+        let output = self
+            .root_client_command(service_data)?
+            .arg("--batch")
+            .arg("--raw")
+            .arg("--skip-column-names")
+            .arg("-e")
+            .arg(
+                "SELECT User, Host, CONCAT('CREATE USER IF NOT EXISTS ', QUOTE(User), '@', QUOTE(Host), ';') \
+                 FROM mysql.user \
+                 WHERE User != '' AND User != 'root' AND is_role = 'N' \
+                 AND User NOT IN ('mysql.sys', 'mysql.infoschema', 'mysql.session', 'mariadb.sys') \
+                 ORDER BY User, Host",
+            )
+            .output()?;
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("查询用户列表失败: {}", error));
+        }
+
+        let mut script = format!(
+            "-- MariaDB {} 用户与权限导出\n-- 导出时间: {}\n\n",
+            service_data.version,
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+        );
+        let mut users: Vec<String> = Vec::new();
+
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        for line in output_str.lines().filter(|l| !l.is_empty()) {
+            let parts: Vec<&str> = line.splitn(3, '\t').collect();
+            if parts.len() < 3 {
+                continue;
+            }
+            let (username, host, create_sql) = (parts[0], parts[1], parts[2]);
+            let account = user_account(username, host);
+
+            let grants_output = self
+                .root_client_command(service_data)?
+                .arg("--batch")
+                .arg("--raw")
+                .arg("--skip-column-names")
+                .arg("-e")
+                .arg(format!("SHOW GRANTS FOR {}", account))
+                .output()?;
+            if !grants_output.status.success() {
+                let error = String::from_utf8_lossy(&grants_output.stderr);
+                return Err(anyhow!("查询用户 {} 的权限失败: {}", account, error.trim()));
+            }
+
+            script.push_str(&format!("-- {}\n{}\n", account, create_sql));
+            let grants_str = String::from_utf8_lossy(&grants_output.stdout);
+            for grant in grants_str.lines().filter(|l| !l.is_empty()) {
+                script.push_str(grant);
+                script.push_str(";\n");
+            }
+            script.push('\n');
+            users.push(account);
+        }
+        script.push_str("FLUSH PRIVILEGES;\n");
+
+        let output_file = Path::new(&output_path);
+        if let Some(parent) = output_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(output_file, &script).map_err(|e| anyhow!("写入导出文件失败: {}", e))?;
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("已导出 {} 个用户的权限", users.len()),
+            data: Some(serde_json::json!({
+                "outputPath": output_path,
+                "size": script.len(),
+                "users": users,
+            })),
+        })
+    }
+
+    /// 构建备份命令（优先 mariadb-dump，回退 mysqldump，输出到 stdout）
+    pub fn build_backup_command(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<Command> {
+        let (root_password, port) = Self::get_root_credentials(service_data)?;
+        let dump_bin = self.get_dump_bin(&service_data.version)?;
+
+        let mut cmd = create_command(&dump_bin);
+        cmd.arg(format!("--port={}", port))
+            .arg("--host=127.0.0.1")
+            .arg("-u")
+            .arg("root")
+            .arg(format!("--password={}", root_password))
+            .arg("--all-databases")
+            .arg("--single-transaction")
+            .arg("--routines")
+            .arg("--events");
+        Ok(cmd)
+    }
+
+    /// 构建恢复命令（mysql 客户端，从 stdin 读取 SQL）
+    pub fn build_restore_command(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<Command> {
+        self.root_client_command(service_data)
+    }
+
+    /// 使用 mysql 客户端执行 SELECT 1，成功时返回耗时（毫秒）
+    fn ping_server(&self, service_data: &ServiceData, port: &str) -> Option<u64> {
+        let install_path = self.get_install_path(&service_data.version);
+        let mysql_client = if cfg!(target_os = "windows") {
+            install_path.join("bin").join("mysql.exe")
+        } else {
+            install_path.join("bin").join("mysql")
+        };
+        if !mysql_client.exists() {
+            return None;
+        }
+
+        let root_password = service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("MARIADB_ROOT_PASSWORD"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+
+        let mut cmd = create_command(&mysql_client);
+        cmd.arg(format!("--port={}", port))
+            .arg("--host=127.0.0.1")
+            .arg("--connect-timeout=2")
+            .arg("-u")
+            .arg("root");
+        if !root_password.is_empty() {
+            cmd.arg(format!("--password={}", root_password));
+        }
+        cmd.arg("-e").arg("SELECT 1");
+
+        health::timed(|| cmd.output().map(|o| o.status.success()).unwrap_or(false))
+    }
+
+    /// 构建以 root 身份连接的 mysql 客户端命令
+    fn root_client_command(&self, service_data: &ServiceData) -> Result<Command> {
+        let (root_password, port) = Self::get_root_credentials(service_data)?;
+        self.client_command(service_data, &port, &root_password)
+    }
+
+    /// 构建使用指定 root 密码连接的 mysql 客户端命令
+    fn client_command(
+        &self,
+        service_data: &ServiceData,
+        port: &str,
+        root_password: &str,
+    ) -> Result<Command> {
+        let install_path = self.get_install_path(&service_data.version);
+        let mysql_client = if cfg!(target_os = "windows") {
+            install_path.join("bin").join("mysql.exe")
+        } else {
+            install_path.join("bin").join("mysql")
+        };
+        if !mysql_client.exists() {
+            return Err(anyhow!("mysql 客户端未安装"));
+        }
+
+        let mut cmd = create_command(&mysql_client);
+        cmd.arg(format!("--port={}", port))
+            .arg("--host=127.0.0.1")
+            .arg("-u")
+            .arg("root")
+            .arg(format!("--password={}", root_password));
+        Ok(cmd)
+    }
+
+    /// 构建 shutdown 命令，通过 my.cnf [client] 段的端口 / socket 连接
+    ///
+    /// 新版本提供 mariadb-admin，旧版本只有 mysqladmin
+    fn shutdown_command(&self, service_data: &ServiceData, config_path: &Path) -> Result<Command> {
+        let (root_password, _) = Self::get_root_credentials(service_data)?;
+        let bin_dir = self.get_install_path(&service_data.version).join("bin");
+        let exe = |name: &str| {
+            if cfg!(target_os = "windows") {
+                bin_dir.join(format!("{}.exe", name))
+            } else {
+                bin_dir.join(name)
+            }
+        };
+        let admin = [exe("mariadb-admin"), exe("mysqladmin")]
+            .into_iter()
+            .find(|path| path.exists())
+            .ok_or_else(|| anyhow!("mariadb-admin 未安装"))?;
+
+        let mut cmd = create_command(&admin);
+        // --defaults-file 必须是第一个参数
+        cmd.arg(format!("--defaults-file={}", config_path.display()))
+            .arg("--connect-timeout=5")
+            .arg("-u")
+            .arg("root")
+            .arg(format!("--password={}", root_password))
+            .arg("shutdown");
+        Ok(cmd)
+    }
+
+    /// 从 metadata 中读取 root 密码和端口
+    fn get_root_credentials(service_data: &ServiceData) -> Result<(String, String)> {
+        let root_password = service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("MARIADB_ROOT_PASSWORD"))
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("未找到 root 密码"))?
+            .to_string();
+
+        let port = service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("MARIADB_PORT"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("3306")
+            .to_string();
+
+        Ok((root_password, port))
+    }
+}
+
+/// 初始化期间用于设置 / 验证 root 密码的临时服务器
+struct InitServer<'a> {
+    mysqld: &'a Path,
+    mysql_client: &'a Path,
+    config_path: &'a Path,
+    service_data_folder: &'a Path,
+    error_log: &'a Path,
+    port: &'a str,
+    socket: &'a Path,
+}
+
+impl InitServer<'_> {
+    /// 构建以 root 身份连接临时服务器的客户端命令，Unix 下默认走 socket
+    fn client_command(&self, password: &str, use_tcp: bool) -> Command {
+        let mut cmd = create_command(self.mysql_client);
+        if use_tcp {
+            cmd.arg(format!("--port={}", self.port))
+                .arg("--host=127.0.0.1");
+        } else {
+            cmd.arg(format!("--socket={}", self.socket.display()));
+        }
+        cmd.arg("--connect-timeout=2")
+            .arg("-u")
+            .arg("root")
+            .arg(format!("--password={}", password));
+        cmd
+    }
+
+    /// 执行 SQL，失败时返回客户端的错误输出
+    fn execute(&self, password: &str, sql: &str, use_tcp: bool) -> Result<()> {
+        let output = self
+            .client_command(password, use_tcp)
+            .arg("-e")
+            .arg(sql)
+            .output()
+            .map_err(|e| anyhow!("执行 mysql 客户端失败: {}", e))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "{}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+
+    fn error_log_tail(&self) -> String {
+        std::fs::read_to_string(self.error_log)
+            .map(|content| output_tail(content.trim(), INIT_LOG_TAIL_CHARS))
+            .unwrap_or_default()
+    }
+
+    /// 启动临时服务器并等待可以用 password 登录，最长 INIT_READY_TIMEOUT
+    ///
+    /// 服务器提前退出时错误附带退出码与错误日志末尾；密码被拒绝时立即返回错误。
+    fn start(&self, password: &str) -> Result<Child> {
+        if !self.mysql_client.exists() {
+            return Err(anyhow!(
+                "未找到 mysql 客户端: {}",
+                self.mysql_client.display()
+            ));
+        }
+
+        let capture = OutputCapture::new(self.service_data_folder, "mariadb-init")?;
+        let mut cmd = create_command(self.mysqld);
+        cmd.arg(format!("--defaults-file={}", self.config_path.display()))
+            .arg(format!("--port={}", self.port))
+            .arg(format!("--socket={}", self.socket.display()));
+        capture.attach(&mut cmd)?;
+        let mut child = cmd.spawn()?;
+
+        let use_tcp = cfg!(target_os = "windows");
+        let deadline = Instant::now() + INIT_READY_TIMEOUT;
+        let error = loop {
+            if let Some(status) = child.try_wait()? {
+                let log = self.error_log_tail();
+                return Err(if log.is_empty() {
+                    anyhow!("临时服务器已退出: {}", capture.failure_detail(&status))
+                } else {
+                    anyhow!(
+                        "临时服务器已退出: {}\n错误日志: {}",
+                        capture.failure_detail(&status),
+                        log
+                    )
+                });
+            }
+            match self.execute(password, "SELECT 1", use_tcp) {
+                Ok(()) => return Ok(child),
+                // 服务器已就绪但拒绝登录，继续等待没有意义
+                Err(e) if e.to_string().contains("Access denied") => {
+                    break anyhow!("root 密码被拒绝: {}", e);
+                }
+                Err(e) if Instant::now() >= deadline => {
+                    break anyhow!(
+                        "等待临时服务器就绪超时（{} 秒）: {}，日志: {}",
+                        INIT_READY_TIMEOUT.as_secs(),
+                        e,
+                        self.error_log.display()
+                    );
+                }
+                Err(_) => std::thread::sleep(INIT_POLL_INTERVAL),
+            }
+        };
+
+        let _ = child.kill();
+        let _ = child.wait();
+        Err(error)
+    }
+
+    /// 通过 SHUTDOWN 正常关闭临时服务器，超时或无法登录时强制结束
+    fn stop(&self, child: &mut Child, password: &str) {
+        let use_tcp = cfg!(target_os = "windows");
+        if let Err(e) = self.execute(password, "SHUTDOWN", use_tcp) {
+            log::warn!("正常关闭临时服务器失败，强制结束: {}", e);
+        } else {
+            let deadline = Instant::now() + INIT_SHUTDOWN_TIMEOUT;
+            while Instant::now() < deadline {
+                if let Ok(Some(_)) = child.try_wait() {
+                    return;
+                }
+                std::thread::sleep(INIT_POLL_INTERVAL);
+            }
+            log::warn!("等待临时服务器关闭超时，强制结束");
+        }
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+/// 在后台线程中读取 stderr，避免管道写满阻塞子进程
+fn spawn_stderr_reader<R: Read + Send + 'static>(mut stderr: R) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stderr.read_to_end(&mut buf);
+        String::from_utf8_lossy(&buf).to_string()
+    })
+}
+
+/// 分块复制数据并回调进度，每累计 1MB 或结束时回调一次，返回复制的字节数
+fn transfer_with_progress<R: Read, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    total: Option<u64>,
+    on_progress: Option<&TransferProgressCallback>,
+) -> Result<u64> {
+    const REPORT_INTERVAL: u64 = 1024 * 1024;
+
+    let mut buf = vec![0u8; TRANSFER_CHUNK_SIZE];
+    let mut transferred: u64 = 0;
+    let mut last_reported: u64 = 0;
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        writer.write_all(&buf[..n])?;
+        transferred += n as u64;
+        if let Some(callback) = on_progress {
+            if transferred - last_reported >= REPORT_INTERVAL {
+                callback(transferred, total);
+                last_reported = transferred;
+            }
+        }
+    }
+    writer.flush()?;
+    if let Some(callback) = on_progress {
+        callback(transferred, total);
+    }
+    Ok(transferred)
+}
+
+impl ServiceLifecycle for MariadbService {
+    fn is_installed(&self, version: &str) -> bool {
+        MariadbService::is_installed(self, version)
+    }
+
+    fn available_versions(&self) -> Vec<String> {
+        self.get_available_versions()
+            .into_iter()
+            .map(|v| v.version)
+            .collect()
+    }
+
+    fn download_and_install<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<DownloadResult>> {
+        Box::pin(MariadbService::download_and_install(self, version))
+    }
+
+    fn cancel_download(&self, version: &str) -> Result<()> {
+        MariadbService::cancel_download(self, version)
+    }
+
+    fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        MariadbService::get_download_progress(self, version)
+    }
+
+    fn archive_dir(&self, version: &str) -> Option<PathBuf> {
+        Some(self.get_install_path(version))
+    }
+
+    fn extract_and_install<'a>(
+        &'a self,
+        task: &'a DownloadTask,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(MariadbService::extract_and_install(self, task, version))
+    }
+
+    fn start_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        MariadbService::start_service(self, environment_id, service_data)
+    }
+
+    fn stop_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        MariadbService::stop_service(self, environment_id, service_data)
+    }
+
+    fn restart_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        MariadbService::restart_service(self, environment_id, service_data)
+    }
+
+    fn get_service_status(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        MariadbService::get_service_status(self, environment_id, service_data)
+    }
 }
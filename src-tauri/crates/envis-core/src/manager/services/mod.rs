@@ -1,8 +1,14 @@
+pub mod archive;
+pub mod compose;
 pub mod custom;
 pub mod dnsmasq;
 pub mod download_manager;
+pub mod health;
 pub mod host;
+pub mod http_api;
 pub mod java;
+pub mod kafka;
+pub mod launch;
 pub mod mariadb;
 pub mod rust;
 pub mod mingw;
@@ -11,18 +17,33 @@ pub mod mysql;
 pub mod nasm;
 pub mod nginx;
 pub mod nodejs;
+pub mod opensearch;
+pub mod php;
 pub mod postgresql;
 pub mod python;
+pub mod rabbitmq;
 pub mod redis;
+pub mod registry;
+pub mod shutdown;
 pub mod ssl;
 pub mod standard;
 pub mod traits;
+pub mod vault;
 
-pub use custom::CustomService;
+pub use compose::ComposeService;
+pub use custom::{
+    CommandOutputCallback, CustomBinary, CustomCommand, CustomCommandOutput, CustomService,
+};
 pub use dnsmasq::DnsmasqService;
-pub use download_manager::{DownloadManager, DownloadResult, DownloadStatus, DownloadTask};
+pub use download_manager::{
+    available_space, download_service_package, install_callback, remove_partial_install,
+    DiskSpaceInsufficient, DownloadManager, DownloadPaused, DownloadResult, DownloadStatus,
+    DownloadTask,
+};
+pub use health::ServiceHealth;
 pub use host::HostService;
 pub use java::JavaService;
+pub use kafka::KafkaService;
 pub use mariadb::MariadbService;
 pub use rust::RustService;
 pub use mingw::MinGWService;
@@ -31,9 +52,14 @@ pub use mysql::MysqlService;
 pub use nasm::NasmService;
 pub use nginx::NginxService;
 pub use nodejs::NodejsService;
+pub use opensearch::OpensearchService;
+pub use php::PhpService;
 pub use postgresql::PostgresqlService;
 pub use python::PythonService;
+pub use rabbitmq::RabbitmqService;
 pub use redis::RedisService;
+pub use registry::ServiceRegistry;
 pub use ssl::SslService;
 pub use standard::StandardService;
 pub use traits::ServiceLifecycle;
+pub use vault::VaultService;
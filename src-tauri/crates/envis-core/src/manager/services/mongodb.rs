@@ -1,14 +1,25 @@
 ﻿use crate::manager::app_config_manager::AppConfigManager;
-use crate::manager::env_serv_data_manager::ServiceDataResult;
-use crate::manager::services::{DownloadManager, DownloadResult, DownloadTask};
-use crate::types::{ServiceData, ServiceStatus};
-use crate::utils::create_command;
+use crate::manager::builders::{MetadataBuilder, MONGODB_METADATA_KEYS};
+use crate::manager::env_serv_data_manager::{EnvServDataManager, ServiceDataResult};
+use crate::manager::service_supervisor::ServiceSupervisor;
+use crate::manager::services::health::{self, InitializationCheck, ServiceHealth};
+use crate::manager::services::launch::{wait_for_early_exit, OutputCapture};
+use crate::manager::services::shutdown::{self, GracefulStop};
+use crate::manager::services::traits::ServiceLifecycle;
+use crate::manager::services::{
+    remove_partial_install, DownloadManager, DownloadResult, DownloadTask,
+};
+use crate::types::ServiceData;
 use crate::utils::path::to_unix_path_string;
+use crate::utils::{create_command, find_free_port, output_tail};
 use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
 use std::sync::{Arc, OnceLock};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// MongoDB 版本信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +28,79 @@ pub struct MongodbVersion {
     pub date: String,
 }
 
+/// 定时备份计划在 metadata 中的键
+const BACKUP_SCHEDULES_METADATA_KEY: &str = "MONGODB_BACKUP_SCHEDULES";
+/// crontab 条目末尾的标记注释前缀，用于识别 envis 创建的条目
+const CRON_MARKER_PREFIX: &str = "# envis-mongodump:";
+/// Windows 计划任务所在的文件夹
+const SCHTASKS_FOLDER: &str = "Envis";
+/// 初始化时创建管理员用户的临时实例可使用的端口范围
+const INIT_TEMP_PORT_RANGE: RangeInclusive<u16> = 27018..=27100;
+/// 初始化时等待临时实例可连接的最长时间
+const INIT_READY_TIMEOUT: Duration = Duration::from_secs(30);
+/// 初始化时探测临时实例是否可连接的间隔
+const INIT_READY_POLL: Duration = Duration::from_millis(500);
+/// 临时实例启动失败时错误信息中附带的日志字符数（保留末尾）
+const INIT_LOG_TAIL_CHARS: usize = 2000;
+
+/// MongoDB 定时备份计划
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MongodbBackupSchedule {
+    pub id: String,
+    pub cron_expression: String,
+    pub output_dir: String,
+    pub command: String,
+    pub created_at: String,
+}
+
+/// 定时备份计划及其在系统调度器中的状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MongodbBackupScheduleStatus {
+    #[serde(flatten)]
+    pub schedule: MongodbBackupSchedule,
+    /// 系统调度器（cron / 任务计划程序）中是否存在对应条目
+    pub installed: bool,
+}
+
+/// 查询计划（explain）的详细程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExplainVerbosity {
+    /// 只返回查询优化器选出的计划
+    QueryPlanner,
+    /// 执行选中的计划并返回执行统计
+    ExecutionStats,
+    /// 执行所有候选计划并返回各自的统计
+    AllPlansExecution,
+}
+
+impl ExplainVerbosity {
+    /// mongosh explain() 接受的参数
+    fn as_str(&self) -> &'static str {
+        match self {
+            ExplainVerbosity::QueryPlanner => "queryPlanner",
+            ExplainVerbosity::ExecutionStats => "executionStats",
+            ExplainVerbosity::AllPlansExecution => "allPlansExecution",
+        }
+    }
+}
+
+/// MongoDB 初始化参数
+#[derive(Debug, Clone, Default)]
+pub struct MongodbInitOptions {
+    pub admin_username: String,
+    pub admin_password: String,
+    /// 为空时使用配置中的端口
+    pub port: Option<String>,
+    /// 为空时使用配置中的监听地址
+    pub bind_ip: Option<String>,
+    pub enable_replica_set: bool,
+    /// 清空已有数据目录后重新初始化
+    pub reset: bool,
+}
+
 /// 全局 MongoDB 服务管理器单例
 static GLOBAL_MONGODB_SERVICE: OnceLock<Arc<MongodbService>> = OnceLock::new();
 
@@ -590,6 +674,16 @@ impl MongodbService {
 
     /// 解压并安装 MongoDB，示例实现：对 tgz 使用 tar 解压，对 zip 使用 Rust zip 库
     pub async fn extract_and_install(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let result = self.install_from_archive(task, version).await;
+        if result.is_err() {
+            // 解压安装失败时清理残缺的安装目录
+            remove_partial_install(&self.get_install_path(version));
+        }
+        result
+    }
+
+    /// 将下载的压缩包解压到安装目录
+    async fn install_from_archive(&self, task: &DownloadTask, version: &str) -> Result<()> {
         let archive_path = &task.target_path;
         let install_dir = self.get_install_path(version);
         std::fs::create_dir_all(&install_dir)?;
@@ -778,7 +872,7 @@ impl MongodbService {
                         false
                     } else {
                         // log::info!("lsof 未返回监听信息，回退到 pgrep 检查 mongod 进程");
-                        let output = create_command("pgrep").arg("-x").arg("mongod").output();
+                        let output = create_command("pgrep").arg("-f").arg(mongod.to_string_lossy().as_ref()).output();
                         match output {
                             Ok(o2) => {
                                 let stdout2 = String::from_utf8_lossy(&o2.stdout);
@@ -801,7 +895,7 @@ impl MongodbService {
                 Err(e) => {
                     // 如果 lsof 不可用，回退到 pgrep 检查进程名（不基于端口）
                     // log::warn!("执行 lsof 失败: {}，回退到 pgrep 检查 mongod 进程", e);
-                    let output = create_command("pgrep").arg("-x").arg("mongod").output();
+                    let output = create_command("pgrep").arg("-f").arg(mongod.to_string_lossy().as_ref()).output();
                     match output {
                         Ok(o) => {
                             let stdout = String::from_utf8_lossy(&o.stdout);
@@ -823,18 +917,26 @@ impl MongodbService {
             }
         };
 
-        let status = if running {
-            ServiceStatus::Running
-        } else {
-            ServiceStatus::Stopped
+        // 健康检查：端口连通后通过 mongosh 执行 ping
+        let port_latency = port
+            .parse::<u16>()
+            .ok()
+            .and_then(|p| health::check_tcp_port(&bind_ip, p));
+        let ping_latency = port_latency.and_then(|_| self.ping_server(version, &port));
+        let health = ServiceHealth {
+            process: running,
+            port_open: port_latency.is_some(),
+            responding: ping_latency.is_some(),
+            latency_ms: ping_latency.or(port_latency),
         };
-        let data = serde_json::json!({
+
+        let mut data = serde_json::json!({
             "isRunning": running,
-            "status": status,
             "port": port,
             "bindIp": bind_ip,
             "configPath": config_path.to_string_lossy().to_string(),
         });
+        health.merge_into(&mut data);
 
         Ok(ServiceDataResult {
             success: true,
@@ -849,6 +951,7 @@ impl MongodbService {
         environment_id: &str,
         service_data: &ServiceData,
     ) -> Result<ServiceDataResult> {
+        ServiceSupervisor::global().clear_intentional_stop(&service_data.id);
         log::info!("==================== 开始启动 MongoDB 服务 ====================");
         log::info!("环境 ID: {}", environment_id);
         log::info!("服务数据: {:?}", service_data);
@@ -902,52 +1005,37 @@ impl MongodbService {
         self.ensure_config_directories(&config_path)?;
         log::info!("目录检查完成");
 
-        // 根据操作系统构建启动命令
-        let os_type = if cfg!(target_os = "windows") {
-            "Windows"
-        } else if cfg!(target_os = "macos") {
-            "macOS"
-        } else {
-            "Linux"
-        };
-        log::info!("检测到操作系统: {}", os_type);
-
-        let child_res = if cfg!(target_os = "windows") {
-            // Windows: 直接启动，不使用 --fork
-            log::info!("使用 Windows 启动模式（不使用 --fork）");
-            log::info!("启动命令: {:?} --config {:?}", mongod, config_path);
-            create_command(&mongod)
-                .arg("--config")
-                .arg(&config_path)
-                .spawn()
-        } else if cfg!(target_os = "macos") {
-            // macOS: 不支持 --fork，使用后台进程方式
-            log::info!("使用 macOS 启动模式（后台进程，重定向输入输出）");
-            log::info!("启动命令: {:?} --config {:?}", mongod, config_path);
-            create_command(&mongod)
-                .arg("--config")
-                .arg(&config_path)
-                .stdin(std::process::Stdio::null())
-                .stdout(std::process::Stdio::null())
-                .stderr(std::process::Stdio::null())
-                .spawn()
-        } else {
-            // Linux: 可以在配置文件中指定 fork: true
-            log::info!("使用 Linux 启动模式");
-            log::info!("启动命令: {:?} --config {:?}", mongod, config_path);
-            create_command(&mongod)
-                .arg("--config")
-                .arg(&config_path)
-                .spawn()
-        };
-
-        match child_res {
-            Ok(child) => {
+        // 子进程输出写入 logs/mongodb-stdout.log，启动失败时可直接返回错误原因
+        let capture = OutputCapture::new(
+            &self.get_service_data_folder(environment_id, version),
+            "mongodb",
+        )?;
+        log::info!("MongoDB 输出日志: {:?}", capture.path());
+
+        // Linux 可以在配置文件中指定 fork: true，此时启动进程会在后台化后以 0 退出
+        log::info!("启动命令: {:?} --config {:?}", mongod, config_path);
+        let mut command = create_command(&mongod);
+        command.arg("--config").arg(&config_path);
+        capture.attach(&mut command)?;
+
+        match command.spawn() {
+            Ok(mut child) => {
                 log::info!("MongoDB 进程已启动，PID: {:?}", child.id());
-                log::info!("等待 500ms 让服务完成初始化...");
+                log::info!("等待进程完成初始化...");
 
-                // 等待一小段时间让服务启动
-                std::thread::sleep(Duration::from_millis(500));
+                if let Some(status) = wait_for_early_exit(&mut child) {
+                    let detail = capture.failure_detail(&status);
+                    log::error!("MongoDB 启动后立即退出: {}", detail);
+                    log::error!("==================== MongoDB 服务启动失败 ====================");
+                    return Ok(ServiceDataResult {
+                        success: false,
+                        message: format!("MongoDB 启动失败: {}", detail),
+                        data: Some(serde_json::json!({
+                            "configPath": config_path.to_string_lossy().to_string(),
+                            "outputLog": capture.path().to_string_lossy().to_string(),
+                        })),
+                    });
+                }
 
                 log::info!("MongoDB 启动流程完成");
                 log::info!("==================== MongoDB 服务启动成功 ====================");
@@ -955,11 +1043,12 @@ impl MongodbService {
                 Ok(ServiceDataResult {
                     success: true,
                     message: format!(
-                        "MongoDB 启动命令已发送（使用配置文件: {}）",
+                        "MongoDB 启动成功（使用配置文件: {}）",
                         config_path.display()
                     ),
                     data: Some(serde_json::json!({
                         "configPath": config_path.to_string_lossy().to_string(),
+                        "outputLog": capture.path().to_string_lossy().to_string(),
                     })),
                 })
             }
@@ -1016,118 +1105,211 @@ impl MongodbService {
         Ok(())
     }
 
+    /// 停止 MongoDB 服务：使用 mongosh 执行 shutdown 命令正常关闭，超时后强制结束 mongod 进程
     pub fn stop_service(
         &self,
         environment_id: &str,
-        _service_data: &ServiceData,
+        service_data: &ServiceData,
     ) -> Result<ServiceDataResult> {
-        log::info!("==================== 开始停止 MongoDB 服务 ====================");
-        log::info!("环境 ID: {}", environment_id);
+        ServiceSupervisor::global().mark_intentional_stop(&service_data.id);
+        log::info!(
+            "停止 MongoDB 服务: env={}, service_id={}",
+            environment_id,
+            service_data.id
+        );
 
-        let os_type = if cfg!(target_os = "windows") {
-            "Windows"
-        } else {
-            "Unix-like (macOS/Linux)"
-        };
-        log::info!("检测到操作系统: {}", os_type);
+        let config_path = self.get_config_path(service_data);
+        let config_content = std::fs::read_to_string(&config_path).unwrap_or_default();
+        let port = Self::parse_port_from_config(&config_content).ok();
+        // 配置了 processManagement.pidFilePath 时使用记录的 PID，否则按端口查找
+        let pid = serde_yaml::from_str::<serde_yaml::Value>(&config_content)
+            .ok()
+            .and_then(|yaml| {
+                yaml.get("processManagement")?
+                    .get("pidFilePath")?
+                    .as_str()
+                    .map(PathBuf::from)
+            })
+            .and_then(|pid_file| shutdown::read_pid_file(&pid_file));
 
-        let res = if cfg!(target_os = "windows") {
-            // Windows: 使用 taskkill
-            log::info!("使用 Windows taskkill 命令停止 MongoDB");
-            log::info!("执行命令: taskkill /IM mongod.exe /F");
+        let command = match &port {
+            Some(port) => self.shutdown_command(service_data, port),
+            None => Err(anyhow!("无法从配置文件中解析端口")),
+        };
 
-            let output = create_command("taskkill")
-                .args(&["/IM", "mongod.exe", "/F"])
-                .output();
+        Ok(GracefulStop {
+            service_name: "MongoDB",
+            method: "db.adminCommand({ shutdown: 1 })",
+            command,
+            pid,
+            port: port.and_then(|port| port.parse().ok()),
+            timeout: shutdown::stop_timeout(service_data),
+        }
+        .run())
+    }
 
-            if let Ok(ref o) = output {
-                let stdout = String::from_utf8_lossy(&o.stdout);
-                let stderr = String::from_utf8_lossy(&o.stderr);
-                log::info!("taskkill 标准输出: {}", stdout);
-                if !stderr.is_empty() {
-                    log::warn!("taskkill 标准错误: {}", stderr);
-                }
-            }
+    /// metadata 指定的配置文件，未指定时使用安装目录下的 mongod.conf
+    fn get_config_path(&self, service_data: &ServiceData) -> PathBuf {
+        service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("MONGODB_CONFIG"))
+            .and_then(|v| v.as_str())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                self.get_install_path(&service_data.version)
+                    .join("mongod.conf")
+            })
+    }
 
-            output
+    /// 构建通过 mongosh 执行 shutdown 的命令，有管理员凭据时以管理员身份连接
+    ///
+    /// shutdown 成功时服务端会直接断开连接，只有服务端返回的错误（带 codeName）才视为失败
+    fn shutdown_command(&self, service_data: &ServiceData, port: &str) -> Result<Command> {
+        let install_path = self.get_install_path(&service_data.version);
+        let mongosh_bin = if cfg!(target_os = "windows") {
+            install_path.join("bin").join("mongosh.exe")
         } else {
-            // Unix-like: 优先使用 pkill -x 精确匹配进程名
-            // -x 表示精确匹配进程名，避免误杀其他包含 mongod 的进程
-            log::info!("使用 Unix pkill 命令停止 MongoDB");
-            log::info!("执行命令: pkill -x mongod");
-            log::info!("说明: -x 参数确保只终止名称完全匹配 'mongod' 的进程");
-
-            let output = create_command("pkill").args(&["-x", "mongod"]).output();
-
-            if let Ok(ref o) = output {
-                let stdout = String::from_utf8_lossy(&o.stdout);
-                let stderr = String::from_utf8_lossy(&o.stderr);
-                if !stdout.is_empty() {
-                    log::info!("pkill 标准输出: {}", stdout);
-                }
-                if !stderr.is_empty() {
-                    log::warn!("pkill 标准错误: {}", stderr);
-                }
-            }
+            install_path.join("bin").join("mongosh")
+        };
+        if !mongosh_bin.exists() {
+            return Err(anyhow!("未找到 mongosh: {}", mongosh_bin.display()));
+        }
+
+        let mut cmd = create_command(&mongosh_bin);
+        cmd.arg(format!(
+            "mongodb://127.0.0.1:{}/?authSource=admin&serverSelectionTimeoutMS=5000",
+            port
+        ));
+        let metadata = service_data.metadata.as_ref();
+        let username = metadata
+            .and_then(|m| m.get("MONGODB_ADMIN_USERNAME"))
+            .and_then(|v| v.as_str());
+        let password = metadata
+            .and_then(|m| m.get("MONGODB_ADMIN_PASSWORD"))
+            .and_then(|v| v.as_str());
+        if let (Some(username), Some(password)) = (username, password) {
+            cmd.arg("-u").arg(username).arg("-p").arg(password);
+        }
+        cmd.arg("--quiet")
+            .arg("--eval")
+            .arg("try { db.adminCommand({ shutdown: 1 }) } catch (e) { if (e.codeName) throw e }");
+        Ok(cmd)
+    }
 
-            output
+    /// 使用 mongosh 执行 db.adminCommand({ ping: 1 })，成功时返回耗时（毫秒）
+    fn ping_server(&self, version: &str, port: &str) -> Option<u64> {
+        let install_path = self.get_install_path(version);
+        let mongosh_bin = if cfg!(target_os = "windows") {
+            install_path.join("bin").join("mongosh.exe")
+        } else {
+            install_path.join("bin").join("mongosh")
         };
+        if !mongosh_bin.exists() {
+            return None;
+        }
 
-        match res {
-            Ok(o) => {
-                // pkill 返回 0 表示成功，1 表示没有找到进程，2+ 表示错误
-                let exit_code = o.status.code().unwrap_or(-1);
-                log::info!("命令执行完成，退出码: {}", exit_code);
+        health::timed(|| Self::ping_with_mongosh(&mongosh_bin, port))
+    }
 
-                if exit_code == 0 || exit_code == 1 {
-                    // 0 = 成功停止, 1 = 进程不存在(也算成功)
-                    if exit_code == 0 {
-                        log::info!("MongoDB 进程已成功终止");
-                    } else {
-                        log::info!("MongoDB 进程不存在或已停止（退出码 1）");
-                    }
-                    log::info!("==================== MongoDB 服务停止成功 ====================");
+    /// 通过 mongosh 对本机指定端口执行 ping（不需要认证）
+    fn ping_with_mongosh(mongosh: &Path, port: &str) -> bool {
+        create_command(mongosh)
+            .arg(format!(
+                "mongodb://127.0.0.1:{}/?serverSelectionTimeoutMS=2000",
+                port
+            ))
+            .arg("--quiet")
+            .arg("--eval")
+            .arg("db.adminCommand({ ping: 1 }).ok")
+            .output()
+            .map(|o| o.status.success() && String::from_utf8_lossy(&o.stdout).trim() == "1")
+            .unwrap_or(false)
+    }
 
-                    Ok(ServiceDataResult {
-                        success: true,
-                        message: "停止 MongoDB 成功".to_string(),
-                        data: None,
-                    })
-                } else {
-                    let stderr = String::from_utf8_lossy(&o.stderr);
-                    log::error!("停止 MongoDB 失败，退出码: {}", exit_code);
-                    log::error!("错误信息: {}", stderr);
-                    log::error!("==================== MongoDB 服务停止失败 ====================");
+    /// 等待初始化用的 mongod 可以响应 ping，最长 INIT_READY_TIMEOUT
+    ///
+    /// mongod 在等待期间退出时，返回的错误附带退出码、输出与 mongod 日志末尾，说明启动失败原因。
+    fn wait_for_init_instance(
+        child: &mut Child,
+        mongosh: &Path,
+        port: &str,
+        capture: &OutputCapture,
+        log_file: &Path,
+    ) -> Result<()> {
+        let log_tail = || {
+            std::fs::read_to_string(log_file)
+                .map(|content| output_tail(content.trim(), INIT_LOG_TAIL_CHARS))
+                .unwrap_or_default()
+        };
 
-                    Ok(ServiceDataResult {
-                        success: false,
-                        message: format!("停止失败(exit {}): {}", exit_code, stderr),
-                        data: None,
-                    })
-                }
-            }
-            Err(e) => {
-                log::error!("执行停止命令时发生错误: {}", e);
-                log::error!("可能的原因: 1) 命令不存在 2) 权限不足 3) 系统错误");
-                log::error!("==================== MongoDB 服务停止失败 ====================");
+        if !mongosh.exists() {
+            return Err(anyhow!(
+                "未找到 mongosh: {}. 请确保已安装 mongosh",
+                mongosh.display()
+            ));
+        }
 
-                Ok(ServiceDataResult {
-                    success: false,
-                    message: format!("停止命令失败: {}", e),
-                    data: None,
-                })
+        let deadline = Instant::now() + INIT_READY_TIMEOUT;
+        loop {
+            if let Some(status) = child.try_wait()? {
+                let log = log_tail();
+                return Err(if log.is_empty() {
+                    anyhow!(
+                        "临时 MongoDB 实例启动失败: {}",
+                        capture.failure_detail(&status)
+                    )
+                } else {
+                    anyhow!(
+                        "临时 MongoDB 实例启动失败: {}\nmongod 日志: {}",
+                        capture.failure_detail(&status),
+                        log
+                    )
+                });
+            }
+            if Self::ping_with_mongosh(mongosh, port) {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(anyhow!(
+                    "等待临时 MongoDB 实例就绪超时（{} 秒），日志: {}",
+                    INIT_READY_TIMEOUT.as_secs(),
+                    log_file.display()
+                ));
             }
+            std::thread::sleep(INIT_READY_POLL);
         }
     }
 
+    /// 重启 MongoDB 服务，等待端口释放后再启动
     pub fn restart_service(
         &self,
         environment_id: &str,
         service_data: &ServiceData,
     ) -> Result<ServiceDataResult> {
-        let _ = self.stop_service(environment_id, service_data);
-        // 小延迟
-        std::thread::sleep(Duration::from_millis(300));
+        let stop_res = self.stop_service(environment_id, service_data)?;
+        if !stop_res.success {
+            return Ok(stop_res);
+        }
+
+        let port = std::fs::read_to_string(self.get_config_path(service_data))
+            .ok()
+            .and_then(|content| Self::parse_port_from_config(&content).ok())
+            .and_then(|port| port.parse::<u16>().ok());
+        if let Some(port) = port {
+            let timeout = shutdown::stop_timeout(service_data);
+            if !shutdown::wait_for_port_release(port, timeout) {
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: format!(
+                        "端口 {} 在 {} 秒内未释放，无法重启 MongoDB",
+                        port,
+                        timeout.as_secs()
+                    ),
+                    data: None,
+                });
+            }
+        }
         self.start_service(environment_id, service_data)
     }
 
@@ -1406,13 +1588,16 @@ impl MongodbService {
         progress_callback: impl Fn(&str, &str),
         environment_id: &str,
         service_data: &ServiceData,
-        admin_username: String,
-        admin_password: String,
-        port: Option<String>,
-        bind_ip: Option<String>,
-        enable_replica_set: bool,
-        reset: bool,
+        options: MongodbInitOptions,
     ) -> Result<ServiceDataResult> {
+        let MongodbInitOptions {
+            admin_username,
+            admin_password,
+            port,
+            bind_ip,
+            enable_replica_set,
+            reset,
+        } = options;
         // 辅助函数：发送进度事件
         let emit_progress = |step: &str, message: &str| {
             progress_callback(step, message);
@@ -1471,6 +1656,17 @@ impl MongodbService {
             emit_progress("mongodb_reset", "数据清理完成");
         }
 
+        // 重置时先标记为未完成，初始化失败后状态检查不会误报为已初始化
+        if reset {
+            if let Err(e) = EnvServDataManager::global().lock().unwrap().merge_metadata(
+                environment_id,
+                &service_data.id,
+                MetadataBuilder::build_database_reset_metadata(&MONGODB_METADATA_KEYS),
+            ) {
+                log::warn!("标记 MongoDB 重置状态失败: {}", e);
+            }
+        }
+
         // 检查是否已初始化（非重置模式）
         if !reset {
             emit_progress("mongodb_check_existing", "检查初始化状态...");
@@ -1538,15 +1734,18 @@ impl MongodbService {
         // 步骤 4: 创建管理员用户
         emit_progress("mongodb_create_admin", "启动临时实例并创建管理员用户...");
         log::info!("步骤 4/6: 启动临时实例并创建管理员用户...");
-        let temp_port = "27018"; // 使用临时端口避免冲突
-        let init_result = self.initialize_with_admin_user(
-            &mongod,
-            &data_dir,
-            &log_dir,
-            temp_port,
-            &admin_username,
-            &admin_password,
-        );
+        // 临时实例使用空闲端口，配置文件中仍是用户指定的端口
+        let init_result =
+            find_free_port(INIT_TEMP_PORT_RANGE, "临时 MongoDB 实例").and_then(|temp_port| {
+                self.initialize_with_admin_user(
+                    &mongod,
+                    &data_dir,
+                    &log_dir,
+                    &temp_port.to_string(),
+                    &admin_username,
+                    &admin_password,
+                )
+            });
 
         if let Err(e) = init_result {
             emit_progress(
@@ -1598,6 +1797,28 @@ impl MongodbService {
             false
         };
 
+        // 保存配置文件、端口与管理员凭据，并标记初始化完成
+        let mut metadata = MetadataBuilder::build_database_init_metadata(
+            &MONGODB_METADATA_KEYS,
+            &config_path,
+            &port,
+            &admin_username,
+            &admin_password,
+        );
+        metadata.insert(
+            "MONGODB_KEYFILE_PATH".to_string(),
+            serde_json::Value::String(if enable_replica_set {
+                keyfile_path.to_string_lossy().to_string()
+            } else {
+                String::new()
+            }),
+        );
+        EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .merge_metadata(environment_id, &service_data.id, metadata.clone())
+            .map_err(|e| anyhow!("保存初始化 metadata 失败: {}", e))?;
+
         emit_progress("mongodb_complete", "初始化完成！");
         log::info!("MongoDB 初始化完成！");
 
@@ -1614,6 +1835,7 @@ impl MongodbService {
                 "port": port,
                 "bindIp": bind_ip,
                 "replicaSetInitialized": replica_set_initialized,
+                "metadata": metadata,
             })),
         })
     }
@@ -1756,15 +1978,6 @@ security:
         log::info!("启动临时 MongoDB 实例...");
         log::info!("启动命令: {} {}", mongod.display(), mongod_args.join(" "));
 
-        let mut child = create_command(mongod)
-            .args(&mongod_args)
-            .spawn()
-            .map_err(|e| anyhow!("启动临时 MongoDB 实例失败: {}", e))?;
-
-        // 等待 MongoDB 启动
-        log::info!("等待 MongoDB 启动 (3秒)...");
-        std::thread::sleep(Duration::from_secs(3));
-
         // 使用 mongosh (与 mongod 在同一个 bin 目录下)
         let mongosh = if cfg!(target_os = "windows") {
             mongod.parent().unwrap().join("mongosh.exe")
@@ -1774,6 +1987,25 @@ security:
 
         log::info!("mongosh 路径: {}", mongosh.display());
 
+        let capture =
+            OutputCapture::new(log_dir.parent().unwrap_or(log_dir.as_path()), "mongod-init")?;
+        let mut command = create_command(mongod);
+        command.args(&mongod_args);
+        capture.attach(&mut command)?;
+        let mut child = command
+            .spawn()
+            .map_err(|e| anyhow!("启动临时 MongoDB 实例失败: {}", e))?;
+
+        // 等待 MongoDB 可以连接
+        log::info!("等待 MongoDB 就绪 (端口 {})...", port);
+        if let Err(e) =
+            Self::wait_for_init_instance(&mut child, &mongosh, port, &capture, &log_file)
+        {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(e);
+        }
+
         // 创建管理员用户
         log::info!("准备创建管理员用户...");
         let create_user_script = format!(
@@ -1889,18 +2121,6 @@ security:
         ];
         log::info!("启动命令: {} {}", mongod.display(), mongod_args.join(" "));
 
-        let mut child = create_command(mongod)
-            .args(&mongod_args)
-            .spawn()
-            .map_err(|e| {
-                log::error!("启动 MongoDB 失败: {}", e);
-                anyhow!("启动 MongoDB 失败: {}", e)
-            })?;
-
-        // 等待 MongoDB 启动
-        log::info!("等待 MongoDB 启动 (5秒)...");
-        std::thread::sleep(Duration::from_secs(5));
-
         // 使用 mongosh 初始化副本集 (mongosh 与 mongod 在同一个 bin 目录下)
         let mongosh = if cfg!(target_os = "windows") {
             mongod.parent().unwrap().join("mongosh.exe")
@@ -1910,6 +2130,29 @@ security:
 
         log::info!("mongosh 路径: {}", mongosh.display());
 
+        // 副本集成员地址会写入副本集配置，因此这里必须使用最终端口，不能换成临时端口
+        let capture = OutputCapture::new(
+            log_dir.parent().unwrap_or(log_dir.as_path()),
+            "mongod-replica-init",
+        )?;
+        let mut command = create_command(mongod);
+        command.args(&mongod_args);
+        capture.attach(&mut command)?;
+        let mut child = command.spawn().map_err(|e| {
+            log::error!("启动 MongoDB 失败: {}", e);
+            anyhow!("启动 MongoDB 失败: {}", e)
+        })?;
+
+        // 等待 MongoDB 可以连接
+        log::info!("等待 MongoDB 就绪 (端口 {})...", port);
+        if let Err(e) =
+            Self::wait_for_init_instance(&mut child, &mongosh, port, &capture, &init_log_file)
+        {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(e);
+        }
+
         // 使用正确的主机名（与配置文件中的 replSetName 一致）
         let init_replica_script = format!(
             r#"
@@ -1989,6 +2232,71 @@ security:
         config_path.exists() && data_dir.exists()
     }
 
+    /// 检查初始化状态：目录、metadata 中的管理员凭据，服务运行时用保存的凭据执行 ping
+    ///
+    /// metadata 以文件中的记录为准，初始化流程保存的凭据不依赖调用方传入的副本
+    pub fn check_initialization(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> InitializationCheck {
+        let service_data = EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .get_service_data(environment_id, &service_data.id)
+            .unwrap_or_else(|_| service_data.clone());
+        InitializationCheck::evaluate(
+            self.is_initialized(environment_id, &service_data),
+            &service_data,
+            &MONGODB_METADATA_KEYS,
+            || self.verify_admin_credentials(environment_id, &service_data),
+        )
+    }
+
+    /// 端口可连接时使用保存的管理员凭据执行 ping，端口不通或缺少 mongosh 时返回 None
+    fn verify_admin_credentials(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Option<bool> {
+        let metadata = service_data.metadata.as_ref()?;
+        let username = metadata.get("MONGODB_ADMIN_USERNAME")?.as_str()?;
+        let password = metadata.get("MONGODB_ADMIN_PASSWORD")?.as_str()?;
+
+        let config_path = self
+            .get_service_data_folder(environment_id, &service_data.version)
+            .join("mongod.conf");
+        let config_content = std::fs::read_to_string(config_path).ok()?;
+        let port = Self::parse_port_from_config(&config_content).ok()?;
+        health::check_tcp_port("127.0.0.1", port.parse().ok()?)?;
+
+        let install_path = self.get_install_path(&service_data.version);
+        let mongosh_bin = if cfg!(target_os = "windows") {
+            install_path.join("bin").join("mongosh.exe")
+        } else {
+            install_path.join("bin").join("mongosh")
+        };
+        if !mongosh_bin.exists() {
+            return None;
+        }
+
+        let output = create_command(&mongosh_bin)
+            .arg(format!(
+                "mongodb://127.0.0.1:{}/?authSource=admin&serverSelectionTimeoutMS=2000",
+                port
+            ))
+            .arg("-u")
+            .arg(username)
+            .arg("-p")
+            .arg(password)
+            .arg("--quiet")
+            .arg("--eval")
+            .arg("db.adminCommand({ ping: 1 }).ok")
+            .output()
+            .ok()?;
+        Some(output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "1")
+    }
+
     /// 列出所有数据库
     pub fn list_databases(
         &self,
@@ -2270,69 +2578,478 @@ security:
         })
     }
 
-    /// 创建普通用户
-    pub fn create_user(
+    /// 创建集合
+    pub fn create_collection(
         &self,
         _environment_id: &str,
         service_data: &ServiceData,
-        username: String,
-        password: String,
-        databases: Vec<String>, // 可访问的数据库列表
-        roles: Vec<String>,     // 角色列表，如 "readWrite", "read"
+        database_name: String,
+        collection_name: String,
     ) -> Result<ServiceDataResult> {
-        log::info!("创建 MongoDB 用户: {}", username);
+        log::info!("创建 MongoDB 集合: {}.{}", database_name, collection_name);
+        Self::validate_database_name(&database_name)?;
+        Self::validate_collection_name(&collection_name)?;
+
+        let script = format!(
+            "JSON.stringify(db.getSiblingDB({}).createCollection({}));",
+            serde_json::to_string(&database_name)?,
+            serde_json::to_string(&collection_name)?
+        );
+        self.eval_admin_json(service_data, &script)
+            .map_err(|e| anyhow!("创建集合失败: {}", e))?;
 
-        // 从配置中获取端口
-        let metadata = service_data
-            .metadata
-            .as_ref()
-            .ok_or_else(|| anyhow!("未找到 metadata"))?;
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("集合 '{}' 创建成功", collection_name),
+            data: Some(serde_json::json!({
+                "database": database_name,
+                "collection": collection_name
+            })),
+        })
+    }
 
-        let config_path = metadata
-            .get("MONGODB_CONFIG")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow!("未找到 MongoDB 配置文件路径"))?;
+    /// 删除集合，system.* 集合不允许删除
+    pub fn drop_collection(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+        database_name: String,
+        collection_name: String,
+    ) -> Result<ServiceDataResult> {
+        log::info!("删除 MongoDB 集合: {}.{}", database_name, collection_name);
+        Self::validate_database_name(&database_name)?;
+        Self::validate_collection_name(&collection_name)?;
+        if collection_name.starts_with("system.") {
+            return Err(anyhow!("不允许删除系统集合: {}", collection_name));
+        }
 
-        let config_content = std::fs::read_to_string(config_path)?;
-        let port = Self::parse_port_from_config(&config_content)?;
+        let script = format!(
+            "JSON.stringify({{ dropped: db.getSiblingDB({}).getCollection({}).drop() }});",
+            serde_json::to_string(&database_name)?,
+            serde_json::to_string(&collection_name)?
+        );
+        let json = self
+            .eval_admin_json(service_data, &script)
+            .map_err(|e| anyhow!("删除集合失败: {}", e))?;
+        let dropped = json
+            .get("dropped")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if !dropped {
+            return Err(anyhow!("集合 '{}' 不存在", collection_name));
+        }
 
-        // 获取管理员凭据
-        let admin_username = metadata
-            .get("MONGODB_ADMIN_USERNAME")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow!("未找到管理员用户名"))?;
-        let admin_password = metadata
-            .get("MONGODB_ADMIN_PASSWORD")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow!("未找到管理员密码"))?;
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("集合 '{}' 已删除", collection_name),
+            data: Some(serde_json::json!({
+                "database": database_name,
+                "collection": collection_name
+            })),
+        })
+    }
 
-        // 获取 mongosh 路径
-        let install_path = self.get_install_path(&service_data.version);
-        let mongosh_path = if cfg!(target_os = "windows") {
-            install_path.join("bin").join("mongosh.exe")
-        } else {
-            install_path.join("bin").join("mongosh")
-        };
+    /// 获取集合统计信息（文档数、存储大小、索引大小）
+    pub fn get_collection_stats(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+        database_name: String,
+        collection_name: String,
+    ) -> Result<ServiceDataResult> {
+        Self::validate_database_name(&database_name)?;
+        Self::validate_collection_name(&collection_name)?;
 
-        if !mongosh_path.exists() {
-            return Err(anyhow!("mongosh 未安装"));
-        }
+        let script = format!(
+            "JSON.stringify(db.getSiblingDB({}).getCollection({}).stats());",
+            serde_json::to_string(&database_name)?,
+            serde_json::to_string(&collection_name)?
+        );
+        let stats = Self::normalize_numbers(
+            self.eval_admin_json(service_data, &script)
+                .map_err(|e| anyhow!("获取集合统计信息失败: {}", e))?,
+        );
 
-        // 构建用户角色数组
-        let mut roles_array = Vec::new();
-        for db in &databases {
-            for role in &roles {
-                roles_array.push(format!("{{ role: '{}', db: '{}' }}", role, db));
-            }
-        }
-        let roles_str = roles_array.join(", ");
+        let number = |key: &str| stats.get(key).and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let index_sizes = stats
+            .get("indexSizes")
+            .cloned()
+            .unwrap_or_else(|| serde_json::json!({}));
 
-        // 构建创建用户的命令
-        let create_user_script = format!(
-            r#"
-            db.getSiblingDB('admin').auth('{}', '{}');
-            db.getSiblingDB('admin').createUser({{
-                user: '{}',
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("获取集合 '{}' 统计信息成功", collection_name),
+            data: Some(serde_json::json!({
+                "database": database_name,
+                "collection": collection_name,
+                "count": number("count") as u64,
+                "size": number("size") as u64,
+                "avgObjSize": number("avgObjSize"),
+                "storageSize": number("storageSize") as u64,
+                "totalIndexSize": number("totalIndexSize") as u64,
+                "totalSize": number("totalSize") as u64,
+                "nindexes": number("nindexes") as u64,
+                "indexSizes": index_sizes,
+                "capped": stats.get("capped").and_then(|v| v.as_bool()).unwrap_or(false),
+            })),
+        })
+    }
+
+    /// 列出集合的索引
+    pub fn list_indexes(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+        database_name: String,
+        collection_name: String,
+    ) -> Result<ServiceDataResult> {
+        Self::validate_database_name(&database_name)?;
+        Self::validate_collection_name(&collection_name)?;
+
+        let script = format!(
+            "JSON.stringify(db.getSiblingDB({}).getCollection({}).getIndexes());",
+            serde_json::to_string(&database_name)?,
+            serde_json::to_string(&collection_name)?
+        );
+        let json = Self::normalize_numbers(
+            self.eval_admin_json(service_data, &script)
+                .map_err(|e| anyhow!("获取索引列表失败: {}", e))?,
+        );
+        let indexes: Vec<serde_json::Value> = json
+            .as_array()
+            .ok_or_else(|| anyhow!("无法解析索引列表"))?
+            .iter()
+            .map(|index| {
+                serde_json::json!({
+                    "name": index.get("name").and_then(|v| v.as_str()).unwrap_or(""),
+                    "key": index.get("key").cloned().unwrap_or_else(|| serde_json::json!({})),
+                    "unique": index.get("unique").and_then(|v| v.as_bool()).unwrap_or(false),
+                    "sparse": index.get("sparse").and_then(|v| v.as_bool()).unwrap_or(false),
+                    "expireAfterSeconds": index.get("expireAfterSeconds").cloned(),
+                })
+            })
+            .collect();
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("获取集合 '{}' 的索引列表成功", collection_name),
+            data: Some(serde_json::json!({ "indexes": indexes })),
+        })
+    }
+
+    /// 创建索引，返回 MongoDB 生成（或 options.name 指定）的索引名称
+    ///
+    /// `keys` 可以是 `{ "field": 1 }` 形式的对象，也可以是 `[["a", 1], ["b", -1]]` 形式的数组；
+    /// 复合索引的字段顺序有意义，需要保证顺序时使用数组形式。
+    pub fn create_index(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+        database_name: String,
+        collection_name: String,
+        keys: serde_json::Value,
+        options: serde_json::Value,
+    ) -> Result<ServiceDataResult> {
+        log::info!("创建 MongoDB 索引: {}.{}", database_name, collection_name);
+        Self::validate_database_name(&database_name)?;
+        Self::validate_collection_name(&collection_name)?;
+        let keys_literal = Self::build_index_keys_literal(&keys)?;
+        let options = match options {
+            serde_json::Value::Null => serde_json::json!({}),
+            serde_json::Value::Object(map) => serde_json::Value::Object(map),
+            _ => return Err(anyhow!("索引选项必须是对象")),
+        };
+
+        let script = format!(
+            "JSON.stringify({{ name: db.getSiblingDB({}).getCollection({}).createIndex({}, {}) }});",
+            serde_json::to_string(&database_name)?,
+            serde_json::to_string(&collection_name)?,
+            keys_literal,
+            serde_json::to_string(&options)?
+        );
+        let json = self
+            .eval_admin_json(service_data, &script)
+            .map_err(|e| anyhow!("创建索引失败: {}", e))?;
+        let index_name = json
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("索引 '{}' 创建成功", index_name),
+            data: Some(serde_json::json!({
+                "database": database_name,
+                "collection": collection_name,
+                "name": index_name
+            })),
+        })
+    }
+
+    /// 将索引字段转换为保持字段顺序的 JS 对象字面量，
+    /// 字段方向只允许 1 / -1 或 "text"、"2dsphere"、"hashed" 等索引类型字符串
+    fn build_index_keys_literal(keys: &serde_json::Value) -> Result<String> {
+        let fields: Vec<(String, &serde_json::Value)> = match keys {
+            serde_json::Value::Object(map) => map.iter().map(|(k, v)| (k.clone(), v)).collect(),
+            serde_json::Value::Array(items) => items
+                .iter()
+                .map(|item| match item.as_array().map(|pair| pair.as_slice()) {
+                    Some([serde_json::Value::String(field), direction]) => {
+                        Ok((field.clone(), direction))
+                    }
+                    _ => Err(anyhow!("索引字段必须是 [字段名, 方向] 形式")),
+                })
+                .collect::<Result<_>>()?,
+            _ => return Err(anyhow!("索引字段必须是对象或数组")),
+        };
+        if fields.is_empty() {
+            return Err(anyhow!("索引字段不能为空"));
+        }
+
+        let mut parts = Vec::with_capacity(fields.len());
+        for (field, direction) in fields {
+            if field.trim().is_empty() || field.contains('\0') {
+                return Err(anyhow!("索引字段名无效: {:?}", field));
+            }
+            let valid = match direction {
+                serde_json::Value::Number(n) => matches!(n.as_i64(), Some(1) | Some(-1)),
+                serde_json::Value::String(s) => !s.trim().is_empty(),
+                _ => false,
+            };
+            if !valid {
+                return Err(anyhow!("索引字段 {} 的方向无效: {}", field, direction));
+            }
+            parts.push(format!(
+                "{}: {}",
+                serde_json::to_string(&field)?,
+                serde_json::to_string(direction)?
+            ));
+        }
+        Ok(format!("{{ {} }}", parts.join(", ")))
+    }
+
+    /// 获取查询的执行计划
+    ///
+    /// `query_json` 为 find 的查询条件，按 Extended JSON 解析，可以使用 `{"$oid": ...}`、`{"$date": ...}` 等写法。
+    pub fn explain_query(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+        database_name: String,
+        collection_name: String,
+        query_json: String,
+        verbosity: ExplainVerbosity,
+    ) -> Result<ServiceDataResult> {
+        Self::validate_database_name(&database_name)?;
+        Self::validate_collection_name(&collection_name)?;
+        let query_json = if query_json.trim().is_empty() {
+            "{}".to_string()
+        } else {
+            query_json
+        };
+        match serde_json::from_str::<serde_json::Value>(&query_json) {
+            Ok(serde_json::Value::Object(_)) => {}
+            Ok(_) => return Err(anyhow!("查询条件必须是 JSON 对象")),
+            Err(e) => return Err(anyhow!("查询条件不是合法的 JSON: {}", e)),
+        }
+
+        let script = format!(
+            "JSON.stringify(db.getSiblingDB({}).getCollection({}).explain({}).find(EJSON.parse({})).finish());",
+            serde_json::to_string(&database_name)?,
+            serde_json::to_string(&collection_name)?,
+            serde_json::to_string(verbosity.as_str())?,
+            serde_json::to_string(&query_json)?
+        );
+        let plan = Self::normalize_numbers(
+            self.eval_admin_json(service_data, &script)
+                .map_err(|e| anyhow!("获取查询计划失败: {}", e))?,
+        );
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("获取集合 '{}' 的查询计划成功", collection_name),
+            data: Some(plan),
+        })
+    }
+
+    /// 校验数据库名称（MongoDB 不允许 /\. "$ 和空字符）
+    fn validate_database_name(name: &str) -> Result<()> {
+        if name.is_empty() {
+            return Err(anyhow!("数据库名称不能为空"));
+        }
+        if name
+            .chars()
+            .any(|c| matches!(c, '/' | '\\' | '.' | ' ' | '"' | '$' | '\0'))
+        {
+            return Err(anyhow!("数据库名称包含非法字符: {}", name));
+        }
+        Ok(())
+    }
+
+    /// 校验集合名称（不能为空，不能包含 $ 和空字符）
+    fn validate_collection_name(name: &str) -> Result<()> {
+        if name.is_empty() {
+            return Err(anyhow!("集合名称不能为空"));
+        }
+        if name.contains('$') || name.contains('\0') {
+            return Err(anyhow!("集合名称包含非法字符: {}", name));
+        }
+        Ok(())
+    }
+
+    /// 以管理员身份执行 mongosh 脚本，解析最后一行输出的 JSON
+    fn eval_admin_json(
+        &self,
+        service_data: &ServiceData,
+        script: &str,
+    ) -> Result<serde_json::Value> {
+        let connection_string = Self::build_admin_connection_string(service_data)?;
+        let install_path = self.get_install_path(&service_data.version);
+        let mongosh_bin = if cfg!(target_os = "windows") {
+            install_path.join("bin").join("mongosh.exe")
+        } else {
+            install_path.join("bin").join("mongosh")
+        };
+        if !mongosh_bin.exists() {
+            return Err(anyhow!("mongosh 未安装，请先安装 MongoDB"));
+        }
+
+        let output = create_command(&mongosh_bin)
+            .arg(&connection_string)
+            .arg("--quiet")
+            .arg("--eval")
+            .arg(script)
+            .output()?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let error = if stderr.trim().is_empty() {
+                stdout.trim()
+            } else {
+                stderr.trim()
+            };
+            return Err(anyhow!("{}", error));
+        }
+
+        let line = stdout
+            .lines()
+            .rev()
+            .find(|l| !l.trim().is_empty())
+            .ok_or_else(|| anyhow!("mongosh 没有输出"))?;
+        serde_json::from_str(line.trim()).map_err(|e| anyhow!("解析 mongosh 输出失败: {}", e))
+    }
+
+    /// 将 mongosh 输出中的 Long / NumberLong 等包装值转换为普通数字
+    ///
+    /// 兼容 `{"$numberLong": "1"}` 等 Extended JSON 形式以及 bson Long 的 `{low, high, unsigned}` 形式。
+    fn normalize_numbers(value: serde_json::Value) -> serde_json::Value {
+        use serde_json::Value;
+        match value {
+            Value::Object(map) => {
+                for key in [
+                    "$numberLong",
+                    "$numberInt",
+                    "$numberDouble",
+                    "$numberDecimal",
+                ] {
+                    if map.len() == 1 {
+                        if let Some(Value::String(raw)) = map.get(key) {
+                            if let Ok(n) = raw.parse::<i64>() {
+                                return Value::from(n);
+                            }
+                            if let Ok(n) = raw.parse::<f64>() {
+                                return Value::from(n);
+                            }
+                        }
+                    }
+                }
+                if let (Some(low), Some(high)) = (
+                    map.get("low").and_then(|v| v.as_i64()),
+                    map.get("high").and_then(|v| v.as_i64()),
+                ) {
+                    if map.len() <= 3 && (map.len() == 2 || map.contains_key("unsigned")) {
+                        let n = (high << 32) | (low & 0xffff_ffff);
+                        return Value::from(n);
+                    }
+                }
+                Value::Object(
+                    map.into_iter()
+                        .map(|(k, v)| (k, Self::normalize_numbers(v)))
+                        .collect(),
+                )
+            }
+            Value::Array(items) => {
+                Value::Array(items.into_iter().map(Self::normalize_numbers).collect())
+            }
+            other => other,
+        }
+    }
+
+    /// 创建普通用户
+    pub fn create_user(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+        username: String,
+        password: String,
+        databases: Vec<String>, // 可访问的数据库列表
+        roles: Vec<String>,     // 角色列表，如 "readWrite", "read"
+    ) -> Result<ServiceDataResult> {
+        log::info!("创建 MongoDB 用户: {}", username);
+
+        // 从配置中获取端口
+        let metadata = service_data
+            .metadata
+            .as_ref()
+            .ok_or_else(|| anyhow!("未找到 metadata"))?;
+
+        let config_path = metadata
+            .get("MONGODB_CONFIG")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("未找到 MongoDB 配置文件路径"))?;
+
+        let config_content = std::fs::read_to_string(config_path)?;
+        let port = Self::parse_port_from_config(&config_content)?;
+
+        // 获取管理员凭据
+        let admin_username = metadata
+            .get("MONGODB_ADMIN_USERNAME")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("未找到管理员用户名"))?;
+        let admin_password = metadata
+            .get("MONGODB_ADMIN_PASSWORD")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("未找到管理员密码"))?;
+
+        // 获取 mongosh 路径
+        let install_path = self.get_install_path(&service_data.version);
+        let mongosh_path = if cfg!(target_os = "windows") {
+            install_path.join("bin").join("mongosh.exe")
+        } else {
+            install_path.join("bin").join("mongosh")
+        };
+
+        if !mongosh_path.exists() {
+            return Err(anyhow!("mongosh 未安装"));
+        }
+
+        // 构建用户角色数组
+        let mut roles_array = Vec::new();
+        for db in &databases {
+            for role in &roles {
+                roles_array.push(format!("{{ role: '{}', db: '{}' }}", role, db));
+            }
+        }
+        let roles_str = roles_array.join(", ");
+
+        // 构建创建用户的命令
+        let create_user_script = format!(
+            r#"
+            db.getSiblingDB('admin').auth('{}', '{}');
+            db.getSiblingDB('admin').createUser({{
+                user: '{}',
                 pwd: '{}',
                 roles: [{}]
             }});
@@ -2633,4 +3350,865 @@ security:
             })),
         })
     }
+
+    /// 修改管理员密码（无需重新初始化），成功后同步更新 metadata 中的密码
+    pub fn change_admin_password(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        new_password: String,
+    ) -> Result<ServiceDataResult> {
+        if new_password.is_empty() {
+            return Err(anyhow!("新密码不能为空"));
+        }
+
+        let metadata = service_data
+            .metadata
+            .as_ref()
+            .ok_or_else(|| anyhow!("未找到 metadata"))?;
+        let admin_username = metadata
+            .get("MONGODB_ADMIN_USERNAME")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("未找到管理员用户名"))?;
+        let admin_password = metadata
+            .get("MONGODB_ADMIN_PASSWORD")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("未找到管理员密码"))?;
+        let config_path = metadata
+            .get("MONGODB_CONFIG")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("未找到 MongoDB 配置文件路径"))?;
+
+        let config_content = std::fs::read_to_string(config_path)?;
+        let port = Self::parse_port_from_config(&config_content)?;
+
+        // 服务必须在运行
+        let is_running = self
+            .get_service_status(environment_id, service_data)?
+            .data
+            .and_then(|d| d.get("isRunning").and_then(|v| v.as_bool()))
+            .unwrap_or(false);
+        if !is_running {
+            return Err(anyhow!("MongoDB 服务未运行，请先启动服务"));
+        }
+
+        let install_path = self.get_install_path(&service_data.version);
+        let mongosh_path = if cfg!(target_os = "windows") {
+            install_path.join("bin").join("mongosh.exe")
+        } else {
+            install_path.join("bin").join("mongosh")
+        };
+        if !mongosh_path.exists() {
+            return Err(anyhow!("mongosh 未安装"));
+        }
+
+        // 用户名和密码以 JSON 字符串字面量传入脚本，避免引号注入
+        let script = format!(
+            "db.getSiblingDB('admin').changeUserPassword({}, {});",
+            serde_json::to_string(admin_username)?,
+            serde_json::to_string(&new_password)?
+        );
+
+        let output = create_command(&mongosh_path)
+            .arg("--port")
+            .arg(&port)
+            .arg("--username")
+            .arg(admin_username)
+            .arg("--password")
+            .arg(admin_password)
+            .arg("--authenticationDatabase")
+            .arg("admin")
+            .arg("--quiet")
+            .arg("--eval")
+            .arg(&script)
+            .output()?;
+
+        if !output.status.success() {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stdout.contains("Authentication failed") || stderr.contains("Authentication failed") {
+                return Err(anyhow!("使用已保存的管理员凭据认证失败"));
+            }
+            return Err(anyhow!("修改管理员密码失败: {}{}", stderr, stdout));
+        }
+
+        // 用新密码重新认证，确认修改生效后才写入 metadata
+        let verify = create_command(&mongosh_path)
+            .arg("--port")
+            .arg(&port)
+            .arg("--username")
+            .arg(admin_username)
+            .arg("--password")
+            .arg(&new_password)
+            .arg("--authenticationDatabase")
+            .arg("admin")
+            .arg("--quiet")
+            .arg("--eval")
+            .arg("JSON.stringify(db.adminCommand({ ping: 1 }))")
+            .output()?;
+        if !verify.status.success() {
+            log::warn!(
+                "MongoDB 管理员 {} 新密码验证失败，metadata 保持原密码: {}",
+                admin_username,
+                String::from_utf8_lossy(&verify.stderr)
+            );
+            return Err(anyhow!(
+                "新密码验证失败，已保留原有凭据，请确认数据库中的管理员密码"
+            ));
+        }
+
+        // 数据库中已修改成功，同步保存到 metadata
+        let mut service_data = service_data.clone();
+        EnvServDataManager::global().lock().unwrap().set_metadata(
+            environment_id,
+            &mut service_data,
+            "MONGODB_ADMIN_PASSWORD",
+            serde_json::Value::String(new_password),
+        )?;
+
+        log::info!("MongoDB 管理员 {} 密码已修改", admin_username);
+        Ok(ServiceDataResult {
+            success: true,
+            message: "管理员密码修改成功".to_string(),
+            data: Some(serde_json::json!({ "username": admin_username })),
+        })
+    }
+
+    /// 轮换副本集 keyfile
+    ///
+    /// mongod 只在启动时读取 keyfile：运行中的服务会先停止，替换配置中的 keyFile 后再启动。
+    /// 启动失败时恢复原 keyfile 配置并重新启动，metadata 不做修改。
+    pub fn rotate_keyfile(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let config_path = service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("MONGODB_CONFIG"))
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("未找到 MongoDB 配置文件路径"))?
+            .to_string();
+        let config_content = std::fs::read_to_string(&config_path)?;
+
+        let old_keyfile = config_content
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("keyFile:"))
+            .map(|v| v.trim().trim_matches('"').to_string())
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| anyhow!("当前配置未启用 keyFile，仅副本集模式需要轮换"))?;
+        let old_keyfile_path = PathBuf::from(&old_keyfile);
+        let keyfile_dir = old_keyfile_path
+            .parent()
+            .map(|p| p.to_path_buf())
+            .ok_or_else(|| anyhow!("无法确定 keyfile 所在目录"))?;
+
+        let new_keyfile_path = keyfile_dir.join(format!(
+            "mongodb-keyfile-{}",
+            chrono::Local::now().format("%Y%m%d%H%M%S")
+        ));
+        self.create_keyfile(&new_keyfile_path)
+            .map_err(|e| anyhow!("创建新 keyfile 失败: {}", e))?;
+
+        let new_config = config_content
+            .lines()
+            .map(|line| match line.trim_start().strip_prefix("keyFile:") {
+                Some(_) => {
+                    let indent = &line[..line.len() - line.trim_start().len()];
+                    format!(
+                        "{}keyFile: {}",
+                        indent,
+                        to_unix_path_string(&new_keyfile_path)
+                    )
+                }
+                None => line.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+            + "\n";
+
+        let was_running = self
+            .get_service_status(environment_id, service_data)?
+            .data
+            .and_then(|d| d.get("isRunning").and_then(|v| v.as_bool()))
+            .unwrap_or(false);
+        if was_running {
+            log::info!("轮换 keyfile 需要重启 MongoDB，正在停止服务");
+            let stopped = self.stop_service(environment_id, service_data)?;
+            if !stopped.success {
+                let _ = std::fs::remove_file(&new_keyfile_path);
+                return Err(anyhow!("停止 MongoDB 失败: {}", stopped.message));
+            }
+        }
+
+        std::fs::write(&config_path, &new_config)?;
+
+        if was_running {
+            let started = self.start_service(environment_id, service_data);
+            let start_error = match started {
+                Ok(res) if res.success => None,
+                Ok(res) => Some(res.message),
+                Err(e) => Some(e.to_string()),
+            };
+            if let Some(error) = start_error {
+                log::warn!("使用新 keyfile 启动失败，恢复原配置: {}", error);
+                std::fs::write(&config_path, &config_content)?;
+                let _ = std::fs::remove_file(&new_keyfile_path);
+                if let Err(e) = self.start_service(environment_id, service_data) {
+                    log::warn!("恢复原 keyfile 后启动 MongoDB 失败: {}", e);
+                }
+                return Err(anyhow!(
+                    "使用新 keyfile 启动失败，已恢复原 keyfile: {}",
+                    error
+                ));
+            }
+        }
+
+        let mut service_data = service_data.clone();
+        EnvServDataManager::global().lock().unwrap().set_metadata(
+            environment_id,
+            &mut service_data,
+            "MONGODB_KEYFILE_PATH",
+            serde_json::Value::String(new_keyfile_path.to_string_lossy().to_string()),
+        )?;
+
+        if old_keyfile_path != new_keyfile_path {
+            if let Err(e) = std::fs::remove_file(&old_keyfile_path) {
+                log::warn!("删除旧 keyfile 失败: {}", e);
+            }
+        }
+
+        log::info!("MongoDB keyfile 已轮换: {}", new_keyfile_path.display());
+        Ok(ServiceDataResult {
+            success: true,
+            message: if was_running {
+                "keyfile 已轮换，服务已重启".to_string()
+            } else {
+                "keyfile 已轮换，下次启动服务时生效".to_string()
+            },
+            data: Some(serde_json::json!({
+                "keyfilePath": new_keyfile_path.to_string_lossy().to_string(),
+                "restarted": was_running,
+            })),
+        })
+    }
+
+    /// 获取副本集状态（rs.status()），返回各成员的状态、健康度与复制延迟
+    pub fn get_replication_status(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let config_path = service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("MONGODB_CONFIG"))
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("未找到 MongoDB 配置文件路径"))?;
+        let config_content = std::fs::read_to_string(config_path)?;
+        let repl_set_name = config_content
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("replSetName:"))
+            .map(|v| v.trim().trim_matches('"').trim_matches('\'').to_string())
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| anyhow!("当前配置未设置 replSetName，未启用副本集"))?;
+
+        let status = Self::normalize_numbers(
+            self.eval_admin_json(service_data, "JSON.stringify(rs.status());")
+                .map_err(|e| anyhow!("获取副本集状态失败: {}", e))?,
+        );
+        let raw_members = status
+            .get("members")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        // 复制延迟以主节点的 optimeDate 为基准
+        let optime = |member: &serde_json::Value| {
+            member
+                .get("optimeDate")
+                .and_then(|v| v.as_str())
+                .and_then(|v| chrono::DateTime::parse_from_rfc3339(v).ok())
+        };
+        let primary_optime = raw_members
+            .iter()
+            .find(|m| m.get("stateStr").and_then(|v| v.as_str()) == Some("PRIMARY"))
+            .and_then(optime);
+
+        let members: Vec<serde_json::Value> = raw_members
+            .iter()
+            .map(|member| {
+                let lag_seconds = primary_optime
+                    .zip(optime(member))
+                    .map(|(primary, current)| (primary - current).num_seconds().max(0));
+                serde_json::json!({
+                    "name": member.get("name").and_then(|v| v.as_str()).unwrap_or(""),
+                    "state": member.get("stateStr").and_then(|v| v.as_str()).unwrap_or("UNKNOWN"),
+                    "health": member.get("health").and_then(|v| v.as_f64()).unwrap_or(0.0) >= 1.0,
+                    "lagSeconds": lag_seconds,
+                })
+            })
+            .collect();
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("获取副本集 '{}' 状态成功", repl_set_name),
+            data: Some(serde_json::json!({
+                "set": status.get("set").and_then(|v| v.as_str()).unwrap_or(&repl_set_name),
+                "members": members,
+                "ok": status.get("ok").and_then(|v| v.as_f64()) == Some(1.0),
+            })),
+        })
+    }
+
+    /// 使用 mongodump 将数据导出到指定目录（供 `envis mongodump` 及定时备份调用）
+    pub fn dump_to_directory(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+        output_dir: &str,
+    ) -> Result<ServiceDataResult> {
+        let connection_string = Self::build_admin_connection_string(service_data)?;
+        let mongodump_bin = self.get_database_tool_bin(&service_data.version, "mongodump")?;
+
+        let output_dir = PathBuf::from(output_dir);
+        std::fs::create_dir_all(&output_dir)?;
+        let archive_path = output_dir.join(format!(
+            "mongodb-{}-{}.archive.gz",
+            service_data.id,
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+
+        let output = create_command(&mongodump_bin)
+            .arg(format!("--uri={}", connection_string))
+            .arg(format!("--archive={}", archive_path.to_string_lossy()))
+            .arg("--gzip")
+            .output()
+            .map_err(|e| anyhow!("执行 mongodump 失败: {}", e))?;
+
+        if !output.status.success() {
+            let _ = std::fs::remove_file(&archive_path);
+            return Ok(ServiceDataResult {
+                success: false,
+                message: format!(
+                    "mongodump 执行失败: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+                data: None,
+            });
+        }
+
+        log::info!("MongoDB 数据已导出到 {}", archive_path.display());
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("已导出到 {}", archive_path.display()),
+            data: Some(serde_json::json!({
+                "archivePath": archive_path.to_string_lossy().to_string(),
+            })),
+        })
+    }
+
+    /// 创建定时备份计划：Unix 写入 crontab，Windows 使用 schtasks 创建计划任务
+    pub fn create_backup_schedule(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        cron_expression: &str,
+        output_dir: &str,
+    ) -> Result<ServiceDataResult> {
+        let cron_expression = cron_expression
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        Self::validate_cron_expression(&cron_expression)?;
+        if output_dir.trim().is_empty() {
+            return Err(anyhow!("备份输出目录不能为空"));
+        }
+        // 提前检查工具与管理员账号，避免创建出必然失败的计划
+        Self::build_admin_connection_string(service_data)?;
+        self.get_database_tool_bin(&service_data.version, "mongodump")?;
+        std::fs::create_dir_all(output_dir)?;
+
+        let envis_exe =
+            std::env::current_exe().map_err(|e| anyhow!("无法获取 envis 可执行文件路径: {}", e))?;
+        let schedule_id = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+        let args = [
+            "mongodump",
+            "--env",
+            environment_id,
+            "--service",
+            &service_data.id,
+            "--output",
+            output_dir,
+        ];
+
+        let command = if cfg!(target_os = "windows") {
+            let command = std::iter::once(envis_exe.to_string_lossy().to_string())
+                .chain(args.iter().map(|a| a.to_string()))
+                .map(|a| {
+                    if a.contains(' ') {
+                        format!("\"{}\"", a)
+                    } else {
+                        a
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            let mut schtasks = create_command("schtasks");
+            schtasks
+                .arg("/Create")
+                .arg("/F")
+                .arg("/TN")
+                .arg(Self::schtasks_name(&schedule_id))
+                .arg("/TR")
+                .arg(&command)
+                .args(Self::cron_to_schtasks_args(&cron_expression)?);
+            let output = schtasks
+                .output()
+                .map_err(|e| anyhow!("执行 schtasks 失败: {}", e))?;
+            if !output.status.success() {
+                return Err(anyhow!(
+                    "创建计划任务失败: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ));
+            }
+            command
+        } else {
+            let log_path = PathBuf::from(output_dir).join("envis-mongodump.log");
+            let command = std::iter::once(envis_exe.to_string_lossy().to_string())
+                .chain(args.iter().map(|a| a.to_string()))
+                .map(|a| Self::shell_quote(&a))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let command = format!(
+                "{} >> {} 2>&1",
+                command,
+                Self::shell_quote(&log_path.to_string_lossy())
+            );
+            let mut lines = Self::read_crontab()?;
+            lines.push(format!(
+                "{} {} {}{}",
+                cron_expression, command, CRON_MARKER_PREFIX, schedule_id
+            ));
+            Self::write_crontab(&lines)?;
+            command
+        };
+
+        let schedule = MongodbBackupSchedule {
+            id: schedule_id,
+            cron_expression,
+            output_dir: output_dir.to_string(),
+            command,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+        let mut schedules = Self::get_backup_schedules(service_data);
+        schedules.push(schedule.clone());
+        let mut service_data = service_data.clone();
+        EnvServDataManager::global().lock().unwrap().set_metadata(
+            environment_id,
+            &mut service_data,
+            BACKUP_SCHEDULES_METADATA_KEY,
+            serde_json::to_value(&schedules)?,
+        )?;
+
+        log::info!(
+            "已为 MongoDB {} 创建定时备份: {} -> {}",
+            service_data.name,
+            schedule.cron_expression,
+            schedule.output_dir
+        );
+        Ok(ServiceDataResult {
+            success: true,
+            message: "定时备份已创建".to_string(),
+            data: Some(serde_json::json!({ "schedule": schedule })),
+        })
+    }
+
+    /// 删除定时备份计划
+    pub fn delete_backup_schedule(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        schedule_id: &str,
+    ) -> Result<ServiceDataResult> {
+        let mut schedules = Self::get_backup_schedules(service_data);
+        let before = schedules.len();
+        schedules.retain(|s| s.id != schedule_id);
+        if schedules.len() == before {
+            return Err(anyhow!("定时备份不存在: {}", schedule_id));
+        }
+
+        if cfg!(target_os = "windows") {
+            let output = create_command("schtasks")
+                .arg("/Delete")
+                .arg("/F")
+                .arg("/TN")
+                .arg(Self::schtasks_name(schedule_id))
+                .output()
+                .map_err(|e| anyhow!("执行 schtasks 失败: {}", e))?;
+            // 任务已被手动删除时仍然清理记录
+            if !output.status.success() {
+                log::warn!(
+                    "删除计划任务失败: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+        } else {
+            let marker = format!("{}{}", CRON_MARKER_PREFIX, schedule_id);
+            let lines = Self::read_crontab()?;
+            let remaining: Vec<String> = lines
+                .iter()
+                .filter(|line| !line.trim_end().ends_with(&marker))
+                .cloned()
+                .collect();
+            if remaining.len() != lines.len() {
+                Self::write_crontab(&remaining)?;
+            }
+        }
+
+        let mut service_data = service_data.clone();
+        EnvServDataManager::global().lock().unwrap().set_metadata(
+            environment_id,
+            &mut service_data,
+            BACKUP_SCHEDULES_METADATA_KEY,
+            serde_json::to_value(&schedules)?,
+        )?;
+
+        log::info!("已删除 MongoDB 定时备份: {}", schedule_id);
+        Ok(ServiceDataResult {
+            success: true,
+            message: "定时备份已删除".to_string(),
+            data: None,
+        })
+    }
+
+    /// 列出定时备份计划，并检查系统调度器中是否仍存在对应条目
+    pub fn list_backup_schedules(
+        &self,
+        service_data: &ServiceData,
+    ) -> Result<Vec<MongodbBackupScheduleStatus>> {
+        let schedules = Self::get_backup_schedules(service_data);
+        let crontab = if cfg!(target_os = "windows") || schedules.is_empty() {
+            Vec::new()
+        } else {
+            Self::read_crontab()?
+        };
+
+        Ok(schedules
+            .into_iter()
+            .map(|schedule| {
+                let installed = if cfg!(target_os = "windows") {
+                    create_command("schtasks")
+                        .arg("/Query")
+                        .arg("/TN")
+                        .arg(Self::schtasks_name(&schedule.id))
+                        .output()
+                        .map(|o| o.status.success())
+                        .unwrap_or(false)
+                } else {
+                    let marker = format!("{}{}", CRON_MARKER_PREFIX, schedule.id);
+                    crontab
+                        .iter()
+                        .any(|line| line.trim_end().ends_with(&marker))
+                };
+                MongodbBackupScheduleStatus {
+                    schedule,
+                    installed,
+                }
+            })
+            .collect())
+    }
+
+    fn get_backup_schedules(service_data: &ServiceData) -> Vec<MongodbBackupSchedule> {
+        service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get(BACKUP_SCHEDULES_METADATA_KEY))
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// 校验 5 段式 cron 表达式（% 在 crontab 中表示换行，不允许出现）
+    fn validate_cron_expression(cron_expression: &str) -> Result<()> {
+        let fields: Vec<&str> = cron_expression.split(' ').collect();
+        let valid_field = |f: &str| {
+            f.chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '*' | '/' | ',' | '-'))
+        };
+        if fields.len() != 5 || !fields.iter().all(|f| valid_field(f)) {
+            return Err(anyhow!(
+                "无效的 cron 表达式（需为 5 段，如 \"0 3 * * *\"）: {}",
+                cron_expression
+            ));
+        }
+        Ok(())
+    }
+
+    /// 将常用的 cron 表达式转换为 schtasks 的调度参数
+    fn cron_to_schtasks_args(cron_expression: &str) -> Result<Vec<String>> {
+        let fields: Vec<&str> = cron_expression.split(' ').collect();
+        let (minute, hour, day, month, weekday) =
+            (fields[0], fields[1], fields[2], fields[3], fields[4]);
+        let is_number = |f: &str| !f.is_empty() && f.chars().all(|c| c.is_ascii_digit());
+        let start_time = |h: &str, m: &str| -> Result<String> {
+            let h: u32 = h.parse()?;
+            let m: u32 = m.parse()?;
+            if h > 23 || m > 59 {
+                return Err(anyhow!("无效的时间: {}:{}", h, m));
+            }
+            Ok(format!("{:02}:{:02}", h, m))
+        };
+        let args = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        if month != "*" {
+            return Err(anyhow!(
+                "Windows 计划任务不支持按月份筛选: {}",
+                cron_expression
+            ));
+        }
+        // */N * * * *：每 N 分钟
+        if let Some(interval) = minute.strip_prefix("*/") {
+            if hour == "*" && day == "*" && weekday == "*" && is_number(interval) {
+                return Ok(args(&["/SC", "MINUTE", "/MO", interval]));
+            }
+        }
+        if is_number(minute) && hour == "*" && day == "*" && weekday == "*" {
+            return Ok(args(&["/SC", "HOURLY", "/ST", &start_time("0", minute)?]));
+        }
+        if is_number(minute) && is_number(hour) {
+            let st = start_time(hour, minute)?;
+            if day == "*" && weekday == "*" {
+                return Ok(args(&["/SC", "DAILY", "/ST", &st]));
+            }
+            if day == "*" {
+                const DAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+                let days = weekday
+                    .split(',')
+                    .map(|d| {
+                        d.parse::<usize>()
+                            .ok()
+                            .map(|n| DAYS[n % 7])
+                            .filter(|_| is_number(d))
+                            .ok_or_else(|| anyhow!("不支持的星期字段: {}", weekday))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                return Ok(args(&["/SC", "WEEKLY", "/D", &days.join(","), "/ST", &st]));
+            }
+            if weekday == "*" && is_number(day) {
+                return Ok(args(&["/SC", "MONTHLY", "/D", day, "/ST", &st]));
+            }
+        }
+        Err(anyhow!(
+            "Windows 计划任务仅支持每 N 分钟、每小时、每天、每周、每月的 cron 表达式: {}",
+            cron_expression
+        ))
+    }
+
+    fn schtasks_name(schedule_id: &str) -> String {
+        format!("{}\\MongoDB Backup {}", SCHTASKS_FOLDER, schedule_id)
+    }
+
+    fn shell_quote(value: &str) -> String {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+
+    /// 读取当前用户的 crontab，没有 crontab 时返回空列表
+    fn read_crontab() -> Result<Vec<String>> {
+        let output = create_command("crontab")
+            .arg("-l")
+            .output()
+            .map_err(|e| anyhow!("执行 crontab -l 失败: {}", e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("no crontab") {
+                return Ok(Vec::new());
+            }
+            return Err(anyhow!("读取 crontab 失败: {}", stderr.trim()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|l| l.to_string())
+            .collect())
+    }
+
+    /// 通过 stdin 写回 crontab，等价于 `crontab -l | { cat; echo "..."; } | crontab -`，
+    /// 但不经过 shell 拼接，避免路径中的引号被再次解释
+    fn write_crontab(lines: &[String]) -> Result<()> {
+        use std::io::Write;
+        let mut child = create_command("crontab")
+            .arg("-")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow!("执行 crontab 失败: {}", e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            let mut content = lines.join("\n");
+            content.push('\n');
+            stdin.write_all(content.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "写入 crontab 失败: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(())
+    }
+
+    /// 构建备份命令（mongodump --archive，输出到 stdout）
+    pub fn build_backup_command(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<Command> {
+        let connection_string = Self::build_admin_connection_string(service_data)?;
+        let mongodump_bin = self.get_database_tool_bin(&service_data.version, "mongodump")?;
+
+        let mut cmd = create_command(&mongodump_bin);
+        cmd.arg(format!("--uri={}", connection_string))
+            .arg("--archive");
+        Ok(cmd)
+    }
+
+    /// 构建恢复命令（mongorestore --archive --drop，从 stdin 读取）
+    pub fn build_restore_command(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<Command> {
+        let connection_string = Self::build_admin_connection_string(service_data)?;
+        let mongorestore_bin = self.get_database_tool_bin(&service_data.version, "mongorestore")?;
+
+        let mut cmd = create_command(&mongorestore_bin);
+        cmd.arg(format!("--uri={}", connection_string))
+            .arg("--archive")
+            .arg("--drop");
+        Ok(cmd)
+    }
+
+    /// 获取 MongoDB Database Tools 中的可执行文件路径
+    fn get_database_tool_bin(&self, version: &str, tool: &str) -> Result<PathBuf> {
+        let install_path = self.get_install_path(version);
+        let tool_bin = if cfg!(target_os = "windows") {
+            install_path.join("bin").join(format!("{}.exe", tool))
+        } else {
+            install_path.join("bin").join(tool)
+        };
+
+        if !tool_bin.exists() {
+            return Err(anyhow!(
+                "{} 未安装，请将 MongoDB Database Tools 放入 {}",
+                tool,
+                install_path.join("bin").display()
+            ));
+        }
+        Ok(tool_bin)
+    }
+
+    /// 使用 metadata 中的管理员账号构建连接字符串
+    fn build_admin_connection_string(service_data: &ServiceData) -> Result<String> {
+        let admin_username = service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("MONGODB_ADMIN_USERNAME"))
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("未找到管理员用户名"))?;
+
+        let admin_password = service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("MONGODB_ADMIN_PASSWORD"))
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("未找到管理员密码"))?;
+
+        let config_path = service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("MONGODB_CONFIG"))
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("未找到配置文件路径"))?;
+
+        let config_content = std::fs::read_to_string(config_path)?;
+        let port = Self::parse_port_from_config(&config_content)?;
+
+        Ok(format!(
+            "mongodb://{}:{}@127.0.0.1:{}/?authSource=admin",
+            admin_username, admin_password, port
+        ))
+    }
+}
+
+impl ServiceLifecycle for MongodbService {
+    fn is_installed(&self, version: &str) -> bool {
+        MongodbService::is_installed(self, version)
+    }
+
+    fn available_versions(&self) -> Vec<String> {
+        self.get_available_versions()
+            .into_iter()
+            .map(|v| v.version)
+            .collect()
+    }
+
+    fn download_and_install<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<DownloadResult>> {
+        Box::pin(MongodbService::download_and_install(self, version))
+    }
+
+    fn cancel_download(&self, version: &str) -> Result<()> {
+        MongodbService::cancel_download(self, version)
+    }
+
+    fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        MongodbService::get_download_progress(self, version)
+    }
+
+    fn archive_dir(&self, version: &str) -> Option<PathBuf> {
+        Some(self.get_install_path(version))
+    }
+
+    fn extract_and_install<'a>(
+        &'a self,
+        task: &'a DownloadTask,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(MongodbService::extract_and_install(self, task, version))
+    }
+
+    fn start_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        MongodbService::start_service(self, environment_id, service_data)
+    }
+
+    fn stop_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        MongodbService::stop_service(self, environment_id, service_data)
+    }
+
+    fn restart_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        MongodbService::restart_service(self, environment_id, service_data)
+    }
+
+    fn get_service_status(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        MongodbService::get_service_status(self, environment_id, service_data)
+    }
 }
@@ -1,13 +1,23 @@
 ﻿use crate::manager::app_config_manager::AppConfigManager;
-use crate::manager::env_serv_data_manager::ServiceDataResult;
-use crate::manager::services::{DownloadManager, DownloadResult, DownloadTask};
-use crate::types::{ServiceData, ServiceStatus};
+use crate::manager::builders::{MetadataBuilder, MYSQL_METADATA_KEYS};
+use crate::manager::env_serv_data_manager::{EnvServDataManager, ServiceDataResult};
+use crate::manager::service_supervisor::ServiceSupervisor;
+use crate::manager::services::health::{self, InitializationCheck, ServiceHealth};
+use crate::manager::services::launch::{wait_for_early_exit, OutputCapture};
+use crate::manager::services::shutdown::{self, GracefulStop};
+use crate::manager::services::traits::ServiceLifecycle;
+use crate::manager::services::{
+    install_callback, remove_partial_install, DownloadManager, DownloadResult, DownloadTask,
+};
+use crate::types::ServiceData;
 use crate::utils::create_command;
 use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
 use serde::{Deserialize, Serialize};
 use std::fs::File;
-use std::io::copy;
-use std::path::PathBuf;
+use std::io::{copy, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 
@@ -18,6 +28,18 @@ pub struct MysqlVersion {
     pub date: String,
 }
 
+/// MySQL 初始化参数
+#[derive(Debug, Clone, Default)]
+pub struct MysqlInitOptions {
+    pub root_password: String,
+    /// 为空时使用配置中的端口
+    pub port: Option<String>,
+    /// 为空时使用配置中的监听地址
+    pub bind_address: Option<String>,
+    /// 清空已有数据目录后重新初始化
+    pub reset: bool,
+}
+
 /// 全局 MySQL 服务管理器单例
 static GLOBAL_MYSQL_SERVICE: OnceLock<Arc<MysqlService>> = OnceLock::new();
 
@@ -163,56 +185,13 @@ impl MysqlService {
         let download_manager = DownloadManager::global();
 
         let version_for_callback = version.to_string();
-        let success_callback = Arc::new(move |task: &DownloadTask| {
-            log::info!(
-                "MySQL {} 下载完成: {} - {}",
-                version_for_callback,
-                task.id,
-                task.filename
-            );
-            let task_for_spawn = task.clone();
-            let version_for_spawn = version_for_callback.clone();
-            let service_for_spawn = MysqlService::global();
-
-            tokio::spawn(async move {
-                let download_manager = DownloadManager::global();
-                if let Err(e) = download_manager.update_task_status(
-                    &task_for_spawn.id,
-                    crate::manager::services::DownloadStatus::Installing,
-                    None,
-                ) {
-                    log::error!("更新任务状态失败: {}", e);
-                } else {
-                    log::info!("MySQL {} 开始安装", version_for_spawn);
-                }
-
-                match service_for_spawn
-                    .extract_and_install(&task_for_spawn, &version_for_spawn)
+        let success_callback = install_callback(format!("MySQL {}", version), move |task| {
+            let version = version_for_callback.clone();
+            async move {
+                MysqlService::global()
+                    .extract_and_install(&task, &version)
                     .await
-                {
-                    Ok(_) => {
-                        if let Err(e) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            crate::manager::services::DownloadStatus::Installed,
-                            None,
-                        ) {
-                            log::error!("更新任务状态失败: {}", e);
-                        } else {
-                            log::info!("MySQL {} 安装成功", version_for_spawn);
-                        }
-                    }
-                    Err(e) => {
-                        if let Err(update_err) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            crate::manager::services::DownloadStatus::Failed,
-                            Some(format!("安装失败: {}", e)),
-                        ) {
-                            log::error!("更新任务状态失败: {}", update_err);
-                        }
-                        log::error!("MySQL {} 安装失败: {}", version_for_spawn, e);
-                    }
-                }
-            });
+            }
         });
 
         match download_manager
@@ -242,6 +221,16 @@ impl MysqlService {
 
     /// 解压并安装 MySQL
     pub async fn extract_and_install(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let result = self.install_from_archive(task, version).await;
+        if result.is_err() {
+            // 解压安装失败时清理残缺的安装目录
+            remove_partial_install(&self.get_install_path(version));
+        }
+        result
+    }
+
+    /// 将下载的压缩包解压到安装目录
+    async fn install_from_archive(&self, task: &DownloadTask, version: &str) -> Result<()> {
         let archive_path = &task.target_path;
         let install_dir = self.get_install_path(version);
         std::fs::create_dir_all(&install_dir)?;
@@ -404,6 +393,12 @@ impl MysqlService {
             }
         }
 
+        let mysqld_path = self
+            .get_install_path(version)
+            .join("bin")
+            .join("mysqld")
+            .to_string_lossy()
+            .to_string();
         let running = if cfg!(target_os = "windows") {
             let output = create_command("tasklist")
                 .arg("/FI")
@@ -428,7 +423,7 @@ impl MysqlService {
                     } else if !stdout.trim().is_empty() {
                         false
                     } else {
-                        let output = create_command("pgrep").arg("-x").arg("mysqld").output();
+                        let output = create_command("pgrep").arg("-f").arg(&mysqld_path).output();
                         match output {
                             Ok(o2) => {
                                 let stdout2 = String::from_utf8_lossy(&o2.stdout);
@@ -439,7 +434,7 @@ impl MysqlService {
                     }
                 }
                 Err(_) => {
-                    let output = create_command("pgrep").arg("-x").arg("mysqld").output();
+                    let output = create_command("pgrep").arg("-f").arg(&mysqld_path).output();
                     match output {
                         Ok(o) => {
                             let stdout = String::from_utf8_lossy(&o.stdout);
@@ -451,18 +446,26 @@ impl MysqlService {
             }
         };
 
-        let status = if running {
-            ServiceStatus::Running
-        } else {
-            ServiceStatus::Stopped
+        // 健康检查：端口连通后执行 SELECT 1
+        let port_latency = port
+            .parse::<u16>()
+            .ok()
+            .and_then(|p| health::check_tcp_port(&bind_address, p));
+        let query_latency = port_latency.and_then(|_| self.ping_server(service_data, &port));
+        let health = ServiceHealth {
+            process: running,
+            port_open: port_latency.is_some(),
+            responding: query_latency.is_some(),
+            latency_ms: query_latency.or(port_latency),
         };
-        let data = serde_json::json!({
+
+        let mut data = serde_json::json!({
             "isRunning": running,
-            "status": status,
             "port": port,
             "bindAddress": bind_address,
             "configPath": config_path.to_string_lossy().to_string(),
         });
+        health.merge_into(&mut data);
         Ok(ServiceDataResult {
             success: true,
             message: "获取状态成功".to_string(),
@@ -476,6 +479,7 @@ impl MysqlService {
         environment_id: &str,
         service_data: &ServiceData,
     ) -> Result<ServiceDataResult> {
+        ServiceSupervisor::global().clear_intentional_stop(&service_data.id);
         let version = &service_data.version;
         let install_path = self.get_install_path(version);
         let service_data_folder = self.getservice_data_folder(environment_id, version);
@@ -502,37 +506,39 @@ impl MysqlService {
             });
         }
 
-        let child_res = if cfg!(target_os = "windows") {
-            create_command(&mysqld)
-                .arg(format!("--defaults-file={}", config_path.to_string_lossy()))
-                .spawn()
-        } else if cfg!(target_os = "macos") {
-            create_command(&mysqld)
-                .arg(format!("--defaults-file={}", config_path.to_string_lossy()))
-                .stdin(std::process::Stdio::null())
-                .stdout(std::process::Stdio::null())
-                .stderr(std::process::Stdio::null())
-                .spawn()
-        } else {
-            create_command(&mysqld)
-                .arg(format!("--defaults-file={}", config_path.to_string_lossy()))
-                .spawn()
-        };
+        // 子进程输出写入 logs/mysql-stdout.log，启动失败时可直接返回错误原因
+        let capture = OutputCapture::new(&service_data_folder, "mysql")?;
+        let mut command = create_command(&mysqld);
+        command.arg(format!("--defaults-file={}", config_path.to_string_lossy()));
+        capture.attach(&mut command)?;
 
-        match child_res {
-            Ok(child) => {
+        match command.spawn() {
+            Ok(mut child) => {
                 log::info!("MySQL 进程已启动，PID: {:?}", child.id());
-                std::thread::sleep(Duration::from_millis(500));
+                // 配置错误时 mysqld 会立即退出，等待一小段时间检查
+                if let Some(status) = wait_for_early_exit(&mut child) {
+                    let detail = capture.failure_detail(&status);
+                    log::error!("MySQL 启动后立即退出: {}", detail);
+                    return Ok(ServiceDataResult {
+                        success: false,
+                        message: format!("MySQL 启动失败: {}", detail),
+                        data: Some(serde_json::json!({
+                            "configPath": config_path.to_string_lossy().to_string(),
+                            "outputLog": capture.path().to_string_lossy().to_string(),
+                        })),
+                    });
+                }
                 Ok(ServiceDataResult {
-                success: true,
-                message: format!(
-                    "MySQL 启动命令已发送（使用配置文件: {}）",
-                    config_path.display()
-                ),
-                data: Some(serde_json::json!({
-                    "configPath": config_path.to_string_lossy().to_string(),
-                })),
-            })
+                    success: true,
+                    message: format!(
+                        "MySQL 启动成功（使用配置文件: {}）",
+                        config_path.display()
+                    ),
+                    data: Some(serde_json::json!({
+                        "configPath": config_path.to_string_lossy().to_string(),
+                        "outputLog": capture.path().to_string_lossy().to_string(),
+                    })),
+                })
             }
             Err(e) => Ok(ServiceDataResult {
                 success: false,
@@ -542,55 +548,58 @@ impl MysqlService {
         }
     }
 
+    /// 停止 MySQL 服务：使用 mysqladmin shutdown 正常关闭，超时后强制结束 pid 文件中的进程
     pub fn stop_service(
         &self,
-        _environment_id: &str,
-        _service_data: &ServiceData,
+        environment_id: &str,
+        service_data: &ServiceData,
     ) -> Result<ServiceDataResult> {
-        let res = if cfg!(target_os = "windows") {
-            create_command("taskkill")
-                .args(&["/IM", "mysqld.exe", "/F"])
-                .output()
-        } else {
-            create_command("pkill").args(&["-x", "mysqld"]).output()
-        };
-
-        match res {
-            Ok(o) => {
-                let exit_code = o.status.code().unwrap_or(-1);
-                if exit_code == 0 || exit_code == 1 {
-                    Ok(ServiceDataResult {
-                        success: true,
-                        message: "停止 MySQL 成功".to_string(),
-                        data: None,
-                    })
-                } else {
-                    Ok(ServiceDataResult {
-                        success: false,
-                        message: format!(
-                            "停止失败(exit {}): {}",
-                            exit_code,
-                            String::from_utf8_lossy(&o.stderr)
-                        ),
-                        data: None,
-                    })
-                }
-            }
-            Err(e) => Ok(ServiceDataResult {
-                success: false,
-                message: format!("停止命令失败: {}", e),
-                data: None,
-            }),
+        ServiceSupervisor::global().mark_intentional_stop(&service_data.id);
+        let config_path = self
+            .getservice_data_folder(environment_id, &service_data.version)
+            .join("my.cnf");
+        let pid = mysqld_config_value(&config_path, "pid-file")
+            .and_then(|pid_file| shutdown::read_pid_file(Path::new(&pid_file)));
+
+        Ok(GracefulStop {
+            service_name: "MySQL",
+            method: "mysqladmin shutdown",
+            command: self.shutdown_command(service_data, &config_path),
+            pid,
+            port: mysqld_port(&config_path),
+            timeout: shutdown::stop_timeout(service_data),
         }
+        .run())
     }
 
+    /// 重启 MySQL 服务，等待端口释放后再启动
     pub fn restart_service(
         &self,
         environment_id: &str,
         service_data: &ServiceData,
     ) -> Result<ServiceDataResult> {
-        let _ = self.stop_service(environment_id, service_data);
-        std::thread::sleep(Duration::from_millis(300));
+        let stop_res = self.stop_service(environment_id, service_data)?;
+        if !stop_res.success {
+            return Ok(stop_res);
+        }
+
+        let config_path = self
+            .getservice_data_folder(environment_id, &service_data.version)
+            .join("my.cnf");
+        if let Some(port) = mysqld_port(&config_path) {
+            let timeout = shutdown::stop_timeout(service_data);
+            if !shutdown::wait_for_port_release(port, timeout) {
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: format!(
+                        "端口 {} 在 {} 秒内未释放，无法重启 MySQL",
+                        port,
+                        timeout.as_secs()
+                    ),
+                    data: None,
+                });
+            }
+        }
         self.start_service(environment_id, service_data)
     }
 
@@ -778,21 +787,79 @@ impl MysqlService {
         config_path.exists() && data_dir.exists()
     }
 
+    /// 检查初始化状态：目录、metadata 中的凭据，服务运行时用保存的 root 密码执行 SELECT 1
+    ///
+    /// metadata 以文件中的记录为准，初始化流程保存的凭据不依赖调用方传入的副本
+    pub fn check_initialization(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> InitializationCheck {
+        let service_data = EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .get_service_data(environment_id, &service_data.id)
+            .unwrap_or_else(|_| service_data.clone());
+        InitializationCheck::evaluate(
+            self.is_initialized(environment_id, &service_data),
+            &service_data,
+            &MYSQL_METADATA_KEYS,
+            || self.verify_root_credentials(&service_data),
+        )
+    }
+
+    /// 端口可连接时使用保存的 root 密码执行 SELECT 1，端口不通时返回 None
+    fn verify_root_credentials(&self, service_data: &ServiceData) -> Option<bool> {
+        let (root_password, port) = Self::get_root_credentials(service_data).ok()?;
+        health::check_tcp_port("127.0.0.1", port.parse().ok()?)?;
+        let output = self
+            .client_command(service_data, &port, &root_password)
+            .ok()?
+            .arg("--connect-timeout=2")
+            .arg("-e")
+            .arg("SELECT 1")
+            .output()
+            .ok()?;
+        Some(output.status.success())
+    }
+
     /// 初始化 MySQL
     pub fn initialize_mysql(
         &self,
         environment_id: &str,
         service_data: &ServiceData,
-        root_password: String,
-        port: Option<String>,
-        bind_address: Option<String>,
-        reset: bool,
+        options: MysqlInitOptions,
+    ) -> Result<ServiceDataResult> {
+        self.initialize_mysql_with_progress_events(|_, _| {}, environment_id, service_data, options)
+    }
+
+    /// 初始化 MySQL，每个步骤通过 progress_callback(step, message) 报告进度
+    /// 步骤与 MongoDB 初始化的进度事件格式一致
+    pub fn initialize_mysql_with_progress_events(
+        &self,
+        progress_callback: impl Fn(&str, &str),
+        environment_id: &str,
+        service_data: &ServiceData,
+        options: MysqlInitOptions,
     ) -> Result<ServiceDataResult> {
+        let MysqlInitOptions {
+            root_password,
+            port,
+            bind_address,
+            reset,
+        } = options;
+        // 辅助函数：发送进度事件
+        let emit_progress = |step: &str, message: &str| {
+            progress_callback(step, message);
+            log::info!("[MySQL 初始化进度] {}: {}", step, message);
+        };
+
         let version = &service_data.version;
         let install_path = self.get_install_path(version);
         let service_data_folder = self.getservice_data_folder(environment_id, version);
 
         // 检查 MySQL 是否已安装
+        emit_progress("mysql_check_installation", "检查安装状态...");
         let mysqld = if cfg!(target_os = "windows") {
             install_path.join("bin").join("mysqld.exe")
         } else {
@@ -800,16 +867,18 @@ impl MysqlService {
         };
 
         if !mysqld.exists() {
+            emit_progress("mysql_check_installation", "未安装");
             return Ok(ServiceDataResult {
                 success: false,
                 message: format!("MySQL {} 未安装，请先下载并安装", version),
                 data: None,
             });
         }
+        emit_progress("mysql_check_installation", "已安装");
 
         // 如果是重置,先清理现有数据
         if reset && service_data_folder.exists() {
-            log::info!("重置模式：清理现有数据...");
+            emit_progress("mysql_reset", "清理现有数据...");
             std::fs::read_dir(&service_data_folder)?.for_each(|entry_res| {
                 if let Ok(entry) = entry_res {
                     let path = entry.path();
@@ -826,10 +895,23 @@ impl MysqlService {
                     };
                 }
             });
+            emit_progress("mysql_reset", "数据清理完成");
+        }
+
+        // 重置时先标记为未完成，初始化失败后状态检查不会误报为已初始化
+        if reset {
+            if let Err(e) = EnvServDataManager::global().lock().unwrap().merge_metadata(
+                environment_id,
+                &service_data.id,
+                MetadataBuilder::build_database_reset_metadata(&MYSQL_METADATA_KEYS),
+            ) {
+                log::warn!("标记 MySQL 重置状态失败: {}", e);
+            }
         }
 
         // 检查是否已初始化（非重置模式）
         if !reset && self.is_initialized(environment_id, service_data) {
+            emit_progress("mysql_check_existing", "已初始化");
             return Ok(ServiceDataResult {
                 success: false,
                 message: "MySQL 已经初始化，如需重新初始化请使用重置功能".to_string(),
@@ -837,11 +919,24 @@ impl MysqlService {
             });
         }
 
-        let port = port.unwrap_or_else(|| "3306".to_string());
+        // 未指定时使用 metadata 中的端口（如按模板创建时分配的端口）
+        let port = port
+            .or_else(|| {
+                service_data
+                    .metadata
+                    .as_ref()
+                    .and_then(|m| m.get("MYSQL_PORT"))
+                    .and_then(|v| {
+                        v.as_str()
+                            .map(|s| s.to_string())
+                            .or_else(|| v.as_u64().map(|n| n.to_string()))
+                    })
+            })
+            .unwrap_or_else(|| "3306".to_string());
         let bind_address = bind_address.unwrap_or_else(|| "127.0.0.1".to_string());
 
         // 创建目录结构
-        log::info!("创建目录结构...");
+        emit_progress("mysql_create_directories", "创建目录结构...");
         std::fs::create_dir_all(&service_data_folder)?;
 
         let data_dir = service_data_folder.join("data");
@@ -852,9 +947,10 @@ impl MysqlService {
 
         let tmp_dir = service_data_folder.join("tmp");
         std::fs::create_dir_all(&tmp_dir)?;
+        emit_progress("mysql_create_directories", "目录结构创建完成");
 
         // 创建配置文件
-        log::info!("创建配置文件...");
+        emit_progress("mysql_create_config", "创建配置文件...");
         let config_path = service_data_folder.join("my.cnf");
         self.create_default_config(
             &config_path,
@@ -864,9 +960,10 @@ impl MysqlService {
             &port,
             &bind_address,
         )?;
+        emit_progress("mysql_create_config", "配置文件创建完成");
 
         // 初始化数据目录
-        log::info!("初始化数据目录...");
+        emit_progress("mysql_init_data", "初始化数据目录...");
         let init_output = create_command(&mysqld)
             .arg("--initialize-insecure")
             .arg(format!("--datadir={}", data_dir.display()))
@@ -875,11 +972,13 @@ impl MysqlService {
 
         if !init_output.status.success() {
             let error = String::from_utf8_lossy(&init_output.stderr);
+            emit_progress("mysql_init_data", "初始化数据目录失败");
             return Err(anyhow!("初始化数据目录失败: {}", error));
         }
+        emit_progress("mysql_init_data", "数据目录初始化完成");
 
         // 启动临时服务器设置 root 密码
-        log::info!("启动临时服务器设置 root 密码...");
+        emit_progress("mysql_start_temp_server", "启动临时服务器...");
         let temp_port = "3307";
         let temp_socket = tmp_dir.join("mysql_init.sock");
         let mut mysqld_process = create_command(&mysqld)
@@ -890,6 +989,7 @@ impl MysqlService {
 
         // 等待服务器启动
         std::thread::sleep(Duration::from_secs(3));
+        emit_progress("mysql_start_temp_server", "临时服务器已启动");
 
         // 设置 root 密码
         let mysql_client = if cfg!(target_os = "windows") {
@@ -898,6 +998,7 @@ impl MysqlService {
             install_path.join("bin").join("mysql")
         };
 
+        emit_progress("mysql_set_root_password", "设置 root 密码...");
         let set_password_cmd = format!(
             "ALTER USER 'root'@'localhost' IDENTIFIED BY '{}'; FLUSH PRIVILEGES;",
             root_password
@@ -929,24 +1030,45 @@ impl MysqlService {
         std::thread::sleep(Duration::from_secs(2));
 
         // 停止临时服务器
+        emit_progress("mysql_stop_temp_server", "停止临时服务器...");
         let _ = mysqld_process.kill();
         let _ = mysqld_process.wait();
+        emit_progress("mysql_stop_temp_server", "临时服务器已停止");
 
         // 检查密码设置是否成功
         match password_output {
             Ok(output) if output.status.success() => {
-                log::info!("root 密码设置成功");
+                emit_progress("mysql_set_root_password", "root 密码设置完成");
             }
             Ok(output) => {
                 let error = String::from_utf8_lossy(&output.stderr);
                 log::warn!("设置 root 密码可能失败: {}", error);
+                emit_progress(
+                    "mysql_set_root_password",
+                    "root 密码设置可能失败，请检查日志",
+                );
             }
             Err(e) => {
                 log::warn!("执行密码设置命令失败: {}", e);
+                emit_progress("mysql_set_root_password", "执行密码设置命令失败");
             }
         }
 
-        log::info!("MySQL 初始化完成！");
+        // 保存配置文件、端口与 root 密码，并标记初始化完成
+        let metadata = MetadataBuilder::build_database_init_metadata(
+            &MYSQL_METADATA_KEYS,
+            &config_path,
+            &port,
+            "root",
+            &root_password,
+        );
+        EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .merge_metadata(environment_id, &service_data.id, metadata.clone())
+            .map_err(|e| anyhow!("保存初始化 metadata 失败: {}", e))?;
+
+        emit_progress("mysql_complete", "初始化完成！");
 
         Ok(ServiceDataResult {
             success: true,
@@ -958,6 +1080,7 @@ impl MysqlService {
                 "rootPassword": root_password,
                 "port": port,
                 "bindAddress": bind_address,
+                "metadata": metadata,
             })),
         })
     }
@@ -1374,7 +1497,7 @@ default-character-set = utf8mb4
             .arg("-u").arg("root")
             .arg(format!("--password={}", root_password))
             .arg("-e")
-            .arg("SELECT User, Host FROM mysql.user WHERE User != '' AND User != 'mysql.sys' AND User != 'mysql.infoschema' AND User != 'mysql.session' AND User != 'root' AND Host = 'localhost' ORDER BY User")
+            .arg("SELECT User, Host FROM mysql.user WHERE User != '' AND User != 'root' AND User NOT IN ('mysql.sys', 'mysql.infoschema', 'mysql.session', 'mariadb.sys') ORDER BY User, Host")
             .arg("--batch")
             .arg("--skip-column-names")
             .output()?;
@@ -1401,7 +1524,7 @@ default-character-set = utf8mb4
                 .arg("-u").arg("root")
                 .arg(format!("--password={}", root_password))
                 .arg("-e")
-                .arg(format!("SHOW GRANTS FOR '{}'@'{}'", username, host))
+                .arg(format!("SHOW GRANTS FOR {}", user_account(username, host)))
                 .arg("--batch")
                 .arg("--skip-column-names")
                 .output()?;
@@ -1410,24 +1533,12 @@ default-character-set = utf8mb4
             if grants_output.status.success() {
                 let grants_str = String::from_utf8_lossy(&grants_output.stdout);
                 for grant_line in grants_str.lines().filter(|l| !l.is_empty()) {
-                    if let Some(on_pos) = grant_line.find(" ON ") {
-                        let priv_part = &grant_line[6..on_pos];
-                        let rest = &grant_line[on_pos + 4..];
-                        if let Some(dot_pos) = rest.find(".*") {
-                            let db_part = &rest[..dot_pos];
-                            let db_name = db_part.trim_matches('`').trim_matches('\'');
-                            if db_name != "*" && db_name != "mysql" {
-                                let privilege = if priv_part.contains("ALL PRIVILEGES") {
-                                    "ALL PRIVILEGES"
-                                } else {
-                                    "SELECT"
-                                };
-                                grants.push(serde_json::json!({
-                                    "database": db_name,
-                                    "privilege": privilege,
-                                }));
-                            }
-                        }
+                    if let Some((db_name, privileges)) = parse_database_grant(grant_line) {
+                        grants.push(serde_json::json!({
+                            "database": db_name,
+                            "privilege": privileges.join(", "),
+                            "privileges": privileges,
+                        }));
                     }
                 }
             }
@@ -1454,6 +1565,7 @@ default-character-set = utf8mb4
         username: String,
         password: String,
         grants: Vec<serde_json::Value>,
+        host: Option<String>,
     ) -> Result<ServiceDataResult> {
         log::info!("创建 MySQL 用户: {}", username);
 
@@ -1491,19 +1603,11 @@ default-character-set = utf8mb4
             return Err(anyhow!("mysql 客户端未安装"));
         }
 
+        let account = user_account(&username, host.as_deref().unwrap_or("localhost"));
         let mut sql_parts = vec![
-            format!("CREATE USER '{}'@'localhost' IDENTIFIED BY '{}'", username, password),
+            format!("CREATE USER {} IDENTIFIED BY '{}'", account, escape_sql_string(&password)),
         ];
-        for grant in &grants {
-            let database = grant.get("database").and_then(|v| v.as_str()).unwrap_or("");
-            let privilege = grant.get("privilege").and_then(|v| v.as_str()).unwrap_or("SELECT");
-            if !database.is_empty() {
-                sql_parts.push(format!(
-                    "GRANT {} ON `{}`.* TO '{}'@'localhost'",
-                    privilege, database, username
-                ));
-            }
-        }
+        sql_parts.extend(grant_statements(&grants, &account)?);
         sql_parts.push("FLUSH PRIVILEGES".to_string());
         let sql = sql_parts.join("; ");
 
@@ -1534,6 +1638,7 @@ default-character-set = utf8mb4
         _environment_id: &str,
         service_data: &ServiceData,
         username: String,
+        host: Option<String>,
     ) -> Result<ServiceDataResult> {
         log::info!("删除 MySQL 用户: {}", username);
 
@@ -1570,8 +1675,8 @@ default-character-set = utf8mb4
         }
 
         let sql = format!(
-            "DROP USER IF EXISTS '{}'@'localhost'; FLUSH PRIVILEGES",
-            username
+            "DROP USER IF EXISTS {}; FLUSH PRIVILEGES",
+            user_account(&username, host.as_deref().unwrap_or("localhost"))
         );
 
         let output = create_command(&mysql_client)
@@ -1602,6 +1707,7 @@ default-character-set = utf8mb4
         service_data: &ServiceData,
         username: String,
         grants: Vec<serde_json::Value>,
+        host: Option<String>,
     ) -> Result<ServiceDataResult> {
         log::info!("更新 MySQL 用户权限: {}", username);
 
@@ -1637,22 +1743,11 @@ default-character-set = utf8mb4
             return Err(anyhow!("mysql 客户端未安装"));
         }
 
+        let account = user_account(&username, host.as_deref().unwrap_or("localhost"));
         let mut sql_parts = vec![
-            format!(
-                "REVOKE ALL PRIVILEGES, GRANT OPTION FROM '{}'@'localhost'",
-                username
-            ),
+            format!("REVOKE ALL PRIVILEGES, GRANT OPTION FROM {}", account),
         ];
-        for grant in &grants {
-            let database = grant.get("database").and_then(|v| v.as_str()).unwrap_or("");
-            let privilege = grant.get("privilege").and_then(|v| v.as_str()).unwrap_or("SELECT");
-            if !database.is_empty() {
-                sql_parts.push(format!(
-                    "GRANT {} ON `{}`.* TO '{}'@'localhost'",
-                    privilege, database, username
-                ));
-            }
-        }
+        sql_parts.extend(grant_statements(&grants, &account)?);
         sql_parts.push("FLUSH PRIVILEGES".to_string());
         let sql = sql_parts.join("; ");
 
@@ -1676,4 +1771,635 @@ default-character-set = utf8mb4
             data: Some(serde_json::json!({ "username": username })),
         })
     }
+
+    /// 为用户授予指定数据库的权限（privileges 如 ["SELECT","INSERT"] 或 ["ALL"]，database 为 "*" 时表示全部数据库）
+    pub fn grant_privileges(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+        username: String,
+        database: String,
+        privileges: Vec<String>,
+        host: Option<String>,
+    ) -> Result<ServiceDataResult> {
+        log::info!("授予 MySQL 用户 {} 数据库 {} 权限: {:?}", username, database, privileges);
+
+        if username.is_empty() {
+            return Err(anyhow!("用户名不能为空"));
+        }
+        if username.to_lowercase() == "root" {
+            return Err(anyhow!("不能修改 root 用户权限"));
+        }
+        if database.is_empty() {
+            return Err(anyhow!("数据库名不能为空"));
+        }
+
+        let privilege_list = normalize_privileges(&privileges)?;
+        let target = if database == "*" {
+            "*.*".to_string()
+        } else {
+            format!("{}.*", quote_identifier(&database))
+        };
+        let account = user_account(&username, host.as_deref().unwrap_or("localhost"));
+        let sql = format!(
+            "GRANT {} ON {} TO {}; FLUSH PRIVILEGES",
+            privilege_list, target, account
+        );
+
+        let output = self
+            .root_client_command(service_data)?
+            .arg("-e")
+            .arg(&sql)
+            .output()?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("授予权限失败: {}", error));
+        }
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("已授予用户 '{}' 数据库 '{}' 的权限", username, database),
+            data: Some(serde_json::json!({
+                "username": username,
+                "database": database,
+                "privileges": privilege_list,
+            })),
+        })
+    }
+
+    /// 修改 root 密码
+    ///
+    /// 使用当前密码执行 ALTER USER，再用新密码重新连接验证，验证通过后才写入 metadata。
+    pub fn change_root_password(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        new_password: String,
+    ) -> Result<ServiceDataResult> {
+        if new_password.is_empty() {
+            return Err(anyhow!("新密码不能为空"));
+        }
+
+        let (_, port) = Self::get_root_credentials(service_data)?;
+
+        // 初始化时可能同时创建了 root@127.0.0.1 / root@::1，这里一并修改，不存在的账号会被跳过
+        let password = escape_sql_string(&new_password);
+        let accounts: Vec<String> = ["localhost", "127.0.0.1", "::1"]
+            .iter()
+            .map(|host| {
+                format!(
+                    "{} IDENTIFIED BY '{}'",
+                    user_account("root", host),
+                    password
+                )
+            })
+            .collect();
+        let sql = format!(
+            "ALTER USER IF EXISTS {}; FLUSH PRIVILEGES;",
+            accounts.join(", ")
+        );
+
+        // SQL 中包含新密码，通过 stdin 传入，避免出现在进程参数中
+        let mut child = self
+            .root_client_command(service_data)?
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(sql.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            // ERROR 1045：已保存的 root 密码失效（例如在 Envis 之外被修改过）
+            if error.contains("ERROR 1045") || error.contains("Access denied") {
+                return Err(anyhow!(
+                    "使用已保存的 root 密码认证失败，密码可能已在 Envis 之外被修改，root 密码未修改"
+                ));
+            }
+            // ERROR 2002/2003：无法连接到服务
+            if error.contains("ERROR 2002") || error.contains("ERROR 2003") {
+                return Err(anyhow!("无法连接到 MySQL 服务，请先启动服务"));
+            }
+            return Err(anyhow!("执行 ALTER USER 失败，root 密码未修改: {}", error));
+        }
+
+        // 用新密码重新连接，确认修改生效
+        let verify = self
+            .client_command(service_data, &port, &new_password)?
+            .arg("-e")
+            .arg("SELECT 1")
+            .output()?;
+        if !verify.status.success() {
+            let error = String::from_utf8_lossy(&verify.stderr);
+            log::warn!("MySQL root 新密码验证失败，metadata 保持原密码: {}", error);
+            return Err(anyhow!(
+                "ALTER USER 已执行成功，但使用新密码重新连接失败，metadata 仍保留原密码: {}",
+                error
+            ));
+        }
+
+        let mut service_data = service_data.clone();
+        EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .set_metadata(
+                environment_id,
+                &mut service_data,
+                "MYSQL_ROOT_PASSWORD",
+                serde_json::Value::String(new_password),
+            )
+            .map_err(|e| anyhow!("数据库中的 root 密码已修改，但保存到 metadata 失败: {}", e))?;
+
+        log::info!("MySQL root 密码已修改");
+        Ok(ServiceDataResult {
+            success: true,
+            message: "root 密码修改成功".to_string(),
+            data: None,
+        })
+    }
+
+    /// 构建备份命令（mysqldump --all-databases，输出到 stdout）
+    pub fn build_backup_command(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<Command> {
+        let (root_password, port) = Self::get_root_credentials(service_data)?;
+
+        let install_path = self.get_install_path(&service_data.version);
+        let mysqldump = if cfg!(target_os = "windows") {
+            install_path.join("bin").join("mysqldump.exe")
+        } else {
+            install_path.join("bin").join("mysqldump")
+        };
+        if !mysqldump.exists() {
+            return Err(anyhow!("mysqldump 未安装"));
+        }
+
+        let mut cmd = create_command(&mysqldump);
+        cmd.arg(format!("--port={}", port))
+            .arg("--host=127.0.0.1")
+            .arg("-u")
+            .arg("root")
+            .arg(format!("--password={}", root_password))
+            .arg("--all-databases")
+            .arg("--single-transaction")
+            .arg("--routines")
+            .arg("--events");
+        Ok(cmd)
+    }
+
+    /// 构建恢复命令（mysql 客户端，从 stdin 读取 SQL）
+    pub fn build_restore_command(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<Command> {
+        self.root_client_command(service_data)
+    }
+
+    /// 使用 mysql 客户端执行 SELECT 1，成功时返回耗时（毫秒）
+    fn ping_server(&self, service_data: &ServiceData, port: &str) -> Option<u64> {
+        let install_path = self.get_install_path(&service_data.version);
+        let mysql_client = if cfg!(target_os = "windows") {
+            install_path.join("bin").join("mysql.exe")
+        } else {
+            install_path.join("bin").join("mysql")
+        };
+        if !mysql_client.exists() {
+            return None;
+        }
+
+        let root_password = service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("MYSQL_ROOT_PASSWORD"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+
+        let mut cmd = create_command(&mysql_client);
+        cmd.arg(format!("--port={}", port))
+            .arg("--host=127.0.0.1")
+            .arg("--connect-timeout=2")
+            .arg("-u")
+            .arg("root");
+        if !root_password.is_empty() {
+            cmd.arg(format!("--password={}", root_password));
+        }
+        cmd.arg("-e").arg("SELECT 1");
+
+        health::timed(|| cmd.output().map(|o| o.status.success()).unwrap_or(false))
+    }
+
+    /// 构建以 root 身份连接的 mysql 客户端命令
+    fn root_client_command(&self, service_data: &ServiceData) -> Result<Command> {
+        let (root_password, port) = Self::get_root_credentials(service_data)?;
+        self.client_command(service_data, &port, &root_password)
+    }
+
+    /// 构建使用指定 root 密码连接的 mysql 客户端命令
+    fn client_command(
+        &self,
+        service_data: &ServiceData,
+        port: &str,
+        root_password: &str,
+    ) -> Result<Command> {
+        let install_path = self.get_install_path(&service_data.version);
+        let mysql_client = if cfg!(target_os = "windows") {
+            install_path.join("bin").join("mysql.exe")
+        } else {
+            install_path.join("bin").join("mysql")
+        };
+        if !mysql_client.exists() {
+            return Err(anyhow!("mysql 客户端未安装"));
+        }
+
+        let mut cmd = create_command(&mysql_client);
+        cmd.arg(format!("--port={}", port))
+            .arg("--host=127.0.0.1")
+            .arg("-u")
+            .arg("root")
+            .arg(format!("--password={}", root_password));
+        Ok(cmd)
+    }
+
+    /// 构建 mysqladmin shutdown 命令，通过 my.cnf [client] 段的端口 / socket 连接
+    fn shutdown_command(&self, service_data: &ServiceData, config_path: &Path) -> Result<Command> {
+        let (root_password, _) = Self::get_root_credentials(service_data)?;
+        let install_path = self.get_install_path(&service_data.version);
+        let mysqladmin = if cfg!(target_os = "windows") {
+            install_path.join("bin").join("mysqladmin.exe")
+        } else {
+            install_path.join("bin").join("mysqladmin")
+        };
+        if !mysqladmin.exists() {
+            return Err(anyhow!("mysqladmin 未安装"));
+        }
+
+        let mut cmd = create_command(&mysqladmin);
+        // --defaults-file 必须是第一个参数
+        cmd.arg(format!("--defaults-file={}", config_path.display()))
+            .arg("--connect-timeout=5")
+            .arg("-u")
+            .arg("root")
+            .arg(format!("--password={}", root_password))
+            .arg("shutdown");
+        Ok(cmd)
+    }
+
+    /// 从 metadata 中读取 root 密码和端口
+    fn get_root_credentials(service_data: &ServiceData) -> Result<(String, String)> {
+        let root_password = service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("MYSQL_ROOT_PASSWORD"))
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("未找到 root 密码"))?
+            .to_string();
+
+        let port = service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("MYSQL_PORT"))
+            .and_then(|v| v.as_str().map(|s| s.to_string()).or_else(|| v.as_u64().map(|n| n.to_string())))
+            .unwrap_or_else(|| "3306".to_string());
+
+        Ok((root_password, port))
+    }
+}
+
+/// 可授予的权限白名单
+const GRANTABLE_PRIVILEGES: &[&str] = &[
+    "SELECT",
+    "INSERT",
+    "UPDATE",
+    "DELETE",
+    "CREATE",
+    "DROP",
+    "ALTER",
+    "INDEX",
+    "REFERENCES",
+    "CREATE VIEW",
+    "SHOW VIEW",
+    "CREATE ROUTINE",
+    "ALTER ROUTINE",
+    "EXECUTE",
+    "TRIGGER",
+    "EVENT",
+    "LOCK TABLES",
+    "CREATE TEMPORARY TABLES",
+];
+
+/// 读取 my.cnf 中 [mysqld] 段的配置项
+pub(crate) fn mysqld_config_value(config_path: &Path, key: &str) -> Option<String> {
+    let content = std::fs::read_to_string(config_path).ok()?;
+    let mut in_mysqld = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_mysqld = line == "[mysqld]";
+            continue;
+        }
+        if !in_mysqld {
+            continue;
+        }
+        if let Some((name, value)) = line.split_once('=') {
+            if name.trim() == key {
+                return Some(value.trim().to_string());
+            }
+        }
+    }
+    None
+}
+
+/// 读取 my.cnf 中 mysqld 监听的端口
+pub(crate) fn mysqld_port(config_path: &Path) -> Option<u16> {
+    mysqld_config_value(config_path, "port").and_then(|port| port.parse().ok())
+}
+
+/// 转义单引号字符串字面量中的内容
+pub(crate) fn escape_sql_string(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\'', "''")
+}
+
+/// 使用反引号引用标识符（数据库名、表名）
+pub(crate) fn quote_identifier(value: &str) -> String {
+    format!("`{}`", value.replace('`', "``"))
+}
+
+/// 构建 'user'@'host' 账号字符串
+pub(crate) fn user_account(username: &str, host: &str) -> String {
+    format!("'{}'@'{}'", escape_sql_string(username), escape_sql_string(host))
+}
+
+/// 校验权限列表并拼接为 GRANT 语句中的权限部分，ALL 只能单独使用
+pub(crate) fn normalize_privileges(privileges: &[String]) -> Result<String> {
+    let normalized: Vec<String> = privileges
+        .iter()
+        .map(|p| p.split_whitespace().collect::<Vec<_>>().join(" ").to_uppercase())
+        .filter(|p| !p.is_empty())
+        .collect();
+
+    if normalized.is_empty() {
+        return Err(anyhow!("权限列表不能为空"));
+    }
+
+    if normalized.iter().any(|p| p == "ALL" || p == "ALL PRIVILEGES") {
+        if normalized.len() > 1 {
+            return Err(anyhow!("ALL 权限不能与其他权限同时使用"));
+        }
+        return Ok("ALL PRIVILEGES".to_string());
+    }
+
+    for privilege in &normalized {
+        if !GRANTABLE_PRIVILEGES.contains(&privilege.as_str()) {
+            return Err(anyhow!("不支持的权限: {}", privilege));
+        }
+    }
+    Ok(normalized.join(", "))
+}
+
+/// 解析 SHOW GRANTS 的一行，返回数据库名及权限列表；全局授权和 mysql 库的授权返回 None
+pub(crate) fn parse_database_grant(grant_line: &str) -> Option<(String, Vec<String>)> {
+    let priv_part = grant_line.strip_prefix("GRANT ")?;
+    let on_pos = priv_part.find(" ON ")?;
+    let rest = &priv_part[on_pos + 4..];
+    let dot_pos = rest.find(".*")?;
+    let db_name = rest[..dot_pos].trim_matches('`').trim_matches('\'');
+    if db_name == "*" || db_name == "mysql" {
+        return None;
+    }
+
+    let privileges: Vec<String> = priv_part[..on_pos]
+        .split(',')
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    Some((db_name.replace("``", "`"), privileges))
+}
+
+/// 读取单条授权的权限列表：优先使用 privileges 数组，否则按逗号拆分 privilege 字符串，默认 SELECT
+pub(crate) fn grant_privilege_list(grant: &serde_json::Value) -> Vec<String> {
+    let privileges: Vec<String> = match grant.get("privileges").and_then(|v| v.as_array()) {
+        Some(values) => values
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(|p| p.to_string())
+            .collect(),
+        None => grant
+            .get("privilege")
+            .and_then(|v| v.as_str())
+            .map(|p| p.split(',').map(|p| p.to_string()).collect())
+            .unwrap_or_default(),
+    };
+
+    if privileges.iter().all(|p| p.trim().is_empty()) {
+        vec!["SELECT".to_string()]
+    } else {
+        privileges
+    }
+}
+
+/// 将授权列表转换为针对指定账号的 GRANT 语句，未指定数据库的授权会被忽略
+pub(crate) fn grant_statements(grants: &[serde_json::Value], account: &str) -> Result<Vec<String>> {
+    let mut statements = Vec::new();
+    for grant in grants {
+        let database = grant.get("database").and_then(|v| v.as_str()).unwrap_or("");
+        if database.is_empty() {
+            continue;
+        }
+        statements.push(format!(
+            "GRANT {} ON {}.* TO {}",
+            normalize_privileges(&grant_privilege_list(grant))?,
+            quote_identifier(database),
+            account
+        ));
+    }
+    Ok(statements)
+}
+
+impl ServiceLifecycle for MysqlService {
+    fn is_installed(&self, version: &str) -> bool {
+        MysqlService::is_installed(self, version)
+    }
+
+    fn available_versions(&self) -> Vec<String> {
+        self.get_available_versions()
+            .into_iter()
+            .map(|v| v.version)
+            .collect()
+    }
+
+    fn download_and_install<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<DownloadResult>> {
+        Box::pin(MysqlService::download_and_install(self, version))
+    }
+
+    fn cancel_download(&self, version: &str) -> Result<()> {
+        MysqlService::cancel_download(self, version)
+    }
+
+    fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        MysqlService::get_download_progress(self, version)
+    }
+
+    fn archive_dir(&self, version: &str) -> Option<PathBuf> {
+        Some(self.get_install_path(version))
+    }
+
+    fn extract_and_install<'a>(
+        &'a self,
+        task: &'a DownloadTask,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(MysqlService::extract_and_install(self, task, version))
+    }
+
+    fn start_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        MysqlService::start_service(self, environment_id, service_data)
+    }
+
+    fn stop_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        MysqlService::stop_service(self, environment_id, service_data)
+    }
+
+    fn restart_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        MysqlService::restart_service(self, environment_id, service_data)
+    }
+
+    fn get_service_status(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        MysqlService::get_service_status(self, environment_id, service_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mysqld_config_value() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("my.cnf");
+        assert_eq!(mysqld_config_value(&config_path, "port"), None);
+
+        std::fs::write(
+            &config_path,
+            "[client]\nport = 3307\n\n[mysqld]\nport = 3308\nbind-address=127.0.0.1\n\n[mysqldump]\nquick\n",
+        )
+        .unwrap();
+        // 只读取 [mysqld] 段，忽略其他段的同名配置
+        assert_eq!(
+            mysqld_config_value(&config_path, "port").as_deref(),
+            Some("3308")
+        );
+        assert_eq!(
+            mysqld_config_value(&config_path, "bind-address").as_deref(),
+            Some("127.0.0.1")
+        );
+        assert_eq!(mysqld_config_value(&config_path, "quick"), None);
+        assert_eq!(mysqld_port(&config_path), Some(3308));
+    }
+
+    fn privileges(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_normalize_privileges() {
+        assert_eq!(
+            normalize_privileges(&privileges(&["select", " insert ", "lock   tables"])).unwrap(),
+            "SELECT, INSERT, LOCK TABLES"
+        );
+        assert_eq!(
+            normalize_privileges(&privileges(&["all"])).unwrap(),
+            "ALL PRIVILEGES"
+        );
+        assert_eq!(
+            normalize_privileges(&privileges(&["ALL PRIVILEGES", ""])).unwrap(),
+            "ALL PRIVILEGES"
+        );
+
+        assert!(normalize_privileges(&[]).is_err());
+        assert!(normalize_privileges(&privileges(&["  "])).is_err());
+        assert!(normalize_privileges(&privileges(&["ALL", "SELECT"])).is_err());
+        assert!(normalize_privileges(&privileges(&["SUPER"])).is_err());
+        assert!(normalize_privileges(&privileges(&["SELECT; DROP DATABASE x"])).is_err());
+    }
+
+    #[test]
+    fn test_grant_round_trip() {
+        let (database, listed) =
+            parse_database_grant("GRANT SELECT, INSERT, UPDATE, DELETE ON `app_db`.* TO `app`@`localhost`")
+                .unwrap();
+        assert_eq!(database, "app_db");
+        assert_eq!(listed, privileges(&["SELECT", "INSERT", "UPDATE", "DELETE"]));
+        assert!(parse_database_grant("GRANT USAGE ON *.* TO `app`@`localhost`").is_none());
+        assert!(parse_database_grant("GRANT SELECT ON `mysql`.* TO `app`@`localhost`").is_none());
+
+        // list_users 返回的授权原样写回时不能丢失权限
+        let account = user_account("app", "localhost");
+        let listed_grant = serde_json::json!({
+            "database": database,
+            "privilege": listed.join(", "),
+            "privileges": listed,
+        });
+        let expected = "GRANT SELECT, INSERT, UPDATE, DELETE ON `app_db`.* TO 'app'@'localhost'";
+        assert_eq!(
+            grant_statements(&[listed_grant], &account).unwrap(),
+            vec![expected.to_string()]
+        );
+
+        // 只有旧的 privilege 字符串时按逗号拆分
+        let legacy_grant = serde_json::json!({
+            "database": "app_db",
+            "privilege": "select, insert, update, delete",
+        });
+        assert_eq!(
+            grant_statements(&[legacy_grant], &account).unwrap(),
+            vec![expected.to_string()]
+        );
+
+        let all_grant = serde_json::json!({ "database": "app_db", "privilege": "ALL PRIVILEGES" });
+        let default_grant = serde_json::json!({ "database": "app_db" });
+        let no_database = serde_json::json!({ "database": "", "privilege": "SELECT" });
+        assert_eq!(
+            grant_statements(&[all_grant, default_grant, no_database], &account).unwrap(),
+            vec![
+                "GRANT ALL PRIVILEGES ON `app_db`.* TO 'app'@'localhost'".to_string(),
+                "GRANT SELECT ON `app_db`.* TO 'app'@'localhost'".to_string(),
+            ]
+        );
+
+        let invalid = serde_json::json!({ "database": "app_db", "privileges": ["SUPER"] });
+        assert!(grant_statements(&[invalid], &account).is_err());
+    }
+
+    #[test]
+    fn test_quote_identifier() {
+        assert_eq!(quote_identifier("app_db"), "`app_db`");
+        assert_eq!(quote_identifier("my`db"), "`my``db`");
+        assert_eq!(quote_identifier("a-b c"), "`a-b c`");
+        assert_eq!(user_account("o'neil", "%"), "'o''neil'@'%'");
+    }
 }
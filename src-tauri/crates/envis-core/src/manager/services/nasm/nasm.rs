@@ -1,6 +1,10 @@
 use crate::manager::app_config_manager::AppConfigManager;
-use crate::manager::services::{DownloadManager, DownloadResult, DownloadTask};
+use crate::manager::services::traits::ServiceLifecycle;
+use crate::manager::services::{
+    install_callback, remove_partial_install, DownloadManager, DownloadResult, DownloadTask,
+};
 use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::{Arc, OnceLock};
@@ -135,57 +139,13 @@ impl NasmService {
         let download_manager = DownloadManager::global();
 
         let version_for_callback = version.to_string();
-        let success_callback = Arc::new(move |task: &DownloadTask| {
-            log::info!(
-                "NASM {} 下载完成: {} - {}",
-                version_for_callback,
-                task.id,
-                task.filename
-            );
-
-            let task_for_spawn = task.clone();
-            let version_for_spawn = version_for_callback.clone();
-            let service_for_spawn = NasmService::global();
-
-            tokio::spawn(async move {
-                let download_manager = DownloadManager::global();
-                if let Err(e) = download_manager.update_task_status(
-                    &task_for_spawn.id,
-                    crate::manager::services::DownloadStatus::Installing,
-                    None,
-                ) {
-                    log::error!("更新任务状态失败: {}", e);
-                } else {
-                    log::info!("NASM {} 开始安装", version_for_spawn);
-                }
-
-                match service_for_spawn
-                    .extract_and_install(&task_for_spawn, &version_for_spawn)
+        let success_callback = install_callback(format!("NASM {}", version), move |task| {
+            let version = version_for_callback.clone();
+            async move {
+                NasmService::global()
+                    .extract_and_install(&task, &version)
                     .await
-                {
-                    Ok(_) => {
-                        if let Err(e) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            crate::manager::services::DownloadStatus::Installed,
-                            None,
-                        ) {
-                            log::error!("更新任务状态失败: {}", e);
-                        } else {
-                            log::info!("NASM {} 安装成功", version_for_spawn);
-                        }
-                    }
-                    Err(e) => {
-                        if let Err(update_err) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            crate::manager::services::DownloadStatus::Failed,
-                            Some(format!("安装失败: {}", e)),
-                        ) {
-                            log::error!("更新任务状态失败: {}", update_err);
-                        }
-                        log::error!("NASM {} 安装失败: {}", version_for_spawn, e);
-                    }
-                }
-            });
+            }
         });
 
         match download_manager
@@ -215,6 +175,16 @@ impl NasmService {
 
     /// 解压和安装 NASM
     pub async fn extract_and_install(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let result = self.install_from_archive(task, version).await;
+        if result.is_err() {
+            // 解压安装失败时清理残缺的安装目录
+            remove_partial_install(&self.get_install_path(version));
+        }
+        result
+    }
+
+    /// 将下载的压缩包解压到安装目录
+    async fn install_from_archive(&self, task: &DownloadTask, version: &str) -> Result<()> {
         let archive_path = &task.target_path;
         let install_dir = self.get_install_path(version);
         std::fs::create_dir_all(&install_dir)?;
@@ -361,3 +331,43 @@ impl NasmService {
         DownloadManager::global().get_task_status(&task_id)
     }
 }
+
+impl ServiceLifecycle for NasmService {
+    fn is_installed(&self, version: &str) -> bool {
+        NasmService::is_installed(self, version)
+    }
+
+    fn available_versions(&self) -> Vec<String> {
+        self.get_available_versions()
+            .into_iter()
+            .map(|v| v.version)
+            .collect()
+    }
+
+    fn download_and_install<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<DownloadResult>> {
+        Box::pin(NasmService::download_and_install(self, version))
+    }
+
+    fn cancel_download(&self, version: &str) -> Result<()> {
+        NasmService::cancel_download(self, version)
+    }
+
+    fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        NasmService::get_download_progress(self, version)
+    }
+
+    fn archive_dir(&self, version: &str) -> Option<PathBuf> {
+        Some(self.get_install_path(version))
+    }
+
+    fn extract_and_install<'a>(
+        &'a self,
+        task: &'a DownloadTask,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(NasmService::extract_and_install(self, task, version))
+    }
+}
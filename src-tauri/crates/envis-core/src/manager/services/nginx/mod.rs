@@ -1,3 +1,3 @@
 pub mod nginx;
 
-pub use nginx::{NginxService, NginxVersion};
+pub use nginx::{BalanceMethod, NginxService, NginxUpstream, NginxVersion, NginxWorkers};
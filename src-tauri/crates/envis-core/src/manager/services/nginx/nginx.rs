@@ -1,15 +1,30 @@
 use crate::manager::app_config_manager::AppConfigManager;
-use crate::manager::env_serv_data_manager::ServiceDataResult;
-use crate::manager::services::{DownloadManager, DownloadResult, DownloadTask};
-use crate::types::{ServiceData, ServiceStatus};
+use crate::manager::env_serv_data_manager::{EnvServDataManager, ServiceDataResult};
+use crate::manager::environment_manager::EnvironmentManager;
+use crate::manager::service_supervisor::ServiceSupervisor;
+use crate::manager::services::health::{self, ServiceHealth};
+use crate::manager::services::launch::{wait_for_early_exit, OutputCapture};
+use crate::manager::services::traits::{status_result, ServiceLifecycle};
+use crate::manager::services::{
+    install_callback, remove_partial_install, DownloadManager, DownloadResult, DownloadTask,
+    PhpService,
+};
+use crate::types::{ServiceData, ServiceDataStatus, ServiceStatus, ServiceType};
 use crate::utils::create_command;
 use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures_util::future::BoxFuture;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs::File;
-use std::io::copy;
+use std::io::{copy, BufRead, BufReader};
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::sync::{Arc, OnceLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
 
 /// Nginx 版本信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,11 +34,109 @@ pub struct NginxVersion {
     pub date: String,
 }
 
+/// upstream 负载均衡策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BalanceMethod {
+    #[default]
+    RoundRobin,
+    LeastConn,
+    IpHash,
+}
+
+impl BalanceMethod {
+    /// 对应的 nginx 指令，轮询为默认策略无需指令
+    fn directive(&self) -> Option<&'static str> {
+        match self {
+            BalanceMethod::RoundRobin => None,
+            BalanceMethod::LeastConn => Some("least_conn"),
+            BalanceMethod::IpHash => Some("ip_hash"),
+        }
+    }
+}
+
+/// upstream 后端服务池
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NginxUpstream {
+    pub name: String,
+    pub servers: Vec<String>,
+    pub method: BalanceMethod,
+}
+
+/// access log 中某个路径的请求次数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NginxPathCount {
+    pub path: String,
+    pub count: u32,
+}
+
+/// stub_status 输出的实时连接统计
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NginxActiveConnections {
+    pub active: u64,
+    pub accepts: u64,
+    pub handled: u64,
+    pub requests: u64,
+    pub reading: u64,
+    pub writing: u64,
+    pub waiting: u64,
+}
+
+/// worker_processes 取值
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NginxWorkers {
+    Auto,
+    Count(u32),
+}
+
+impl NginxWorkers {
+    fn directive_value(&self) -> String {
+        match self {
+            NginxWorkers::Auto => "auto".to_string(),
+            NginxWorkers::Count(count) => count.to_string(),
+        }
+    }
+}
+
+/// upstream 配置文件名，与 nginx.conf 放在同一目录
+const UPSTREAMS_CONF: &str = "upstreams.conf";
+/// PHP FastCGI 片段文件名，与 nginx.conf 放在同一目录，由启用 PHP 的 server 块 include
+const PHP_FASTCGI_CONF: &str = "php-fastcgi.conf";
+/// 写入前校验配置时使用的临时文件后缀
+const CHECK_SUFFIX: &str = "envis-check";
+/// 原子写入配置时使用的临时文件后缀
+const WRITE_SUFFIX: &str = "envis-tmp";
+
+/// 开启定时日志轮转的 metadata 键，值为轮转日志的保留天数
+pub const NGINX_LOG_ROTATE_DAYS_KEY: &str = "NGINX_LOG_ROTATE_DAYS";
+/// 轮转文件名中的时间戳格式
+const ROTATE_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+/// 定时轮转线程的检查间隔
+const ROTATE_CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+/// access log 统计返回的热门路径数量
+const ACCESS_LOG_TOP_PATHS: usize = 10;
+/// envis 添加的 stub_status 路径
+const STATUS_LOCATION: &str = "/_envis_status";
+/// 请求 stub_status 的超时时间
+const STATUS_TIMEOUT: Duration = Duration::from_secs(5);
+/// 添加 location 并重载后，等待新 worker 接管请求的重试次数与间隔
+const STATUS_RETRY_TIMES: usize = 5;
+const STATUS_RETRY_INTERVAL: Duration = Duration::from_millis(300);
+
 /// 全局 Nginx 服务管理器单例
 static GLOBAL_NGINX_SERVICE: OnceLock<Arc<NginxService>> = OnceLock::new();
 
 /// Nginx 服务管理器
-pub struct NginxService {}
+pub struct NginxService {
+    /// 定时轮转线程是否已启动
+    rotation_started: AtomicBool,
+    /// 各服务数据最近一次定时轮转的日期
+    last_rotation: Mutex<HashMap<String, NaiveDate>>,
+}
 
 impl NginxService {
     /// 获取全局 Nginx 服务管理器单例
@@ -35,7 +148,10 @@ impl NginxService {
 
     /// 创建新的 Nginx 服务管理器（内部使用）
     fn new() -> Self {
-        Self {}
+        Self {
+            rotation_started: AtomicBool::new(false),
+            last_rotation: Mutex::new(HashMap::new()),
+        }
     }
 
     /// 获取可用的 Nginx 版本列表（静态列表示例）
@@ -54,7 +170,7 @@ impl NginxService {
         ]
     }
 
-    /// 检查 Nginx 是否已安装（判断 sbin/nginx 是否存在）
+    /// 检查 Nginx 是否已安装（Windows 判断根目录 nginx.exe，其他平台判断 sbin/nginx）
     pub fn is_installed(&self, version: &str) -> bool {
         let install_path = self.get_install_path(version);
         #[cfg(target_os = "windows")]
@@ -83,7 +199,16 @@ impl NginxService {
     }
 
     fn nginx_binary_candidates(&self, install_path: &Path) -> Vec<PathBuf> {
-        if cfg!(target_os = "windows") {
+        Self::binary_candidates(install_path, cfg!(target_os = "windows"))
+    }
+
+    fn resolve_nginx_binary(&self, install_path: &Path) -> PathBuf {
+        Self::locate_binary(install_path, cfg!(target_os = "windows"))
+    }
+
+    /// 可执行文件候选路径：Windows 发行包的 nginx.exe 位于根目录，没有 sbin/
+    fn binary_candidates(install_path: &Path, windows: bool) -> Vec<PathBuf> {
+        if windows {
             vec![
                 install_path.join("nginx.exe"),
                 install_path.join("nginx"),
@@ -98,12 +223,13 @@ impl NginxService {
         }
     }
 
-    fn resolve_nginx_binary(&self, install_path: &Path) -> PathBuf {
-        self.nginx_binary_candidates(install_path)
+    /// 按候选顺序查找可执行文件，均不存在时返回该布局的默认路径
+    fn locate_binary(install_path: &Path, windows: bool) -> PathBuf {
+        Self::binary_candidates(install_path, windows)
             .into_iter()
             .find(|path| path.exists())
             .unwrap_or_else(|| {
-                if cfg!(target_os = "windows") {
+                if windows {
                     install_path.join("nginx.exe")
                 } else {
                     install_path.join("sbin").join("nginx")
@@ -118,13 +244,22 @@ impl NginxService {
             return Ok(());
         }
 
-        for candidate in [install_path.join("nginx"), install_path.join("sbin").join("nginx")] {
+        // 旧版本可能安装在 sbin/ 下，统一移动到根目录，与 conf/、html/ 同级
+        for candidate in [
+            install_path.join("nginx"),
+            install_path.join("sbin").join("nginx.exe"),
+            install_path.join("sbin").join("nginx"),
+        ] {
             if candidate.exists() {
-                let renamed = candidate.with_extension("exe");
-                std::fs::rename(&candidate, &renamed)?;
+                // 运行中的 nginx.exe 无法移动，此时仍按原位置执行
+                if let Err(e) = std::fs::rename(&candidate, &binary_with_ext) {
+                    log::warn!("移动 Nginx 可执行文件 {} 失败: {}", candidate.display(), e);
+                    return Ok(());
+                }
                 log::info!(
-                    "Windows 下检测到无扩展名 Nginx 可执行文件，已重命名为: {}",
-                    renamed.display()
+                    "已将 Nginx 可执行文件 {} 移动到 {}",
+                    candidate.display(),
+                    binary_with_ext.display()
                 );
                 return Ok(());
             }
@@ -187,56 +322,13 @@ impl NginxService {
         let download_manager = DownloadManager::global();
 
         let version_for_callback = version.to_string();
-        let success_callback = Arc::new(move |task: &DownloadTask| {
-            log::info!(
-                "Nginx {} 下载完成: {} - {}",
-                version_for_callback,
-                task.id,
-                task.filename
-            );
-            let task_for_spawn = task.clone();
-            let version_for_spawn = version_for_callback.clone();
-            let service_for_spawn = NginxService::global();
-
-            tokio::spawn(async move {
-                let download_manager = DownloadManager::global();
-                if let Err(e) = download_manager.update_task_status(
-                    &task_for_spawn.id,
-                    crate::manager::services::DownloadStatus::Installing,
-                    None,
-                ) {
-                    log::error!("更新任务状态失败: {}", e);
-                } else {
-                    log::info!("Nginx {} 开始安装", version_for_spawn);
-                }
-
-                match service_for_spawn
-                    .extract_and_install(&task_for_spawn, &version_for_spawn)
+        let success_callback = install_callback(format!("Nginx {}", version), move |task| {
+            let version = version_for_callback.clone();
+            async move {
+                NginxService::global()
+                    .extract_and_install(&task, &version)
                     .await
-                {
-                    Ok(_) => {
-                        if let Err(e) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            crate::manager::services::DownloadStatus::Installed,
-                            None,
-                        ) {
-                            log::error!("更新任务状态失败: {}", e);
-                        } else {
-                            log::info!("Nginx {} 安装成功", version_for_spawn);
-                        }
-                    }
-                    Err(e) => {
-                        if let Err(update_err) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            crate::manager::services::DownloadStatus::Failed,
-                            Some(format!("安装失败: {}", e)),
-                        ) {
-                            log::error!("更新任务状态失败: {}", update_err);
-                        }
-                        log::error!("Nginx {} 安装失败: {}", version_for_spawn, e);
-                    }
-                }
-            });
+            }
         });
 
         match download_manager
@@ -266,6 +358,16 @@ impl NginxService {
 
     /// 解压和安装 Nginx
     pub async fn extract_and_install(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let result = self.install_from_archive(task, version).await;
+        if result.is_err() {
+            // 解压安装失败时清理残缺的安装目录
+            remove_partial_install(&self.get_install_path(version));
+        }
+        result
+    }
+
+    /// 将下载的压缩包解压到安装目录
+    async fn install_from_archive(&self, task: &DownloadTask, version: &str) -> Result<()> {
         let archive_path = &task.target_path;
         let install_path = self.get_install_path(version);
         std::fs::create_dir_all(&install_path)?;
@@ -299,6 +401,8 @@ impl NginxService {
         #[cfg(target_os = "windows")]
         self.normalize_windows_binary_name(&install_path)?;
 
+        self.move_binary_to_layout(&install_path)?;
+
         #[cfg(not(target_os = "windows"))]
         self.set_executable_permissions(&install_path)?;
 
@@ -322,6 +426,74 @@ impl NginxService {
         if archive_path.exists() {
             let _ = std::fs::remove_file(archive_path);
         }
+
+        self.verify_nginx_binary(&nginx_bin)?;
+        Ok(())
+    }
+
+    /// 压缩包内的可执行文件不在预期位置时，扫描解压目录并移动到
+    /// 安装根目录（Windows）或 sbin/（其他平台）
+    fn move_binary_to_layout(&self, install_path: &Path) -> Result<()> {
+        if self
+            .nginx_binary_candidates(install_path)
+            .iter()
+            .any(|path| path.exists())
+        {
+            return Ok(());
+        }
+
+        let binary_name = if cfg!(target_os = "windows") {
+            "nginx.exe"
+        } else {
+            "nginx"
+        };
+        let found = walkdir::WalkDir::new(install_path)
+            .max_depth(5)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .find(|e| {
+                e.path().is_file()
+                    && e.file_name()
+                        .to_str()
+                        .map(|n| n.eq_ignore_ascii_case(binary_name))
+                        .unwrap_or(false)
+            })
+            .map(|e| e.path().to_path_buf());
+
+        let Some(source) = found else {
+            return Ok(());
+        };
+
+        let destination = if cfg!(target_os = "windows") {
+            install_path.join(binary_name)
+        } else {
+            let sbin_dir = install_path.join("sbin");
+            std::fs::create_dir_all(&sbin_dir)?;
+            sbin_dir.join(binary_name)
+        };
+        std::fs::rename(&source, &destination)?;
+        log::info!(
+            "已将 Nginx 可执行文件从 {} 移动到 {}",
+            source.display(),
+            destination.display()
+        );
+        Ok(())
+    }
+
+    /// 运行 `nginx -v` 确认安装的二进制文件可以正常执行
+    fn verify_nginx_binary(&self, nginx_bin: &Path) -> Result<()> {
+        let output = create_command(nginx_bin)
+            .arg("-v")
+            .output()
+            .map_err(|e| anyhow!("无法执行 Nginx 可执行文件 {}: {}", nginx_bin.display(), e))?;
+
+        // nginx -v 将版本信息输出到 stderr
+        let version_info = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if !output.status.success() {
+            return Err(anyhow!("Nginx 可执行文件校验失败: {}", version_info));
+        }
+
+        log::info!("Nginx 安装校验通过: {}", version_info);
         Ok(())
     }
 
@@ -348,18 +520,24 @@ impl NginxService {
     }
 
     fn promote_extracted_contents(&self, extracted_dir: &PathBuf, target_dir: &PathBuf) -> Result<()> {
-        let entries: Vec<_> = std::fs::read_dir(extracted_dir)?.collect::<std::result::Result<Vec<_>, _>>()?;
+        // 压缩包可能带有 nginx-<version>/ 顶层目录（可能多层），展开到找到可执行文件或多个条目为止
+        let mut source_dir = extracted_dir.clone();
+        loop {
+            let entries: Vec<_> =
+                std::fs::read_dir(&source_dir)?.collect::<std::result::Result<Vec<_>, _>>()?;
+            if entries.is_empty() {
+                return Err(anyhow!("压缩包为空，未提取到任何文件"));
+            }
 
-        if entries.is_empty() {
-            return Err(anyhow!("压缩包为空，未提取到任何文件"));
+            let has_binary = Self::binary_candidates(&source_dir, cfg!(target_os = "windows"))
+                .iter()
+                .any(|path| path.exists());
+            if has_binary || entries.len() != 1 || !entries[0].path().is_dir() {
+                break;
+            }
+            source_dir = entries[0].path();
         }
 
-        let source_dir = if entries.len() == 1 && entries[0].path().is_dir() {
-            entries[0].path()
-        } else {
-            extracted_dir.clone()
-        };
-
         for entry in std::fs::read_dir(&source_dir)? {
             let entry = entry?;
             let destination = target_dir.join(entry.file_name());
@@ -418,7 +596,7 @@ impl NginxService {
 
     /// 设置可执行权限 (Unix 系统)
     #[cfg(not(target_os = "windows"))]
-    fn set_executable_permissions(&self, install_dir: &PathBuf) -> Result<()> {
+    fn set_executable_permissions(&self, install_dir: &Path) -> Result<()> {
         use std::os::unix::fs::PermissionsExt;
         let sbin_dir = install_dir.join("sbin");
         if sbin_dir.exists() {
@@ -447,22 +625,76 @@ impl NginxService {
 
     fn create_runtime_command(
         &self,
-        nginx_bin: &PathBuf,
-        install_path: &PathBuf,
-        conf_path: &PathBuf,
+        nginx_bin: &Path,
+        install_path: &Path,
+        conf_path: &Path,
     ) -> Command {
+        let prefix = Self::runtime_prefix(install_path, conf_path);
+        // nginx 启动时会打开 prefix 下的 logs/error.log，Windows 还需要 temp/ 存放临时文件
+        for dir in ["logs", "temp"] {
+            let dir_path = prefix.join(dir);
+            if dir_path.is_file() {
+                let _ = std::fs::remove_file(&dir_path);
+            }
+            if let Err(e) = std::fs::create_dir_all(&dir_path) {
+                log::warn!("创建 Nginx {} 目录失败: {}", dir, e);
+            }
+        }
+
         let mut command = create_command(nginx_bin);
         command
-            .current_dir(install_path)
+            .current_dir(&prefix)
             .arg("-p")
-            .arg(install_path)
+            .arg(&prefix)
             .arg("-c")
             .arg(conf_path);
         command
     }
 
+    /// 运行时的 -p 前缀：使用配置文件所在的环境数据目录，使 pid、日志和临时文件按环境隔离；
+    /// 配置文件位于安装目录内时仍使用安装目录
+    fn runtime_prefix(install_path: &Path, conf_path: &Path) -> PathBuf {
+        match conf_path.parent() {
+            Some(conf_dir) if !conf_path.starts_with(install_path) => conf_dir.to_path_buf(),
+            _ => install_path.to_path_buf(),
+        }
+    }
+
+    /// 从 prefix 下的 logs/nginx.pid 读取主进程 PID
+    fn read_master_pid(prefix: &Path) -> Option<u32> {
+        std::fs::read_to_string(prefix.join("logs").join("nginx.pid"))
+            .ok()
+            .and_then(|content| content.trim().parse::<u32>().ok())
+    }
+
+    /// 默认配置中 root html 相对 prefix 解析，环境数据目录缺少 html/ 时从安装目录复制
+    fn ensure_prefix_html(install_path: &Path, prefix: &Path) -> Result<()> {
+        let source = install_path.join("html");
+        let target = prefix.join("html");
+        if target.exists() || !source.is_dir() || prefix == install_path {
+            return Ok(());
+        }
+
+        for entry in walkdir::WalkDir::new(&source)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            let Ok(relative) = entry.path().strip_prefix(&source) else {
+                continue;
+            };
+            let destination = target.join(relative);
+            if entry.file_type().is_dir() {
+                std::fs::create_dir_all(&destination)?;
+            } else {
+                std::fs::copy(entry.path(), &destination)?;
+            }
+        }
+        Ok(())
+    }
+
     /// 启动 Nginx 服务
     pub fn start_service(&self, service_data: &ServiceData) -> Result<ServiceDataResult> {
+        ServiceSupervisor::global().clear_intentional_stop(&service_data.id);
         log::info!("启动 Nginx 服务");
         let version = &service_data.version;
         let install_path = self.get_install_path(version);
@@ -483,19 +715,6 @@ impl NginxService {
             });
         }
 
-        // 确保 logs 目录存在
-        let logs_dir = install_path.join("logs");
-        if logs_dir.exists() && logs_dir.is_file() {
-            if let Err(e) = std::fs::remove_file(&logs_dir) {
-                log::warn!("删除 logs 文件失败: {}", e);
-            }
-        }
-        if !logs_dir.exists() {
-            if let Err(e) = std::fs::create_dir_all(&logs_dir) {
-                log::warn!("创建 logs 目录失败: {}", e);
-            }
-        }
-
         // 读取 metadata 的配置路径，否则报错
         let conf_path = service_data
             .metadata
@@ -534,25 +753,49 @@ impl NginxService {
         // 修复未加引号的 error_log 路径
         self.quote_error_log_path_in_conf(&conf_path)?;
 
-        // 执行 {nginx_bin} -c {config_path} 启动服务
-        self.create_runtime_command(&nginx_bin, &install_path, &conf_path)
-            .stdout(std::process::Stdio::null())
-            .stderr(std::process::Stdio::null())
+        let prefix = Self::runtime_prefix(&install_path, &conf_path);
+        if let Err(e) = Self::ensure_prefix_html(&install_path, &prefix) {
+            log::warn!("复制 html 目录失败: {}", e);
+        }
+
+        // 输出写入配置目录下的 logs/nginx-stdout.log，不能使用管道（见 nginx.md）
+        let capture = OutputCapture::new(conf_path.parent().unwrap_or(&install_path), "nginx")?;
+
+        // 执行 {nginx_bin} -p {prefix} -c {config_path} 启动服务
+        let mut command = self.create_runtime_command(&nginx_bin, &install_path, &conf_path);
+        capture.attach(&mut command)?;
+        let mut child = command
             .spawn()
             .map_err(|e| anyhow!("启动 Nginx 失败: {}", e))?;
 
+        // 配置错误时 nginx 会立即以非零状态退出
+        if let Some(status) = wait_for_early_exit(&mut child) {
+            let detail = capture.failure_detail(&status);
+            log::error!("Nginx 启动失败: {}", detail);
+            return Ok(ServiceDataResult {
+                success: false,
+                message: format!("Nginx 启动失败: {}", detail),
+                data: Some(serde_json::json!({
+                    "configPath": conf_path.to_string_lossy().to_string(),
+                    "outputLog": capture.path().to_string_lossy().to_string(),
+                })),
+            });
+        }
+
         log::info!("Nginx 服务启动成功");
         Ok(ServiceDataResult {
             success: true,
             message: "Nginx 启动成功".to_string(),
             data: Some(serde_json::json!({
-                "configPath": conf_path.to_string_lossy().to_string()
+                "configPath": conf_path.to_string_lossy().to_string(),
+                "outputLog": capture.path().to_string_lossy().to_string(),
             })),
         })
     }
 
     /// 停止 Nginx 服务
     pub fn stop_service(&self, service_data: &ServiceData) -> Result<()> {
+        ServiceSupervisor::global().mark_intentional_stop(&service_data.id);
         log::info!("停止 Nginx 服务");
         let version = &service_data.version;
         let install_path = self.get_install_path(version);
@@ -579,23 +822,56 @@ impl NginxService {
             return Err(anyhow!("Nginx 配置文件不存在: {}", conf_path.display()));
         }
 
-        // 使用安装路径下的 nginx 执行优雅停止
+        // 使用与启动时相同的 prefix 执行优雅退出
         let output = self
             .create_runtime_command(&nginx_bin, &install_path, &conf_path)
             .arg("-s")
-            .arg("stop")
+            .arg("quit")
             .output()
             .map_err(|e| anyhow!("停止 Nginx 失败: {}", e))?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow!("停止 Nginx 失败: {}", stderr));
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            log::warn!("nginx -s quit 失败，尝试按 PID 结束进程: {}", stderr);
+            self.kill_master_process(&install_path, &conf_path)
+                .map_err(|e| anyhow!("停止 Nginx 失败: {}; {}", stderr, e))?;
         }
 
         log::info!("Nginx 服务停止成功");
         Ok(())
     }
 
+    /// -s quit 失败时（如 pid 文件位于旧的安装目录 prefix 下）按 PID 结束主进程
+    fn kill_master_process(&self, install_path: &Path, conf_path: &Path) -> Result<()> {
+        let pid = [
+            Self::runtime_prefix(install_path, conf_path),
+            install_path.to_path_buf(),
+        ]
+        .iter()
+        .find_map(|prefix| Self::read_master_pid(prefix))
+        .ok_or_else(|| anyhow!("未找到 nginx.pid"))?;
+
+        let output = if cfg!(target_os = "windows") {
+            create_command("taskkill")
+                .args(["/PID", &pid.to_string(), "/T", "/F"])
+                .output()
+        } else {
+            create_command("kill")
+                .args(["-TERM", &pid.to_string()])
+                .output()
+        }
+        .map_err(|e| anyhow!("结束进程 {} 失败: {}", pid, e))?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "结束进程 {} 失败: {}",
+                pid,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(())
+    }
+
     /// 重启 Nginx 服务
     pub fn restart_service(&self, service_data: &ServiceData) -> Result<()> {
         log::info!("重启 Nginx 服务");
@@ -642,6 +918,17 @@ impl NginxService {
 
     /// 获取 Nginx 服务状态
     pub fn get_service_status(&self, service_data: &ServiceData) -> Result<ServiceStatus> {
+        match self.get_service_health(service_data) {
+            Ok(health) => Ok(health.status()),
+            Err(e) => {
+                log::warn!("{}", e);
+                Ok(ServiceStatus::Unknown)
+            }
+        }
+    }
+
+    /// 获取 Nginx 服务健康检查结果（进程、端口、HTTP 响应）
+    pub fn get_service_health(&self, service_data: &ServiceData) -> Result<ServiceHealth> {
         // log::info!("获取 Nginx 服务状态");
 
         let version = &service_data.version;
@@ -677,77 +964,1411 @@ impl NginxService {
         match output {
             Ok(output) => {
                 let stdout = String::from_utf8_lossy(&output.stdout);
-                if stdout.contains("nginx") {
-                    // log::info!("Nginx 服务正在运行");
-                    Ok(ServiceStatus::Running)
-                } else {
-                    // log::info!("Nginx 服务未运行");
-                    Ok(ServiceStatus::Stopped)
-                }
-            }
-            Err(e) => {
-                log::warn!("检查 Nginx 服务状态失败: {}", e);
-                Ok(ServiceStatus::Unknown)
+                let process = stdout.contains("nginx");
+
+                // 健康检查：向配置的监听端口发送 HTTP 请求
+                let port = std::fs::read_to_string(&conf_path)
+                    .ok()
+                    .and_then(|content| Self::parse_listen_port(&content))
+                    .unwrap_or(80);
+                let port_latency = health::check_tcp_port("127.0.0.1", port);
+                let http_latency = port_latency.and_then(|_| health::check_http("127.0.0.1", port));
+
+                Ok(ServiceHealth {
+                    process,
+                    port_open: port_latency.is_some(),
+                    responding: http_latency.is_some(),
+                    latency_ms: http_latency.or(port_latency),
+                })
             }
+            Err(e) => Err(anyhow!("检查 Nginx 服务状态失败: {}", e)),
         }
     }
 
-    fn format_path_for_nginx_conf<P: AsRef<Path>>(path: P) -> String {
-        // Nginx 配置文件中应统一使用 / 作为路径分隔符，Windows 也能识别。
-        path.as_ref().to_string_lossy().replace('\\', "/")
+    /// 从配置内容中解析第一个 listen 端口
+    fn parse_listen_port(content: &str) -> Option<u16> {
+        content
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.strip_prefix("listen"))
+            .filter_map(|rest| rest.split_whitespace().next())
+            .filter_map(|addr| {
+                let addr = addr.trim_end_matches(';');
+                addr.rsplit(':').next().and_then(|p| p.parse::<u16>().ok())
+            })
+            .next()
     }
 
-    /// 修复未加引号的 error_log 路径
-    fn quote_error_log_path_in_conf(&self, conf_path: &PathBuf) -> Result<()> {
-        let content = std::fs::read_to_string(conf_path)?;
-        let mut updated = false;
-        let levels = ["debug", "info", "notice", "warn", "error", "crit", "alert", "emerg"];
+    /// 检查 Nginx 配置文件语法（nginx -t）
+    pub fn validate_nginx_config(&self, service_data: &ServiceData) -> Result<ServiceDataResult> {
+        let conf_path = self.resolve_conf_path(service_data);
+        if !conf_path.exists() {
+            return Err(anyhow!("Nginx 配置文件不存在: {}", conf_path.display()));
+        }
+        self.test_config(&service_data.version, &conf_path)
+    }
 
-        let modified = content
-            .lines()
-            .map(|line| {
-                let trimmed = line.trim_start();
-                if !trimmed.starts_with("error_log") {
-                    return line.to_string();
-                }
+    /// 列出 upstreams.conf 中的 upstream
+    pub fn list_upstreams(&self, service_data: &ServiceData) -> Result<Vec<NginxUpstream>> {
+        let upstreams_path = self.upstreams_conf_path(service_data)?;
+        if !upstreams_path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&upstreams_path)?;
+        Ok(Self::parse_upstreams(&content))
+    }
 
-                let directive = trimmed.splitn(2, '#').next().unwrap_or(trimmed).trim_end();
-                if !directive.ends_with(';') {
-                    return line.to_string();
-                }
+    /// 添加 upstream，同名时覆盖；写入前先校验配置
+    pub fn add_upstream(
+        &self,
+        service_data: &ServiceData,
+        upstream_name: &str,
+        servers: Vec<String>,
+        method: BalanceMethod,
+    ) -> Result<ServiceDataResult> {
+        let upstream_name = upstream_name.trim();
+        if upstream_name.is_empty()
+            || !upstream_name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(anyhow!(
+                "upstream 名称只能包含字母、数字、下划线和连字符: {}",
+                upstream_name
+            ));
+        }
+        let servers: Vec<String> = servers
+            .iter()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        if servers.is_empty() {
+            return Err(anyhow!("upstream {} 至少需要一个后端地址", upstream_name));
+        }
+        if let Some(invalid) = servers
+            .iter()
+            .find(|s| s.contains([';', '{', '}', '#', '\n', '\r']))
+        {
+            return Err(anyhow!("无效的后端地址: {}", invalid));
+        }
 
-                let directive = directive[..directive.len() - 1].trim();
-                let tokens: Vec<&str> = directive.split_whitespace().collect();
-                if tokens.len() <= 2 {
-                    return line.to_string();
-                }
+        let mut upstreams = self.list_upstreams(service_data)?;
+        let upstream = NginxUpstream {
+            name: upstream_name.to_string(),
+            servers,
+            method,
+        };
+        match upstreams.iter_mut().find(|u| u.name == upstream.name) {
+            Some(existing) => *existing = upstream.clone(),
+            None => upstreams.push(upstream.clone()),
+        }
 
-                let (path_parts, level_suffix) = if levels.contains(&tokens.last().unwrap()) && tokens.len() > 2 {
-                    (tokens[1..tokens.len() - 1].to_vec(), Some(tokens.last().unwrap()))
-                } else {
-                    (tokens[1..].to_vec(), None)
-                };
+        let result = self.write_upstreams(service_data, &upstreams)?;
+        if !result.success {
+            return Ok(result);
+        }
+        log::info!("已写入 Nginx upstream: {}", upstream.name);
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("upstream {} 已保存", upstream.name),
+            data: Some(serde_json::json!({
+                "upstream": upstream,
+                "path": self.upstreams_conf_path(service_data)?.to_string_lossy().to_string(),
+            })),
+        })
+    }
 
-                let path = path_parts.join(" ");
-                let normalized_path = Self::format_path_for_nginx_conf(PathBuf::from(&path));
-                let requires_quote = trimmed.contains('"') || trimmed.contains('\'');
-                let indent = &line[..line.len() - trimmed.len()];
-                let level_extension = level_suffix.map_or(String::new(), |lvl| format!(" {}", lvl));
+    /// 删除 upstream；写入前先校验配置
+    pub fn remove_upstream(
+        &self,
+        service_data: &ServiceData,
+        upstream_name: &str,
+    ) -> Result<ServiceDataResult> {
+        let mut upstreams = self.list_upstreams(service_data)?;
+        let before = upstreams.len();
+        upstreams.retain(|u| u.name != upstream_name);
+        if upstreams.len() == before {
+            return Err(anyhow!("upstream 不存在: {}", upstream_name));
+        }
 
-                if !requires_quote || path.contains('\\') {
-                    updated = true;
-                    return format!("{indent}error_log \"{normalized_path}\"{level_extension};");
-                }
+        let result = self.write_upstreams(service_data, &upstreams)?;
+        if !result.success {
+            return Ok(result);
+        }
+        log::info!("已删除 Nginx upstream: {}", upstream_name);
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("upstream {} 已删除", upstream_name),
+            data: None,
+        })
+    }
 
-                line.to_string()
+    /// 按当前环境的 PHP 服务生成 php-fastcgi.conf，声明 php: true 的 server 块
+    /// include 该文件即可把 .php 请求转发到本环境的 php-fpm
+    pub fn write_php_fastcgi_conf(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        // 同一环境有多个 PHP 版本时优先使用已激活的
+        let mut php_service_datas: Vec<ServiceData> = EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .get_environment_all_service_datas(environment_id)?
+            .into_iter()
+            .filter(|sd| matches!(sd.service_type, ServiceType::Php))
+            .collect();
+        php_service_datas.sort_by_key(|sd| !matches!(sd.status, ServiceDataStatus::Active));
+        let php_service_data = php_service_datas
+            .first()
+            .ok_or_else(|| anyhow!("当前环境未添加 PHP 服务"))?;
+
+        let fastcgi_pass =
+            PhpService::global().get_fastcgi_pass(environment_id, php_service_data)?;
+        let conf_path = self.resolve_conf_path(service_data);
+        let fastcgi_conf_path = conf_path
+            .parent()
+            .map(|dir| dir.join(PHP_FASTCGI_CONF))
+            .ok_or_else(|| anyhow!("无法确定 Nginx 配置目录: {}", conf_path.display()))?;
+        std::fs::write(&fastcgi_conf_path, Self::render_php_fastcgi(&fastcgi_pass))?;
+        log::info!(
+            "已生成 {}，fastcgi_pass {}",
+            fastcgi_conf_path.display(),
+            fastcgi_pass
+        );
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("{} 已生成", PHP_FASTCGI_CONF),
+            data: Some(serde_json::json!({
+                "path": fastcgi_conf_path.to_string_lossy().to_string(),
+                "fastcgiPass": fastcgi_pass,
+                "phpVersion": php_service_data.version,
+                "include": format!("include {};", PHP_FASTCGI_CONF),
+            })),
+        })
+    }
+
+    /// 启用 PHP 的 server 块中使用的 location 配置
+    pub fn render_php_fastcgi(fastcgi_pass: &str) -> String {
+        format!(
+            r#"# Auto-generated PHP FastCGI location by envis
+location ~ \.php$ {{
+    try_files $uri =404;
+    fastcgi_pass {fastcgi_pass};
+    fastcgi_index index.php;
+    include fastcgi_params;
+    fastcgi_param SCRIPT_FILENAME $document_root$fastcgi_script_name;
+}}
+"#
+        )
+    }
+
+    /// 修改 nginx.conf 主配置中的 worker_processes
+    pub fn set_worker_processes(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+        value: NginxWorkers,
+    ) -> Result<ServiceDataResult> {
+        if value == NginxWorkers::Count(0) {
+            return Err(anyhow!("worker_processes 必须大于 0"));
+        }
+        self.patch_conf_directive(
+            service_data,
+            None,
+            "worker_processes",
+            &value.directive_value(),
+        )
+    }
+
+    /// 修改 nginx.conf events 块中的 worker_connections
+    pub fn set_worker_connections(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+        connections: u32,
+    ) -> Result<ServiceDataResult> {
+        if connections == 0 {
+            return Err(anyhow!("worker_connections 必须大于 0"));
+        }
+        self.patch_conf_directive(
+            service_data,
+            Some("events"),
+            "worker_connections",
+            &connections.to_string(),
+        )
+    }
+
+    /// 读取 metadata 中的配置路径，回退到安装目录 conf/nginx.conf
+    fn resolve_conf_path(&self, service_data: &ServiceData) -> PathBuf {
+        service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("NGINX_CONF"))
+            .and_then(|v| v.as_str())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                self.get_install_path(&service_data.version)
+                    .join("conf")
+                    .join("nginx.conf")
             })
-            .collect::<Vec<_>>()
-            .join("\n");
+    }
 
-        if updated {
-            std::fs::write(conf_path, modified)?;
+    fn upstreams_conf_path(&self, service_data: &ServiceData) -> Result<PathBuf> {
+        let conf_path = self.resolve_conf_path(service_data);
+        conf_path
+            .parent()
+            .map(|dir| dir.join(UPSTREAMS_CONF))
+            .ok_or_else(|| anyhow!("无法确定 Nginx 配置目录: {}", conf_path.display()))
+    }
+
+    /// 使用 nginx -t 检查指定配置文件
+    fn test_config(&self, version: &str, conf_path: &Path) -> Result<ServiceDataResult> {
+        let install_path = self.get_install_path(version);
+
+        #[cfg(target_os = "windows")]
+        self.normalize_windows_binary_name(&install_path)?;
+
+        let nginx_bin = self.resolve_nginx_binary(&install_path);
+        if !nginx_bin.exists() {
+            return Err(anyhow!("Nginx 可执行文件不存在: {:?}", nginx_bin));
         }
 
-        Ok(())
+        let output = self
+            .create_runtime_command(&nginx_bin, &install_path, conf_path)
+            .arg("-t")
+            .output()
+            .map_err(|e| anyhow!("检查 Nginx 配置失败: {}", e))?;
+
+        // nginx -t 的结果输出在 stderr
+        let detail = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let success = output.status.success();
+        Ok(ServiceDataResult {
+            success,
+            message: if success {
+                "Nginx 配置检查通过".to_string()
+            } else {
+                format!("Nginx 配置检查失败: {}", detail)
+            },
+            data: Some(serde_json::json!({
+                "configPath": conf_path.to_string_lossy().to_string(),
+                "output": detail,
+            })),
+        })
+    }
+
+    /// 先在临时文件中校验 upstreams.conf 与 nginx.conf，通过后再写入正式文件
+    fn write_upstreams(
+        &self,
+        service_data: &ServiceData,
+        upstreams: &[NginxUpstream],
+    ) -> Result<ServiceDataResult> {
+        let conf_path = self.resolve_conf_path(service_data);
+        if !conf_path.exists() {
+            return Err(anyhow!("Nginx 配置文件不存在: {}", conf_path.display()));
+        }
+        let upstreams_path = self.upstreams_conf_path(service_data)?;
+        let conf_content = std::fs::read_to_string(&conf_path)?;
+        let upstreams_content = Self::render_upstreams(upstreams);
+
+        // 临时文件与正式文件同目录，保证 nginx.conf 中的相对路径仍然有效
+        let check_upstreams_path = upstreams_path.with_extension(format!("conf.{}", CHECK_SUFFIX));
+        let check_conf_path = conf_path.with_extension(format!("conf.{}", CHECK_SUFFIX));
+        std::fs::write(&check_upstreams_path, &upstreams_content)?;
+        let check = Self::with_upstreams_include(&conf_content, &check_upstreams_path)
+            .and_then(|content| Ok(std::fs::write(&check_conf_path, content)?))
+            .and_then(|_| self.test_config(&service_data.version, &check_conf_path));
+        let _ = std::fs::remove_file(&check_upstreams_path);
+        let _ = std::fs::remove_file(&check_conf_path);
+
+        let mut check = check?;
+        if !check.success {
+            log::warn!("{}", check.message);
+            return Ok(check);
+        }
+
+        std::fs::write(&upstreams_path, upstreams_content)?;
+        let updated_conf = Self::with_upstreams_include(&conf_content, &upstreams_path)?;
+        if updated_conf != conf_content {
+            std::fs::write(&conf_path, updated_conf)?;
+            log::info!("已在 {} 中引入 {}", conf_path.display(), UPSTREAMS_CONF);
+        }
+        check.data = None;
+        Ok(check)
+    }
+
+    /// 在 http 块开头引入 upstream 配置，替换已有的引入语句
+    fn with_upstreams_include(conf_content: &str, upstreams_path: &Path) -> Result<String> {
+        let include_line = format!(
+            "include \"{}\";",
+            Self::format_path_for_nginx_conf(upstreams_path)
+        );
+        let mut lines: Vec<String> = conf_content
+            .lines()
+            .filter(|line| {
+                let trimmed = line.trim_start();
+                !(trimmed.starts_with("include") && trimmed.contains(UPSTREAMS_CONF))
+            })
+            .map(|line| line.to_string())
+            .collect();
+
+        let http_index = lines
+            .iter()
+            .position(|line| {
+                let directive = line.split('#').next().unwrap_or("").trim();
+                directive
+                    .strip_prefix("http")
+                    .is_some_and(|rest| rest.trim() == "{")
+            })
+            .ok_or_else(|| anyhow!("nginx.conf 中未找到 http 块"))?;
+        let indent = lines
+            .get(http_index + 1)
+            .map(|line| &line[..line.len() - line.trim_start().len()])
+            .filter(|indent| !indent.is_empty())
+            .unwrap_or("    ")
+            .to_string();
+        lines.insert(http_index + 1, format!("{}{}", indent, include_line));
+
+        let mut content = lines.join("\n");
+        if conf_content.ends_with('\n') {
+            content.push('\n');
+        }
+        Ok(content)
+    }
+
+    fn render_upstreams(upstreams: &[NginxUpstream]) -> String {
+        let mut content = String::from("# Auto-generated upstreams by envis\n");
+        for upstream in upstreams {
+            content.push_str(&format!("\nupstream {} {{\n", upstream.name));
+            if let Some(directive) = upstream.method.directive() {
+                content.push_str(&format!("    {};\n", directive));
+            }
+            for server in &upstream.servers {
+                content.push_str(&format!("    server {};\n", server));
+            }
+            content.push_str("}\n");
+        }
+        content
+    }
+
+    fn parse_upstreams(content: &str) -> Vec<NginxUpstream> {
+        let mut upstreams = Vec::new();
+        let mut current: Option<NginxUpstream> = None;
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if let Some(rest) = line.strip_prefix("upstream ") {
+                current = Some(NginxUpstream {
+                    name: rest.trim_end_matches('{').trim().to_string(),
+                    servers: Vec::new(),
+                    method: BalanceMethod::RoundRobin,
+                });
+            } else if line == "}" {
+                upstreams.extend(current.take());
+            } else if let Some(upstream) = current.as_mut() {
+                let directive = line.trim_end_matches(';').trim();
+                match directive {
+                    "least_conn" => upstream.method = BalanceMethod::LeastConn,
+                    "ip_hash" => upstream.method = BalanceMethod::IpHash,
+                    _ => {
+                        if let Some(server) = directive.strip_prefix("server ") {
+                            upstream.servers.push(server.trim().to_string());
+                        }
+                    }
+                }
+            }
+        }
+        upstreams
+    }
+
+    /// 写入前后各校验一次配置，写入后校验失败时恢复原配置
+    fn patch_conf_directive(
+        &self,
+        service_data: &ServiceData,
+        block: Option<&str>,
+        directive: &str,
+        value: &str,
+    ) -> Result<ServiceDataResult> {
+        // 原配置本身有错误时不做修改，避免把已有问题归咎于本次修改
+        let before = self.validate_nginx_config(service_data)?;
+        if !before.success {
+            log::warn!("{}", before.message);
+            return Ok(before);
+        }
+
+        let conf_path = self.resolve_conf_path(service_data);
+        let conf_content = std::fs::read_to_string(&conf_path)?;
+        let updated_conf = Self::with_directive(&conf_content, block, directive, value)?;
+        if updated_conf != conf_content {
+            Self::write_conf_atomically(&conf_path, &updated_conf)?;
+        }
+
+        let mut after = self.validate_nginx_config(service_data)?;
+        if !after.success {
+            Self::write_conf_atomically(&conf_path, &conf_content)?;
+            after.message = format!("{}，已恢复原配置", after.message);
+            log::warn!("{}", after.message);
+            return Ok(after);
+        }
+
+        log::info!(
+            "已将 {} 中的 {} 设置为 {}",
+            conf_path.display(),
+            directive,
+            value
+        );
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("{} 已设置为 {}", directive, value),
+            data: Some(serde_json::json!({
+                "configPath": conf_path.to_string_lossy().to_string(),
+                "directive": directive,
+                "value": value,
+            })),
+        })
+    }
+
+    /// 按行定位指令所在的上下文（block 为 None 时为主配置，否则为顶层的 block 块），
+    /// 替换已有指令的值，不存在时插入到上下文中
+    fn with_directive(
+        conf_content: &str,
+        block: Option<&str>,
+        directive: &str,
+        value: &str,
+    ) -> Result<String> {
+        let mut lines: Vec<String> = conf_content.lines().map(|line| line.to_string()).collect();
+        let target_depth = if block.is_some() { 1 } else { 0 };
+        let mut depth = 0usize;
+        // 指令所在上下文的开始行（主配置为 None），以及已有指令和第一个顶层块所在的行
+        let mut block_index = None;
+        let mut existing_index = None;
+        let mut first_block_index = None;
+
+        for (index, line) in lines.iter().enumerate() {
+            let code = line.split('#').next().unwrap_or("").trim();
+            let in_context = depth == target_depth && (block.is_none() || block_index.is_some());
+            if in_context
+                && code.split(|c: char| c.is_whitespace() || c == ';').next() == Some(directive)
+            {
+                existing_index = Some(index);
+                break;
+            }
+            if depth == 0 && code.contains('{') {
+                first_block_index.get_or_insert(index);
+                if let Some(name) = block {
+                    if block_index.is_none()
+                        && code
+                            .strip_prefix(name)
+                            .is_some_and(|rest| rest.trim() == "{")
+                    {
+                        block_index = Some(index);
+                    }
+                }
+            }
+
+            depth += code.matches('{').count();
+            depth = depth.saturating_sub(code.matches('}').count());
+            if block_index.is_some() && depth == 0 {
+                break;
+            }
+        }
+
+        match (existing_index, block, block_index) {
+            (Some(index), _, _) => {
+                let line = &lines[index];
+                let trimmed = line.trim_start();
+                let indent = &line[..line.len() - trimmed.len()];
+                let rest = &trimmed[directive.len()..];
+                // 保留原有的对齐空白和行尾注释
+                let separator = match &rest[..rest.len() - rest.trim_start().len()] {
+                    "" => " ",
+                    separator => separator,
+                };
+                let trailing = rest.find(';').map(|pos| &rest[pos + 1..]).unwrap_or("");
+                lines[index] =
+                    format!("{}{}{}{};{}", indent, directive, separator, value, trailing);
+            }
+            (None, Some(name), None) => {
+                return Err(anyhow!("nginx.conf 中未找到 {} 块", name));
+            }
+            (None, Some(_), Some(index)) => {
+                let indent = lines
+                    .get(index + 1)
+                    .map(|line| &line[..line.len() - line.trim_start().len()])
+                    .filter(|indent| !indent.is_empty())
+                    .unwrap_or("    ")
+                    .to_string();
+                lines.insert(index + 1, format!("{}{} {};", indent, directive, value));
+            }
+            (None, None, _) => {
+                let index = first_block_index.unwrap_or(lines.len());
+                lines.insert(index, format!("{} {};", directive, value));
+            }
+        }
+
+        let mut content = lines.join("\n");
+        if conf_content.ends_with('\n') {
+            content.push('\n');
+        }
+        Ok(content)
+    }
+
+    /// 先写同目录的临时文件再重命名覆盖，避免写入中断时留下不完整的配置
+    fn write_conf_atomically(conf_path: &Path, content: &str) -> Result<()> {
+        let tmp_path = conf_path.with_extension(format!("conf.{}", WRITE_SUFFIX));
+        std::fs::write(&tmp_path, content)?;
+        if let Err(e) = std::fs::rename(&tmp_path, conf_path) {
+            let _ = std::fs::remove_file(&tmp_path);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// 轮转 access.log / error.log：重命名为带时间戳的文件并通知 nginx 重新打开日志，
+    /// 可选 gzip 压缩，并清理超过 retain_days 天的轮转日志。nginx 未运行时只重命名。
+    pub fn rotate_nginx_logs(
+        &self,
+        service_data: &ServiceData,
+        compress: bool,
+        retain_days: Option<u32>,
+    ) -> Result<ServiceDataResult> {
+        let log_paths = self.resolve_log_paths(service_data);
+        let running = self
+            .get_service_health(service_data)
+            .map(|health| health.process)
+            .unwrap_or(false);
+        let timestamp = Local::now().format(ROTATE_TIMESTAMP_FORMAT).to_string();
+
+        // Windows 下 nginx 运行时日志文件被占用无法重命名，需要先停止
+        let restart_required = cfg!(target_os = "windows") && running;
+        if restart_required {
+            self.stop_service(service_data)?;
+        }
+
+        let mut rotated = Vec::new();
+        let mut failed = Vec::new();
+        for log_path in &log_paths {
+            let non_empty = std::fs::metadata(log_path)
+                .map(|m| m.is_file() && m.len() > 0)
+                .unwrap_or(false);
+            if !non_empty {
+                continue;
+            }
+            let rotated_path = Self::rotated_log_path(log_path, &timestamp);
+            match Self::move_log_file(log_path, &rotated_path) {
+                Ok(_) => rotated.push(rotated_path),
+                Err(e) => {
+                    log::warn!("轮转 Nginx 日志 {} 失败: {}", log_path.display(), e);
+                    failed.push(log_path.to_string_lossy().to_string());
+                }
+            }
+        }
+
+        // 通知 nginx 重新打开日志文件（Unix 下 -s reopen 即发送 USR1 信号）
+        let reopened = if restart_required {
+            match self.start_service(service_data) {
+                Ok(result) => result.success,
+                Err(e) => {
+                    log::warn!("轮转日志后重新启动 Nginx 失败: {}", e);
+                    false
+                }
+            }
+        } else if running && !rotated.is_empty() {
+            match self.reopen_logs(service_data) {
+                Ok(_) => true,
+                Err(e) => {
+                    log::warn!("通知 Nginx 重新打开日志失败: {}", e);
+                    false
+                }
+            }
+        } else {
+            false
+        };
+
+        // nginx 重新打开日志后旧文件不再被写入，此时再压缩
+        if compress {
+            for rotated_path in rotated.iter_mut() {
+                match Self::gzip_file(rotated_path) {
+                    Ok(gz_path) => *rotated_path = gz_path,
+                    Err(e) => log::warn!("压缩 Nginx 日志 {} 失败: {}", rotated_path.display(), e),
+                }
+            }
+        }
+
+        let mut removed = Vec::new();
+        if let Some(days) = retain_days {
+            for log_path in &log_paths {
+                removed.extend(Self::prune_rotated_logs(log_path, days));
+            }
+        }
+
+        log::info!(
+            "Nginx 日志轮转完成: 轮转 {} 个，清理 {} 个",
+            rotated.len(),
+            removed.len()
+        );
+        let to_strings = |paths: &[PathBuf]| -> Vec<String> {
+            paths
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect()
+        };
+        Ok(ServiceDataResult {
+            success: failed.is_empty(),
+            message: if failed.is_empty() {
+                format!("已轮转 {} 个日志文件", rotated.len())
+            } else {
+                format!("部分日志轮转失败: {}", failed.join(", "))
+            },
+            data: Some(serde_json::json!({
+                "rotated": to_strings(&rotated),
+                "removed": to_strings(&removed),
+                "failed": failed,
+                "running": running,
+                "reopened": reopened,
+            })),
+        })
+    }
+
+    /// 统计最近 hours 小时的 access log：按状态码分组计数，并返回请求最多的路径。
+    /// 按 nginx 默认的 combined 日志格式解析，无法解析的行计入 unparsed。
+    pub fn get_access_log_stats(
+        &self,
+        service_data: &ServiceData,
+        hours: u32,
+    ) -> Result<ServiceDataResult> {
+        if hours == 0 {
+            return Err(anyhow!("统计时长必须大于 0 小时"));
+        }
+        let cutoff = Utc::now().timestamp() - i64::from(hours) * 3600;
+        let log_paths: Vec<PathBuf> = self
+            .resolve_directive_log_paths(service_data, "access_log", "access.log")
+            .into_iter()
+            .filter(|path| path.is_file())
+            .collect();
+
+        let mut total_requests: u32 = 0;
+        let mut unparsed: u32 = 0;
+        let mut status_counts: HashMap<String, u32> = ["2xx", "3xx", "4xx", "5xx"]
+            .iter()
+            .map(|group| (group.to_string(), 0))
+            .collect();
+        let mut path_counts: HashMap<String, u32> = HashMap::new();
+        for log_path in &log_paths {
+            let reader = BufReader::new(File::open(log_path)?);
+            // 日志中可能混有非 UTF-8 字节（如恶意请求），按字节切分后有损解码
+            for line in reader.split(b'\n') {
+                let line = line?;
+                let line = String::from_utf8_lossy(&line);
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let Some((timestamp, status, path)) = Self::parse_access_log_line(&line) else {
+                    unparsed += 1;
+                    continue;
+                };
+                if timestamp < cutoff {
+                    continue;
+                }
+                total_requests += 1;
+                *status_counts
+                    .entry(format!("{}xx", status / 100))
+                    .or_insert(0) += 1;
+                *path_counts.entry(path).or_insert(0) += 1;
+            }
+        }
+
+        let mut top_paths: Vec<NginxPathCount> = path_counts
+            .into_iter()
+            .map(|(path, count)| NginxPathCount { path, count })
+            .collect();
+        top_paths.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.path.cmp(&b.path)));
+        top_paths.truncate(ACCESS_LOG_TOP_PATHS);
+
+        let log_paths: Vec<String> = log_paths
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("最近 {} 小时共 {} 个请求", hours, total_requests),
+            data: Some(serde_json::json!({
+                "totalRequests": total_requests,
+                "statusCounts": status_counts,
+                "topPaths": top_paths,
+                "unparsed": unparsed,
+                "logPaths": log_paths,
+            })),
+        })
+    }
+
+    /// 读取 stub_status 获取实时连接数，配置中没有 stub_status 时先在第一个 server 块中
+    /// 添加 /_envis_status 并重载
+    pub async fn get_active_connections(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let health = self.get_service_health(service_data)?;
+        if !health.process {
+            return Ok(ServiceDataResult {
+                success: false,
+                message: "Nginx 未在运行".to_string(),
+                data: None,
+            });
+        }
+
+        let conf_path = self.resolve_conf_path(service_data);
+        let conf_content = std::fs::read_to_string(&conf_path)?;
+        let port = Self::parse_listen_port(&conf_content).unwrap_or(80);
+        let added = !conf_content.contains("stub_status");
+        if added {
+            let before = self.validate_nginx_config(service_data)?;
+            if !before.success {
+                log::warn!("{}", before.message);
+                return Ok(before);
+            }
+
+            let updated_conf = Self::with_status_location(&conf_content)?;
+            Self::write_conf_atomically(&conf_path, &updated_conf)?;
+            let mut after = self.validate_nginx_config(service_data)?;
+            if !after.success {
+                Self::write_conf_atomically(&conf_path, &conf_content)?;
+                after.message = format!("添加 stub_status 失败: {}，已恢复原配置", after.message);
+                log::warn!("{}", after.message);
+                return Ok(after);
+            }
+            self.restart_service(service_data)?;
+            log::info!("已在 {} 中添加 {}", conf_path.display(), STATUS_LOCATION);
+        }
+
+        let url = format!("http://127.0.0.1:{}{}", port, STATUS_LOCATION);
+        let client = reqwest::Client::builder().timeout(STATUS_TIMEOUT).build()?;
+        let mut attempt = 0;
+        let body = loop {
+            attempt += 1;
+            let response = client
+                .get(&url)
+                .send()
+                .await
+                .map_err(|e| anyhow!("请求 {} 失败: {}", url, e))?;
+            let status = response.status();
+            if status.is_success() {
+                break response.text().await?;
+            }
+            // 重载是异步的，旧 worker 退出前请求可能仍返回 404
+            if !added || attempt >= STATUS_RETRY_TIMES {
+                return Err(anyhow!("请求 {} 失败: HTTP {}", url, status));
+            }
+            tokio::time::sleep(STATUS_RETRY_INTERVAL).await;
+        };
+
+        let connections = Self::parse_stub_status(&body)
+            .ok_or_else(|| anyhow!("无法解析 stub_status 输出: {}", body.trim()))?;
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("当前活动连接数 {}", connections.active),
+            data: Some(serde_json::json!({
+                "connections": connections,
+                "statusUrl": url,
+                "configAdded": added,
+            })),
+        })
+    }
+
+    /// 在第一个 server 块开头插入仅允许本机访问的 stub_status location
+    fn with_status_location(conf_content: &str) -> Result<String> {
+        let mut lines: Vec<String> = conf_content.lines().map(|line| line.to_string()).collect();
+        let server_index = lines
+            .iter()
+            .position(|line| {
+                let directive = line.split('#').next().unwrap_or("").trim();
+                directive
+                    .strip_prefix("server")
+                    .is_some_and(|rest| rest.trim() == "{")
+            })
+            .ok_or_else(|| anyhow!("nginx.conf 中未找到 server 块"))?;
+        let indent = lines
+            .get(server_index + 1)
+            .map(|line| &line[..line.len() - line.trim_start().len()])
+            .filter(|indent| !indent.is_empty())
+            .unwrap_or("    ")
+            .to_string();
+
+        let location = [
+            format!("location = {} {{", STATUS_LOCATION),
+            "    stub_status;".to_string(),
+            "    access_log off;".to_string(),
+            "    allow 127.0.0.1;".to_string(),
+            "    deny all;".to_string(),
+            "}".to_string(),
+        ];
+        for (offset, line) in location.iter().enumerate() {
+            lines.insert(server_index + 1 + offset, format!("{}{}", indent, line));
+        }
+
+        let mut content = lines.join("\n");
+        if conf_content.ends_with('\n') {
+            content.push('\n');
+        }
+        Ok(content)
+    }
+
+    /// 解析 stub_status 输出：
+    ///
+    /// ```text
+    /// Active connections: 2
+    /// server accepts handled requests
+    ///  10 10 12
+    /// Reading: 0 Writing: 1 Waiting: 1
+    /// ```
+    fn parse_stub_status(text: &str) -> Option<NginxActiveConnections> {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        let active = lines
+            .next()?
+            .strip_prefix("Active connections:")?
+            .trim()
+            .parse()
+            .ok()?;
+        lines
+            .next()?
+            .strip_prefix("server accepts handled requests")?;
+        let counters: Vec<u64> = lines
+            .next()?
+            .split_whitespace()
+            .map(|value| value.parse().ok())
+            .collect::<Option<_>>()?;
+        let [accepts, handled, requests] = counters[..] else {
+            return None;
+        };
+
+        // Reading: 0 Writing: 1 Waiting: 1
+        let states: HashMap<&str, u64> = lines
+            .next()?
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .chunks(2)
+            .filter_map(|pair| match pair {
+                [name, value] => Some((name.trim_end_matches(':'), value.parse().ok()?)),
+                _ => None,
+            })
+            .collect();
+
+        Some(NginxActiveConnections {
+            active,
+            accepts,
+            handled,
+            requests,
+            reading: *states.get("Reading")?,
+            writing: *states.get("Writing")?,
+            waiting: *states.get("Waiting")?,
+        })
+    }
+
+    /// 启动定时日志轮转线程：每天对设置了 NGINX_LOG_ROTATE_DAYS 的 Nginx 服务轮转一次，重复调用时忽略
+    pub fn start_log_rotation_scheduler(&self) {
+        if self.rotation_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let spawned = std::thread::Builder::new()
+            .name("nginx-log-rotation".to_string())
+            .spawn(|| loop {
+                if let Err(e) = NginxService::global().run_scheduled_rotation() {
+                    log::error!("定时轮转 Nginx 日志失败: {}", e);
+                }
+                std::thread::sleep(ROTATE_CHECK_INTERVAL);
+            });
+        if let Err(e) = spawned {
+            self.rotation_started.store(false, Ordering::SeqCst);
+            log::error!("启动 Nginx 日志轮转线程失败: {}", e);
+        }
+    }
+
+    /// 对当天尚未轮转过的服务执行一次轮转
+    fn run_scheduled_rotation(&self) -> Result<()> {
+        let today = Local::now().date_naive();
+        let environments = EnvironmentManager::global()
+            .lock()
+            .unwrap()
+            .get_all_environments()?;
+
+        for environment in environments {
+            let service_datas = EnvServDataManager::global()
+                .lock()
+                .unwrap()
+                .get_environment_all_service_datas(&environment.id)?;
+            for service_data in service_datas
+                .iter()
+                .filter(|sd| matches!(sd.service_type, ServiceType::Nginx))
+            {
+                let Some(days) = Self::rotate_days(service_data) else {
+                    continue;
+                };
+                if self.last_rotation.lock().unwrap().get(&service_data.id) == Some(&today) {
+                    continue;
+                }
+                match self.rotate_nginx_logs(service_data, true, Some(days)) {
+                    Ok(result) => log::info!("定时轮转 {}: {}", service_data.name, result.message),
+                    Err(e) => log::warn!("定时轮转 {} 失败: {}", service_data.name, e),
+                }
+                self.last_rotation
+                    .lock()
+                    .unwrap()
+                    .insert(service_data.id.clone(), today);
+            }
+        }
+        Ok(())
+    }
+
+    /// 读取 NGINX_LOG_ROTATE_DAYS，未设置或为 0 时不做定时轮转
+    fn rotate_days(service_data: &ServiceData) -> Option<u32> {
+        let value = service_data
+            .metadata
+            .as_ref()?
+            .get(NGINX_LOG_ROTATE_DAYS_KEY)?;
+        let days = match value {
+            serde_json::Value::Number(n) => n.as_u64().map(|n| n as u32),
+            serde_json::Value::String(s) => s.trim().parse::<u32>().ok(),
+            _ => None,
+        }?;
+        (days > 0).then_some(days)
+    }
+
+    /// 从配置文件中解析 access_log / error_log 路径，未配置时使用 nginx 默认的 logs 目录
+    fn resolve_log_paths(&self, service_data: &ServiceData) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        for (directive, default_name) in [("access_log", "access.log"), ("error_log", "error.log")]
+        {
+            paths.extend(self.resolve_directive_log_paths(service_data, directive, default_name));
+        }
+        paths.dedup();
+        paths
+    }
+
+    /// 解析单个日志指令配置的文件路径，未配置时使用 logs 目录下的 default_name
+    fn resolve_directive_log_paths(
+        &self,
+        service_data: &ServiceData,
+        directive: &str,
+        default_name: &str,
+    ) -> Vec<PathBuf> {
+        let install_path = self.get_install_path(&service_data.version);
+        let content =
+            std::fs::read_to_string(self.resolve_conf_path(service_data)).unwrap_or_default();
+
+        let mut paths: Vec<PathBuf> = content
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or("").trim())
+            .filter_map(|line| line.strip_prefix(directive))
+            .filter(|rest| rest.starts_with(char::is_whitespace))
+            .filter_map(Self::parse_log_directive_path)
+            .map(|path| {
+                if path.is_absolute() {
+                    path
+                } else {
+                    install_path.join(path)
+                }
+            })
+            .collect();
+        if paths.is_empty() {
+            paths.push(install_path.join("logs").join(default_name));
+        }
+        paths.dedup();
+        paths
+    }
+
+    /// 解析 combined 格式的一行日志，返回 (请求时间戳, 状态码, 不含查询参数的路径)：
+    /// `$remote_addr - $remote_user [$time_local] "$request" $status $body_bytes_sent "$http_referer" "$http_user_agent"`
+    fn parse_access_log_line(line: &str) -> Option<(i64, u16, String)> {
+        let (_, rest) = line.split_once('[')?;
+        let (time_local, rest) = rest.split_once(']')?;
+        let timestamp = DateTime::parse_from_str(time_local, "%d/%b/%Y:%H:%M:%S %z")
+            .ok()?
+            .timestamp();
+        let (_, rest) = rest.split_once('"')?;
+        let (request, rest) = rest.split_once('"')?;
+        let status = rest
+            .split_whitespace()
+            .next()?
+            .parse::<u16>()
+            .ok()
+            .filter(|status| (100..600).contains(status))?;
+        // 非法请求（如 "-" 或 TLS 握手数据）没有路径，统一记为 "-"
+        let path = request
+            .split_whitespace()
+            .nth(1)
+            .and_then(|target| target.split('?').next())
+            .unwrap_or("-");
+        Some((timestamp, status, path.to_string()))
+    }
+
+    /// 解析日志指令的路径参数，off / syslog / stderr 等非文件目标返回 None
+    fn parse_log_directive_path(args: &str) -> Option<PathBuf> {
+        let args = args.trim().trim_end_matches(';').trim();
+        let path = if let Some(rest) = args.strip_prefix('"') {
+            rest.split('"').next()?
+        } else if let Some(rest) = args.strip_prefix('\'') {
+            rest.split('\'').next()?
+        } else {
+            args.split_whitespace().next()?
+        };
+        if path.is_empty() || path == "off" || path == "stderr" || path.starts_with("syslog:") {
+            return None;
+        }
+        Some(PathBuf::from(path))
+    }
+
+    fn rotated_log_path(log_path: &Path, timestamp: &str) -> PathBuf {
+        let file_name = log_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        log_path.with_file_name(format!("{}.{}", file_name, timestamp))
+    }
+
+    /// 重命名日志文件；重命名失败（如文件被占用）时复制后清空原文件
+    fn move_log_file(from: &Path, to: &Path) -> Result<()> {
+        if std::fs::rename(from, to).is_ok() {
+            return Ok(());
+        }
+        std::fs::copy(from, to)?;
+        File::create(from)?;
+        Ok(())
+    }
+
+    fn reopen_logs(&self, service_data: &ServiceData) -> Result<()> {
+        let install_path = self.get_install_path(&service_data.version);
+        let nginx_bin = self.resolve_nginx_binary(&install_path);
+        let conf_path = self.resolve_conf_path(service_data);
+        let output = self
+            .create_runtime_command(&nginx_bin, &install_path, &conf_path)
+            .arg("-s")
+            .arg("reopen")
+            .output()
+            .map_err(|e| anyhow!("执行 nginx -s reopen 失败: {}", e))?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "{}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(())
+    }
+
+    fn gzip_file(path: &Path) -> Result<PathBuf> {
+        let gz_path = PathBuf::from(format!("{}.gz", path.to_string_lossy()));
+        let mut input = File::open(path)?;
+        let mut encoder = GzEncoder::new(File::create(&gz_path)?, Compression::default());
+        copy(&mut input, &mut encoder)?;
+        encoder.finish()?;
+        drop(input);
+        std::fs::remove_file(path)?;
+        Ok(gz_path)
+    }
+
+    /// 删除日志目录中超过保留天数的轮转文件（按文件名中的时间戳判断）
+    fn prune_rotated_logs(log_path: &Path, retain_days: u32) -> Vec<PathBuf> {
+        let (Some(dir), Some(file_name)) = (log_path.parent(), log_path.file_name()) else {
+            return Vec::new();
+        };
+        let prefix = format!("{}.", file_name.to_string_lossy());
+        let cutoff = Local::now().naive_local() - chrono::Duration::days(retain_days as i64);
+
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut removed = Vec::new();
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some(rest) = name.strip_prefix(&prefix) else {
+                continue;
+            };
+            let timestamp = rest.trim_end_matches(".gz");
+            let Ok(rotated_at) = NaiveDateTime::parse_from_str(timestamp, ROTATE_TIMESTAMP_FORMAT)
+            else {
+                continue;
+            };
+            if rotated_at < cutoff {
+                match std::fs::remove_file(entry.path()) {
+                    Ok(_) => removed.push(entry.path()),
+                    Err(e) => log::warn!("删除过期日志 {} 失败: {}", entry.path().display(), e),
+                }
+            }
+        }
+        removed
+    }
+
+    fn format_path_for_nginx_conf<P: AsRef<Path>>(path: P) -> String {
+        // Nginx 配置文件中应统一使用 / 作为路径分隔符，Windows 也能识别。
+        path.as_ref().to_string_lossy().replace('\\', "/")
+    }
+
+    /// 修复未加引号的 error_log 路径
+    fn quote_error_log_path_in_conf(&self, conf_path: &PathBuf) -> Result<()> {
+        let content = std::fs::read_to_string(conf_path)?;
+        let mut updated = false;
+        let levels = ["debug", "info", "notice", "warn", "error", "crit", "alert", "emerg"];
+
+        let modified = content
+            .lines()
+            .map(|line| {
+                let trimmed = line.trim_start();
+                if !trimmed.starts_with("error_log") {
+                    return line.to_string();
+                }
+
+                let directive = trimmed.splitn(2, '#').next().unwrap_or(trimmed).trim_end();
+                if !directive.ends_with(';') {
+                    return line.to_string();
+                }
+
+                let directive = directive[..directive.len() - 1].trim();
+                let tokens: Vec<&str> = directive.split_whitespace().collect();
+                if tokens.len() <= 2 {
+                    return line.to_string();
+                }
+
+                let (path_parts, level_suffix) = if levels.contains(&tokens.last().unwrap()) && tokens.len() > 2 {
+                    (tokens[1..tokens.len() - 1].to_vec(), Some(tokens.last().unwrap()))
+                } else {
+                    (tokens[1..].to_vec(), None)
+                };
+
+                let path = path_parts.join(" ");
+                let normalized_path = Self::format_path_for_nginx_conf(PathBuf::from(&path));
+                let requires_quote = trimmed.contains('"') || trimmed.contains('\'');
+                let indent = &line[..line.len() - trimmed.len()];
+                let level_extension = level_suffix.map_or(String::new(), |lvl| format!(" {}", lvl));
+
+                if !requires_quote || path.contains('\\') {
+                    updated = true;
+                    return format!("{indent}error_log \"{normalized_path}\"{level_extension};");
+                }
+
+                line.to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if updated {
+            std::fs::write(conf_path, modified)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ServiceLifecycle for NginxService {
+    fn is_installed(&self, version: &str) -> bool {
+        NginxService::is_installed(self, version)
+    }
+
+    fn available_versions(&self) -> Vec<String> {
+        self.get_available_versions()
+            .into_iter()
+            .map(|v| v.version)
+            .collect()
+    }
+
+    fn download_and_install<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<DownloadResult>> {
+        Box::pin(NginxService::download_and_install(self, version))
+    }
+
+    fn cancel_download(&self, version: &str) -> Result<()> {
+        NginxService::cancel_download(self, version)
+    }
+
+    fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        NginxService::get_download_progress(self, version)
+    }
+
+    fn archive_dir(&self, version: &str) -> Option<PathBuf> {
+        Some(self.get_install_path(version))
+    }
+
+    fn extract_and_install<'a>(
+        &'a self,
+        task: &'a DownloadTask,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(NginxService::extract_and_install(self, task, version))
+    }
+
+    fn start_service(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        NginxService::start_service(self, service_data)
+    }
+
+    fn stop_service(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        NginxService::stop_service(self, service_data)?;
+        Ok(ServiceDataResult {
+            success: true,
+            message: "停止 Nginx 成功".to_string(),
+            data: None,
+        })
+    }
+
+    fn restart_service(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        NginxService::restart_service(self, service_data)?;
+        Ok(ServiceDataResult {
+            success: true,
+            message: "重启 Nginx 成功".to_string(),
+            data: None,
+        })
+    }
+
+    fn get_service_status(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let status = NginxService::get_service_status(self, service_data)?;
+        Ok(status_result(status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(path: &Path) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, b"").unwrap();
+    }
+
+    #[test]
+    fn test_locate_binary_windows_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        let install_path = dir.path();
+
+        // 未安装时返回根目录 nginx.exe
+        assert_eq!(
+            NginxService::locate_binary(install_path, true),
+            install_path.join("nginx.exe")
+        );
+
+        // 旧版本的 sbin/nginx.exe 仍可找到
+        touch(&install_path.join("sbin").join("nginx.exe"));
+        assert_eq!(
+            NginxService::locate_binary(install_path, true),
+            install_path.join("sbin").join("nginx.exe")
+        );
+
+        // 根目录 nginx.exe 优先
+        touch(&install_path.join("nginx.exe"));
+        assert_eq!(
+            NginxService::locate_binary(install_path, true),
+            install_path.join("nginx.exe")
+        );
+    }
+
+    #[test]
+    fn test_locate_binary_unix_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        let install_path = dir.path();
+
+        assert_eq!(
+            NginxService::locate_binary(install_path, false),
+            install_path.join("sbin").join("nginx")
+        );
+
+        touch(&install_path.join("nginx"));
+        assert_eq!(
+            NginxService::locate_binary(install_path, false),
+            install_path.join("nginx")
+        );
+
+        touch(&install_path.join("sbin").join("nginx"));
+        assert_eq!(
+            NginxService::locate_binary(install_path, false),
+            install_path.join("sbin").join("nginx")
+        );
+    }
+
+    #[test]
+    fn test_promote_extracted_contents_flattens_version_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let extracted = dir.path().join("extract");
+        let target = dir.path().join("1.26.3");
+        std::fs::create_dir_all(&target).unwrap();
+
+        let binary_name = if cfg!(target_os = "windows") {
+            "nginx.exe"
+        } else {
+            "sbin/nginx"
+        };
+        let top = extracted.join("nginx-1.26.3");
+        touch(&top.join(binary_name));
+        touch(&top.join("conf").join("nginx.conf"));
+
+        NginxService::new()
+            .promote_extracted_contents(&extracted, &target)
+            .unwrap();
+
+        assert!(target.join(binary_name).exists());
+        assert!(target.join("conf").join("nginx.conf").exists());
+        assert!(!target.join("nginx-1.26.3").exists());
+    }
+
+    #[test]
+    fn test_parse_stub_status() {
+        let output = "Active connections: 2 \nserver accepts handled requests\n 10 10 12 \nReading: 0 Writing: 1 Waiting: 1 \n";
+        assert_eq!(
+            NginxService::parse_stub_status(output),
+            Some(NginxActiveConnections {
+                active: 2,
+                accepts: 10,
+                handled: 10,
+                requests: 12,
+                reading: 0,
+                writing: 1,
+                waiting: 1,
+            })
+        );
+
+        assert_eq!(NginxService::parse_stub_status("<html>404</html>"), None);
+    }
+
+    #[test]
+    fn test_with_status_location() {
+        let conf = "http {\n    upstream api {\n        server 127.0.0.1:3000;\n    }\n    server {\n        listen 8080;\n    }\n}\n";
+        let updated = NginxService::with_status_location(conf).unwrap();
+        assert_eq!(
+            updated,
+            "http {\n    upstream api {\n        server 127.0.0.1:3000;\n    }\n    server {\n        location = /_envis_status {\n            stub_status;\n            access_log off;\n            allow 127.0.0.1;\n            deny all;\n        }\n        listen 8080;\n    }\n}\n"
+        );
+
+        assert!(NginxService::with_status_location("events {}\n").is_err());
+    }
+
+    #[test]
+    fn test_runtime_prefix() {
+        let install_path = Path::new("/services/nginx/1.26.3");
+        let env_conf = Path::new("/envs/env-1/nginx/1.26.3/nginx.conf");
+        assert_eq!(
+            NginxService::runtime_prefix(install_path, env_conf),
+            PathBuf::from("/envs/env-1/nginx/1.26.3")
+        );
+
+        let install_conf = install_path.join("conf").join("nginx.conf");
+        assert_eq!(
+            NginxService::runtime_prefix(install_path, &install_conf),
+            install_path.to_path_buf()
+        );
     }
 }
@@ -1,3 +1,5 @@
 pub mod nodejs;
 
-pub use nodejs::{GlobalPackage, NodejsService, NodejsVersion};
+pub use nodejs::{
+    GlobalPackage, NodeProcess, NodeProcessEnvironment, NodejsService, NodejsVersion,
+};
@@ -1,12 +1,21 @@
 use crate::manager::app_config_manager::AppConfigManager;
+use crate::manager::env_serv_data_manager::{EnvServDataManager, ServiceDataResult};
+use crate::manager::environment_manager::EnvironmentManager;
 use crate::manager::services::traits::ServiceLifecycle;
-use crate::manager::services::{DownloadManager, DownloadResult, DownloadTask};
+use crate::manager::services::{
+    install_callback, remove_partial_install, DownloadManager, DownloadResult, DownloadTask,
+};
 use crate::manager::shell_manamger::ShellManager;
-use crate::types::ServiceData;
-use anyhow::{anyhow, Result};
+use crate::manager::system_info_manager::SystemInfoManager;
+use crate::types::{ServiceData, ServiceType};
+use anyhow::{anyhow, Context, Result};
+use futures_util::future::BoxFuture;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use std::sync::{Arc, OnceLock};
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex, OnceLock};
 
 /// Node.js 版本信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,11 +32,39 @@ pub struct GlobalPackage {
     pub version: String,
 }
 
+/// 正在运行的 Node.js 进程
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeProcess {
+    pub pid: u32,
+    pub working_dir: String,
+    /// 命令行参数（不含 node 本身）
+    pub args: String,
+    pub memory_kb: u64,
+    pub exe: String,
+    /// 由 Envis 安装的 Node.js 启动时为对应版本
+    pub version: Option<String>,
+    /// 使用该版本的环境
+    pub environments: Vec<NodeProcessEnvironment>,
+}
+
+/// Node.js 进程所属的环境
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeProcessEnvironment {
+    pub environment_id: String,
+    pub environment_name: String,
+    pub service_data_id: String,
+}
+
 /// 全局 Node.js 服务管理器单例
 static GLOBAL_NODEJS_SERVICE: OnceLock<Arc<NodejsService>> = OnceLock::new();
 
 /// Node.js 服务管理器
-pub struct NodejsService {}
+pub struct NodejsService {
+    /// 正在运行的 npm 脚本进程 PID
+    npm_scripts: Mutex<HashSet<u32>>,
+}
 
 impl NodejsService {
     /// 获取全局 Node.js 服务管理器单例
@@ -39,7 +76,9 @@ impl NodejsService {
 
     /// 创建新的 Node.js 服务管理器
     pub fn new() -> Self {
-        Self {}
+        Self {
+            npm_scripts: Mutex::new(HashSet::new()),
+        }
     }
 
     /// 获取可用的 Node.js 版本列表
@@ -274,62 +313,13 @@ impl NodejsService {
 
         // 创建安装回调
         let version_for_callback = version.to_string();
-
-        let success_callback = Arc::new(move |task: &DownloadTask| {
-            log::info!(
-                "Node.js {} 下载完成: {} - {}",
-                version_for_callback,
-                task.id,
-                task.filename
-            );
-
-            // 异步执行安装步骤 闭包和异步块都需要拥有数据的所有权，在多个地方被使用，所以需要克隆来满足所有权要求
-            let task_for_spawn = task.clone();
-            let version_for_spawn = version_for_callback.clone();
-            let service_for_spawn = NodejsService::global();
-
-            tokio::spawn(async move {
-                // 更新状态为 Installing
-                let download_manager = DownloadManager::global();
-                if let Err(e) = download_manager.update_task_status(
-                    &task_for_spawn.id,
-                    crate::manager::services::DownloadStatus::Installing,
-                    None,
-                ) {
-                    log::error!("更新任务状态失败: {}", e);
-                } else {
-                    log::info!("Node.js {} 开始安装", version_for_spawn);
-                }
-
-                match service_for_spawn
-                    .extract_and_install(&task_for_spawn, &version_for_spawn)
+        let success_callback = install_callback(format!("Node.js {}", version), move |task| {
+            let version = version_for_callback.clone();
+            async move {
+                NodejsService::global()
+                    .extract_and_install(&task, &version)
                     .await
-                {
-                    Ok(_) => {
-                        // 更新状态为 Installed
-                        if let Err(e) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            crate::manager::services::DownloadStatus::Installed,
-                            None,
-                        ) {
-                            log::error!("更新任务状态失败: {}", e);
-                        } else {
-                            log::info!("Node.js {} 安装成功", version_for_spawn);
-                        }
-                    }
-                    Err(e) => {
-                        // 安装失败，更新状态为 Failed
-                        if let Err(update_err) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            crate::manager::services::DownloadStatus::Failed,
-                            Some(format!("安装失败: {}", e)),
-                        ) {
-                            log::error!("更新任务状态失败: {}", update_err);
-                        }
-                        log::error!("Node.js {} 安装失败: {}", version_for_spawn, e);
-                    }
-                }
-            });
+            }
         });
 
         // 开始下载
@@ -360,6 +350,16 @@ impl NodejsService {
 
     /// 解压和安装 Node.js
     pub async fn extract_and_install(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let result = self.install_from_archive(task, version).await;
+        if result.is_err() {
+            // 解压安装失败时清理残缺的安装目录
+            remove_partial_install(&self.get_install_path(version));
+        }
+        result
+    }
+
+    /// 将下载的压缩包解压到安装目录
+    async fn install_from_archive(&self, task: &DownloadTask, version: &str) -> Result<()> {
         let archive_path = &task.target_path;
         let install_dir = self.get_install_path(version);
 
@@ -561,7 +561,8 @@ impl NodejsService {
             install_path.join("bin").to_string_lossy().to_string()
         };
 
-        shell_manager.add_path(&node_bin_path)?;
+        let mut update = shell_manager.begin_update();
+        update.add_path(&node_bin_path);
 
         // 添加 ${NPM_CONFIG_PREFIX}/bin 到 PATH (如果 NPM_CONFIG_PREFIX 不是空值)
         let npm_config_prefix = service_data
@@ -581,7 +582,7 @@ impl NodejsService {
 
                 let npm_bin_path = PathBuf::from(prefix).join("bin");
                 if npm_bin_path.exists() {
-                    shell_manager.add_path(&npm_bin_path.to_string_lossy().to_string())?;
+                    update.add_path(&npm_bin_path.to_string_lossy().to_string());
                 }
             }
         }
@@ -589,7 +590,7 @@ impl NodejsService {
         // 添加 NPM_CONFIG_PREFIX 环境变量 (如果有)
         if let Some(prefix) = npm_config_prefix {
             if !prefix.is_empty() {
-                shell_manager.add_export("NPM_CONFIG_PREFIX", prefix)?;
+                update.add_export("NPM_CONFIG_PREFIX", prefix);
             }
         }
 
@@ -600,7 +601,19 @@ impl NodejsService {
             .and_then(|m| m.get("NPM_CONFIG_REGISTRY"))
             .and_then(|v| v.as_str())
         {
-            shell_manager.add_export("NPM_CONFIG_REGISTRY", registry)?;
+            update.add_export("NPM_CONFIG_REGISTRY", registry);
+        }
+
+        // 添加 NODE_OPTIONS 环境变量 (如果有)
+        if let Some(node_options) = service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("NODE_OPTIONS"))
+            .and_then(|v| v.as_str())
+        {
+            if !node_options.is_empty() {
+                update.add_export("NODE_OPTIONS", node_options);
+            }
         }
 
         // 添加 PNPM_HOME 环境变量和 PATH (如果有)
@@ -620,13 +633,14 @@ impl NodejsService {
                 }
 
                 // 设置环境变量
-                shell_manager.add_export("PNPM_HOME", pnpm_home)?;
+                update.add_export("PNPM_HOME", pnpm_home);
                 
                 // 添加到 PATH
-                shell_manager.add_path(pnpm_home)?;
+                update.add_path(pnpm_home);
             }
         }
 
+        update.commit()?;
         Ok(())
     }
 
@@ -644,7 +658,8 @@ impl NodejsService {
             install_path.join("bin").to_string_lossy().to_string()
         };
 
-        shell_manager.delete_path(&node_bin_path)?;
+        let mut update = shell_manager.begin_update();
+        update.delete_path(&node_bin_path);
 
         // 从 PATH 中移除 ${NPM_CONFIG_PREFIX}/bin (如果 NPM_CONFIG_PREFIX 不是空值)
         let npm_config_prefix = service_data
@@ -656,15 +671,17 @@ impl NodejsService {
             if !prefix.is_empty() {
                 let npm_bin_path = PathBuf::from(prefix).join("bin");
                 if npm_bin_path.exists() {
-                    shell_manager.delete_path(&npm_bin_path.to_string_lossy().to_string())?;
+                    update.delete_path(&npm_bin_path.to_string_lossy().to_string());
                 }
             }
         }
 
         // 移除 NPM_CONFIG_PREFIX 环境变量
-        shell_manager.delete_export("NPM_CONFIG_PREFIX")?;
+        update.delete_export("NPM_CONFIG_PREFIX");
         // 移除 NPM_CONFIG_REGISTRY 环境变量
-        shell_manager.delete_export("NPM_CONFIG_REGISTRY")?;
+        update.delete_export("NPM_CONFIG_REGISTRY");
+        // 移除 NODE_OPTIONS 环境变量
+        update.delete_export("NODE_OPTIONS");
 
         // 从 PATH 中移除 PNPM_HOME (如果 PNPM_HOME 不是空值)
         if let Some(pnpm_home) = service_data
@@ -674,12 +691,12 @@ impl NodejsService {
             .and_then(|v| v.as_str())
         {
             if !pnpm_home.is_empty() {
-                shell_manager.delete_path(pnpm_home)?;
+                update.delete_path(pnpm_home);
             }
         }
 
         // 移除 PNPM_HOME 环境变量
-        shell_manager.delete_export("PNPM_HOME")?;
+        update.delete_export("PNPM_HOME").commit()?;
 
         Ok(())
     }
@@ -750,6 +767,70 @@ impl NodejsService {
         Ok(())
     }
 
+    /// 设置 NODE_OPTIONS，多个选项以空格拼接
+    pub fn set_node_options(
+        &self,
+        environment_id: &str,
+        service_data: &mut ServiceData,
+        options: Vec<String>,
+    ) -> Result<()> {
+        let node_options = options
+            .iter()
+            .map(|o| o.trim())
+            .filter(|o| !o.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if node_options.is_empty() {
+            return self.clear_node_options(environment_id, service_data);
+        }
+
+        EnvServDataManager::global().lock().unwrap().set_metadata(
+            environment_id,
+            service_data,
+            "NODE_OPTIONS",
+            serde_json::Value::String(node_options.clone()),
+        )?;
+
+        let shell_manager = ShellManager::global();
+        let shell_manager = shell_manager.lock().unwrap();
+        shell_manager.add_export("NODE_OPTIONS", &node_options)?;
+        Ok(())
+    }
+
+    /// 清除 NODE_OPTIONS
+    pub fn clear_node_options(
+        &self,
+        environment_id: &str,
+        service_data: &mut ServiceData,
+    ) -> Result<()> {
+        if let Some(metadata) = service_data.metadata.as_mut() {
+            if metadata.remove("NODE_OPTIONS").is_some() && !environment_id.is_empty() {
+                EnvServDataManager::global()
+                    .lock()
+                    .unwrap()
+                    .save_service_data(environment_id, service_data)?;
+            }
+        }
+
+        let shell_manager = ShellManager::global();
+        let shell_manager = shell_manager.lock().unwrap();
+        shell_manager.delete_export("NODE_OPTIONS")?;
+        Ok(())
+    }
+
+    /// 从环境变量块中读取当前生效的 NODE_OPTIONS
+    pub fn get_current_node_options(&self) -> Option<String> {
+        let shell_manager = ShellManager::global();
+        let shell_manager = shell_manager.lock().unwrap();
+        match shell_manager.export_current_env_vars() {
+            Ok(mut vars) => vars.remove("NODE_OPTIONS"),
+            Err(e) => {
+                log::warn!("读取 NODE_OPTIONS 失败: {}", e);
+                None
+            }
+        }
+    }
+
     /// 获取全局安装的 npm 包列表
     pub fn get_global_packages(&self, service_data: &ServiceData) -> Result<Vec<GlobalPackage>> {
         use std::collections::HashSet;
@@ -852,6 +933,333 @@ impl NodejsService {
 
         Ok(())
     }
+
+    /// 读取 `npm config get <key>`，未设置时返回 None
+    pub fn get_npm_config(&self, service_data: &ServiceData, key: &str) -> Result<Option<String>> {
+        let key = Self::validate_npm_config_key(key)?;
+        let stdout = self.run_npm_config(service_data, &["get", key])?;
+        let value = stdout.trim();
+        if value.is_empty() || value == "undefined" {
+            Ok(None)
+        } else {
+            Ok(Some(value.to_string()))
+        }
+    }
+
+    /// 执行 `npm config set <key> <value> -g`，写入该版本 Node.js 的全局 npmrc
+    pub fn set_npm_config_key(
+        &self,
+        service_data: &ServiceData,
+        key: &str,
+        value: &str,
+    ) -> Result<()> {
+        let key = Self::validate_npm_config_key(key)?;
+        self.run_npm_config(service_data, &["set", key, value, "-g"])?;
+        log::info!("已设置 npm 配置 {} = {}", key, value);
+        Ok(())
+    }
+
+    /// 执行 `npm config list --json`，返回全部生效配置
+    pub fn list_npm_config(
+        &self,
+        service_data: &ServiceData,
+    ) -> Result<serde_json::Map<String, serde_json::Value>> {
+        let stdout = self.run_npm_config(service_data, &["list", "--json"])?;
+        serde_json::from_str(&stdout).context("解析 npm config list 输出失败")
+    }
+
+    /// 配置项名不能为空，也不能以 - 开头被 npm 当作参数
+    fn validate_npm_config_key(key: &str) -> Result<&str> {
+        let key = key.trim();
+        if key.is_empty() || key.starts_with('-') || key.contains(char::is_whitespace) {
+            return Err(anyhow!("无效的 npm 配置项: {}", key));
+        }
+        Ok(key)
+    }
+
+    /// 使用服务对应版本的 npm 执行 `npm config ...`，返回 stdout
+    ///
+    /// metadata 中的 NPM_CONFIG_PREFIX / NPM_CONFIG_REGISTRY 同样传给 npm，与终端中看到的配置一致
+    fn run_npm_config(&self, service_data: &ServiceData, args: &[&str]) -> Result<String> {
+        if !self.is_installed(&service_data.version) {
+            return Err(anyhow!("Node.js {} 未安装", service_data.version));
+        }
+
+        let npm_bin = self.get_npm_bin(&service_data.version);
+        let mut command = crate::utils::create_command(&npm_bin);
+        command
+            .arg("config")
+            .args(args)
+            .env("PATH", Self::node_path_env(&npm_bin)?)
+            .stdin(Stdio::null());
+        for key in ["NPM_CONFIG_PREFIX", "NPM_CONFIG_REGISTRY"] {
+            if let Some(value) = service_data
+                .metadata
+                .as_ref()
+                .and_then(|m| m.get(key))
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.is_empty())
+            {
+                command.env(key, value);
+            }
+        }
+
+        let output = command.output().context("执行 npm config 失败")?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "npm config {} 失败 (exit code: {}): {}",
+                args.join(" "),
+                output.status.code().unwrap_or(-1),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// 将 npm 所在版本的 node 目录放在 PATH 最前面，保证 npm 使用当前版本
+    fn node_path_env(npm_bin: &Path) -> Result<std::ffi::OsString> {
+        let node_bin_dir = npm_bin.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(match std::env::var_os("PATH") {
+            Some(path) => {
+                let mut paths = vec![node_bin_dir];
+                paths.extend(std::env::split_paths(&path));
+                std::env::join_paths(paths)?
+            }
+            None => node_bin_dir.into_os_string(),
+        })
+    }
+
+    /// 获取指定版本的 npm 可执行文件路径
+    fn get_npm_bin(&self, version: &str) -> PathBuf {
+        let install_path = self.get_install_path(version);
+        if cfg!(target_os = "windows") {
+            install_path.join("npm.cmd")
+        } else {
+            install_path.join("bin").join("npm")
+        }
+    }
+
+    /// 读取 package.json 并确认脚本存在
+    fn ensure_npm_script_exists(working_dir: &Path, script_name: &str) -> Result<()> {
+        let package_json_path = working_dir.join("package.json");
+        let content = std::fs::read_to_string(&package_json_path)
+            .with_context(|| format!("读取 {} 失败", package_json_path.display()))?;
+        let package_json: serde_json::Value =
+            serde_json::from_str(&content).context("解析 package.json 失败")?;
+
+        let exists = package_json
+            .get("scripts")
+            .and_then(|s| s.get(script_name))
+            .is_some();
+        if !exists {
+            return Err(anyhow!("package.json 中不存在脚本: {}", script_name));
+        }
+        Ok(())
+    }
+
+    /// 在指定目录中执行 `npm run <script_name>`，不阻塞，返回进程 PID
+    ///
+    /// 每行 stdout/stderr 通过 `output_callback(pid, stream, line)` 回调，
+    /// 进程结束时以 stream = "exit"、line = 退出码 回调一次
+    pub fn run_npm_script(
+        &self,
+        environment_id: &str,
+        service_data_id: &str,
+        working_dir: &str,
+        script_name: &str,
+        output_callback: impl Fn(u32, &str, &str) + Send + Sync + 'static,
+    ) -> Result<u32> {
+        let service_data = EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .get_service_data(environment_id, service_data_id)?;
+        if service_data.service_type != ServiceType::Nodejs {
+            return Err(anyhow!("服务 {} 不是 Node.js 服务", service_data.name));
+        }
+        if !self.is_installed(&service_data.version) {
+            return Err(anyhow!("Node.js {} 未安装", service_data.version));
+        }
+
+        let script_name = script_name.trim();
+        if script_name.is_empty() || script_name.starts_with('-') {
+            return Err(anyhow!("无效的脚本名: {}", script_name));
+        }
+        let working_dir = PathBuf::from(working_dir);
+        if !working_dir.is_dir() {
+            return Err(anyhow!("工作目录不存在: {}", working_dir.display()));
+        }
+        Self::ensure_npm_script_exists(&working_dir, script_name)?;
+
+        let npm_bin = self.get_npm_bin(&service_data.version);
+        let path_env = Self::node_path_env(&npm_bin)?;
+
+        let mut command = crate::utils::create_command(&npm_bin);
+        command
+            .args(["run", script_name])
+            .current_dir(&working_dir)
+            .env("PATH", path_env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // 单独的进程组，停止时可以连同子进程一起结束
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+
+        let mut child = command.spawn().context("启动 npm 脚本失败")?;
+        let pid = child.id();
+        self.npm_scripts.lock().unwrap().insert(pid);
+        log::info!("npm 脚本 {} 已启动, pid: {}", script_name, pid);
+
+        let output_callback = Arc::new(output_callback);
+        let mut readers = Vec::new();
+        if let Some(stdout) = child.stdout.take() {
+            let callback = output_callback.clone();
+            readers.push(std::thread::spawn(move || {
+                for line in BufReader::new(stdout).lines().map_while(|l| l.ok()) {
+                    callback(pid, "stdout", &line);
+                }
+            }));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            let callback = output_callback.clone();
+            readers.push(std::thread::spawn(move || {
+                for line in BufReader::new(stderr).lines().map_while(|l| l.ok()) {
+                    callback(pid, "stderr", &line);
+                }
+            }));
+        }
+
+        std::thread::spawn(move || {
+            for reader in readers {
+                let _ = reader.join();
+            }
+            let exit_code = match child.wait() {
+                Ok(status) => status.code().unwrap_or(-1),
+                Err(e) => {
+                    log::error!("等待 npm 脚本进程失败: {}", e);
+                    -1
+                }
+            };
+            NodejsService::global().npm_scripts.lock().unwrap().remove(&pid);
+            output_callback(pid, "exit", &exit_code.to_string());
+        });
+
+        Ok(pid)
+    }
+
+    /// 停止由 run_npm_script 启动的进程
+    pub fn stop_npm_script(&self, pid: u32) -> Result<()> {
+        if !self.npm_scripts.lock().unwrap().contains(&pid) {
+            return Err(anyhow!("未找到正在运行的 npm 脚本: {}", pid));
+        }
+
+        let output = if cfg!(target_os = "windows") {
+            crate::utils::create_command("taskkill")
+                .args(["/PID", &pid.to_string(), "/T", "/F"])
+                .output()?
+        } else {
+            // 负 PID 表示整个进程组
+            crate::utils::create_command("kill")
+                .args(["-TERM", "--", &format!("-{}", pid)])
+                .output()?
+        };
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "停止 npm 脚本失败: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        log::info!("npm 脚本已停止, pid: {}", pid);
+        Ok(())
+    }
+
+    /// 获取所有正在运行的 Node.js 进程
+    ///
+    /// 可执行文件位于 services/nodejs/<version> 下的进程标记为对应版本，
+    /// 并列出服务数据使用该版本的环境
+    pub fn get_node_process_list(&self) -> Result<ServiceDataResult> {
+        let names: &[&str] = if cfg!(target_os = "windows") {
+            &["node.exe"]
+        } else {
+            &["node"]
+        };
+        let processes = SystemInfoManager::global().find_processes_by_names(names)?;
+
+        let nodejs_folder = {
+            let app_config_manager = AppConfigManager::global();
+            let app_config_manager = app_config_manager.lock().unwrap();
+            PathBuf::from(app_config_manager.get_services_folder()).join("nodejs")
+        };
+        let nodejs_folder = std::fs::canonicalize(&nodejs_folder).unwrap_or(nodejs_folder);
+        let version_environments = self.get_version_environments()?;
+
+        let processes: Vec<NodeProcess> = processes
+            .into_iter()
+            .map(|process| {
+                let exe = PathBuf::from(&process.exe);
+                let exe = std::fs::canonicalize(&exe).unwrap_or(exe);
+                let version = exe
+                    .strip_prefix(&nodejs_folder)
+                    .ok()
+                    .and_then(|rest| rest.components().next())
+                    .map(|c| c.as_os_str().to_string_lossy().to_string());
+                let environments = version
+                    .as_ref()
+                    .and_then(|v| version_environments.get(v))
+                    .cloned()
+                    .unwrap_or_default();
+                NodeProcess {
+                    pid: process.pid,
+                    working_dir: process.cwd,
+                    args: process.cmd.get(1..).unwrap_or_default().join(" "),
+                    memory_kb: process.memory_bytes / 1024,
+                    exe: process.exe,
+                    version,
+                    environments,
+                }
+            })
+            .collect();
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("找到 {} 个 Node.js 进程", processes.len()),
+            data: Some(serde_json::json!({ "processes": processes })),
+        })
+    }
+
+    /// 版本号 -> 使用该版本 Node.js 的环境
+    fn get_version_environments(&self) -> Result<HashMap<String, Vec<NodeProcessEnvironment>>> {
+        let environments = EnvironmentManager::global()
+            .lock()
+            .unwrap()
+            .get_all_environments()?;
+        let env_serv_data_manager = EnvServDataManager::global();
+        let env_serv_data_manager = env_serv_data_manager.lock().unwrap();
+
+        let mut version_environments: HashMap<String, Vec<NodeProcessEnvironment>> = HashMap::new();
+        for environment in environments {
+            for service_data in
+                env_serv_data_manager.get_environment_all_service_datas(&environment.id)?
+            {
+                if service_data.service_type != ServiceType::Nodejs {
+                    continue;
+                }
+                version_environments
+                    .entry(service_data.version)
+                    .or_default()
+                    .push(NodeProcessEnvironment {
+                        environment_id: environment.id.clone(),
+                        environment_name: environment.name.clone(),
+                        service_data_id: service_data.id,
+                    });
+            }
+        }
+        Ok(version_environments)
+    }
 }
 
 impl ServiceLifecycle for NodejsService {
@@ -872,4 +1280,42 @@ impl ServiceLifecycle for NodejsService {
     ) -> Result<()> {
         self.deactivate_service(service_data)
     }
+
+    fn is_installed(&self, version: &str) -> bool {
+        NodejsService::is_installed(self, version)
+    }
+
+    fn available_versions(&self) -> Vec<String> {
+        self.get_available_versions()
+            .into_iter()
+            .map(|v| v.version)
+            .collect()
+    }
+
+    fn download_and_install<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<DownloadResult>> {
+        Box::pin(NodejsService::download_and_install(self, version))
+    }
+
+    fn cancel_download(&self, version: &str) -> Result<()> {
+        NodejsService::cancel_download(self, version)
+    }
+
+    fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        NodejsService::get_download_progress(self, version)
+    }
+
+    fn archive_dir(&self, version: &str) -> Option<PathBuf> {
+        Some(self.get_install_path(version))
+    }
+
+    fn extract_and_install<'a>(
+        &'a self,
+        task: &'a DownloadTask,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(NodejsService::extract_and_install(self, task, version))
+    }
 }
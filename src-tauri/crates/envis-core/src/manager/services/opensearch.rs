@@ -0,0 +1,743 @@
+use crate::manager::app_config_manager::AppConfigManager;
+use crate::manager::builders::{metadata_number, metadata_port};
+use crate::manager::env_serv_data_manager::ServiceDataResult;
+use crate::manager::service_supervisor::ServiceSupervisor;
+use crate::manager::services::archive::extract_tar_strip_root;
+use crate::manager::services::health::{self, ServiceHealth};
+use crate::manager::services::launch::OutputCapture;
+use crate::manager::services::traits::ServiceLifecycle;
+use crate::manager::services::{
+    download_service_package, http_api, remove_partial_install, shutdown, DownloadManager,
+    DownloadResult, DownloadTask,
+};
+use crate::types::{ServiceData, ServiceType};
+use crate::utils::create_command;
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpensearchVersion {
+    pub version: String,
+    pub date: String,
+}
+
+/// OpenSearch 索引信息（来自 `_cat/indices`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpensearchIndex {
+    pub index: String,
+    pub health: String,
+    pub status: String,
+    pub docs_count: Option<u64>,
+    pub store_size_bytes: Option<u64>,
+}
+
+/// 默认 HTTP 端口
+pub const DEFAULT_OPENSEARCH_PORT: u16 = 9200;
+/// 默认 JVM 堆大小（MB）
+pub const DEFAULT_OPENSEARCH_HEAP_MB: u32 = 512;
+/// 仅监听本机
+const OPENSEARCH_HOST: &str = "127.0.0.1";
+/// 启动后等待 HTTP 端口就绪的最长时间
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+static GLOBAL_OPENSEARCH_SERVICE: OnceLock<Arc<OpensearchService>> = OnceLock::new();
+
+/// OpenSearch 服务：使用发行包内置的 JDK 运行单节点集群，默认关闭安全插件
+pub struct OpensearchService {}
+
+impl OpensearchService {
+    pub fn global() -> Arc<OpensearchService> {
+        GLOBAL_OPENSEARCH_SERVICE
+            .get_or_init(|| Arc::new(OpensearchService::new()))
+            .clone()
+    }
+
+    fn new() -> Self {
+        Self {}
+    }
+
+    pub fn get_available_versions(&self) -> Vec<OpensearchVersion> {
+        vec![
+            OpensearchVersion {
+                version: "3.0.0".to_string(),
+                date: "2025-05-06".to_string(),
+            },
+            OpensearchVersion {
+                version: "2.19.1".to_string(),
+                date: "2025-02-27".to_string(),
+            },
+            OpensearchVersion {
+                version: "2.18.0".to_string(),
+                date: "2024-11-05".to_string(),
+            },
+        ]
+    }
+
+    pub fn is_installed(&self, version: &str) -> bool {
+        self.get_opensearch_bin_path(version).exists()
+    }
+
+    fn get_install_path(&self, version: &str) -> PathBuf {
+        let services_folder = {
+            let app_config_manager = AppConfigManager::global();
+            let app_config_manager = app_config_manager.lock().unwrap();
+            PathBuf::from(app_config_manager.get_services_folder())
+        };
+        services_folder.join("opensearch").join(version)
+    }
+
+    fn get_service_data_folder(&self, environment_id: &str, version: &str) -> PathBuf {
+        let app_config_manager = AppConfigManager::global();
+        let app_config_manager = app_config_manager.lock().unwrap();
+        let envs_folder = app_config_manager.get_envs_folder();
+
+        PathBuf::from(envs_folder)
+            .join(environment_id)
+            .join("opensearch")
+            .join(version)
+    }
+
+    fn get_opensearch_bin_path(&self, version: &str) -> PathBuf {
+        let bin_dir = self.get_install_path(version).join("bin");
+        if cfg!(target_os = "windows") {
+            bin_dir.join("opensearch.bat")
+        } else {
+            bin_dir.join("opensearch")
+        }
+    }
+
+    /// 发行包内置的 JDK 目录，缺失时返回明确的错误
+    fn get_bundled_java_home(&self, version: &str) -> Result<PathBuf> {
+        let java_home = self.get_install_path(version).join("jdk");
+        let java_bin = if cfg!(target_os = "windows") {
+            java_home.join("bin").join("java.exe")
+        } else {
+            java_home.join("bin").join("java")
+        };
+        if !java_bin.exists() {
+            return Err(anyhow!(
+                "未找到 OpenSearch 内置 JDK: {}，请删除后重新安装 OpenSearch {}",
+                java_bin.to_string_lossy(),
+                version
+            ));
+        }
+        Ok(java_home)
+    }
+
+    /// 官方仅提供 Linux（x64/arm64）与 Windows（x64）发行包
+    fn map_platform_arch(&self) -> Result<(&'static str, &'static str)> {
+        let os = match std::env::consts::OS {
+            "linux" => "linux",
+            "windows" => "windows",
+            "macos" => return Err(anyhow!("OpenSearch 官方未提供 macOS 发行包")),
+            os => return Err(anyhow!("不支持的操作系统: {}", os)),
+        };
+        let arch = match (os, std::env::consts::ARCH) {
+            (_, "x86_64") => "x64",
+            ("linux", "aarch64") => "arm64",
+            (_, arch) => return Err(anyhow!("OpenSearch 不支持的架构: {}", arch)),
+        };
+        Ok((os, arch))
+    }
+
+    fn build_download_info(&self, version: &str) -> Result<(Vec<String>, String)> {
+        let (os, arch) = self.map_platform_arch()?;
+        let ext = if os == "windows" { "zip" } else { "tar.gz" };
+        let filename = format!("opensearch-{}-{}-{}.{}", version, os, arch, ext);
+        let url = format!(
+            "https://artifacts.opensearch.org/releases/bundle/opensearch/{}/{}",
+            version, filename
+        );
+
+        Ok((vec![url], filename))
+    }
+
+    pub async fn download_and_install(&self, version: &str) -> Result<DownloadResult> {
+        if self.is_installed(version) {
+            return Ok(DownloadResult::success(
+                format!("OpenSearch {} 已经安装", version),
+                None,
+            ));
+        }
+
+        let (urls, filename) = self.build_download_info(version)?;
+        download_service_package(
+            ServiceType::Opensearch,
+            version,
+            urls,
+            self.get_install_path(version),
+            filename,
+        )
+        .await
+    }
+
+    pub async fn extract_and_install(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let result = self.install_from_archive(task, version).await;
+        if result.is_err() {
+            // 解压安装失败时清理残缺的安装目录
+            remove_partial_install(&self.get_install_path(version));
+        }
+        result
+    }
+
+    /// 将下载的压缩包解压到安装目录（去掉顶层的 opensearch-<version> 目录）
+    async fn install_from_archive(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let archive_path = &task.target_path;
+        let install_dir = self.get_install_path(version);
+        fs::create_dir_all(&install_dir)?;
+
+        if task.filename.ends_with(".tar.gz") {
+            extract_tar_strip_root(archive_path, &install_dir)?;
+        } else if task.filename.ends_with(".zip") {
+            Self::extract_zip_strip_root(archive_path, &install_dir)?;
+        } else {
+            return Err(anyhow!("不支持的压缩格式: {}", task.filename));
+        }
+
+        if !self.get_opensearch_bin_path(version).exists() {
+            return Err(anyhow!("未找到 opensearch 启动脚本"));
+        }
+        self.get_bundled_java_home(version)?;
+
+        if archive_path.exists() {
+            fs::remove_file(archive_path)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn cancel_download(&self, version: &str) -> Result<()> {
+        let task_id = format!("opensearch-{}", version);
+        DownloadManager::global().cancel_download(&task_id)
+    }
+
+    pub fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        let task_id = format!("opensearch-{}", version);
+        DownloadManager::global().get_task_status(&task_id)
+    }
+
+    /// 状态中附带集群健康状态：green/yellow 视为正常响应，red 视为 Degraded
+    pub fn get_service_status(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let config = self.get_runtime_config(environment_id, service_data)?;
+        let (health, cluster_status) = self.check_health(&config);
+        let running = health.process || health.port_open;
+
+        let mut data = serde_json::json!({
+            "isRunning": running,
+            "port": config.port,
+            "heapMb": config.heap_mb,
+            "clusterStatus": cluster_status,
+            "pid": shutdown::read_running_pid(&config.pid_path),
+            "configPath": config.config_dir,
+            "dataPath": config.data_dir,
+            "logPath": config.logs_dir,
+        });
+        health.merge_into(&mut data);
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "获取 OpenSearch 状态成功".to_string(),
+            data: Some(data),
+        })
+    }
+
+    /// 生成环境配置后以前台方式启动：
+    /// `OPENSEARCH_PATH_CONF=<env>/config OPENSEARCH_JAVA_HOME=<install>/jdk bin/opensearch`
+    pub fn start_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        ServiceSupervisor::global().clear_intentional_stop(&service_data.id);
+        let version = &service_data.version;
+        let opensearch_bin = self.get_opensearch_bin_path(version);
+
+        if !opensearch_bin.exists() {
+            return Ok(ServiceDataResult {
+                success: false,
+                message: "opensearch 启动脚本不存在".to_string(),
+                data: None,
+            });
+        }
+
+        let java_home = match self.get_bundled_java_home(version) {
+            Ok(java_home) => java_home,
+            Err(e) => {
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: e.to_string(),
+                    data: None,
+                })
+            }
+        };
+
+        let config = self.get_runtime_config(environment_id, service_data)?;
+
+        if let Some(pid) = shutdown::read_running_pid(&config.pid_path) {
+            return Ok(ServiceDataResult {
+                success: true,
+                message: "OpenSearch 已在运行".to_string(),
+                data: Some(serde_json::json!({
+                    "port": config.port,
+                    "pid": pid,
+                    "alreadyRunning": true
+                })),
+            });
+        }
+
+        if health::check_tcp_port(OPENSEARCH_HOST, config.port).is_some() {
+            return Ok(ServiceDataResult {
+                success: false,
+                message: format!("端口 {} 已被占用", config.port),
+                data: None,
+            });
+        }
+
+        self.write_config_files(version, &config)?;
+        if let Some(parent) = config.pid_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let service_data_folder = self.get_service_data_folder(environment_id, version);
+        let capture = OutputCapture::new(&service_data_folder, "opensearch")?;
+        let mut command = create_command(&opensearch_bin);
+        command
+            .env("OPENSEARCH_PATH_CONF", &config.config_dir)
+            .env("OPENSEARCH_JAVA_HOME", &java_home)
+            .current_dir(self.get_install_path(version));
+        capture.attach(&mut command)?;
+
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: format!("启动失败: {}", e),
+                    data: None,
+                })
+            }
+        };
+        let pid = child.id();
+        fs::write(&config.pid_path, pid.to_string())?;
+
+        // JVM 启动与集群初始化较慢，等待 HTTP 端口就绪或进程退出
+        let deadline = Instant::now() + STARTUP_TIMEOUT;
+        while Instant::now() < deadline {
+            if health::check_tcp_port(OPENSEARCH_HOST, config.port).is_some() {
+                log::info!("OpenSearch 已启动，PID: {}", pid);
+                return Ok(ServiceDataResult {
+                    success: true,
+                    message: "OpenSearch 启动成功".to_string(),
+                    data: Some(serde_json::json!({
+                        "port": config.port,
+                        "pid": pid,
+                    })),
+                });
+            }
+            if let Ok(Some(status)) = child.try_wait() {
+                let _ = fs::remove_file(&config.pid_path);
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: format!("OpenSearch 启动失败: {}", capture.failure_detail(&status)),
+                    data: Some(serde_json::json!({
+                        "logPath": capture.path(),
+                    })),
+                });
+            }
+            std::thread::sleep(Duration::from_millis(500));
+        }
+
+        Ok(ServiceDataResult {
+            success: false,
+            message: format!(
+                "OpenSearch 进程已启动，但端口 {} 尚未就绪，请检查日志: {}",
+                config.port,
+                capture.path().to_string_lossy()
+            ),
+            data: Some(serde_json::json!({
+                "port": config.port,
+                "pid": pid,
+                "logPath": capture.path(),
+            })),
+        })
+    }
+
+    pub fn stop_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        ServiceSupervisor::global().mark_intentional_stop(&service_data.id);
+        let config = self.get_runtime_config(environment_id, service_data)?;
+
+        // 关闭节点需要刷盘，等待时间比普通进程长
+        Ok(shutdown::stop_pid_file_process(
+            "OpenSearch",
+            &config.pid_path,
+            "TERM",
+            Duration::from_secs(10),
+        ))
+    }
+
+    pub fn restart_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let _ = self.stop_service(environment_id, service_data);
+        std::thread::sleep(Duration::from_millis(300));
+        self.start_service(environment_id, service_data)
+    }
+
+    /// 列出所有索引，按索引名排序
+    pub async fn list_opensearch_indices(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let config = self.get_runtime_config(environment_id, service_data)?;
+        let url = format!(
+            "{}/_cat/indices?format=json&bytes=b&s=index",
+            config.base_url()
+        );
+
+        let response = http_api::api_client()?.get(&url).send().await?;
+        let body = Self::parse_response(response).await?;
+
+        // _cat 接口的数值字段以字符串返回
+        let field = |row: &serde_json::Value, key: &str| {
+            row.get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let indices: Vec<OpensearchIndex> = body
+            .as_array()
+            .map(|rows| {
+                rows.iter()
+                    .map(|row| OpensearchIndex {
+                        index: field(row, "index"),
+                        health: field(row, "health"),
+                        status: field(row, "status"),
+                        docs_count: field(row, "docs.count").parse().ok(),
+                        store_size_bytes: field(row, "store.size").parse().ok(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "获取 OpenSearch 索引列表成功".to_string(),
+            data: Some(serde_json::json!({ "indices": indices })),
+        })
+    }
+
+    /// 删除单个索引，不允许通配符与批量删除
+    pub async fn delete_opensearch_index(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        index: &str,
+    ) -> Result<ServiceDataResult> {
+        let config = self.get_runtime_config(environment_id, service_data)?;
+        let index = Self::validate_index_name(index)?;
+
+        let url = format!("{}/{}", config.base_url(), index);
+        let response = http_api::api_client()?.delete(&url).send().await?;
+        Self::parse_response(response).await?;
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("已删除索引 {}", index),
+            data: Some(serde_json::json!({ "index": index })),
+        })
+    }
+
+    fn validate_index_name(index: &str) -> Result<String> {
+        let index = index.trim();
+        if index.is_empty() {
+            return Err(anyhow!("索引名不能为空"));
+        }
+        if index == "_all"
+            || index
+                .chars()
+                .any(|c| c.is_whitespace() || "*?,/\\\"<>|#".contains(c))
+        {
+            return Err(anyhow!("索引名无效: {}", index));
+        }
+        Ok(index.to_string())
+    }
+
+    /// 解析 OpenSearch API 响应，非 2xx 时取出 error.reason 作为错误信息
+    async fn parse_response(response: reqwest::Response) -> Result<serde_json::Value> {
+        http_api::parse_json_response(response, "OpenSearch API", |body| {
+            body.pointer("/error/reason")
+                .or_else(|| body.get("error"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        })
+        .await
+    }
+
+    /// 检查进程、端口与 `_cluster/health`，返回健康状态和集群状态（green/yellow/red）
+    fn check_health(&self, config: &OpensearchRuntimeConfig) -> (ServiceHealth, Option<String>) {
+        let process = shutdown::read_running_pid(&config.pid_path).is_some();
+        let port_latency = health::check_tcp_port(OPENSEARCH_HOST, config.port);
+        let cluster = port_latency
+            .and_then(|_| health::http_get(OPENSEARCH_HOST, config.port, "/_cluster/health"))
+            .and_then(|(body, latency)| {
+                let body: serde_json::Value = serde_json::from_str(&body).ok()?;
+                let status = body.get("status")?.as_str()?.to_string();
+                Some((status, latency))
+            });
+
+        // 单节点集群的副本无法分配，yellow 属于正常状态
+        let responding = cluster
+            .as_ref()
+            .is_some_and(|(status, _)| status == "green" || status == "yellow");
+        let health = ServiceHealth {
+            process,
+            port_open: port_latency.is_some(),
+            responding,
+            latency_ms: cluster
+                .as_ref()
+                .map(|(_, latency)| *latency)
+                .or(port_latency),
+        };
+        (health, cluster.map(|(status, _)| status))
+    }
+
+    fn get_runtime_config(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<OpensearchRuntimeConfig> {
+        let service_data_folder =
+            self.get_service_data_folder(environment_id, &service_data.version);
+
+        let port =
+            metadata_port(service_data, "OPENSEARCH_PORT")?.unwrap_or(DEFAULT_OPENSEARCH_PORT);
+        let heap_mb = metadata_number(service_data, "OPENSEARCH_HEAP_MB")
+            .map(|heap| {
+                u32::try_from(heap)
+                    .ok()
+                    .filter(|heap| *heap >= 128)
+                    .ok_or_else(|| anyhow!("OPENSEARCH_HEAP_MB 不能小于 128: {}", heap))
+            })
+            .transpose()?
+            .unwrap_or(DEFAULT_OPENSEARCH_HEAP_MB);
+
+        Ok(OpensearchRuntimeConfig {
+            port,
+            heap_mb,
+            config_dir: service_data_folder.join("config"),
+            data_dir: service_data_folder.join("data"),
+            logs_dir: service_data_folder.join("logs"),
+            pid_path: service_data_folder.join("run").join("opensearch.pid"),
+        })
+    }
+
+    /// 在环境目录生成配置：复制安装包中的默认配置文件（已存在的不覆盖），
+    /// 每次启动时按 metadata 重新生成 opensearch.yml 与 jvm.options
+    fn write_config_files(&self, version: &str, config: &OpensearchRuntimeConfig) -> Result<()> {
+        let template_dir = self.get_install_path(version).join("config");
+        Self::copy_missing_files(&template_dir, &config.config_dir)?;
+        fs::create_dir_all(&config.data_dir)?;
+        fs::create_dir_all(&config.logs_dir)?;
+
+        let opensearch_yml = format!(
+            "# 由 Envis 生成，启动时会按服务配置覆盖\n\
+             cluster.name: envis\n\
+             node.name: envis-node\n\
+             path.data: {}\n\
+             path.logs: {}\n\
+             network.host: {}\n\
+             http.port: {}\n\
+             discovery.type: single-node\n\
+             # 本地开发默认关闭安全插件（无需 TLS 与账号密码）\n\
+             plugins.security.disabled: true\n",
+            Self::yaml_quote(&config.data_dir),
+            Self::yaml_quote(&config.logs_dir),
+            OPENSEARCH_HOST,
+            config.port
+        );
+        fs::write(config.config_dir.join("opensearch.yml"), opensearch_yml)?;
+
+        // 以安装包中的 jvm.options 为模板，只替换堆大小
+        let template = fs::read_to_string(template_dir.join("jvm.options")).unwrap_or_default();
+        let mut lines: Vec<String> = template
+            .lines()
+            .filter(|line| {
+                let line = line.trim_start();
+                !line.starts_with("-Xms") && !line.starts_with("-Xmx")
+            })
+            .map(|line| line.to_string())
+            .collect();
+        lines.push(format!("-Xms{}m", config.heap_mb));
+        lines.push(format!("-Xmx{}m", config.heap_mb));
+        fs::write(
+            config.config_dir.join("jvm.options"),
+            lines.join("\n") + "\n",
+        )?;
+
+        Ok(())
+    }
+
+    /// 以 YAML 单引号字符串写入路径，避免 Windows 路径中的反斜杠被转义
+    fn yaml_quote(path: &Path) -> String {
+        format!("'{}'", path.to_string_lossy().replace('\'', "''"))
+    }
+
+    fn copy_missing_files(from: &Path, to: &Path) -> Result<()> {
+        fs::create_dir_all(to)?;
+
+        for entry in walkdir::WalkDir::new(from)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            let src_path = entry.path();
+            let rel = src_path
+                .strip_prefix(from)
+                .map_err(|e| anyhow!("计算目录相对路径失败: {}", e))?;
+            let target = to.join(rel);
+
+            if src_path.is_dir() {
+                fs::create_dir_all(&target)?;
+            } else if !target.exists() {
+                fs::copy(src_path, &target)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 解压 zip 并去掉顶层目录
+    fn extract_zip_strip_root(archive_path: &Path, dest_dir: &Path) -> Result<()> {
+        let file = fs::File::open(archive_path).map_err(|e| anyhow!("无法打开 zip 文件: {}", e))?;
+        let mut archive =
+            zip::ZipArchive::new(file).map_err(|e| anyhow!("无法读取 zip 文件: {}", e))?;
+
+        for i in 0..archive.len() {
+            let mut file = archive
+                .by_index(i)
+                .map_err(|e| anyhow!("读取 zip 条目失败: {}", e))?;
+            let relative: PathBuf = file.mangled_name().components().skip(1).collect();
+            if relative.as_os_str().is_empty() {
+                continue;
+            }
+            let out_path = dest_dir.join(relative);
+
+            if file.is_dir() {
+                fs::create_dir_all(&out_path)?;
+            } else {
+                if let Some(parent) = out_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let mut out_file = fs::File::create(&out_path)
+                    .map_err(|e| anyhow!("创建文件失败 {:?}: {}", out_path, e))?;
+                std::io::copy(&mut file, &mut out_file)
+                    .map_err(|e| anyhow!("写入文件失败 {:?}: {}", out_path, e))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct OpensearchRuntimeConfig {
+    port: u16,
+    heap_mb: u32,
+    config_dir: PathBuf,
+    data_dir: PathBuf,
+    logs_dir: PathBuf,
+    pid_path: PathBuf,
+}
+
+impl OpensearchRuntimeConfig {
+    fn base_url(&self) -> String {
+        format!("http://{}:{}", OPENSEARCH_HOST, self.port)
+    }
+}
+
+impl ServiceLifecycle for OpensearchService {
+    fn is_installed(&self, version: &str) -> bool {
+        OpensearchService::is_installed(self, version)
+    }
+
+    fn available_versions(&self) -> Vec<String> {
+        self.get_available_versions()
+            .into_iter()
+            .map(|v| v.version)
+            .collect()
+    }
+
+    fn download_and_install<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<DownloadResult>> {
+        Box::pin(OpensearchService::download_and_install(self, version))
+    }
+
+    fn cancel_download(&self, version: &str) -> Result<()> {
+        OpensearchService::cancel_download(self, version)
+    }
+
+    fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        OpensearchService::get_download_progress(self, version)
+    }
+
+    fn archive_dir(&self, version: &str) -> Option<PathBuf> {
+        Some(self.get_install_path(version))
+    }
+
+    fn extract_and_install<'a>(
+        &'a self,
+        task: &'a DownloadTask,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(OpensearchService::extract_and_install(self, task, version))
+    }
+
+    fn start_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        OpensearchService::start_service(self, environment_id, service_data)
+    }
+
+    fn stop_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        OpensearchService::stop_service(self, environment_id, service_data)
+    }
+
+    fn restart_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        OpensearchService::restart_service(self, environment_id, service_data)
+    }
+
+    fn get_service_status(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        OpensearchService::get_service_status(self, environment_id, service_data)
+    }
+}
@@ -0,0 +1,246 @@
+use crate::manager::env_serv_data_manager::ServiceDataResult;
+use crate::manager::services::php::PhpService;
+use crate::utils::{create_command, http_client_builder};
+use anyhow::{anyhow, Result};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+/// composer.phar 最新稳定版下载地址
+const COMPOSER_PHAR_URL: &str = "https://getcomposer.org/download/latest-stable/composer.phar";
+/// composer.phar 对应的 SHA-256 校验值
+const COMPOSER_SHA256_URL: &str =
+    "https://getcomposer.org/download/latest-stable/composer.phar.sha256";
+/// 下载 composer.phar 的超时时间
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+/// Packagist 镜像预设：(名称, 地址)
+const MIRROR_PRESETS: [(&str, &str); 3] = [
+    ("packagist", "https://repo.packagist.org"),
+    ("aliyun", "https://mirrors.aliyun.com/composer/"),
+    ("tencent", "https://mirrors.tencent.com/composer/"),
+];
+
+/// 全局 Composer 服务管理器单例
+static GLOBAL_COMPOSER_SERVICE: OnceLock<Arc<ComposerService>> = OnceLock::new();
+
+/// Composer 服务管理器：按 PHP 版本安装 composer.phar 与 composer 包装脚本
+pub struct ComposerService {}
+
+impl ComposerService {
+    /// 获取全局 Composer 服务管理器单例
+    pub fn global() -> Arc<ComposerService> {
+        GLOBAL_COMPOSER_SERVICE
+            .get_or_init(|| Arc::new(ComposerService::new()))
+            .clone()
+    }
+
+    fn new() -> Self {
+        Self {}
+    }
+
+    /// composer 与 php 放在同一目录，激活 PHP 时随 PATH 一起暴露
+    fn get_composer_dir(&self, php_version: &str) -> PathBuf {
+        let install_path = PhpService::global().get_install_path(php_version);
+        if cfg!(target_os = "windows") {
+            install_path
+        } else {
+            install_path.join("bin")
+        }
+    }
+
+    fn get_phar_path(&self, php_version: &str) -> PathBuf {
+        self.get_composer_dir(php_version).join("composer.phar")
+    }
+
+    fn get_wrapper_path(&self, php_version: &str) -> PathBuf {
+        let name = if cfg!(target_os = "windows") {
+            "composer.bat"
+        } else {
+            "composer"
+        };
+        self.get_composer_dir(php_version).join(name)
+    }
+
+    /// 检查指定 PHP 版本是否已安装 Composer
+    pub fn is_composer_installed(&self, php_version: &str) -> bool {
+        self.get_phar_path(php_version).exists() && self.get_wrapper_path(php_version).exists()
+    }
+
+    /// 下载 composer.phar 并校验 SHA-256，写入 PHP 版本目录并生成 composer 包装脚本
+    pub async fn install_composer(&self, php_version: &str) -> Result<ServiceDataResult> {
+        if !PhpService::global().is_installed(php_version) {
+            return Err(anyhow!("PHP {} 未安装，请先下载并安装", php_version));
+        }
+
+        let client = http_client_builder()?.timeout(DOWNLOAD_TIMEOUT).build()?;
+        let checksum = Self::fetch(&client, COMPOSER_SHA256_URL).await?;
+        let expected = String::from_utf8_lossy(&checksum)
+            .split_whitespace()
+            .next()
+            .map(|s| s.to_ascii_lowercase())
+            .filter(|s| s.len() == 64 && s.chars().all(|c| c.is_ascii_hexdigit()))
+            .ok_or_else(|| anyhow!("无法解析 composer.phar 的校验值"))?;
+
+        let phar = Self::fetch(&client, COMPOSER_PHAR_URL).await?;
+        let actual = format!("{:x}", Sha256::digest(&phar));
+        if actual != expected {
+            return Err(anyhow!(
+                "composer.phar 校验失败: 期望 {}，实际 {}",
+                expected,
+                actual
+            ));
+        }
+
+        let phar_path = self.get_phar_path(php_version);
+        let wrapper_path = self.get_wrapper_path(php_version);
+        std::fs::create_dir_all(self.get_composer_dir(php_version))?;
+        // 先写临时文件再替换，避免中断时留下不完整的 composer.phar
+        let tmp_path = phar_path.with_extension("phar.download");
+        std::fs::write(&tmp_path, &phar)?;
+        std::fs::rename(&tmp_path, &phar_path)?;
+        Self::write_wrapper(&wrapper_path)?;
+
+        let version = self.get_composer_version(php_version);
+        log::info!(
+            "PHP {} 已安装 Composer {}",
+            php_version,
+            version.as_deref().unwrap_or("")
+        );
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("Composer 已安装到 PHP {}", php_version),
+            data: Some(serde_json::json!({
+                "version": version,
+                "pharPath": phar_path.to_string_lossy().to_string(),
+                "wrapperPath": wrapper_path.to_string_lossy().to_string(),
+                "sha256": actual,
+            })),
+        })
+    }
+
+    async fn fetch(client: &reqwest::Client, url: &str) -> Result<Vec<u8>> {
+        let response = client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("下载 {} 失败: {}", url, response.status()));
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// 生成调用同目录 php 执行 composer.phar 的包装脚本
+    fn write_wrapper(wrapper_path: &Path) -> Result<()> {
+        if cfg!(target_os = "windows") {
+            std::fs::write(
+                wrapper_path,
+                "@echo off\r\n\"%~dp0php.exe\" \"%~dp0composer.phar\" %*\r\n",
+            )?;
+            return Ok(());
+        }
+
+        std::fs::write(
+            wrapper_path,
+            "#!/bin/sh\n\
+             # Auto-generated by envis\n\
+             DIR=$(cd \"$(dirname \"$0\")\" && pwd)\n\
+             exec \"$DIR/php\" \"$DIR/composer.phar\" \"$@\"\n",
+        )?;
+        #[cfg(not(target_os = "windows"))]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(wrapper_path, std::fs::Permissions::from_mode(0o755))?;
+        }
+        Ok(())
+    }
+
+    /// 读取 composer --version 输出的版本号
+    pub fn get_composer_version(&self, php_version: &str) -> Option<String> {
+        let output = self
+            .composer_command(php_version)
+            .args(["--version", "--no-ansi"])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        // 输出形如 "Composer version 2.8.4 2024-12-11 11:57:47"
+        String::from_utf8_lossy(&output.stdout)
+            .split_whitespace()
+            .skip_while(|word| *word != "version")
+            .nth(1)
+            .map(|v| v.to_string())
+    }
+
+    /// 设置全局 Packagist 镜像，mirror 可以是预设名称（packagist、aliyun、tencent）或镜像地址
+    pub fn set_composer_mirror(
+        &self,
+        php_version: &str,
+        mirror: &str,
+    ) -> Result<ServiceDataResult> {
+        if !self.is_composer_installed(php_version) {
+            return Err(anyhow!("PHP {} 未安装 Composer", php_version));
+        }
+        let url = Self::resolve_mirror(mirror)?;
+
+        let output = self
+            .composer_command(php_version)
+            .args([
+                "config",
+                "-g",
+                "--no-interaction",
+                "repo.packagist",
+                "composer",
+            ])
+            .arg(&url)
+            .output()?;
+        if !output.status.success() {
+            return Ok(ServiceDataResult {
+                success: false,
+                message: format!(
+                    "设置 Composer 镜像失败: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+                data: None,
+            });
+        }
+
+        log::info!("Composer 镜像已设置为 {}", url);
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("Composer 镜像已设置为 {}", url),
+            data: Some(serde_json::json!({ "mirror": url })),
+        })
+    }
+
+    /// 预设名称转换为地址，其余值必须是 http(s) 地址
+    fn resolve_mirror(mirror: &str) -> Result<String> {
+        let mirror = mirror.trim();
+        if let Some((_, url)) = MIRROR_PRESETS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(mirror))
+        {
+            return Ok(url.to_string());
+        }
+        if (mirror.starts_with("https://") || mirror.starts_with("http://"))
+            && !mirror.contains(char::is_whitespace)
+        {
+            return Ok(mirror.to_string());
+        }
+        Err(anyhow!(
+            "无效的 Composer 镜像: {}，可使用 {} 或镜像地址",
+            mirror,
+            MIRROR_PRESETS
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>()
+                .join("、")
+        ))
+    }
+
+    /// 使用该 PHP 版本的 php 执行 composer.phar
+    fn composer_command(&self, php_version: &str) -> std::process::Command {
+        let mut command = create_command(PhpService::global().get_php_bin_path(php_version));
+        command.arg(self.get_phar_path(php_version));
+        command
+    }
+}
@@ -0,0 +1,5 @@
+pub mod composer;
+pub mod service;
+
+pub use composer::ComposerService;
+pub use service::*;
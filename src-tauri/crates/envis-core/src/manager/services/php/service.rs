@@ -0,0 +1,1069 @@
+use crate::manager::app_config_manager::AppConfigManager;
+use crate::manager::env_serv_data_manager::{EnvServDataManager, ServiceDataResult};
+use crate::manager::service_supervisor::ServiceSupervisor;
+use crate::manager::services::traits::ServiceLifecycle;
+use crate::manager::services::{
+    install_callback, remove_partial_install, DownloadManager, DownloadResult, DownloadTask,
+};
+use crate::manager::system_info_manager::SystemInfoManager;
+use crate::types::{ServiceData, ServiceStatus};
+use crate::utils::create_command;
+use crate::utils::path::to_unix_path_string;
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhpVersion {
+    pub version: String,
+    pub date: String,
+}
+
+/// php-fpm 默认监听地址
+const DEFAULT_FPM_LISTEN: &str = "127.0.0.1:9000";
+/// php.ini 默认内存限制
+const DEFAULT_MEMORY_LIMIT: &str = "128M";
+/// metadata 中 php-fpm 监听地址的键：host:port、端口号或 Unix socket 绝对路径
+pub const FPM_LISTEN_METADATA_KEY: &str = "PHP_FPM_LISTEN";
+/// 允许通过 set_php_ini_values 修改的常用 php.ini 配置项
+const COMMON_INI_KEYS: [&str; 5] = [
+    "memory_limit",
+    "upload_max_filesize",
+    "post_max_size",
+    "max_execution_time",
+    "display_errors",
+];
+
+static GLOBAL_PHP_SERVICE: OnceLock<Arc<PhpService>> = OnceLock::new();
+
+pub struct PhpService {}
+
+impl PhpService {
+    pub fn global() -> Arc<PhpService> {
+        GLOBAL_PHP_SERVICE
+            .get_or_init(|| Arc::new(PhpService::new()))
+            .clone()
+    }
+
+    fn new() -> Self {
+        Self {}
+    }
+
+    pub fn get_available_versions(&self) -> Vec<PhpVersion> {
+        vec![
+            PhpVersion {
+                version: "8.4.13".to_string(),
+                date: "2025-09-25".to_string(),
+            },
+            PhpVersion {
+                version: "8.3.26".to_string(),
+                date: "2025-09-25".to_string(),
+            },
+            PhpVersion {
+                version: "8.2.29".to_string(),
+                date: "2025-07-03".to_string(),
+            },
+            PhpVersion {
+                version: "8.1.33".to_string(),
+                date: "2025-07-03".to_string(),
+            },
+        ]
+    }
+
+    pub fn is_installed(&self, version: &str) -> bool {
+        self.get_php_bin_path(version).exists()
+    }
+
+    pub(crate) fn get_install_path(&self, version: &str) -> PathBuf {
+        let services_folder = {
+            let app_config_manager = AppConfigManager::global();
+            let app_config_manager = app_config_manager.lock().unwrap();
+            std::path::PathBuf::from(app_config_manager.get_services_folder())
+        };
+        services_folder.join("php").join(version)
+    }
+
+    fn get_service_data_folder(&self, environment_id: &str, version: &str) -> PathBuf {
+        let app_config_manager = AppConfigManager::global();
+        let app_config_manager = app_config_manager.lock().unwrap();
+        let envs_folder = app_config_manager.get_envs_folder();
+
+        PathBuf::from(envs_folder)
+            .join(environment_id)
+            .join("php")
+            .join(version)
+    }
+
+    pub(crate) fn get_php_bin_path(&self, version: &str) -> PathBuf {
+        let install_path = self.get_install_path(version);
+        if cfg!(target_os = "windows") {
+            install_path.join("php.exe")
+        } else {
+            install_path.join("bin").join("php")
+        }
+    }
+
+    /// FastCGI 进程管理器：Unix 为 php-fpm，Windows 官方包只提供 php-cgi
+    fn get_fpm_bin_path(&self, version: &str) -> PathBuf {
+        let install_path = self.get_install_path(version);
+        if cfg!(target_os = "windows") {
+            install_path.join("php-cgi.exe")
+        } else {
+            install_path.join("sbin").join("php-fpm")
+        }
+    }
+
+    fn map_platform_arch(&self) -> Result<(&'static str, &'static str, &'static str)> {
+        let os = std::env::consts::OS;
+        let arch = std::env::consts::ARCH;
+
+        match os {
+            "macos" => {
+                let arch_str = if arch == "aarch64" { "arm64" } else { "x86_64" };
+                Ok(("macos", arch_str, "tar.gz"))
+            }
+            "linux" => {
+                let arch_str = if arch == "aarch64" { "arm64" } else { "x86_64" };
+                Ok(("linux", arch_str, "tar.gz"))
+            }
+            "windows" => Ok(("windows", "x86_64", "zip")),
+            _ => Err(anyhow!("不支持的操作系统: {}", os)),
+        }
+    }
+
+    /// php.net/distributions 只提供源码包，预编译包统一托管在 php-archive；
+    /// Windows 额外回退到 windows.php.net 官方 NTS 构建
+    fn build_download_info(&self, version: &str) -> Result<(Vec<String>, String)> {
+        let (os, arch, ext) = self.map_platform_arch()?;
+        let filename = format!("php-{}-{}-{}.{}", version, os, arch, ext);
+        let mut urls = vec![format!(
+            "https://github.com/xopenbeta/php-archive/releases/latest/download/{}",
+            filename
+        )];
+
+        if os == "windows" {
+            let vs = if Self::is_version_at_least(version, 8, 4) {
+                "vs17"
+            } else {
+                "vs16"
+            };
+            let official = format!("php-{}-nts-Win32-{}-x64.zip", version, vs);
+            urls.push(format!(
+                "https://windows.php.net/downloads/releases/{}",
+                official
+            ));
+            urls.push(format!(
+                "https://windows.php.net/downloads/releases/archives/{}",
+                official
+            ));
+        }
+
+        Ok((urls, filename))
+    }
+
+    fn is_version_at_least(version: &str, major: u32, minor: u32) -> bool {
+        let mut parts = version.split('.').map(|p| p.parse::<u32>().unwrap_or(0));
+        let v_major = parts.next().unwrap_or(0);
+        let v_minor = parts.next().unwrap_or(0);
+        (v_major, v_minor) >= (major, minor)
+    }
+
+    pub async fn download_and_install(&self, version: &str) -> Result<DownloadResult> {
+        if self.is_installed(version) {
+            return Ok(DownloadResult::success(
+                format!("PHP {} 已经安装", version),
+                None,
+            ));
+        }
+
+        let (urls, filename) = self.build_download_info(version)?;
+        let install_path = self.get_install_path(version);
+        let task_id = format!("php-{}", version);
+        let download_manager = DownloadManager::global();
+
+        let version_for_callback = version.to_string();
+        let success_callback = install_callback(format!("PHP {}", version), move |task| {
+            let version = version_for_callback.clone();
+            async move {
+                PhpService::global()
+                    .extract_and_install(&task, &version)
+                    .await
+            }
+        });
+
+        match download_manager
+            .start_download(
+                task_id.clone(),
+                urls,
+                install_path,
+                filename,
+                true,
+                Some(success_callback),
+            )
+            .await
+        {
+            Ok(_) => {
+                if let Some(task) = download_manager.get_task_status(&task_id) {
+                    Ok(DownloadResult::success(
+                        format!("PHP {} 下载完成", version),
+                        Some(task),
+                    ))
+                } else {
+                    Ok(DownloadResult::error("无法获取下载任务状态".to_string()))
+                }
+            }
+            Err(e) => Ok(DownloadResult::error(format!("下载失败: {}", e))),
+        }
+    }
+
+    pub async fn extract_and_install(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let result = self.install_from_archive(task, version).await;
+        if result.is_err() {
+            // 解压安装失败时清理残缺的安装目录
+            remove_partial_install(&self.get_install_path(version));
+        }
+        result
+    }
+
+    /// 将下载的压缩包解压到安装目录
+    async fn install_from_archive(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let archive_path = &task.target_path;
+        let install_dir = self.get_install_path(version);
+        std::fs::create_dir_all(&install_dir)?;
+
+        if task.filename.ends_with(".tar.gz") || task.filename.ends_with(".tgz") {
+            let output = create_command("tar")
+                .args([
+                    "-xzf",
+                    &archive_path.to_string_lossy(),
+                    "-C",
+                    &install_dir.to_string_lossy(),
+                    "--strip-components=1",
+                ])
+                .output()?;
+            if !output.status.success() {
+                return Err(anyhow!(
+                    "解压失败: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                ));
+            }
+        } else if task.filename.ends_with(".zip") {
+            Self::extract_zip(archive_path, &install_dir)?;
+        } else {
+            return Err(anyhow!("不支持的压缩格式: {}", task.filename));
+        }
+
+        self.normalize_binary_layout(&install_dir)?;
+
+        if archive_path.exists() {
+            std::fs::remove_file(archive_path)?;
+        }
+
+        Ok(())
+    }
+
+    fn normalize_binary_layout(&self, install_dir: &Path) -> Result<()> {
+        #[cfg(target_os = "windows")]
+        {
+            // Windows 官方包 php.exe / php-cgi.exe 位于根目录
+            if !install_dir.join("php.exe").exists() {
+                return Err(anyhow!("未找到 php 可执行文件"));
+            }
+            return Ok(());
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let bin_dir = install_dir.join("bin");
+            let sbin_dir = install_dir.join("sbin");
+            std::fs::create_dir_all(&bin_dir)?;
+            std::fs::create_dir_all(&sbin_dir)?;
+
+            Self::move_binary_if_found(install_dir, &bin_dir, "php")?;
+            Self::move_binary_if_found(install_dir, &sbin_dir, "php-fpm")?;
+
+            for bin in [bin_dir.join("php"), sbin_dir.join("php-fpm")] {
+                if bin.exists() {
+                    let mut perms = std::fs::metadata(&bin)?.permissions();
+                    perms.set_mode(0o755);
+                    std::fs::set_permissions(&bin, perms)?;
+                }
+            }
+
+            if !bin_dir.join("php").exists() {
+                return Err(anyhow!("未找到 php 可执行文件"));
+            }
+            if !sbin_dir.join("php-fpm").exists() {
+                log::warn!("PHP 安装包中未找到 php-fpm，FPM 相关功能不可用");
+            }
+
+            Ok(())
+        }
+    }
+
+    #[cfg_attr(target_os = "windows", allow(dead_code))]
+    fn move_binary_if_found(search_root: &Path, bin_dir: &Path, name: &str) -> Result<()> {
+        let direct = bin_dir.join(name);
+        if direct.exists() {
+            return Ok(());
+        }
+
+        let found = walkdir::WalkDir::new(search_root)
+            .max_depth(5)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .find(|e| {
+                e.path().is_file()
+                    && e.path()
+                        .file_name()
+                        .and_then(|v| v.to_str())
+                        .map(|n| n == name)
+                        .unwrap_or(false)
+            });
+
+        if let Some(entry) = found {
+            std::fs::rename(entry.path(), &direct)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn cancel_download(&self, version: &str) -> Result<()> {
+        let task_id = format!("php-{}", version);
+        DownloadManager::global().cancel_download(&task_id)
+    }
+
+    pub fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        let task_id = format!("php-{}", version);
+        DownloadManager::global().get_task_status(&task_id)
+    }
+
+    /// 确保环境目录下存在 php.ini 与 php-fpm.conf（已存在则不覆盖），返回 php.ini 所在目录（PHPRC）
+    pub fn ensure_config_files(&self, environment_id: &str, version: &str) -> Result<PathBuf> {
+        let service_data_folder = self.get_service_data_folder(environment_id, version);
+        let log_dir = service_data_folder.join("logs");
+        let run_dir = service_data_folder.join("run");
+        std::fs::create_dir_all(&log_dir)?;
+        std::fs::create_dir_all(&run_dir)?;
+
+        let ini_path = service_data_folder.join("php.ini");
+        if !ini_path.exists() {
+            let extension_dir = self
+                .detect_extension_dir(version)
+                .map(|p| to_unix_path_string(&p))
+                .unwrap_or_default();
+            let content = format!(
+                "; Auto-generated php.ini by envis\n\
+                 memory_limit = {memory_limit}\n\
+                 extension_dir = \"{extension_dir}\"\n\
+                 error_log = \"{error_log}\"\n\
+                 log_errors = On\n\
+                 display_errors = On\n\
+                 date.timezone = UTC\n",
+                memory_limit = DEFAULT_MEMORY_LIMIT,
+                extension_dir = extension_dir,
+                error_log = to_unix_path_string(&log_dir.join("php_errors.log")),
+            );
+            std::fs::write(&ini_path, content)?;
+        }
+
+        let fpm_conf_path = service_data_folder.join("php-fpm.conf");
+        if !fpm_conf_path.exists() {
+            let content = format!(
+                "; Auto-generated php-fpm.conf by envis\n\
+                 [global]\n\
+                 pid = {pid}\n\
+                 error_log = {error_log}\n\
+                 daemonize = yes\n\
+                 \n\
+                 [www]\n\
+                 listen = {listen}\n\
+                 pm = dynamic\n\
+                 pm.max_children = 5\n\
+                 pm.start_servers = 2\n\
+                 pm.min_spare_servers = 1\n\
+                 pm.max_spare_servers = 3\n",
+                pid = to_unix_path_string(&run_dir.join("php-fpm.pid")),
+                error_log = to_unix_path_string(&log_dir.join("php-fpm.log")),
+                listen = DEFAULT_FPM_LISTEN,
+            );
+            std::fs::write(&fpm_conf_path, content)?;
+        }
+
+        Ok(service_data_folder)
+    }
+
+    /// 扩展目录：Unix 为 lib/php/extensions/no-debug-*，Windows 为 ext
+    fn detect_extension_dir(&self, version: &str) -> Option<PathBuf> {
+        let install_path = self.get_install_path(version);
+        let ext_dir = install_path.join("ext");
+        if ext_dir.is_dir() {
+            return Some(ext_dir);
+        }
+
+        let extensions_root = install_path.join("lib").join("php").join("extensions");
+        std::fs::read_dir(&extensions_root)
+            .ok()?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .find(|p| p.is_dir())
+    }
+
+    pub fn get_php_fpm_status(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let config = self.get_runtime_config(environment_id, service_data)?;
+        let pid = self.read_running_pid(&config);
+        let running = pid.is_some();
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "获取 PHP-FPM 状态成功".to_string(),
+            data: Some(serde_json::json!({
+                "isRunning": running,
+                "status": if running { ServiceStatus::Running } else { ServiceStatus::Stopped },
+                "listen": config.listen,
+                "pid": pid,
+                "iniPath": config.ini_path,
+                "fpmConfigPath": config.fpm_config_path,
+                "logPath": config.log_path,
+            })),
+        })
+    }
+
+    pub fn start_php_fpm(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        ServiceSupervisor::global().clear_intentional_stop(&service_data.id);
+        let version = &service_data.version;
+        let fpm_bin = self.get_fpm_bin_path(version);
+
+        if !fpm_bin.exists() {
+            return Ok(ServiceDataResult {
+                success: false,
+                message: format!("{} 可执行文件不存在", fpm_bin.to_string_lossy()),
+                data: None,
+            });
+        }
+
+        self.ensure_config_files(environment_id, version)?;
+        self.sync_fpm_listen(environment_id, service_data)?;
+        let config = self.get_runtime_config(environment_id, service_data)?;
+
+        if let Some(pid) = self.read_running_pid(&config) {
+            return Ok(ServiceDataResult {
+                success: true,
+                message: "PHP-FPM 已在运行".to_string(),
+                data: Some(serde_json::json!({
+                    "listen": config.listen,
+                    "pid": pid,
+                    "alreadyRunning": true
+                })),
+            });
+        }
+
+        if cfg!(target_os = "windows") {
+            // php-cgi 不支持 -D，也不会写 pid 文件，由这里记录子进程 PID
+            let child = create_command(&fpm_bin)
+                .arg("-b")
+                .arg(&config.listen)
+                .arg("-c")
+                .arg(&config.ini_path)
+                .stdin(std::process::Stdio::null())
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .spawn();
+            match child {
+                Ok(child) => {
+                    std::fs::write(&config.pid_path, child.id().to_string())?;
+                }
+                Err(e) => {
+                    return Ok(ServiceDataResult {
+                        success: false,
+                        message: format!("启动失败: {}", e),
+                        data: None,
+                    })
+                }
+            }
+        } else {
+            let output = create_command(&fpm_bin)
+                .arg("-D")
+                .arg("-y")
+                .arg(&config.fpm_config_path)
+                .arg("-c")
+                .arg(&config.ini_path)
+                .output();
+            match output {
+                Ok(o) if !o.status.success() => {
+                    return Ok(ServiceDataResult {
+                        success: false,
+                        message: format!(
+                            "PHP-FPM 启动失败: {}",
+                            String::from_utf8_lossy(&o.stderr).trim()
+                        ),
+                        data: None,
+                    })
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    return Ok(ServiceDataResult {
+                        success: false,
+                        message: format!("启动失败: {}", e),
+                        data: None,
+                    })
+                }
+            }
+        }
+
+        std::thread::sleep(Duration::from_millis(500));
+        match self.read_running_pid(&config) {
+            Some(pid) => {
+                log::info!("PHP-FPM 已启动，PID: {}", pid);
+                Ok(ServiceDataResult {
+                    success: true,
+                    message: "PHP-FPM 启动成功".to_string(),
+                    data: Some(serde_json::json!({
+                        "listen": config.listen,
+                        "pid": pid,
+                    })),
+                })
+            }
+            None => Ok(ServiceDataResult {
+                success: false,
+                message: format!(
+                    "PHP-FPM 启动命令已执行，但服务未处于运行状态，请检查日志: {}",
+                    config.log_path
+                ),
+                data: Some(serde_json::json!({
+                    "listen": config.listen,
+                    "logPath": config.log_path,
+                })),
+            }),
+        }
+    }
+
+    pub fn stop_php_fpm(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        ServiceSupervisor::global().mark_intentional_stop(&service_data.id);
+        let config = self.get_runtime_config(environment_id, service_data)?;
+
+        let pid = match self.read_running_pid(&config) {
+            Some(pid) => pid,
+            None => {
+                let _ = std::fs::remove_file(&config.pid_path);
+                return Ok(ServiceDataResult {
+                    success: true,
+                    message: "PHP-FPM 未在运行".to_string(),
+                    data: None,
+                });
+            }
+        };
+
+        let kill_res = if cfg!(target_os = "windows") {
+            create_command("taskkill")
+                .args(["/PID", &pid.to_string(), "/T", "/F"])
+                .output()
+        } else {
+            // SIGQUIT 让 master 等待 worker 处理完当前请求后退出
+            create_command("kill")
+                .args(["-QUIT", &pid.to_string()])
+                .output()
+        };
+
+        match kill_res {
+            Ok(o) if o.status.success() => {
+                for _ in 0..20 {
+                    if !SystemInfoManager::global().is_process_alive(pid) {
+                        break;
+                    }
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                if SystemInfoManager::global().is_process_alive(pid) && !cfg!(target_os = "windows")
+                {
+                    let _ = create_command("kill")
+                        .args(["-TERM", &pid.to_string()])
+                        .output();
+                }
+                let _ = std::fs::remove_file(&config.pid_path);
+                Ok(ServiceDataResult {
+                    success: true,
+                    message: "PHP-FPM 已停止".to_string(),
+                    data: None,
+                })
+            }
+            Ok(o) => Ok(ServiceDataResult {
+                success: false,
+                message: format!(
+                    "停止失败(exit {}): {}",
+                    o.status.code().unwrap_or(-1),
+                    String::from_utf8_lossy(&o.stderr)
+                ),
+                data: None,
+            }),
+            Err(e) => Ok(ServiceDataResult {
+                success: false,
+                message: format!("停止命令失败: {}", e),
+                data: None,
+            }),
+        }
+    }
+
+    pub fn restart_php_fpm(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let _ = self.stop_php_fpm(environment_id, service_data);
+        std::thread::sleep(Duration::from_millis(300));
+        self.start_php_fpm(environment_id, service_data)
+    }
+
+    pub fn get_php_config(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        self.ensure_config_files(environment_id, &service_data.version)?;
+        let config = self.get_runtime_config(environment_id, service_data)?;
+        let ini_content = std::fs::read_to_string(&config.ini_path).unwrap_or_default();
+        let fpm_content = std::fs::read_to_string(&config.fpm_config_path).unwrap_or_default();
+        let ini_values: HashMap<&str, Option<String>> = COMMON_INI_KEYS
+            .iter()
+            .map(|key| (*key, Self::read_ini_value(Path::new(&config.ini_path), key)))
+            .collect();
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "获取 PHP 配置成功".to_string(),
+            data: Some(serde_json::json!({
+                "iniPath": config.ini_path,
+                "fpmConfigPath": config.fpm_config_path,
+                "logPath": config.log_path,
+                "listen": config.listen,
+                "memoryLimit": config.memory_limit,
+                "extensionDir": config.extension_dir,
+                "iniValues": ini_values,
+                "fastcgiPass": Self::fastcgi_pass_for(&config.listen),
+                "iniContent": ini_content,
+                "fpmContent": fpm_content,
+                "isRunning": self.read_running_pid(&config).is_some(),
+            })),
+        })
+    }
+
+    /// 修改配置项：listen 写入 php-fpm.conf，其余键写入 php.ini
+    pub fn set_php_config_value(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        key: &str,
+        value: &str,
+    ) -> Result<ServiceDataResult> {
+        let key = key.trim();
+        let value = value.trim();
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        {
+            return Err(anyhow!("无效的配置项名称: {}", key));
+        }
+        if value.contains('\n') || value.contains('\r') {
+            return Err(anyhow!("配置值不能包含换行"));
+        }
+        if key == "listen" {
+            Self::validate_fpm_listen(value)?;
+        }
+
+        self.ensure_config_files(environment_id, &service_data.version)?;
+        let config = self.get_runtime_config(environment_id, service_data)?;
+
+        let (path, line_value) = match key {
+            "listen" => (&config.fpm_config_path, value.to_string()),
+            "extension_dir" => (&config.ini_path, format!("\"{}\"", value.trim_matches('"'))),
+            _ => (&config.ini_path, value.to_string()),
+        };
+
+        let content = std::fs::read_to_string(path)?;
+        std::fs::write(path, Self::upsert_ini_value(&content, key, &line_value))?;
+
+        if key == "listen" {
+            // 监听地址同时记录到 metadata，启动时以 metadata 为准同步到 php-fpm.conf
+            let mut service_data = service_data.clone();
+            EnvServDataManager::global().lock().unwrap().set_metadata(
+                environment_id,
+                &mut service_data,
+                FPM_LISTEN_METADATA_KEY,
+                serde_json::Value::String(value.to_string()),
+            )?;
+        }
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("已更新 PHP 配置 {}", key),
+            data: Some(serde_json::json!({
+                "key": key,
+                "value": value,
+                "configPath": path,
+                "needsRestart": self.read_running_pid(&config).is_some(),
+            })),
+        })
+    }
+
+    /// 批量修改常用 php.ini 配置项（memory_limit、upload_max_filesize 等），只重写对应的指令行
+    pub fn set_php_ini_values(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        values: &HashMap<String, String>,
+    ) -> Result<ServiceDataResult> {
+        if values.is_empty() {
+            return Err(anyhow!("未指定要修改的配置项"));
+        }
+        for (key, value) in values {
+            if !COMMON_INI_KEYS.contains(&key.as_str()) {
+                return Err(anyhow!(
+                    "不支持的配置项: {}，可修改: {}",
+                    key,
+                    COMMON_INI_KEYS.join(", ")
+                ));
+            }
+            let value = value.trim();
+            if value.is_empty() || value.contains(['\n', '\r', ';']) {
+                return Err(anyhow!("配置项 {} 的值无效: {}", key, value));
+            }
+        }
+
+        self.ensure_config_files(environment_id, &service_data.version)?;
+        let config = self.get_runtime_config(environment_id, service_data)?;
+        let mut content = std::fs::read_to_string(&config.ini_path)?;
+        for (key, value) in values {
+            content = Self::upsert_ini_value(&content, key, value.trim());
+        }
+        std::fs::write(&config.ini_path, content)?;
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "已更新 php.ini".to_string(),
+            data: Some(serde_json::json!({
+                "values": values,
+                "iniPath": config.ini_path,
+                "needsRestart": self.read_running_pid(&config).is_some(),
+            })),
+        })
+    }
+
+    /// 当前环境 php-fpm 对应的 nginx fastcgi_pass 地址
+    pub fn get_fastcgi_pass(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<String> {
+        let config = self.get_runtime_config(environment_id, service_data)?;
+        Ok(Self::fastcgi_pass_for(&config.listen))
+    }
+
+    /// listen 为端口号或通配地址时改为本机地址，socket 路径加 unix: 前缀
+    fn fastcgi_pass_for(listen: &str) -> String {
+        if listen.parse::<u16>().is_ok() {
+            return format!("127.0.0.1:{}", listen);
+        }
+        if let Some(port) = listen
+            .strip_prefix("0.0.0.0:")
+            .or_else(|| listen.strip_prefix("[::]:"))
+        {
+            return format!("127.0.0.1:{}", port);
+        }
+        if Path::new(listen).is_absolute() {
+            return format!("unix:{}", listen);
+        }
+        listen.to_string()
+    }
+
+    /// listen 支持 host:port、端口号，Unix 下还支持 socket 绝对路径
+    fn validate_fpm_listen(listen: &str) -> Result<()> {
+        if listen.is_empty() || listen.contains(|c: char| c.is_whitespace() || c == ';') {
+            return Err(anyhow!("无效的 PHP-FPM 监听地址: {}", listen));
+        }
+        if !cfg!(target_os = "windows") && Path::new(listen).is_absolute() {
+            return Ok(());
+        }
+        let port = listen.rsplit_once(':').map(|(_, p)| p).unwrap_or(listen);
+        match port.parse::<u16>() {
+            Ok(port) if port > 0 => Ok(()),
+            _ => Err(anyhow!("无效的 PHP-FPM 监听地址: {}", listen)),
+        }
+    }
+
+    /// metadata 中配置了监听地址时写入 php-fpm.conf
+    fn sync_fpm_listen(&self, environment_id: &str, service_data: &ServiceData) -> Result<()> {
+        let Some(listen) = Self::metadata_listen(service_data) else {
+            return Ok(());
+        };
+        let fpm_config_path = self
+            .get_service_data_folder(environment_id, &service_data.version)
+            .join("php-fpm.conf");
+        if Self::read_ini_value(&fpm_config_path, "listen").as_deref() == Some(listen.as_str()) {
+            return Ok(());
+        }
+        Self::validate_fpm_listen(&listen)?;
+        let content = std::fs::read_to_string(&fpm_config_path)?;
+        std::fs::write(
+            &fpm_config_path,
+            Self::upsert_ini_value(&content, "listen", &listen),
+        )?;
+        log::info!("PHP-FPM 监听地址已同步为 {}", listen);
+        Ok(())
+    }
+
+    fn metadata_listen(service_data: &ServiceData) -> Option<String> {
+        service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get(FPM_LISTEN_METADATA_KEY))
+            .and_then(|v| v.as_str())
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .map(ToString::to_string)
+    }
+
+    /// 替换第一个未注释的 `key = value` 行，不存在则追加到末尾
+    fn upsert_ini_value(content: &str, key: &str, value: &str) -> String {
+        let mut replaced = false;
+        let mut lines: Vec<String> = content
+            .lines()
+            .map(|line| {
+                if !replaced && Self::parse_ini_line(line).map(|(k, _)| k) == Some(key) {
+                    replaced = true;
+                    format!("{} = {}", key, value)
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect();
+        if !replaced {
+            lines.push(format!("{} = {}", key, value));
+        }
+        lines.join("\n") + "\n"
+    }
+
+    fn parse_ini_line(line: &str) -> Option<(&str, &str)> {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with(';') || trimmed.starts_with('[') {
+            return None;
+        }
+        let (k, v) = trimmed.split_once('=')?;
+        Some((k.trim(), v.trim().trim_matches('"')))
+    }
+
+    fn read_ini_value(path: &Path, key: &str) -> Option<String> {
+        let content = std::fs::read_to_string(path).ok()?;
+        content
+            .lines()
+            .filter_map(Self::parse_ini_line)
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.to_string())
+    }
+
+    fn get_runtime_config(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<PhpRuntimeConfig> {
+        let service_data_folder =
+            self.get_service_data_folder(environment_id, &service_data.version);
+        let ini_path = service_data_folder.join("php.ini");
+        let fpm_config_path = service_data_folder.join("php-fpm.conf");
+
+        let listen = Self::metadata_listen(service_data)
+            .or_else(|| Self::read_ini_value(&fpm_config_path, "listen"))
+            .unwrap_or_else(|| DEFAULT_FPM_LISTEN.to_string());
+        let pid_path = Self::read_ini_value(&fpm_config_path, "pid")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| service_data_folder.join("run").join("php-fpm.pid"));
+        let log_path = Self::read_ini_value(&fpm_config_path, "error_log").unwrap_or_else(|| {
+            service_data_folder
+                .join("logs")
+                .join("php-fpm.log")
+                .to_string_lossy()
+                .to_string()
+        });
+        let memory_limit = Self::read_ini_value(&ini_path, "memory_limit")
+            .unwrap_or_else(|| DEFAULT_MEMORY_LIMIT.to_string());
+        let extension_dir = Self::read_ini_value(&ini_path, "extension_dir").unwrap_or_default();
+
+        Ok(PhpRuntimeConfig {
+            ini_path: ini_path.to_string_lossy().to_string(),
+            fpm_config_path: fpm_config_path.to_string_lossy().to_string(),
+            pid_path,
+            log_path,
+            listen,
+            memory_limit,
+            extension_dir,
+        })
+    }
+
+    /// 读取 pid 文件并确认进程仍存活
+    fn read_running_pid(&self, config: &PhpRuntimeConfig) -> Option<u32> {
+        let pid = std::fs::read_to_string(&config.pid_path)
+            .ok()?
+            .trim()
+            .parse::<u32>()
+            .ok()?;
+        if SystemInfoManager::global().is_process_alive(pid) {
+            Some(pid)
+        } else {
+            None
+        }
+    }
+
+    fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<()> {
+        let file =
+            std::fs::File::open(archive_path).map_err(|e| anyhow!("无法打开 zip 文件: {}", e))?;
+        let mut archive =
+            zip::ZipArchive::new(file).map_err(|e| anyhow!("无法读取 zip 文件: {}", e))?;
+
+        // 官方 Windows 包无顶层目录，php-archive 包可能有，存在公共前缀时剥去
+        let strip_prefix: Option<String> = {
+            let names: Vec<String> = archive.file_names().map(|n| n.to_string()).collect();
+            names
+                .first()
+                .and_then(|n| n.split_once('/').map(|(top, _)| top.to_string()))
+                .filter(|top| {
+                    !top.is_empty() && names.iter().all(|n| n.starts_with(&format!("{}/", top)))
+                })
+        };
+
+        for i in 0..archive.len() {
+            let mut file = archive
+                .by_index(i)
+                .map_err(|e| anyhow!("读取 zip 条目失败: {}", e))?;
+
+            let raw_name = file.name().to_string();
+            if raw_name.contains("__MACOSX") || raw_name.ends_with(".DS_Store") {
+                continue;
+            }
+
+            let relative = match strip_prefix {
+                Some(ref prefix) => raw_name
+                    .strip_prefix(&format!("{}/", prefix))
+                    .unwrap_or(&raw_name)
+                    .to_string(),
+                None => raw_name.clone(),
+            };
+            if relative.is_empty() {
+                continue;
+            }
+
+            let out_path = dest_dir.join(&relative);
+            if file.is_dir() {
+                std::fs::create_dir_all(&out_path)?;
+            } else {
+                if let Some(parent) = out_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                let mut out_file = std::fs::File::create(&out_path)
+                    .map_err(|e| anyhow!("创建文件失败 {:?}: {}", out_path, e))?;
+                std::io::copy(&mut file, &mut out_file)
+                    .map_err(|e| anyhow!("写入文件失败 {:?}: {}", out_path, e))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct PhpRuntimeConfig {
+    ini_path: String,
+    fpm_config_path: String,
+    pid_path: PathBuf,
+    log_path: String,
+    listen: String,
+    memory_limit: String,
+    extension_dir: String,
+}
+
+impl ServiceLifecycle for PhpService {
+    fn is_installed(&self, version: &str) -> bool {
+        PhpService::is_installed(self, version)
+    }
+
+    fn available_versions(&self) -> Vec<String> {
+        self.get_available_versions()
+            .into_iter()
+            .map(|v| v.version)
+            .collect()
+    }
+
+    fn download_and_install<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<DownloadResult>> {
+        Box::pin(PhpService::download_and_install(self, version))
+    }
+
+    fn cancel_download(&self, version: &str) -> Result<()> {
+        PhpService::cancel_download(self, version)
+    }
+
+    fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        PhpService::get_download_progress(self, version)
+    }
+
+    fn archive_dir(&self, version: &str) -> Option<PathBuf> {
+        Some(self.get_install_path(version))
+    }
+
+    fn extract_and_install<'a>(
+        &'a self,
+        task: &'a DownloadTask,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(PhpService::extract_and_install(self, task, version))
+    }
+
+    fn start_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        self.start_php_fpm(environment_id, service_data)
+    }
+
+    fn stop_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        self.stop_php_fpm(environment_id, service_data)
+    }
+
+    fn restart_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        self.restart_php_fpm(environment_id, service_data)
+    }
+
+    fn get_service_status(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        self.get_php_fpm_status(environment_id, service_data)
+    }
+}
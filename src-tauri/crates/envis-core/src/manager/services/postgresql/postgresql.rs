@@ -1,9 +1,17 @@
 use crate::manager::app_config_manager::AppConfigManager;
 use crate::manager::env_serv_data_manager::ServiceDataResult;
-use crate::manager::services::{DownloadManager, DownloadResult, DownloadTask};
+use crate::manager::service_supervisor::ServiceSupervisor;
+use crate::manager::services::health::{self, ServiceHealth};
+use crate::manager::services::launch::OutputCapture;
+use crate::manager::services::shutdown::{self, GracefulStop};
+use crate::manager::services::traits::ServiceLifecycle;
+use crate::manager::services::{
+    install_callback, remove_partial_install, DownloadManager, DownloadResult, DownloadTask,
+};
 use crate::types::ServiceData;
 use crate::utils::create_command;
 use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
 use serde_json::Value;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -14,6 +22,9 @@ use std::process::Command;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, OnceLock};
 
+/// PostgreSQL 默认端口，postgresql.conf 与 metadata 均未设置时使用
+pub const DEFAULT_POSTGRESQL_PORT: u16 = 5432;
+
 /// PostgreSQL 版本信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostgresqlVersion {
@@ -21,6 +32,62 @@ pub struct PostgresqlVersion {
     pub date: String,
 }
 
+/// pg_dump 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DumpFormat {
+    /// 纯 SQL 文本，使用 psql 恢复
+    Plain,
+    /// 自定义压缩格式，使用 pg_restore 恢复
+    Custom,
+    /// 目录格式（每张表一个文件）
+    Directory,
+    /// tar 归档
+    Tar,
+}
+
+impl DumpFormat {
+    /// 对应 pg_dump 的 --format 参数
+    fn pg_dump_arg(&self) -> &'static str {
+        match self {
+            DumpFormat::Plain => "plain",
+            DumpFormat::Custom => "custom",
+            DumpFormat::Directory => "directory",
+            DumpFormat::Tar => "tar",
+        }
+    }
+
+    /// 根据备份文件内容推断格式
+    fn detect(backup_path: &Path) -> Result<Self> {
+        if backup_path.is_dir() {
+            return Ok(DumpFormat::Directory);
+        }
+
+        let mut header = [0u8; 262];
+        let read = {
+            use std::io::Read;
+            let mut file = File::open(backup_path)?;
+            let mut total = 0;
+            while total < header.len() {
+                let n = file.read(&mut header[total..])?;
+                if n == 0 {
+                    break;
+                }
+                total += n;
+            }
+            total
+        };
+
+        if read >= 5 && &header[..5] == b"PGDMP" {
+            Ok(DumpFormat::Custom)
+        } else if read >= 262 && &header[257..262] == b"ustar" {
+            Ok(DumpFormat::Tar)
+        } else {
+            Ok(DumpFormat::Plain)
+        }
+    }
+}
+
 /// 全局 PostgreSQL 服务管理器单例
 static GLOBAL_POSTGRESQL_SERVICE: OnceLock<Arc<PostgresqlService>> = OnceLock::new();
 
@@ -167,56 +234,13 @@ impl PostgresqlService {
         let download_manager = DownloadManager::global();
 
         let version_for_callback = version.to_string();
-        let success_callback = Arc::new(move |task: &DownloadTask| {
-            log::info!(
-                "PostgreSQL {} 下载完成: {} - {}",
-                version_for_callback,
-                task.id,
-                task.filename
-            );
-            let task_for_spawn = task.clone();
-            let version_for_spawn = version_for_callback.clone();
-            let service_for_spawn = PostgresqlService::global();
-
-            tokio::spawn(async move {
-                let download_manager = DownloadManager::global();
-                if let Err(e) = download_manager.update_task_status(
-                    &task_for_spawn.id,
-                    crate::manager::services::DownloadStatus::Installing,
-                    None,
-                ) {
-                    log::error!("更新任务状态失败: {}", e);
-                } else {
-                    log::info!("PostgreSQL {} 开始安装", version_for_spawn);
-                }
-
-                match service_for_spawn
-                    .extract_and_install(&task_for_spawn, &version_for_spawn)
+        let success_callback = install_callback(format!("PostgreSQL {}", version), move |task| {
+            let version = version_for_callback.clone();
+            async move {
+                PostgresqlService::global()
+                    .extract_and_install(&task, &version)
                     .await
-                {
-                    Ok(_) => {
-                        if let Err(e) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            crate::manager::services::DownloadStatus::Installed,
-                            None,
-                        ) {
-                            log::error!("更新任务状态失败: {}", e);
-                        } else {
-                            log::info!("PostgreSQL {} 安装成功", version_for_spawn);
-                        }
-                    }
-                    Err(e) => {
-                        if let Err(update_err) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            crate::manager::services::DownloadStatus::Failed,
-                            Some(format!("安装失败: {}", e)),
-                        ) {
-                            log::error!("更新任务状态失败: {}", update_err);
-                        }
-                        log::error!("PostgreSQL {} 安装失败: {}", version_for_spawn, e);
-                    }
-                }
-            });
+            }
         });
 
         match download_manager
@@ -246,6 +270,16 @@ impl PostgresqlService {
 
     /// 解压并安装 PostgreSQL
     pub async fn extract_and_install(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let result = self.install_from_archive(task, version).await;
+        if result.is_err() {
+            // 解压安装失败时清理残缺的安装目录
+            remove_partial_install(&self.get_install_path(version));
+        }
+        result
+    }
+
+    /// 将下载的压缩包解压到安装目录
+    async fn install_from_archive(&self, task: &DownloadTask, version: &str) -> Result<()> {
         let mut archive_path = task.target_path.clone();
         let install_dir = self.get_install_path(version);
 
@@ -517,6 +551,10 @@ impl PostgresqlService {
 
         let config_path = data_dir.join("postgresql.conf");
         let metadata = service_data.metadata.get_or_insert_with(HashMap::new);
+        metadata.insert(
+            "POSTGRESQL_DATA".to_string(),
+            Value::String(data_dir.to_string_lossy().to_string()),
+        );
         metadata.insert(
             "POSTGRESQL_CONFIG".to_string(),
             Value::String(config_path.to_string_lossy().to_string()),
@@ -536,25 +574,69 @@ impl PostgresqlService {
         let log_path = self.get_log_path("", service_data);
         self.update_postgresql_conf(&config_path, port as u16, &bind_address, &log_path)?;
 
+        let metadata = service_data.metadata.get_or_insert_with(HashMap::new);
+        metadata.insert(
+            "POSTGRESQL_PORT".to_string(),
+            Value::String(port.to_string()),
+        );
+
         Ok(())
     }
 
     /// 设置日志路径
+    ///
+    /// 只改写 postgresql.conf 中的 logging_collector / log_directory / log_filename，
+    /// 其余配置保持不变。相对路径按 PostgreSQL 的规则相对数据目录解析。
     pub fn set_log_path(&self, service_data: &mut ServiceData, log_path: &str) -> Result<()> {
-        if log_path.trim().is_empty() {
+        let log_path = log_path.trim();
+        if log_path.is_empty() {
             return Err(anyhow!("日志路径不能为空"));
         }
 
         let config_path = self.get_config_path(service_data);
-        let port = self.get_port(service_data) as u16;
-        let bind_address = self.get_host(service_data);
-        self.update_postgresql_conf(
+        if !config_path.exists() {
+            return Err(anyhow!("PostgreSQL 配置文件不存在，请先初始化"));
+        }
+
+        let input_path = PathBuf::from(log_path);
+        let file_name = input_path
+            .file_name()
+            .and_then(|v| v.to_str())
+            .ok_or_else(|| anyhow!("日志路径缺少文件名"))?
+            .to_string();
+        let data_dir = self.get_data_dir("", service_data);
+        let resolved_path = if input_path.is_absolute() {
+            input_path.clone()
+        } else {
+            data_dir.join(&input_path)
+        };
+
+        // 相对路径原样写入 log_directory，保持与 PostgreSQL 的解析方式一致
+        let log_directory = match input_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => to_unix_path_string(parent),
+            _ if input_path.is_absolute() => to_unix_path_string(&data_dir),
+            _ => ".".to_string(),
+        };
+
+        if let Some(parent) = resolved_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        Self::set_config_values(
             &config_path,
-            port,
-            &bind_address,
-            &PathBuf::from(log_path),
+            &[
+                ("logging_collector", "on".to_string()),
+                ("log_directory", Self::quote_literal(&log_directory)),
+                ("log_filename", Self::quote_literal(&file_name)),
+            ],
         )?;
 
+        let metadata = service_data.metadata.get_or_insert_with(HashMap::new);
+        metadata.insert(
+            "POSTGRESQL_LOG".to_string(),
+            Value::String(resolved_path.to_string_lossy().to_string()),
+        );
+
         Ok(())
     }
 
@@ -583,16 +665,25 @@ impl PostgresqlService {
             Err(_) => false,
         };
 
-        let status = if running {
-            crate::types::ServiceStatus::Running
-        } else {
-            crate::types::ServiceStatus::Stopped
+        // 健康检查：端口连通后使用 pg_isready 检测是否接受连接
+        let host = self.get_host_with_env(environment_id, service_data);
+        let port = self.get_port_with_env(environment_id, service_data);
+        let port_latency = u16::try_from(port)
+            .ok()
+            .and_then(|p| health::check_tcp_port(&host, p));
+        let ready_latency = port_latency.and_then(|_| self.check_ready(service_data, &host, port));
+        let health = ServiceHealth {
+            process: running,
+            port_open: port_latency.is_some(),
+            responding: ready_latency.is_some(),
+            latency_ms: ready_latency.or(port_latency),
         };
 
-        let data = serde_json::json!({ 
+        let mut data = serde_json::json!({
             "isRunning": running,
-            "status": status
+            "port": port,
         });
+        health.merge_into(&mut data);
         Ok(ServiceDataResult {
             success: true,
             message: "获取状态成功".to_string(),
@@ -684,7 +775,7 @@ impl PostgresqlService {
                 .arg("-D")
                 .arg(&data_dir)
                 .arg("-U")
-                .arg("postgres")
+                .arg(self.get_superuser(service_data))
                 .arg("-A")
                 .arg("scram-sha-256")
                 .arg("--pwfile")
@@ -749,6 +840,7 @@ impl PostgresqlService {
                 "dataPath": data_dir,
                 "logPath": final_log_path,
                 "superPassword": super_password,
+                "superuser": self.get_superuser(service_data),
                 "port": final_port.to_string(),
                 "bindAddress": final_bind,
             })),
@@ -761,6 +853,7 @@ impl PostgresqlService {
         environment_id: &str,
         service_data: &ServiceData,
     ) -> Result<ServiceDataResult> {
+        ServiceSupervisor::global().clear_intentional_stop(&service_data.id);
         let pg_ctl = self.get_pg_ctl_bin(service_data);
         let data_dir = self.get_data_dir(environment_id, service_data);
         let log_path = self.get_log_path(environment_id, service_data);
@@ -842,31 +935,35 @@ impl PostgresqlService {
             log::info!("PostgreSQL 启动命令: {}", cmd_str);
         }
 
-        let output = cmd.output()?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        // pg_ctl 的输出写入 logs/postgresql-stdout.log，不使用管道，避免 postgres 子进程继承管道导致阻塞
+        let capture = OutputCapture::new(
+            &self.get_service_data_folder(environment_id, service_data),
+            "postgresql",
+        )?;
+        capture.attach(&mut cmd)?;
+        let status = cmd.spawn()?.wait()?;
+        let output = capture.read_output();
 
         log::info!(
-            "PostgreSQL pg_ctl 执行完成: env={}, exit_code={}, stdout={}, stderr={}",
+            "PostgreSQL pg_ctl 执行完成: env={}, exit_code={}, output={}",
             environment_id,
-            output.status.code().unwrap_or(-1),
-            stdout.trim(),
-            stderr.trim()
+            status.code().unwrap_or(-1),
+            output
         );
 
-        if !output.status.success() {
-            let stderr_trimmed = stderr.trim().to_string();
+        if !status.success() {
             log::error!(
-                "PostgreSQL 启动失败: env={}, version={}, stderr={}",
+                "PostgreSQL 启动失败: env={}, version={}, output={}",
                 environment_id,
                 service_data.version,
-                stderr_trimmed
+                output
             );
             return Ok(ServiceDataResult {
                 success: false,
-                message: format!("PostgreSQL 启动失败: {}", Self::build_start_failure_detail(&stderr_trimmed, &log_path)),
-                data: None,
+                message: format!("PostgreSQL 启动失败: {}", Self::build_start_failure_detail(&output, &log_path)),
+                data: Some(serde_json::json!({
+                    "outputLog": capture.path().to_string_lossy().to_string(),
+                })),
             });
         }
 
@@ -883,65 +980,72 @@ impl PostgresqlService {
         })
     }
 
-    /// 停止 PostgreSQL 服务
+    /// 停止 PostgreSQL 服务：使用 pg_ctl stop -m fast 正常关闭，超时后强制结束 postmaster 进程
     pub fn stop_service(
         &self,
         environment_id: &str,
         service_data: &ServiceData,
     ) -> Result<ServiceDataResult> {
+        ServiceSupervisor::global().mark_intentional_stop(&service_data.id);
         let pg_ctl = self.get_pg_ctl_bin(service_data);
         let data_dir = self.get_data_dir(environment_id, service_data);
+        let timeout = shutdown::stop_timeout(service_data);
 
-        if pg_ctl.exists() && data_dir.exists() {
+        let command = if !pg_ctl.exists() {
+            Err(anyhow!("pg_ctl 可执行文件不存在"))
+        } else if !data_dir.exists() {
+            Err(anyhow!("数据目录不存在: {}", data_dir.display()))
+        } else {
             let mut cmd = create_command(&pg_ctl);
             Self::apply_runtime_lib_env(&mut cmd, &self.get_install_path(&service_data.version));
-            let output = cmd
-                .arg("-D")
+            cmd.arg("-D")
                 .arg(&data_dir)
                 .arg("stop")
                 .arg("-m")
                 .arg("fast")
-                .output()?;
-
-            if output.status.success() {
-                return Ok(ServiceDataResult {
-                    success: true,
-                    message: "PostgreSQL 服务停止成功".to_string(),
-                    data: None,
-                });
-            }
-        }
-
-        #[cfg(target_os = "windows")]
-        let output = create_command("taskkill")
-            .args(["/IM", "postgres.exe", "/F"])
-            .output()?;
-
-        #[cfg(not(target_os = "windows"))]
-        let output = create_command("pkill").arg("-f").arg("postgres").output()?;
+                .arg("-w")
+                .arg("-t")
+                .arg(timeout.as_secs().to_string());
+            Ok(cmd)
+        };
 
-        if !output.status.success() {
-            return Ok(ServiceDataResult {
-                success: false,
-                message: format!("PostgreSQL 停止失败: {}", String::from_utf8_lossy(&output.stderr)),
-                data: None,
-            });
+        Ok(GracefulStop {
+            service_name: "PostgreSQL",
+            method: "pg_ctl stop -m fast",
+            command,
+            // postmaster.pid 首行为主进程 PID
+            pid: shutdown::read_pid_file(&data_dir.join("postmaster.pid")),
+            port: u16::try_from(self.get_port_with_env(environment_id, service_data)).ok(),
+            timeout,
         }
-
-        Ok(ServiceDataResult {
-            success: true,
-            message: "PostgreSQL 服务停止成功".to_string(),
-            data: None,
-        })
+        .run())
     }
 
-    /// 重启 PostgreSQL 服务
+    /// 重启 PostgreSQL 服务，等待端口释放后再启动
     pub fn restart_service(
         &self,
         environment_id: &str,
         service_data: &ServiceData,
     ) -> Result<ServiceDataResult> {
-        self.stop_service(environment_id, service_data)?;
+        let stop_res = self.stop_service(environment_id, service_data)?;
+        if !stop_res.success {
+            return Ok(stop_res);
+        }
+
+        if let Ok(port) = u16::try_from(self.get_port_with_env(environment_id, service_data)) {
+            let timeout = shutdown::stop_timeout(service_data);
+            if !shutdown::wait_for_port_release(port, timeout) {
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: format!(
+                        "端口 {} 在 {} 秒内未释放，无法重启 PostgreSQL",
+                        port,
+                        timeout.as_secs()
+                    ),
+                    data: None,
+                });
+            }
+        }
         self.start_service(environment_id, service_data)
     }
 
@@ -1046,7 +1150,7 @@ impl PostgresqlService {
             "-p".to_string(),
             port.clone(),
             "-U".to_string(),
-            "postgres".to_string(),
+            self.get_superuser(service_data),
             "postgres".to_string(),
         ];
 
@@ -1375,13 +1479,26 @@ ORDER BY r.rolname, d.datname
     }
 
     fn get_config_path_with_env(&self, environment_id: &str, service_data: &ServiceData) -> PathBuf {
+        if let Some(config_path) = Self::metadata_str(service_data, "POSTGRESQL_CONFIG") {
+            return PathBuf::from(config_path);
+        }
+
+        Self::metadata_str(service_data, "POSTGRESQL_DATA")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| self.get_default_data_dir_with_env(environment_id, service_data))
+            .join("postgresql.conf")
+    }
+
+    /// 读取非空的 metadata 字符串值
+    fn metadata_str(service_data: &ServiceData, key: &str) -> Option<String> {
         service_data
             .metadata
             .as_ref()
-            .and_then(|m| m.get("POSTGRESQL_CONFIG"))
+            .and_then(|m| m.get(key))
             .and_then(|v| v.as_str())
-            .map(PathBuf::from)
-            .unwrap_or_else(|| self.get_default_data_dir_with_env(environment_id, service_data).join("postgresql.conf"))
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .map(ToString::to_string)
     }
 
     fn get_config_path(&self, service_data: &ServiceData) -> PathBuf {
@@ -1419,6 +1536,10 @@ ORDER BY r.rolname, d.datname
     }
 
     fn get_data_dir(&self, environment_id: &str, service_data: &ServiceData) -> PathBuf {
+        if let Some(data_dir) = Self::metadata_str(service_data, "POSTGRESQL_DATA") {
+            return PathBuf::from(data_dir);
+        }
+
         let config_path = self.get_config_path_with_env(environment_id, service_data);
         if let Some(parent) = config_path.parent() {
             return parent.to_path_buf();
@@ -1431,8 +1552,18 @@ ORDER BY r.rolname, d.datname
         self.read_config_content_with_env(environment_id, service_data)
             .as_deref()
             .and_then(|content| Self::parse_config_value(content, "port"))
+            .or_else(|| Self::metadata_str(service_data, "POSTGRESQL_PORT"))
             .and_then(|value| value.parse::<i64>().ok())
-            .unwrap_or(5432)
+            .unwrap_or(DEFAULT_POSTGRESQL_PORT as i64)
+    }
+
+    /// 读取 postgresql.conf 中设置的端口，文件不存在或未设置时返回 None
+    pub fn read_config_port(config_path: &Path) -> Option<u16> {
+        fs::read_to_string(config_path)
+            .ok()
+            .as_deref()
+            .and_then(|content| Self::parse_config_value(content, "port"))
+            .and_then(|value| value.parse::<u16>().ok())
     }
 
     fn get_port(&self, service_data: &ServiceData) -> i64 {
@@ -1475,17 +1606,21 @@ ORDER BY r.rolname, d.datname
             return data_dir.join(log_filename);
         }
 
-        data_dir.join("postgresql.log")
+        Self::metadata_str(service_data, "POSTGRESQL_LOG")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| data_dir.join("postgresql.log"))
+    }
+
+    fn get_superuser(&self, service_data: &ServiceData) -> String {
+        Self::metadata_str(service_data, "POSTGRESQL_SUPERUSER")
+            .unwrap_or_else(|| "postgres".to_string())
     }
 
     fn get_super_password(&self, service_data: &ServiceData) -> String {
-        service_data
-            .metadata
-            .as_ref()
-            .and_then(|m| m.get("POSTGRESQL_SUPER_PASSWORD"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string()
+        // 旧版本将密码保存在 POSTGRESQL_SUPER_PASSWORD 中
+        Self::metadata_str(service_data, "POSTGRESQL_PASSWORD")
+            .or_else(|| Self::metadata_str(service_data, "POSTGRESQL_SUPER_PASSWORD"))
+            .unwrap_or_default()
     }
 
     fn platform_binary_name(base_name: &str) -> String {
@@ -1621,6 +1756,28 @@ ORDER BY r.rolname, d.datname
         }
     }
 
+    /// 使用 pg_isready 检测服务是否接受连接，成功时返回耗时（毫秒）
+    fn check_ready(&self, service_data: &ServiceData, host: &str, port: i64) -> Option<u64> {
+        let install_path = self.get_install_path(&service_data.version);
+        let pg_isready = install_path
+            .join("bin")
+            .join(Self::platform_binary_name("pg_isready"));
+        if !pg_isready.exists() {
+            return None;
+        }
+
+        let mut cmd = create_command(&pg_isready);
+        Self::apply_runtime_lib_env(&mut cmd, &install_path);
+        cmd.arg("-h")
+            .arg(health::connect_host(host))
+            .arg("-p")
+            .arg(port.to_string())
+            .arg("-t")
+            .arg("2");
+
+        health::timed(|| cmd.output().map(|o| o.status.success()).unwrap_or(false))
+    }
+
     fn get_pg_ctl_bin(&self, service_data: &ServiceData) -> PathBuf {
         let install_path = self.get_install_path(&service_data.version);
         if cfg!(target_os = "windows") {
@@ -1775,6 +1932,51 @@ ORDER BY r.rolname, d.datname
         Ok(())
     }
 
+    /// 取配置行的键名（支持 `key = value` 与 `key value` 两种写法），注释和空行返回 None
+    fn config_line_key(line: &str) -> Option<&str> {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            return None;
+        }
+        trimmed
+            .split(|c: char| c == '=' || c.is_whitespace())
+            .next()
+            .filter(|key| !key.is_empty())
+    }
+
+    /// 就地改写 postgresql.conf 中的指定键：保留第一次出现的位置并删除重复项，
+    /// 不存在的键追加到文件末尾，其余内容原样保留
+    fn set_config_values(config_path: &Path, values: &[(&str, String)]) -> Result<()> {
+        let existing = fs::read_to_string(config_path)?;
+        let mut written: Vec<&str> = Vec::new();
+        let mut lines: Vec<String> = Vec::new();
+
+        for line in existing.lines() {
+            let matched =
+                Self::config_line_key(line).and_then(|key| values.iter().find(|(k, _)| *k == key));
+            match matched {
+                Some((key, value)) => {
+                    if !written.contains(key) {
+                        lines.push(format!("{} = {}", key, value));
+                        written.push(*key);
+                    }
+                }
+                None => lines.push(line.to_string()),
+            }
+        }
+
+        for (key, value) in values {
+            if !written.contains(key) {
+                lines.push(format!("{} = {}", key, value));
+            }
+        }
+
+        let mut content = lines.join("\n");
+        content.push('\n');
+        fs::write(config_path, content)?;
+        Ok(())
+    }
+
     fn execute_psql(
         &self,
         service_data: &ServiceData,
@@ -1797,7 +1999,7 @@ ORDER BY r.rolname, d.datname
             .arg("-p")
             .arg(&port)
             .arg("-U")
-            .arg("postgres")
+            .arg(self.get_superuser(service_data))
             .arg("-At")
             .arg("-F")
             .arg("|")
@@ -1855,4 +2057,300 @@ ORDER BY r.rolname, d.datname
     fn escape_applescript_string(value: &str) -> String {
         value.replace('\\', "\\\\").replace('"', "\\\"")
     }
+
+    /// 使用 pg_dump 导出单个数据库
+    pub fn dump_database(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        database_name: String,
+        output_path: String,
+        format: DumpFormat,
+    ) -> Result<ServiceDataResult> {
+        let db_name = database_name.trim();
+        if db_name.is_empty() {
+            return Err(anyhow!("数据库名称不能为空"));
+        }
+        self.ensure_valid_identifier(db_name)?;
+
+        let output_path = PathBuf::from(output_path.trim());
+        if output_path.as_os_str().is_empty() {
+            return Err(anyhow!("导出路径不能为空"));
+        }
+        // 目录格式要求目标目录不存在（或为空），其余格式会直接覆盖文件
+        if format == DumpFormat::Directory
+            && output_path.is_dir()
+            && fs::read_dir(&output_path)?.next().is_some()
+        {
+            return Err(anyhow!(
+                "目录格式导出的目标目录必须为空: {}",
+                output_path.display()
+            ));
+        }
+        if let Some(parent) = output_path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+
+        let install_path = self.get_install_path(&service_data.version);
+        let pg_dump = install_path
+            .join("bin")
+            .join(Self::platform_binary_name("pg_dump"));
+        if !pg_dump.exists() {
+            return Err(anyhow!("pg_dump 可执行文件不存在"));
+        }
+
+        let mut cmd = create_command(&pg_dump);
+        Self::apply_runtime_lib_env(&mut cmd, &install_path);
+        self.apply_connection_args(&mut cmd, environment_id, service_data);
+        cmd.arg("--format")
+            .arg(format.pg_dump_arg())
+            .arg("--file")
+            .arg(&output_path)
+            .arg(db_name);
+
+        let output = cmd.output()?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "导出数据库失败: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        let size: u64 = walkdir::WalkDir::new(&output_path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.metadata().ok())
+            .filter(|m| m.is_file())
+            .map(|m| m.len())
+            .sum();
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("数据库 '{}' 导出成功", db_name),
+            data: Some(serde_json::json!({
+                "database": db_name,
+                "outputPath": output_path.to_string_lossy(),
+                "format": format,
+                "size": size,
+            })),
+        })
+    }
+
+    /// 恢复单个数据库：自定义/目录/tar 格式使用 pg_restore，纯 SQL 使用 psql
+    pub fn restore_database(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        database_name: String,
+        backup_path: String,
+    ) -> Result<ServiceDataResult> {
+        let db_name = database_name.trim();
+        if db_name.is_empty() {
+            return Err(anyhow!("数据库名称不能为空"));
+        }
+        self.ensure_valid_identifier(db_name)?;
+
+        let backup_path = PathBuf::from(backup_path.trim());
+        if !backup_path.exists() {
+            return Err(anyhow!("备份文件不存在: {}", backup_path.display()));
+        }
+        let format = DumpFormat::detect(&backup_path)?;
+
+        // 目标库不存在时先创建
+        let exists_sql = format!(
+            "SELECT 1 FROM pg_database WHERE datname = {}",
+            Self::quote_literal(db_name)
+        );
+        if self
+            .execute_psql(service_data, Some("postgres"), &exists_sql)?
+            .trim()
+            .is_empty()
+        {
+            let create_sql = format!("CREATE DATABASE {}", Self::quote_ident(db_name));
+            self.execute_psql(service_data, Some("postgres"), &create_sql)?;
+        }
+
+        let install_path = self.get_install_path(&service_data.version);
+        let mut cmd = if format == DumpFormat::Plain {
+            let psql = self.get_psql_bin(service_data);
+            if !psql.exists() {
+                return Err(anyhow!("psql 可执行文件不存在"));
+            }
+            let mut cmd = create_command(&psql);
+            Self::apply_runtime_lib_env(&mut cmd, &install_path);
+            self.apply_connection_args(&mut cmd, environment_id, service_data);
+            cmd.arg("-d")
+                .arg(db_name)
+                .arg("-q")
+                .arg("-v")
+                .arg("ON_ERROR_STOP=1")
+                .arg("-f")
+                .arg(&backup_path);
+            cmd
+        } else {
+            let pg_restore = install_path
+                .join("bin")
+                .join(Self::platform_binary_name("pg_restore"));
+            if !pg_restore.exists() {
+                return Err(anyhow!("pg_restore 可执行文件不存在"));
+            }
+            let mut cmd = create_command(&pg_restore);
+            Self::apply_runtime_lib_env(&mut cmd, &install_path);
+            self.apply_connection_args(&mut cmd, environment_id, service_data);
+            cmd.arg("--dbname")
+                .arg(db_name)
+                .arg("--clean")
+                .arg("--if-exists")
+                .arg("--no-owner")
+                .arg(&backup_path);
+            cmd
+        };
+
+        let output = cmd.output()?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "恢复数据库失败: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("数据库 '{}' 恢复成功", db_name),
+            data: Some(serde_json::json!({
+                "database": db_name,
+                "backupPath": backup_path.to_string_lossy(),
+                "format": format,
+            })),
+        })
+    }
+
+    /// 构建备份命令（pg_dumpall，输出到 stdout）
+    pub fn build_backup_command(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<Command> {
+        let install_path = self.get_install_path(&service_data.version);
+        let pg_dumpall = install_path
+            .join("bin")
+            .join(Self::platform_binary_name("pg_dumpall"));
+        if !pg_dumpall.exists() {
+            return Err(anyhow!("pg_dumpall 可执行文件不存在"));
+        }
+
+        let mut cmd = create_command(&pg_dumpall);
+        Self::apply_runtime_lib_env(&mut cmd, &install_path);
+        self.apply_connection_args(&mut cmd, environment_id, service_data);
+        Ok(cmd)
+    }
+
+    /// 构建恢复命令（psql 连接 postgres 库，从 stdin 读取 SQL）
+    pub fn build_restore_command(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<Command> {
+        let psql = self.get_psql_bin(service_data);
+        if !psql.exists() {
+            return Err(anyhow!("psql 可执行文件不存在"));
+        }
+
+        let mut cmd = create_command(&psql);
+        Self::apply_runtime_lib_env(&mut cmd, &self.get_install_path(&service_data.version));
+        self.apply_connection_args(&mut cmd, environment_id, service_data);
+        cmd.arg("-d").arg("postgres").arg("-q").arg("-f").arg("-");
+        Ok(cmd)
+    }
+
+    fn apply_connection_args(&self, cmd: &mut Command, environment_id: &str, service_data: &ServiceData) {
+        let host = self.get_host_with_env(environment_id, service_data);
+        let port = self.get_port_with_env(environment_id, service_data).to_string();
+        let super_password = self.get_super_password(service_data);
+
+        cmd.arg("-h")
+            .arg(&host)
+            .arg("-p")
+            .arg(&port)
+            .arg("-U")
+            .arg(self.get_superuser(service_data));
+
+        if !super_password.is_empty() {
+            cmd.env("PGPASSWORD", &super_password);
+        }
+    }
+}
+
+impl ServiceLifecycle for PostgresqlService {
+    fn is_installed(&self, version: &str) -> bool {
+        PostgresqlService::is_installed(self, version)
+    }
+
+    fn available_versions(&self) -> Vec<String> {
+        self.get_available_versions()
+            .into_iter()
+            .map(|v| v.version)
+            .collect()
+    }
+
+    fn download_and_install<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<DownloadResult>> {
+        Box::pin(PostgresqlService::download_and_install(self, version))
+    }
+
+    fn cancel_download(&self, version: &str) -> Result<()> {
+        PostgresqlService::cancel_download(self, version)
+    }
+
+    fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        PostgresqlService::get_download_progress(self, version)
+    }
+
+    fn archive_dir(&self, version: &str) -> Option<PathBuf> {
+        Some(self.get_install_path(version))
+    }
+
+    fn extract_and_install<'a>(
+        &'a self,
+        task: &'a DownloadTask,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(PostgresqlService::extract_and_install(self, task, version))
+    }
+
+    fn start_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        PostgresqlService::start_service(self, environment_id, service_data)
+    }
+
+    fn stop_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        PostgresqlService::stop_service(self, environment_id, service_data)
+    }
+
+    fn restart_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        PostgresqlService::restart_service(self, environment_id, service_data)
+    }
+
+    fn get_service_status(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        PostgresqlService::get_service_status(self, environment_id, service_data)
+    }
 }
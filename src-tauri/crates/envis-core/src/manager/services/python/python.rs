@@ -1,13 +1,17 @@
 use crate::manager::app_config_manager::AppConfigManager;
 use crate::manager::env_serv_data_manager::EnvServDataManager;
-use crate::manager::services::{DownloadManager, DownloadResult, DownloadTask};
+use crate::manager::services::traits::ServiceLifecycle;
+use crate::manager::services::{install_callback, DownloadManager, DownloadResult, DownloadTask};
 use crate::manager::shell_manamger::ShellManager;
 use crate::types::ServiceData;
 use crate::utils::create_command;
 use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use std::sync::{Arc, OnceLock};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{mpsc, Arc, OnceLock};
 use std::collections::HashSet;
 
 /// Python 安装模式
@@ -35,6 +39,39 @@ pub struct PythonVersion {
     pub date: String,
 }
 
+/// pip 已安装包信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipPackage {
+    pub name: String,
+    pub version: String,
+}
+
+/// Python 服务概要信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PythonInfo {
+    pub python_path: String,
+    /// python -V 输出
+    pub python_version: Option<String>,
+    /// pip -V 输出
+    pub pip_version: Option<String>,
+    /// sys.executable
+    pub executable: Option<String>,
+    /// ssl.OPENSSL_VERSION
+    pub openssl_version: Option<String>,
+    pub venv_count: usize,
+}
+
+/// pip 升级结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipUpgradeResult {
+    /// 升级后的 pip -V 输出
+    pub pip_version: Option<String>,
+    /// pip install 的输出
+    pub output: String,
+}
+
 /// 全局 Python 服务管理器单例
 static GLOBAL_PYTHON_SERVICE: OnceLock<Arc<PythonService>> = OnceLock::new();
 
@@ -265,6 +302,261 @@ impl PythonService {
         Ok(())
     }
 
+    /// 获取 venv 中的 pip 可执行文件路径
+    fn get_venv_pip_path(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        venv_name: &str,
+    ) -> Result<PathBuf> {
+        let venv_path = self.get_venvs_dir(environment_id, service_data)?.join(venv_name);
+        if !venv_path.exists() {
+            return Err(anyhow!("venv 不存在: {}", venv_path.display()));
+        }
+
+        let pip_path = if cfg!(target_os = "windows") {
+            venv_path.join("Scripts").join("pip.exe")
+        } else {
+            venv_path.join("bin").join("pip")
+        };
+        if !pip_path.exists() {
+            return Err(anyhow!("pip 不存在: {}", pip_path.display()));
+        }
+        Ok(pip_path)
+    }
+
+    /// 在指定 venv 中安装包，pip 的输出逐行通过 progress_callback(stream, line) 回调
+    pub fn install_packages(
+        &self,
+        progress_callback: impl Fn(&str, &str),
+        environment_id: &str,
+        service_data: &ServiceData,
+        venv_name: &str,
+        packages: Vec<String>,
+    ) -> Result<()> {
+        let packages: Vec<String> = packages
+            .into_iter()
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+        if packages.is_empty() {
+            return Err(anyhow!("包列表不能为空"));
+        }
+        // 禁止以 - 开头，避免被 pip 当作选项解析
+        if let Some(invalid) = packages.iter().find(|p| p.starts_with('-')) {
+            return Err(anyhow!("无效的包名: {}", invalid));
+        }
+
+        let pip_path = self.get_venv_pip_path(environment_id, service_data, venv_name)?;
+
+        let mut child = create_command(&pip_path)
+            .arg("install")
+            .args(&packages)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // stdout/stderr 分别在线程中读取，统一在当前线程回调
+        let (tx, rx) = mpsc::channel::<(&'static str, String)>();
+        let mut readers = Vec::new();
+        if let Some(stdout) = child.stdout.take() {
+            let tx = tx.clone();
+            readers.push(std::thread::spawn(move || {
+                for line in BufReader::new(stdout).lines().map_while(|l| l.ok()) {
+                    let _ = tx.send(("stdout", line));
+                }
+            }));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            let tx = tx.clone();
+            readers.push(std::thread::spawn(move || {
+                for line in BufReader::new(stderr).lines().map_while(|l| l.ok()) {
+                    let _ = tx.send(("stderr", line));
+                }
+            }));
+        }
+        drop(tx);
+
+        let mut stderr_lines = Vec::new();
+        for (stream, line) in rx {
+            progress_callback(stream, &line);
+            if stream == "stderr" {
+                stderr_lines.push(line);
+            }
+        }
+        for reader in readers {
+            let _ = reader.join();
+        }
+
+        let status = child.wait()?;
+        if !status.success() {
+            return Err(anyhow!("pip install 失败: {}", stderr_lines.join("\n")));
+        }
+        Ok(())
+    }
+
+    /// 列出指定 venv 中已安装的包
+    pub fn list_installed_packages(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        venv_name: &str,
+    ) -> Result<Vec<PipPackage>> {
+        let pip_path = self.get_venv_pip_path(environment_id, service_data, venv_name)?;
+
+        let output = create_command(&pip_path)
+            .args(["list", "--format=json", "--disable-pip-version-check"])
+            .output()?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "获取已安装包失败: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        let packages: Vec<PipPackage> = serde_json::from_slice(&output.stdout)?;
+        Ok(packages)
+    }
+
+    /// 获取 Python 服务概要信息：解释器、pip、OpenSSL 版本及 venv 数量
+    pub fn get_python_info(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<PythonInfo> {
+        let python_path = self.get_executable_path(&service_data.version);
+        if !python_path.exists() {
+            return Err(anyhow!("Python {} 未安装", service_data.version));
+        }
+
+        let pip_version = self
+            .get_pip_path(&service_data.version)
+            .and_then(|pip_path| Self::run_for_output(&pip_path, &["-V"]));
+
+        Ok(PythonInfo {
+            python_path: python_path.to_string_lossy().to_string(),
+            python_version: Self::run_for_output(&python_path, &["-V"]),
+            pip_version,
+            executable: Self::run_for_output(
+                &python_path,
+                &["-c", "import sys; print(sys.executable)"],
+            ),
+            openssl_version: Self::run_for_output(
+                &python_path,
+                &["-c", "import ssl; print(ssl.OPENSSL_VERSION)"],
+            ),
+            venv_count: self.list_venvs(environment_id, service_data)?.len(),
+        })
+    }
+
+    /// 升级安装目录中的 pip
+    pub fn upgrade_pip(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<PipUpgradeResult> {
+        let python_path = self.get_executable_path(&service_data.version);
+        if !python_path.exists() {
+            return Err(anyhow!("Python {} 未安装", service_data.version));
+        }
+        if self.get_pip_path(&service_data.version).is_none() {
+            return Err(anyhow!("Python {} 未安装 pip", service_data.version));
+        }
+        Self::run_pip_upgrade(&python_path)
+    }
+
+    /// 升级指定 venv 中的 pip
+    pub fn upgrade_pip_in_venv(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        venv_name: &str,
+    ) -> Result<PipUpgradeResult> {
+        let pip_path = self.get_venv_pip_path(environment_id, service_data, venv_name)?;
+        let python_path = pip_path.with_file_name(if cfg!(target_os = "windows") {
+            "python.exe"
+        } else {
+            "python"
+        });
+        if !python_path.exists() {
+            return Err(anyhow!(
+                "venv 中的 Python 不存在: {}",
+                python_path.display()
+            ));
+        }
+        Self::run_pip_upgrade(&python_path)
+    }
+
+    /// 通过 `python -m pip` 升级 pip，Windows 下运行中的 pip.exe 无法替换自身
+    fn run_pip_upgrade(python_path: &Path) -> Result<PipUpgradeResult> {
+        let output = create_command(python_path)
+            .args([
+                "-m",
+                "pip",
+                "install",
+                "--upgrade",
+                "pip",
+                "--disable-pip-version-check",
+            ])
+            .output()?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "升级 pip 失败: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        let pip_version = Self::run_for_output(python_path, &["-m", "pip", "-V"]);
+        log::info!("pip 升级完成: {:?}", pip_version);
+        Ok(PipUpgradeResult {
+            pip_version,
+            output: String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        })
+    }
+
+    /// 获取安装目录中的 pip 可执行文件路径
+    fn get_pip_path(&self, version: &str) -> Option<PathBuf> {
+        let install_path = self.get_install_path(version);
+        let candidates = if cfg!(target_os = "windows") {
+            vec![install_path.join("Scripts").join("pip.exe")]
+        } else {
+            vec![
+                install_path.join("bin").join("pip3"),
+                install_path.join("bin").join("pip"),
+            ]
+        };
+        candidates.into_iter().find(|p| p.exists())
+    }
+
+    /// 执行命令并返回输出（Python 2 的 -V 输出在 stderr），失败时返回 None
+    fn run_for_output(program: &Path, args: &[&str]) -> Option<String> {
+        let output = match create_command(program).args(args).output() {
+            Ok(output) => output,
+            Err(e) => {
+                log::warn!("执行 {} 失败: {}", program.display(), e);
+                return None;
+            }
+        };
+        if !output.status.success() {
+            log::warn!(
+                "执行 {} {:?} 失败: {}",
+                program.display(),
+                args,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return None;
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !stdout.is_empty() {
+            return Some(stdout);
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        (!stderr.is_empty()).then_some(stderr)
+    }
+
     /// 创建新的 Python 服务管理器（内部使用）
     fn new() -> Self {
         Self {}
@@ -425,70 +717,13 @@ impl PythonService {
 
         let download_manager = DownloadManager::global();
         let version_for_callback = version.to_string();
-        let mode_for_callback = mode;
-
-        let success_callback = Arc::new(move |task: &DownloadTask| {
-            log::info!(
-                "Python {} 下载完成: {} - {} (模式: {:?})",
-                version_for_callback,
-                task.id,
-                task.filename,
-                mode_for_callback
-            );
-
-            // 异步执行安装步骤
-            let task_for_spawn = task.clone();
-            let version_for_spawn = version_for_callback.clone();
-            let mode_for_spawn = mode_for_callback;
-            let service_for_spawn = PythonService::global();
-
-            tokio::spawn(async move {
-                // 更新状态为 Installing
-                let download_manager = DownloadManager::global();
-                if let Err(e) = download_manager.update_task_status(
-                    &task_for_spawn.id,
-                    crate::manager::services::DownloadStatus::Installing,
-                    None,
-                ) {
-                    log::error!("更新任务状态失败: {}", e);
-                } else {
-                    log::info!(
-                        "Python {} 开始安装 (模式: {:?})",
-                        version_for_spawn,
-                        mode_for_spawn
-                    );
-                }
-
-                // 执行安装（统一方法，根据模式选择）
-                match service_for_spawn
-                    .install(&task_for_spawn, &version_for_spawn, mode_for_spawn)
-                    .await
-                {
-                    Ok(_) => {
-                        // 安装成功，更新状态为 Installed
-                        if let Err(e) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            crate::manager::services::DownloadStatus::Installed,
-                            None,
-                        ) {
-                            log::error!("更新任务状态失败: {}", e);
-                        }
-                        log::info!("Python {} 安装成功", version_for_spawn);
-                    }
-                    Err(e) => {
-                        // 安装失败，更新状态为 Failed
-                        if let Err(update_err) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            crate::manager::services::DownloadStatus::Failed,
-                            Some(format!("安装失败: {}", e)),
-                        ) {
-                            log::error!("更新任务状态失败: {}", update_err);
-                        }
-                        log::error!("Python {} 安装失败: {}", version_for_spawn, e);
-                    }
-                }
-            });
-        });
+        let success_callback = install_callback(
+            format!("Python {} (模式: {:?})", version, mode),
+            move |task| {
+                let version = version_for_callback.clone();
+                async move { PythonService::global().install(&task, &version, mode).await }
+            },
+        );
 
         // 开始下载
         match download_manager
@@ -1305,3 +1540,43 @@ fn copy_dir_recursive(src: &PathBuf, dst: &PathBuf) -> Result<()> {
     }
     Ok(())
 }
+
+impl ServiceLifecycle for PythonService {
+    fn is_installed(&self, version: &str) -> bool {
+        PythonService::is_installed(self, version)
+    }
+
+    fn available_versions(&self) -> Vec<String> {
+        self.get_available_versions()
+            .into_iter()
+            .map(|v| v.version)
+            .collect()
+    }
+
+    fn download_and_install<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<DownloadResult>> {
+        Box::pin(self.download_and_install_with_mode(version, PythonInstallMode::default()))
+    }
+
+    fn cancel_download(&self, version: &str) -> Result<()> {
+        PythonService::cancel_download(self, version)
+    }
+
+    fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        PythonService::get_download_progress(self, version)
+    }
+
+    fn archive_dir(&self, version: &str) -> Option<PathBuf> {
+        Some(self.get_install_path(version))
+    }
+
+    fn extract_and_install<'a>(
+        &'a self,
+        task: &'a DownloadTask,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.install(task, version, PythonInstallMode::default()))
+    }
+}
@@ -0,0 +1,797 @@
+use crate::manager::app_config_manager::AppConfigManager;
+use crate::manager::builders::metadata_port;
+use crate::manager::env_serv_data_manager::ServiceDataResult;
+use crate::manager::service_supervisor::ServiceSupervisor;
+use crate::manager::services::archive::extract_tar_strip_root;
+use crate::manager::services::health::{self, ServiceHealth};
+use crate::manager::services::launch::OutputCapture;
+use crate::manager::services::traits::ServiceLifecycle;
+use crate::manager::services::{
+    download_service_package, http_api, remove_partial_install, shutdown, DownloadManager,
+    DownloadResult, DownloadTask,
+};
+use crate::manager::system_info_manager::SystemInfoManager;
+use crate::types::{ServiceData, ServiceType};
+use crate::utils::create_command;
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RabbitmqVersion {
+    pub version: String,
+    pub date: String,
+}
+
+/// RabbitMQ 队列信息（来自管理 API `/api/queues`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RabbitmqQueue {
+    pub name: String,
+    pub vhost: String,
+    pub durable: bool,
+    pub auto_delete: bool,
+    pub state: Option<String>,
+    pub messages: u64,
+    pub messages_ready: u64,
+    pub messages_unacknowledged: u64,
+    pub consumers: u64,
+}
+
+/// 默认 AMQP 端口
+pub const DEFAULT_RABBITMQ_PORT: u16 = 5672;
+/// 默认管理插件 HTTP 端口
+pub const DEFAULT_RABBITMQ_MANAGEMENT_PORT: u16 = 15672;
+/// 管理插件名
+const MANAGEMENT_PLUGIN: &str = "rabbitmq_management";
+/// 默认账号，RabbitMQ 只允许从本机使用 guest 登录
+const RABBITMQ_USER: &str = "guest";
+const RABBITMQ_PASSWORD: &str = "guest";
+/// 仅监听本机
+const RABBITMQ_HOST: &str = "127.0.0.1";
+/// 启动后等待 AMQP 端口就绪的最长时间
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+static GLOBAL_RABBITMQ_SERVICE: OnceLock<Arc<RabbitmqService>> = OnceLock::new();
+
+/// RabbitMQ 服务：使用 generic-unix / windows 发行包，运行依赖系统安装的 Erlang/OTP
+pub struct RabbitmqService {}
+
+impl RabbitmqService {
+    pub fn global() -> Arc<RabbitmqService> {
+        GLOBAL_RABBITMQ_SERVICE
+            .get_or_init(|| Arc::new(RabbitmqService::new()))
+            .clone()
+    }
+
+    fn new() -> Self {
+        Self {}
+    }
+
+    pub fn get_available_versions(&self) -> Vec<RabbitmqVersion> {
+        vec![
+            RabbitmqVersion {
+                version: "4.1.0".to_string(),
+                date: "2025-04-15".to_string(),
+            },
+            RabbitmqVersion {
+                version: "4.0.9".to_string(),
+                date: "2025-04-08".to_string(),
+            },
+            RabbitmqVersion {
+                version: "3.13.7".to_string(),
+                date: "2024-08-29".to_string(),
+            },
+        ]
+    }
+
+    pub fn is_installed(&self, version: &str) -> bool {
+        self.get_script_path(version, "rabbitmq-server").exists()
+    }
+
+    fn get_install_path(&self, version: &str) -> PathBuf {
+        let services_folder = {
+            let app_config_manager = AppConfigManager::global();
+            let app_config_manager = app_config_manager.lock().unwrap();
+            PathBuf::from(app_config_manager.get_services_folder())
+        };
+        services_folder.join("rabbitmq").join(version)
+    }
+
+    fn get_service_data_folder(&self, environment_id: &str, version: &str) -> PathBuf {
+        let app_config_manager = AppConfigManager::global();
+        let app_config_manager = app_config_manager.lock().unwrap();
+        let envs_folder = app_config_manager.get_envs_folder();
+
+        PathBuf::from(envs_folder)
+            .join(environment_id)
+            .join("rabbitmq")
+            .join(version)
+    }
+
+    /// 发行包自带的脚本：Unix 为 sbin/<name>，Windows 为 sbin/<name>.bat
+    fn get_script_path(&self, version: &str, name: &str) -> PathBuf {
+        let sbin_dir = self.get_install_path(version).join("sbin");
+        if cfg!(target_os = "windows") {
+            sbin_dir.join(format!("{}.bat", name))
+        } else {
+            sbin_dir.join(name)
+        }
+    }
+
+    fn build_download_info(&self, version: &str) -> Result<(Vec<String>, String)> {
+        let filename = if cfg!(target_os = "windows") {
+            format!("rabbitmq-server-windows-{}.zip", version)
+        } else {
+            format!("rabbitmq-server-generic-unix-{}.tar.xz", version)
+        };
+        let url = format!(
+            "https://github.com/rabbitmq/rabbitmq-server/releases/download/v{}/{}",
+            version, filename
+        );
+
+        Ok((vec![url], filename))
+    }
+
+    pub async fn download_and_install(&self, version: &str) -> Result<DownloadResult> {
+        if self.is_installed(version) {
+            return Ok(DownloadResult::success(
+                format!("RabbitMQ {} 已经安装", version),
+                None,
+            ));
+        }
+
+        let (urls, filename) = self.build_download_info(version)?;
+        download_service_package(
+            ServiceType::Rabbitmq,
+            version,
+            urls,
+            self.get_install_path(version),
+            filename,
+        )
+        .await
+    }
+
+    pub async fn extract_and_install(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let result = self.install_from_archive(task, version);
+        if result.is_err() {
+            // 解压安装失败时清理残缺的安装目录
+            remove_partial_install(&self.get_install_path(version));
+        }
+        result
+    }
+
+    /// 将下载的压缩包解压到安装目录（去掉顶层的 rabbitmq_server-<version> 目录）
+    fn install_from_archive(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let archive_path = &task.target_path;
+        let install_dir = self.get_install_path(version);
+        fs::create_dir_all(&install_dir)?;
+
+        extract_tar_strip_root(archive_path, &install_dir)?;
+
+        if !self.is_installed(version) {
+            return Err(anyhow!("未找到 rabbitmq-server 启动脚本"));
+        }
+
+        if archive_path.exists() {
+            fs::remove_file(archive_path)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn cancel_download(&self, version: &str) -> Result<()> {
+        let task_id = format!("rabbitmq-{}", version);
+        DownloadManager::global().cancel_download(&task_id)
+    }
+
+    pub fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        let task_id = format!("rabbitmq-{}", version);
+        DownloadManager::global().get_task_status(&task_id)
+    }
+
+    pub fn get_service_status(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let config = self.get_runtime_config(environment_id, service_data)?;
+        let health = self.check_health(&config);
+        let running = health.process || health.port_open;
+
+        let mut data = serde_json::json!({
+            "isRunning": running,
+            "port": config.port,
+            "managementPort": config.management_port,
+            "managementUrl": config.management_url(),
+            "managementEnabled": Self::is_management_enabled(&config),
+            "nodeName": config.node_name,
+            "pid": shutdown::read_running_pid(&config.pid_path),
+            "configPath": config.config_path,
+            "dataPath": config.data_dir,
+            "logPath": config.logs_dir,
+        });
+        health.merge_into(&mut data);
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "获取 RabbitMQ 状态成功".to_string(),
+            data: Some(data),
+        })
+    }
+
+    /// 生成 rabbitmq.conf，首次启动前离线启用管理插件，然后以前台方式启动 rabbitmq-server，
+    /// RABBITMQ_BASE / RABBITMQ_LOG_BASE 等目录均指向环境的服务数据目录
+    pub fn start_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        ServiceSupervisor::global().clear_intentional_stop(&service_data.id);
+        let version = &service_data.version;
+
+        if !self.is_installed(version) {
+            return Ok(ServiceDataResult {
+                success: false,
+                message: "rabbitmq-server 启动脚本不存在".to_string(),
+                data: None,
+            });
+        }
+
+        let config = self.get_runtime_config(environment_id, service_data)?;
+
+        if let Some(pid) = shutdown::read_running_pid(&config.pid_path) {
+            return Ok(ServiceDataResult {
+                success: true,
+                message: "RabbitMQ 已在运行".to_string(),
+                data: Some(serde_json::json!({
+                    "port": config.port,
+                    "pid": pid,
+                    "alreadyRunning": true
+                })),
+            });
+        }
+
+        for port in [config.port, config.management_port] {
+            if health::check_tcp_port(RABBITMQ_HOST, port).is_some() {
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: format!("端口 {} 已被占用", port),
+                    data: None,
+                });
+            }
+        }
+
+        self.write_config_file(&config)?;
+        if !Self::is_management_enabled(&config) {
+            if let Err(e) = self.run_enable_management(version, &config, true) {
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: e.to_string(),
+                    data: None,
+                });
+            }
+        }
+        if let Some(parent) = config.pid_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let capture = OutputCapture::new(&config.base_dir, "rabbitmq")?;
+        let mut command = match self.script_command(version, "rabbitmq-server", &config) {
+            Ok(command) => command,
+            Err(e) => {
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: e.to_string(),
+                    data: None,
+                })
+            }
+        };
+        command.current_dir(self.get_install_path(version));
+        capture.attach(&mut command)?;
+
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: format!("启动失败: {}", e),
+                    data: None,
+                })
+            }
+        };
+        let pid = child.id();
+        fs::write(&config.pid_path, pid.to_string())?;
+
+        // 等待 AMQP 端口就绪或进程退出
+        let deadline = Instant::now() + STARTUP_TIMEOUT;
+        while Instant::now() < deadline {
+            if health::check_tcp_port(RABBITMQ_HOST, config.port).is_some() {
+                log::info!("RabbitMQ 已启动，PID: {}", pid);
+                return Ok(ServiceDataResult {
+                    success: true,
+                    message: "RabbitMQ 启动成功".to_string(),
+                    data: Some(serde_json::json!({
+                        "port": config.port,
+                        "pid": pid,
+                        "managementUrl": config.management_url(),
+                    })),
+                });
+            }
+            if let Ok(Some(status)) = child.try_wait() {
+                let _ = fs::remove_file(&config.pid_path);
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: format!("RabbitMQ 启动失败: {}", capture.failure_detail(&status)),
+                    data: Some(serde_json::json!({
+                        "logPath": capture.path(),
+                    })),
+                });
+            }
+            std::thread::sleep(Duration::from_millis(500));
+        }
+
+        Ok(ServiceDataResult {
+            success: false,
+            message: format!(
+                "RabbitMQ 进程已启动，但端口 {} 尚未就绪，请检查日志: {}",
+                config.port,
+                capture.path().to_string_lossy()
+            ),
+            data: Some(serde_json::json!({
+                "port": config.port,
+                "pid": pid,
+                "logPath": capture.path(),
+            })),
+        })
+    }
+
+    /// 优先使用 `rabbitmqctl stop` 正常关闭节点，失败时结束进程
+    pub fn stop_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        ServiceSupervisor::global().mark_intentional_stop(&service_data.id);
+        let config = self.get_runtime_config(environment_id, service_data)?;
+
+        // 先尝试 rabbitmqctl stop，失败或未及时退出时再发送信号结束进程
+        if let Some(pid) = shutdown::read_running_pid(&config.pid_path) {
+            let graceful = self
+                .script_command(&service_data.version, "rabbitmqctl", &config)
+                .and_then(|mut command| Ok(command.arg("stop").output()?))
+                .map(|o| o.status.success())
+                .unwrap_or(false);
+            if graceful {
+                let system = SystemInfoManager::global();
+                for _ in 0..100 {
+                    if !system.is_process_alive(pid) {
+                        break;
+                    }
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                if !system.is_process_alive(pid) {
+                    let _ = fs::remove_file(&config.pid_path);
+                    return Ok(ServiceDataResult {
+                        success: true,
+                        message: "RabbitMQ 已停止".to_string(),
+                        data: None,
+                    });
+                }
+            }
+        }
+
+        Ok(shutdown::stop_pid_file_process(
+            "RabbitMQ",
+            &config.pid_path,
+            "TERM",
+            Duration::from_secs(5),
+        ))
+    }
+
+    pub fn restart_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let _ = self.stop_service(environment_id, service_data);
+        std::thread::sleep(Duration::from_millis(300));
+        self.start_service(environment_id, service_data)
+    }
+
+    /// 启用管理插件：节点运行中时立即生效，未运行时以 --offline 写入 enabled_plugins
+    pub fn enable_rabbitmq_management(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let config = self.get_runtime_config(environment_id, service_data)?;
+        let running = shutdown::read_running_pid(&config.pid_path).is_some();
+        self.run_enable_management(&service_data.version, &config, !running)?;
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: if running {
+                "RabbitMQ 管理插件已启用".to_string()
+            } else {
+                "RabbitMQ 管理插件已启用，将在服务启动后生效".to_string()
+            },
+            data: Some(serde_json::json!({
+                "managementUrl": config.management_url(),
+                "applied": running,
+            })),
+        })
+    }
+
+    /// 列出队列，指定 vhost 时只列出该 vhost 下的队列
+    pub async fn list_rabbitmq_queues(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        vhost: Option<&str>,
+    ) -> Result<ServiceDataResult> {
+        let config = self.get_runtime_config(environment_id, service_data)?;
+        let url = match vhost.filter(|v| !v.is_empty()) {
+            Some(vhost) => config.api_url(&["queues", vhost])?,
+            None => config.api_url(&["queues"])?,
+        };
+
+        let response = Self::send(http_api::api_client()?.get(url)).await?;
+        let body = Self::parse_response(response).await?;
+
+        let text = |row: &serde_json::Value, key: &str| {
+            row.get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        // 统计数据尚未收集时计数字段可能缺失
+        let count = |row: &serde_json::Value, key: &str| {
+            row.get(key).and_then(|v| v.as_u64()).unwrap_or_default()
+        };
+        let mut queues: Vec<RabbitmqQueue> = body
+            .as_array()
+            .map(|rows| {
+                rows.iter()
+                    .map(|row| RabbitmqQueue {
+                        name: text(row, "name"),
+                        vhost: text(row, "vhost"),
+                        durable: row
+                            .get("durable")
+                            .and_then(|v| v.as_bool())
+                            .unwrap_or(false),
+                        auto_delete: row
+                            .get("auto_delete")
+                            .and_then(|v| v.as_bool())
+                            .unwrap_or(false),
+                        state: row
+                            .get("state")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string()),
+                        messages: count(row, "messages"),
+                        messages_ready: count(row, "messages_ready"),
+                        messages_unacknowledged: count(row, "messages_unacknowledged"),
+                        consumers: count(row, "consumers"),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        queues.sort_by(|a, b| a.vhost.cmp(&b.vhost).then_with(|| a.name.cmp(&b.name)));
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "获取 RabbitMQ 队列列表成功".to_string(),
+            data: Some(serde_json::json!({ "queues": queues })),
+        })
+    }
+
+    /// 删除单个队列（队列中的消息一并删除）
+    pub async fn delete_rabbitmq_queue(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        vhost: &str,
+        queue: &str,
+    ) -> Result<ServiceDataResult> {
+        let config = self.get_runtime_config(environment_id, service_data)?;
+        let queue = queue.trim();
+        if queue.is_empty() {
+            return Err(anyhow!("队列名不能为空"));
+        }
+        let vhost = if vhost.is_empty() { "/" } else { vhost };
+
+        let url = config.api_url(&["queues", vhost, queue])?;
+        let response = Self::send(http_api::api_client()?.delete(url)).await?;
+        Self::parse_response(response).await?;
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("已删除队列 {}", queue),
+            data: Some(serde_json::json!({ "vhost": vhost, "queue": queue })),
+        })
+    }
+
+    /// 使用本机 guest 账号请求管理 API，连接失败时提示检查服务与管理插件
+    async fn send(request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        request
+            .basic_auth(RABBITMQ_USER, Some(RABBITMQ_PASSWORD))
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_connect() || e.is_timeout() {
+                    anyhow!("无法连接 RabbitMQ 管理接口，请确认服务已启动并已启用管理插件")
+                } else {
+                    anyhow!("请求 RabbitMQ 管理接口失败: {}", e)
+                }
+            })
+    }
+
+    /// 解析管理 API 响应，非 2xx 时取出 reason 作为错误信息
+    async fn parse_response(response: reqwest::Response) -> Result<serde_json::Value> {
+        http_api::parse_json_response(response, "RabbitMQ 管理 API", |body| {
+            body.get("reason")
+                .or_else(|| body.get("error"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        })
+        .await
+    }
+
+    /// 执行 `rabbitmq-plugins enable rabbitmq_management`，offline 时只写入 enabled_plugins
+    fn run_enable_management(
+        &self,
+        version: &str,
+        config: &RabbitmqRuntimeConfig,
+        offline: bool,
+    ) -> Result<()> {
+        if let Some(parent) = config.enabled_plugins_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut command = self.script_command(version, "rabbitmq-plugins", config)?;
+        command.args(["enable", MANAGEMENT_PLUGIN]);
+        if offline {
+            command.arg("--offline");
+        }
+        let output = command.output()?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "启用管理插件失败: {}{}",
+                String::from_utf8_lossy(&output.stderr),
+                String::from_utf8_lossy(&output.stdout)
+            ));
+        }
+        log::info!("RabbitMQ 管理插件已启用: {}", config.node_name);
+        Ok(())
+    }
+
+    fn is_management_enabled(config: &RabbitmqRuntimeConfig) -> bool {
+        fs::read_to_string(&config.enabled_plugins_path)
+            .map(|content| content.contains(MANAGEMENT_PLUGIN))
+            .unwrap_or(false)
+    }
+
+    /// 构建发行包脚本命令，数据、日志、配置与插件列表均指向环境的服务数据目录
+    fn script_command(
+        &self,
+        version: &str,
+        name: &str,
+        config: &RabbitmqRuntimeConfig,
+    ) -> Result<Command> {
+        let script = self.get_script_path(version, name);
+        if !script.exists() {
+            return Err(anyhow!("未找到 {} 脚本", name));
+        }
+        Self::check_erlang()?;
+
+        let mut command = create_command(&script);
+        command
+            .env("RABBITMQ_BASE", &config.base_dir)
+            .env("RABBITMQ_CONFIG_FILE", &config.config_path)
+            .env(
+                "RABBITMQ_ENABLED_PLUGINS_FILE",
+                &config.enabled_plugins_path,
+            )
+            .env("RABBITMQ_MNESIA_BASE", &config.data_dir)
+            .env("RABBITMQ_LOG_BASE", &config.logs_dir)
+            .env("RABBITMQ_NODENAME", &config.node_name)
+            .env("RABBITMQ_NODE_IP_ADDRESS", RABBITMQ_HOST)
+            .env("RABBITMQ_NODE_PORT", config.port.to_string());
+        Ok(command)
+    }
+
+    /// 检查 Erlang 运行环境：Windows 的启动脚本依赖 ERLANG_HOME，其他系统需要 erl 在 PATH 中
+    fn check_erlang() -> Result<()> {
+        if cfg!(target_os = "windows") {
+            let found = std::env::var_os("ERLANG_HOME")
+                .map(PathBuf::from)
+                .is_some_and(|home| home.join("bin").join("erl.exe").exists());
+            if !found {
+                return Err(anyhow!(
+                    "RabbitMQ 需要 Erlang/OTP 运行环境，请先安装 Erlang 并设置 ERLANG_HOME"
+                ));
+            }
+        } else {
+            let found = create_command("erl")
+                .args(["-noshell", "-eval", "halt()."])
+                .output()
+                .map(|o| o.status.success())
+                .unwrap_or(false);
+            if !found {
+                return Err(anyhow!(
+                    "RabbitMQ 需要 Erlang/OTP 运行环境，请先安装 Erlang 并确保 erl 在 PATH 中"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 检查进程与 AMQP 端口；管理接口可访问时以其耗时为准
+    fn check_health(&self, config: &RabbitmqRuntimeConfig) -> ServiceHealth {
+        let process = shutdown::read_running_pid(&config.pid_path).is_some();
+        let port_latency = health::check_tcp_port(RABBITMQ_HOST, config.port);
+        let http_latency =
+            port_latency.and_then(|_| health::check_http(RABBITMQ_HOST, config.management_port));
+        ServiceHealth {
+            process,
+            port_open: port_latency.is_some(),
+            responding: port_latency.is_some(),
+            latency_ms: http_latency.or(port_latency),
+        }
+    }
+
+    fn get_runtime_config(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<RabbitmqRuntimeConfig> {
+        let base_dir = self.get_service_data_folder(environment_id, &service_data.version);
+
+        let port = metadata_port(service_data, "RABBITMQ_PORT")?.unwrap_or(DEFAULT_RABBITMQ_PORT);
+        let management_port = metadata_port(service_data, "RABBITMQ_MANAGEMENT_PORT")?
+            .unwrap_or(DEFAULT_RABBITMQ_MANAGEMENT_PORT);
+        if port == management_port {
+            return Err(anyhow!(
+                "RABBITMQ_PORT 与 RABBITMQ_MANAGEMENT_PORT 不能相同: {}",
+                port
+            ));
+        }
+
+        Ok(RabbitmqRuntimeConfig {
+            port,
+            management_port,
+            // 节点名按端口区分，多个环境可同时运行
+            node_name: format!("rabbit_{}@localhost", port),
+            config_path: base_dir.join("config").join("rabbitmq.conf"),
+            enabled_plugins_path: base_dir.join("config").join("enabled_plugins"),
+            data_dir: base_dir.join("data"),
+            logs_dir: base_dir.join("logs"),
+            pid_path: base_dir.join("run").join("rabbitmq.pid"),
+            base_dir,
+        })
+    }
+
+    /// 每次启动时按 metadata 重新生成配置，AMQP 端口通过 RABBITMQ_NODE_PORT 指定
+    fn write_config_file(&self, config: &RabbitmqRuntimeConfig) -> Result<()> {
+        if let Some(config_dir) = config.config_path.parent() {
+            fs::create_dir_all(config_dir)?;
+        }
+        fs::create_dir_all(&config.data_dir)?;
+        fs::create_dir_all(&config.logs_dir)?;
+
+        let rabbitmq_conf = format!(
+            "# 由 Envis 生成，启动时会按服务配置覆盖\n\
+             loopback_users.guest = true\n\
+             management.tcp.ip = {host}\n\
+             management.tcp.port = {management_port}\n",
+            host = RABBITMQ_HOST,
+            management_port = config.management_port,
+        );
+        fs::write(&config.config_path, rabbitmq_conf)?;
+
+        Ok(())
+    }
+}
+
+struct RabbitmqRuntimeConfig {
+    port: u16,
+    management_port: u16,
+    node_name: String,
+    base_dir: PathBuf,
+    config_path: PathBuf,
+    enabled_plugins_path: PathBuf,
+    data_dir: PathBuf,
+    logs_dir: PathBuf,
+    pid_path: PathBuf,
+}
+
+impl RabbitmqRuntimeConfig {
+    fn management_url(&self) -> String {
+        format!("http://{}:{}", RABBITMQ_HOST, self.management_port)
+    }
+
+    /// 拼接管理 API 地址，vhost 与队列名按路径段编码（默认 vhost "/" 编码为 %2F）
+    fn api_url(&self, segments: &[&str]) -> Result<reqwest::Url> {
+        let mut url = reqwest::Url::parse(&format!("{}/api", self.management_url()))?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow!("无效的管理接口地址"))?
+            .extend(segments);
+        Ok(url)
+    }
+}
+
+impl ServiceLifecycle for RabbitmqService {
+    fn is_installed(&self, version: &str) -> bool {
+        RabbitmqService::is_installed(self, version)
+    }
+
+    fn available_versions(&self) -> Vec<String> {
+        self.get_available_versions()
+            .into_iter()
+            .map(|v| v.version)
+            .collect()
+    }
+
+    fn download_and_install<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<DownloadResult>> {
+        Box::pin(RabbitmqService::download_and_install(self, version))
+    }
+
+    fn cancel_download(&self, version: &str) -> Result<()> {
+        RabbitmqService::cancel_download(self, version)
+    }
+
+    fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        RabbitmqService::get_download_progress(self, version)
+    }
+
+    fn archive_dir(&self, version: &str) -> Option<PathBuf> {
+        Some(self.get_install_path(version))
+    }
+
+    fn extract_and_install<'a>(
+        &'a self,
+        task: &'a DownloadTask,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(RabbitmqService::extract_and_install(self, task, version))
+    }
+
+    fn start_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        RabbitmqService::start_service(self, environment_id, service_data)
+    }
+
+    fn stop_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        RabbitmqService::stop_service(self, environment_id, service_data)
+    }
+
+    fn restart_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        RabbitmqService::restart_service(self, environment_id, service_data)
+    }
+
+    fn get_service_status(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        RabbitmqService::get_service_status(self, environment_id, service_data)
+    }
+}
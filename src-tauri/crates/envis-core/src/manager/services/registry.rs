@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use crate::manager::services::traits::ServiceLifecycle;
+use crate::manager::services::{
+    ComposeService, CustomService, DnsmasqService, HostService, JavaService, KafkaService,
+    MariadbService, MongodbService, MysqlService, NasmService, NginxService, NodejsService,
+    OpensearchService, PhpService, PostgresqlService, PythonService, RabbitmqService, RedisService,
+    RustService, StandardService, VaultService,
+};
+use crate::types::ServiceType;
+
+/// 服务注册表：按服务类型获取对应的服务实例，供通用逻辑统一调度
+pub struct ServiceRegistry;
+
+impl ServiceRegistry {
+    /// 获取服务类型对应的服务实例（SSL 等没有专门实现的服务使用 StandardService）
+    pub fn get(service_type: &ServiceType) -> Arc<dyn ServiceLifecycle> {
+        match service_type {
+            ServiceType::Mongodb => MongodbService::global(),
+            ServiceType::Mysql => MysqlService::global(),
+            ServiceType::Mariadb => MariadbService::global(),
+            ServiceType::Postgresql => PostgresqlService::global(),
+            ServiceType::Redis => RedisService::global(),
+            ServiceType::Nginx => NginxService::global(),
+            ServiceType::Nodejs => NodejsService::global(),
+            ServiceType::Python => PythonService::global(),
+            ServiceType::Java => JavaService::global(),
+            ServiceType::Rust => RustService::global(),
+            ServiceType::Dnsmasq => DnsmasqService::global(),
+            ServiceType::Nasm => NasmService::global(),
+            ServiceType::Php => PhpService::global(),
+            ServiceType::Vault => VaultService::global(),
+            ServiceType::Opensearch => OpensearchService::global(),
+            ServiceType::Kafka => KafkaService::global(),
+            ServiceType::Rabbitmq => RabbitmqService::global(),
+            ServiceType::Compose => ComposeService::global(),
+            ServiceType::Custom => CustomService::global(),
+            ServiceType::Host => HostService::global(),
+            ServiceType::SSL => StandardService::global(),
+        }
+    }
+}
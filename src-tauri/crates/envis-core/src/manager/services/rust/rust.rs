@@ -1,11 +1,15 @@
 use crate::manager::app_config_manager::AppConfigManager;
+use crate::manager::env_serv_data_manager::ServiceDataResult;
 use crate::manager::services::traits::ServiceLifecycle;
-use crate::manager::services::{DownloadManager, DownloadResult, DownloadStatus, DownloadTask};
+use crate::manager::services::{
+    install_callback, remove_partial_install, DownloadManager, DownloadResult, DownloadTask,
+};
 use crate::manager::shell_manamger::ShellManager;
 use crate::types::ServiceData;
 use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, OnceLock};
 
 /// Rust 版本信息
@@ -20,6 +24,25 @@ pub struct RustVersion {
 static GLOBAL_RUST_SERVICE: OnceLock<Arc<RustService>> = OnceLock::new();
 
 const EDITION_2024_MIN_MINOR: u64 = 85;
+/// 官方独立安装包下载地址
+const RUST_DIST_URL: &str = "https://static.rust-lang.org/dist";
+/// 安装时跳过的组件（离线文档体积较大）
+const SKIPPED_COMPONENTS: [&str; 1] = ["rust-docs"];
+/// CARGO_HOME 中替换 crates-io 的镜像源名称
+const MIRROR_SOURCE_NAME: &str = "envis-mirror";
+/// crates.io 镜像预设：(名称, 地址)，crates-io 表示恢复官方源
+const REGISTRY_MIRROR_PRESETS: [(&str, &str); 4] = [
+    ("crates-io", "sparse+https://index.crates.io/"),
+    ("rsproxy", "sparse+https://rsproxy.cn/index/"),
+    (
+        "tuna",
+        "sparse+https://mirrors.tuna.tsinghua.edu.cn/crates.io-index/",
+    ),
+    (
+        "ustc",
+        "sparse+https://mirrors.ustc.edu.cn/crates.io-index/",
+    ),
+];
 
 /// Rust 服务管理器
 pub struct RustService {}
@@ -50,6 +73,26 @@ impl RustService {
                 stable: true,
                 date: "2025-04-03".to_string(),
             },
+            RustVersion {
+                version: "1.87.0".to_string(),
+                stable: true,
+                date: "2025-05-15".to_string(),
+            },
+            RustVersion {
+                version: "1.88.0".to_string(),
+                stable: true,
+                date: "2025-06-26".to_string(),
+            },
+            RustVersion {
+                version: "1.89.0".to_string(),
+                stable: true,
+                date: "2025-08-07".to_string(),
+            },
+            RustVersion {
+                version: "1.90.0".to_string(),
+                stable: true,
+                date: "2025-09-18".to_string(),
+            },
         ]
     }
 
@@ -74,35 +117,61 @@ impl RustService {
         services_folder.join("rust").join(version)
     }
 
+    /// 默认的 CARGO_HOME：按环境隔离，cargo install 安装的工具不会在环境之间共享
+    pub(crate) fn get_default_cargo_home(&self, environment_id: &str, version: &str) -> PathBuf {
+        let envs_folder = {
+            let app_config_manager = AppConfigManager::global();
+            let app_config_manager = app_config_manager.lock().unwrap();
+            PathBuf::from(app_config_manager.get_envs_folder())
+        };
+        envs_folder
+            .join(environment_id)
+            .join("rust")
+            .join(version)
+            .join("cargo")
+    }
+
+    /// 读取 metadata 中的 CARGO_HOME，未设置时使用环境目录下的默认路径
+    fn get_cargo_home(&self, environment_id: &str, service_data: &ServiceData) -> PathBuf {
+        service_data
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("CARGO_HOME"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| self.get_default_cargo_home(environment_id, &service_data.version))
+    }
+
     /// 构建下载 URL 和文件名
     fn build_download_info(&self, version: &str) -> Result<(Vec<String>, String)> {
         let platform = std::env::consts::OS;
         let arch = std::env::consts::ARCH;
 
         // 构建 Rust target triple
-        let (target_triple, ext) = match (platform, arch) {
-            ("macos", "aarch64") => ("aarch64-apple-darwin", "tar.gz"),
-            ("macos", "x86_64") => ("x86_64-apple-darwin", "tar.gz"),
-            ("linux", "aarch64") => ("aarch64-unknown-linux-gnu", "tar.gz"),
-            ("linux", "x86_64") => ("x86_64-unknown-linux-gnu", "tar.gz"),
-            ("windows", "x86_64") => ("x86_64-pc-windows-msvc", "zip"),
+        let target_triple = match (platform, arch) {
+            ("macos", "aarch64") => "aarch64-apple-darwin",
+            ("macos", "x86_64") => "x86_64-apple-darwin",
+            ("linux", "aarch64") => "aarch64-unknown-linux-gnu",
+            ("linux", "x86_64") => "x86_64-unknown-linux-gnu",
+            ("windows", "aarch64") => "aarch64-pc-windows-msvc",
+            ("windows", "x86_64") => "x86_64-pc-windows-msvc",
             _ => return Err(anyhow!("不支持的平台/架构: {}/{}", platform, arch)),
         };
 
         // 验证 Rust 版本是否支持
-        match version {
-            "1.85.0" | "1.86.0" => {}
-            _ => return Err(anyhow!("不支持的 Rust 版本: {}", version)),
-        };
-
-        // 文件命名格式：rust-{version}-{target_triple}.{ext}
-        let filename = format!("rust-{}-{}.{}", version, target_triple, ext);
+        if !self
+            .get_available_versions()
+            .iter()
+            .any(|v| v.version == version)
+        {
+            return Err(anyhow!("不支持的 Rust 版本: {}", version));
+        }
 
-        // 下载地址
-        let urls = vec![format!(
-            "https://github.com/xopenbeta/rust-archive/releases/latest/download/{}",
-            filename
-        )];
+        // 官方独立安装包：rust-{version}-{target_triple}.tar.xz，各平台均为同一格式
+        let filename = format!("rust-{}-{}.tar.xz", version, target_triple);
+        let urls = vec![format!("{}/{}", RUST_DIST_URL, filename)];
 
         Ok((urls, filename))
     }
@@ -122,50 +191,13 @@ impl RustService {
         let download_manager = DownloadManager::global();
 
         let version_for_callback = version.to_string();
-        let success_callback = Arc::new(move |task: &DownloadTask| {
-            log::info!("Rust {} 下载完成: {}", version_for_callback, task.filename);
-
-            let task_for_spawn = task.clone();
-            let version_for_spawn = version_for_callback.clone();
-            let service_for_spawn = RustService::global();
-
-            tokio::spawn(async move {
-                let download_manager = DownloadManager::global();
-                if let Err(e) = download_manager.update_task_status(
-                    &task_for_spawn.id,
-                    DownloadStatus::Installing,
-                    None,
-                ) {
-                    log::error!("更新任务状态失败: {}", e);
-                }
-
-                match service_for_spawn
-                    .extract_and_install(&task_for_spawn, &version_for_spawn)
+        let success_callback = install_callback(format!("Rust {}", version), move |task| {
+            let version = version_for_callback.clone();
+            async move {
+                RustService::global()
+                    .extract_and_install(&task, &version)
                     .await
-                {
-                    Ok(_) => {
-                        if let Err(e) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            DownloadStatus::Installed,
-                            None,
-                        ) {
-                            log::error!("更新任务状态失败: {}", e);
-                        } else {
-                            log::info!("Rust {} 安装成功", version_for_spawn);
-                        }
-                    }
-                    Err(e) => {
-                        if let Err(update_err) = download_manager.update_task_status(
-                            &task_for_spawn.id,
-                            DownloadStatus::Failed,
-                            Some(format!("安装失败: {}", e)),
-                        ) {
-                            log::error!("更新任务状态失败: {}", update_err);
-                        }
-                        log::error!("Rust {} 安装失败: {}", version_for_spawn, e);
-                    }
-                }
-            });
+            }
         });
 
         match download_manager
@@ -195,28 +227,42 @@ impl RustService {
 
     /// 解压和安装 Rust
     pub async fn extract_and_install(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let result = self.install_from_archive(task, version).await;
+        if result.is_err() {
+            // 解压安装失败时清理残缺的安装目录
+            remove_partial_install(&self.get_install_path(version));
+        }
+        result
+    }
+
+    /// 解压官方独立安装包，Unix 上运行自带的 install.sh，Windows 上手动复制各组件
+    async fn install_from_archive(&self, task: &DownloadTask, version: &str) -> Result<()> {
         let archive_path = &task.target_path;
         let install_dir = self.get_install_path(version);
+        if !task.filename.ends_with(".tar.xz") {
+            return Err(anyhow!("不支持的压缩格式"));
+        }
 
-        std::fs::create_dir_all(&install_dir)?;
+        // 安装包先解压到安装目录下的临时目录，安装完成后删除
+        let dist_dir = install_dir.join(".dist");
+        if dist_dir.exists() {
+            std::fs::remove_dir_all(&dist_dir)?;
+        }
+        std::fs::create_dir_all(&dist_dir)?;
+        extract_tar(archive_path, &dist_dir).await?;
+        let _ = std::fs::remove_file(archive_path);
 
-        if task.filename.ends_with(".tar.gz") {
-            extract_tar(archive_path, &install_dir).await?;
-        } else if task.filename.ends_with(".zip") {
-            extract_zip(archive_path, &install_dir).await?;
-            // 先删除压缩包，再提升目录（避免 zip 文件干扰子目录检测）
-            let _ = std::fs::remove_file(archive_path);
-            flatten_single_subdir(&install_dir)?;
+        let result = if cfg!(target_os = "windows") {
+            install_components(&dist_dir, &install_dir)
         } else {
-            return Err(anyhow!("不支持的压缩格式"));
-        }
+            run_install_script(&dist_dir, &install_dir).await
+        };
+        let _ = std::fs::remove_dir_all(&dist_dir);
+        result?;
 
         #[cfg(not(target_os = "windows"))]
         set_executable_permissions(&install_dir)?;
 
-        // zip 文件已在上方提前删除，tar.gz 在此清理
-        let _ = std::fs::remove_file(archive_path);
-
         log::info!("Rust {} 解压和安装完成", version);
         Ok(())
     }
@@ -234,7 +280,7 @@ impl RustService {
     }
 
     /// 激活服务
-    pub fn activate_service(&self, service_data: &ServiceData) -> Result<()> {
+    pub fn activate_service(&self, environment_id: &str, service_data: &ServiceData) -> Result<()> {
         let install_path = self.get_install_path(&service_data.version);
 
         if !supports_edition_2024(&service_data.version) {
@@ -254,37 +300,35 @@ impl RustService {
         let rust_home = install_path.to_string_lossy().to_string();
         let bin_path = install_path.join("bin").to_string_lossy().to_string();
 
-        shell_manager.add_export("RUST_HOME", &rust_home)?;
-        shell_manager.add_path(&bin_path)?;
+        let mut update = shell_manager.begin_update();
+        update.add_export("RUST_HOME", &rust_home).add_path(&bin_path);
 
-        // 设置 CARGO_HOME：优先使用 metadata 中的自定义路径，否则默认为安装目录下的 cargo 子文件夹
-        let cargo_home = service_data
-            .metadata
-            .as_ref()
-            .and_then(|m| m.get("CARGO_HOME"))
-            .and_then(|v| v.as_str())
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .unwrap_or_else(|| install_path.join("cargo").to_string_lossy().to_string());
+        // 设置 CARGO_HOME：优先使用 metadata 中的自定义路径，否则为环境目录下的 cargo 子文件夹
+        let cargo_home_path = self.get_cargo_home(environment_id, service_data);
 
         // 确保 cargo 目录存在
-        let cargo_home_path = std::path::PathBuf::from(&cargo_home);
         if !cargo_home_path.exists() {
             std::fs::create_dir_all(&cargo_home_path)?;
         }
 
-        shell_manager.add_export("CARGO_HOME", &cargo_home)?;
-
         // 将 CARGO_HOME/bin 加入 PATH
-        let cargo_bin = PathBuf::from(&cargo_home).join("bin").to_string_lossy().to_string();
-        shell_manager.add_path(&cargo_bin)?;
+        let cargo_home = cargo_home_path.to_string_lossy().to_string();
+        let cargo_bin = cargo_home_path.join("bin").to_string_lossy().to_string();
+        update
+            .add_export("CARGO_HOME", &cargo_home)
+            .add_path(&cargo_bin)
+            .commit()?;
 
         log::info!("Rust {} 服务已激活", service_data.version);
         Ok(())
     }
 
     /// 取消激活服务
-    pub fn deactivate_service(&self, service_data: &ServiceData) -> Result<()> {
+    pub fn deactivate_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<()> {
         let install_path = self.get_install_path(&service_data.version);
 
         let shell_manager = ShellManager::global();
@@ -292,22 +336,20 @@ impl RustService {
 
         let bin_path = install_path.join("bin").to_string_lossy().to_string();
 
-        shell_manager.delete_path(&bin_path)?;
-        shell_manager.delete_export("RUST_HOME")?;
-
-        // 移除 CARGO_HOME/bin
-        let cargo_home = service_data
-            .metadata
-            .as_ref()
-            .and_then(|m| m.get("CARGO_HOME"))
-            .and_then(|v| v.as_str())
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .unwrap_or_else(|| install_path.join("cargo").to_string_lossy().to_string());
-        let cargo_bin = PathBuf::from(&cargo_home).join("bin").to_string_lossy().to_string();
-        let _ = shell_manager.delete_path(&cargo_bin);
-
-        shell_manager.delete_export("CARGO_HOME")?;
+        // 同时移除 CARGO_HOME/bin
+        let cargo_bin = self
+            .get_cargo_home(environment_id, service_data)
+            .join("bin")
+            .to_string_lossy()
+            .to_string();
+
+        shell_manager
+            .begin_update()
+            .delete_path(&bin_path)
+            .delete_export("RUST_HOME")
+            .delete_path(&cargo_bin)
+            .delete_export("CARGO_HOME")
+            .commit()?;
 
         log::info!("Rust {} 服务已取消激活", service_data.version);
         Ok(())
@@ -344,6 +386,50 @@ impl RustService {
         Ok(())
     }
 
+    /// 在 CARGO_HOME 的 config.toml 中用镜像替换 crates-io，
+    /// mirror 可以是预设名称（crates-io、rsproxy、tuna、ustc）或镜像地址，crates-io 表示恢复官方源
+    pub fn set_cargo_registry_mirror(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        mirror: &str,
+    ) -> Result<ServiceDataResult> {
+        let registry = resolve_registry_mirror(mirror)?;
+        let use_official = registry == REGISTRY_MIRROR_PRESETS[0].1;
+
+        let cargo_home = self.get_cargo_home(environment_id, service_data);
+        std::fs::create_dir_all(&cargo_home)?;
+        let config_path = cargo_home.join("config.toml");
+        let content = if config_path.exists() {
+            std::fs::read_to_string(&config_path)?
+        } else {
+            String::new()
+        };
+
+        let mut updated = without_mirror_sections(&content);
+        if !use_official {
+            if !updated.is_empty() {
+                updated.push('\n');
+            }
+            updated.push_str(&format!(
+                "[source.crates-io]\nreplace-with = \"{name}\"\n\n[source.{name}]\nregistry = \"{registry}\"\n",
+                name = MIRROR_SOURCE_NAME,
+                registry = registry
+            ));
+        }
+        std::fs::write(&config_path, updated)?;
+
+        log::info!("Cargo 镜像已设置为 {}", registry);
+        Ok(ServiceDataResult {
+            success: true,
+            message: format!("Cargo 镜像已设置为 {}", registry),
+            data: Some(serde_json::json!({
+                "configPath": config_path.to_string_lossy().to_string(),
+                "registry": registry,
+            })),
+        })
+    }
+
     /// 获取 Rust 版本信息
     pub fn get_rust_info(&self, service_data: &ServiceData) -> Result<serde_json::Value> {
         let install_path = self.get_install_path(&service_data.version);
@@ -404,29 +490,67 @@ fn supports_edition_2024(version: &str) -> bool {
 impl ServiceLifecycle for RustService {
     fn active(
         &self,
-        _environment_id: &str,
+        environment_id: &str,
         service_data: &ServiceData,
         _password: Option<String>,
     ) -> Result<()> {
-        self.activate_service(service_data)
+        self.activate_service(environment_id, service_data)
     }
 
     fn deactive(
         &self,
-        _environment_id: &str,
+        environment_id: &str,
         service_data: &ServiceData,
         _password: Option<String>,
     ) -> Result<()> {
-        self.deactivate_service(service_data)
+        self.deactivate_service(environment_id, service_data)
+    }
+
+    fn is_installed(&self, version: &str) -> bool {
+        RustService::is_installed(self, version)
+    }
+
+    fn available_versions(&self) -> Vec<String> {
+        self.get_available_versions()
+            .into_iter()
+            .map(|v| v.version)
+            .collect()
+    }
+
+    fn download_and_install<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<DownloadResult>> {
+        Box::pin(RustService::download_and_install(self, version))
+    }
+
+    fn cancel_download(&self, version: &str) -> Result<()> {
+        RustService::cancel_download(self, version)
+    }
+
+    fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        RustService::get_download_progress(self, version)
+    }
+
+    fn archive_dir(&self, version: &str) -> Option<PathBuf> {
+        Some(self.get_install_path(version))
+    }
+
+    fn extract_and_install<'a>(
+        &'a self,
+        task: &'a DownloadTask,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(RustService::extract_and_install(self, task, version))
     }
 }
 
 // ─── 共享工具方法 ───────────────────────────────────────────────────────────
 
-/// 解压 tar 格式文件（strip 顶层目录）
+/// 解压 tar.xz 格式文件（strip 顶层目录）
 async fn extract_tar(archive_path: &PathBuf, target_dir: &PathBuf) -> Result<()> {
     let mut cmd = tokio::process::Command::new("tar");
-    cmd.arg("-xzf")
+    cmd.arg("-xJf")
         .arg(archive_path)
         .arg("-C")
         .arg(target_dir)
@@ -449,71 +573,107 @@ async fn extract_tar(archive_path: &PathBuf, target_dir: &PathBuf) -> Result<()>
     Ok(())
 }
 
-/// 若 target_dir 下存在唯一的子目录（忽略文件），则将其内容提升到 target_dir
-fn flatten_single_subdir(target_dir: &PathBuf) -> Result<()> {
-    let subdirs: Vec<_> = std::fs::read_dir(target_dir)
-        .map_err(|e| anyhow!("读取目录失败: {}", e))?
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().is_dir())
-        .collect();
+/// 运行安装包自带的 install.sh 安装到指定前缀
+async fn run_install_script(dist_dir: &Path, install_dir: &Path) -> Result<()> {
+    let output = tokio::process::Command::new("sh")
+        .arg(dist_dir.join("install.sh"))
+        .arg(format!("--prefix={}", install_dir.to_string_lossy()))
+        .arg(format!("--without={}", SKIPPED_COMPONENTS.join(",")))
+        // 安装到用户目录，无需刷新系统动态库缓存
+        .arg("--disable-ldconfig")
+        .current_dir(dist_dir)
+        .output()
+        .await?;
 
-    if subdirs.len() != 1 {
-        return Ok(());
+    if !output.status.success() {
+        return Err(anyhow!(
+            "运行 install.sh 失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
     }
+    Ok(())
+}
 
-    let nested_dir = subdirs[0].path();
+/// 按 components 文件列出的组件，将各组件目录下的文件复制到安装目录（Windows 没有 sh 可运行 install.sh）
+fn install_components(dist_dir: &Path, install_dir: &Path) -> Result<()> {
+    let components = std::fs::read_to_string(dist_dir.join("components"))
+        .map_err(|e| anyhow!("读取安装包组件列表失败: {}", e))?;
 
-    for entry in std::fs::read_dir(&nested_dir)
-        .map_err(|e| anyhow!("读取嵌套目录失败: {}", e))?
+    for component in components
+        .lines()
+        .map(str::trim)
+        .filter(|c| !c.is_empty() && !SKIPPED_COMPONENTS.contains(c))
     {
-        let entry = entry.map_err(|e| anyhow!("读取目录条目失败: {}", e))?;
-        let src = entry.path();
-        let dest = target_dir.join(entry.file_name());
-        std::fs::rename(&src, &dest)
-            .map_err(|e| anyhow!("移动 {:?} 到 {:?} 失败: {}", src, dest, e))?;
+        let component_dir = dist_dir.join(component);
+        for entry in walkdir::WalkDir::new(&component_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+        {
+            let relative = entry.path().strip_prefix(&component_dir)?;
+            if relative == Path::new("manifest.in") {
+                continue;
+            }
+            let dest = install_dir.join(relative);
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(entry.path(), &dest)
+                .map_err(|e| anyhow!("复制 {:?} 到 {:?} 失败: {}", entry.path(), dest, e))?;
+        }
     }
-
-    std::fs::remove_dir(&nested_dir)
-        .map_err(|e| anyhow!("删除嵌套目录 {:?} 失败: {}", nested_dir, e))?;
-
     Ok(())
 }
 
-/// 解压 zip 格式文件
-async fn extract_zip(archive_path: &PathBuf, target_dir: &PathBuf) -> Result<()> {
-    use std::fs::File;
-    use zip::ZipArchive;
-
-    let file = File::open(archive_path)?;
-    let mut archive = ZipArchive::new(file)?;
-
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i)?;
-        let outpath = match file.enclosed_name() {
-            Some(path) => target_dir.join(path),
-            None => continue,
-        };
+/// 预设名称转换为地址，其余值必须是 http(s) 或 sparse+http(s) 地址
+fn resolve_registry_mirror(mirror: &str) -> Result<String> {
+    let mirror = mirror.trim();
+    if let Some((_, url)) = REGISTRY_MIRROR_PRESETS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(mirror))
+    {
+        return Ok(url.to_string());
+    }
+    let url = mirror.strip_prefix("sparse+").unwrap_or(mirror);
+    if (url.starts_with("https://") || url.starts_with("http://"))
+        && !mirror.contains(|c: char| c.is_whitespace() || c == '"')
+    {
+        return Ok(mirror.to_string());
+    }
+    Err(anyhow!(
+        "无效的 Cargo 镜像: {}，可使用 {} 或镜像地址",
+        mirror,
+        REGISTRY_MIRROR_PRESETS
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join("、")
+    ))
+}
 
-        if file.name().ends_with('/') {
-            std::fs::create_dir_all(&outpath)?;
-        } else {
-            if let Some(parent) = outpath.parent() {
-                std::fs::create_dir_all(parent)?;
-            }
-            let mut outfile = File::create(&outpath)?;
-            std::io::copy(&mut file, &mut outfile)?;
+/// 去掉 config.toml 中 [source.crates-io] 与镜像源的配置段，保留其余内容
+fn without_mirror_sections(content: &str) -> String {
+    let mirror_section = format!("[source.{}]", MIRROR_SOURCE_NAME);
+    let mut skipping = false;
+    let mut lines = Vec::new();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            skipping = trimmed == "[source.crates-io]" || trimmed == mirror_section;
         }
-
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            if let Some(mode) = file.unix_mode() {
-                std::fs::set_permissions(&outpath, std::fs::Permissions::from_mode(mode))?;
-            }
+        if !skipping {
+            lines.push(line);
         }
     }
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
 
-    Ok(())
+    let mut result = lines.join("\n");
+    if !result.is_empty() {
+        result.push('\n');
+    }
+    result
 }
 
 /// 设置可执行权限
@@ -0,0 +1,364 @@
+use crate::manager::env_serv_data_manager::ServiceDataResult;
+use crate::manager::services::health;
+use crate::manager::system_info_manager::SystemInfoManager;
+use crate::types::ServiceData;
+use crate::utils::create_command;
+use anyhow::Result;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// 未配置 STOP_TIMEOUT 时等待正常关闭的最长时间
+pub const DEFAULT_STOP_TIMEOUT: Duration = Duration::from_secs(30);
+/// metadata 中配置正常关闭超时（秒）的键
+pub const STOP_TIMEOUT_KEY: &str = "STOP_TIMEOUT";
+/// 轮询进程与端口状态的间隔
+const STOP_POLL: Duration = Duration::from_millis(200);
+/// 关闭命令报错后仍等待进程退出的时间（服务端关闭时断开连接也会导致客户端报错）
+const COMMAND_FAILURE_GRACE: Duration = Duration::from_secs(3);
+/// 强制结束后等待进程消失的时间
+const KILL_WAIT: Duration = Duration::from_secs(5);
+
+/// 读取 metadata 中的 STOP_TIMEOUT（秒），未配置或无效时使用默认值
+pub fn stop_timeout(service_data: &ServiceData) -> Duration {
+    service_data
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get(STOP_TIMEOUT_KEY))
+        .and_then(|v| {
+            v.as_u64()
+                .or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()))
+        })
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_STOP_TIMEOUT)
+}
+
+/// 读取 pid 文件首行记录的进程号
+pub fn read_pid_file(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path)
+        .ok()?
+        .lines()
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// 读取 pid 文件，进程仍在运行时返回进程号
+pub fn read_running_pid(path: &Path) -> Option<u32> {
+    read_pid_file(path).filter(|pid| SystemInfoManager::global().is_process_alive(*pid))
+}
+
+/// 本机端口是否仍可连接
+fn is_port_open(port: u16) -> bool {
+    health::check_tcp_port("127.0.0.1", port).is_some()
+}
+
+/// 在 deadline 之前轮询 done，满足时返回 true
+fn wait_until(deadline: Instant, mut done: impl FnMut() -> bool) -> bool {
+    loop {
+        if done() {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(STOP_POLL);
+    }
+}
+
+/// 等待本机端口不再被监听，用于重启前确认旧进程已释放端口
+pub fn wait_for_port_release(port: u16, timeout: Duration) -> bool {
+    wait_until(Instant::now() + timeout, || !is_port_open(port))
+}
+
+/// 执行关闭命令，超过 deadline 时结束命令本身
+fn run_until(mut command: Command, deadline: Instant) -> std::result::Result<(), String> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("执行关闭命令失败: {}", e))?;
+
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok(()),
+            Ok(Some(status)) => {
+                let mut stderr = String::new();
+                if let Some(mut pipe) = child.stderr.take() {
+                    let _ = pipe.read_to_string(&mut stderr);
+                }
+                return Err(format!(
+                    "关闭命令失败(exit {}): {}",
+                    status.code().unwrap_or(-1),
+                    stderr.trim()
+                ));
+            }
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err("关闭命令执行超时".to_string());
+            }
+            Ok(None) => std::thread::sleep(STOP_POLL),
+            Err(e) => return Err(format!("等待关闭命令失败: {}", e)),
+        }
+    }
+}
+
+/// 数据库服务的正常关闭流程：执行关闭命令并等待进程退出、端口释放，
+/// 超时后强制结束记录的进程
+pub struct GracefulStop<'a> {
+    /// 服务名称，用于返回消息
+    pub service_name: &'a str,
+    /// 正常关闭方式，如 "mysqladmin shutdown"
+    pub method: &'a str,
+    /// 关闭命令，缺少凭据等无法构建时直接强制结束
+    pub command: Result<Command>,
+    /// pid 文件记录的进程，缺失时按端口查找
+    pub pid: Option<u32>,
+    pub port: Option<u16>,
+    pub timeout: Duration,
+}
+
+impl GracefulStop<'_> {
+    pub fn run(self) -> ServiceDataResult {
+        let system = SystemInfoManager::global();
+        let pid = self
+            .pid
+            .filter(|pid| system.is_process_alive(*pid))
+            .or_else(|| self.port.and_then(|port| system.find_listening_pid(port)));
+        let port = self.port;
+        let stopped = || {
+            pid.map(|pid| !system.is_process_alive(pid)).unwrap_or(true)
+                && port.map(|port| !is_port_open(port)).unwrap_or(true)
+        };
+
+        if pid.is_none() && port.map(|port| !is_port_open(port)).unwrap_or(true) {
+            return ServiceDataResult {
+                success: true,
+                message: format!("{} 未在运行", self.service_name),
+                data: Some(serde_json::json!({ "stopMethod": "none" })),
+            };
+        }
+
+        let deadline = Instant::now() + self.timeout;
+        let failure = match self.command {
+            Ok(command) => {
+                log::info!("使用 {} 停止 {}", self.method, self.service_name);
+                run_until(command, deadline).err()
+            }
+            Err(e) => Some(e.to_string()),
+        };
+        let wait_deadline = match failure {
+            Some(_) => deadline.min(Instant::now() + COMMAND_FAILURE_GRACE),
+            None => deadline,
+        };
+        if wait_until(wait_deadline, stopped) {
+            return ServiceDataResult {
+                success: true,
+                message: format!("{} 已通过 {} 正常停止", self.service_name, self.method),
+                data: Some(serde_json::json!({
+                    "stopMethod": "graceful",
+                    "method": self.method,
+                })),
+            };
+        }
+
+        let reason = failure.unwrap_or_else(|| format!("{} 秒内未停止", self.timeout.as_secs()));
+        log::warn!(
+            "{} 未能通过 {} 正常停止: {}",
+            self.service_name,
+            self.method,
+            reason
+        );
+        let Some(pid) = pid else {
+            return ServiceDataResult {
+                success: false,
+                message: format!(
+                    "{} 未能通过 {} 正常停止（{}），且未找到进程 PID，无法强制结束",
+                    self.service_name, self.method, reason
+                ),
+                data: None,
+            };
+        };
+
+        match system.kill_process(pid) {
+            Ok(_) => {
+                wait_until(Instant::now() + KILL_WAIT, stopped);
+                ServiceDataResult {
+                    success: true,
+                    message: format!(
+                        "{} 未能通过 {} 正常停止（{}），已强制结束进程 {}",
+                        self.service_name, self.method, reason, pid
+                    ),
+                    data: Some(serde_json::json!({
+                        "stopMethod": "hardKill",
+                        "method": self.method,
+                        "reason": reason,
+                        "pid": pid,
+                    })),
+                }
+            }
+            Err(e) => ServiceDataResult {
+                success: false,
+                message: format!("强制结束 {} 进程 {} 失败: {}", self.service_name, pid, e),
+                data: None,
+            },
+        }
+    }
+}
+
+/// 停止 pid 文件记录的进程：发送 signal（Windows 上直接 taskkill）后最多等待 timeout，
+/// 仍未退出时强制结束，最后删除 pid 文件
+pub fn stop_pid_file_process(
+    service_name: &str,
+    pid_path: &Path,
+    signal: &str,
+    timeout: Duration,
+) -> ServiceDataResult {
+    let Some(pid) = read_running_pid(pid_path) else {
+        let _ = std::fs::remove_file(pid_path);
+        return ServiceDataResult {
+            success: true,
+            message: format!("{} 未在运行", service_name),
+            data: None,
+        };
+    };
+
+    let output = if cfg!(target_os = "windows") {
+        create_command("taskkill")
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .output()
+    } else {
+        create_command("kill")
+            .args([format!("-{}", signal), pid.to_string()])
+            .output()
+    };
+    match output {
+        Ok(o) if o.status.success() => {}
+        Ok(o) => {
+            return ServiceDataResult {
+                success: false,
+                message: format!(
+                    "停止失败(exit {}): {}",
+                    o.status.code().unwrap_or(-1),
+                    String::from_utf8_lossy(&o.stderr)
+                ),
+                data: None,
+            }
+        }
+        Err(e) => {
+            return ServiceDataResult {
+                success: false,
+                message: format!("停止命令失败: {}", e),
+                data: None,
+            }
+        }
+    }
+
+    let system = SystemInfoManager::global();
+    let exited = || !system.is_process_alive(pid);
+    if !wait_until(Instant::now() + timeout, exited) {
+        log::warn!(
+            "{} 未在 {} 秒内退出，强制结束进程 {}",
+            service_name,
+            timeout.as_secs(),
+            pid
+        );
+        let _ = system.kill_process(pid);
+        wait_until(Instant::now() + KILL_WAIT, exited);
+    }
+    let _ = std::fs::remove_file(pid_path);
+    ServiceDataResult {
+        success: true,
+        message: format!("{} 已停止", service_name),
+        data: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_pid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("service.pid");
+        assert_eq!(read_pid_file(&path), None);
+
+        std::fs::write(&path, "1234\n").unwrap();
+        assert_eq!(read_pid_file(&path), Some(1234));
+
+        // 只取首行，忽略首尾空白
+        std::fs::write(&path, "  5678  \nextra\n").unwrap();
+        assert_eq!(read_pid_file(&path), Some(5678));
+
+        std::fs::write(&path, "not-a-pid\n").unwrap();
+        assert_eq!(read_pid_file(&path), None);
+        std::fs::write(&path, "").unwrap();
+        assert_eq!(read_pid_file(&path), None);
+    }
+
+    fn graceful_stop(command: Result<Command>, pid: Option<u32>) -> ServiceDataResult {
+        GracefulStop {
+            service_name: "Test",
+            method: "test shutdown",
+            command,
+            pid,
+            port: None,
+            timeout: Duration::from_secs(1),
+        }
+        .run()
+    }
+
+    /// 启动一个长时间运行的进程，退出后由后台线程回收，避免僵尸进程被视为仍在运行
+    #[cfg(unix)]
+    fn spawn_sleeper() -> u32 {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let pid = child.id();
+        std::thread::spawn(move || child.wait());
+        pid
+    }
+
+    #[test]
+    fn test_graceful_stop_not_running() {
+        let result = graceful_stop(Ok(Command::new("true")), None);
+        assert!(result.success);
+        assert_eq!(result.data.unwrap()["stopMethod"], "none");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_graceful_stop_by_command() {
+        let pid = spawn_sleeper();
+        let mut command = Command::new("kill");
+        command.arg(pid.to_string());
+        let result = graceful_stop(Ok(command), Some(pid));
+        assert!(result.success);
+        assert_eq!(result.data.unwrap()["stopMethod"], "graceful");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_graceful_stop_falls_back_to_kill() {
+        // 关闭命令成功但进程未退出
+        let pid = spawn_sleeper();
+        let result = graceful_stop(Ok(Command::new("true")), Some(pid));
+        assert!(result.success);
+        let data = result.data.unwrap();
+        assert_eq!(data["stopMethod"], "hardKill");
+        assert_eq!(data["pid"], pid);
+        assert!(!SystemInfoManager::global().is_process_alive(pid));
+
+        // 无法构建关闭命令时同样强制结束
+        let pid = spawn_sleeper();
+        let result = graceful_stop(Err(anyhow::anyhow!("缺少凭据")), Some(pid));
+        assert!(result.success);
+        let data = result.data.unwrap();
+        assert_eq!(data["stopMethod"], "hardKill");
+        assert_eq!(data["reason"], "缺少凭据");
+    }
+}
@@ -47,6 +47,11 @@ pub struct CertificateFormats {
     pub jks: Option<String>,
 }
 
+/// macOS 系统钥匙串
+const MACOS_SYSTEM_KEYCHAIN: &str = "/Library/Keychains/System.keychain";
+/// 安装到 Linux 信任目录时使用的文件名
+const SYSTEM_TRUST_CA_FILE_NAME: &str = "envis-ca.crt";
+
 /// 全局 SSL 服务管理器单例
 static GLOBAL_SSL_SERVICE: OnceLock<Arc<SslService>> = OnceLock::new();
 
@@ -643,6 +648,110 @@ subjectAltName = @alt_names
         })
     }
 
+    /// 将 CA 证书安装到系统信任存储
+    ///
+    /// macOS / Linux 通过 `sudo -S` 提权，需要提供用户密码；Windows 需以管理员身份运行应用。
+    pub fn install_ca_to_system_trust(
+        &self,
+        environment_id: &str,
+        _service_data_id: &str,
+        password: Option<&str>,
+    ) -> Result<ServiceDataResult> {
+        if !self.is_ca_initialized(environment_id) {
+            return Err(anyhow!("CA 未初始化"));
+        }
+        let ca_cert_path = self.get_ca_folder().join("ca.crt");
+        let ca_cert = ca_cert_path.to_string_lossy().to_string();
+
+        if cfg!(target_os = "macos") {
+            run_privileged(
+                "security",
+                &[
+                    "add-trusted-cert",
+                    "-d",
+                    "-r",
+                    "trustRoot",
+                    "-k",
+                    MACOS_SYSTEM_KEYCHAIN,
+                    &ca_cert,
+                ],
+                password,
+            )?;
+        } else if cfg!(target_os = "windows") {
+            run_privileged("certutil", &["-addstore", "Root", &ca_cert], password)?;
+        } else if cfg!(target_os = "linux") {
+            let (anchor_dir, update_cmd) = linux_trust_store();
+            let target = format!("{}/{}", anchor_dir, SYSTEM_TRUST_CA_FILE_NAME);
+            // 路径通过位置参数传入，避免拼接到 shell 脚本中
+            let script = format!("mkdir -p \"$1\" && cp \"$2\" \"$3\" && {}", update_cmd);
+            run_privileged(
+                "sh",
+                &["-c", &script, "sh", anchor_dir, &ca_cert, &target],
+                password,
+            )?;
+        } else {
+            return Err(anyhow!("当前操作系统不支持自动安装 CA 证书"));
+        }
+
+        log::info!("CA 证书已安装到系统信任存储: {}", ca_cert);
+        Ok(ServiceDataResult {
+            success: true,
+            message: "CA 证书已安装到系统信任存储".to_string(),
+            data: Some(serde_json::json!({
+                "installed": true,
+                "certPath": ca_cert,
+            })),
+        })
+    }
+
+    /// 从系统信任存储中移除 CA 证书
+    pub fn uninstall_ca_from_system_trust(
+        &self,
+        _environment_id: &str,
+        _service_data_id: &str,
+        password: Option<&str>,
+    ) -> Result<ServiceDataResult> {
+        let ca_cert_path = self.get_ca_folder().join("ca.crt");
+        let ca_cert = ca_cert_path.to_string_lossy().to_string();
+
+        if cfg!(target_os = "macos") {
+            let fingerprint = cert_fingerprint(&ca_cert_path, "-sha1")?;
+            // 先移除信任设置，证书不在钥匙串中时忽略失败
+            let _ = run_privileged(
+                "security",
+                &["remove-trusted-cert", "-d", &ca_cert],
+                password,
+            );
+            run_privileged(
+                "security",
+                &[
+                    "delete-certificate",
+                    "-Z",
+                    &fingerprint,
+                    MACOS_SYSTEM_KEYCHAIN,
+                ],
+                password,
+            )?;
+        } else if cfg!(target_os = "windows") {
+            let fingerprint = cert_fingerprint(&ca_cert_path, "-sha1")?;
+            run_privileged("certutil", &["-delstore", "Root", &fingerprint], password)?;
+        } else if cfg!(target_os = "linux") {
+            let (anchor_dir, update_cmd) = linux_trust_store();
+            let target = format!("{}/{}", anchor_dir, SYSTEM_TRUST_CA_FILE_NAME);
+            let script = format!("rm -f \"$1\" && {}", update_cmd);
+            run_privileged("sh", &["-c", &script, "sh", &target], password)?;
+        } else {
+            return Err(anyhow!("当前操作系统不支持自动移除 CA 证书"));
+        }
+
+        log::info!("CA 证书已从系统信任存储移除: {}", ca_cert);
+        Ok(ServiceDataResult {
+            success: true,
+            message: "CA 证书已从系统信任存储移除".to_string(),
+            data: Some(serde_json::json!({ "installed": false })),
+        })
+    }
+
     /// 检查 CA 在 macOS 系统中是否已安装
     fn check_ca_installed_macos(&self, ca_cert_path: &PathBuf) -> Result<bool> {
         // 获取证书的 SHA-1 指纹
@@ -789,6 +898,99 @@ subjectAltName = @alt_names
     }
 }
 
+/// Linux 信任目录及刷新命令：Debian 系使用 update-ca-certificates，RHEL 系使用 update-ca-trust
+fn linux_trust_store() -> (&'static str, &'static str) {
+    let debian_dir = "/usr/local/share/ca-certificates";
+    let rhel_dir = "/etc/pki/ca-trust/source/anchors";
+    if !PathBuf::from(debian_dir).exists() && PathBuf::from(rhel_dir).exists() {
+        (rhel_dir, "update-ca-trust")
+    } else {
+        (debian_dir, "update-ca-certificates")
+    }
+}
+
+/// 获取证书指纹（十六进制，无冒号），algorithm 如 `-sha1`
+fn cert_fingerprint(cert_path: &PathBuf, algorithm: &str) -> Result<String> {
+    let output = create_command("openssl")
+        .args(["x509", "-noout", "-fingerprint", algorithm, "-in"])
+        .arg(cert_path)
+        .output()?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "读取证书指纹失败: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let fingerprint = stdout
+        .split('=')
+        .nth(1)
+        .unwrap_or("")
+        .trim()
+        .replace(':', "");
+    if fingerprint.is_empty() {
+        return Err(anyhow!("读取证书指纹失败"));
+    }
+    Ok(fingerprint)
+}
+
+/// 以管理员权限执行命令：Unix 下通过 `sudo -S` 从 stdin 读取密码，Windows 下直接执行
+fn run_privileged(program: &str, args: &[&str], password: Option<&str>) -> Result<()> {
+    #[cfg(not(target_os = "windows"))]
+    let output = {
+        use std::io::Write;
+        use std::process::Stdio;
+
+        let password = password
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| anyhow!("需要提供管理员密码"))?;
+        let mut child = create_command("sudo")
+            .arg("-S")
+            .arg("-p")
+            .arg("")
+            .arg(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            writeln!(stdin, "{}", password)?;
+        }
+        child.wait_with_output()?
+    };
+
+    #[cfg(target_os = "windows")]
+    let output = {
+        let _ = password;
+        create_command(program).args(args).output()?
+    };
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if stderr.contains("incorrect password") || stderr.contains("Sorry, try again") {
+            return Err(anyhow!("密码错误，请重新输入"));
+        }
+        if cfg!(target_os = "windows") {
+            return Err(anyhow!(
+                "执行 {} 失败，请以管理员身份运行应用: {}{}",
+                program,
+                stderr.trim(),
+                stdout.trim()
+            ));
+        }
+        return Err(anyhow!(
+            "执行 {} 失败: {}{}",
+            program,
+            stderr.trim(),
+            stdout.trim()
+        ));
+    }
+    Ok(())
+}
+
 #[derive(Debug, Default)]
 struct CertInfo {
     subject: String,
@@ -48,6 +48,8 @@ impl ServiceLifecycle for StandardService {
             .join(service_dir_name)
             .join(&service_data.version);
 
+        let mut update = shell_manager.begin_update();
+
         // 构建环境变量
         let env_vars =
             EnvVarBuilder::build_env_vars_for_service(&service_data.service_type, &service_folder)?;
@@ -67,9 +69,7 @@ impl ServiceLifecycle for StandardService {
                 default_value.clone()
             };
 
-            shell_manager
-                .add_export(env_var_name, &value_str)
-                .with_context(|| format!("设置环境变量 {} 失败", env_var_name))?;
+            update.add_export(env_var_name, &value_str);
             log::debug!("已设置环境变量: {}={}", env_var_name, value_str);
         }
 
@@ -77,15 +77,14 @@ impl ServiceLifecycle for StandardService {
         let paths = EnvPathBuilder::build_paths(service_data)?;
         for path_str in paths {
             if std::path::Path::new(&path_str).exists() {
-                shell_manager
-                    .add_path(&path_str)
-                    .with_context(|| format!("添加路径到 PATH 失败: {}", path_str))?;
+                update.add_path(&path_str);
                 log::debug!("已添加到 PATH: {}", path_str);
             } else {
                 log::debug!("路径不存在，跳过添加到 PATH: {}", path_str);
             }
         }
 
+        update.commit().context("更新 Shell 环境变量失败")?;
         Ok(())
     }
 
@@ -115,22 +114,20 @@ impl ServiceLifecycle for StandardService {
         let env_vars =
             EnvVarBuilder::build_env_vars_for_service(&service_data.service_type, &service_folder)?;
 
+        let mut update = shell_manager.begin_update();
         for (env_var_name, _) in env_vars.iter() {
-            shell_manager
-                .delete_export(env_var_name)
-                .with_context(|| format!("移除环境变量 {} 失败", env_var_name))?;
+            update.delete_export(env_var_name);
             log::debug!("已移除环境变量: {}", env_var_name);
         }
 
         // 从 PATH 中移除对应路径
         let paths = EnvPathBuilder::build_paths(service_data)?;
         for path_str in paths {
-            shell_manager
-                .delete_path(&path_str)
-                .with_context(|| format!("从 PATH 移除失败: {}", path_str))?;
+            update.delete_path(&path_str);
             log::debug!("已从 PATH 移除: {}", path_str);
         }
 
+        update.commit().context("移除 Shell 环境变量失败")?;
         Ok(())
     }
 }
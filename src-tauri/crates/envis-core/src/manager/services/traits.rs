@@ -1,22 +1,126 @@
-use crate::types::ServiceData;
-use anyhow::Result;
+use crate::manager::env_serv_data_manager::ServiceDataResult;
+use crate::manager::services::download_manager::{DownloadResult, DownloadTask};
+use crate::manager::services::standard::StandardService;
+use crate::types::{ServiceData, ServiceStatus};
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use std::path::PathBuf;
 
 /// 服务生命周期特征
-/// 定义服务的激活和停用行为
-pub trait ServiceLifecycle {
-    /// 激活服务
+/// 定义服务的激活、停用、下载安装和进程启停行为，服务不具备的能力使用默认实现
+pub trait ServiceLifecycle: Send + Sync {
+    /// 激活服务（默认写入构建器生成的环境变量和 PATH）
     fn active(
         &self,
         environment_id: &str,
         service_data: &ServiceData,
         password: Option<String>,
-    ) -> Result<()>;
+    ) -> Result<()> {
+        StandardService::global().active(environment_id, service_data, password)
+    }
 
-    /// 停用服务
+    /// 停用服务（默认移除构建器生成的环境变量和 PATH）
     fn deactive(
         &self,
         environment_id: &str,
         service_data: &ServiceData,
         password: Option<String>,
-    ) -> Result<()>;
+    ) -> Result<()> {
+        StandardService::global().deactive(environment_id, service_data, password)
+    }
+
+    /// 指定版本是否已安装（预期的可执行文件存在），无需安装的服务视为已安装
+    fn is_installed(&self, _version: &str) -> bool {
+        true
+    }
+
+    /// 可下载的版本号列表
+    fn available_versions(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// 下载指定版本，下载完成后在后台解压安装
+    fn download_and_install<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<DownloadResult>> {
+        Box::pin(async move { Err(anyhow!("该服务无需下载安装: {}", version)) })
+    }
+
+    /// 取消下载
+    fn cancel_download(&self, version: &str) -> Result<()> {
+        Err(anyhow!("该服务无需下载安装: {}", version))
+    }
+
+    /// 获取下载进度
+    fn get_download_progress(&self, _version: &str) -> Option<DownloadTask> {
+        None
+    }
+
+    /// 压缩包的存放目录（与下载时一致），离线安装时将本地压缩包复制到此处；None 表示不支持离线安装
+    fn archive_dir(&self, _version: &str) -> Option<PathBuf> {
+        None
+    }
+
+    /// 解压安装 archive_dir 中的压缩包
+    fn extract_and_install<'a>(
+        &'a self,
+        _task: &'a DownloadTask,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move { Err(anyhow!("该服务无需下载安装: {}", version)) })
+    }
+
+    /// 启动服务进程
+    fn start_service(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        Err(not_daemon(service_data))
+    }
+
+    /// 停止服务进程
+    fn stop_service(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        Err(not_daemon(service_data))
+    }
+
+    /// 重启服务进程
+    fn restart_service(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        Err(not_daemon(service_data))
+    }
+
+    /// 获取服务进程状态，data 中的 isRunning 表示进程是否在运行
+    fn get_service_status(
+        &self,
+        _environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        Err(not_daemon(service_data))
+    }
+}
+
+fn not_daemon(service_data: &ServiceData) -> anyhow::Error {
+    anyhow!("服务类型 {:?} 不是常驻服务", service_data.service_type)
+}
+
+/// 将进程状态包装为 get_service_status 的返回值（Degraded 视为仍在运行）
+pub(crate) fn status_result(status: ServiceStatus) -> ServiceDataResult {
+    let is_running = matches!(status, ServiceStatus::Running | ServiceStatus::Degraded);
+    ServiceDataResult {
+        success: true,
+        message: "获取服务状态成功".to_string(),
+        data: Some(serde_json::json!({
+            "status": status,
+            "isRunning": is_running,
+        })),
+    }
 }
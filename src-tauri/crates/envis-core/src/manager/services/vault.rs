@@ -0,0 +1,761 @@
+use crate::manager::app_config_manager::AppConfigManager;
+use crate::manager::env_serv_data_manager::ServiceDataResult;
+use crate::manager::service_supervisor::ServiceSupervisor;
+use crate::manager::services::health::{self, ServiceHealth};
+use crate::manager::services::traits::ServiceLifecycle;
+use crate::manager::services::{
+    download_service_package, http_api, remove_partial_install, shutdown, DownloadManager,
+    DownloadResult, DownloadTask,
+};
+use crate::manager::system_info_manager::SystemInfoManager;
+use crate::types::{ServiceData, ServiceType};
+use crate::utils::create_command;
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultVersion {
+    pub version: String,
+    pub date: String,
+}
+
+/// Vault 默认监听地址
+pub const DEFAULT_VAULT_ADDR: &str = "http://127.0.0.1:8200";
+/// dev 模式下默认挂载的 KV v2 引擎路径
+const KV_MOUNT: &str = "secret";
+
+static GLOBAL_VAULT_SERVICE: OnceLock<Arc<VaultService>> = OnceLock::new();
+
+/// HashiCorp Vault 服务，仅以 dev 模式运行（数据保存在内存中，停止后丢失）
+pub struct VaultService {}
+
+impl VaultService {
+    pub fn global() -> Arc<VaultService> {
+        GLOBAL_VAULT_SERVICE
+            .get_or_init(|| Arc::new(VaultService::new()))
+            .clone()
+    }
+
+    fn new() -> Self {
+        Self {}
+    }
+
+    pub fn get_available_versions(&self) -> Vec<VaultVersion> {
+        vec![
+            VaultVersion {
+                version: "1.20.4".to_string(),
+                date: "2025-09-24".to_string(),
+            },
+            VaultVersion {
+                version: "1.19.5".to_string(),
+                date: "2025-06-05".to_string(),
+            },
+            VaultVersion {
+                version: "1.18.5".to_string(),
+                date: "2025-02-24".to_string(),
+            },
+        ]
+    }
+
+    pub fn is_installed(&self, version: &str) -> bool {
+        self.get_vault_bin_path(version).exists()
+    }
+
+    fn get_install_path(&self, version: &str) -> PathBuf {
+        let services_folder = {
+            let app_config_manager = AppConfigManager::global();
+            let app_config_manager = app_config_manager.lock().unwrap();
+            PathBuf::from(app_config_manager.get_services_folder())
+        };
+        services_folder.join("vault").join(version)
+    }
+
+    fn get_service_data_folder(&self, environment_id: &str, version: &str) -> PathBuf {
+        let app_config_manager = AppConfigManager::global();
+        let app_config_manager = app_config_manager.lock().unwrap();
+        let envs_folder = app_config_manager.get_envs_folder();
+
+        PathBuf::from(envs_folder)
+            .join(environment_id)
+            .join("vault")
+            .join(version)
+    }
+
+    fn get_vault_bin_path(&self, version: &str) -> PathBuf {
+        let install_path = self.get_install_path(version);
+        if cfg!(target_os = "windows") {
+            install_path.join("vault.exe")
+        } else {
+            install_path.join("vault")
+        }
+    }
+
+    fn map_platform_arch(&self) -> Result<(&'static str, &'static str)> {
+        let os = match std::env::consts::OS {
+            "macos" => "darwin",
+            "linux" => "linux",
+            "windows" => "windows",
+            os => return Err(anyhow!("不支持的操作系统: {}", os)),
+        };
+        let arch = match std::env::consts::ARCH {
+            "x86_64" => "amd64",
+            "aarch64" => "arm64",
+            arch => return Err(anyhow!("不支持的架构: {}", arch)),
+        };
+        Ok((os, arch))
+    }
+
+    fn build_download_info(&self, version: &str) -> Result<(Vec<String>, String)> {
+        let (os, arch) = self.map_platform_arch()?;
+        let filename = format!("vault_{}_{}_{}.zip", version, os, arch);
+        let url = format!(
+            "https://releases.hashicorp.com/vault/{}/{}",
+            version, filename
+        );
+
+        Ok((vec![url], filename))
+    }
+
+    pub async fn download_and_install(&self, version: &str) -> Result<DownloadResult> {
+        if self.is_installed(version) {
+            return Ok(DownloadResult::success(
+                format!("Vault {} 已经安装", version),
+                None,
+            ));
+        }
+
+        let (urls, filename) = self.build_download_info(version)?;
+        download_service_package(
+            ServiceType::Vault,
+            version,
+            urls,
+            self.get_install_path(version),
+            filename,
+        )
+        .await
+    }
+
+    pub async fn extract_and_install(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let result = self.install_from_archive(task, version).await;
+        if result.is_err() {
+            // 解压安装失败时清理残缺的安装目录
+            remove_partial_install(&self.get_install_path(version));
+        }
+        result
+    }
+
+    /// 将下载的压缩包解压到安装目录
+    async fn install_from_archive(&self, task: &DownloadTask, version: &str) -> Result<()> {
+        let archive_path = &task.target_path;
+        let install_dir = self.get_install_path(version);
+        std::fs::create_dir_all(&install_dir)?;
+
+        if !task.filename.ends_with(".zip") {
+            return Err(anyhow!("不支持的压缩格式: {}", task.filename));
+        }
+        // 官方发布包为不含顶层目录的 zip，直接解压到安装目录
+        Self::extract_zip(archive_path, &install_dir)?;
+
+        let vault_bin = self.get_vault_bin_path(version);
+        if !vault_bin.exists() {
+            return Err(anyhow!("未找到 vault 可执行文件"));
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = std::fs::metadata(&vault_bin)?.permissions();
+            perms.set_mode(0o755);
+            std::fs::set_permissions(&vault_bin, perms)?;
+        }
+
+        if archive_path.exists() {
+            std::fs::remove_file(archive_path)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn cancel_download(&self, version: &str) -> Result<()> {
+        let task_id = format!("vault-{}", version);
+        DownloadManager::global().cancel_download(&task_id)
+    }
+
+    pub fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        let task_id = format!("vault-{}", version);
+        DownloadManager::global().get_task_status(&task_id)
+    }
+
+    pub fn get_service_status(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let config = self.get_runtime_config(environment_id, service_data)?;
+        let health = self.check_health(&config);
+        let running = health.process || health.port_open;
+
+        let mut data = serde_json::json!({
+            "isRunning": running,
+            "addr": config.addr,
+            "pid": shutdown::read_running_pid(&config.pid_path),
+            "logPath": config.log_path,
+        });
+        health.merge_into(&mut data);
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "获取 Vault 状态成功".to_string(),
+            data: Some(data),
+        })
+    }
+
+    /// 以 dev 模式启动 Vault：
+    /// `vault server -dev -dev-root-token-id=<token> -dev-listen-address=<addr>:<port>`
+    pub fn start_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        ServiceSupervisor::global().clear_intentional_stop(&service_data.id);
+        let vault_bin = self.get_vault_bin_path(&service_data.version);
+
+        if !vault_bin.exists() {
+            return Ok(ServiceDataResult {
+                success: false,
+                message: "vault 可执行文件不存在".to_string(),
+                data: None,
+            });
+        }
+
+        let config = self.get_runtime_config(environment_id, service_data)?;
+
+        if let Some(pid) = shutdown::read_running_pid(&config.pid_path) {
+            return Ok(ServiceDataResult {
+                success: true,
+                message: "Vault 已在运行".to_string(),
+                data: Some(serde_json::json!({
+                    "addr": config.addr,
+                    "pid": pid,
+                    "alreadyRunning": true
+                })),
+            });
+        }
+
+        if health::check_tcp_port(&config.host, config.port).is_some() {
+            return Ok(ServiceDataResult {
+                success: false,
+                message: format!("端口 {} 已被占用", config.port),
+                data: None,
+            });
+        }
+
+        if let Some(parent) = config.log_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        if let Some(parent) = config.pid_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let log_file = std::fs::File::create(&config.log_path)?;
+
+        // dev 模式不写 pid 文件，由这里记录子进程 PID
+        let child = create_command(&vault_bin)
+            .arg("server")
+            .arg("-dev")
+            .arg(format!("-dev-root-token-id={}", config.token))
+            .arg(format!(
+                "-dev-listen-address={}:{}",
+                config.host, config.port
+            ))
+            .stdin(std::process::Stdio::null())
+            .stdout(log_file.try_clone()?)
+            .stderr(log_file)
+            .spawn();
+
+        let pid = match child {
+            Ok(child) => child.id(),
+            Err(e) => {
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: format!("启动失败: {}", e),
+                    data: None,
+                })
+            }
+        };
+        std::fs::write(&config.pid_path, pid.to_string())?;
+
+        // dev 模式初始化并解封需要一点时间，等待端口就绪
+        for _ in 0..30 {
+            if health::check_tcp_port(&config.host, config.port).is_some() {
+                break;
+            }
+            if !SystemInfoManager::global().is_process_alive(pid) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(200));
+        }
+
+        if health::check_tcp_port(&config.host, config.port).is_some() {
+            log::info!("Vault 已启动，PID: {}", pid);
+            Ok(ServiceDataResult {
+                success: true,
+                message: "Vault 启动成功".to_string(),
+                data: Some(serde_json::json!({
+                    "addr": config.addr,
+                    "pid": pid,
+                })),
+            })
+        } else {
+            Ok(ServiceDataResult {
+                success: false,
+                message: format!(
+                    "Vault 启动命令已执行，但服务未处于运行状态，请检查日志: {}",
+                    config.log_path.to_string_lossy()
+                ),
+                data: Some(serde_json::json!({
+                    "addr": config.addr,
+                    "logPath": config.log_path,
+                })),
+            })
+        }
+    }
+
+    pub fn stop_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        ServiceSupervisor::global().mark_intentional_stop(&service_data.id);
+        let config = self.get_runtime_config(environment_id, service_data)?;
+
+        Ok(shutdown::stop_pid_file_process(
+            "Vault",
+            &config.pid_path,
+            "TERM",
+            Duration::from_secs(2),
+        ))
+    }
+
+    pub fn restart_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        let _ = self.stop_service(environment_id, service_data);
+        std::thread::sleep(Duration::from_millis(300));
+        self.start_service(environment_id, service_data)
+    }
+
+    /// 列出指定路径下的密钥名称，以 `/` 结尾的为子目录
+    pub async fn list_vault_secrets(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        path: &str,
+    ) -> Result<ServiceDataResult> {
+        let config = self.get_runtime_config(environment_id, service_data)?;
+        let path = Self::normalize_secret_path(path);
+        let url = format!(
+            "{}/v1/{}/metadata/{}?list=true",
+            config.addr, KV_MOUNT, path
+        );
+
+        let response = http_api::api_client()?
+            .get(&url)
+            .header("X-Vault-Token", &config.token)
+            .send()
+            .await?;
+
+        // 路径下没有任何密钥时 Vault 返回 404
+        let keys: Vec<String> = if response.status() == reqwest::StatusCode::NOT_FOUND {
+            Vec::new()
+        } else {
+            let body = Self::parse_response(response).await?;
+            body.pointer("/data/keys")
+                .and_then(|v| v.as_array())
+                .map(|keys| {
+                    keys.iter()
+                        .filter_map(|k| k.as_str().map(|s| s.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "获取 Vault 密钥列表成功".to_string(),
+            data: Some(serde_json::json!({
+                "path": path,
+                "keys": keys,
+            })),
+        })
+    }
+
+    /// 读取密钥中指定字段的值
+    pub async fn read_vault_secret(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        path: &str,
+        key: &str,
+    ) -> Result<ServiceDataResult> {
+        let config = self.get_runtime_config(environment_id, service_data)?;
+        let path = Self::require_secret_path(path)?;
+
+        let data = self.read_secret_data(&config, &path).await?;
+        let value = match data.as_ref().and_then(|d| d.get(key)) {
+            Some(value) => value.clone(),
+            None => {
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: format!("密钥 {} 中不存在字段 {}", path, key),
+                    data: None,
+                })
+            }
+        };
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "读取 Vault 密钥成功".to_string(),
+            data: Some(serde_json::json!({
+                "path": path,
+                "key": key,
+                "value": value,
+            })),
+        })
+    }
+
+    /// 写入密钥中的一个字段，保留同一路径下的其他字段
+    pub async fn write_vault_secret(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        path: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<ServiceDataResult> {
+        let config = self.get_runtime_config(environment_id, service_data)?;
+        let path = Self::require_secret_path(path)?;
+        if key.trim().is_empty() {
+            return Err(anyhow!("字段名不能为空"));
+        }
+
+        let mut data = self
+            .read_secret_data(&config, &path)
+            .await?
+            .unwrap_or_default();
+        data.insert(
+            key.to_string(),
+            serde_json::Value::String(value.to_string()),
+        );
+        let version = self.put_secret_data(&config, &path, data).await?;
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "写入 Vault 密钥成功".to_string(),
+            data: Some(serde_json::json!({
+                "path": path,
+                "key": key,
+                "version": version,
+            })),
+        })
+    }
+
+    /// 删除密钥中的一个字段；字段全部删除后同时移除该路径的所有版本
+    pub async fn delete_vault_secret(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+        path: &str,
+        key: &str,
+    ) -> Result<ServiceDataResult> {
+        let config = self.get_runtime_config(environment_id, service_data)?;
+        let path = Self::require_secret_path(path)?;
+
+        let mut data = match self.read_secret_data(&config, &path).await? {
+            Some(data) if data.contains_key(key) => data,
+            _ => {
+                return Ok(ServiceDataResult {
+                    success: false,
+                    message: format!("密钥 {} 中不存在字段 {}", path, key),
+                    data: None,
+                })
+            }
+        };
+        data.remove(key);
+
+        let removed_path = data.is_empty();
+        if removed_path {
+            let url = format!("{}/v1/{}/metadata/{}", config.addr, KV_MOUNT, path);
+            let response = http_api::api_client()?
+                .delete(&url)
+                .header("X-Vault-Token", &config.token)
+                .send()
+                .await?;
+            Self::parse_response(response).await?;
+        } else {
+            self.put_secret_data(&config, &path, data).await?;
+        }
+
+        Ok(ServiceDataResult {
+            success: true,
+            message: "删除 Vault 密钥成功".to_string(),
+            data: Some(serde_json::json!({
+                "path": path,
+                "key": key,
+                "removedPath": removed_path,
+            })),
+        })
+    }
+
+    /// 读取 KV v2 密钥的最新版本数据，不存在时返回 None
+    async fn read_secret_data(
+        &self,
+        config: &VaultRuntimeConfig,
+        path: &str,
+    ) -> Result<Option<serde_json::Map<String, serde_json::Value>>> {
+        let url = format!("{}/v1/{}/data/{}", config.addr, KV_MOUNT, path);
+        let response = http_api::api_client()?
+            .get(&url)
+            .header("X-Vault-Token", &config.token)
+            .send()
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let body = Self::parse_response(response).await?;
+        Ok(body
+            .pointer("/data/data")
+            .and_then(|v| v.as_object())
+            .cloned())
+    }
+
+    /// 以整体覆盖的方式写入新版本，返回新版本号
+    async fn put_secret_data(
+        &self,
+        config: &VaultRuntimeConfig,
+        path: &str,
+        data: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<Option<u64>> {
+        let url = format!("{}/v1/{}/data/{}", config.addr, KV_MOUNT, path);
+        let payload = serde_json::json!({ "data": data });
+        let response = http_api::api_client()?
+            .post(&url)
+            .header("X-Vault-Token", &config.token)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(serde_json::to_string(&payload)?)
+            .send()
+            .await?;
+
+        let body = Self::parse_response(response).await?;
+        Ok(body.pointer("/data/version").and_then(|v| v.as_u64()))
+    }
+
+    /// 解析 Vault API 响应，非 2xx 时取出 errors 字段作为错误信息
+    async fn parse_response(response: reqwest::Response) -> Result<serde_json::Value> {
+        http_api::parse_json_response(response, "Vault API", |body| {
+            body.get("errors").and_then(|v| v.as_array()).map(|errors| {
+                errors
+                    .iter()
+                    .filter_map(|e| e.as_str())
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            })
+        })
+        .await
+    }
+
+    fn normalize_secret_path(path: &str) -> String {
+        path.trim().trim_matches('/').to_string()
+    }
+
+    fn require_secret_path(path: &str) -> Result<String> {
+        let path = Self::normalize_secret_path(path);
+        if path.is_empty() {
+            return Err(anyhow!("密钥路径不能为空"));
+        }
+        Ok(path)
+    }
+
+    fn check_health(&self, config: &VaultRuntimeConfig) -> ServiceHealth {
+        let process = shutdown::read_running_pid(&config.pid_path).is_some();
+        let port_latency = health::check_tcp_port(&config.host, config.port);
+        let http_latency = port_latency.and_then(|_| health::check_http(&config.host, config.port));
+
+        ServiceHealth {
+            process,
+            port_open: port_latency.is_some(),
+            responding: http_latency.is_some(),
+            latency_ms: http_latency.or(port_latency),
+        }
+    }
+
+    fn get_runtime_config(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<VaultRuntimeConfig> {
+        let service_data_folder =
+            self.get_service_data_folder(environment_id, &service_data.version);
+        let metadata = service_data.metadata.as_ref();
+
+        let addr = metadata
+            .and_then(|m| m.get("VAULT_ADDR"))
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(DEFAULT_VAULT_ADDR)
+            .trim()
+            .trim_end_matches('/')
+            .to_string();
+        let (host, port) = Self::parse_listen_address(&addr)?;
+
+        let token = metadata
+            .and_then(|m| m.get("VAULT_TOKEN"))
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| s.to_string())
+            .ok_or_else(|| anyhow!("未配置 VAULT_TOKEN"))?;
+
+        Ok(VaultRuntimeConfig {
+            addr,
+            host,
+            port,
+            token,
+            log_path: service_data_folder.join("logs").join("vault.log"),
+            pid_path: service_data_folder.join("run").join("vault.pid"),
+        })
+    }
+
+    /// 从 VAULT_ADDR（如 http://127.0.0.1:8200）中解析监听地址与端口
+    fn parse_listen_address(addr: &str) -> Result<(String, u16)> {
+        let without_scheme = addr
+            .strip_prefix("http://")
+            .or_else(|| addr.strip_prefix("https://"))
+            .unwrap_or(addr);
+        let authority = without_scheme.split('/').next().unwrap_or("");
+        let (host, port) = authority
+            .rsplit_once(':')
+            .ok_or_else(|| anyhow!("VAULT_ADDR 缺少端口: {}", addr))?;
+        let port = port
+            .parse::<u16>()
+            .map_err(|_| anyhow!("VAULT_ADDR 端口无效: {}", addr))?;
+        if host.is_empty() {
+            return Err(anyhow!("VAULT_ADDR 缺少主机地址: {}", addr));
+        }
+        Ok((host.to_string(), port))
+    }
+
+    fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<()> {
+        let file =
+            std::fs::File::open(archive_path).map_err(|e| anyhow!("无法打开 zip 文件: {}", e))?;
+        let mut archive =
+            zip::ZipArchive::new(file).map_err(|e| anyhow!("无法读取 zip 文件: {}", e))?;
+
+        for i in 0..archive.len() {
+            let mut file = archive
+                .by_index(i)
+                .map_err(|e| anyhow!("读取 zip 条目失败: {}", e))?;
+            let out_path = dest_dir.join(file.mangled_name());
+
+            if file.is_dir() {
+                std::fs::create_dir_all(&out_path)?;
+            } else {
+                if let Some(parent) = out_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                let mut out_file = std::fs::File::create(&out_path)
+                    .map_err(|e| anyhow!("创建文件失败 {:?}: {}", out_path, e))?;
+                std::io::copy(&mut file, &mut out_file)
+                    .map_err(|e| anyhow!("写入文件失败 {:?}: {}", out_path, e))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct VaultRuntimeConfig {
+    addr: String,
+    host: String,
+    port: u16,
+    token: String,
+    log_path: PathBuf,
+    pid_path: PathBuf,
+}
+
+impl ServiceLifecycle for VaultService {
+    fn is_installed(&self, version: &str) -> bool {
+        VaultService::is_installed(self, version)
+    }
+
+    fn available_versions(&self) -> Vec<String> {
+        self.get_available_versions()
+            .into_iter()
+            .map(|v| v.version)
+            .collect()
+    }
+
+    fn download_and_install<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<DownloadResult>> {
+        Box::pin(VaultService::download_and_install(self, version))
+    }
+
+    fn cancel_download(&self, version: &str) -> Result<()> {
+        VaultService::cancel_download(self, version)
+    }
+
+    fn get_download_progress(&self, version: &str) -> Option<DownloadTask> {
+        VaultService::get_download_progress(self, version)
+    }
+
+    fn archive_dir(&self, version: &str) -> Option<PathBuf> {
+        Some(self.get_install_path(version))
+    }
+
+    fn extract_and_install<'a>(
+        &'a self,
+        task: &'a DownloadTask,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(VaultService::extract_and_install(self, task, version))
+    }
+
+    fn start_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        VaultService::start_service(self, environment_id, service_data)
+    }
+
+    fn stop_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        VaultService::stop_service(self, environment_id, service_data)
+    }
+
+    fn restart_service(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        VaultService::restart_service(self, environment_id, service_data)
+    }
+
+    fn get_service_status(
+        &self,
+        environment_id: &str,
+        service_data: &ServiceData,
+    ) -> Result<ServiceDataResult> {
+        VaultService::get_service_status(self, environment_id, service_data)
+    }
+}
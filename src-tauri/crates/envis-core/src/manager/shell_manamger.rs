@@ -1,11 +1,16 @@
-use crate::utils::create_command;
+use crate::manager::app_config_manager::AppConfigManager;
+use crate::manager::windows_user_env::WindowsUserEnvUpdate;
+use crate::utils::{create_command, kill_process_tree};
 use anyhow::{Context, Result};
-use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
-use std::process::Command;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 use std::sync::{Arc, Mutex, OnceLock};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 // Shell 配置相关常量
 const ENVIS_ACTIVE_BLOCK_START: &str = "# BEGIN Envis Environment Block";
@@ -25,6 +30,37 @@ pub enum ShellType {
 }
 
 impl ShellType {
+    /// 根据配置文件路径推断所属的 Shell 类型
+    fn from_config_file_path(path: &Path) -> Self {
+        match path.extension().and_then(|s| s.to_str()) {
+            Some("cmd") => ShellType::Cmd,
+            Some("ps1") => {
+                // PowerShell 7 的配置文件位于 Documents/PowerShell 目录下
+                let parent = path
+                    .parent()
+                    .and_then(|p| p.file_name())
+                    .and_then(|s| s.to_str());
+                if parent == Some("PowerShell") {
+                    ShellType::PowerShellCore
+                } else {
+                    ShellType::PowerShell
+                }
+            }
+            _ if path.file_name().and_then(|s| s.to_str()) == Some(".zshrc") => ShellType::Zsh,
+            _ => ShellType::Bash,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            ShellType::Bash => "bash",
+            ShellType::Zsh => "zsh",
+            ShellType::PowerShell => "powershell",
+            ShellType::PowerShellCore => "pwsh",
+            ShellType::Cmd => "cmd",
+        }
+    }
+
     fn config_file_name(&self) -> &str {
         match self {
             ShellType::Bash => ".bash_profile",
@@ -36,6 +72,116 @@ impl ShellType {
     }
 }
 
+/// 受管理的 Shell 配置文件信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShellConfigFileInfo {
+    pub path: String,
+    pub exists: bool,
+    pub has_envis_block: bool,
+    pub shell_type: String,
+}
+
+/// Shell 配置文件诊断问题类型
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShellConfigIssueKind {
+    /// 没有 Envis 环境块
+    MissingBlock,
+    /// 嵌套的 BEGIN 标记（上一个块还没有 END）
+    DuplicateBlockStart,
+    /// 存在多个完整或未闭合的环境块
+    DuplicateBlock,
+    /// BEGIN 之后缺少 END 标记
+    MissingBlockEnd,
+    /// 没有对应 BEGIN 的 END 标记
+    OrphanBlockEnd,
+    /// 重复的 export/PATH/alias 等行
+    DuplicateLine,
+    /// 环境块中不是 Envis 写入的行
+    UnknownLine,
+}
+
+/// Shell 配置文件诊断问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShellConfigIssue {
+    pub kind: ShellConfigIssueKind,
+    /// 问题所在行号（从 1 开始）
+    pub line: Option<usize>,
+    pub content: Option<String>,
+}
+
+/// 单个 Shell 配置文件的诊断结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShellConfigDiagnostic {
+    pub path: String,
+    pub shell_type: String,
+    pub exists: bool,
+    pub issues: Vec<ShellConfigIssue>,
+    /// 修复前备份文件路径
+    pub backup_path: Option<String>,
+}
+
+/// Shell 配置文件检查与修复报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShellConfigReport {
+    /// 检查时所有配置文件均无问题
+    pub healthy: bool,
+    pub repaired: bool,
+    /// 检查（修复前）的诊断结果
+    pub files: Vec<ShellConfigDiagnostic>,
+    /// 修复后重新检查的诊断结果
+    pub after_repair: Option<Vec<ShellConfigDiagnostic>>,
+}
+
+/// Shell 配置文件的单个备份
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShellConfigBackup {
+    /// 备份标签；自动备份的标签为扩展名（如 envbak1700000000）
+    pub tag: String,
+    /// 备份时间（Unix 秒）
+    pub timestamp: u64,
+    pub size_bytes: u64,
+    /// 是否为写入 / 修复前自动创建的备份
+    pub automatic: bool,
+}
+
+/// Shell 配置文件及其可用备份
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShellConfigBackups {
+    pub config_path: String,
+    pub backups: Vec<ShellConfigBackup>,
+}
+
+/// Envis 环境块 BEGIN / END 标记的完整性状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IntegrityStatus {
+    Ok,
+    /// 没有环境块，或 END 标记缺少对应的 BEGIN
+    MissingStart,
+    /// BEGIN 标记缺少对应的 END
+    MissingEnd,
+    /// 存在多个完整的环境块
+    Duplicated,
+    /// BEGIN / END 标记顺序错乱，无法配对
+    Unpaired,
+}
+
+/// 单个配置文件的环境块完整性检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvBlockIntegrity {
+    pub path: String,
+    pub status: IntegrityStatus,
+    pub details: String,
+}
+
 /// 全局 Shell 管理器单例
 static SHELL_MANAGER: OnceLock<Arc<Mutex<ShellManager>>> = OnceLock::new();
 
@@ -228,7 +374,7 @@ impl ShellManager {
         &self,
         envis_path: Option<&PathBuf>,
         envis_exe: Option<&PathBuf>,
-        config_file_path: &PathBuf,
+        config_file_path: &Path,
     ) -> String {
         let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
         let is_ps = config_file_path.extension().and_then(|s| s.to_str()) == Some("ps1");
@@ -351,6 +497,42 @@ fi
         format!("{}{}", envis_path_line, envis_alias_line)
     }
 
+    /// 在内容末尾追加包含 envis 基础配置的环境变量块
+    fn append_env_block(
+        &self,
+        config_file_path: &Path,
+        base_content: &str,
+        combined_lines: &str,
+    ) -> String {
+        // 判断文件类型以使用正确的注释前缀
+        let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
+
+        let block_content = if is_cmd {
+            // CMD 使用 REM 作为注释（不包含 # 符号）
+            let prefix = if base_content.is_empty() {
+                "@echo off\n"
+            } else {
+                "\n"
+            };
+            format!(
+                "{}REM {}\nREM {}\n{}REM {}\n",
+                prefix,
+                ENVIS_ACTIVE_BLOCK_START,
+                ENVIS_WARNING,
+                combined_lines,
+                ENVIS_ACTIVE_BLOCK_END
+            )
+        } else {
+            // PowerShell 和 Unix Shell 使用 # 作为注释
+            format!(
+                "\n{}\n{}\n{}{}\n",
+                ENVIS_ACTIVE_BLOCK_START, ENVIS_WARNING, combined_lines, ENVIS_ACTIVE_BLOCK_END
+            )
+        };
+
+        format!("{}{}", base_content, block_content)
+    }
+
     /// 初始化环境变量块
     fn initialize_env_block(&self) -> Result<()> {
         // 获取 envis 可执行文件路径
@@ -377,44 +559,13 @@ fi
                 base_content = self.remove_env_block(&base_content)?;
             }
 
-            // 判断文件类型以使用正确的注释前缀
-            let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
-            let is_ps = config_file_path.extension().and_then(|s| s.to_str()) == Some("ps1");
-
             let combined_lines = self.generate_envis_setup_commands(
                 envis_path.as_ref(),
                 envis_exe.as_ref(),
                 config_file_path,
             );
-
-            let block_content = if is_cmd {
-                // CMD 使用 REM 作为注释（不包含 # 符号）
-                let prefix = if base_content.is_empty() {
-                    "@echo off\n"
-                } else {
-                    "\n"
-                };
-                format!(
-                    "{}REM {}\nREM {}\n{}REM {}\n",
-                    prefix,
-                    ENVIS_ACTIVE_BLOCK_START,
-                    ENVIS_WARNING,
-                    combined_lines,
-                    ENVIS_ACTIVE_BLOCK_END
-                )
-            } else {
-                // PowerShell 和 Unix Shell 使用 # 作为注释
-                format!(
-                    "\n{}\n{}\n{}{}\n",
-                    ENVIS_ACTIVE_BLOCK_START, ENVIS_WARNING, combined_lines, ENVIS_ACTIVE_BLOCK_END
-                )
-            };
-
-            let new_content = if base_content.is_empty() {
-                block_content
-            } else {
-                format!("{}{}", base_content, block_content)
-            };
+            let new_content =
+                self.append_env_block(config_file_path, &base_content, &combined_lines);
 
             // 内容未变化时跳过写入，避免每次启动都无谓地 rewrite shell 配置文件
             if content == new_content {
@@ -443,263 +594,1031 @@ fi
         Ok(())
     }
 
-    /// echo Envis: Current environment is environment name, environment id
-    pub fn add_echo_environment(&self, environment_name: &str, environment_id: &str) -> Result<()> {
-        // 为每个配置文件生成对应的 echo 命令
-        for config_file_path in &self.config_file_paths {
-            let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
-            let is_ps = config_file_path.extension().and_then(|s| s.to_str()) == Some("ps1");
+    /// 获取所有受管理的 Shell 配置文件及其状态
+    pub fn get_config_file_info(&self) -> Vec<ShellConfigFileInfo> {
+        self.config_file_paths
+            .iter()
+            .map(|path| {
+                let exists = path.exists();
+                let has_envis_block = exists
+                    && fs::read_to_string(path)
+                        .map(|content| {
+                            content.lines().any(|line| {
+                                let trimmed = line.trim();
+                                let cleaned = trimmed.strip_prefix("REM ").unwrap_or(trimmed);
+                                cleaned.trim() == ENVIS_ACTIVE_BLOCK_START
+                            })
+                        })
+                        .unwrap_or(false);
+
+                ShellConfigFileInfo {
+                    path: path.to_string_lossy().to_string(),
+                    exists,
+                    has_envis_block,
+                    shell_type: ShellType::from_config_file_path(path).name().to_string(),
+                }
+            })
+            .collect()
+    }
 
-            let echo_line = if is_cmd {
-                // CMD 语法 - 不使用 ANSI 颜色代码
-                format!(
-                    "echo Envis: Current environment is {}, {}",
-                    environment_name, environment_id
-                )
-            } else if is_ps {
-                // PowerShell 语法
-                format!(
-                    "Write-Host 'Envis: Current environment is {}, {}' -ForegroundColor Green",
-                    environment_name, environment_id
-                )
-            } else {
-                // Unix Shell 语法
-                format!(
-                    "echo Envis: Current environment is {}, {}",
-                    environment_name, environment_id
-                )
-            };
+    /// 检查所有配置文件中 Envis 环境块的完整性
+    pub fn diagnose_config_files(&self) -> Vec<ShellConfigDiagnostic> {
+        let (envis_path, envis_exe) = self.get_envis_executable_info();
 
-            // 先删除已有的 echo 行，避免重复显示
-            let prefix = if is_cmd {
-                "echo Envis: Current environment is"
-            } else if is_ps {
-                "Write-Host 'Envis: Current environment is"
-            } else {
-                "echo Envis: Current environment is"
-            };
-            let _ = self.remove_line_from_file(config_file_path, prefix);
-            self.add_line_to_file(config_file_path, &echo_line)?;
+        self.config_file_paths
+            .iter()
+            .map(|path| {
+                let exists = path.exists();
+                let issues = if exists {
+                    match fs::read_to_string(path) {
+                        Ok(content) => {
+                            let setup_cmds = self.generate_envis_setup_commands(
+                                envis_path.as_ref(),
+                                envis_exe.as_ref(),
+                                path,
+                            );
+                            self.diagnose_content(path, &content, &setup_cmds)
+                        }
+                        Err(e) => {
+                            log::warn!("读取 Shell 配置文件失败 {}: {}", path.display(), e);
+                            Vec::new()
+                        }
+                    }
+                } else {
+                    vec![ShellConfigIssue {
+                        kind: ShellConfigIssueKind::MissingBlock,
+                        line: None,
+                        content: None,
+                    }]
+                };
+
+                ShellConfigDiagnostic {
+                    path: path.to_string_lossy().to_string(),
+                    shell_type: ShellType::from_config_file_path(path).name().to_string(),
+                    exists,
+                    issues,
+                    backup_path: None,
+                }
+            })
+            .collect()
+    }
+
+    /// 诊断单个配置文件内容
+    fn diagnose_content(
+        &self,
+        config_file_path: &Path,
+        content: &str,
+        setup_cmds: &str,
+    ) -> Vec<ShellConfigIssue> {
+        let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
+        let is_ps = config_file_path.extension().and_then(|s| s.to_str()) == Some("ps1");
+        let setup_lines: HashSet<&str> = setup_cmds.lines().map(|l| l.trim()).collect();
+
+        let mut issues = Vec::new();
+        let mut inside_block = false;
+        let mut has_block_start = false;
+        let mut seen_keys = HashSet::new();
+
+        let mut push_issue = |kind, index: usize, line: &str| {
+            issues.push(ShellConfigIssue {
+                kind,
+                line: Some(index + 1),
+                content: Some(line.to_string()),
+            });
+        };
+
+        for (index, line) in content.lines().enumerate() {
+            let cleaned = block_marker_text(line);
+
+            if cleaned == ENVIS_ACTIVE_BLOCK_START {
+                if inside_block {
+                    push_issue(ShellConfigIssueKind::DuplicateBlockStart, index, line);
+                } else if has_block_start {
+                    push_issue(ShellConfigIssueKind::DuplicateBlock, index, line);
+                }
+                inside_block = true;
+                has_block_start = true;
+            } else if cleaned == ENVIS_ACTIVE_BLOCK_END {
+                if !inside_block {
+                    push_issue(ShellConfigIssueKind::OrphanBlockEnd, index, line);
+                }
+                inside_block = false;
+            } else if inside_block {
+                let trimmed = line.trim();
+                if trimmed.is_empty() || cleaned == ENVIS_WARNING {
+                    continue;
+                }
+
+                match envis_line_key(trimmed, is_cmd, is_ps) {
+                    Some(key) => {
+                        // envis 基础配置中的 PATH 行允许与 Envis 写入的 PATH 行同时存在
+                        if !setup_lines.contains(trimmed) && !seen_keys.insert(key) {
+                            push_issue(ShellConfigIssueKind::DuplicateLine, index, line);
+                        }
+                    }
+                    None if setup_lines.contains(trimmed) => {}
+                    None => push_issue(ShellConfigIssueKind::UnknownLine, index, line),
+                }
+            }
         }
 
-        Ok(())
+        if inside_block {
+            issues.push(ShellConfigIssue {
+                kind: ShellConfigIssueKind::MissingBlockEnd,
+                line: None,
+                content: None,
+            });
+        }
+        if !has_block_start {
+            issues.push(ShellConfigIssue {
+                kind: ShellConfigIssueKind::MissingBlock,
+                line: None,
+                content: None,
+            });
+        }
+
+        issues
     }
 
-    /// 移除环境 echo 信息
-    pub fn remove_echo_environment(&self) -> Result<()> {
-        // 为每个配置文件删除对应的 echo 命令
+    /// 备份所有配置文件，移除其中所有（包括损坏的）Envis 环境块并重建一个只含基础配置的新块
+    ///
+    /// 返回 配置文件路径 -> 备份文件路径
+    pub fn rebuild_env_blocks(&self) -> Result<HashMap<String, String>> {
+        let (envis_path, envis_exe) = self.get_envis_executable_info();
+        let mut backups = HashMap::new();
+
         for config_file_path in &self.config_file_paths {
-            let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
-            let is_ps = config_file_path.extension().and_then(|s| s.to_str()) == Some("ps1");
+            if let Some(parent_dir) = config_file_path.parent() {
+                if !parent_dir.exists() {
+                    fs::create_dir_all(parent_dir).context("创建配置文件目录失败")?;
+                }
+            }
 
-            let prefix = if is_cmd {
-                "echo Envis: Current environment is"
-            } else if is_ps {
-                "Write-Host 'Envis: Current environment is"
+            let content = if config_file_path.exists() {
+                fs::read_to_string(config_file_path).context("读取 Shell 配置文件失败")?
             } else {
-                "echo Envis: Current environment is"
+                String::new()
             };
 
-            let _ = self.remove_line_from_file(config_file_path, prefix);
+            let setup_cmds = self.generate_envis_setup_commands(
+                envis_path.as_ref(),
+                envis_exe.as_ref(),
+                config_file_path,
+            );
+            let base_content = self.strip_env_blocks(config_file_path, &content, &setup_cmds);
+            let new_content = self.append_env_block(config_file_path, &base_content, &setup_cmds);
+            if let Some(backup) =
+                self.write_content_atomic_for_path(config_file_path, &new_content)?
+            {
+                backups.insert(
+                    config_file_path.to_string_lossy().to_string(),
+                    backup.to_string_lossy().to_string(),
+                );
+            }
+            log::info!(
+                "已重建 Shell 配置文件环境块: {}",
+                config_file_path.display()
+            );
         }
 
-        Ok(())
+        Ok(backups)
     }
 
-    /// 添加服务信息 echo
-    pub fn add_echo_services(&self, services_info: Vec<String>) -> Result<()> {
-        self.remove_echo_services()?;
+    /// 检查所有配置文件中环境块 BEGIN / END 标记的完整性
+    pub fn verify_env_block_integrity(&self) -> Result<Vec<EnvBlockIntegrity>> {
+        self.config_file_paths
+            .iter()
+            .map(|config_file_path| {
+                let (status, details) = if config_file_path.exists() {
+                    let content =
+                        fs::read_to_string(config_file_path).context("读取 Shell 配置文件失败")?;
+                    Self::env_block_integrity(&content)
+                } else {
+                    (IntegrityStatus::MissingStart, "配置文件不存在".to_string())
+                };
+                Ok(EnvBlockIntegrity {
+                    path: config_file_path.to_string_lossy().to_string(),
+                    status,
+                    details,
+                })
+            })
+            .collect()
+    }
 
-        if services_info.is_empty() {
-            return Ok(());
+    /// 按完整性状态修复环境块，尽量保留块内已写入的内容
+    ///
+    /// Duplicated 移除多余的块，MissingEnd 补上 END，MissingStart 补上 BEGIN；
+    /// 没有环境块或标记无法配对时移除残留标记并重建只含基础配置的块。
+    /// 返回修复前存在问题的文件及其状态。
+    pub fn repair_env_block(&self) -> Result<Vec<EnvBlockIntegrity>> {
+        let (envis_path, envis_exe) = self.get_envis_executable_info();
+        let mut repaired = Vec::new();
+
+        for (config_file_path, integrity) in self
+            .config_file_paths
+            .iter()
+            .zip(self.verify_env_block_integrity()?)
+        {
+            if integrity.status == IntegrityStatus::Ok {
+                continue;
+            }
+
+            if let Some(parent_dir) = config_file_path.parent() {
+                if !parent_dir.exists() {
+                    fs::create_dir_all(parent_dir).context("创建配置文件目录失败")?;
+                }
+            }
+            let content = if config_file_path.exists() {
+                fs::read_to_string(config_file_path).context("读取 Shell 配置文件失败")?
+            } else {
+                String::new()
+            };
+
+            let setup_cmds = self.generate_envis_setup_commands(
+                envis_path.as_ref(),
+                envis_exe.as_ref(),
+                config_file_path,
+            );
+            let new_content = self.repair_env_block_content(
+                config_file_path,
+                &content,
+                integrity.status,
+                &setup_cmds,
+            );
+            self.write_content_atomic_for_path(config_file_path, &new_content)?;
+            log::info!(
+                "已修复 Shell 配置文件环境块 ({:?}): {}",
+                integrity.status,
+                config_file_path.display()
+            );
+            repaired.push(integrity);
         }
 
-        let info = services_info.join("\t");
+        Ok(repaired)
+    }
 
-        for config_file_path in &self.config_file_paths {
-            let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
-            let is_ps = config_file_path.extension().and_then(|s| s.to_str()) == Some("ps1");
+    /// 返回内容中所有 BEGIN / END 标记的行号（从 0 开始）及是否为 BEGIN
+    fn env_block_markers(content: &str) -> Vec<(usize, bool)> {
+        content
+            .lines()
+            .enumerate()
+            .filter_map(|(index, line)| match block_marker_text(line) {
+                ENVIS_ACTIVE_BLOCK_START => Some((index, true)),
+                ENVIS_ACTIVE_BLOCK_END => Some((index, false)),
+                _ => None,
+            })
+            .collect()
+    }
 
+    /// 根据 BEGIN / END 标记判断环境块的完整性状态
+    fn env_block_integrity(content: &str) -> (IntegrityStatus, String) {
+        let markers = Self::env_block_markers(content);
+        let starts = markers.iter().filter(|(_, is_start)| *is_start).count();
+        let ends = markers.len() - starts;
+        let paired = markers.len().is_multiple_of(2)
+            && markers.chunks(2).all(|pair| pair[0].1 && !pair[1].1);
+
+        match (starts, ends) {
+            (0, 0) => (
+                IntegrityStatus::MissingStart,
+                "未找到 Envis 环境块".to_string(),
+            ),
+            (1, 1) if paired => (
+                IntegrityStatus::Ok,
+                format!("环境块位于第 {}-{} 行", markers[0].0 + 1, markers[1].0 + 1),
+            ),
+            _ if paired => (
+                IntegrityStatus::Duplicated,
+                format!("存在 {} 个 Envis 环境块", starts),
+            ),
+            (1, 0) => (
+                IntegrityStatus::MissingEnd,
+                format!("第 {} 行的 BEGIN 标记缺少对应的 END", markers[0].0 + 1),
+            ),
+            (0, 1) => (
+                IntegrityStatus::MissingStart,
+                format!("第 {} 行的 END 标记缺少对应的 BEGIN", markers[0].0 + 1),
+            ),
+            _ => (
+                IntegrityStatus::Unpaired,
+                format!("BEGIN 标记 {} 个、END 标记 {} 个，无法配对", starts, ends),
+            ),
+        }
+    }
+
+    /// 生成修复后的配置文件内容
+    fn repair_env_block_content(
+        &self,
+        config_file_path: &Path,
+        content: &str,
+        status: IntegrityStatus,
+        setup_cmds: &str,
+    ) -> String {
+        let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
+        let is_ps = config_file_path.extension().and_then(|s| s.to_str()) == Some("ps1");
+        let setup_lines: HashSet<&str> = setup_cmds.lines().map(|l| l.trim()).collect();
+        // 空行视为块内内容，但不决定补充标记的位置
+        let is_envis_line = |line: &str| {
+            let trimmed = line.trim();
+            block_marker_text(line) == ENVIS_WARNING
+                || setup_lines.contains(trimmed)
+                || envis_line_key(trimmed, is_cmd, is_ps).is_some()
+        };
+        let marker_line = |text: &str| {
             if is_cmd {
-                self.add_line_to_file(config_file_path, &format!("echo Envis Service: {}", info))?;
-            } else if is_ps {
-                self.add_line_to_file(
-                    config_file_path,
-                    &format!("Write-Host 'Envis Service: {}'", info),
-                )?;
+                format!("REM {}", text)
             } else {
-                self.add_line_to_file(
-                    config_file_path,
-                    &format!("echo \"Envis Service: {}\"", info),
-                )?;
+                text.to_string()
+            }
+        };
+
+        let markers = Self::env_block_markers(content);
+        let mut lines: Vec<String> = content.lines().map(String::from).collect();
+
+        match status {
+            IntegrityStatus::Ok => return content.to_string(),
+            IntegrityStatus::Duplicated => {
+                // 保留第一个块，移除其后的完整块
+                let extra: Vec<(usize, usize)> = markers
+                    .chunks(2)
+                    .skip(1)
+                    .map(|pair| (pair[0].0, pair[1].0))
+                    .collect();
+                lines = lines
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(index, _)| {
+                        !extra
+                            .iter()
+                            .any(|(start, end)| index >= start && index <= end)
+                    })
+                    .map(|(_, line)| line)
+                    .collect();
+            }
+            IntegrityStatus::MissingEnd => {
+                // END 补在 BEGIN 之后连续的最后一行 Envis 内容后，不包住用户内容
+                let start = markers[0].0;
+                let mut insert_at = start + 1;
+                for (index, line) in lines.iter().enumerate().skip(start + 1) {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    if !is_envis_line(line) {
+                        break;
+                    }
+                    insert_at = index + 1;
+                }
+                lines.insert(insert_at, marker_line(ENVIS_ACTIVE_BLOCK_END));
+            }
+            IntegrityStatus::MissingStart if !markers.is_empty() => {
+                // BEGIN 补在 END 之前连续的第一行 Envis 内容前
+                let end = markers[0].0;
+                let mut insert_at = end;
+                for index in (0..end).rev() {
+                    if lines[index].trim().is_empty() {
+                        continue;
+                    }
+                    if !is_envis_line(&lines[index]) {
+                        break;
+                    }
+                    insert_at = index;
+                }
+                let has_warning =
+                    insert_at < end && block_marker_text(&lines[insert_at]) == ENVIS_WARNING;
+                if !has_warning {
+                    lines.insert(insert_at, marker_line(ENVIS_WARNING));
+                }
+                lines.insert(insert_at, marker_line(ENVIS_ACTIVE_BLOCK_START));
+            }
+            IntegrityStatus::MissingStart | IntegrityStatus::Unpaired => {
+                let base_content = self.strip_env_blocks(config_file_path, content, setup_cmds);
+                return self.append_env_block(config_file_path, &base_content, setup_cmds);
             }
         }
-        Ok(())
+
+        let mut repaired = lines.join("\n");
+        if content.ends_with('\n') {
+            repaired.push('\n');
+        }
+        repaired
     }
 
-    /// 移除服务信息 echo
-    pub fn remove_echo_services(&self) -> Result<()> {
-        for config_file_path in &self.config_file_paths {
-            let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
-            let is_ps = config_file_path.extension().and_then(|s| s.to_str()) == Some("ps1");
+    /// 将所有已存在的配置文件复制为 `<file>.envbak.<tag>`，返回创建的备份文件路径
+    pub fn backup_all_configs(&self, tag: &str) -> Result<Vec<String>> {
+        Self::validate_backup_tag(tag)?;
 
-            let prefix = if is_cmd {
-                "echo Envis Service:"
-            } else if is_ps {
-                "Write-Host 'Envis Service:"
-            } else {
-                "echo \"Envis Service:"
-            };
+        let existing: Vec<&PathBuf> = self
+            .config_file_paths
+            .iter()
+            .filter(|p| p.exists())
+            .collect();
+        if let Some(path) = existing
+            .iter()
+            .map(|p| Self::tagged_backup_path(p, tag))
+            .find(|p| p.exists())
+        {
+            return Err(anyhow::anyhow!("备份已存在: {}", path.display()));
+        }
 
-            let _ = self.remove_line_from_file(config_file_path, prefix);
+        let mut created = Vec::new();
+        for config_file_path in existing {
+            let backup = Self::tagged_backup_path(config_file_path, tag);
+            // 读取后写入而不是 fs::copy，保证备份文件的修改时间即备份时间
+            let content = fs::read(config_file_path).context("读取 Shell 配置文件失败")?;
+            fs::write(&backup, content).context("写入 Shell 配置备份失败")?;
+            log::info!("已备份 Shell 配置文件: {}", backup.display());
+            created.push(backup.to_string_lossy().to_string());
         }
-        Ok(())
+
+        if created.is_empty() {
+            return Err(anyhow::anyhow!("没有可备份的 Shell 配置文件"));
+        }
+        Ok(created)
     }
 
-    /// 添加环境变量导出
-    pub fn add_export(&self, key: &str, value: &str) -> Result<()> {
-        // 为每个配置文件生成对应的环境变量设置命令
-        for config_file_path in &self.config_file_paths {
-            let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
-            let is_ps = config_file_path.extension().and_then(|s| s.to_str()) == Some("ps1");
+    /// 用指定标签的备份覆盖配置文件（覆盖前会自动备份当前内容），返回已恢复的配置文件路径
+    ///
+    /// 标签可以是 backup_all_configs 使用的标签，也可以是自动备份的标签（如 envbak1700000000）
+    pub fn restore_all_configs(&self, tag: &str) -> Result<Vec<String>> {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return Err(anyhow::anyhow!("备份标签不能为空"));
+        }
 
-            let (prefix, export_line) = if is_cmd {
-                // CMD 语法
-                (format!("set {}=", key), format!("set {}={}", key, value))
-            } else if is_ps {
-                // PowerShell 语法
-                (
-                    format!("$env:{} =", key),
-                    format!("$env:{} = \"{}\"", key, value),
-                )
-            } else {
-                // Unix Shell 语法
-                (
-                    format!("export {}=", key),
-                    format!("export {}=\"{}\"", key, value),
-                )
-            };
+        let sources: Vec<(&PathBuf, PathBuf)> = self
+            .config_file_paths
+            .iter()
+            .filter_map(|config_file_path| {
+                Self::find_backup(config_file_path, tag).map(|p| (config_file_path, p))
+            })
+            .collect();
+        if sources.is_empty() {
+            return Err(anyhow::anyhow!("未找到标签为 {} 的备份", tag));
+        }
 
-            // 先删除已有的同名 export 行，避免重复添加
-            let _ = self.remove_line_from_file(config_file_path, &prefix);
-            self.add_line_to_file(config_file_path, &export_line)?;
+        let mut restored = Vec::new();
+        for (config_file_path, backup) in sources {
+            let content = fs::read_to_string(&backup)
+                .with_context(|| format!("读取备份文件失败: {}", backup.display()))?;
+            self.write_content_atomic_for_path(config_file_path, &content)?;
+            log::info!(
+                "已从 {} 恢复 Shell 配置文件: {}",
+                backup.display(),
+                config_file_path.display()
+            );
+            restored.push(config_file_path.to_string_lossy().to_string());
+        }
+        Ok(restored)
+    }
+
+    /// 用指定备份覆盖单个配置文件（覆盖前会自动备份当前内容），然后重新初始化 Envis 环境块
+    ///
+    /// 环境块会被重置为只含基础配置的新块，已激活环境需要由调用方重新写入
+    pub fn restore_config_backup(&self, config_path: &str, tag: &str) -> Result<String> {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return Err(anyhow::anyhow!("备份标签不能为空"));
+        }
+        let config_file_path = self
+            .config_file_paths
+            .iter()
+            .find(|p| p.as_path() == Path::new(config_path))
+            .ok_or_else(|| anyhow::anyhow!("不是受管理的 Shell 配置文件: {}", config_path))?;
+        let backup = Self::find_backup(config_file_path, tag)
+            .ok_or_else(|| anyhow::anyhow!("未找到 {} 标签为 {} 的备份", config_path, tag))?;
+
+        let content = fs::read_to_string(&backup)
+            .with_context(|| format!("读取备份文件失败: {}", backup.display()))?;
+        self.write_content_atomic_for_path(config_file_path, &content)?;
+        log::info!(
+            "已从 {} 恢复 Shell 配置文件: {}",
+            backup.display(),
+            config_file_path.display()
+        );
+
+        self.initialize_env_block()?;
+        Ok(config_file_path.to_string_lossy().to_string())
+    }
+
+    /// 列出每个配置文件的可用备份（手动备份与自动备份），按时间从新到旧排列
+    pub fn list_available_backups(&self) -> Vec<ShellConfigBackups> {
+        self.config_file_paths
+            .iter()
+            .map(|config_file_path| {
+                let mut backups = Vec::new();
+                let file_name = config_file_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let tagged_prefix = format!("{}.envbak.", file_name);
+                let file_stem = config_file_path.file_stem().map(|s| s.to_os_string());
+
+                let entries = config_file_path
+                    .parent()
+                    .and_then(|dir| fs::read_dir(dir).ok());
+                for entry in entries.into_iter().flatten().flatten() {
+                    let path = entry.path();
+                    let Ok(metadata) = entry.metadata() else {
+                        continue;
+                    };
+                    if !metadata.is_file() {
+                        continue;
+                    }
+                    let name = entry.file_name().to_string_lossy().to_string();
+
+                    if let Some(tag) = name.strip_prefix(&tagged_prefix) {
+                        let timestamp = metadata
+                            .modified()
+                            .ok()
+                            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                            .map(|d| d.as_secs())
+                            .unwrap_or(0);
+                        backups.push(ShellConfigBackup {
+                            tag: tag.to_string(),
+                            timestamp,
+                            size_bytes: metadata.len(),
+                            automatic: false,
+                        });
+                    } else if path.file_stem().map(|s| s.to_os_string()) == file_stem {
+                        let ext = path
+                            .extension()
+                            .map(|e| e.to_string_lossy().to_string())
+                            .unwrap_or_default();
+                        if let Some(timestamp) = Self::automatic_backup_timestamp(&ext) {
+                            backups.push(ShellConfigBackup {
+                                tag: ext,
+                                timestamp,
+                                size_bytes: metadata.len(),
+                                automatic: true,
+                            });
+                        }
+                    }
+                }
+
+                backups.sort_by_key(|b| std::cmp::Reverse(b.timestamp));
+                ShellConfigBackups {
+                    config_path: config_file_path.to_string_lossy().to_string(),
+                    backups,
+                }
+            })
+            .collect()
+    }
+
+    /// 按标签查找配置文件的备份，先查手动备份，再查自动备份
+    fn find_backup(config_file_path: &Path, tag: &str) -> Option<PathBuf> {
+        let tagged = Self::tagged_backup_path(config_file_path, tag);
+        if tagged.exists() {
+            return Some(tagged);
         }
+        Self::automatic_backup_timestamp(tag)
+            .map(|_| config_file_path.with_extension(tag))
+            .filter(|p| p.exists())
+    }
 
+    fn tagged_backup_path(config_file_path: &Path, tag: &str) -> PathBuf {
+        let mut name = config_file_path
+            .file_name()
+            .map(|n| n.to_os_string())
+            .unwrap_or_default();
+        name.push(format!(".envbak.{}", tag));
+        config_file_path.with_file_name(name)
+    }
+
+    /// 自动备份的扩展名为 envbak{timestamp}，返回其中的时间戳
+    fn automatic_backup_timestamp(ext: &str) -> Option<u64> {
+        ext.strip_prefix("envbak")
+            .filter(|ts| !ts.is_empty() && ts.chars().all(|c| c.is_ascii_digit()))
+            .and_then(|ts| ts.parse().ok())
+    }
+
+    fn validate_backup_tag(tag: &str) -> Result<()> {
+        if tag.is_empty()
+            || !tag
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(anyhow::anyhow!(
+                "备份标签只能包含字母、数字、- 和 _: {}",
+                tag
+            ));
+        }
+        if Self::automatic_backup_timestamp(tag).is_some() {
+            return Err(anyhow::anyhow!("备份标签不能与自动备份的标签重名: {}", tag));
+        }
         Ok(())
     }
 
-    /// 删除环境变量导出
-    pub fn delete_export(&self, key: &str) -> Result<()> {
-        for config_file_path in &self.config_file_paths {
-            let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
-            let is_ps = config_file_path.extension().and_then(|s| s.to_str()) == Some("ps1");
+    /// 移除内容中所有 Envis 环境块，兼容缺少 END、孤立 END、重复 BEGIN 等损坏情况
+    fn strip_env_blocks(
+        &self,
+        config_file_path: &Path,
+        content: &str,
+        setup_cmds: &str,
+    ) -> String {
+        let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
+        let is_ps = config_file_path.extension().and_then(|s| s.to_str()) == Some("ps1");
+        let setup_lines: HashSet<&str> = setup_cmds.lines().map(|l| l.trim()).collect();
 
-            let prefix = if is_cmd {
-                format!("set {}=", key)
-            } else if is_ps {
-                format!("$env:{} =", key)
+        let lines: Vec<&str> = content.lines().collect();
+        let mut result_lines = Vec::new();
+        let mut index = 0;
+
+        while index < lines.len() {
+            let cleaned = block_marker_text(lines[index]);
+
+            if cleaned == ENVIS_ACTIVE_BLOCK_START {
+                let next_marker = lines[index + 1..].iter().position(|l| {
+                    let c = block_marker_text(l);
+                    c == ENVIS_ACTIVE_BLOCK_START || c == ENVIS_ACTIVE_BLOCK_END
+                });
+
+                match next_marker.map(|offset| index + 1 + offset) {
+                    // 完整的块：整体移除
+                    Some(end) if block_marker_text(lines[end]) == ENVIS_ACTIVE_BLOCK_END => {
+                        index = end + 1;
+                    }
+                    // 缺少 END：只移除紧随其后的 Envis 写入的行，保留用户内容
+                    _ => {
+                        index += 1;
+                        while index < lines.len() {
+                            let trimmed = lines[index].trim();
+                            let c = block_marker_text(lines[index]);
+                            let is_envis_line = trimmed.is_empty()
+                                || c == ENVIS_WARNING
+                                || setup_lines.contains(trimmed)
+                                || envis_line_key(trimmed, is_cmd, is_ps).is_some();
+                            if !is_envis_line
+                                || c == ENVIS_ACTIVE_BLOCK_START
+                                || c == ENVIS_ACTIVE_BLOCK_END
+                            {
+                                break;
+                            }
+                            index += 1;
+                        }
+                    }
+                }
+            } else if cleaned == ENVIS_ACTIVE_BLOCK_END || cleaned == ENVIS_WARNING {
+                // 孤立的 END 或警告行
+                index += 1;
             } else {
-                format!("export {}=", key)
-            };
+                result_lines.push(lines[index]);
+                index += 1;
+            }
+        }
 
-            self.remove_line_from_file(config_file_path, &prefix)?;
+        // 移除末尾的空行
+        while let Some(last) = result_lines.last() {
+            if last.trim().is_empty() {
+                result_lines.pop();
+            } else {
+                break;
+            }
         }
 
-        Ok(())
+        result_lines.join("\n")
+    }
+
+    /// 开始一次批量更新，commit 时每个配置文件只读写一次
+    pub fn begin_update(&self) -> ShellUpdate<'_> {
+        ShellUpdate {
+            manager: self,
+            ops: Vec::new(),
+        }
+    }
+
+    /// echo Envis: Current environment is environment name, environment id
+    pub fn add_echo_environment(&self, environment_name: &str, environment_id: &str) -> Result<()> {
+        self.begin_update()
+            .add_echo_environment(environment_name, environment_id)
+            .commit()
+    }
+
+    /// 移除环境 echo 信息
+    pub fn remove_echo_environment(&self) -> Result<()> {
+        self.begin_update().remove_echo_environment().commit()
+    }
+
+    /// 添加服务信息 echo
+    pub fn add_echo_services(&self, services_info: Vec<String>) -> Result<()> {
+        self.begin_update()
+            .add_echo_services(services_info)
+            .commit()
+    }
+
+    /// 移除服务信息 echo
+    pub fn remove_echo_services(&self) -> Result<()> {
+        self.begin_update().remove_echo_services().commit()
+    }
+
+    /// 添加环境变量导出
+    pub fn add_export(&self, key: &str, value: &str) -> Result<()> {
+        self.begin_update().add_export(key, value).commit()
+    }
+
+    /// 添加按字面值导出的环境变量（值中的引号、$、% 等会按各 shell 规则转义）
+    pub fn add_literal_export(&self, key: &str, value: &str) -> Result<()> {
+        self.begin_update().add_literal_export(key, value).commit()
+    }
+
+    /// 删除环境变量导出
+    pub fn delete_export(&self, key: &str) -> Result<()> {
+        self.begin_update().delete_export(key).commit()
     }
 
     /// 添加 PATH 路径
     pub fn add_path(&self, path: &str) -> Result<()> {
-        // 为每个配置文件单独处理
-        for config_file_path in &self.config_file_paths {
-            let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
-            let is_ps = config_file_path.extension().and_then(|s| s.to_str()) == Some("ps1");
+        self.begin_update().add_path(path).commit()
+    }
 
-            // 先读取当前的 PATH 设置
-            let current_paths = self.get_current_paths_from_file(config_file_path)?;
+    /// 删除 PATH 路径
+    pub fn delete_path(&self, path: &str) -> Result<()> {
+        self.begin_update().delete_path(path).commit()
+    }
 
-            // 如果路径已存在，跳过
-            if current_paths.contains(path) {
-                continue;
+    /// 在内存中对单个配置文件的内容执行一项更新操作
+    fn apply_update_op(
+        &self,
+        config_file_path: &Path,
+        content: &str,
+        op: &ShellUpdateOp,
+    ) -> Result<String> {
+        let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
+        let is_ps = config_file_path.extension().and_then(|s| s.to_str()) == Some("ps1");
+
+        match op {
+            ShellUpdateOp::ClearBlock => {
+                let (envis_path, envis_exe) = self.get_envis_executable_info();
+                let cleared_content = self.clear_env_block_content(content)?;
+                let setup_cmds = self.generate_envis_setup_commands(
+                    envis_path.as_ref(),
+                    envis_exe.as_ref(),
+                    config_file_path,
+                );
+                self.insert_line_in_block(&cleared_content, &setup_cmds)
+            }
+            ShellUpdateOp::EchoEnvironment { name, id } => {
+                let echo_line = if is_ps {
+                    // PowerShell 语法
+                    format!(
+                        "Write-Host 'Envis: Current environment is {}, {}' -ForegroundColor Green",
+                        name, id
+                    )
+                } else {
+                    // CMD 与 Unix Shell 语法（CMD 不使用 ANSI 颜色代码）
+                    format!("echo Envis: Current environment is {}, {}", name, id)
+                };
+
+                // 先删除已有的 echo 行，避免重复显示
+                let content = self.apply_update_op(
+                    config_file_path,
+                    content,
+                    &ShellUpdateOp::RemoveEchoEnvironment,
+                )?;
+                self.insert_line_in_block(&content, &echo_line)
+            }
+            ShellUpdateOp::RemoveEchoEnvironment => {
+                let prefix = if is_ps {
+                    "Write-Host 'Envis: Current environment is"
+                } else {
+                    "echo Envis: Current environment is"
+                };
+                self.remove_lines_with_prefix_from_block(content, prefix)
+            }
+            ShellUpdateOp::EchoServices(services_info) => {
+                let content = self.apply_update_op(
+                    config_file_path,
+                    content,
+                    &ShellUpdateOp::RemoveEchoServices,
+                )?;
+                if services_info.is_empty() {
+                    return Ok(content);
+                }
+
+                let info = services_info.join("\t");
+                let echo_line = if is_cmd {
+                    format!("echo Envis Service: {}", info)
+                } else if is_ps {
+                    format!("Write-Host 'Envis Service: {}'", info)
+                } else {
+                    format!("echo \"Envis Service: {}\"", info)
+                };
+                self.insert_line_in_block(&content, &echo_line)
             }
+            ShellUpdateOp::RemoveEchoServices => {
+                let prefix = if is_cmd {
+                    "echo Envis Service:"
+                } else if is_ps {
+                    "Write-Host 'Envis Service:"
+                } else {
+                    "echo \"Envis Service:"
+                };
+                self.remove_lines_with_prefix_from_block(content, prefix)
+            }
+            ShellUpdateOp::Export {
+                key,
+                value,
+                literal,
+            } => {
+                let export_line = match (is_cmd, is_ps, literal) {
+                    // CMD 语法
+                    (true, _, false) => format!("set {}={}", key, value),
+                    (true, _, true) => format!("set {}={}", key, escape_cmd_value(value)),
+                    // PowerShell 语法
+                    (_, true, false) => format!("$env:{} = \"{}\"", key, value),
+                    (_, true, true) => format!("$env:{} = '{}'", key, value.replace('\'', "''")),
+                    // Unix Shell 语法
+                    (_, _, false) => format!("export {}=\"{}\"", key, value),
+                    (_, _, true) => {
+                        format!("export {}='{}'", key, value.replace('\'', "'\\''"))
+                    }
+                };
 
-            // 构建新的 PATH 导出语句
-            let mut all_paths: Vec<String> = current_paths.into_iter().collect();
-            all_paths.insert(0, path.to_string()); // 新路径插入到最前面
+                // 先删除已有的同名 export 行，避免重复添加
+                let content = self.apply_update_op(
+                    config_file_path,
+                    content,
+                    &ShellUpdateOp::DeleteExport(key.clone()),
+                )?;
+                self.insert_line_in_block(&content, &export_line)
+            }
+            ShellUpdateOp::DeleteExport(key) => {
+                let prefix = if is_cmd {
+                    format!("set {}=", key)
+                } else if is_ps {
+                    format!("$env:{} =", key)
+                } else {
+                    format!("export {}=", key)
+                };
+                self.remove_lines_with_prefix_from_block(content, &prefix)
+            }
+            ShellUpdateOp::AddPath(path) | ShellUpdateOp::DeletePath(path) => {
+                let is_add = matches!(op, ShellUpdateOp::AddPath(_));
+                let mut current_paths =
+                    self.get_current_paths_from_content(config_file_path, content)?;
+
+                // 如果路径已存在，跳过
+                if is_add && current_paths.contains(path.as_str()) {
+                    return Ok(content.to_string());
+                }
 
-            let (path_line, remove_prefix) = if is_cmd {
-                // CMD 语法 - 使用分号分隔
-                (
-                    format!("set PATH={};%PATH%", all_paths.join(";")),
-                    "set PATH=",
-                )
-            } else if is_ps {
-                // PowerShell 语法 - 使用字符串拼接（避免 profile 加载时 $env:PATH 被静态展开）
-                (
-                    format!("$env:PATH = \"{};\" + $env:PATH", all_paths.join(";")),
-                    "$env:PATH =",
-                )
+                let all_paths: Vec<String> = if is_add {
+                    let mut all_paths: Vec<String> = current_paths.into_iter().collect();
+                    all_paths.insert(0, path.clone()); // 新路径插入到最前面
+                    all_paths
+                } else {
+                    current_paths.remove(path.as_str());
+                    current_paths.into_iter().collect()
+                };
+
+                let remove_prefix = if is_cmd {
+                    "set PATH="
+                } else if is_ps {
+                    "$env:PATH ="
+                } else {
+                    "export PATH="
+                };
+
+                // 先删除所有现有的 PATH 设置
+                let mut content =
+                    self.remove_lines_with_prefix_from_block(content, remove_prefix)?;
+                // 兼容旧格式 ($env:Path =)
+                if is_ps {
+                    content = self.remove_lines_with_prefix_from_block(&content, "$env:Path =")?;
+                }
+
+                if all_paths.is_empty() {
+                    return Ok(content);
+                }
+
+                let path_line = if is_cmd {
+                    // CMD 语法 - 使用分号分隔
+                    format!("set PATH={};%PATH%", all_paths.join(";"))
+                } else if is_ps {
+                    // PowerShell 语法 - 使用字符串拼接（避免 profile 加载时 $env:PATH 被静态展开）
+                    format!("$env:PATH = \"{};\" + $env:PATH", all_paths.join(";"))
+                } else {
+                    // Unix Shell 语法 - 使用冒号分隔
+                    format!("export PATH=\"{}:$PATH\"", all_paths.join(":"))
+                };
+                self.insert_line_in_block(&content, &path_line)
+            }
+            ShellUpdateOp::Alias { key, value } => {
+                let alias_line = if is_cmd {
+                    // CMD 语法: doskey key=value $*
+                    format!("doskey {}={} $*", key, value)
+                } else if is_ps {
+                    // PowerShell 语法: Set-Alias key value
+                    // 若 value 含空格（带参数），回退为 function 包装
+                    if value.contains(' ') {
+                        format!("function {} {{ {} @args }}", key, value)
+                    } else {
+                        format!("Set-Alias {} {}", key, value)
+                    }
+                } else {
+                    // Unix Shell 语法: alias key="value"
+                    format!("alias {}=\"{}\"", key, value)
+                };
+
+                // 先删除已有的同名 alias 行
+                let content = self.apply_update_op(
+                    config_file_path,
+                    content,
+                    &ShellUpdateOp::DeleteAlias(key.clone()),
+                )?;
+                self.insert_line_in_block(&content, &alias_line)
+            }
+            ShellUpdateOp::DeleteAlias(key) => {
+                if is_cmd {
+                    self.remove_lines_with_prefix_from_block(content, &format!("doskey {}=", key))
+                } else if is_ps {
+                    // 兼容两种写法：Set-Alias 和 function 包装
+                    let content = self.remove_lines_with_prefix_from_block(
+                        content,
+                        &format!("Set-Alias {}", key),
+                    )?;
+                    self.remove_lines_with_prefix_from_block(
+                        &content,
+                        &format!("function {} {{", key),
+                    )
+                } else {
+                    self.remove_lines_with_prefix_from_block(content, &format!("alias {}=", key))
+                }
+            }
+        }
+    }
+
+    /// 对所有配置文件依次执行一组更新操作，每个文件只写入（并备份）一次
+    fn apply_update_ops(&self, ops: &[ShellUpdateOp]) -> Result<()> {
+        if ops.is_empty() {
+            return Ok(());
+        }
+
+        for config_file_path in &self.config_file_paths {
+            let original = if config_file_path.exists() {
+                fs::read_to_string(config_file_path).context("读取 Shell 配置文件失败")?
             } else {
-                // Unix Shell 语法 - 使用冒号分隔
-                (
-                    format!("export PATH=\"{}:$PATH\"", all_paths.join(":")),
-                    "export PATH=",
-                )
+                String::new()
             };
 
-            // 先删除所有现有的 PATH 设置
-            self.remove_line_from_file(config_file_path, remove_prefix)?;
-            // 兼容旧格式 ($env:Path =)
-            if is_ps {
-                let _ = self.remove_line_from_file(config_file_path, "$env:Path =");
+            let mut content = original.clone();
+            for op in ops {
+                content = self
+                    .apply_update_op(config_file_path, &content, op)
+                    .with_context(|| format!("更新 {} 失败", config_file_path.display()))?;
             }
 
-            // 添加新的 PATH 设置
-            self.add_line_to_file(config_file_path, &path_line)?;
+            if content == original {
+                continue;
+            }
+
+            if let Some(parent_dir) = config_file_path.parent() {
+                if !parent_dir.exists() {
+                    fs::create_dir_all(parent_dir).context("创建配置文件目录失败")?;
+                }
+            }
+            self.write_content_atomic_for_path(config_file_path, &content)?;
+        }
+
+        if cfg!(target_os = "windows") {
+            if let Err(e) = self.sync_windows_user_env(ops) {
+                log::warn!("同步 Windows 用户环境变量失败: {}", e);
+            }
         }
 
         Ok(())
     }
 
-    /// 删除 PATH 路径
-    pub fn delete_path(&self, path: &str) -> Result<()> {
-        for config_file_path in &self.config_file_paths {
-            let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
-            let is_ps = config_file_path.extension().and_then(|s| s.to_str()) == Some("ps1");
-
-            let mut current_paths = self.get_current_paths_from_file(config_file_path)?;
-            current_paths.remove(path);
-
-            let remove_prefix = if is_cmd {
-                "set PATH="
-            } else if is_ps {
-                "$env:PATH ="
-            } else {
-                "export PATH="
-            };
-
-            // 先删除所有现有的 PATH 设置
-            self.remove_line_from_file(config_file_path, remove_prefix)?;
-            // 兼容旧格式 ($env:Path =)
-            if is_ps {
-                let _ = self.remove_line_from_file(config_file_path, "$env:Path =");
+    /// 将 PATH 与环境变量的修改同步到 HKCU\Environment（需开启 windows_use_user_environment）
+    fn sync_windows_user_env(&self, ops: &[ShellUpdateOp]) -> Result<()> {
+        let app_config = {
+            let app_config_manager = AppConfigManager::global();
+            let app_config_manager = app_config_manager.lock().unwrap();
+            app_config_manager.get_app_config()
+        };
+        let envis_folder = Path::new(&app_config.envis_folder);
+
+        if !app_config.windows_use_user_environment {
+            // 关闭该模式后，撤销之前写入注册表的内容
+            if WindowsUserEnvUpdate::has_state(envis_folder) {
+                let mut update = WindowsUserEnvUpdate::load(envis_folder)?;
+                update.clear();
+                update.commit()?;
             }
+            return Ok(());
+        }
 
-            if !current_paths.is_empty() {
-                // 重新构建 PATH
-                let all_paths: Vec<String> = current_paths.into_iter().collect();
-                let path_line = if is_cmd {
-                    format!("set PATH={};%PATH%", all_paths.join(";"))
-                } else if is_ps {
-                    format!("$env:PATH = \"{};\" + $env:PATH", all_paths.join(";"))
-                } else {
-                    format!("export PATH=\"{}:$PATH\"", all_paths.join(":"))
-                };
-                self.add_line_to_file(config_file_path, &path_line)?;
+        let mut update = WindowsUserEnvUpdate::load(envis_folder)?;
+        for op in ops {
+            match op {
+                ShellUpdateOp::ClearBlock => update.clear(),
+                ShellUpdateOp::Export { key, value, .. } => update.set_var(key, value)?,
+                ShellUpdateOp::DeleteExport(key) => update.delete_var(key),
+                ShellUpdateOp::AddPath(path) => update.add_path(path),
+                ShellUpdateOp::DeletePath(path) => update.delete_path(path),
+                _ => {}
             }
         }
-
-        Ok(())
+        update.commit()
     }
 
     /// 获取当前在环境变量块中的 PATH 路径（从第一个配置文件）
@@ -717,7 +1636,16 @@ fi
         }
 
         let content = fs::read_to_string(config_file_path).context("读取 Shell 配置文件失败")?;
-        let block_content = self.extract_env_block_content(&content)?;
+        self.get_current_paths_from_content(config_file_path, &content)
+    }
+
+    /// 从配置文件内容中解析环境变量块里的 PATH 路径
+    fn get_current_paths_from_content(
+        &self,
+        config_file_path: &Path,
+        content: &str,
+    ) -> Result<HashSet<String>> {
+        let block_content = self.extract_env_block_content(content)?;
         let mut paths = HashSet::new();
 
         let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
@@ -792,17 +1720,92 @@ fi
         Ok(paths)
     }
 
+    /// 获取当前环境变量块中导出的环境变量（从第一个配置文件，不含 PATH）
+    pub fn export_current_env_vars(&self) -> Result<HashMap<String, String>> {
+        let config_file_path = &self.config_file_paths[0];
+        if !config_file_path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let content = fs::read_to_string(config_file_path).context("读取 Shell 配置文件失败")?;
+        self.get_env_vars_from_content(config_file_path, &content)
+    }
+
+    /// 从配置文件内容中解析环境变量块里导出的环境变量
+    fn get_env_vars_from_content(
+        &self,
+        config_file_path: &Path,
+        content: &str,
+    ) -> Result<HashMap<String, String>> {
+        let block_content = self.extract_env_block_content(content)?;
+        let mut vars = HashMap::new();
+
+        let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
+        let is_ps = config_file_path.extension().and_then(|s| s.to_str()) == Some("ps1");
+
+        for line in block_content.lines() {
+            let line = line.trim();
+            let assignment = if is_cmd {
+                line.strip_prefix("set ")
+            } else if is_ps {
+                line.strip_prefix("$env:")
+            } else {
+                line.strip_prefix("export ")
+            };
+            let Some((key, value)) = assignment.and_then(|a| a.split_once('=')) else {
+                continue;
+            };
+
+            let key = key.trim();
+            if key.is_empty() || key.eq_ignore_ascii_case("PATH") {
+                continue;
+            }
+
+            let value = value.trim();
+            let value = if is_cmd {
+                unescape_cmd_value(value)
+            } else if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+                // 按字面值导出的单引号字符串
+                let inner = &value[1..value.len() - 1];
+                if is_ps {
+                    inner.replace("''", "'")
+                } else {
+                    inner.replace("'\\''", "'")
+                }
+            } else if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+                value[1..value.len() - 1].to_string()
+            } else {
+                value.to_string()
+            };
+            vars.insert(key.to_string(), value);
+        }
+
+        Ok(vars)
+    }
+
     /// 备份指定路径的文件并以原子方式写入新内容
-    fn write_content_atomic_for_path(&self, path: &PathBuf, new_content: &str) -> Result<()> {
+    ///
+    /// 返回写入前自动创建（或同一秒内已创建）的备份文件路径
+    fn write_content_atomic_for_path(
+        &self,
+        path: &PathBuf,
+        new_content: &str,
+    ) -> Result<Option<PathBuf>> {
         // 备份原文件(如存在)
-        if path.exists() {
+        let backup = if path.exists() {
             let ts = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
             let backup = path.with_extension(format!("envbak{}", ts));
-            fs::copy(path, &backup).context("备份 Shell 配置文件失败")?;
+            // 同一秒内多次写入时保留最早的备份，即这一系列修改之前的内容
+            if !backup.exists() {
+                fs::copy(path, &backup).context("备份 Shell 配置文件失败")?;
+            }
 
-            // 清理旧备份文件，只保留最近的 2 个
-            self.cleanup_old_backups(path, 2)?;
-        }
+            // 清理旧备份文件，只保留应用配置中指定数量的最近备份
+            self.cleanup_old_backups(path, Self::backup_retention())?;
+            Some(backup)
+        } else {
+            None
+        };
 
         // 写临时文件并重命名
         let tmp = path.with_extension("tmp");
@@ -819,7 +1822,14 @@ fi
             let _ = fs::remove_file(&tmp); // 忽略清理失败的错误
         }
 
-        write_result
+        write_result.map(|_| backup)
+    }
+
+    /// 自动备份的保留数量，至少保留 1 个
+    fn backup_retention() -> usize {
+        let app_config_manager = AppConfigManager::global();
+        let app_config = app_config_manager.lock().unwrap().get_app_config();
+        (app_config.shell_config_backup_retention as usize).max(1)
     }
 
     /// 清理旧的备份文件，只保留最近的 N 个
@@ -1235,80 +2245,17 @@ fi
 
     /// 添加 Alias
     pub fn add_alias(&self, key: &str, value: &str) -> Result<()> {
-        for config_file_path in &self.config_file_paths {
-            let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
-            let is_ps = config_file_path.extension().and_then(|s| s.to_str()) == Some("ps1");
-
-            let (prefix, alias_line) = if is_cmd {
-                // CMD 语法: doskey key=value $*
-                (
-                    format!("doskey {}=", key),
-                    format!("doskey {}={} $*", key, value),
-                )
-            } else if is_ps {
-                // PowerShell 语法: Set-Alias key value
-                // 若 value 含空格（带参数），回退为 function 包装
-                if value.contains(' ') {
-                    (
-                        format!("function {} {{", key),
-                        format!("function {} {{ {} @args }}", key, value),
-                    )
-                } else {
-                    (
-                        format!("Set-Alias {}", key),
-                        format!("Set-Alias {} {}", key, value),
-                    )
-                }
-            } else {
-                // Unix Shell 语法: alias key="value"
-                (
-                    format!("alias {}=", key),
-                    format!("alias {}=\"{}\"", key, value),
-                )
-            };
-
-            // 先删除已有的同名 alias 行
-            let _ = self.remove_line_from_file(config_file_path, &prefix);
-            if let Err(e) = self.add_line_to_file(config_file_path, &alias_line) {
-                log::error!(
-                    "Failed to add alias to {}: {}",
-                    config_file_path.display(),
-                    e
-                );
-            }
+        if let Err(e) = self.begin_update().add_alias(key, value).commit() {
+            log::error!("Failed to add alias {}: {}", key, e);
         }
-
         Ok(())
     }
 
     /// 删除 Alias
     pub fn delete_alias(&self, key: &str) -> Result<()> {
-        for config_file_path in &self.config_file_paths {
-            let is_cmd = config_file_path.extension().and_then(|s| s.to_str()) == Some("cmd");
-            let is_ps = config_file_path.extension().and_then(|s| s.to_str()) == Some("ps1");
-
-            let prefix = if is_cmd {
-                format!("doskey {}=", key)
-            } else if is_ps {
-                // 兼容两种写法：Set-Alias 和 function 包装
-                if self
-                    .remove_line_from_file(config_file_path, &format!("Set-Alias {}", key))
-                    .is_ok()
-                {}
-                format!("function {} {{", key)
-            } else {
-                format!("alias {}=", key)
-            };
-
-            if let Err(e) = self.remove_line_from_file(config_file_path, &prefix) {
-                log::error!(
-                    "Failed to remove alias from {}: {}",
-                    config_file_path.display(),
-                    e
-                );
-            }
+        if let Err(e) = self.begin_update().delete_alias(key).commit() {
+            log::error!("Failed to remove alias {}: {}", key, e);
         }
-
         Ok(())
     }
 
@@ -1370,8 +2317,20 @@ fi
     /// 在加载了 shell 配置文件的环境中执行命令
     /// 返回 (stdout, stderr, exit_code)
     pub fn execute_command_with_env(&self, command: &str) -> Result<(String, String, i32)> {
+        Self::execute_command_with_env_vars(command, &HashMap::new(), None)
+    }
+
+    /// 同 execute_command_with_env，额外为命令设置 env_vars
+    ///
+    /// 不依赖 ShellManager 实例，长时间运行的命令无需持有全局锁。
+    /// 设置 timeout 时，超时后结束整个进程树并返回错误。
+    pub fn execute_command_with_env_vars(
+        command: &str,
+        env_vars: &HashMap<String, String>,
+        timeout: Option<Duration>,
+    ) -> Result<(String, String, i32)> {
         #[cfg(target_os = "windows")]
-        let output = {
+        let mut cmd = {
             // Windows: 尝试使用 PowerShell
             let documents_dir = dirs::document_dir().context("无法获取文档目录")?;
             let ps_profile = documents_dir
@@ -1389,13 +2348,13 @@ fi
                 command.to_string()
             };
 
-            create_command("powershell")
-                .args(["-NoLogo", "-Command", &ps_command])
-                .output()
+            let mut cmd = create_command("powershell");
+            cmd.args(["-NoLogo", "-Command", &ps_command]);
+            cmd
         };
 
         #[cfg(not(target_os = "windows"))]
-        let output = {
+        let mut cmd = {
             // macOS/Linux: 使用 login shell 以获取完整的环境变量
             let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string());
 
@@ -1412,12 +2371,17 @@ fi
             // 使用 -l (login shell) 和 -c 选项来执行命令
             // login shell 会自动加载 .zshrc (zsh) 或 .bash_profile (bash)
             // 这样可以获取到完整的 PATH，包括 VS Code 的 code 命令等
-            create_command(shell_cmd)
-                .args(["-l", "-c", command])
-                .output()
+            let mut cmd = create_command(shell_cmd);
+            cmd.args(["-l", "-c", command]);
+            cmd
         };
 
-        match output {
+        cmd.envs(env_vars);
+        if let Some(timeout) = timeout {
+            return Self::output_with_timeout(cmd, timeout);
+        }
+
+        match cmd.output() {
             Ok(output) => {
                 let stdout = String::from_utf8_lossy(&output.stdout).to_string();
                 let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -1427,6 +2391,335 @@ fi
             Err(e) => Err(anyhow::anyhow!("执行命令失败: {}", e)),
         }
     }
+
+    /// 执行命令并等待退出，超时后结束整个进程树
+    fn output_with_timeout(mut cmd: Command, timeout: Duration) -> Result<(String, String, i32)> {
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        // 独立进程组，超时时可以连同子进程一起结束
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("执行命令失败: {}", e))?;
+        let stdout_reader = child.stdout.take().map(spawn_pipe_reader);
+        let stderr_reader = child.stderr.take().map(spawn_pipe_reader);
+
+        let started = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait().context("等待命令退出失败")? {
+                break status;
+            }
+            if started.elapsed() >= timeout {
+                kill_process_tree(&mut child);
+                let _ = child.wait();
+                return Err(anyhow::anyhow!(
+                    "命令执行超时（{} 秒），已结束进程",
+                    timeout.as_secs()
+                ));
+            }
+            thread::sleep(Duration::from_millis(100));
+        };
+
+        // 后台子进程（如 `sleep 300 &`）会继承管道，管道要等它们退出才关闭，
+        // 所以命令退出后只再等待一小段时间读取剩余输出
+        let drain_deadline = Instant::now() + PIPE_DRAIN_GRACE;
+        while [&stdout_reader, &stderr_reader]
+            .iter()
+            .any(|reader| reader.as_ref().is_some_and(|(_, h)| !h.is_finished()))
+            && Instant::now() < drain_deadline
+        {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let collect = |reader: Option<PipeReader>| {
+            reader
+                .map(|(buf, _)| String::from_utf8_lossy(&buf.lock().unwrap()).to_string())
+                .unwrap_or_default()
+        };
+        Ok((
+            collect(stdout_reader),
+            collect(stderr_reader),
+            status.code().unwrap_or(-1),
+        ))
+    }
+}
+
+/// 命令退出后等待读取剩余输出的最长时间
+const PIPE_DRAIN_GRACE: Duration = Duration::from_secs(1);
+
+/// 已读到的管道内容及读取线程
+type PipeReader = (Arc<Mutex<Vec<u8>>>, thread::JoinHandle<()>);
+
+/// 在后台线程中持续读取管道，已读到的内容可以随时取出
+fn spawn_pipe_reader(mut pipe: impl Read + Send + 'static) -> PipeReader {
+    let buf = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&buf);
+    let handle = thread::spawn(move || {
+        let mut chunk = [0u8; 4096];
+        loop {
+            match pipe.read(&mut chunk) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => sink.lock().unwrap().extend_from_slice(&chunk[..n]),
+            }
+        }
+    });
+    (buf, handle)
+}
+
+/// 批量更新中的单项操作
+enum ShellUpdateOp {
+    ClearBlock,
+    EchoEnvironment {
+        name: String,
+        id: String,
+    },
+    RemoveEchoEnvironment,
+    EchoServices(Vec<String>),
+    RemoveEchoServices,
+    Export {
+        key: String,
+        value: String,
+        literal: bool,
+    },
+    DeleteExport(String),
+    AddPath(String),
+    DeletePath(String),
+    Alias {
+        key: String,
+        value: String,
+    },
+    DeleteAlias(String),
+}
+
+/// Shell 配置文件批量更新
+///
+/// 收集 PATH、环境变量、别名和 echo 行的修改，`commit()` 时按顺序在内存中计算
+/// 最终内容，每个配置文件只读写一次（每次提交每个文件只产生一个备份）。
+pub struct ShellUpdate<'a> {
+    manager: &'a ShellManager,
+    ops: Vec<ShellUpdateOp>,
+}
+
+impl ShellUpdate<'_> {
+    /// 清除环境块内容（保留 envis 基础配置）
+    pub fn clear_block(&mut self) -> &mut Self {
+        self.ops.push(ShellUpdateOp::ClearBlock);
+        self
+    }
+
+    pub fn add_echo_environment(
+        &mut self,
+        environment_name: &str,
+        environment_id: &str,
+    ) -> &mut Self {
+        self.ops.push(ShellUpdateOp::EchoEnvironment {
+            name: environment_name.to_string(),
+            id: environment_id.to_string(),
+        });
+        self
+    }
+
+    pub fn remove_echo_environment(&mut self) -> &mut Self {
+        self.ops.push(ShellUpdateOp::RemoveEchoEnvironment);
+        self
+    }
+
+    pub fn add_echo_services(&mut self, services_info: Vec<String>) -> &mut Self {
+        self.ops.push(ShellUpdateOp::EchoServices(services_info));
+        self
+    }
+
+    pub fn remove_echo_services(&mut self) -> &mut Self {
+        self.ops.push(ShellUpdateOp::RemoveEchoServices);
+        self
+    }
+
+    pub fn add_export(&mut self, key: &str, value: &str) -> &mut Self {
+        self.ops.push(ShellUpdateOp::Export {
+            key: key.to_string(),
+            value: value.to_string(),
+            literal: false,
+        });
+        self
+    }
+
+    pub fn add_literal_export(&mut self, key: &str, value: &str) -> &mut Self {
+        self.ops.push(ShellUpdateOp::Export {
+            key: key.to_string(),
+            value: value.to_string(),
+            literal: true,
+        });
+        self
+    }
+
+    pub fn delete_export(&mut self, key: &str) -> &mut Self {
+        self.ops.push(ShellUpdateOp::DeleteExport(key.to_string()));
+        self
+    }
+
+    pub fn add_path(&mut self, path: &str) -> &mut Self {
+        self.ops.push(ShellUpdateOp::AddPath(path.to_string()));
+        self
+    }
+
+    pub fn delete_path(&mut self, path: &str) -> &mut Self {
+        self.ops.push(ShellUpdateOp::DeletePath(path.to_string()));
+        self
+    }
+
+    pub fn add_alias(&mut self, key: &str, value: &str) -> &mut Self {
+        self.ops.push(ShellUpdateOp::Alias {
+            key: key.to_string(),
+            value: value.to_string(),
+        });
+        self
+    }
+
+    pub fn delete_alias(&mut self, key: &str) -> &mut Self {
+        self.ops.push(ShellUpdateOp::DeleteAlias(key.to_string()));
+        self
+    }
+
+    /// 将收集的修改一次性写入所有配置文件
+    pub fn commit(&mut self) -> Result<()> {
+        let ops = std::mem::take(&mut self.ops);
+        self.manager.apply_update_ops(&ops)
+    }
+}
+
+/// 去掉 CMD 的 REM 前缀，得到用于比较块标记的文本
+fn block_marker_text(line: &str) -> &str {
+    let trimmed = line.trim();
+    trimmed
+        .strip_prefix("REM ")
+        .map(|rest| rest.trim())
+        .unwrap_or(trimmed)
+}
+
+/// 识别 Envis 写入的行，返回用于检测重复的键（同一键只应出现一次）
+fn envis_line_key(line: &str, is_cmd: bool, is_ps: bool) -> Option<String> {
+    let line = line.trim();
+    if line.starts_with("echo Envis: Current environment is")
+        || line.starts_with("Write-Host 'Envis: Current environment is")
+    {
+        return Some("echo:environment".to_string());
+    }
+    if line.starts_with("echo Envis Service:")
+        || line.starts_with("echo \"Envis Service:")
+        || line.starts_with("Write-Host 'Envis Service:")
+    {
+        return Some("echo:services".to_string());
+    }
+
+    if is_cmd {
+        let lower = line.to_lowercase();
+        if let Some(rest) = lower.strip_prefix("set ") {
+            return rest
+                .split_once('=')
+                .map(|(key, _)| format!("export:{}", key.trim()));
+        }
+        if let Some(rest) = lower.strip_prefix("doskey ") {
+            return rest
+                .split_once('=')
+                .map(|(key, _)| format!("alias:{}", key.trim()));
+        }
+        if line.starts_with("cd /d \"") {
+            return Some("chdir".to_string());
+        }
+    } else if is_ps {
+        if let Some(rest) = line.strip_prefix("$env:") {
+            return rest
+                .split_once('=')
+                .map(|(key, _)| format!("export:{}", key.trim().to_lowercase()));
+        }
+        if let Some(rest) = line.strip_prefix("Set-Alias ") {
+            let mut parts = rest.split_whitespace();
+            let name = match parts.next() {
+                Some("-Name") => parts.next(),
+                other => other,
+            };
+            return name.map(|name| format!("alias:{}", name.to_lowercase()));
+        }
+        if let Some(rest) = line.strip_prefix("function ") {
+            return rest
+                .split_whitespace()
+                .next()
+                .map(|name| format!("alias:{}", name.to_lowercase()));
+        }
+        if line.starts_with("Set-Location -Path \"") {
+            return Some("chdir".to_string());
+        }
+    } else {
+        if let Some(rest) = line.strip_prefix("export ") {
+            return rest
+                .split_once('=')
+                .map(|(key, _)| format!("export:{}", key.trim()));
+        }
+        if let Some(rest) = line.strip_prefix("alias ") {
+            return rest
+                .split_once('=')
+                .map(|(key, _)| format!("alias:{}", key.trim()));
+        }
+        if line.starts_with("cd \"") {
+            return Some("chdir".to_string());
+        }
+    }
+
+    None
+}
+
+/// 转义 CMD 批处理中 `set` 的值：% 加倍，引号外的特殊字符前加 ^
+fn escape_cmd_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    let mut in_quotes = false;
+    for c in value.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                escaped.push(c);
+            }
+            '%' => escaped.push_str("%%"),
+            '^' | '&' | '|' | '<' | '>' | '(' | ')' if !in_quotes => {
+                escaped.push('^');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// 还原 `escape_cmd_value` 转义过的值
+fn unescape_cmd_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut in_quotes = false;
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                unescaped.push(c);
+            }
+            '%' if chars.peek() == Some(&'%') => {
+                chars.next();
+                unescaped.push('%');
+            }
+            '^' if !in_quotes => {
+                if let Some(next) = chars.next() {
+                    unescaped.push(next);
+                }
+            }
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
 }
 
 /// 初始化 Shell 管理器
@@ -1485,4 +2778,337 @@ mod tests {
         assert!(paths.contains("/c"), "paths: {:?}", paths);
         assert!(paths.contains("/d"), "paths: {:?}", paths);
     }
+
+    #[test]
+    fn test_escape_cmd_value() {
+        assert_eq!(escape_cmd_value("p@ss\"word$1"), "p@ss\"word$1");
+        assert_eq!(escape_cmd_value("a&b|c"), "a^&b^|c");
+        assert_eq!(escape_cmd_value("100%"), "100%%");
+        assert_eq!(escape_cmd_value("\"a&b\"&c"), "\"a&b\"^&c");
+    }
+
+    #[test]
+    fn test_get_env_vars_from_content() {
+        let mgr = ShellManager {
+            config_file_paths: vec![],
+            is_development: true,
+        };
+        let content = format!(
+            "{}\nexport NODE_OPTIONS=\"--max-old-space-size=4096\"\nexport PASS='it'\\''s'\nexport PATH=\"/a:$PATH\"\n{}\nexport OUTSIDE=\"1\"\n",
+            ENVIS_ACTIVE_BLOCK_START, ENVIS_ACTIVE_BLOCK_END
+        );
+        let vars = mgr
+            .get_env_vars_from_content(&PathBuf::from("/tmp/.zshrc"), &content)
+            .unwrap();
+        assert_eq!(
+            vars.get("NODE_OPTIONS").map(String::as_str),
+            Some("--max-old-space-size=4096")
+        );
+        assert_eq!(vars.get("PASS").map(String::as_str), Some("it's"));
+        assert!(!vars.contains_key("PATH"));
+        assert!(!vars.contains_key("OUTSIDE"));
+
+        let content = format!(
+            "REM {}\nset A=100%%\nset B=a^&b\nREM {}\n",
+            ENVIS_ACTIVE_BLOCK_START, ENVIS_ACTIVE_BLOCK_END
+        );
+        let vars = mgr
+            .get_env_vars_from_content(&PathBuf::from("C:\\envis.cmd"), &content)
+            .unwrap();
+        assert_eq!(vars.get("A").map(String::as_str), Some("100%"));
+        assert_eq!(vars.get("B").map(String::as_str), Some("a&b"));
+    }
+
+    #[test]
+    fn test_begin_update_commit() {
+        let tmp = std::env::temp_dir().join("envis_test_shellrc_update");
+        let initial = format!(
+            "line1\n{}\n{}\nexport OLD=\"1\"\n{}\n",
+            ENVIS_ACTIVE_BLOCK_START, ENVIS_WARNING, ENVIS_ACTIVE_BLOCK_END
+        );
+        fs::write(&tmp, &initial).unwrap();
+        let mgr = ShellManager {
+            config_file_paths: vec![tmp.clone()],
+            is_development: true,
+        };
+
+        mgr.begin_update()
+            .add_path("/a")
+            .add_path("/b")
+            .add_export("FOO", "bar")
+            .delete_export("OLD")
+            .add_alias("ll", "ls -l")
+            .commit()
+            .unwrap();
+
+        let content = fs::read_to_string(&tmp).unwrap();
+        assert_eq!(content.matches("export PATH=").count(), 1);
+        assert!(content.contains("export FOO=\"bar\""));
+        assert!(content.contains("alias ll=\"ls -l\""));
+        assert!(!content.contains("export OLD="));
+
+        let paths = mgr.get_current_paths().unwrap();
+        assert!(
+            paths.contains("/a") && paths.contains("/b"),
+            "paths: {:?}",
+            paths
+        );
+        let _ = fs::remove_file(&tmp);
+    }
+
+    #[test]
+    fn test_backup_and_restore_all_configs() {
+        let dir = std::env::temp_dir().join("envis_test_shell_backups");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let rc = dir.join(".zshrc");
+        fs::write(&rc, "original\n").unwrap();
+        let mgr = ShellManager {
+            config_file_paths: vec![rc.clone(), dir.join(".bash_profile")],
+            is_development: true,
+        };
+
+        assert!(mgr.backup_all_configs("bad tag").is_err());
+        let created = mgr.backup_all_configs("before-upgrade").unwrap();
+        assert_eq!(created.len(), 1);
+        assert!(dir.join(".zshrc.envbak.before-upgrade").exists());
+        assert!(mgr.backup_all_configs("before-upgrade").is_err());
+
+        fs::write(&rc, "corrupted\n").unwrap();
+        let restored = mgr.restore_all_configs("before-upgrade").unwrap();
+        assert_eq!(restored, vec![rc.to_string_lossy().to_string()]);
+        assert_eq!(fs::read_to_string(&rc).unwrap(), "original\n");
+
+        // 恢复前的内容会被自动备份，可以再恢复回去
+        let listed = mgr.list_available_backups();
+        let zshrc = &listed[0];
+        assert!(zshrc
+            .backups
+            .iter()
+            .any(|b| b.tag == "before-upgrade" && !b.automatic));
+        let auto = zshrc.backups.iter().find(|b| b.automatic).unwrap();
+        mgr.restore_all_configs(&auto.tag).unwrap();
+        assert_eq!(fs::read_to_string(&rc).unwrap(), "corrupted\n");
+        assert!(listed[1].backups.is_empty());
+
+        assert!(mgr.restore_all_configs("missing").is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_restore_config_backup_reinitializes_block() {
+        let dir = std::env::temp_dir().join("envis_test_shell_restore_one");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let rc = dir.join(".zshrc");
+        let other = dir.join(".bash_profile");
+        fs::write(&rc, "original\n").unwrap();
+        fs::write(&other, "other\n").unwrap();
+        let mgr = ShellManager {
+            config_file_paths: vec![rc.clone(), other.clone()],
+            is_development: true,
+        };
+        mgr.backup_all_configs("good").unwrap();
+
+        fs::write(&rc, "mangled\n# BEGIN Envis Environment Block\n").unwrap();
+        fs::write(&other, "other changed\n").unwrap();
+        let restored = mgr
+            .restore_config_backup(&rc.to_string_lossy(), "good")
+            .unwrap();
+        assert_eq!(restored, rc.to_string_lossy());
+
+        // 只恢复指定文件，并重新写入完整的环境块
+        let content = fs::read_to_string(&rc).unwrap();
+        assert!(content.starts_with("original\n"));
+        assert_eq!(content.matches(ENVIS_ACTIVE_BLOCK_START).count(), 1);
+        assert!(content.contains(ENVIS_ACTIVE_BLOCK_END));
+        assert!(fs::read_to_string(&other)
+            .unwrap()
+            .starts_with("other changed\n"));
+
+        // 恢复前的内容保留在自动备份中
+        let listed = mgr.list_available_backups();
+        let auto = listed[0].backups.iter().find(|b| b.automatic).unwrap();
+        let backup = fs::read_to_string(rc.with_extension(&auto.tag)).unwrap();
+        assert!(backup.starts_with("mangled\n"));
+
+        assert!(mgr
+            .restore_config_backup(&dir.join(".profile").to_string_lossy(), "good")
+            .is_err());
+        assert!(mgr
+            .restore_config_backup(&rc.to_string_lossy(), "missing")
+            .is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_diagnose_and_strip_corrupted_block() {
+        let path = PathBuf::from(".bash_profile");
+        let mgr = ShellManager {
+            config_file_paths: vec![],
+            is_development: true,
+        };
+        let content = format!(
+            "line1\n{end}\n{start}\n{warn}\nexport FOO=\"1\"\nexport FOO=\"2\"\nmy_custom_cmd\n{start}\nexport BAR=\"1\"\n{end}\nline2\n{start}\nexport BAZ=\"1\"\nuser_line\n",
+            start = ENVIS_ACTIVE_BLOCK_START,
+            end = ENVIS_ACTIVE_BLOCK_END,
+            warn = ENVIS_WARNING,
+        );
+
+        let kinds: Vec<ShellConfigIssueKind> = mgr
+            .diagnose_content(&path, &content, "")
+            .into_iter()
+            .map(|issue| issue.kind)
+            .collect();
+        assert!(kinds.contains(&ShellConfigIssueKind::OrphanBlockEnd));
+        assert!(kinds.contains(&ShellConfigIssueKind::DuplicateBlockStart));
+        assert!(kinds.contains(&ShellConfigIssueKind::DuplicateLine));
+        assert!(kinds.contains(&ShellConfigIssueKind::UnknownLine));
+        assert!(kinds.contains(&ShellConfigIssueKind::MissingBlockEnd));
+
+        let stripped = mgr.strip_env_blocks(&path, &content, "");
+        // 未闭合块中无法识别的行视为用户内容保留
+        assert_eq!(stripped, "line1\nmy_custom_cmd\nline2\nuser_line");
+
+        let rebuilt = mgr.append_env_block(&path, &stripped, "");
+        assert!(mgr.diagnose_content(&path, &rebuilt, "").is_empty());
+    }
+
+    #[test]
+    fn test_diagnose_duplicate_blocks() {
+        let path = PathBuf::from(".zshrc");
+        let mgr = ShellManager {
+            config_file_paths: vec![],
+            is_development: true,
+        };
+        let block = format!(
+            "{}\n{}\nexport FOO=\"bar\"\n{}\n",
+            ENVIS_ACTIVE_BLOCK_START, ENVIS_WARNING, ENVIS_ACTIVE_BLOCK_END
+        );
+        let kinds = |content: &str| -> Vec<ShellConfigIssueKind> {
+            mgr.diagnose_content(&path, content, "")
+                .into_iter()
+                .map(|issue| issue.kind)
+                .collect()
+        };
+
+        assert!(kinds(&format!("user\n{}", block)).is_empty());
+        assert_eq!(kinds("user\n"), vec![ShellConfigIssueKind::MissingBlock]);
+        // 两个完整的块：第二个 BEGIN 不在块内，报告为重复的块而不是嵌套
+        assert_eq!(
+            kinds(&format!("{}user\n{}", block, block.replace("FOO", "BAR"))),
+            vec![ShellConfigIssueKind::DuplicateBlock]
+        );
+        assert_eq!(
+            kinds(&format!("{}\nexport FOO=1\n", ENVIS_ACTIVE_BLOCK_START)),
+            vec![ShellConfigIssueKind::MissingBlockEnd]
+        );
+        assert_eq!(
+            kinds(&format!("user\n{}\n", ENVIS_ACTIVE_BLOCK_END)),
+            vec![
+                ShellConfigIssueKind::OrphanBlockEnd,
+                ShellConfigIssueKind::MissingBlock
+            ]
+        );
+    }
+
+    #[test]
+    fn test_env_block_integrity_status() {
+        let block = format!(
+            "{}\n{}\nexport FOO=\"bar\"\n{}\n",
+            ENVIS_ACTIVE_BLOCK_START, ENVIS_WARNING, ENVIS_ACTIVE_BLOCK_END
+        );
+        let status = |content: &str| ShellManager::env_block_integrity(content).0;
+
+        assert_eq!(status(&format!("user\n{}", block)), IntegrityStatus::Ok);
+        assert_eq!(status("user\n"), IntegrityStatus::MissingStart);
+        assert_eq!(
+            status(&format!("{}\n{}", block, block)),
+            IntegrityStatus::Duplicated
+        );
+        assert_eq!(
+            status(&format!("{}\nexport FOO=1\n", ENVIS_ACTIVE_BLOCK_START)),
+            IntegrityStatus::MissingEnd
+        );
+        assert_eq!(
+            status(&format!("export FOO=1\n{}\n", ENVIS_ACTIVE_BLOCK_END)),
+            IntegrityStatus::MissingStart
+        );
+        assert_eq!(
+            status(&format!(
+                "{}\n{}\n{}\n",
+                ENVIS_ACTIVE_BLOCK_END, ENVIS_ACTIVE_BLOCK_START, ENVIS_ACTIVE_BLOCK_START
+            )),
+            IntegrityStatus::Unpaired
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_with_timeout_ignores_background_children() {
+        // 后台的 sleep 继承了 stdout/stderr 管道，命令本身退出后不应等它结束
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo hello; sleep 300 & echo $!"]);
+
+        let started = Instant::now();
+        let (stdout, _, exit_code) =
+            ShellManager::output_with_timeout(cmd, Duration::from_secs(30)).unwrap();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(exit_code, 0);
+
+        let mut lines = stdout.lines();
+        assert_eq!(lines.next(), Some("hello"));
+        if let Some(pid) = lines.next() {
+            let _ = Command::new("kill").arg(pid.trim()).status();
+        }
+    }
+
+    #[test]
+    fn test_repair_env_block_content_keeps_block_lines() {
+        let mgr = make_manager_with_content("");
+        let path = PathBuf::from("/tmp/envis_test_integrity/.zshrc");
+
+        // 缺少 END：补在最后一行 Envis 内容之后，用户内容保留在块外
+        let missing_end = format!(
+            "user\n{}\n{}\nexport FOO=\"bar\"\n\nsource ~/.custom\n",
+            ENVIS_ACTIVE_BLOCK_START, ENVIS_WARNING
+        );
+        let repaired =
+            mgr.repair_env_block_content(&path, &missing_end, IntegrityStatus::MissingEnd, "");
+        assert_eq!(
+            ShellManager::env_block_integrity(&repaired).0,
+            IntegrityStatus::Ok
+        );
+        assert!(repaired.contains(&format!(
+            "export FOO=\"bar\"\n{}\n\nsource ~/.custom",
+            ENVIS_ACTIVE_BLOCK_END
+        )));
+
+        // 缺少 BEGIN：补在 Envis 内容之前
+        let missing_start = format!("user\nexport FOO=\"bar\"\n{}\n", ENVIS_ACTIVE_BLOCK_END);
+        let repaired =
+            mgr.repair_env_block_content(&path, &missing_start, IntegrityStatus::MissingStart, "");
+        assert_eq!(
+            ShellManager::env_block_integrity(&repaired).0,
+            IntegrityStatus::Ok
+        );
+        assert!(repaired.starts_with(&format!("user\n{}\n", ENVIS_ACTIVE_BLOCK_START)));
+        assert!(repaired.contains("export FOO=\"bar\""));
+
+        // 重复：只保留第一个块
+        let duplicated = format!(
+            "{s}\nexport FOO=1\n{e}\nuser\n{s}\nexport BAR=2\n{e}\n",
+            s = ENVIS_ACTIVE_BLOCK_START,
+            e = ENVIS_ACTIVE_BLOCK_END
+        );
+        let repaired =
+            mgr.repair_env_block_content(&path, &duplicated, IntegrityStatus::Duplicated, "");
+        assert_eq!(
+            ShellManager::env_block_integrity(&repaired).0,
+            IntegrityStatus::Ok
+        );
+        assert!(repaired.contains("export FOO=1"));
+        assert!(repaired.contains("user"));
+        assert!(!repaired.contains("export BAR=2"));
+    }
 }
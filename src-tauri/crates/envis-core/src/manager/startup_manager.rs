@@ -0,0 +1,139 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::manager::app_config_manager::AppConfigManager;
+use crate::manager::env_serv_data_manager::EnvServDataManager;
+use crate::manager::environment_manager::EnvironmentManager;
+use crate::manager::service_manager::ServiceManager;
+use crate::types::{EnvironmentStatus, ServiceData, ServiceDataStatus, ServiceType};
+
+/// 单个服务的自动启动结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoStartResult {
+    pub environment_id: String,
+    pub service_id: String,
+    pub service_name: String,
+    pub service_type: ServiceType,
+    pub success: bool,
+    /// 已在运行而跳过
+    pub skipped: bool,
+    pub message: String,
+}
+
+/// 自动启动汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoStartSummary {
+    pub started: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub results: Vec<AutoStartResult>,
+}
+
+/// 启动顺序：数据库先于 Nginx
+fn start_order(service_type: &ServiceType) -> u8 {
+    match service_type {
+        ServiceType::Nginx => 1,
+        _ => 0,
+    }
+}
+
+fn start_one(environment_id: &str, service_data: &ServiceData) -> AutoStartResult {
+    let manager = ServiceManager::global();
+    let mut result = AutoStartResult {
+        environment_id: environment_id.to_string(),
+        service_id: service_data.id.clone(),
+        service_name: service_data.name.clone(),
+        service_type: service_data.service_type.clone(),
+        success: false,
+        skipped: false,
+        message: String::new(),
+    };
+
+    match manager.is_service_running(environment_id, service_data) {
+        Ok(true) => {
+            result.success = true;
+            result.skipped = true;
+            result.message = "服务已在运行".to_string();
+            return result;
+        }
+        Ok(false) => {}
+        Err(e) => log::warn!("检查服务 {} 状态失败: {}", service_data.name, e),
+    }
+
+    match manager.start_service(environment_id, service_data) {
+        Ok(res) => {
+            result.success = res.success;
+            result.message = res.message;
+        }
+        Err(e) => result.message = e.to_string(),
+    }
+    result
+}
+
+/// 应用启动时自动启动已激活环境中处于激活状态的常驻服务
+///
+/// 未开启 `auto_start_services` 时返回 None；仅供 GUI 调用，CLI 不应触发
+pub fn start_services_on_app_launch() -> Result<Option<AutoStartSummary>> {
+    let app_config = {
+        let manager = AppConfigManager::global();
+        let manager = manager
+            .lock()
+            .map_err(|_| anyhow!("AppConfigManager 锁获取失败"))?;
+        manager.get_app_config()
+    };
+    if !app_config.auto_start_services {
+        return Ok(None);
+    }
+
+    let environments = {
+        let manager = EnvironmentManager::global();
+        let manager = manager
+            .lock()
+            .map_err(|_| anyhow!("EnvironmentManager 锁获取失败"))?;
+        manager.get_all_environments().context("读取环境列表失败")?
+    };
+
+    let mut targets = Vec::new();
+    for environment in environments
+        .iter()
+        .filter(|env| env.status == EnvironmentStatus::Active)
+    {
+        let service_datas = {
+            let manager = EnvServDataManager::global();
+            let manager = manager
+                .lock()
+                .map_err(|_| anyhow!("EnvServDataManager 锁获取失败"))?;
+            manager.get_environment_all_service_datas(&environment.id)?
+        };
+        targets.extend(
+            service_datas
+                .into_iter()
+                .filter(|sd| matches!(sd.status, ServiceDataStatus::Active))
+                .filter(|sd| ServiceManager::is_daemon_service(&sd.service_type))
+                .map(|sd| (environment.id.clone(), sd)),
+        );
+    }
+    targets.sort_by_key(|(_, sd)| start_order(&sd.service_type));
+
+    log::info!("自动启动服务，共 {} 个", targets.len());
+    let results: Vec<AutoStartResult> = targets
+        .iter()
+        .map(|(environment_id, service_data)| start_one(environment_id, service_data))
+        .collect();
+
+    let summary = AutoStartSummary {
+        started: results.iter().filter(|r| r.success && !r.skipped).count(),
+        skipped: results.iter().filter(|r| r.skipped).count(),
+        failed: results.iter().filter(|r| !r.success).count(),
+        results,
+    };
+    log::info!(
+        "自动启动服务完成: 启动 {}，跳过 {}，失败 {}",
+        summary.started,
+        summary.skipped,
+        summary.failed
+    );
+    Ok(Some(summary))
+}
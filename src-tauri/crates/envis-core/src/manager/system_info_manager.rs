@@ -1,10 +1,17 @@
+use crate::types::{ServiceData, ServiceType};
 use crate::utils::create_command;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::net::IpAddr;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, OnceLock};
-use sysinfo::{ProcessRefreshKind, System};
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessStatus, System, UpdateKind};
+
+/// 监听端口列表的缓存时间，避免频繁调用 lsof / netstat
+const LISTENING_PORTS_TTL: Duration = Duration::from_secs(5);
 
 /// 系统信息数据结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,10 +63,93 @@ pub struct NetworkStats {
     pub interfaces: Vec<NetworkInterface>,
 }
 
+/// Envis 目录的磁盘占用统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiskUsage {
+    /// 按服务类型目录名统计（如 mongodb、nodejs）
+    pub services: BTreeMap<String, ServiceDiskUsage>,
+    /// 按环境 ID 统计
+    pub environments: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServiceDiskUsage {
+    pub size_bytes: u64,
+    pub versions: Vec<VersionDiskUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionDiskUsage {
+    pub version: String,
+    pub size_bytes: u64,
+}
+
+/// 正在运行的进程
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningProcess {
+    pub pid: u32,
+    pub name: String,
+    pub exe: String,
+}
+
+/// 按进程名查找到的进程详情
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessDetail {
+    pub pid: u32,
+    pub name: String,
+    pub exe: String,
+    /// 工作目录，无权限读取时为空
+    pub cwd: String,
+    pub cmd: Vec<String>,
+    /// 常驻内存 (bytes)
+    pub memory_bytes: u64,
+}
+
+/// 托管服务进程的资源占用（同一服务的子进程合并统计）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedProcessStat {
+    pub environment_id: String,
+    pub service_id: String,
+    pub service_name: String,
+    pub service_type: ServiceType,
+    pub version: String,
+    /// 主进程 PID
+    pub pid: u32,
+    /// 主进程及其子进程的 PID
+    pub pids: Vec<u32>,
+    /// CPU 使用率 (%)，基于与上一次采样之间的差值
+    pub cpu_usage: f32,
+    /// 常驻内存 (bytes)
+    pub memory_bytes: u64,
+    /// 主进程运行时长 (秒)
+    pub uptime_secs: u64,
+    /// 正在监听的 TCP 端口
+    pub ports: Vec<u16>,
+}
+
+/// 服务依赖的系统工具检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemDependency {
+    pub tool: String,
+    pub found: bool,
+    pub path: Option<String>,
+}
+
+/// 环境 ID -> 服务类型目录名 -> 进程统计
+pub type ManagedProcessStats = BTreeMap<String, BTreeMap<String, Vec<ManagedProcessStat>>>;
+
+/// 监听端口缓存：采样时间 + PID -> 端口
+type ListeningPortsCache = Option<(Instant, HashMap<u32, Vec<u16>>)>;
+
 /// 系统信息管理器 - 单例模式
 pub struct SystemInfoManager {
     system: Arc<Mutex<System>>,
     last_network_stats: Arc<Mutex<Option<NetworkStats>>>,
+    listening_ports: Arc<Mutex<ListeningPortsCache>>,
 }
 
 impl SystemInfoManager {
@@ -72,6 +162,7 @@ impl SystemInfoManager {
             SystemInfoManager {
                 system: Arc::new(Mutex::new(system)),
                 last_network_stats: Arc::new(Mutex::new(None)),
+                listening_ports: Arc::new(Mutex::new(None)),
             }
         })
     }
@@ -699,6 +790,526 @@ impl SystemInfoManager {
         Ok(stats_map.into_values().collect())
     }
 
+    /// 获取单个进程的 CPU 使用率 (%) 与常驻内存 (bytes)，进程不存在时返回 None
+    ///
+    /// 复用缓存的 System，CPU 使用率为与上一次采样之间的均值，首次采样时为 0。
+    pub fn get_process_usage(&self, pid: u32) -> Result<Option<(f32, u64)>> {
+        let mut system = self
+            .system
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Failed to lock system"))?;
+        let pid = Pid::from_u32(pid);
+        if !system
+            .refresh_process_specifics(pid, ProcessRefreshKind::new().with_cpu().with_memory())
+        {
+            return Ok(None);
+        }
+        Ok(system
+            .process(pid)
+            .map(|process| (process.cpu_usage(), process.memory())))
+    }
+
+    /// 统计托管服务进程的资源占用
+    ///
+    /// 可执行文件位于服务安装目录下的进程视为该服务的进程；同一安装目录被多个服务数据
+    /// 共用时，按命令行中是否引用环境目录区分。复用缓存的 System，CPU 使用率为两次调用之间的均值。
+    pub fn get_managed_process_stats(
+        &self,
+        targets: &[(String, ServiceData)],
+        services_folder: &Path,
+        envs_folder: &Path,
+    ) -> Result<ManagedProcessStats> {
+        let mut stats = ManagedProcessStats::new();
+        if targets.is_empty() {
+            return Ok(stats);
+        }
+
+        let listening_ports = self.get_listening_ports();
+        let mut system = self
+            .system
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Failed to lock system"))?;
+        // 已退出的进程会在刷新时被移除，之后只读取这一份快照
+        system.refresh_processes_specifics(
+            ProcessRefreshKind::new()
+                .with_cpu()
+                .with_memory()
+                .with_exe(UpdateKind::OnlyIfNotSet)
+                .with_cmd(UpdateKind::OnlyIfNotSet),
+        );
+
+        let install_paths: Vec<PathBuf> = targets
+            .iter()
+            .map(|(_, sd)| {
+                let path = services_folder
+                    .join(sd.service_type.dir_name())
+                    .join(&sd.version);
+                std::fs::canonicalize(&path).unwrap_or(path)
+            })
+            .collect();
+
+        for ((environment_id, service_data), install_path) in targets.iter().zip(&install_paths) {
+            let shared = install_paths.iter().filter(|p| *p == install_path).count() > 1;
+            let env_folder = envs_folder
+                .join(environment_id)
+                .to_string_lossy()
+                .to_string();
+
+            let candidates: HashMap<Pid, &Process> = system
+                .processes()
+                .iter()
+                .filter(|(_, p)| p.exe().is_some_and(|exe| exe.starts_with(install_path)))
+                .map(|(pid, p)| (*pid, p))
+                .collect();
+            let mut selected: HashSet<Pid> = candidates
+                .iter()
+                .filter(|(_, p)| p.cmd().iter().any(|arg| arg.contains(&env_folder)))
+                .map(|(pid, _)| *pid)
+                .collect();
+            if selected.is_empty() {
+                if shared {
+                    continue;
+                }
+                selected = candidates.keys().copied().collect();
+            } else {
+                // 补上未在命令行中引用环境目录的子进程（如 postgres 后台进程、nginx worker）
+                loop {
+                    let children: Vec<Pid> = candidates
+                        .iter()
+                        .filter(|(pid, p)| {
+                            !selected.contains(pid)
+                                && p.parent().is_some_and(|parent| selected.contains(&parent))
+                        })
+                        .map(|(pid, _)| *pid)
+                        .collect();
+                    if children.is_empty() {
+                        break;
+                    }
+                    selected.extend(children);
+                }
+            }
+
+            let processes: Vec<(Pid, &Process)> = selected
+                .iter()
+                .filter_map(|pid| candidates.get(pid).map(|p| (*pid, *p)))
+                .collect();
+            // 主进程：父进程不属于该服务的进程中最早启动的一个
+            let Some((main_pid, main_process)) = processes
+                .iter()
+                .filter(|(_, p)| !p.parent().is_some_and(|parent| selected.contains(&parent)))
+                .min_by_key(|(_, p)| p.start_time())
+                .copied()
+            else {
+                continue;
+            };
+
+            let mut pids: Vec<u32> = processes.iter().map(|(pid, _)| pid.as_u32()).collect();
+            pids.sort_unstable();
+            let mut ports: Vec<u16> = pids
+                .iter()
+                .filter_map(|pid| listening_ports.get(pid))
+                .flatten()
+                .copied()
+                .collect();
+            ports.sort_unstable();
+            ports.dedup();
+
+            stats
+                .entry(environment_id.clone())
+                .or_default()
+                .entry(service_data.service_type.dir_name().to_string())
+                .or_default()
+                .push(ManagedProcessStat {
+                    environment_id: environment_id.clone(),
+                    service_id: service_data.id.clone(),
+                    service_name: service_data.name.clone(),
+                    service_type: service_data.service_type.clone(),
+                    version: service_data.version.clone(),
+                    pid: main_pid.as_u32(),
+                    pids,
+                    cpu_usage: processes.iter().map(|(_, p)| p.cpu_usage()).sum(),
+                    memory_bytes: processes.iter().map(|(_, p)| p.memory()).sum(),
+                    uptime_secs: main_process.run_time(),
+                    ports,
+                });
+        }
+
+        Ok(stats)
+    }
+
+    /// 获取各进程正在监听的 TCP 端口（PID -> 端口），失败时返回空表
+    fn get_listening_ports(&self) -> HashMap<u32, Vec<u16>> {
+        let mut cache = match self.listening_ports.lock() {
+            Ok(cache) => cache,
+            Err(_) => return HashMap::new(),
+        };
+        if let Some((at, ports)) = cache.as_ref() {
+            if at.elapsed() < LISTENING_PORTS_TTL {
+                return ports.clone();
+            }
+        }
+
+        let ports = Self::query_listening_ports().unwrap_or_else(|e| {
+            log::debug!("获取监听端口失败: {}", e);
+            HashMap::new()
+        });
+        *cache = Some((Instant::now(), ports.clone()));
+        ports
+    }
+
+    fn query_listening_ports() -> Result<HashMap<u32, Vec<u16>>> {
+        let mut ports: HashMap<u32, Vec<u16>> = HashMap::new();
+        let parse_port = |addr: &str| addr.rsplit(':').next().and_then(|p| p.parse::<u16>().ok());
+
+        if cfg!(target_os = "windows") {
+            // TCP    0.0.0.0:27017    0.0.0.0:0    LISTENING    1234
+            let output = create_command("netstat")
+                .args(["-ano", "-p", "TCP"])
+                .output()?;
+            let output_v6 = create_command("netstat")
+                .args(["-ano", "-p", "TCPv6"])
+                .output()?;
+            let stdout = String::from_utf8_lossy(&output.stdout).to_string()
+                + &String::from_utf8_lossy(&output_v6.stdout);
+            for line in stdout.lines() {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                if fields.len() == 5 && fields[3] == "LISTENING" {
+                    if let (Some(port), Ok(pid)) = (parse_port(fields[1]), fields[4].parse()) {
+                        ports.entry(pid).or_default().push(port);
+                    }
+                }
+            }
+        } else {
+            // -F pn 输出形如 "p1234" 与 "n*:27017" 的行
+            let output = create_command("lsof")
+                .args(["-nP", "-iTCP", "-sTCP:LISTEN", "-Fpn"])
+                .output()?;
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let mut current_pid = None;
+            for line in stdout.lines() {
+                if let Some(pid) = line.strip_prefix('p') {
+                    current_pid = pid.parse::<u32>().ok();
+                } else if let (Some(addr), Some(pid)) = (line.strip_prefix('n'), current_pid) {
+                    if let Some(port) = parse_port(addr) {
+                        ports.entry(pid).or_default().push(port);
+                    }
+                }
+            }
+        }
+
+        Ok(ports)
+    }
+
+    /// 查找可执行文件位于指定目录下的进程
+    pub fn find_processes_by_exe_dir(&self, dir: &Path) -> Result<Vec<RunningProcess>> {
+        let dir = std::fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+
+        let mut system = self
+            .system
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Failed to lock system"))?;
+        system.refresh_processes_specifics(ProcessRefreshKind::new().with_exe(UpdateKind::Always));
+
+        let mut processes = Vec::new();
+        for (pid, process) in system.processes() {
+            let Some(exe) = process.exe() else {
+                continue;
+            };
+            let exe = std::fs::canonicalize(exe).unwrap_or_else(|_| exe.to_path_buf());
+            if exe.starts_with(&dir) {
+                processes.push(RunningProcess {
+                    pid: pid.as_u32(),
+                    name: process.name().to_string(),
+                    exe: exe.to_string_lossy().to_string(),
+                });
+            }
+        }
+        processes.sort_by_key(|p| p.pid);
+        Ok(processes)
+    }
+
+    /// 按进程名查找进程，返回可执行文件、工作目录、命令行和内存占用
+    pub fn find_processes_by_names(&self, names: &[&str]) -> Result<Vec<ProcessDetail>> {
+        let mut system = self
+            .system
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Failed to lock system"))?;
+        system.refresh_processes_specifics(
+            ProcessRefreshKind::new()
+                .with_memory()
+                .with_exe(UpdateKind::OnlyIfNotSet)
+                .with_cmd(UpdateKind::OnlyIfNotSet)
+                .with_cwd(UpdateKind::Always),
+        );
+
+        let mut processes: Vec<ProcessDetail> = system
+            .processes()
+            .iter()
+            .filter(|(_, p)| names.contains(&p.name()))
+            .map(|(pid, p)| ProcessDetail {
+                pid: pid.as_u32(),
+                name: p.name().to_string(),
+                exe: p
+                    .exe()
+                    .map(|exe| exe.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                cwd: p
+                    .cwd()
+                    .map(|cwd| cwd.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                cmd: p.cmd().to_vec(),
+                memory_bytes: p.memory(),
+            })
+            .collect();
+        processes.sort_by_key(|p| p.pid);
+        Ok(processes)
+    }
+
+    /// 检查服务类型在安装和启停过程中调用的系统工具是否存在
+    pub fn check_system_dependencies(&self, service_type: &ServiceType) -> Vec<SystemDependency> {
+        Self::required_tools(service_type)
+            .into_iter()
+            .map(|tool| {
+                let path = Self::find_tool(tool);
+                SystemDependency {
+                    tool: tool.to_string(),
+                    found: path.is_some(),
+                    path,
+                }
+            })
+            .collect()
+    }
+
+    /// 缺少系统工具时返回提示信息
+    pub fn missing_dependencies_warning(&self, service_type: &ServiceType) -> Option<String> {
+        let missing: Vec<String> = self
+            .check_system_dependencies(service_type)
+            .into_iter()
+            .filter(|d| !d.found)
+            .map(|d| d.tool)
+            .collect();
+        if missing.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "系统缺少 {} 依赖的工具: {}，相关操作可能失败",
+            service_type.default_name(),
+            missing.join(", ")
+        ))
+    }
+
+    /// 各服务通过命令行调用的系统工具（解压、进程查找与结束、证书生成）
+    fn required_tools(service_type: &ServiceType) -> Vec<&'static str> {
+        if cfg!(target_os = "windows") {
+            return match service_type {
+                ServiceType::Mongodb
+                | ServiceType::Mysql
+                | ServiceType::Mariadb
+                | ServiceType::Redis
+                | ServiceType::Postgresql
+                | ServiceType::Nginx
+                | ServiceType::Dnsmasq
+                | ServiceType::Php
+                | ServiceType::Vault
+                | ServiceType::Opensearch => vec!["tasklist", "taskkill"],
+                // Kafka 只提供 tgz 发行包，Windows 下同样使用 tar 解压
+                ServiceType::Kafka => vec!["tasklist", "taskkill", "tar"],
+                // RabbitMQ 的 Windows 发行包为 zip，同样使用 tar 解压；Erlang 通过 ERLANG_HOME 查找
+                ServiceType::Rabbitmq => vec!["tasklist", "taskkill", "tar"],
+                _ => vec![],
+            };
+        }
+
+        let mut tools = Vec::new();
+        if matches!(
+            service_type,
+            ServiceType::Nodejs
+                | ServiceType::Mongodb
+                | ServiceType::Mysql
+                | ServiceType::Mariadb
+                | ServiceType::Postgresql
+                | ServiceType::Redis
+                | ServiceType::Python
+                | ServiceType::Dnsmasq
+                | ServiceType::Nasm
+                | ServiceType::Php
+                | ServiceType::Opensearch
+                | ServiceType::Kafka
+                | ServiceType::Rabbitmq
+        ) {
+            tools.push("tar");
+        }
+        if matches!(
+            service_type,
+            ServiceType::Mongodb | ServiceType::Mysql | ServiceType::Mariadb | ServiceType::Redis
+        ) {
+            tools.extend(["lsof", "pgrep", "pkill"]);
+        }
+        match service_type {
+            ServiceType::Postgresql => tools.push("pkill"),
+            // Dnsmasq 需要从源码编译
+            ServiceType::Dnsmasq => tools.push("make"),
+            ServiceType::SSL => tools.push("openssl"),
+            // RabbitMQ 运行依赖 Erlang/OTP
+            ServiceType::Rabbitmq => tools.push("erl"),
+            _ => {}
+        }
+        tools
+    }
+
+    /// 通过 which / where 查找工具路径
+    fn find_tool(tool: &str) -> Option<String> {
+        let finder = if cfg!(target_os = "windows") {
+            "where"
+        } else {
+            "which"
+        };
+        let output = create_command(finder).arg(tool).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(ToString::to_string)
+    }
+
+    /// 查找正在监听指定 TCP 端口的进程（不使用缓存）
+    pub fn find_listening_pid(&self, port: u16) -> Option<u32> {
+        Self::query_listening_ports()
+            .ok()?
+            .into_iter()
+            .find(|(_, ports)| ports.contains(&port))
+            .map(|(pid, _)| pid)
+    }
+
+    /// 进程是否仍在运行，已退出但未被回收的僵尸进程视为不存在
+    pub fn is_process_alive(&self, pid: u32) -> bool {
+        let Ok(mut system) = self.system.lock() else {
+            return false;
+        };
+        // 已退出的进程不会从缓存中移除，需要以刷新结果为准
+        let pid = Pid::from_u32(pid);
+        system.refresh_process(pid)
+            && system
+                .process(pid)
+                .map(|p| p.status() != ProcessStatus::Zombie)
+                .unwrap_or(false)
+    }
+
+    /// 强制结束进程，进程不存在时返回 false
+    pub fn kill_process(&self, pid: u32) -> Result<bool> {
+        let mut system = self
+            .system
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Failed to lock system"))?;
+        let pid = Pid::from_u32(pid);
+        if !system.refresh_process(pid) {
+            return Ok(false);
+        }
+        Ok(system.process(pid).map(|p| p.kill()).unwrap_or(false))
+    }
+
+    /// 统计 services 与 envs 目录的磁盘占用
+    ///
+    /// 使用 tokio::fs 异步遍历，不跟随符号链接；无法读取的文件按 0 计算。
+    pub async fn get_disk_usage(services_folder: &Path, envs_folder: &Path) -> Result<DiskUsage> {
+        let mut usage = DiskUsage::default();
+
+        // services/<type>/<version>
+        for service_dir in Self::list_sub_dirs(services_folder).await? {
+            let service_name = Self::dir_name(&service_dir);
+            let mut service_usage = ServiceDiskUsage::default();
+            for version_dir in Self::list_sub_dirs(&service_dir).await? {
+                let size_bytes = Self::dir_size(&version_dir).await;
+                service_usage.size_bytes += size_bytes;
+                service_usage.versions.push(VersionDiskUsage {
+                    version: Self::dir_name(&version_dir),
+                    size_bytes,
+                });
+            }
+            // 类型目录下的散落文件（如下载残留）也计入总量
+            service_usage.size_bytes += Self::loose_files_size(&service_dir).await;
+            service_usage
+                .versions
+                .sort_by_key(|v| Reverse(v.size_bytes));
+            usage.services.insert(service_name, service_usage);
+        }
+
+        // envs/<environment_id>
+        for env_dir in Self::list_sub_dirs(envs_folder).await? {
+            let size_bytes = Self::dir_size(&env_dir).await;
+            usage
+                .environments
+                .insert(Self::dir_name(&env_dir), size_bytes);
+        }
+
+        Ok(usage)
+    }
+
+    fn dir_name(path: &Path) -> String {
+        path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    /// 列出直接子目录，目录不存在时返回空列表
+    async fn list_sub_dirs(path: &Path) -> Result<Vec<PathBuf>> {
+        let mut dirs = Vec::new();
+        let mut entries = match tokio::fs::read_dir(path).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(dirs),
+            Err(e) => return Err(anyhow::anyhow!("读取目录 {} 失败: {}", path.display(), e)),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_type().await.map(|t| t.is_dir()).unwrap_or(false) {
+                dirs.push(entry.path());
+            }
+        }
+        dirs.sort();
+        Ok(dirs)
+    }
+
+    /// 目录下直接文件（不含子目录）的大小
+    async fn loose_files_size(path: &Path) -> u64 {
+        let mut total = 0;
+        let Ok(mut entries) = tokio::fs::read_dir(path).await else {
+            return 0;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if let Ok(metadata) = entry.metadata().await {
+                if metadata.is_file() {
+                    total += metadata.len();
+                }
+            }
+        }
+        total
+    }
+
+    /// 递归统计目录大小
+    async fn dir_size(path: &Path) -> u64 {
+        let mut total = 0;
+        let mut stack = vec![path.to_path_buf()];
+        while let Some(dir) = stack.pop() {
+            let Ok(mut entries) = tokio::fs::read_dir(&dir).await else {
+                continue;
+            };
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                // DirEntry::metadata 不跟随符号链接
+                let Ok(metadata) = entry.metadata().await else {
+                    continue;
+                };
+                if metadata.is_dir() {
+                    stack.push(entry.path());
+                } else {
+                    total += metadata.len();
+                }
+            }
+        }
+        total
+    }
+
     /// 格式化运行时间为人类可读格式
     fn format_uptime(seconds: u64) -> String {
         let days = seconds / 86400;
@@ -0,0 +1,285 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::manager::env_serv_data_manager::EnvServDataManager;
+use crate::manager::environment_manager::{EnvironmentManager, EnvironmentResult};
+use crate::manager::service_manager::ServiceManager;
+use crate::manager::services::kafka::DEFAULT_KAFKA_PORT;
+use crate::manager::services::opensearch::DEFAULT_OPENSEARCH_PORT;
+use crate::manager::services::postgresql::DEFAULT_POSTGRESQL_PORT;
+use crate::manager::services::rabbitmq::DEFAULT_RABBITMQ_PORT;
+use crate::manager::services::ServiceRegistry;
+use crate::types::{Environment, ServiceData, ServiceType};
+
+/// 内置环境模板定义
+struct TemplateDefinition {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    service_types: &'static [ServiceType],
+}
+
+/// 内置模板列表
+const TEMPLATES: &[TemplateDefinition] = &[
+    TemplateDefinition {
+        id: "lemp",
+        name: "LEMP",
+        description: "Nginx + MariaDB + PHP",
+        service_types: &[ServiceType::Nginx, ServiceType::Mariadb, ServiceType::Php],
+    },
+    TemplateDefinition {
+        id: "mern",
+        name: "MERN",
+        description: "MongoDB + Node.js",
+        service_types: &[ServiceType::Mongodb, ServiceType::Nodejs],
+    },
+    TemplateDefinition {
+        id: "django",
+        name: "Django",
+        description: "Python + PostgreSQL",
+        service_types: &[ServiceType::Python, ServiceType::Postgresql],
+    },
+];
+
+/// 环境模板（供前端展示）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub services: Vec<EnvironmentTemplateService>,
+}
+
+/// 模板中的服务及其推荐版本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentTemplateService {
+    pub service_type: ServiceType,
+    /// 推荐版本：已安装的最新版本，否则为可下载的最新版本
+    pub version: Option<String>,
+    pub installed: bool,
+    /// 与现有环境不冲突的端口（仅端口保存在 metadata 中的服务）
+    pub port: Option<u16>,
+}
+
+/// 环境模板：按预设的服务组合一键创建环境
+pub struct EnvironmentTemplates;
+
+impl EnvironmentTemplates {
+    /// 列出内置模板，并解析推荐版本和端口
+    pub fn list() -> Result<Vec<EnvironmentTemplate>> {
+        let used_ports = Self::collect_used_ports()?;
+        Ok(TEMPLATES
+            .iter()
+            .map(|template| Self::resolve(template, &used_ports))
+            .collect())
+    }
+
+    /// 按模板创建环境及其全部服务数据
+    ///
+    /// 任一服务创建失败时删除已创建的环境，不留下半成品。
+    pub fn create_environment(template_id: &str, name: String) -> Result<EnvironmentResult> {
+        let definition = TEMPLATES
+            .iter()
+            .find(|t| t.id == template_id)
+            .ok_or_else(|| anyhow!("未知的环境模板: {}", template_id))?;
+        let template = Self::resolve(definition, &Self::collect_used_ports()?);
+        if let Some(service) = template.services.iter().find(|s| s.version.is_none()) {
+            return Err(anyhow!(
+                "{} 没有可用的版本",
+                service.service_type.default_name()
+            ));
+        }
+
+        let environment = {
+            let environment_manager = EnvironmentManager::global();
+            let environment_manager = environment_manager.lock().unwrap();
+            let result =
+                environment_manager.create_environment(name, Some(template.description.clone()))?;
+            result
+                .data
+                .and_then(|data| data.get("environment").cloned())
+                .and_then(|value| serde_json::from_value::<Environment>(value).ok())
+                .ok_or_else(|| anyhow!("创建环境失败"))?
+        }; // 锁在这里被释放
+
+        let service_datas = match Self::create_services(&environment.id, &template) {
+            Ok(service_datas) => service_datas,
+            Err(e) => {
+                let environment_manager = EnvironmentManager::global();
+                let environment_manager = environment_manager.lock().unwrap();
+                if let Err(cleanup_err) = environment_manager.delete_environment(&environment) {
+                    log::warn!("回滚模板环境 {} 失败: {}", environment.id, cleanup_err);
+                }
+                return Err(anyhow!("按模板创建服务失败，已删除环境: {}", e));
+            }
+        };
+
+        let needs_download: Vec<serde_json::Value> = template
+            .services
+            .iter()
+            .filter(|s| !s.installed)
+            .map(|s| {
+                serde_json::json!({
+                    "serviceType": s.service_type,
+                    "version": s.version,
+                })
+            })
+            .collect();
+
+        log::info!(
+            "已按模板 {} 创建环境: {} ({})",
+            template.id,
+            environment.name,
+            environment.id
+        );
+        Ok(EnvironmentResult {
+            success: true,
+            message: format!("环境 '{}' 创建成功", environment.name),
+            data: Some(serde_json::json!({
+                "environment": environment,
+                "serviceDatas": service_datas,
+                "needsDownload": needs_download,
+            })),
+        })
+    }
+
+    /// 创建模板中的服务数据，并写入分配的端口
+    fn create_services(
+        environment_id: &str,
+        template: &EnvironmentTemplate,
+    ) -> Result<Vec<ServiceData>> {
+        let env_serv_data_manager = EnvServDataManager::global();
+        let env_serv_data_manager = env_serv_data_manager.lock().unwrap();
+
+        let mut service_datas = Vec::new();
+        // 倒序创建：新服务排在最前面，创建完成后顺序与模板一致
+        for service in template.services.iter().rev() {
+            let version = service.version.clone().unwrap_or_default();
+            let result = env_serv_data_manager.create_service_data(
+                environment_id,
+                service.service_type.clone(),
+                version,
+            )?;
+            let mut service_data = result
+                .data
+                .and_then(|data| data.get("serviceData").cloned())
+                .and_then(|value| serde_json::from_value::<ServiceData>(value).ok())
+                .ok_or_else(|| anyhow!("创建服务数据失败"))?;
+
+            if let (Some((key, _)), Some(port)) = (Self::port_key(&service.service_type), service.port)
+            {
+                env_serv_data_manager.set_metadata(
+                    environment_id,
+                    &mut service_data,
+                    key,
+                    serde_json::Value::String(port.to_string()),
+                )?;
+            }
+            service_datas.insert(0, service_data);
+        }
+        Ok(service_datas)
+    }
+
+    /// 解析模板的推荐版本和端口
+    fn resolve(template: &TemplateDefinition, used_ports: &HashSet<u16>) -> EnvironmentTemplate {
+        let mut used_ports = used_ports.clone();
+        let services = template
+            .service_types
+            .iter()
+            .map(|service_type| {
+                let version = Self::resolve_version(service_type);
+                let installed = version
+                    .as_deref()
+                    .map(|v| ServiceRegistry::get(service_type).is_installed(v))
+                    .unwrap_or(false);
+                let port = Self::port_key(service_type).map(|(_, default_port)| {
+                    let port = (default_port..=u16::MAX)
+                        .find(|p| !used_ports.contains(p))
+                        .unwrap_or(default_port);
+                    used_ports.insert(port);
+                    port
+                });
+                EnvironmentTemplateService {
+                    service_type: service_type.clone(),
+                    version,
+                    installed,
+                    port,
+                }
+            })
+            .collect();
+
+        EnvironmentTemplate {
+            id: template.id.to_string(),
+            name: template.name.to_string(),
+            description: template.description.to_string(),
+            services,
+        }
+    }
+
+    /// 已安装的最新版本，否则为可下载的最新版本
+    fn resolve_version(service_type: &ServiceType) -> Option<String> {
+        let service = ServiceRegistry::get(service_type);
+        let mut versions: Vec<(semver::Version, String)> = service
+            .available_versions()
+            .into_iter()
+            .filter_map(|v| ServiceManager::parse_lenient_version(&v).map(|parsed| (parsed, v)))
+            .collect();
+        versions.sort_by(|a, b| b.0.cmp(&a.0));
+
+        versions
+            .iter()
+            .find(|(_, v)| service.is_installed(v))
+            .or(versions.first())
+            .map(|(_, v)| v.clone())
+    }
+
+    /// 端口保存在 metadata 中的服务：(metadata 键, 默认端口)
+    fn port_key(service_type: &ServiceType) -> Option<(&'static str, u16)> {
+        match service_type {
+            ServiceType::Mysql => Some(("MYSQL_PORT", 3306)),
+            ServiceType::Mariadb => Some(("MARIADB_PORT", 3306)),
+            ServiceType::Postgresql => Some(("POSTGRESQL_PORT", DEFAULT_POSTGRESQL_PORT)),
+            ServiceType::Opensearch => Some(("OPENSEARCH_PORT", DEFAULT_OPENSEARCH_PORT)),
+            ServiceType::Kafka => Some(("KAFKA_PORT", DEFAULT_KAFKA_PORT)),
+            ServiceType::Rabbitmq => Some(("RABBITMQ_PORT", DEFAULT_RABBITMQ_PORT)),
+            _ => None,
+        }
+    }
+
+    /// 收集现有环境中已占用的端口，未设置端口的服务按默认端口计算
+    fn collect_used_ports() -> Result<HashSet<u16>> {
+        let environments = {
+            let environment_manager = EnvironmentManager::global();
+            let environment_manager = environment_manager.lock().unwrap();
+            environment_manager.get_all_environments()?
+        }; // 锁在这里被释放
+
+        let env_serv_data_manager = EnvServDataManager::global();
+        let env_serv_data_manager = env_serv_data_manager.lock().unwrap();
+        let mut used_ports = HashSet::new();
+        for environment in environments {
+            let service_datas =
+                env_serv_data_manager.get_environment_all_service_datas(&environment.id)?;
+            for service_data in service_datas {
+                let Some((key, default_port)) = Self::port_key(&service_data.service_type) else {
+                    continue;
+                };
+                let port = service_data
+                    .metadata
+                    .as_ref()
+                    .and_then(|m| m.get(key))
+                    .and_then(|v| {
+                        v.as_str()
+                            .and_then(|s| s.trim().parse::<u16>().ok())
+                            .or_else(|| v.as_u64().and_then(|n| u16::try_from(n).ok()))
+                    })
+                    .unwrap_or(default_port);
+                used_ports.insert(port);
+            }
+        }
+        Ok(used_ports)
+    }
+}
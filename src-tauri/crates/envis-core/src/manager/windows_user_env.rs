@@ -0,0 +1,337 @@
+use crate::utils::create_command;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 用户级环境变量注册表键
+const USER_ENV_KEY: &str = "HKCU\\Environment";
+/// 记录 Envis 写入注册表内容的状态文件（位于 envis 主目录）
+const STATE_FILE_NAME: &str = "windows_user_env.json";
+
+/// 注册表字符串值（REG_SZ / REG_EXPAND_SZ）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RegValue {
+    value: String,
+    expand: bool,
+}
+
+/// Envis 对 HKCU\Environment 所做修改的记录
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UserEnvState {
+    /// Envis 追加到用户 PATH 中的条目
+    #[serde(default)]
+    paths: Vec<String>,
+    /// Envis 设置过的变量及其被覆盖前的原值（None 表示原本不存在）
+    #[serde(default)]
+    vars: HashMap<String, Option<RegValue>>,
+}
+
+impl UserEnvState {
+    fn is_empty(&self) -> bool {
+        self.paths.is_empty() && self.vars.is_empty()
+    }
+}
+
+/// 对 Windows 用户环境变量（HKCU\Environment）的一次批量修改
+///
+/// PATH 只增删 Envis 自己追加的条目，用户原有条目不会被移除；变量在首次覆盖时记录原值，
+/// 删除时恢复。`commit()` 时统一写入注册表并广播 WM_SETTINGCHANGE。
+pub(crate) struct WindowsUserEnvUpdate {
+    state_path: PathBuf,
+    state: UserEnvState,
+    path_value: Option<RegValue>,
+    path_dirty: bool,
+    /// 待写入的变量，None 表示删除
+    pending_vars: BTreeMap<String, Option<RegValue>>,
+}
+
+impl WindowsUserEnvUpdate {
+    /// 是否存在 Envis 写入注册表的记录
+    pub(crate) fn has_state(envis_folder: &Path) -> bool {
+        envis_folder.join(STATE_FILE_NAME).exists()
+    }
+
+    pub(crate) fn load(envis_folder: &Path) -> Result<Self> {
+        let state_path = envis_folder.join(STATE_FILE_NAME);
+        let state = match fs::read_to_string(&state_path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::warn!("解析 {} 失败，视为空记录: {}", state_path.display(), e);
+                UserEnvState::default()
+            }),
+            Err(_) => UserEnvState::default(),
+        };
+
+        Ok(Self {
+            state_path,
+            state,
+            path_value: query_value("Path")?,
+            path_dirty: false,
+            pending_vars: BTreeMap::new(),
+        })
+    }
+
+    pub(crate) fn add_path(&mut self, path: &str) {
+        let current = self
+            .path_value
+            .as_ref()
+            .map(|v| v.value.clone())
+            .unwrap_or_default();
+        let tracked = self.state.paths.iter().any(|p| path_entry_eq(p, path));
+
+        if let Some(merged) = prepend_path_entry(&current, path) {
+            self.set_path_value(merged);
+            if !tracked {
+                self.state.paths.push(path.to_string());
+            }
+        }
+        // 已存在且未记录的条目属于用户，不纳入管理
+    }
+
+    pub(crate) fn delete_path(&mut self, path: &str) {
+        let before = self.state.paths.len();
+        self.state.paths.retain(|p| !path_entry_eq(p, path));
+        if self.state.paths.len() == before {
+            return;
+        }
+
+        let current = self
+            .path_value
+            .as_ref()
+            .map(|v| v.value.clone())
+            .unwrap_or_default();
+        if let Some(stripped) = remove_path_entry(&current, path) {
+            self.set_path_value(stripped);
+        }
+    }
+
+    pub(crate) fn set_var(&mut self, key: &str, value: &str) -> Result<()> {
+        if key.eq_ignore_ascii_case("Path") {
+            return Ok(());
+        }
+        if !self.state.vars.contains_key(key) {
+            // 同一批次内刚被撤销的变量，以待恢复的原值为准，而不是注册表中 Envis 写入的旧值
+            let previous = match self.pending_vars.get(key) {
+                Some(pending) => pending.clone(),
+                None => query_value(key)?,
+            };
+            self.state.vars.insert(key.to_string(), previous);
+        }
+        self.pending_vars.insert(
+            key.to_string(),
+            Some(RegValue {
+                value: value.to_string(),
+                expand: value.contains('%'),
+            }),
+        );
+        Ok(())
+    }
+
+    pub(crate) fn delete_var(&mut self, key: &str) {
+        if let Some(previous) = self.state.vars.remove(key) {
+            self.pending_vars.insert(key.to_string(), previous);
+        }
+    }
+
+    /// 撤销 Envis 写入的所有 PATH 条目与变量
+    pub(crate) fn clear(&mut self) {
+        for path in std::mem::take(&mut self.state.paths) {
+            let current = self
+                .path_value
+                .as_ref()
+                .map(|v| v.value.clone())
+                .unwrap_or_default();
+            if let Some(stripped) = remove_path_entry(&current, &path) {
+                self.set_path_value(stripped);
+            }
+        }
+        for (key, previous) in std::mem::take(&mut self.state.vars) {
+            self.pending_vars.insert(key, previous);
+        }
+    }
+
+    fn set_path_value(&mut self, value: String) {
+        // 保留原有 REG_EXPAND_SZ 类型，避免 %USERPROFILE% 等条目失效
+        let expand =
+            self.path_value.as_ref().map(|v| v.expand).unwrap_or(true) || value.contains('%');
+        self.path_value = Some(RegValue { value, expand });
+        self.path_dirty = true;
+    }
+
+    pub(crate) fn commit(self) -> Result<()> {
+        let changed = self.path_dirty || !self.pending_vars.is_empty();
+
+        if self.path_dirty {
+            match &self.path_value {
+                Some(v) if !v.value.is_empty() => write_value("Path", v)?,
+                _ => delete_value("Path")?,
+            }
+        }
+        for (key, value) in &self.pending_vars {
+            match value {
+                Some(v) => write_value(key, v)?,
+                None => delete_value(key)?,
+            }
+        }
+
+        if self.state.is_empty() {
+            if self.state_path.exists() {
+                fs::remove_file(&self.state_path).context("删除用户环境变量记录失败")?;
+            }
+        } else {
+            let content = serde_json::to_string_pretty(&self.state)?;
+            fs::write(&self.state_path, content).context("写入用户环境变量记录失败")?;
+        }
+
+        if changed {
+            broadcast_environment_change();
+        }
+        Ok(())
+    }
+}
+
+/// PATH 条目比较：忽略大小写与末尾分隔符
+fn path_entry_eq(a: &str, b: &str) -> bool {
+    let normalize = |s: &str| s.trim().trim_end_matches(['\\', '/']).to_lowercase();
+    normalize(a) == normalize(b)
+}
+
+/// 将条目加到 PATH 最前面，已存在时返回 None
+fn prepend_path_entry(current: &str, entry: &str) -> Option<String> {
+    if current.split(';').any(|p| path_entry_eq(p, entry)) {
+        return None;
+    }
+    let rest: Vec<&str> = current
+        .split(';')
+        .filter(|p| !p.trim().is_empty())
+        .collect();
+    let mut parts = vec![entry];
+    parts.extend(rest);
+    Some(parts.join(";"))
+}
+
+/// 从 PATH 中移除条目，不存在时返回 None
+fn remove_path_entry(current: &str, entry: &str) -> Option<String> {
+    let parts: Vec<&str> = current
+        .split(';')
+        .filter(|p| !p.trim().is_empty())
+        .collect();
+    let kept: Vec<&str> = parts
+        .iter()
+        .copied()
+        .filter(|p| !path_entry_eq(p, entry))
+        .collect();
+    if kept.len() == parts.len() {
+        return None;
+    }
+    Some(kept.join(";"))
+}
+
+/// 读取 HKCU\Environment 下的字符串值，不存在时返回 None
+fn query_value(name: &str) -> Result<Option<RegValue>> {
+    let output = create_command("reg")
+        .args(["query", USER_ENV_KEY, "/v", name])
+        .output()
+        .context("执行 reg query 命令失败")?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    // reg query 输出示例：
+    //     Path    REG_EXPAND_SZ    C:\a;%USERPROFILE%\b
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for line in stdout.lines() {
+        let trimmed = line.trim_start();
+        let Some((value_name, rest)) = trimmed.split_once("    ") else {
+            continue;
+        };
+        if !value_name.eq_ignore_ascii_case(name) {
+            continue;
+        }
+        let rest = rest.trim_start();
+        let (expand, value) = if let Some(v) = rest.strip_prefix("REG_EXPAND_SZ") {
+            (true, v)
+        } else if let Some(v) = rest.strip_prefix("REG_SZ") {
+            (false, v)
+        } else {
+            continue;
+        };
+        return Ok(Some(RegValue {
+            value: value.trim().to_string(),
+            expand,
+        }));
+    }
+    Ok(None)
+}
+
+fn write_value(name: &str, value: &RegValue) -> Result<()> {
+    let reg_type = if value.expand {
+        "REG_EXPAND_SZ"
+    } else {
+        "REG_SZ"
+    };
+    let output = create_command("reg")
+        .args([
+            "add",
+            USER_ENV_KEY,
+            "/v",
+            name,
+            "/t",
+            reg_type,
+            "/d",
+            &value.value,
+            "/f",
+        ])
+        .output()
+        .context("执行 reg add 命令失败")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "写入用户环境变量 {} 失败: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(())
+}
+
+fn delete_value(name: &str) -> Result<()> {
+    let output = create_command("reg")
+        .args(["delete", USER_ENV_KEY, "/v", name, "/f"])
+        .output()
+        .context("执行 reg delete 命令失败")?;
+    // 值本身不存在时 reg delete 也会失败，这里只记录日志
+    if !output.status.success() {
+        log::debug!(
+            "删除用户环境变量 {} 失败: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(())
+}
+
+/// 广播 WM_SETTINGCHANGE("Environment")，让资源管理器等重新读取用户环境变量
+fn broadcast_environment_change() {
+    let script = r#"
+Add-Type -Namespace Envis -Name Native -MemberDefinition @'
+[DllImport("user32.dll", SetLastError = true, CharSet = CharSet.Unicode)]
+public static extern IntPtr SendMessageTimeout(IntPtr hWnd, uint Msg, UIntPtr wParam, string lParam, uint fuFlags, uint uTimeout, out UIntPtr lpdwResult);
+'@
+$result = [UIntPtr]::Zero
+[Envis.Native]::SendMessageTimeout([IntPtr]0xffff, 0x1A, [UIntPtr]::Zero, "Environment", 2, 5000, [ref]$result) | Out-Null
+"#;
+    match create_command("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .output()
+    {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => log::warn!(
+            "广播 WM_SETTINGCHANGE 失败: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ),
+        Err(e) => log::warn!("广播 WM_SETTINGCHANGE 失败: {}", e),
+    }
+}
@@ -44,6 +44,12 @@ pub enum ServiceType {
     SSL,
     Dnsmasq,
     Nasm,
+    Php,
+    Vault,
+    Opensearch,
+    Kafka,
+    Rabbitmq,
+    Compose,
     // 可以根据需要添加更多服务类型
 }
 
@@ -66,6 +72,12 @@ impl ServiceType {
             ServiceType::SSL => "ssl",
             ServiceType::Dnsmasq => "dnsmasq",
             ServiceType::Nasm => "nasm",
+            ServiceType::Php => "php",
+            ServiceType::Vault => "vault",
+            ServiceType::Opensearch => "opensearch",
+            ServiceType::Kafka => "kafka",
+            ServiceType::Rabbitmq => "rabbitmq",
+            ServiceType::Compose => "compose",
         }
     }
 
@@ -87,12 +99,14 @@ impl ServiceType {
             ServiceType::Mysql => &["bin"],   // MySQL 可执行文件目录
             ServiceType::Postgresql => &["bin"], // PostgreSQL 可执行文件目录
             ServiceType::Nginx => {
+                // Windows: nginx.exe 位于安装根目录
+                // Unix: Nginx 可执行文件位于 sbin 子目录
                 if cfg!(target_os = "windows") {
-                    &["", "sbin"]
+                    &[""]
                 } else {
                     &["sbin"]
                 }
-            } // Nginx 可执行文件目录
+            }
             ServiceType::Python => {
                 // Windows: python.exe 位于安装根目录
                 // Unix: Python 可执行文件位于 bin 子目录
@@ -109,6 +123,29 @@ impl ServiceType {
             ServiceType::SSL => &[],          // SSL 服务不需要 PATH
             ServiceType::Dnsmasq => &["sbin"], // Dnsmasq 可执行文件目录
             ServiceType::Nasm => &[""],       // Nasm 解压后执行文件在根目录或自身路径
+            ServiceType::Php => {
+                // Windows: php.exe 位于安装根目录
+                // Unix: php 在 bin，php-fpm 在 sbin
+                // composer 包装脚本与 php 放在同一目录
+                if cfg!(target_os = "windows") {
+                    &[""]
+                } else {
+                    &["bin", "sbin"]
+                }
+            }
+            ServiceType::Vault => &[""], // vault 可执行文件位于安装根目录
+            ServiceType::Opensearch => &["bin"], // opensearch-plugin 等命令行工具
+            ServiceType::Kafka => {
+                // kafka-topics 等命令行脚本，Windows 版本位于 bin/windows
+                if cfg!(target_os = "windows") {
+                    &["bin/windows"]
+                } else {
+                    &["bin"]
+                }
+            }
+            // rabbitmqctl / rabbitmq-plugins 等命令行工具
+            ServiceType::Rabbitmq => &["sbin"],
+            ServiceType::Compose => &[], // 使用系统已安装的 docker / podman
         }
     }
 
@@ -118,6 +155,7 @@ impl ServiceType {
             ServiceType::Redis => vec![],
             ServiceType::Nodejs => vec![
                 "NPM_CONFIG_PREFIX", // npm 全局安装路径
+                "NODE_OPTIONS",      // Node.js 运行时选项
             ],
             ServiceType::Mongodb => vec![],
             ServiceType::Mariadb => vec![],
@@ -132,12 +170,21 @@ impl ServiceType {
             ServiceType::SSL => vec![],     // SSL 服务不需要环境变量
             ServiceType::Dnsmasq => vec![], // Dnsmasq 服务不需要环境变量
             ServiceType::Nasm => vec![],
+            ServiceType::Php => vec!["PHPRC"], // php.ini 所在目录
+            ServiceType::Vault => vec!["VAULT_ADDR", "VAULT_TOKEN"], // vault CLI 连接 dev 服务
+            ServiceType::Opensearch => vec![], // 端口与堆大小写入环境目录的配置文件
+            ServiceType::Kafka => vec![], // 端口与堆大小写入环境目录的配置文件
+            ServiceType::Rabbitmq => vec![], // 端口写入环境目录的配置文件
+            ServiceType::Compose => vec![], // 同一环境可有多个 compose 项目，不导出 COMPOSE_FILE
         }
     }
 
-    /// 判断该服务类型是否需要下载安装（Custom、Host、SSL 不需要）
+    /// 判断该服务类型是否需要下载安装（Custom、Host、SSL、Compose 不需要）
     pub fn needs_download(&self) -> bool {
-        !matches!(self, ServiceType::Custom | ServiceType::Host | ServiceType::SSL)
+        !matches!(
+            self,
+            ServiceType::Custom | ServiceType::Host | ServiceType::SSL | ServiceType::Compose
+        )
     }
 
     pub fn default_name(&self) -> String {
@@ -157,6 +204,12 @@ impl ServiceType {
             ServiceType::SSL => "SSL".to_string(),
             ServiceType::Dnsmasq => "Dnsmasq".to_string(),
             ServiceType::Nasm => "Nasm".to_string(),
+            ServiceType::Php => "PHP".to_string(),
+            ServiceType::Vault => "Vault".to_string(),
+            ServiceType::Opensearch => "OpenSearch".to_string(),
+            ServiceType::Kafka => "Kafka".to_string(),
+            ServiceType::Rabbitmq => "RabbitMQ".to_string(),
+            ServiceType::Compose => "Compose".to_string(),
         }
     }
 
@@ -166,18 +219,27 @@ impl ServiceType {
                 "REDIS_CONFIG",
                 "REDIS_PASSWORD",
             ],
-            ServiceType::Nodejs => vec!["NPM_CONFIG_PREFIX"],
+            ServiceType::Nodejs => vec!["NPM_CONFIG_PREFIX", "NODE_OPTIONS"],
             ServiceType::Mongodb => vec!["MONGODB_CONFIG", "MONGODB_KEYFILE_PATH"],
             ServiceType::Mariadb => vec![],
             ServiceType::Mysql => vec![],
-            ServiceType::Postgresql => vec![],
+            ServiceType::Postgresql => vec![
+                "POSTGRESQL_CONFIG",
+                "POSTGRESQL_DATA",
+                "POSTGRESQL_LOG",
+                "POSTGRESQL_PORT",
+                "POSTGRESQL_SUPERUSER",
+                "POSTGRESQL_PASSWORD",
+            ],
             ServiceType::Nginx => vec![],
             ServiceType::Python => vec!["PYTHON_HOME"],
             ServiceType::Java => vec!["JAVA_HOME", "JAVA_OPTS", "MAVEN_HOME", "GRADLE_HOME"],
             ServiceType::Rust => vec!["RUST_HOME", "CARGO_HOME"],
             ServiceType::Custom => vec![
-                "paths",   // 自定义路径列表
-                "envVars", // 自定义环境变量
+                "paths",    // 自定义路径列表
+                "envVars",  // 自定义环境变量
+                "commands", // 自定义可执行命令
+                "binaries", // 已注册的本地工具目录
             ],
             ServiceType::Host => vec![
                 "hosts", // host 条目列表
@@ -193,6 +255,12 @@ impl ServiceType {
             ],
             ServiceType::Dnsmasq => vec!["DNSMASQ_CONF"],
             ServiceType::Nasm => vec![],
+            ServiceType::Php => vec!["PHPRC", "PHP_FPM_LISTEN"],
+            ServiceType::Vault => vec!["VAULT_ADDR", "VAULT_TOKEN"],
+            ServiceType::Opensearch => vec!["OPENSEARCH_PORT", "OPENSEARCH_HEAP_MB"],
+            ServiceType::Kafka => vec!["KAFKA_PORT", "KAFKA_CONTROLLER_PORT", "KAFKA_HEAP_MB"],
+            ServiceType::Rabbitmq => vec!["RABBITMQ_PORT", "RABBITMQ_MANAGEMENT_PORT"],
+            ServiceType::Compose => vec!["COMPOSE_FILE", "COMPOSE_PROJECT_NAME"],
         }
     }
 
@@ -209,23 +277,31 @@ impl ServiceType {
             ServiceType::Python => vec!["PIP_INDEX_URL", "PIP_TRUSTED_HOST", "PYTHON3_AS_PYTHON"],
             // Host 条目（域名映射，跨机器有意义）
             ServiceType::Host => vec!["hosts"],
+            // 日志轮转保留天数
+            ServiceType::Nginx => vec!["NGINX_LOG_ROTATE_DAYS"],
             // 以下服务无可导出的远程配置项
             ServiceType::Redis => vec![],
             ServiceType::Mongodb => vec![],
             ServiceType::Mariadb => vec![],
             ServiceType::Mysql => vec![],
             ServiceType::Postgresql => vec![],
-            ServiceType::Nginx => vec![],
             ServiceType::Rust => vec![],
             ServiceType::Custom => vec![],
             ServiceType::SSL => vec![],
             ServiceType::Dnsmasq => vec![],
             ServiceType::Nasm => vec![],
+            ServiceType::Php => vec![],
+            ServiceType::Vault => vec![],
+            ServiceType::Opensearch => vec![],
+            ServiceType::Kafka => vec![],
+            ServiceType::Rabbitmq => vec![],
+            // compose 文件为本地路径
+            ServiceType::Compose => vec![],
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum ServiceDataStatus {
     Active,
@@ -239,6 +315,8 @@ pub enum ServiceStatus {
     Error,
     Running,
     Stopped,
+    /// 进程存在但服务未正常响应
+    Degraded,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
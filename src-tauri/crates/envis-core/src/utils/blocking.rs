@@ -0,0 +1,49 @@
+use anyhow::{anyhow, Result};
+
+/// 在阻塞线程池中执行同步操作，避免 sleep、等待子进程等阻塞异步运行时
+///
+/// 任务 panic 或被取消时返回的 JoinError 合并为普通错误。
+pub async fn run_blocking<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| anyhow!("后台任务执行失败: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn test_run_blocking_calls_overlap() {
+        let sleep = || {
+            run_blocking(|| {
+                std::thread::sleep(Duration::from_millis(500));
+                Ok(())
+            })
+        };
+
+        // 单线程运行时下两个阻塞调用仍在各自的阻塞线程中同时执行
+        let started = Instant::now();
+        let (a, b) = tokio::join!(sleep(), sleep());
+        assert!(a.is_ok() && b.is_ok());
+        assert!(started.elapsed() < Duration::from_millis(900));
+    }
+
+    #[tokio::test]
+    async fn test_run_blocking_flattens_errors() {
+        let err = run_blocking(|| -> Result<()> { Err(anyhow!("启动失败")) })
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "启动失败");
+
+        let err = run_blocking(|| -> Result<()> { panic!("boom") })
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("后台任务执行失败"));
+    }
+}
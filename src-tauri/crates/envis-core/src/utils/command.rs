@@ -1,5 +1,5 @@
 use std::ffi::OsStr;
-use std::process::Command;
+use std::process::{Child, Command};
 
 /// 创建一个在 Windows 上不弹出终端窗口的 Command
 ///
@@ -26,3 +26,41 @@ pub fn create_command<S: AsRef<OsStr>>(program: S) -> Command {
 
     cmd
 }
+
+/// 结束子进程及其派生的进程
+///
+/// Unix 下需要子进程以 `process_group(0)` 启动，才能按进程组结束。
+pub fn kill_process_tree(child: &mut Child) {
+    let pid = child.id().to_string();
+
+    #[cfg(unix)]
+    let killed = create_command("kill")
+        .args(["-KILL", "--", &format!("-{}", pid)])
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false);
+
+    #[cfg(windows)]
+    let killed = create_command("taskkill")
+        .args(["/PID", &pid, "/T", "/F"])
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false);
+
+    if !killed {
+        if let Err(e) = child.kill() {
+            log::warn!("结束进程 {} 失败: {}", pid, e);
+        }
+    }
+}
+
+/// 截取命令输出末尾的 max_chars 个字符，用于保存执行记录
+pub fn output_tail(output: &str, max_chars: usize) -> String {
+    let output = output.trim_end();
+    let count = output.chars().count();
+    if count <= max_chars {
+        return output.to_string();
+    }
+    let tail: String = output.chars().skip(count - max_chars).collect();
+    format!("...{}", tail)
+}
@@ -0,0 +1,112 @@
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use crate::manager::app_config_manager::{AppConfig, AppConfigManager, ProxyMode};
+
+/// 代理连通性测试的默认目标（安装包均从 GitHub 下载）
+pub const PROXY_TEST_URL: &str = "https://github.com";
+/// 代理连通性测试的超时时间
+const PROXY_TEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 代理连通性测试结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyTestResult {
+    pub success: bool,
+    pub url: String,
+    pub status: Option<u16>,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// 按应用配置中的代理设置创建 HTTP 客户端构建器
+///
+/// 每次调用时读取最新配置，修改代理设置后新发起的请求即可生效，无需重启应用。
+pub fn http_client_builder() -> Result<reqwest::ClientBuilder> {
+    let app_config = AppConfigManager::global().lock().unwrap().get_app_config();
+    apply_proxy(reqwest::Client::builder(), &app_config)
+}
+
+fn apply_proxy(
+    builder: reqwest::ClientBuilder,
+    app_config: &AppConfig,
+) -> Result<reqwest::ClientBuilder> {
+    match app_config.proxy_mode {
+        ProxyMode::None => Ok(builder.no_proxy()),
+        // reqwest 默认读取代理环境变量及系统设置
+        ProxyMode::System => Ok(builder),
+        ProxyMode::Manual => {
+            let proxy_url = app_config
+                .proxy_url
+                .as_deref()
+                .map(str::trim)
+                .filter(|url| !url.is_empty())
+                .ok_or_else(|| anyhow!("未配置代理地址"))?;
+            // URL 中的 user:password@ 会作为代理认证信息
+            let proxy = reqwest::Proxy::all(proxy_url)
+                .map_err(|e| anyhow!("代理地址无效 {}: {}", proxy_url, e))?
+                .no_proxy(reqwest::NoProxy::from_string(
+                    &app_config.no_proxy.join(","),
+                ));
+            Ok(builder.proxy(proxy))
+        }
+    }
+}
+
+/// 通过当前代理设置向 target_url 发送 HEAD 请求，返回耗时或具体的连接 / 认证错误
+pub async fn test_proxy_connection(target_url: Option<String>) -> Result<ProxyTestResult> {
+    let url = target_url
+        .filter(|url| !url.trim().is_empty())
+        .unwrap_or_else(|| PROXY_TEST_URL.to_string());
+    let client = http_client_builder()?.timeout(PROXY_TEST_TIMEOUT).build()?;
+
+    let started = Instant::now();
+    let result = client.head(&url).send().await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let (status, error) = match result {
+        Ok(response) if response.status() == reqwest::StatusCode::PROXY_AUTHENTICATION_REQUIRED => {
+            (
+                Some(response.status().as_u16()),
+                Some("代理认证失败（407），请检查代理地址中的用户名和密码".to_string()),
+            )
+        }
+        Ok(response) => (Some(response.status().as_u16()), None),
+        Err(e) => (
+            e.status().map(|s| s.as_u16()),
+            Some(describe_request_error(&e)),
+        ),
+    };
+
+    Ok(ProxyTestResult {
+        success: error.is_none(),
+        url,
+        status,
+        latency_ms: error.is_none().then_some(latency_ms),
+        error,
+    })
+}
+
+/// 展开 reqwest 错误的 source 链，保留底层的连接 / TLS / 认证错误信息
+fn describe_request_error(error: &reqwest::Error) -> String {
+    let kind = if error.is_timeout() {
+        "连接超时"
+    } else if error.is_connect() {
+        "连接失败"
+    } else {
+        "请求失败"
+    };
+
+    let mut detail = Vec::new();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        detail.push(cause.to_string());
+        source = cause.source();
+    }
+    if detail.is_empty() {
+        format!("{}: {}", kind, error)
+    } else {
+        format!("{}: {}", kind, detail.join(": "))
+    }
+}
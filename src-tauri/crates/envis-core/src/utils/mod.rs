@@ -1,4 +1,10 @@
+pub mod blocking;
 pub mod command;
+pub mod http;
+pub mod net;
 pub mod path;
 
-pub use command::create_command;
+pub use blocking::run_blocking;
+pub use command::{create_command, kill_process_tree, output_tail};
+pub use http::http_client_builder;
+pub use net::find_free_port;
@@ -0,0 +1,34 @@
+use anyhow::{anyhow, Result};
+use std::net::TcpListener;
+use std::ops::RangeInclusive;
+
+/// 在给定范围内查找本机 127.0.0.1 可绑定的端口
+///
+/// 全部被占用时返回的错误说明端口范围，label 描述需要该端口的用途。
+pub fn find_free_port(range: RangeInclusive<u16>, label: &str) -> Result<u16> {
+    range
+        .clone()
+        .find(|port| TcpListener::bind(("127.0.0.1", *port)).is_ok())
+        .ok_or_else(|| {
+            anyhow!(
+                "端口 {}-{} 均被占用，无法启动{}",
+                range.start(),
+                range.end(),
+                label
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_free_port_skips_bound_port() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let taken = listener.local_addr().unwrap().port();
+
+        let err = find_free_port(taken..=taken, "测试实例").unwrap_err();
+        assert!(err.to_string().contains("无法启动测试实例"));
+    }
+}
@@ -9,28 +9,39 @@ use envis_core::manager::env_serv_data_manager::initialize_env_serv_data_manager
 use envis_core::manager::environment_manager::initialize_environment_manager;
 use envis_core::manager::exit_cleanup_manager::cleanup_on_app_close;
 use envis_core::manager::service_manager::initialize_service_manager;
+use envis_core::manager::services::nginx::NginxService;
 use envis_core::manager::shell_manamger::initialize_shell_manager;
+use envis_core::manager::startup_manager::start_services_on_app_launch;
 use tauri::Manager;
-use tauri_command::app_config_commands::{get_app_config, open_app_config_folder, set_app_config};
+use tauri_command::app_config_commands::{
+    get_app_config, open_app_config_folder, set_app_config, test_proxy_connection,
+};
+use tauri_command::backup_commands::*;
 use tauri_command::env_serv_data_commands::*;
 use tauri_command::environment_commands::*;
 use tauri_command::file_commands::*;
 use tauri_command::service_commands::*;
+use tauri_command::services::compose_commands::*;
 use tauri_command::services::custom_commands::*;
 use tauri_command::services::dnsmasq_commands::*;
 use tauri_command::services::host_commands::*;
 use tauri_command::services::java_commands::*;
+use tauri_command::services::kafka_commands::*;
 use tauri_command::services::mariadb_commands::*;
 use tauri_command::services::mongodb_commands::*;
 use tauri_command::services::mysql_commands::*;
 use tauri_command::services::nasm_commands::*;
 use tauri_command::services::nginx_commands::*;
 use tauri_command::services::nodejs_commands::*;
+use tauri_command::services::opensearch_commands::*;
+use tauri_command::services::php_commands::*;
 use tauri_command::services::postgresql_commands::*;
 use tauri_command::services::python_commands::*;
+use tauri_command::services::rabbitmq_commands::*;
 use tauri_command::services::redis_commands::*;
 use tauri_command::services::rust_commands::*;
 use tauri_command::services::ssl_commands::*;
+use tauri_command::services::vault_commands::*;
 use tauri_command::system_info_commands::*;
 use tauri_plugin_log::{Target, TargetKind};
 
@@ -115,6 +126,18 @@ pub fn run(ctx: tauri::Context<tauri::Wry>) {
             // 初始化状态事件推送模块（内含配置文件轮询，检测 CLI 对 active 字段的修改）
             status_events::init(app.handle().clone());
 
+            // 按 NGINX_LOG_ROTATE_DAYS 每天轮转 Nginx 日志
+            NginxService::global().start_log_rotation_scheduler();
+
+            // 按配置自动启动已激活环境中的服务（后台执行，不阻塞启动）
+            if !is_cli_mode {
+                std::thread::spawn(|| match start_services_on_app_launch() {
+                    Ok(Some(summary)) => status_events::emit_services_auto_start_summary(&summary),
+                    Ok(None) => {}
+                    Err(e) => log::error!("自动启动服务失败: {}", e),
+                });
+            }
+
             // 设置系统托盘
             if let Err(e) = tray::setup_tray(app.handle()) {
                 log::error!("设置系统托盘失败: {}", e);
@@ -131,6 +154,7 @@ pub fn run(ctx: tauri::Context<tauri::Wry>) {
             // 应用配置相关命令
             get_app_config,
             set_app_config,
+            test_proxy_connection,
             open_app_config_folder,
             // 文件相关命令
             open_file_dialog,
@@ -142,8 +166,13 @@ pub fn run(ctx: tauri::Context<tauri::Wry>) {
             write_file_content,
             // 环境相关命令
             get_all_environments,
+            get_environment_service_tree,
             get_environment,
             create_environment,
+            list_environment_templates,
+            create_environment_from_template,
+            read_project_manifest,
+            apply_project_manifest,
             save_environment,
             delete_environment,
             is_environment_exists,
@@ -154,22 +183,69 @@ pub fn run(ctx: tauri::Context<tauri::Wry>) {
             switch_environment_and_services,
             export_environment_data,
             import_environment_data,
+            get_environment_env_vars,
+            set_environment_env_var,
+            delete_environment_env_var,
+            get_environment_metadata,
+            set_environment_metadata,
+            get_environment_hooks,
+            set_environment_hooks,
+            // 环境计划任务相关命令
+            list_scheduled_tasks,
+            add_scheduled_task,
+            update_scheduled_task,
+            delete_scheduled_task,
+            run_scheduled_task_now,
             // 环境服务数据相关命令
             get_environment_all_service_datas,
             get_service_data,
             create_service_data,
             update_service_data,
+            bulk_update_service_data,
             delete_service_data,
+            duplicate_service_data,
+            search_service_data,
             active_service_data,
             deactive_service_data,
             // 服务相关命令
             get_all_installed_services,
             get_service_size,
             delete_service,
+            scan_services_folder,
+            cleanup_services_folder,
             get_services_process_stats,
+            get_service_resource_usage,
+            get_environment_services_status,
+            check_for_service_updates,
+            upgrade_service_data,
+            list_installed_services_for_environment,
+            validate_service_data_integrity,
+            set_service_auto_restart,
+            get_supervision_status,
+            pause_service_download,
+            resume_service_download,
+            get_download_history,
+            clear_download_history,
+            get_concurrent_download_limit,
+            set_concurrent_download_limit,
+            install_service_from_file,
+            // 数据库备份相关命令
+            backup_service_data,
+            list_service_backups,
+            restore_service_backup,
             // 系统信息相关命令
             get_system_info,
             open_terminal,
+            list_shell_config_files,
+            verify_and_repair_shell_config,
+            verify_shell_integrity,
+            backup_all_configs,
+            restore_all_configs,
+            restore_shell_config_backup,
+            list_available_backups,
+            get_disk_usage,
+            get_managed_process_stats,
+            check_system_dependencies,
             toggle_dev_tools,
             quit_app,
             open_system_env_settings,
@@ -184,11 +260,22 @@ pub fn run(ctx: tauri::Context<tauri::Wry>) {
             set_npm_registry,
             set_npm_config_prefix,
             set_pnpm_home,
+            set_node_options,
+            clear_node_options,
+            get_current_node_options,
             get_global_npm_packages,
             install_global_npm_package,
+            get_npm_config,
+            set_npm_config_key,
+            list_npm_config,
+            run_npm_script,
+            stop_npm_script,
+            get_node_process_list,
             // Java 服务命令
             check_java_installed,
             check_maven_installed,
+            get_maven_versions,
+            download_maven,
             get_java_versions,
             download_java,
             cancel_download_java,
@@ -196,7 +283,9 @@ pub fn run(ctx: tauri::Context<tauri::Wry>) {
             initialize_maven,
             get_maven_download_progress,
             get_java_info,
+            get_java_home,
             set_java_home,
+            detect_system_java_installations,
             set_java_opts,
             set_maven_home,
             set_gradle_home,
@@ -205,6 +294,8 @@ pub fn run(ctx: tauri::Context<tauri::Wry>) {
             initialize_gradle,
             get_gradle_download_progress,
             set_maven_local_repository,
+            get_maven_repository,
+            set_maven_repository,
             // Rust 服务命令
             check_rust_installed,
             get_rust_versions,
@@ -213,6 +304,7 @@ pub fn run(ctx: tauri::Context<tauri::Wry>) {
             get_rust_download_progress,
             get_rust_info,
             set_cargo_home,
+            set_cargo_registry_mirror,
             // Nginx 服务命令
             check_nginx_installed,
             get_nginx_versions,
@@ -226,12 +318,32 @@ pub fn run(ctx: tauri::Context<tauri::Wry>) {
             stop_nginx_service,
             restart_nginx_service,
             get_nginx_service_status,
+            validate_nginx_config,
+            list_nginx_upstreams,
+            add_nginx_upstream,
+            remove_nginx_upstream,
+            write_nginx_php_fastcgi_conf,
+            set_nginx_worker_processes,
+            set_nginx_worker_connections,
+            rotate_nginx_logs,
+            get_nginx_access_log_stats,
+            get_nginx_active_connections,
             // 自定义服务命令
             update_custom_service_paths,
             update_custom_service_env_vars,
             update_custom_service_aliases,
             update_custom_service_chdir,
             execute_custom_service_alias,
+            list_custom_service_commands,
+            add_custom_service_command,
+            update_custom_service_command,
+            remove_custom_service_command,
+            execute_custom_service_command,
+            stop_custom_service_command,
+            list_custom_binaries,
+            register_custom_binary,
+            unregister_custom_binary,
+            switch_custom_binary,
             open_project_in_vscode,
             open_folder_in_finder,
             open_terminal_in_folder,
@@ -242,6 +354,8 @@ pub fn run(ctx: tauri::Context<tauri::Wry>) {
             delete_host,
             toggle_host,
             clear_hosts,
+            bulk_import_hosts,
+            export_hosts_as_etc_format,
             open_hosts_file,
             // MongoDB 服务命令
             download_mongodb,
@@ -262,10 +376,22 @@ pub fn run(ctx: tauri::Context<tauri::Wry>) {
             list_mongodb_databases,
             list_mongodb_collections,
             create_mongodb_database,
+            create_mongodb_collection,
+            drop_mongodb_collection,
+            get_mongodb_collection_stats,
+            list_mongodb_indexes,
+            explain_mongodb_query,
+            create_mongodb_index,
             create_mongodb_user,
             list_mongodb_users,
             update_mongodb_user_roles,
             delete_mongodb_user,
+            change_mongodb_admin_password,
+            rotate_mongodb_keyfile,
+            get_mongodb_replication_status,
+            create_mongodb_backup_schedule,
+            delete_mongodb_backup_schedule,
+            list_mongodb_backup_schedules,
             // Redis 服务命令
             download_redis,
             get_redis_versions,
@@ -306,6 +432,11 @@ pub fn run(ctx: tauri::Context<tauri::Wry>) {
             create_mariadb_user,
             delete_mariadb_user,
             update_mariadb_user_grants,
+            grant_mariadb_privileges,
+            dump_mariadb_database,
+            restore_mariadb_database,
+            change_mariadb_root_password,
+            export_mariadb_users_sql,
             // MySQL 服务命令
             download_mysql,
             get_mysql_versions,
@@ -331,6 +462,8 @@ pub fn run(ctx: tauri::Context<tauri::Wry>) {
             create_mysql_user,
             delete_mysql_user,
             update_mysql_user_grants,
+            grant_mysql_privileges,
+            change_mysql_root_password,
             // PostgreSQL 服务命令
             download_postgresql,
             get_postgresql_versions,
@@ -356,6 +489,8 @@ pub fn run(ctx: tauri::Context<tauri::Wry>) {
             create_postgresql_role,
             delete_postgresql_role,
             update_postgresql_role_grants,
+            dump_postgresql_database,
+            restore_postgresql_database,
             // Python 服务命令
             download_python,
             get_python_versions,
@@ -373,6 +508,11 @@ pub fn run(ctx: tauri::Context<tauri::Wry>) {
             create_python_venv,
             remove_python_venv,
             open_python_venv_terminal,
+            install_python_packages,
+            list_python_installed_packages,
+            get_python_info,
+            upgrade_python_pip,
+            upgrade_python_pip_in_venv,
             // SSL 证书服务命令
             check_ca_initialized,
             initialize_ca,
@@ -382,6 +522,8 @@ pub fn run(ctx: tauri::Context<tauri::Wry>) {
             delete_certificate,
             export_ca_certificate,
             check_ca_installed,
+            install_ca_to_system_trust,
+            uninstall_ca_from_system_trust,
             // Dnsmasq 服务命令
             check_dnsmasq_installed,
             get_dnsmasq_versions,
@@ -396,11 +538,98 @@ pub fn run(ctx: tauri::Context<tauri::Wry>) {
             get_nasm_download_progress,
             // Dnsmasq 配置命令
             get_dnsmasq_config,
+            get_dnsmasq_upstream_dns,
+            set_dnsmasq_upstream_dns,
+            reset_dnsmasq_upstream_dns,
             // Dnsmasq 控制命令
             start_dnsmasq_service,
             stop_dnsmasq_service,
             restart_dnsmasq_service,
             get_dnsmasq_service_status,
+            // PHP 服务命令
+            get_php_versions,
+            download_php,
+            cancel_download_php,
+            check_php_installed,
+            get_php_download_progress,
+            // PHP-FPM 控制与配置
+            start_php_fpm,
+            stop_php_fpm,
+            get_php_fpm_status,
+            get_php_config,
+            set_php_config_value,
+            set_php_ini_values,
+            install_composer,
+            check_composer_installed,
+            set_composer_mirror,
+            start_php_service,
+            stop_php_service,
+            restart_php_service,
+            get_php_service_status,
+            // Vault 服务命令
+            get_vault_versions,
+            download_vault,
+            cancel_download_vault,
+            check_vault_installed,
+            get_vault_download_progress,
+            start_vault_service,
+            stop_vault_service,
+            restart_vault_service,
+            get_vault_service_status,
+            // Vault 密钥管理
+            list_vault_secrets,
+            read_vault_secret,
+            write_vault_secret,
+            delete_vault_secret,
+            // OpenSearch 服务命令
+            get_opensearch_versions,
+            download_opensearch,
+            cancel_download_opensearch,
+            check_opensearch_installed,
+            get_opensearch_download_progress,
+            start_opensearch_service,
+            stop_opensearch_service,
+            restart_opensearch_service,
+            get_opensearch_service_status,
+            // OpenSearch 索引管理
+            list_opensearch_indices,
+            delete_opensearch_index,
+            // Kafka 服务命令
+            get_kafka_versions,
+            download_kafka,
+            cancel_download_kafka,
+            check_kafka_installed,
+            get_kafka_download_progress,
+            start_kafka_service,
+            stop_kafka_service,
+            restart_kafka_service,
+            get_kafka_service_status,
+            // Kafka topic 管理
+            create_kafka_topic,
+            list_kafka_topics,
+            delete_kafka_topic,
+            describe_kafka_topic,
+            // RabbitMQ 服务命令
+            get_rabbitmq_versions,
+            download_rabbitmq,
+            cancel_download_rabbitmq,
+            check_rabbitmq_installed,
+            get_rabbitmq_download_progress,
+            start_rabbitmq_service,
+            stop_rabbitmq_service,
+            restart_rabbitmq_service,
+            get_rabbitmq_service_status,
+            enable_rabbitmq_management,
+            // RabbitMQ 队列管理
+            list_rabbitmq_queues,
+            delete_rabbitmq_queue,
+            // Compose 服务命令
+            detect_container_runtime,
+            set_compose_file,
+            start_compose_service,
+            stop_compose_service,
+            restart_compose_service,
+            get_compose_service_status,
         ])
         .on_window_event(|_window, event| {
             match event {
@@ -1,13 +1,16 @@
 use envis_core::manager::app_config_manager::AppConfigManager;
-use envis_core::manager::services::{
-    DnsmasqService, DownloadManager, MariadbService, MongodbService, MysqlService, NginxService,
-    PostgresqlService, RedisService,
-};
+use envis_core::manager::change_events;
+use envis_core::manager::environment_hooks::{EnvironmentHookResult, ENVIRONMENT_HOOK_FAILED_EVENT};
+use envis_core::manager::scheduler::TaskScheduler;
+use envis_core::manager::service_manager::ServiceManager;
+use envis_core::manager::service_supervisor::ServiceSupervisor;
+use envis_core::manager::startup_manager::AutoStartSummary;
+use envis_core::manager::services::{DownloadManager, ServiceRegistry};
 use envis_core::types::{ServiceData, ServiceType};
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 use tauri::{AppHandle, Emitter};
 
@@ -21,9 +24,12 @@ const SERVICE_CONFIG_FILE: &str = "service.json";
 /// 应在 setup 回调中调用一次。
 pub fn init(handle: AppHandle) {
     let _ = APP_HANDLE.set(handle);
+    start_change_events();
     start_config_watcher();
     start_service_status_watcher();
     start_download_watcher();
+    start_service_supervisor();
+    start_task_scheduler();
 }
 
 fn emit(event: &str, payload: serde_json::Value) {
@@ -34,12 +40,40 @@ fn emit(event: &str, payload: serde_json::Value) {
     }
 }
 
+/// 启动环境 / 服务数据变更事件分发，事件直接推送到前端
+fn start_change_events() {
+    change_events::start(Arc::new(|event: &str, payload| emit(event, payload)));
+}
+
+/// 启动服务自动重启监控，崩溃 / 重启事件直接推送到前端
+fn start_service_supervisor() {
+    ServiceSupervisor::global().start(Arc::new(|event: &str, payload| emit(event, payload)));
+}
+
+/// 启动环境计划任务调度，任务执行完成事件直接推送到前端
+fn start_task_scheduler() {
+    TaskScheduler::global().start(Arc::new(|event: &str, payload| emit(event, payload)));
+}
+
 /// 推送环境状态变化事件（激活 / 停用），status 为 "active" 或 "inactive"
 pub fn emit_environment_status(environment_id: &str, status: &str) {
     emit(
         "status:environment",
         serde_json::json!({ "environmentId": environment_id, "status": status }),
     );
+    if let Some(handle) = APP_HANDLE.get() {
+        crate::tray::update_tray_menu(handle);
+    }
+}
+
+/// 推送环境钩子执行失败事件，每个失败的钩子推送一次
+pub fn emit_environment_hook_failures(results: &[EnvironmentHookResult]) {
+    for result in results.iter().filter(|r| !r.success) {
+        emit(
+            ENVIRONMENT_HOOK_FAILED_EVENT,
+            serde_json::to_value(result).unwrap_or_default(),
+        );
+    }
 }
 
 /// 推送服务数据激活状态变化事件（激活 / 停用），status 为 "active" 或 "inactive"
@@ -48,6 +82,9 @@ pub fn emit_service_data_status(environment_id: &str, service_id: &str, status:
         "status:service-data",
         serde_json::json!({ "environmentId": environment_id, "serviceId": service_id, "status": status }),
     );
+    if let Some(handle) = APP_HANDLE.get() {
+        crate::tray::update_tray_menu(handle);
+    }
 }
 
 /// 推送服务运行状态变化事件（启动 / 停止 / 重启），status 为 "running" 或 "stopped"
@@ -56,6 +93,9 @@ pub fn emit_service_status(environment_id: &str, service_id: &str, status: &str)
         "status:service",
         serde_json::json!({ "environmentId": environment_id, "serviceId": service_id, "status": status }),
     );
+    if let Some(handle) = APP_HANDLE.get() {
+        crate::tray::on_service_status(handle, service_id, status);
+    }
 }
 
 /// 推送服务下载状态变化事件，status 为 DownloadStatus 的小写字符串，progress 为 0-100
@@ -66,6 +106,53 @@ pub fn emit_download_status(task_id: &str, status: &str, progress: f64) {
     );
 }
 
+/// 推送自定义命令的一行输出，stream 为 "stdout" 或 "stderr"
+pub fn emit_custom_command_output(run_id: &str, stream: &str, line: &str) {
+    emit(
+        "custom-command:output",
+        serde_json::json!({ "runId": run_id, "stream": stream, "line": line }),
+    );
+}
+
+/// 推送自定义命令结束事件，payload 为执行结果（不含已逐行推送的输出）
+pub fn emit_custom_command_exit(run_id: &str, result: serde_json::Value) {
+    emit(
+        "custom-command:exit",
+        serde_json::json!({ "runId": run_id, "result": result }),
+    );
+}
+
+/// 推送数据库导出 / 导入进度，total 未知时 progress 为 null
+pub fn emit_database_transfer_progress(
+    event: &str,
+    service_id: &str,
+    database: &str,
+    processed: u64,
+    total: Option<u64>,
+) {
+    let progress = total
+        .filter(|t| *t > 0)
+        .map(|t| (processed as f64 / t as f64 * 100.0).min(100.0));
+    emit(
+        event,
+        serde_json::json!({
+            "serviceId": service_id,
+            "database": database,
+            "processedBytes": processed,
+            "totalBytes": total,
+            "progress": progress,
+        }),
+    );
+}
+
+/// 推送应用启动时自动启动服务的汇总结果
+pub fn emit_services_auto_start_summary(summary: &AutoStartSummary) {
+    match serde_json::to_value(summary) {
+        Ok(payload) => emit("services-auto-start-summary", payload),
+        Err(e) => log::warn!("序列化自动启动结果失败: {}", e),
+    }
+}
+
 // ── 配置文件轮询 ────────────────────────────────────────────────────────────
 
 /// 启动后台轮询线程，每隔 [`POLL_INTERVAL_SECS`] 秒扫描 `envs_folder` 下所有
@@ -322,9 +409,8 @@ fn start_service_status_watcher() {
 
                         // 与快照对比，变化则推送
                         let key = (env_id.clone(), svc_id.clone());
-                        let prev = snapshot.get(&key);
-                        let changed = prev.map(|p| p != &status_str).unwrap_or(true);
-                        snapshot.insert(key, status_str.clone());
+                        let prev = snapshot.insert(key, status_str.clone());
+                        let changed = prev.as_ref().map(|p| p != &status_str).unwrap_or(true);
 
                         if changed {
                             log::debug!(
@@ -332,6 +418,15 @@ fn start_service_status_watcher() {
                                 env_id, svc_id, status_str
                             );
                             emit_service_status(&env_id, &svc_id, &status_str);
+                            // 首次检测到的状态不算启动 / 停止
+                            if let Some(prev) = prev {
+                                change_events::service_status_changed(
+                                    &env_id,
+                                    &svc_id,
+                                    &prev,
+                                    &status_str,
+                                );
+                            }
                         }
                     }
                 }
@@ -340,55 +435,21 @@ fn start_service_status_watcher() {
     });
 }
 
-/// 根据服务类型调用对应的服务管理器检测进程运行状态，返回小写状态字符串。
+/// 通过 ServiceRegistry 检测常驻服务的进程运行状态，返回小写状态字符串。
 /// 返回 None 表示该服务类型不支持运行状态检测（如 SSL、Host、Custom 等无守护进程的服务）。
 fn get_service_running_status(environment_id: &str, service_data: &ServiceData) -> Option<String> {
-    match service_data.service_type {
-        ServiceType::Nginx => NginxService::global()
-            .get_service_status(service_data)
-            .ok()
-            .and_then(|s| serde_json::to_value(s).ok())
-            .and_then(|v| v.as_str().map(|s| s.to_string())),
-
-        ServiceType::Dnsmasq => DnsmasqService::global()
-            .get_service_status(service_data)
-            .ok()
-            .and_then(|s| serde_json::to_value(s).ok())
-            .and_then(|v| v.as_str().map(|s| s.to_string())),
-
-        ServiceType::Mongodb => MongodbService::global()
-            .get_service_status(environment_id, service_data)
-            .ok()
-            .and_then(|r| r.data)
-            .and_then(|d| d.get("status").and_then(|v| v.as_str()).map(|s| s.to_string())),
-
-        ServiceType::Mariadb => MariadbService::global()
-            .get_service_status(environment_id, service_data)
-            .ok()
-            .and_then(|r| r.data)
-            .and_then(|d| d.get("status").and_then(|v| v.as_str()).map(|s| s.to_string())),
-
-        ServiceType::Mysql => MysqlService::global()
-            .get_service_status(environment_id, service_data)
-            .ok()
-            .and_then(|r| r.data)
-            .and_then(|d| d.get("status").and_then(|v| v.as_str()).map(|s| s.to_string())),
-
-        ServiceType::Redis => RedisService::global()
-            .get_service_status(environment_id, service_data)
-            .ok()
-            .and_then(|r| r.data)
-            .and_then(|d| d.get("status").and_then(|v| v.as_str()).map(|s| s.to_string())),
-
-        ServiceType::Postgresql => PostgresqlService::global()
-            .get_service_status(environment_id, service_data)
-            .ok()
-            .and_then(|r| r.data)
-            .and_then(|d| d.get("status").and_then(|v| v.as_str()).map(|s| s.to_string())),
-
-        // Custom、Host、SSL、Java、NodeJs、Python、Rust、Nasm、MinGW 等无守护进程，不需要运行状态检测
-        _ => None,
+    // Dnsmasq 不属于 is_daemon_service，但同样有需要检测运行状态的守护进程
+    let has_daemon = ServiceManager::is_daemon_service(&service_data.service_type)
+        || service_data.service_type == ServiceType::Dnsmasq;
+    if !has_daemon {
+        return None;
     }
+
+    ServiceRegistry::get(&service_data.service_type)
+        .get_service_status(environment_id, service_data)
+        .ok()
+        .and_then(|r| r.data)
+        .and_then(|d| d.get("status").and_then(|v| v.as_str()).map(|s| s.to_string()))
 }
 
 // ── 下载状态轮询 ────────────────────────────────────────────────────────────
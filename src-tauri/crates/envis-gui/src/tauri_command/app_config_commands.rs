@@ -1,37 +1,63 @@
 use anyhow::Result;
 use envis_core::manager::app_config_manager::{AppConfig, AppConfigManager};
 use envis_core::manager::file_manager::FileManager;
+use envis_core::manager::services::{available_space, DownloadManager};
+use envis_core::utils::http;
 use serde_json::Value;
+use tauri::{AppHandle, Manager};
 
+/// 获取应用配置，附带派生字段（不写入配置文件，每次调用时计算）
 #[tauri::command]
-pub fn get_app_config() -> Result<Value, String> {
-    let app_config_manager = AppConfigManager::global();
-    let app_config_manager = app_config_manager.lock().map_err(|e| e.to_string())?;
-    let app_config = app_config_manager.get_app_config();
+pub fn get_app_config(app_handle: AppHandle) -> Result<Value, String> {
+    let (app_config, envs_folder, services_folder, config_file_path) = {
+        let app_config_manager = AppConfigManager::global();
+        let app_config_manager = app_config_manager.lock().map_err(|e| e.to_string())?;
+        (
+            app_config_manager.get_app_config(),
+            app_config_manager.get_envs_folder(),
+            app_config_manager.get_services_folder(),
+            app_config_manager.get_app_config_file_path(),
+        )
+    }; // 锁在这里被释放
+
+    let available_disk_space_gb = available_space(std::path::Path::new(&services_folder))
+        .map(|bytes| bytes as f64 / 1024.0 / 1024.0 / 1024.0);
 
     Ok(serde_json::json!({
         "success": true,
         "message": "获取应用配置成功",
         "data": {
-            "appConfig": app_config
+            "appConfig": app_config,
+            "resolvedEnvsFolder": envs_folder,
+            "resolvedServicesFolder": services_folder,
+            "appVersion": app_handle.package_info().version.to_string(),
+            "configFilePath": config_file_path,
+            "availableDiskSpaceGb": available_disk_space_gb
         }
     }))
 }
 
 #[tauri::command]
 pub fn set_app_config(app_config: AppConfig) -> Result<Value, String> {
-    let app_config_manager = AppConfigManager::global();
-    let mut app_config_manager = app_config_manager.lock().map_err(|e| e.to_string())?;
     let app_config_clone = app_config.clone();
+    let result = {
+        let app_config_manager = AppConfigManager::global();
+        let mut app_config_manager = app_config_manager.lock().map_err(|e| e.to_string())?;
+        app_config_manager.set_app_config(app_config)
+    }; // 下载管理器初始化时需要读取配置，先释放锁
 
-    match app_config_manager.set_app_config(app_config) {
-        Ok(_) => Ok(serde_json::json!({
-            "success": true,
-            "message": "设置应用配置成功",
-            "data": {
-                "appConfig": app_config_clone
-            }
-        })),
+    match result {
+        Ok(_) => {
+            DownloadManager::global()
+                .apply_concurrent_download_limit(app_config_clone.max_concurrent_downloads);
+            Ok(serde_json::json!({
+                "success": true,
+                "message": "设置应用配置成功",
+                "data": {
+                    "appConfig": app_config_clone
+                }
+            }))
+        }
         Err(e) => Ok(serde_json::json!({
             "success": false,
             "message": format!("设置应用配置失败: {}", e),
@@ -40,6 +66,23 @@ pub fn set_app_config(app_config: AppConfig) -> Result<Value, String> {
     }
 }
 
+/// 通过当前代理设置发送 HEAD 请求，返回耗时或具体的连接 / 认证错误
+#[tauri::command]
+pub async fn test_proxy_connection(target_url: Option<String>) -> Result<Value, String> {
+    match http::test_proxy_connection(target_url).await {
+        Ok(result) => Ok(serde_json::json!({
+            "success": result.success,
+            "message": result.error.clone().unwrap_or_else(|| "代理连接测试成功".to_string()),
+            "data": result
+        })),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "message": format!("代理连接测试失败: {}", e),
+            "data": {}
+        })),
+    }
+}
+
 #[tauri::command]
 pub fn open_app_config_folder() -> Result<Value, String> {
     let app_config_manager = AppConfigManager::global();
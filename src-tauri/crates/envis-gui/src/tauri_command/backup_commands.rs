@@ -0,0 +1,44 @@
+use envis_core::manager::backup_manager::BackupManager;
+use envis_core::types::{CommandResponse, ServiceData};
+
+/// 备份数据库服务数据
+#[tauri::command]
+pub async fn backup_service_data(
+    environment_id: String,
+    service_data: ServiceData,
+    retention: Option<usize>,
+) -> Result<CommandResponse, String> {
+    let manager = BackupManager::global();
+    match manager.backup_service_data(&environment_id, &service_data, retention) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("备份失败: {}", e))),
+    }
+}
+
+/// 列出数据库服务的备份
+#[tauri::command]
+pub async fn list_service_backups(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let manager = BackupManager::global();
+    match manager.list_service_backups(&environment_id, &service_data) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("获取备份列表失败: {}", e))),
+    }
+}
+
+/// 从备份恢复数据库服务数据（服务运行中时拒绝）
+#[tauri::command]
+pub async fn restore_service_backup(
+    environment_id: String,
+    service_data: ServiceData,
+    backup_file: String,
+) -> Result<CommandResponse, String> {
+    let manager = BackupManager::global();
+    match manager.restore_service_backup(&environment_id, &service_data, &backup_file) {
+        Ok(res) if res.success => Ok(CommandResponse::success(res.message, res.data)),
+        Ok(res) => Ok(CommandResponse::error(res.message)),
+        Err(e) => Ok(CommandResponse::error(format!("恢复备份失败: {}", e))),
+    }
+}
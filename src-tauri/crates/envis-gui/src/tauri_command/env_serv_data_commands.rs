@@ -2,9 +2,12 @@ use anyhow::Result;
 use serde_json::Value;
 
 use envis_core::manager::env_serv_data_manager::EnvServDataManager;
+use envis_core::manager::environment_manager::EnvironmentManager;
 use envis_core::types::{
-    CreateServiceDataRequest, ServiceData, ServiceDataStatus, UpdateServiceDataRequest,
+    CommandResponse, CreateServiceDataRequest, ServiceData, ServiceDataStatus, ServiceType,
+    UpdateServiceDataRequest,
 };
+use envis_core::utils::run_blocking;
 
 /// 获取指定环境的所有服务数据
 #[tauri::command]
@@ -80,6 +83,24 @@ pub async fn update_service_data(
     }
 }
 
+/// 批量更新服务数据（如拖拽排序），只获取一次锁
+#[tauri::command]
+pub async fn bulk_update_service_data(
+    environment_id: String,
+    updates: Vec<UpdateServiceDataRequest>,
+) -> Result<Value, String> {
+    let manager = EnvServDataManager::global();
+    let manager = manager.lock().unwrap();
+
+    match manager.bulk_update_service_data(&environment_id, updates) {
+        Ok(result) => Ok(serde_json::to_value(result).map_err(|e| e.to_string())?),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "message": e.to_string()
+        })),
+    }
+}
+
 /// 删除服务数据
 #[tauri::command]
 pub async fn delete_service_data(
@@ -98,6 +119,59 @@ pub async fn delete_service_data(
     }
 }
 
+/// 复制服务数据到同一或另一个环境
+#[tauri::command]
+pub async fn duplicate_service_data(
+    src_environment_id: String,
+    src_service_id: String,
+    dest_environment_id: String,
+    new_name: Option<String>,
+) -> Result<Value, String> {
+    let manager = EnvServDataManager::global();
+    let manager = manager.lock().unwrap();
+
+    match manager.duplicate_service_data(
+        &src_environment_id,
+        &src_service_id,
+        &dest_environment_id,
+        new_name,
+    ) {
+        Ok(result) => Ok(serde_json::to_value(result).map_err(|e| e.to_string())?),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "message": e.to_string()
+        })),
+    }
+}
+
+/// 跨所有环境搜索服务数据，按名称 / 版本匹配，可按类型和状态过滤
+#[tauri::command]
+pub async fn search_service_data(
+    query: String,
+    service_type: Option<ServiceType>,
+    status: Option<ServiceDataStatus>,
+) -> Result<CommandResponse, String> {
+    let task = run_blocking(move || {
+        // 先获取环境列表并释放锁，再获取服务数据管理器的锁
+        let environments = EnvironmentManager::global()
+            .lock()
+            .unwrap()
+            .get_all_environments()?;
+        EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .search_service_data(&environments, &query, service_type, status)
+    });
+
+    match task.await {
+        Ok(results) => Ok(CommandResponse::success(
+            "搜索服务数据成功".to_string(),
+            Some(serde_json::json!({ "results": results })),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("搜索服务数据失败: {}", e))),
+    }
+}
+
 /// 激活服务数据
 #[tauri::command]
 pub async fn active_service_data(
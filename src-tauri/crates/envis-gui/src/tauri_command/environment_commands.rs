@@ -1,8 +1,14 @@
 use anyhow::Result;
 use envis_core::manager::env_serv_data_manager::EnvServDataManager;
+use envis_core::manager::environment_hooks::EnvironmentHooks;
 use envis_core::manager::environment_manager::EnvironmentManager;
 use envis_core::manager::export_import;
-use envis_core::types::Environment;
+use envis_core::manager::project_manifest::ProjectManifest;
+use envis_core::manager::scheduler::{ScheduledTaskInput, TaskScheduler};
+use envis_core::manager::service_manager::ServiceManager;
+use envis_core::manager::templates::EnvironmentTemplates;
+use envis_core::types::{CommandResponse, Environment, ServiceData, ServiceDataStatus};
+use envis_core::utils::run_blocking;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::time::Instant;
@@ -71,6 +77,82 @@ pub async fn get_all_environments() -> Result<EnvironmentCommandResult, String>
     }
 }
 
+/// 环境下服务数据的数量统计
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceStatusSummary {
+    pub total: usize,
+    pub active: usize,
+    pub installed: usize,
+}
+
+/// 环境及其服务数据，用于一次性构建侧边栏树
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentServiceTreeNode {
+    pub environment: Environment,
+    pub service_datas: Vec<ServiceData>,
+    pub status_summary: ServiceStatusSummary,
+}
+
+/// 获取所有环境及其服务数据
+#[tauri::command]
+pub async fn get_environment_service_tree() -> Result<CommandResponse, String> {
+    // 先取环境列表并释放锁，避免同时持有两个管理器的锁
+    let environments = match EnvironmentManager::global()
+        .lock()
+        .unwrap()
+        .get_all_environments()
+    {
+        Ok(environments) => environments,
+        Err(e) => return Ok(CommandResponse::error(format!("获取环境列表失败: {}", e))),
+    };
+
+    let service_manager = ServiceManager::global();
+    let tree: Vec<EnvironmentServiceTreeNode> = environments
+        .into_iter()
+        .map(|environment| {
+            let service_datas = EnvServDataManager::global()
+                .lock()
+                .unwrap()
+                .get_environment_all_service_datas(&environment.id)
+                .unwrap_or_else(|e| {
+                    log::warn!("获取环境 {} 的服务数据失败: {}", environment.name, e);
+                    Vec::new()
+                });
+            let status_summary = ServiceStatusSummary {
+                total: service_datas.len(),
+                active: service_datas
+                    .iter()
+                    .filter(|sd| matches!(sd.status, ServiceDataStatus::Active))
+                    .count(),
+                installed: service_datas
+                    .iter()
+                    .filter(|sd| {
+                        service_manager.is_service_installed(&sd.service_type, &sd.version)
+                    })
+                    .count(),
+            };
+            EnvironmentServiceTreeNode {
+                environment,
+                service_datas,
+                status_summary,
+            }
+        })
+        .collect();
+
+    match serde_json::to_value(tree) {
+        Ok(data) => Ok(CommandResponse::success(
+            "获取环境服务树成功".to_string(),
+            Some(data),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "序列化环境服务树失败: {}",
+            e
+        ))),
+    }
+}
+
 /// 创建环境
 #[tauri::command]
 pub async fn create_environment(
@@ -90,6 +172,81 @@ pub async fn create_environment(
     }
 }
 
+/// 列出内置环境模板
+#[tauri::command]
+pub async fn list_environment_templates() -> Result<EnvironmentCommandResult, String> {
+    match EnvironmentTemplates::list() {
+        Ok(templates) => Ok(EnvironmentCommandResult {
+            success: true,
+            message: "获取环境模板成功".to_string(),
+            data: Some(serde_json::json!({ "templates": templates })),
+        }),
+        Err(e) => Ok(EnvironmentCommandResult {
+            success: false,
+            message: e.to_string(),
+            data: None,
+        }),
+    }
+}
+
+/// 按模板创建环境及其服务
+#[tauri::command]
+pub async fn create_environment_from_template(
+    template_id: String,
+    name: String,
+) -> Result<EnvironmentCommandResult, String> {
+    match EnvironmentTemplates::create_environment(&template_id, name) {
+        Ok(result) => Ok(result.into()),
+        Err(e) => Ok(EnvironmentCommandResult {
+            success: false,
+            message: e.to_string(),
+            data: None,
+        }),
+    }
+}
+
+/// 读取并校验项目清单（.envisrc），返回每项是否已安装、已激活环境是否满足
+#[tauri::command]
+pub async fn read_project_manifest(path: String) -> Result<EnvironmentCommandResult, String> {
+    let result =
+        ProjectManifest::load(std::path::Path::new(&path)).and_then(|manifest| manifest.check());
+    match result {
+        Ok(check) => Ok(EnvironmentCommandResult {
+            success: true,
+            message: "读取项目清单成功".to_string(),
+            data: Some(serde_json::json!(check)),
+        }),
+        Err(e) => Ok(EnvironmentCommandResult {
+            success: false,
+            message: e.to_string(),
+            data: None,
+        }),
+    }
+}
+
+/// 按项目清单创建或更新环境中的服务，可选下载缺失的版本
+#[tauri::command]
+pub async fn apply_project_manifest(
+    path: String,
+    environment_id: String,
+    download_missing: Option<bool>,
+) -> Result<EnvironmentCommandResult, String> {
+    let result = ProjectManifest::load(std::path::Path::new(&path))
+        .and_then(|manifest| manifest.apply(&environment_id, download_missing.unwrap_or(false)));
+    match result {
+        Ok(applied) => Ok(EnvironmentCommandResult {
+            success: true,
+            message: "已按项目清单更新环境服务".to_string(),
+            data: Some(serde_json::json!(applied)),
+        }),
+        Err(e) => Ok(EnvironmentCommandResult {
+            success: false,
+            message: e.to_string(),
+            data: None,
+        }),
+    }
+}
+
 /// 保存环境
 #[tauri::command]
 pub async fn save_environment(
@@ -179,6 +336,9 @@ pub async fn activate_environment(
         Ok(result) => {
             // 无论服务是否全部成功，环境本身状态已变更，始终推送事件
             crate::status_events::emit_environment_status(&environment.id, "active");
+            crate::status_events::emit_environment_hook_failures(
+                &EnvironmentHooks::results_from_data(result.data.as_ref()),
+            );
             Ok(result.into())
         }
         Err(e) => Ok(EnvironmentCommandResult {
@@ -220,6 +380,9 @@ pub async fn activate_environment_and_services(
                     }
                 }
             }
+            crate::status_events::emit_environment_hook_failures(
+                &EnvironmentHooks::results_from_data(result.data.as_ref()),
+            );
             Ok(result.into())
         }
         Err(e) => Ok(EnvironmentCommandResult {
@@ -242,6 +405,9 @@ pub async fn deactivate_environment(
         Ok(result) => {
             // 无论服务是否全部成功，环境本身状态已变更，始终推送事件
             crate::status_events::emit_environment_status(&environment.id, "inactive");
+            crate::status_events::emit_environment_hook_failures(
+                &EnvironmentHooks::results_from_data(result.data.as_ref()),
+            );
             Ok(result.into())
         }
         Err(e) => Ok(EnvironmentCommandResult {
@@ -283,6 +449,9 @@ pub async fn deactivate_environment_and_services(
                     }
                 }
             }
+            crate::status_events::emit_environment_hook_failures(
+                &EnvironmentHooks::results_from_data(result.data.as_ref()),
+            );
             Ok(result.into())
         }
         Err(e) => Ok(EnvironmentCommandResult {
@@ -349,10 +518,16 @@ pub async fn switch_environment_and_services(
                 }
             }
 
+            crate::status_events::emit_environment_hook_failures(&res.hook_results);
+
             Ok(EnvironmentCommandResult {
                 success: res.success,
                 message: res.message,
-                data: None,
+                data: if res.hook_results.is_empty() {
+                    None
+                } else {
+                    Some(serde_json::json!({ "hookResults": res.hook_results }))
+                },
             })
         }
         Err(e) => Ok(EnvironmentCommandResult {
@@ -405,3 +580,226 @@ pub async fn import_environment_data(
         }),
     }
 }
+
+/// 获取环境自定义的环境变量
+#[tauri::command]
+pub async fn get_environment_env_vars(
+    environment_id: String,
+) -> Result<EnvironmentCommandResult, String> {
+    let manager = EnvironmentManager::global();
+    let manager = manager.lock().unwrap();
+
+    match manager.get_environment_env_vars(&environment_id) {
+        Ok(env_vars) => Ok(EnvironmentCommandResult {
+            success: true,
+            message: "获取环境变量成功".to_string(),
+            data: Some(serde_json::json!({ "envVars": env_vars })),
+        }),
+        Err(e) => Ok(EnvironmentCommandResult {
+            success: false,
+            message: e.to_string(),
+            data: None,
+        }),
+    }
+}
+
+/// 设置环境变量（环境已激活时立即写入终端配置）
+#[tauri::command]
+pub async fn set_environment_env_var(
+    environment_id: String,
+    key: String,
+    value: String,
+) -> Result<EnvironmentCommandResult, String> {
+    let manager = EnvironmentManager::global();
+    let manager = manager.lock().unwrap();
+
+    match manager.set_environment_env_var(&environment_id, &key, &value) {
+        Ok(result) => Ok(result.into()),
+        Err(e) => Ok(EnvironmentCommandResult {
+            success: false,
+            message: e.to_string(),
+            data: None,
+        }),
+    }
+}
+
+/// 删除环境变量
+#[tauri::command]
+pub async fn delete_environment_env_var(
+    environment_id: String,
+    key: String,
+) -> Result<EnvironmentCommandResult, String> {
+    let manager = EnvironmentManager::global();
+    let manager = manager.lock().unwrap();
+
+    match manager.delete_environment_env_var(&environment_id, &key) {
+        Ok(result) => Ok(result.into()),
+        Err(e) => Ok(EnvironmentCommandResult {
+            success: false,
+            message: e.to_string(),
+            data: None,
+        }),
+    }
+}
+
+/// 读取环境 metadata 中的单个键
+#[tauri::command]
+pub async fn get_environment_metadata(
+    environment_id: String,
+    key: String,
+) -> Result<EnvironmentCommandResult, String> {
+    let manager = EnvironmentManager::global();
+    let manager = manager.lock().unwrap();
+
+    match manager.get_environment_metadata(&environment_id, &key) {
+        Ok(value) => Ok(EnvironmentCommandResult {
+            success: true,
+            message: "获取环境 metadata 成功".to_string(),
+            data: Some(serde_json::json!({ "key": key, "value": value })),
+        }),
+        Err(e) => Ok(EnvironmentCommandResult {
+            success: false,
+            message: e.to_string(),
+            data: None,
+        }),
+    }
+}
+
+/// 设置环境 metadata 中的单个键（value 为 null 时删除），用于保存前端的环境级设置
+#[tauri::command]
+pub async fn set_environment_metadata(
+    environment_id: String,
+    key: String,
+    value: Value,
+) -> Result<EnvironmentCommandResult, String> {
+    let manager = EnvironmentManager::global();
+    let manager = manager.lock().unwrap();
+
+    match manager.set_environment_metadata(&environment_id, &key, value) {
+        Ok(result) => Ok(result.into()),
+        Err(e) => Ok(EnvironmentCommandResult {
+            success: false,
+            message: e.to_string(),
+            data: None,
+        }),
+    }
+}
+
+/// 获取环境的激活 / 停用钩子
+#[tauri::command]
+pub async fn get_environment_hooks(
+    environment_id: String,
+) -> Result<EnvironmentCommandResult, String> {
+    let manager = EnvironmentManager::global();
+    let manager = manager.lock().unwrap();
+
+    match manager.get_environment_hooks(&environment_id) {
+        Ok(result) => Ok(result.into()),
+        Err(e) => Ok(EnvironmentCommandResult {
+            success: false,
+            message: e.to_string(),
+            data: None,
+        }),
+    }
+}
+
+/// 设置环境的激活 / 停用钩子
+#[tauri::command]
+pub async fn set_environment_hooks(
+    environment_id: String,
+    hooks: EnvironmentHooks,
+) -> Result<EnvironmentCommandResult, String> {
+    let manager = EnvironmentManager::global();
+    let manager = manager.lock().unwrap();
+
+    match manager.set_environment_hooks(&environment_id, hooks) {
+        Ok(result) => Ok(result.into()),
+        Err(e) => Ok(EnvironmentCommandResult {
+            success: false,
+            message: e.to_string(),
+            data: None,
+        }),
+    }
+}
+
+/// 列出环境的计划任务
+#[tauri::command]
+pub async fn list_scheduled_tasks(
+    environment_id: String,
+) -> Result<EnvironmentCommandResult, String> {
+    match TaskScheduler::global().list_tasks(&environment_id) {
+        Ok(result) => Ok(result.into()),
+        Err(e) => Ok(EnvironmentCommandResult {
+            success: false,
+            message: e.to_string(),
+            data: None,
+        }),
+    }
+}
+
+/// 新增环境计划任务
+#[tauri::command]
+pub async fn add_scheduled_task(
+    environment_id: String,
+    task: ScheduledTaskInput,
+) -> Result<EnvironmentCommandResult, String> {
+    match TaskScheduler::global().add_task(&environment_id, task) {
+        Ok(result) => Ok(result.into()),
+        Err(e) => Ok(EnvironmentCommandResult {
+            success: false,
+            message: e.to_string(),
+            data: None,
+        }),
+    }
+}
+
+/// 修改环境计划任务
+#[tauri::command]
+pub async fn update_scheduled_task(
+    environment_id: String,
+    task_id: String,
+    task: ScheduledTaskInput,
+) -> Result<EnvironmentCommandResult, String> {
+    match TaskScheduler::global().update_task(&environment_id, &task_id, task) {
+        Ok(result) => Ok(result.into()),
+        Err(e) => Ok(EnvironmentCommandResult {
+            success: false,
+            message: e.to_string(),
+            data: None,
+        }),
+    }
+}
+
+/// 删除环境计划任务
+#[tauri::command]
+pub async fn delete_scheduled_task(
+    environment_id: String,
+    task_id: String,
+) -> Result<EnvironmentCommandResult, String> {
+    match TaskScheduler::global().delete_task(&environment_id, &task_id) {
+        Ok(result) => Ok(result.into()),
+        Err(e) => Ok(EnvironmentCommandResult {
+            success: false,
+            message: e.to_string(),
+            data: None,
+        }),
+    }
+}
+
+/// 立即执行一次计划任务，等待执行结束后返回执行记录
+#[tauri::command]
+pub async fn run_scheduled_task_now(
+    environment_id: String,
+    task_id: String,
+) -> Result<EnvironmentCommandResult, String> {
+    let task =
+        run_blocking(move || TaskScheduler::global().run_task_now(&environment_id, &task_id));
+    match task.await {
+        Ok(result) => Ok(result.into()),
+        Err(e) => Ok(EnvironmentCommandResult {
+            success: false,
+            message: e.to_string(),
+            data: None,
+        }),
+    }
+}
@@ -1,4 +1,5 @@
 pub mod app_config_commands;
+pub mod backup_commands;
 pub mod env_serv_data_commands;
 pub mod environment_commands;
 pub mod file_commands;
@@ -1,9 +1,13 @@
 use anyhow::Result;
 use serde_json::Value;
 
-use envis_core::manager::service_manager::ServiceManager;
+use envis_core::manager::env_serv_data_manager::EnvServDataManager;
+use envis_core::manager::service_manager::{ServiceManager, ServicesFolderEntryKind};
+use envis_core::manager::service_supervisor::ServiceSupervisor;
+use envis_core::manager::services::DownloadManager;
 use envis_core::manager::system_info_manager::SystemInfoManager;
-use envis_core::types::ServiceType;
+use envis_core::types::{CommandResponse, ServiceData, ServiceType};
+use envis_core::utils::run_blocking;
 
 /// 获取已安装的所有服务列表
 #[tauri::command]
@@ -35,10 +39,47 @@ pub async fn get_service_size(service_type: ServiceType, version: String) -> Res
 
 /// 删除已安装的服务
 #[tauri::command]
-pub async fn delete_service(service_type: ServiceType, version: String) -> Result<Value, String> {
-    let manager = ServiceManager::global();
+pub async fn delete_service(
+    service_type: ServiceType,
+    version: String,
+    force: Option<bool>,
+    stop_running: Option<bool>,
+) -> Result<Value, String> {
+    let force = force.unwrap_or(false);
+    let stop_running = stop_running.unwrap_or(false);
+    let task = run_blocking(move || {
+        ServiceManager::global().delete_service(&service_type, &version, force, stop_running)
+    });
+
+    match task.await {
+        Ok(result) => Ok(serde_json::to_value(result).map_err(|e| e.to_string())?),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "message": e.to_string()
+        })),
+    }
+}
+
+/// 扫描服务文件夹，区分有效安装、孤立目录、残留压缩包和临时目录
+#[tauri::command]
+pub async fn scan_services_folder() -> Result<CommandResponse, String> {
+    let task = run_blocking(move || ServiceManager::global().scan_services_folder());
+
+    match task.await {
+        Ok(entries) => Ok(CommandResponse::success(
+            "扫描服务文件夹成功".to_string(),
+            Some(serde_json::json!({ "entries": entries })),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("扫描服务文件夹失败: {}", e))),
+    }
+}
+
+/// 清理服务文件夹中指定分类的条目
+#[tauri::command]
+pub async fn cleanup_services_folder(kinds: Vec<ServicesFolderEntryKind>) -> Result<Value, String> {
+    let task = run_blocking(move || ServiceManager::global().cleanup_services_folder(&kinds));
 
-    match manager.delete_service(&service_type, &version) {
+    match task.await {
         Ok(result) => Ok(serde_json::to_value(result).map_err(|e| e.to_string())?),
         Err(e) => Ok(serde_json::json!({
             "success": false,
@@ -47,6 +88,90 @@ pub async fn delete_service(service_type: ServiceType, version: String) -> Resul
     }
 }
 
+/// 检查环境中已激活服务是否有可用更新（后台执行，最多等待 30 秒）
+#[tauri::command]
+pub async fn check_for_service_updates(environment_id: String) -> Result<Value, String> {
+    let task =
+        run_blocking(move || ServiceManager::global().check_for_service_updates(&environment_id));
+
+    match tokio::time::timeout(std::time::Duration::from_secs(30), task).await {
+        Ok(Ok(result)) => Ok(serde_json::to_value(result).map_err(|e| e.to_string())?),
+        Ok(Err(e)) => Ok(serde_json::json!({
+            "success": false,
+            "message": e.to_string()
+        })),
+        Err(_) => Ok(serde_json::json!({
+            "success": false,
+            "message": "检查服务更新超时"
+        })),
+    }
+}
+
+/// 将服务数据升级到目标版本，数据库跨大版本升级需要 allow_major
+#[tauri::command]
+pub async fn upgrade_service_data(
+    environment_id: String,
+    service_data_id: String,
+    target_version: String,
+    allow_major: Option<bool>,
+) -> Result<Value, String> {
+    let result = ServiceManager::global()
+        .upgrade_service_data(
+            &environment_id,
+            &service_data_id,
+            &target_version,
+            allow_major.unwrap_or(false),
+        )
+        .await;
+
+    match result {
+        Ok(result) => Ok(serde_json::to_value(result).map_err(|e| e.to_string())?),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "message": e.to_string()
+        })),
+    }
+}
+
+/// 获取环境中的服务数据，附带是否已安装和运行状态
+#[tauri::command]
+pub async fn list_installed_services_for_environment(
+    environment_id: String,
+) -> Result<CommandResponse, String> {
+    let task = run_blocking(move || {
+        ServiceManager::global().list_installed_services_for_environment(&environment_id)
+    });
+
+    match task.await {
+        Ok(services) => Ok(CommandResponse::success(
+            "获取环境服务列表成功".to_string(),
+            Some(serde_json::json!({ "services": services })),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("获取环境服务列表失败: {}", e))),
+    }
+}
+
+/// 检查环境中服务数据的完整性（未安装的版本、不存在的路径、无法解析的配置文件）
+#[tauri::command]
+pub async fn validate_service_data_integrity(
+    environment_id: String,
+) -> Result<CommandResponse, String> {
+    let task = run_blocking(move || {
+        EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .validate_service_data_integrity(&environment_id)
+    });
+
+    match task.await {
+        Ok(results) => Ok(CommandResponse::success(
+            "服务数据完整性检查完成".to_string(),
+            Some(serde_json::json!({ "results": results })),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("服务数据完整性检查失败: {}", e))),
+    }
+}
+
 /// 按服务类型列表查询进程资源统计（CPU + 内存）
 #[tauri::command]
 pub async fn get_services_process_stats(service_types: Vec<ServiceType>) -> Result<Value, String> {
@@ -59,6 +184,9 @@ pub async fn get_services_process_stats(service_types: Vec<ServiceType>) -> Resu
             ServiceType::Mysql => &["mysqld"],
             ServiceType::Postgresql => &["postgres"],
             ServiceType::Dnsmasq => &["dnsmasq"],
+            ServiceType::Php => &["php-fpm", "php-cgi"],
+            ServiceType::Vault => &["vault"],
+            // OpenSearch、Kafka 以 java 进程运行，RabbitMQ 以 Erlang 虚拟机运行，无法与其他应用区分
             _ => &[],
         }
     }
@@ -83,3 +211,187 @@ pub async fn get_services_process_stats(service_types: Vec<ServiceType>) -> Resu
         })),
     }
 }
+
+/// 获取单个服务主进程的 CPU 与内存占用
+#[tauri::command]
+pub async fn get_service_resource_usage(
+    environment_id: String,
+    service_data_id: String,
+) -> Result<CommandResponse, String> {
+    let task = run_blocking(move || {
+        let service_data = EnvServDataManager::global()
+            .lock()
+            .unwrap()
+            .get_service_data(&environment_id, &service_data_id)?;
+        ServiceManager::global().get_service_resource_usage(&environment_id, &service_data)
+    });
+
+    match task.await {
+        Ok(usage) => Ok(CommandResponse::success(
+            "获取服务资源占用成功".to_string(),
+            Some(serde_json::json!(usage)),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("获取服务资源占用失败: {}", e))),
+    }
+}
+
+/// 一次获取环境中所有服务的运行状态，供环境仪表盘使用
+#[tauri::command]
+pub async fn get_environment_services_status(
+    environment_id: String,
+) -> Result<CommandResponse, String> {
+    match ServiceManager::global()
+        .get_environment_services_status(&environment_id)
+        .await
+    {
+        Ok(statuses) => Ok(CommandResponse::success(
+            "获取环境服务状态成功".to_string(),
+            Some(serde_json::json!(statuses)),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("获取环境服务状态失败: {}", e))),
+    }
+}
+
+/// 开启或关闭服务崩溃后的自动重启
+#[tauri::command]
+pub async fn set_service_auto_restart(
+    environment_id: String,
+    service_data: ServiceData,
+    enabled: bool,
+) -> Result<Value, String> {
+    let supervisor = ServiceSupervisor::global();
+
+    match supervisor.set_auto_restart(&environment_id, &service_data, enabled) {
+        Ok(result) => Ok(serde_json::to_value(result).map_err(|e| e.to_string())?),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "message": e.to_string()
+        })),
+    }
+}
+
+/// 获取服务自动重启监控状态
+#[tauri::command]
+pub async fn get_supervision_status() -> Result<Value, String> {
+    let status = ServiceSupervisor::global().get_status();
+    Ok(serde_json::json!({
+        "success": true,
+        "data": status
+    }))
+}
+
+/// 暂停服务下载（保留已下载部分）
+#[tauri::command]
+pub async fn pause_service_download(
+    service_type: ServiceType,
+    version: String,
+) -> Result<Value, String> {
+    let task_id = format!("{}-{}", service_type.dir_name(), version);
+
+    match DownloadManager::global().pause_download(&task_id) {
+        Ok(_) => Ok(serde_json::json!({
+            "success": true,
+            "message": "下载已暂停"
+        })),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "message": e.to_string()
+        })),
+    }
+}
+
+/// 恢复已暂停的服务下载
+#[tauri::command]
+pub async fn resume_service_download(
+    service_type: ServiceType,
+    version: String,
+) -> Result<Value, String> {
+    let task_id = format!("{}-{}", service_type.dir_name(), version);
+
+    match DownloadManager::global().resume_download(&task_id) {
+        Ok(_) => Ok(serde_json::json!({
+            "success": true,
+            "message": "下载已恢复"
+        })),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "message": e.to_string()
+        })),
+    }
+}
+
+/// 获取已安装或失败的下载历史（应用重启后仍保留）
+#[tauri::command]
+pub async fn get_download_history() -> Result<Value, String> {
+    let history = DownloadManager::global().get_all_download_history();
+    Ok(serde_json::json!({
+        "success": true,
+        "data": { "history": history }
+    }))
+}
+
+/// 清空下载历史
+#[tauri::command]
+pub async fn clear_download_history() -> Result<Value, String> {
+    match DownloadManager::global().clear_download_history() {
+        Ok(_) => Ok(serde_json::json!({
+            "success": true,
+            "message": "下载历史已清空"
+        })),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "message": e.to_string()
+        })),
+    }
+}
+
+/// 获取同时进行的最大下载任务数
+#[tauri::command]
+pub async fn get_concurrent_download_limit() -> Result<Value, String> {
+    let limit = DownloadManager::global().get_concurrent_download_limit();
+    Ok(serde_json::json!({
+        "success": true,
+        "data": { "limit": limit }
+    }))
+}
+
+/// 设置同时进行的最大下载任务数，超出的下载排队等待
+#[tauri::command]
+pub async fn set_concurrent_download_limit(limit: u32) -> Result<Value, String> {
+    match DownloadManager::global().set_concurrent_download_limit(limit) {
+        Ok(_) => Ok(serde_json::json!({
+            "success": true,
+            "message": "并发下载数已更新",
+            "data": { "limit": limit }
+        })),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "message": e.to_string()
+        })),
+    }
+}
+
+/// 从本地压缩包离线安装服务版本，可选校验 SHA-256
+#[tauri::command]
+pub async fn install_service_from_file(
+    service_type: ServiceType,
+    version: String,
+    archive_path: String,
+    sha256: Option<String>,
+) -> Result<CommandResponse, String> {
+    let result = ServiceManager::global()
+        .install_service_from_file(&service_type, &version, &archive_path, sha256.as_deref())
+        .await;
+
+    match result {
+        Ok(result) => {
+            let data = serde_json::json!({ "task": result.task });
+            if result.success {
+                Ok(CommandResponse::success(result.message, Some(data)))
+            } else {
+                Ok(CommandResponse::error(result.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("离线安装失败: {}", e))),
+    }
+}
@@ -0,0 +1,121 @@
+use envis_core::manager::services::compose::ComposeService;
+use envis_core::types::{CommandResponse, ServiceData};
+
+#[tauri::command]
+pub async fn detect_container_runtime() -> Result<CommandResponse, String> {
+    let service = ComposeService::global();
+    match service.detect_runtime() {
+        Ok(runtime) => Ok(CommandResponse::success(
+            "检测容器运行时成功".to_string(),
+            Some(serde_json::json!({ "runtime": runtime })),
+        )),
+        Err(e) => Ok(CommandResponse::error(e.to_string())),
+    }
+}
+
+#[tauri::command]
+pub async fn set_compose_file(
+    environment_id: String,
+    mut service_data: ServiceData,
+    compose_file: String,
+    project_name: Option<String>,
+) -> Result<CommandResponse, String> {
+    let service = ComposeService::global();
+    match service.set_compose_file(
+        &environment_id,
+        &mut service_data,
+        &compose_file,
+        project_name.as_deref(),
+    ) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "设置 compose 文件失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn start_compose_service(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = ComposeService::global();
+    match service.start_service(&environment_id, &service_data) {
+        Ok(res) => {
+            if res.success {
+                crate::status_events::emit_service_status(
+                    &environment_id,
+                    &service_data.id,
+                    "running",
+                );
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("启动 Compose 失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn stop_compose_service(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = ComposeService::global();
+    match service.stop_service(&environment_id, &service_data) {
+        Ok(res) => {
+            if res.success {
+                crate::status_events::emit_service_status(
+                    &environment_id,
+                    &service_data.id,
+                    "stopped",
+                );
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("停止 Compose 失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn restart_compose_service(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = ComposeService::global();
+    match service.restart_service(&environment_id, &service_data) {
+        Ok(res) => {
+            if res.success {
+                crate::status_events::emit_service_status(
+                    &environment_id,
+                    &service_data.id,
+                    "running",
+                );
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("重启 Compose 失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn get_compose_service_status(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = ComposeService::global();
+    match service.get_service_status(&environment_id, &service_data) {
+        Ok(res) if res.success => Ok(CommandResponse::success(res.message, res.data)),
+        Ok(res) => Ok(CommandResponse::error(res.message)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "获取 Compose 状态失败: {}",
+            e
+        ))),
+    }
+}
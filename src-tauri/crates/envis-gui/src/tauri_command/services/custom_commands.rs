@@ -1,5 +1,9 @@
+use envis_core::manager::services::{CommandOutputCallback, CustomCommand, CustomService};
 use envis_core::manager::shell_manamger::ShellManager;
 use envis_core::types::{CommandResponse, ServiceData};
+use envis_core::utils::run_blocking;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// 更新自定义服务的路径配置
 #[tauri::command]
@@ -175,6 +179,221 @@ pub async fn execute_custom_service_alias(
     }
 }
 
+/// 获取自定义服务的命令列表
+#[tauri::command]
+pub async fn list_custom_service_commands(
+    _environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = CustomService::global();
+    match service.list_commands(&service_data) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("获取自定义命令列表失败: {}", e))),
+    }
+}
+
+/// 新增自定义服务命令
+#[tauri::command]
+pub async fn add_custom_service_command(
+    environment_id: String,
+    mut service_data: ServiceData,
+    command: CustomCommand,
+) -> Result<CommandResponse, String> {
+    let service = CustomService::global();
+    match service.add_command(&environment_id, &mut service_data, command) {
+        Ok(res) if res.success => Ok(CommandResponse::success(res.message, res.data)),
+        Ok(res) => Ok(CommandResponse::error(res.message)),
+        Err(e) => Ok(CommandResponse::error(format!("新增自定义命令失败: {}", e))),
+    }
+}
+
+/// 更新自定义服务命令
+#[tauri::command]
+pub async fn update_custom_service_command(
+    environment_id: String,
+    mut service_data: ServiceData,
+    name: String,
+    command: CustomCommand,
+) -> Result<CommandResponse, String> {
+    let service = CustomService::global();
+    match service.update_command(&environment_id, &mut service_data, &name, command) {
+        Ok(res) if res.success => Ok(CommandResponse::success(res.message, res.data)),
+        Ok(res) => Ok(CommandResponse::error(res.message)),
+        Err(e) => Ok(CommandResponse::error(format!("更新自定义命令失败: {}", e))),
+    }
+}
+
+/// 删除自定义服务命令
+#[tauri::command]
+pub async fn remove_custom_service_command(
+    environment_id: String,
+    mut service_data: ServiceData,
+    name: String,
+) -> Result<CommandResponse, String> {
+    let service = CustomService::global();
+    match service.remove_command(&environment_id, &mut service_data, &name) {
+        Ok(res) if res.success => Ok(CommandResponse::success(res.message, res.data)),
+        Ok(res) => Ok(CommandResponse::error(res.message)),
+        Err(e) => Ok(CommandResponse::error(format!("删除自定义命令失败: {}", e))),
+    }
+}
+
+/// 执行自定义服务命令
+///
+/// - `timeout_secs`：超时秒数，到期后结束进程树；为空表示不限制
+/// - `stream`：为 true 时立即返回 runId，输出通过 `custom-command:output` 事件逐行推送，
+///   结束时推送 `custom-command:exit`
+#[tauri::command]
+pub async fn execute_custom_service_command(
+    _environment_id: String,
+    service_data: ServiceData,
+    name: String,
+    timeout_secs: Option<u64>,
+    stream: Option<bool>,
+) -> Result<CommandResponse, String> {
+    let run_id = CustomService::generate_run_id();
+    let timeout = timeout_secs.filter(|s| *s > 0).map(Duration::from_secs);
+
+    if stream.unwrap_or(false) {
+        let event_run_id = run_id.clone();
+        let on_output: CommandOutputCallback = Arc::new(move |stream: &str, line: &str| {
+            crate::status_events::emit_custom_command_output(&event_run_id, stream, line);
+        });
+        let task_run_id = run_id.clone();
+        tokio::task::spawn_blocking(move || {
+            let result = match CustomService::global().execute_command(
+                &task_run_id,
+                &service_data,
+                &name,
+                timeout,
+                Some(on_output),
+            ) {
+                Ok(output) => serde_json::json!({
+                    "exitCode": output.exit_code,
+                    "timedOut": output.timed_out,
+                    "durationMs": output.duration_ms,
+                }),
+                Err(e) => {
+                    log::error!("执行自定义命令 {} 失败: {}", name, e);
+                    serde_json::json!({ "error": e.to_string() })
+                }
+            };
+            crate::status_events::emit_custom_command_exit(&task_run_id, result);
+        });
+        return Ok(CommandResponse::success(
+            "自定义命令已开始执行".to_string(),
+            Some(serde_json::json!({ "runId": run_id })),
+        ));
+    }
+
+    let task = run_blocking(move || {
+        CustomService::global().execute_command(&run_id, &service_data, &name, timeout, None)
+    });
+    match task.await {
+        Ok(output) => {
+            let data = serde_json::to_value(&output).ok();
+            if output.timed_out {
+                Ok(CommandResponse {
+                    success: false,
+                    message: "命令执行超时，已结束进程".to_string(),
+                    data,
+                })
+            } else if output.exit_code == 0 {
+                Ok(CommandResponse::success("命令执行成功".to_string(), data))
+            } else {
+                Ok(CommandResponse {
+                    success: false,
+                    message: format!("命令执行失败: 退出码 {}", output.exit_code),
+                    data,
+                })
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("执行自定义命令失败: {}", e))),
+    }
+}
+
+/// 停止以流式方式执行中的自定义命令
+#[tauri::command]
+pub async fn stop_custom_service_command(run_id: String) -> Result<CommandResponse, String> {
+    match CustomService::global().stop_command(&run_id) {
+        Ok(res) if res.success => Ok(CommandResponse::success(res.message, res.data)),
+        Ok(res) => Ok(CommandResponse::error(res.message)),
+        Err(e) => Ok(CommandResponse::error(format!("停止自定义命令失败: {}", e))),
+    }
+}
+
+/// 获取自定义服务中已注册的工具目录
+#[tauri::command]
+pub async fn list_custom_binaries(
+    _environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    match CustomService::get_binaries(&service_data) {
+        Ok(binaries) => Ok(CommandResponse::success(
+            "获取已注册工具目录成功".to_string(),
+            Some(serde_json::json!({ "binaries": binaries })),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("获取已注册工具目录失败: {}", e))),
+    }
+}
+
+/// 将本地工具目录注册为自定义服务的一个版本
+#[tauri::command]
+pub async fn register_custom_binary(
+    environment_id: String,
+    mut service_data: ServiceData,
+    name: String,
+    version: String,
+    folder: String,
+    bin_dir: Option<String>,
+) -> Result<CommandResponse, String> {
+    let service = CustomService::global();
+    match service.register_binary(
+        &environment_id,
+        &mut service_data,
+        &name,
+        &version,
+        &folder,
+        bin_dir,
+    ) {
+        Ok(res) if res.success => Ok(CommandResponse::success(res.message, res.data)),
+        Ok(res) => Ok(CommandResponse::error(res.message)),
+        Err(e) => Ok(CommandResponse::error(format!("注册工具目录失败: {}", e))),
+    }
+}
+
+/// 取消注册本地工具目录，并从 PATH 中移除
+#[tauri::command]
+pub async fn unregister_custom_binary(
+    environment_id: String,
+    mut service_data: ServiceData,
+    name: String,
+    version: String,
+) -> Result<CommandResponse, String> {
+    let service = CustomService::global();
+    match service.unregister_binary(&environment_id, &mut service_data, &name, &version) {
+        Ok(res) if res.success => Ok(CommandResponse::success(res.message, res.data)),
+        Ok(res) => Ok(CommandResponse::error(res.message)),
+        Err(e) => Ok(CommandResponse::error(format!("取消注册工具目录失败: {}", e))),
+    }
+}
+
+/// 切换同名工具的选中版本
+#[tauri::command]
+pub async fn switch_custom_binary(
+    environment_id: String,
+    mut service_data: ServiceData,
+    name: String,
+    version: String,
+) -> Result<CommandResponse, String> {
+    let service = CustomService::global();
+    match service.switch_binary(&environment_id, &mut service_data, &name, &version) {
+        Ok(res) if res.success => Ok(CommandResponse::success(res.message, res.data)),
+        Ok(res) => Ok(CommandResponse::error(res.message)),
+        Err(e) => Ok(CommandResponse::error(format!("切换工具版本失败: {}", e))),
+    }
+}
+
 /// 用 VSCode 打开项目目录
 #[tauri::command]
 pub async fn open_project_in_vscode(
@@ -1,5 +1,7 @@
+use crate::tauri_command::system_info_commands::with_dependency_warning;
 use envis_core::manager::services::dnsmasq::DnsmasqService;
-use envis_core::types::{CommandResponse, ServiceData};
+use envis_core::types::{CommandResponse, ServiceData, ServiceType};
+use envis_core::utils::run_blocking;
 
 /// 按版本检查 Dnsmasq 是否已安装
 #[tauri::command]
@@ -51,6 +53,55 @@ pub async fn get_dnsmasq_config(
     }
 }
 
+/// 获取 Dnsmasq 上游 DNS 服务器
+#[tauri::command]
+pub async fn get_dnsmasq_upstream_dns(
+    _environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let dnsmasq_service = DnsmasqService::global();
+    match dnsmasq_service.get_upstream_dns(&service_data) {
+        Ok(servers) => Ok(CommandResponse::success(
+            "获取上游 DNS 成功".to_string(),
+            Some(serde_json::json!({ "servers": servers })),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("获取上游 DNS 失败: {}", e))),
+    }
+}
+
+/// 设置 Dnsmasq 上游 DNS 服务器
+#[tauri::command]
+pub async fn set_dnsmasq_upstream_dns(
+    environment_id: String,
+    service_data: ServiceData,
+    servers: Vec<String>,
+) -> Result<CommandResponse, String> {
+    let task = run_blocking(move || {
+        DnsmasqService::global().set_upstream_dns(&environment_id, &service_data, &servers)
+    });
+    match task.await {
+        Ok(result) if result.success => Ok(CommandResponse::success(result.message, result.data)),
+        Ok(result) => Ok(CommandResponse::error(result.message)),
+        Err(e) => Ok(CommandResponse::error(format!("设置上游 DNS 失败: {}", e))),
+    }
+}
+
+/// 重置 Dnsmasq 上游 DNS 服务器，恢复使用系统 resolv.conf
+#[tauri::command]
+pub async fn reset_dnsmasq_upstream_dns(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let task = run_blocking(move || {
+        DnsmasqService::global().reset_upstream_dns(&environment_id, &service_data)
+    });
+    match task.await {
+        Ok(result) if result.success => Ok(CommandResponse::success(result.message, result.data)),
+        Ok(result) => Ok(CommandResponse::error(result.message)),
+        Err(e) => Ok(CommandResponse::error(format!("重置上游 DNS 失败: {}", e))),
+    }
+}
+
 /// 启动 Dnsmasq 服务
 #[tauri::command]
 pub async fn start_dnsmasq_service(
@@ -59,10 +110,11 @@ pub async fn start_dnsmasq_service(
 ) -> Result<CommandResponse, String> {
     let dnsmasq_service = DnsmasqService::global();
     match dnsmasq_service.start_service(&service_data) {
-        Ok(_) => {
+        Ok(result) if result.success => {
             crate::status_events::emit_service_status(&environment_id, &service_data.id, "running");
-            Ok(CommandResponse::success("Dnsmasq 服务启动成功".to_string(), None))
+            Ok(CommandResponse::success("Dnsmasq 服务启动成功".to_string(), result.data))
         }
+        Ok(result) => Ok(CommandResponse::error(result.message)),
         Err(e) => Ok(CommandResponse::error(format!(
             "启动 Dnsmasq 服务失败: {}",
             e
@@ -149,7 +201,7 @@ pub async fn get_dnsmasq_versions() -> Result<CommandResponse, String> {
 pub async fn download_dnsmasq(version: String) -> Result<CommandResponse, String> {
     log::info!("tauri::command 开始下载 Dnsmasq {}...", version);
     let dnsmasq_service = DnsmasqService::global();
-    match dnsmasq_service.download_and_install(&version).await {
+    let response = match dnsmasq_service.download_and_install(&version).await {
         Ok(result) => {
             let data = serde_json::json!(result);
             Ok(CommandResponse::success(
@@ -158,7 +210,8 @@ pub async fn download_dnsmasq(version: String) -> Result<CommandResponse, String
             ))
         }
         Err(e) => Ok(CommandResponse::error(format!("下载 Dnsmasq 失败: {}", e))),
-    }
+    };
+    response.map(|r| with_dependency_warning(&ServiceType::Dnsmasq, r))
 }
 
 /// 取消 Dnsmasq 下载的 Tauri 命令
@@ -145,6 +145,53 @@ pub async fn clear_hosts(password: String) -> Result<CommandResponse, String> {
     }
 }
 
+/// 从 /etc/hosts 格式的文件批量导入 host 条目
+#[tauri::command]
+pub async fn bulk_import_hosts(
+    file_path: String,
+    overwrite: bool,
+    password: String,
+) -> Result<CommandResponse, String> {
+    let host_manager = HostManager::global();
+    let manager = host_manager.lock().map_err(|e| e.to_string())?;
+
+    match manager.bulk_import_hosts(&file_path, overwrite, &password) {
+        Ok(result) => Ok(CommandResponse::success(
+            format!(
+                "导入 hosts 完成: 导入 {} 条，跳过 {} 条",
+                result.imported, result.skipped
+            ),
+            Some(serde_json::to_value(result).map_err(|e| e.to_string())?),
+        )),
+        Err(e) => {
+            let error_msg = e.to_string();
+            if error_msg.contains("密码错误") {
+                Ok(CommandResponse::error("密码错误，请重新输入".to_string()))
+            } else {
+                Ok(CommandResponse::error(format!(
+                    "导入 hosts 失败: {}",
+                    error_msg
+                )))
+            }
+        }
+    }
+}
+
+/// 将启用的 host 条目以 /etc/hosts 格式导出到文件
+#[tauri::command]
+pub async fn export_hosts_as_etc_format(dest_path: String) -> Result<CommandResponse, String> {
+    let host_manager = HostManager::global();
+    let manager = host_manager.lock().map_err(|e| e.to_string())?;
+
+    match manager.export_hosts_as_etc_format(&dest_path) {
+        Ok(count) => Ok(CommandResponse::success(
+            format!("已导出 {} 条 host", count),
+            Some(serde_json::json!({ "count": count, "path": dest_path })),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("导出 hosts 失败: {}", e))),
+    }
+}
+
 /// 打开 hosts 文件所在文件夹
 #[tauri::command]
 pub async fn open_hosts_file() -> Result<CommandResponse, String> {
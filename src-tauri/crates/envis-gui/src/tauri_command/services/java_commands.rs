@@ -1,6 +1,7 @@
+use crate::tauri_command::system_info_commands::with_dependency_warning;
 use envis_core::manager::env_serv_data_manager::EnvServDataManager;
-use envis_core::manager::services::java::JavaService;
-use envis_core::types::{CommandResponse, ServiceData};
+use envis_core::manager::services::java::{JavaService, MavenService};
+use envis_core::types::{CommandResponse, ServiceData, ServiceDataStatus, ServiceType};
 
 /// 检查 Java 是否已安装的 Tauri 命令
 #[tauri::command]
@@ -32,10 +33,44 @@ pub async fn check_maven_installed(version: String) -> Result<CommandResponse, S
     let data = serde_json::json!({
         "installed": is_installed,
         "home": maven_home,
+        "version": MavenService::global().get_maven_version_for_java(&version),
     });
     Ok(CommandResponse::success(message.to_string(), Some(data)))
 }
 
+/// 获取各 Java 版本对应的 Maven 版本列表
+#[tauri::command]
+pub async fn get_maven_versions() -> Result<CommandResponse, String> {
+    let java_service = JavaService::global();
+    let versions = java_service.get_maven_versions();
+    let data = serde_json::json!({
+        "versions": versions
+    });
+    Ok(CommandResponse::success(
+        "获取 Maven 版本列表成功".to_string(),
+        Some(data),
+    ))
+}
+
+/// 单独下载 Maven（不重新下载 JDK），进度通过 get_maven_download_progress 查询
+#[tauri::command]
+pub async fn download_maven(version: String) -> Result<CommandResponse, String> {
+    let java_service = JavaService::global();
+    match java_service.download_and_install_maven(&version).await {
+        Ok(result) => {
+            let data = serde_json::json!({
+                "task": result.task
+            });
+            if result.success {
+                Ok(CommandResponse::success(result.message, Some(data)))
+            } else {
+                Ok(CommandResponse::error(result.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("下载 Maven 失败: {}", e))),
+    }
+}
+
 /// 获取可用的 Java 版本列表的 Tauri 命令
 #[tauri::command]
 pub async fn get_java_versions() -> Result<CommandResponse, String> {
@@ -72,7 +107,7 @@ pub async fn download_java(
         });
     }
 
-    match java_service.download_and_install(&version).await {
+    let response = match java_service.download_and_install(&version).await {
         Ok(result) => {
             let data = serde_json::json!({
                 "task": result.task
@@ -84,7 +119,8 @@ pub async fn download_java(
             }
         }
         Err(e) => Ok(CommandResponse::error(format!("下载 Java 失败: {}", e))),
-    }
+    };
+    response.map(|r| with_dependency_warning(&ServiceType::Java, r))
 }
 
 /// 取消 Java 下载的 Tauri 命令
@@ -202,28 +238,49 @@ pub async fn get_java_info(service_data: ServiceData) -> Result<CommandResponse,
     }
 }
 
-/// 设置 JAVA_HOME
+/// 获取服务当前使用的 JAVA_HOME
+#[tauri::command]
+pub async fn get_java_home(
+    environment_id: String,
+    service_data_id: String,
+) -> Result<CommandResponse, String> {
+    let java_service = JavaService::global();
+    match java_service.get_java_home(&environment_id, &service_data_id) {
+        Ok(result) => Ok(CommandResponse::success(result.message, result.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "获取 JAVA_HOME 失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 设置 JAVA_HOME（需指向包含 bin/java 的 JDK 目录）
 #[tauri::command]
 pub async fn set_java_home(
     environment_id: String,
-    mut service_data: ServiceData,
-    java_home: String,
+    service_data_id: String,
+    java_home_path: String,
 ) -> Result<CommandResponse, String> {
-    // 写入 metadata
-    let env_serv_data_manager = EnvServDataManager::global();
-    let env_serv_data_manager = env_serv_data_manager.lock().unwrap();
-    let _ = env_serv_data_manager.set_metadata(
-        &environment_id,
-        &mut service_data,
-        "JAVA_HOME",
-        serde_json::Value::String(java_home.clone()),
-    );
+    let java_service = JavaService::global();
+    match java_service.set_java_home(&environment_id, &service_data_id, java_home_path) {
+        Ok(result) => Ok(CommandResponse::success(result.message, result.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "设置 JAVA_HOME 失败: {}",
+            e
+        ))),
+    }
+}
 
+/// 扫描系统中已安装的 JDK
+#[tauri::command]
+pub async fn detect_system_java_installations() -> Result<CommandResponse, String> {
+    let java_service = JavaService::global();
+    let installations = java_service.detect_system_java_installations();
     let data = serde_json::json!({
-        "javaHome": java_home,
+        "installations": installations,
     });
     Ok(CommandResponse::success(
-        "JAVA_HOME 设置成功".to_string(),
+        format!("检测到 {} 个 JDK", installations.len()),
         Some(data),
     ))
 }
@@ -444,3 +501,74 @@ pub async fn set_maven_local_repository(
         Some(data),
     ))
 }
+
+/// 获取 Maven 仓库地址与本地仓库路径
+#[tauri::command]
+pub async fn get_maven_repository(service_data: ServiceData) -> Result<CommandResponse, String> {
+    let repository_url = MavenService::global().get_maven_repository(&service_data);
+    let local_repo = service_data
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get("MAVEN_LOCAL_REPO"))
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string());
+
+    let data = serde_json::json!({
+        "repositoryUrl": repository_url,
+        "localRepo": local_repo,
+    });
+    Ok(CommandResponse::success(
+        "获取 Maven 仓库地址成功".to_string(),
+        Some(data),
+    ))
+}
+
+/// 设置 Maven 仓库地址
+#[tauri::command]
+pub async fn set_maven_repository(
+    environment_id: String,
+    mut service_data: ServiceData,
+    repository_url: String,
+) -> Result<CommandResponse, String> {
+    let repository_url = repository_url.trim().to_string();
+    if !repository_url.starts_with("http://") && !repository_url.starts_with("https://") {
+        return Ok(CommandResponse::error(
+            "Maven 仓库地址必须以 http:// 或 https:// 开头".to_string(),
+        ));
+    }
+
+    // 写入 metadata
+    {
+        let env_serv_data_manager = EnvServDataManager::global();
+        let env_serv_data_manager = env_serv_data_manager.lock().unwrap();
+        if let Err(e) = env_serv_data_manager.set_metadata(
+            &environment_id,
+            &mut service_data,
+            "MAVEN_REPO_URL",
+            serde_json::Value::String(repository_url.clone()),
+        ) {
+            return Ok(CommandResponse::error(format!(
+                "保存 Maven 仓库地址失败: {}",
+                e
+            )));
+        }
+    } // 锁在这里被释放
+
+    // 已激活的服务立即更新环境变量
+    if matches!(service_data.status, ServiceDataStatus::Active) {
+        if let Err(e) = MavenService::global().apply_maven_repository(&repository_url) {
+            return Ok(CommandResponse::error(format!(
+                "更新 MAVEN_REPO_URL 环境变量失败: {}",
+                e
+            )));
+        }
+    }
+
+    let data = serde_json::json!({
+        "repositoryUrl": repository_url,
+    });
+    Ok(CommandResponse::success(
+        "Maven 仓库地址设置成功".to_string(),
+        Some(data),
+    ))
+}
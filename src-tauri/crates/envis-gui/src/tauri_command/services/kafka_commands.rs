@@ -0,0 +1,228 @@
+use crate::tauri_command::system_info_commands::with_dependency_warning;
+use envis_core::manager::services::kafka::KafkaService;
+use envis_core::types::{CommandResponse, ServiceData, ServiceType};
+
+#[tauri::command]
+pub async fn get_kafka_versions() -> Result<CommandResponse, String> {
+    let service = KafkaService::global();
+    let versions = service.get_available_versions();
+    let data = serde_json::json!({ "versions": versions });
+    Ok(CommandResponse::success(
+        "获取 Kafka 版本列表成功".to_string(),
+        Some(data),
+    ))
+}
+
+#[tauri::command]
+pub async fn download_kafka(version: String) -> Result<CommandResponse, String> {
+    let service = KafkaService::global();
+    let response = match service.download_and_install(&version).await {
+        Ok(result) => {
+            let data = serde_json::json!({ "task": result.task });
+            if result.success {
+                Ok(CommandResponse::success(result.message, Some(data)))
+            } else {
+                Ok(CommandResponse::error(result.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("下载 Kafka 失败: {}", e))),
+    };
+    response.map(|r| with_dependency_warning(&ServiceType::Kafka, r))
+}
+
+#[tauri::command]
+pub async fn cancel_download_kafka(version: String) -> Result<CommandResponse, String> {
+    let service = KafkaService::global();
+    match service.cancel_download(&version) {
+        Ok(_) => {
+            crate::status_events::emit_download_status(
+                &format!("kafka-{}", version),
+                "cancelled",
+                0.0,
+            );
+            Ok(CommandResponse::success(
+                "Kafka 下载已取消".to_string(),
+                Some(serde_json::json!({ "cancelled": true })),
+            ))
+        }
+        Err(e) => Ok(CommandResponse::error(format!(
+            "取消 Kafka 下载失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn check_kafka_installed(version: String) -> Result<CommandResponse, String> {
+    let service = KafkaService::global();
+    let installed = service.is_installed(&version);
+    Ok(CommandResponse::success(
+        "检查 Kafka 安装状态成功".to_string(),
+        Some(serde_json::json!({ "installed": installed })),
+    ))
+}
+
+#[tauri::command]
+pub async fn get_kafka_download_progress(version: String) -> Result<CommandResponse, String> {
+    let service = KafkaService::global();
+    let task = service.get_download_progress(&version);
+    Ok(CommandResponse::success(
+        "获取 Kafka 下载进度成功".to_string(),
+        Some(serde_json::json!({ "task": task })),
+    ))
+}
+
+#[tauri::command]
+pub async fn start_kafka_service(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = KafkaService::global();
+    match service.start_service(&environment_id, &service_data) {
+        Ok(res) => {
+            if res.success {
+                crate::status_events::emit_service_status(
+                    &environment_id,
+                    &service_data.id,
+                    "running",
+                );
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("启动 Kafka 失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn stop_kafka_service(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = KafkaService::global();
+    match service.stop_service(&environment_id, &service_data) {
+        Ok(res) => {
+            if res.success {
+                crate::status_events::emit_service_status(
+                    &environment_id,
+                    &service_data.id,
+                    "stopped",
+                );
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("停止 Kafka 失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn restart_kafka_service(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = KafkaService::global();
+    match service.restart_service(&environment_id, &service_data) {
+        Ok(res) => {
+            if res.success {
+                crate::status_events::emit_service_status(
+                    &environment_id,
+                    &service_data.id,
+                    "running",
+                );
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("重启 Kafka 失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn get_kafka_service_status(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = KafkaService::global();
+    match service.get_service_status(&environment_id, &service_data) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "获取 Kafka 状态失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn create_kafka_topic(
+    environment_id: String,
+    service_data: ServiceData,
+    topic: String,
+    partitions: u32,
+    replication_factor: u32,
+) -> Result<CommandResponse, String> {
+    let service = KafkaService::global();
+    match service.create_kafka_topic(
+        &environment_id,
+        &service_data,
+        &topic,
+        partitions,
+        replication_factor,
+    ) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "创建 Kafka topic 失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn list_kafka_topics(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = KafkaService::global();
+    match service.list_kafka_topics(&environment_id, &service_data) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "获取 Kafka topic 列表失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_kafka_topic(
+    environment_id: String,
+    service_data: ServiceData,
+    topic: String,
+) -> Result<CommandResponse, String> {
+    let service = KafkaService::global();
+    match service.delete_kafka_topic(&environment_id, &service_data, &topic) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "删除 Kafka topic 失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn describe_kafka_topic(
+    environment_id: String,
+    service_data: ServiceData,
+    topic: String,
+) -> Result<CommandResponse, String> {
+    let service = KafkaService::global();
+    match service.describe_kafka_topic(&environment_id, &service_data, &topic) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "获取 Kafka topic 详情失败: {}",
+            e
+        ))),
+    }
+}
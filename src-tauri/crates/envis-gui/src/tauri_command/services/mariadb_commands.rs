@@ -1,6 +1,13 @@
+use crate::tauri_command::system_info_commands::with_dependency_warning;
 use envis_core::manager::env_serv_data_manager::EnvServDataManager;
-use envis_core::manager::services::mariadb::MariadbService;
-use envis_core::types::{CommandResponse, ServiceData};
+use envis_core::manager::services::health::InitializationState;
+use envis_core::manager::services::mariadb::{
+    MariadbInitOptions, MariadbService, TransferProgressCallback,
+};
+use envis_core::types::{CommandResponse, ServiceData, ServiceType};
+use envis_core::utils::run_blocking;
+use std::sync::Arc;
+use tauri::AppHandle;
 
 #[tauri::command]
 pub async fn get_mariadb_versions() -> Result<CommandResponse, String> {
@@ -32,7 +39,7 @@ pub async fn check_mariadb_installed(version: String) -> Result<CommandResponse,
 pub async fn download_mariadb(version: String) -> Result<CommandResponse, String> {
     log::info!("tauri::command 开始下载 MariaDB {}...", version);
     let service = MariadbService::global();
-    match service.download_and_install(&version).await {
+    let response = match service.download_and_install(&version).await {
         Ok(result) => {
             if result.success {
                 let data = serde_json::json!({ "task": result.task });
@@ -42,7 +49,8 @@ pub async fn download_mariadb(version: String) -> Result<CommandResponse, String
             }
         }
         Err(e) => Ok(CommandResponse::error(format!("下载 MariaDB 失败: {}", e))),
-    }
+    };
+    response.map(|r| with_dependency_warning(&ServiceType::Mariadb, r))
 }
 
 #[tauri::command]
@@ -96,13 +104,19 @@ pub async fn start_mariadb_service(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let service = MariadbService::global();
-    match service.start_service(&environment_id, &service_data) {
+    let service_id = service_data.id.clone();
+    let task = run_blocking({
+        let environment_id = environment_id.clone();
+        move || MariadbService::global().start_service(&environment_id, &service_data)
+    });
+    match task.await {
         Ok(res) => {
             if res.success {
-                crate::status_events::emit_service_status(&environment_id, &service_data.id, "running");
+                crate::status_events::emit_service_status(&environment_id, &service_id, "running");
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
             }
-            Ok(CommandResponse::success(res.message, res.data))
         }
         Err(e) => Ok(CommandResponse::error(format!("启动失败: {}", e))),
     }
@@ -113,11 +127,15 @@ pub async fn stop_mariadb_service(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let service = MariadbService::global();
-    match service.stop_service(&environment_id, &service_data) {
+    let service_id = service_data.id.clone();
+    let task = run_blocking({
+        let environment_id = environment_id.clone();
+        move || MariadbService::global().stop_service(&environment_id, &service_data)
+    });
+    match task.await {
         Ok(res) => {
             if res.success {
-                crate::status_events::emit_service_status(&environment_id, &service_data.id, "stopped");
+                crate::status_events::emit_service_status(&environment_id, &service_id, "stopped");
             }
             Ok(CommandResponse::success(res.message, res.data))
         }
@@ -130,11 +148,15 @@ pub async fn restart_mariadb_service(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let service = MariadbService::global();
-    match service.restart_service(&environment_id, &service_data) {
+    let service_id = service_data.id.clone();
+    let task = run_blocking({
+        let environment_id = environment_id.clone();
+        move || MariadbService::global().restart_service(&environment_id, &service_data)
+    });
+    match task.await {
         Ok(res) => {
             if res.success {
-                crate::status_events::emit_service_status(&environment_id, &service_data.id, "running");
+                crate::status_events::emit_service_status(&environment_id, &service_id, "running");
             }
             Ok(CommandResponse::success(res.message, res.data))
         }
@@ -195,6 +217,7 @@ pub async fn set_mariadb_port(
 
 #[tauri::command]
 pub async fn initialize_mariadb(
+    app_handle: AppHandle,
     environment_id: String,
     service_data: ServiceData,
     root_password: String,
@@ -202,15 +225,35 @@ pub async fn initialize_mariadb(
     bind_address: Option<String>,
     reset: Option<bool>,
 ) -> Result<CommandResponse, String> {
-    let service = MariadbService::global();
-    match service.initialize_mariadb(
-        &environment_id,
-        &service_data,
-        root_password,
-        port,
-        bind_address,
-        reset.unwrap_or(false),
-    ) {
+    // 构造进度回调：在 Tauri 层将进度 emit 到前端，事件格式与 mongodb-init-progress 一致
+    let emit_progress = {
+        use tauri::Emitter;
+        let handle = app_handle.clone();
+        move |step: &str, message: &str| {
+            let full_message = format!("MariaDB: {}", message);
+            let _ = handle.emit(
+                "mariadb-init-progress",
+                serde_json::json!({
+                    "step": step,
+                    "message": full_message,
+                }),
+            );
+        }
+    };
+    let task = run_blocking(move || {
+        MariadbService::global().initialize_mariadb_with_progress_events(
+            emit_progress,
+            &environment_id,
+            &service_data,
+            MariadbInitOptions {
+                root_password,
+                port,
+                bind_address,
+                reset: reset.unwrap_or(false),
+            },
+        )
+    });
+    match task.await {
         Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
         Err(e) => Ok(CommandResponse::error(format!(
             "初始化 MariaDB 失败: {}",
@@ -224,11 +267,23 @@ pub async fn check_mariadb_initialized(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let service = MariadbService::global();
-    let initialized = service.is_initialized(&environment_id, &service_data);
-    let data = serde_json::json!({ "initialized": initialized });
+    // 校验凭据时会连接数据库，放到阻塞线程池执行
+    let task = tokio::task::spawn_blocking(move || {
+        MariadbService::global().check_initialization(&environment_id, &service_data)
+    });
+    let check = match task.await {
+        Ok(check) => check,
+        Err(e) => return Ok(CommandResponse::error(format!("检查初始化状态失败: {}", e))),
+    };
+    let data = serde_json::json!({
+        // 目录已存在即视为已初始化，部分初始化时由 state / reason 说明原因
+        "initialized": check.state != InitializationState::NotInitialized,
+        "state": check.state,
+        "reason": check.reason,
+        "credentialsVerified": check.credentials_verified,
+    });
     Ok(CommandResponse::success(
-        if initialized {
+        if check.state != InitializationState::NotInitialized {
             "MariaDB 已初始化"
         } else {
             "MariaDB 未初始化"
@@ -243,8 +298,10 @@ pub async fn list_mariadb_databases(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let service = MariadbService::global();
-    match service.list_databases(&environment_id, &service_data) {
+    let task = run_blocking(move || {
+        MariadbService::global().list_databases(&environment_id, &service_data)
+    });
+    match task.await {
         Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
         Err(e) => Ok(CommandResponse::error(format!("列出数据库失败: {}", e))),
     }
@@ -307,9 +364,10 @@ pub async fn create_mariadb_user(
     username: String,
     password: String,
     grants: Vec<serde_json::Value>,
+    host: Option<String>,
 ) -> Result<CommandResponse, String> {
     let service = MariadbService::global();
-    match service.create_user(&environment_id, &service_data, username, password, grants) {
+    match service.create_user(&environment_id, &service_data, username, password, grants, host) {
         Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
         Err(e) => Ok(CommandResponse::error(format!("创建用户失败: {}", e))),
     }
@@ -320,9 +378,10 @@ pub async fn delete_mariadb_user(
     environment_id: String,
     service_data: ServiceData,
     username: String,
+    host: Option<String>,
 ) -> Result<CommandResponse, String> {
     let service = MariadbService::global();
-    match service.delete_user(&environment_id, &service_data, username) {
+    match service.delete_user(&environment_id, &service_data, username, host) {
         Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
         Err(e) => Ok(CommandResponse::error(format!("删除用户失败: {}", e))),
     }
@@ -334,10 +393,131 @@ pub async fn update_mariadb_user_grants(
     service_data: ServiceData,
     username: String,
     grants: Vec<serde_json::Value>,
+    host: Option<String>,
 ) -> Result<CommandResponse, String> {
     let service = MariadbService::global();
-    match service.update_user_grants(&environment_id, &service_data, username, grants) {
+    match service.update_user_grants(&environment_id, &service_data, username, grants, host) {
         Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
         Err(e) => Ok(CommandResponse::error(format!("更新用户权限失败: {}", e))),
     }
 }
+
+#[tauri::command]
+pub async fn grant_mariadb_privileges(
+    environment_id: String,
+    service_data: ServiceData,
+    username: String,
+    database: String,
+    privileges: Vec<String>,
+    host: Option<String>,
+) -> Result<CommandResponse, String> {
+    let service = MariadbService::global();
+    match service.grant_privileges(&environment_id, &service_data, username, database, privileges, host) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("授予权限失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn change_mariadb_root_password(
+    environment_id: String,
+    service_data: ServiceData,
+    new_password: String,
+) -> Result<CommandResponse, String> {
+    let task = run_blocking(move || {
+        MariadbService::global().change_root_password(&environment_id, &service_data, new_password)
+    });
+    match task.await {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("修改 root 密码失败: {}", e))),
+    }
+}
+
+/// 导出所有用户及权限为 SQL 脚本，用于灾难恢复时重建账号
+#[tauri::command]
+pub async fn export_mariadb_users_sql(
+    environment_id: String,
+    service_data: ServiceData,
+    output_path: String,
+) -> Result<CommandResponse, String> {
+    let task = run_blocking(move || {
+        MariadbService::global().export_users_sql(&environment_id, &service_data, output_path)
+    });
+    match task.await {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("导出用户权限失败: {}", e))),
+    }
+}
+
+/// 构建推送导出 / 导入进度事件的回调
+fn transfer_progress_emitter(
+    event: &'static str,
+    service_id: String,
+    database: String,
+) -> TransferProgressCallback {
+    Arc::new(move |processed, total| {
+        crate::status_events::emit_database_transfer_progress(
+            event,
+            &service_id,
+            &database,
+            processed,
+            total,
+        );
+    })
+}
+
+/// 导出单个数据库为 SQL 文件，进度通过 `mariadb-dump-progress` 事件推送
+#[tauri::command]
+pub async fn dump_mariadb_database(
+    environment_id: String,
+    service_data: ServiceData,
+    database_name: String,
+    output_path: String,
+) -> Result<CommandResponse, String> {
+    let on_progress = transfer_progress_emitter(
+        "mariadb-dump-progress",
+        service_data.id.clone(),
+        database_name.clone(),
+    );
+    let task = run_blocking(move || {
+        MariadbService::global().dump_database(
+            &environment_id,
+            &service_data,
+            database_name,
+            output_path,
+            Some(on_progress),
+        )
+    });
+    match task.await {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("导出数据库失败: {}", e))),
+    }
+}
+
+/// 从 SQL 文件导入数据库，进度通过 `mariadb-restore-progress` 事件推送
+#[tauri::command]
+pub async fn restore_mariadb_database(
+    environment_id: String,
+    service_data: ServiceData,
+    database_name: String,
+    sql_path: String,
+) -> Result<CommandResponse, String> {
+    let on_progress = transfer_progress_emitter(
+        "mariadb-restore-progress",
+        service_data.id.clone(),
+        database_name.clone(),
+    );
+    let task = run_blocking(move || {
+        MariadbService::global().restore_database(
+            &environment_id,
+            &service_data,
+            database_name,
+            sql_path,
+            Some(on_progress),
+        )
+    });
+    match task.await {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("导入数据库失败: {}", e))),
+    }
+}
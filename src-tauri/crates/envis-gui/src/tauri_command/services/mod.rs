@@ -1,15 +1,21 @@
+pub mod compose_commands;
 pub mod custom_commands;
 pub mod dnsmasq_commands;
 pub mod host_commands;
 pub mod java_commands;
+pub mod kafka_commands;
 pub mod mariadb_commands;
 pub mod mongodb_commands;
 pub mod mysql_commands;
 pub mod nasm_commands;
 pub mod nginx_commands;
 pub mod nodejs_commands;
+pub mod opensearch_commands;
+pub mod php_commands;
 pub mod postgresql_commands;
 pub mod python_commands;
+pub mod rabbitmq_commands;
 pub mod redis_commands;
 pub mod rust_commands;
 pub mod ssl_commands;
+pub mod vault_commands;
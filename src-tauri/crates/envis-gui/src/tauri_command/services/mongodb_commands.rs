@@ -1,6 +1,11 @@
+use crate::tauri_command::system_info_commands::with_dependency_warning;
 use envis_core::manager::env_serv_data_manager::EnvServDataManager;
-use envis_core::manager::services::mongodb::MongodbService;
-use envis_core::types::{CommandResponse, ServiceData};
+use envis_core::manager::services::health::InitializationState;
+use envis_core::manager::services::mongodb::{
+    ExplainVerbosity, MongodbInitOptions, MongodbService,
+};
+use envis_core::types::{CommandResponse, ServiceData, ServiceType};
+use envis_core::utils::run_blocking;
 use tauri::AppHandle;
 
 #[tauri::command]
@@ -18,7 +23,7 @@ pub async fn get_mongodb_versions() -> Result<CommandResponse, String> {
 pub async fn download_mongodb(version: String) -> Result<CommandResponse, String> {
     log::info!("tauri::command 开始下载 MongoDB {}...", version);
     let service = MongodbService::global();
-    match service.download_and_install(&version).await {
+    let response = match service.download_and_install(&version).await {
         Ok(result) => {
             if result.success {
                 let data = serde_json::json!({ "task": result.task });
@@ -28,7 +33,8 @@ pub async fn download_mongodb(version: String) -> Result<CommandResponse, String
             }
         }
         Err(e) => Ok(CommandResponse::error(format!("下载 MongoDB 失败: {}", e))),
-    }
+    };
+    response.map(|r| with_dependency_warning(&ServiceType::Mongodb, r))
 }
 
 #[tauri::command]
@@ -93,13 +99,19 @@ pub async fn start_mongodb_service(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let service = MongodbService::global();
-    match service.start_service(&environment_id, &service_data) {
+    let service_id = service_data.id.clone();
+    let task = run_blocking({
+        let environment_id = environment_id.clone();
+        move || MongodbService::global().start_service(&environment_id, &service_data)
+    });
+    match task.await {
         Ok(res) => {
             if res.success {
-                crate::status_events::emit_service_status(&environment_id, &service_data.id, "running");
+                crate::status_events::emit_service_status(&environment_id, &service_id, "running");
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
             }
-            Ok(CommandResponse::success(res.message, res.data))
         }
         Err(e) => Ok(CommandResponse::error(format!("启动失败: {}", e))),
     }
@@ -110,11 +122,15 @@ pub async fn stop_mongodb_service(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let service = MongodbService::global();
-    match service.stop_service(&environment_id, &service_data) {
+    let service_id = service_data.id.clone();
+    let task = run_blocking({
+        let environment_id = environment_id.clone();
+        move || MongodbService::global().stop_service(&environment_id, &service_data)
+    });
+    match task.await {
         Ok(res) => {
             if res.success {
-                crate::status_events::emit_service_status(&environment_id, &service_data.id, "stopped");
+                crate::status_events::emit_service_status(&environment_id, &service_id, "stopped");
             }
             Ok(CommandResponse::success(res.message, res.data))
         }
@@ -127,11 +143,15 @@ pub async fn restart_mongodb_service(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let service = MongodbService::global();
-    match service.restart_service(&environment_id, &service_data) {
+    let service_id = service_data.id.clone();
+    let task = run_blocking({
+        let environment_id = environment_id.clone();
+        move || MongodbService::global().restart_service(&environment_id, &service_data)
+    });
+    match task.await {
         Ok(res) => {
             if res.success {
-                crate::status_events::emit_service_status(&environment_id, &service_data.id, "running");
+                crate::status_events::emit_service_status(&environment_id, &service_id, "running");
             }
             Ok(CommandResponse::success(res.message, res.data))
         }
@@ -193,9 +213,6 @@ pub async fn initialize_mongodb(
     enable_replica_set: Option<bool>,
     reset: Option<bool>,
 ) -> Result<CommandResponse, String> {
-    let service = MongodbService::global();
-    let reset = reset.unwrap_or(false);
-    let enable_replica_set = enable_replica_set.unwrap_or(false);
     // 构造进度回调：在 Tauri 层将进度 emit 到前端
     let emit_progress = {
         use tauri::Emitter;
@@ -211,17 +228,22 @@ pub async fn initialize_mongodb(
             );
         }
     };
-    match service.initialize_mongodb(
-        emit_progress,
-        &environment_id,
-        &service_data,
-        admin_username,
-        admin_password,
-        port,
-        bind_ip,
-        enable_replica_set,
-        reset,
-    ) {
+    let task = run_blocking(move || {
+        MongodbService::global().initialize_mongodb(
+            emit_progress,
+            &environment_id,
+            &service_data,
+            MongodbInitOptions {
+                admin_username,
+                admin_password,
+                port,
+                bind_ip,
+                enable_replica_set: enable_replica_set.unwrap_or(false),
+                reset: reset.unwrap_or(false),
+            },
+        )
+    });
+    match task.await {
         Ok(res) => {
             if res.success {
                 Ok(CommandResponse::success(res.message, res.data))
@@ -241,9 +263,21 @@ pub async fn check_mongodb_initialized(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let service = MongodbService::global();
-    let initialized = service.is_initialized(&environment_id, &service_data);
-    let data = serde_json::json!({ "initialized": initialized });
+    // 校验凭据时会连接数据库，放到阻塞线程池执行
+    let task = tokio::task::spawn_blocking(move || {
+        MongodbService::global().check_initialization(&environment_id, &service_data)
+    });
+    let check = match task.await {
+        Ok(check) => check,
+        Err(e) => return Ok(CommandResponse::error(format!("检查初始化状态失败: {}", e))),
+    };
+    let data = serde_json::json!({
+        // 目录已存在即视为已初始化，部分初始化时由 state / reason 说明原因
+        "initialized": check.state != InitializationState::NotInitialized,
+        "state": check.state,
+        "reason": check.reason,
+        "credentialsVerified": check.credentials_verified,
+    });
     Ok(CommandResponse::success(
         "检查初始化状态成功".to_string(),
         Some(data),
@@ -255,8 +289,10 @@ pub async fn list_mongodb_databases(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let service = MongodbService::global();
-    match service.list_databases(&environment_id, &service_data) {
+    let task = run_blocking(move || {
+        MongodbService::global().list_databases(&environment_id, &service_data)
+    });
+    match task.await {
         Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
         Err(e) => Ok(CommandResponse::error(format!("获取数据库列表失败: {}", e))),
     }
@@ -288,6 +324,128 @@ pub async fn create_mongodb_database(
     }
 }
 
+#[tauri::command]
+pub async fn create_mongodb_collection(
+    environment_id: String,
+    service_data: ServiceData,
+    database_name: String,
+    collection_name: String,
+) -> Result<CommandResponse, String> {
+    let service = MongodbService::global();
+    match service.create_collection(
+        &environment_id,
+        &service_data,
+        database_name,
+        collection_name,
+    ) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("创建集合失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn drop_mongodb_collection(
+    environment_id: String,
+    service_data: ServiceData,
+    database_name: String,
+    collection_name: String,
+) -> Result<CommandResponse, String> {
+    let service = MongodbService::global();
+    match service.drop_collection(
+        &environment_id,
+        &service_data,
+        database_name,
+        collection_name,
+    ) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("删除集合失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn get_mongodb_collection_stats(
+    environment_id: String,
+    service_data: ServiceData,
+    database_name: String,
+    collection_name: String,
+) -> Result<CommandResponse, String> {
+    let service = MongodbService::global();
+    match service.get_collection_stats(
+        &environment_id,
+        &service_data,
+        database_name,
+        collection_name,
+    ) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("获取集合统计信息失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn list_mongodb_indexes(
+    environment_id: String,
+    service_data: ServiceData,
+    database_name: String,
+    collection_name: String,
+) -> Result<CommandResponse, String> {
+    let service = MongodbService::global();
+    match service.list_indexes(
+        &environment_id,
+        &service_data,
+        database_name,
+        collection_name,
+    ) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("获取索引列表失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn explain_mongodb_query(
+    environment_id: String,
+    service_data: ServiceData,
+    database_name: String,
+    collection_name: String,
+    query_json: String,
+    verbosity: ExplainVerbosity,
+) -> Result<CommandResponse, String> {
+    let service = MongodbService::global();
+    match service.explain_query(
+        &environment_id,
+        &service_data,
+        database_name,
+        collection_name,
+        query_json,
+        verbosity,
+    ) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("获取查询计划失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn create_mongodb_index(
+    environment_id: String,
+    service_data: ServiceData,
+    database_name: String,
+    collection_name: String,
+    keys: serde_json::Value,
+    options: Option<serde_json::Value>,
+) -> Result<CommandResponse, String> {
+    let service = MongodbService::global();
+    match service.create_index(
+        &environment_id,
+        &service_data,
+        database_name,
+        collection_name,
+        keys,
+        options.unwrap_or(serde_json::Value::Null),
+    ) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("创建索引失败: {}", e))),
+    }
+}
+
 #[tauri::command]
 pub async fn create_mongodb_user(
     environment_id: String,
@@ -350,3 +508,90 @@ pub async fn delete_mongodb_user(
         Err(e) => Ok(CommandResponse::error(format!("删除用户失败: {}", e))),
     }
 }
+
+#[tauri::command]
+pub async fn change_mongodb_admin_password(
+    environment_id: String,
+    service_data: ServiceData,
+    new_password: String,
+) -> Result<CommandResponse, String> {
+    let service = MongodbService::global();
+    match service.change_admin_password(&environment_id, &service_data, new_password) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("修改管理员密码失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn rotate_mongodb_keyfile(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let task = run_blocking(move || {
+        MongodbService::global().rotate_keyfile(&environment_id, &service_data)
+    });
+    match task.await {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("轮换 keyfile 失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn get_mongodb_replication_status(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let task = run_blocking(move || {
+        MongodbService::global().get_replication_status(&environment_id, &service_data)
+    });
+    match task.await {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("获取副本集状态失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn create_mongodb_backup_schedule(
+    environment_id: String,
+    service_data: ServiceData,
+    cron_expression: String,
+    output_dir: String,
+) -> Result<CommandResponse, String> {
+    let service = MongodbService::global();
+    match service.create_backup_schedule(
+        &environment_id,
+        &service_data,
+        &cron_expression,
+        &output_dir,
+    ) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("创建定时备份失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_mongodb_backup_schedule(
+    environment_id: String,
+    service_data: ServiceData,
+    schedule_id: String,
+) -> Result<CommandResponse, String> {
+    let service = MongodbService::global();
+    match service.delete_backup_schedule(&environment_id, &service_data, &schedule_id) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("删除定时备份失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn list_mongodb_backup_schedules(
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = MongodbService::global();
+    match service.list_backup_schedules(&service_data) {
+        Ok(schedules) => Ok(CommandResponse::success(
+            "获取定时备份列表成功".to_string(),
+            Some(serde_json::json!({ "schedules": schedules })),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("获取定时备份列表失败: {}", e))),
+    }
+}
@@ -1,5 +1,9 @@
-use envis_core::manager::services::mysql::MysqlService;
-use envis_core::types::{CommandResponse, ServiceData};
+use crate::tauri_command::system_info_commands::with_dependency_warning;
+use envis_core::manager::services::health::InitializationState;
+use envis_core::manager::services::mysql::{MysqlInitOptions, MysqlService};
+use envis_core::types::{CommandResponse, ServiceData, ServiceType};
+use envis_core::utils::run_blocking;
+use tauri::AppHandle;
 
 /// 检查 MySQL 是否已安装的 Tauri 命令
 #[tauri::command]
@@ -36,7 +40,7 @@ pub async fn get_mysql_versions() -> Result<CommandResponse, String> {
 pub async fn download_mysql(version: String) -> Result<CommandResponse, String> {
     log::info!("tauri::command 开始下载 MySQL {}...", version);
     let mysql_service = MysqlService::global();
-    match mysql_service.download_and_install(&version).await {
+    let response = match mysql_service.download_and_install(&version).await {
         Ok(result) => {
             let data = serde_json::json!({
                 "task": result.task
@@ -48,7 +52,8 @@ pub async fn download_mysql(version: String) -> Result<CommandResponse, String>
             }
         }
         Err(e) => Ok(CommandResponse::error(format!("下载 MySQL 失败: {}", e))),
-    }
+    };
+    response.map(|r| with_dependency_warning(&ServiceType::Mysql, r))
 }
 
 /// 取消 MySQL 下载的 Tauri 命令
@@ -109,13 +114,19 @@ pub async fn start_mysql_service(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let service = MysqlService::global();
-    match service.start_service(&environment_id, &service_data) {
+    let service_id = service_data.id.clone();
+    let task = run_blocking({
+        let environment_id = environment_id.clone();
+        move || MysqlService::global().start_service(&environment_id, &service_data)
+    });
+    match task.await {
         Ok(res) => {
             if res.success {
-                crate::status_events::emit_service_status(&environment_id, &service_data.id, "running");
+                crate::status_events::emit_service_status(&environment_id, &service_id, "running");
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
             }
-            Ok(CommandResponse::success(res.message, res.data))
         }
         Err(e) => Ok(CommandResponse::error(format!("启动失败: {}", e))),
     }
@@ -127,11 +138,15 @@ pub async fn stop_mysql_service(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let service = MysqlService::global();
-    match service.stop_service(&environment_id, &service_data) {
+    let service_id = service_data.id.clone();
+    let task = run_blocking({
+        let environment_id = environment_id.clone();
+        move || MysqlService::global().stop_service(&environment_id, &service_data)
+    });
+    match task.await {
         Ok(res) => {
             if res.success {
-                crate::status_events::emit_service_status(&environment_id, &service_data.id, "stopped");
+                crate::status_events::emit_service_status(&environment_id, &service_id, "stopped");
             }
             Ok(CommandResponse::success(res.message, res.data))
         }
@@ -145,11 +160,15 @@ pub async fn restart_mysql_service(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let service = MysqlService::global();
-    match service.restart_service(&environment_id, &service_data) {
+    let service_id = service_data.id.clone();
+    let task = run_blocking({
+        let environment_id = environment_id.clone();
+        move || MysqlService::global().restart_service(&environment_id, &service_data)
+    });
+    match task.await {
         Ok(res) => {
             if res.success {
-                crate::status_events::emit_service_status(&environment_id, &service_data.id, "running");
+                crate::status_events::emit_service_status(&environment_id, &service_id, "running");
             }
             Ok(CommandResponse::success(res.message, res.data))
         }
@@ -215,6 +234,7 @@ pub async fn set_mysql_port(
 /// 初始化 MySQL
 #[tauri::command]
 pub async fn initialize_mysql(
+    app_handle: AppHandle,
     environment_id: String,
     service_data: ServiceData,
     root_password: String,
@@ -222,15 +242,35 @@ pub async fn initialize_mysql(
     bind_address: Option<String>,
     reset: Option<bool>,
 ) -> Result<CommandResponse, String> {
-    let service = MysqlService::global();
-    match service.initialize_mysql(
-        &environment_id,
-        &service_data,
-        root_password,
-        port,
-        bind_address,
-        reset.unwrap_or(false),
-    ) {
+    // 构造进度回调：在 Tauri 层将进度 emit 到前端，事件格式与 mongodb-init-progress 一致
+    let emit_progress = {
+        use tauri::Emitter;
+        let handle = app_handle.clone();
+        move |step: &str, message: &str| {
+            let full_message = format!("MySQL: {}", message);
+            let _ = handle.emit(
+                "mysql-init-progress",
+                serde_json::json!({
+                    "step": step,
+                    "message": full_message,
+                }),
+            );
+        }
+    };
+    let task = run_blocking(move || {
+        MysqlService::global().initialize_mysql_with_progress_events(
+            emit_progress,
+            &environment_id,
+            &service_data,
+            MysqlInitOptions {
+                root_password,
+                port,
+                bind_address,
+                reset: reset.unwrap_or(false),
+            },
+        )
+    });
+    match task.await {
         Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
         Err(e) => Ok(CommandResponse::error(format!("初始化 MySQL 失败: {}", e))),
     }
@@ -242,11 +282,23 @@ pub async fn check_mysql_initialized(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let service = MysqlService::global();
-    let initialized = service.is_initialized(&environment_id, &service_data);
-    let data = serde_json::json!({ "initialized": initialized });
+    // 校验凭据时会连接数据库，放到阻塞线程池执行
+    let task = tokio::task::spawn_blocking(move || {
+        MysqlService::global().check_initialization(&environment_id, &service_data)
+    });
+    let check = match task.await {
+        Ok(check) => check,
+        Err(e) => return Ok(CommandResponse::error(format!("检查初始化状态失败: {}", e))),
+    };
+    let data = serde_json::json!({
+        // 目录已存在即视为已初始化，部分初始化时由 state / reason 说明原因
+        "initialized": check.state != InitializationState::NotInitialized,
+        "state": check.state,
+        "reason": check.reason,
+        "credentialsVerified": check.credentials_verified,
+    });
     Ok(CommandResponse::success(
-        if initialized {
+        if check.state != InitializationState::NotInitialized {
             "MySQL 已初始化"
         } else {
             "MySQL 未初始化"
@@ -262,8 +314,9 @@ pub async fn list_mysql_databases(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let service = MysqlService::global();
-    match service.list_databases(&environment_id, &service_data) {
+    let task =
+        run_blocking(move || MysqlService::global().list_databases(&environment_id, &service_data));
+    match task.await {
         Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
         Err(e) => Ok(CommandResponse::error(format!("列出数据库失败: {}", e))),
     }
@@ -331,9 +384,10 @@ pub async fn create_mysql_user(
     username: String,
     password: String,
     grants: Vec<serde_json::Value>,
+    host: Option<String>,
 ) -> Result<CommandResponse, String> {
     let service = MysqlService::global();
-    match service.create_user(&environment_id, &service_data, username, password, grants) {
+    match service.create_user(&environment_id, &service_data, username, password, grants, host) {
         Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
         Err(e) => Ok(CommandResponse::error(format!("创建用户失败: {}", e))),
     }
@@ -345,9 +399,10 @@ pub async fn delete_mysql_user(
     environment_id: String,
     service_data: ServiceData,
     username: String,
+    host: Option<String>,
 ) -> Result<CommandResponse, String> {
     let service = MysqlService::global();
-    match service.delete_user(&environment_id, &service_data, username) {
+    match service.delete_user(&environment_id, &service_data, username, host) {
         Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
         Err(e) => Ok(CommandResponse::error(format!("删除用户失败: {}", e))),
     }
@@ -360,10 +415,43 @@ pub async fn update_mysql_user_grants(
     service_data: ServiceData,
     username: String,
     grants: Vec<serde_json::Value>,
+    host: Option<String>,
 ) -> Result<CommandResponse, String> {
     let service = MysqlService::global();
-    match service.update_user_grants(&environment_id, &service_data, username, grants) {
+    match service.update_user_grants(&environment_id, &service_data, username, grants, host) {
         Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
         Err(e) => Ok(CommandResponse::error(format!("更新用户权限失败: {}", e))),
     }
 }
+
+/// 授予 MySQL 用户数据库权限
+#[tauri::command]
+pub async fn grant_mysql_privileges(
+    environment_id: String,
+    service_data: ServiceData,
+    username: String,
+    database: String,
+    privileges: Vec<String>,
+    host: Option<String>,
+) -> Result<CommandResponse, String> {
+    let service = MysqlService::global();
+    match service.grant_privileges(&environment_id, &service_data, username, database, privileges, host) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("授予权限失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn change_mysql_root_password(
+    environment_id: String,
+    service_data: ServiceData,
+    new_password: String,
+) -> Result<CommandResponse, String> {
+    let task = run_blocking(move || {
+        MysqlService::global().change_root_password(&environment_id, &service_data, new_password)
+    });
+    match task.await {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("修改 root 密码失败: {}", e))),
+    }
+}
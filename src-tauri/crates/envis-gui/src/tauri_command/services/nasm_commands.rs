@@ -1,5 +1,6 @@
+use crate::tauri_command::system_info_commands::with_dependency_warning;
 use envis_core::manager::services::nasm::NasmService;
-use envis_core::types::CommandResponse;
+use envis_core::types::{CommandResponse, ServiceType};
 
 /// 检查 NASM 是否已安装
 #[tauri::command]
@@ -38,7 +39,7 @@ pub async fn get_nasm_versions() -> Result<CommandResponse, String> {
 #[tauri::command]
 pub async fn download_nasm(version: String) -> Result<CommandResponse, String> {
     let nasm_service = NasmService::global();
-    match nasm_service.download_and_install(&version).await {
+    let response = match nasm_service.download_and_install(&version).await {
         Ok(result) => {
             let data = serde_json::json!({
                 "task": result.task,
@@ -50,7 +51,8 @@ pub async fn download_nasm(version: String) -> Result<CommandResponse, String> {
             }
         }
         Err(e) => Ok(CommandResponse::error(format!("下载 NASM 失败: {}", e))),
-    }
+    };
+    response.map(|r| with_dependency_warning(&ServiceType::Nasm, r))
 }
 
 /// 取消 NASM 下载
@@ -1,5 +1,6 @@
-use envis_core::manager::services::nginx::NginxService;
-use envis_core::types::{CommandResponse, ServiceData};
+use crate::tauri_command::system_info_commands::with_dependency_warning;
+use envis_core::manager::services::nginx::{BalanceMethod, NginxService, NginxWorkers};
+use envis_core::types::{CommandResponse, ServiceData, ServiceType};
 
 /// 按版本检查 Nginx 是否已安装
 #[tauri::command]
@@ -123,11 +124,12 @@ pub async fn get_nginx_service_status(
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
     let nginx_service = NginxService::global();
-    match nginx_service.get_service_status(&service_data) {
-        Ok(status) => {
-            let data = serde_json::json!({
-                "status": status
+    match nginx_service.get_service_health(&service_data) {
+        Ok(health) => {
+            let mut data = serde_json::json!({
+                "isRunning": health.process,
             });
+            health.merge_into(&mut data);
             Ok(CommandResponse::success(
                 "获取 Nginx 服务状态成功".to_string(),
                 Some(data),
@@ -140,6 +142,198 @@ pub async fn get_nginx_service_status(
     }
 }
 
+/// 检查 Nginx 配置语法
+#[tauri::command]
+pub async fn validate_nginx_config(
+    _environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let nginx_service = NginxService::global();
+    match nginx_service.validate_nginx_config(&service_data) {
+        Ok(result) if result.success => Ok(CommandResponse::success(result.message, result.data)),
+        Ok(result) => Ok(CommandResponse::error(result.message)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "检查 Nginx 配置失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 获取 Nginx upstream 列表
+#[tauri::command]
+pub async fn list_nginx_upstreams(
+    _environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let nginx_service = NginxService::global();
+    match nginx_service.list_upstreams(&service_data) {
+        Ok(upstreams) => Ok(CommandResponse::success(
+            "获取 upstream 列表成功".to_string(),
+            Some(serde_json::json!({ "upstreams": upstreams })),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "获取 upstream 列表失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 添加或更新 Nginx upstream
+#[tauri::command]
+pub async fn add_nginx_upstream(
+    _environment_id: String,
+    service_data: ServiceData,
+    upstream_name: String,
+    servers: Vec<String>,
+    method: Option<BalanceMethod>,
+) -> Result<CommandResponse, String> {
+    let nginx_service = NginxService::global();
+    match nginx_service.add_upstream(
+        &service_data,
+        &upstream_name,
+        servers,
+        method.unwrap_or_default(),
+    ) {
+        Ok(result) if result.success => Ok(CommandResponse::success(result.message, result.data)),
+        Ok(result) => Ok(CommandResponse::error(result.message)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "添加 upstream 失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 删除 Nginx upstream
+#[tauri::command]
+pub async fn remove_nginx_upstream(
+    _environment_id: String,
+    service_data: ServiceData,
+    upstream_name: String,
+) -> Result<CommandResponse, String> {
+    let nginx_service = NginxService::global();
+    match nginx_service.remove_upstream(&service_data, &upstream_name) {
+        Ok(result) if result.success => Ok(CommandResponse::success(result.message, result.data)),
+        Ok(result) => Ok(CommandResponse::error(result.message)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "删除 upstream 失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 修改 Nginx worker_processes
+#[tauri::command]
+pub async fn set_nginx_worker_processes(
+    environment_id: String,
+    service_data: ServiceData,
+    value: NginxWorkers,
+) -> Result<CommandResponse, String> {
+    let nginx_service = NginxService::global();
+    match nginx_service.set_worker_processes(&environment_id, &service_data, value) {
+        Ok(result) if result.success => Ok(CommandResponse::success(result.message, result.data)),
+        Ok(result) => Ok(CommandResponse::error(result.message)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "设置 worker_processes 失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 修改 Nginx worker_connections
+#[tauri::command]
+pub async fn set_nginx_worker_connections(
+    environment_id: String,
+    service_data: ServiceData,
+    connections: u32,
+) -> Result<CommandResponse, String> {
+    let nginx_service = NginxService::global();
+    match nginx_service.set_worker_connections(&environment_id, &service_data, connections) {
+        Ok(result) if result.success => Ok(CommandResponse::success(result.message, result.data)),
+        Ok(result) => Ok(CommandResponse::error(result.message)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "设置 worker_connections 失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 按当前环境的 PHP 服务生成 php-fastcgi.conf
+#[tauri::command]
+pub async fn write_nginx_php_fastcgi_conf(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let nginx_service = NginxService::global();
+    match nginx_service.write_php_fastcgi_conf(&environment_id, &service_data) {
+        Ok(result) => Ok(CommandResponse::success(result.message, result.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "生成 PHP FastCGI 配置失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 轮转 Nginx 日志
+#[tauri::command]
+pub async fn rotate_nginx_logs(
+    _environment_id: String,
+    service_data: ServiceData,
+    compress: Option<bool>,
+    retain_days: Option<u32>,
+) -> Result<CommandResponse, String> {
+    let nginx_service = NginxService::global();
+    match nginx_service.rotate_nginx_logs(&service_data, compress.unwrap_or(true), retain_days) {
+        Ok(result) => {
+            if result.success {
+                Ok(CommandResponse::success(result.message, result.data))
+            } else {
+                Ok(CommandResponse::error(result.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!(
+            "轮转 Nginx 日志失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 统计 Nginx access log（默认最近 24 小时）
+#[tauri::command]
+pub async fn get_nginx_access_log_stats(
+    _environment_id: String,
+    service_data: ServiceData,
+    hours: Option<u32>,
+) -> Result<CommandResponse, String> {
+    let nginx_service = NginxService::global();
+    match nginx_service.get_access_log_stats(&service_data, hours.unwrap_or(24)) {
+        Ok(result) => Ok(CommandResponse::success(result.message, result.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "统计 Nginx 访问日志失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 获取 Nginx 实时连接数（stub_status）
+#[tauri::command]
+pub async fn get_nginx_active_connections(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let nginx_service = NginxService::global();
+    match nginx_service
+        .get_active_connections(&environment_id, &service_data)
+        .await
+    {
+        Ok(result) if result.success => Ok(CommandResponse::success(result.message, result.data)),
+        Ok(result) => Ok(CommandResponse::error(result.message)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "获取 Nginx 连接数失败: {}",
+            e
+        ))),
+    }
+}
+
 // /// 获取可用的 Nginx 版本列表的 Tauri 命令
 #[tauri::command]
 pub async fn get_nginx_versions() -> Result<CommandResponse, String> {
@@ -157,7 +351,7 @@ pub async fn get_nginx_versions() -> Result<CommandResponse, String> {
 pub async fn download_nginx(version: String) -> Result<CommandResponse, String> {
     log::info!("tauri::command 开始下载 Nginx {}...", version);
     let nginx_service = NginxService::global();
-    match nginx_service.download_and_install(&version).await {
+    let response = match nginx_service.download_and_install(&version).await {
         Ok(result) => {
             let data = serde_json::json!({
                 "task": result.task
@@ -169,7 +363,8 @@ pub async fn download_nginx(version: String) -> Result<CommandResponse, String>
             }
         }
         Err(e) => Ok(CommandResponse::error(format!("下载 Nginx 失败: {}", e))),
-    }
+    };
+    response.map(|r| with_dependency_warning(&ServiceType::Nginx, r))
 }
 
 // /// 取消 Nginx 下载的 Tauri 命令
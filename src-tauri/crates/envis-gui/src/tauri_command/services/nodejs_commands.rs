@@ -1,6 +1,8 @@
+use crate::tauri_command::system_info_commands::with_dependency_warning;
 use envis_core::manager::env_serv_data_manager::EnvServDataManager;
 use envis_core::manager::services::nodejs::NodejsService;
-use envis_core::types::{CommandResponse, ServiceData};
+use envis_core::types::{CommandResponse, ServiceData, ServiceType};
+use tauri::AppHandle;
 
 /// 检查 Node.js 是否已安装的 Tauri 命令
 #[tauri::command]
@@ -49,7 +51,7 @@ pub async fn download_nodejs(version: String) -> Result<CommandResponse, String>
     // 打印日志
     log::info!("tauri::command 开始下载 Node.js {}...", version);
     let nodejs_service = NodejsService::global();
-    match nodejs_service.download_and_install(&version).await {
+    let response = match nodejs_service.download_and_install(&version).await {
         Ok(result) => {
             let data = serde_json::json!({
                 "task": result.task
@@ -61,7 +63,8 @@ pub async fn download_nodejs(version: String) -> Result<CommandResponse, String>
             }
         }
         Err(e) => Ok(CommandResponse::error(format!("下载 Node.js 失败: {}", e))),
-    }
+    };
+    response.map(|r| with_dependency_warning(&ServiceType::Nodejs, r))
 }
 
 /// 取消 Node.js 下载的 Tauri 命令
@@ -207,6 +210,63 @@ pub async fn set_pnpm_home(
     }
 }
 
+/// 设置 NODE_OPTIONS 的 Tauri 命令
+#[tauri::command]
+pub async fn set_node_options(
+    environment_id: String,
+    mut service_data: ServiceData,
+    options: Vec<String>,
+) -> Result<CommandResponse, String> {
+    let nodejs_service = NodejsService::global();
+    match nodejs_service.set_node_options(&environment_id, &mut service_data, options) {
+        Ok(_) => {
+            let data = serde_json::json!({
+                "nodeOptions": nodejs_service.get_current_node_options(),
+            });
+            Ok(CommandResponse::success(
+                "设置 NODE_OPTIONS 成功".to_string(),
+                Some(data),
+            ))
+        }
+        Err(e) => Ok(CommandResponse::error(format!(
+            "设置 NODE_OPTIONS 失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 清除 NODE_OPTIONS 的 Tauri 命令
+#[tauri::command]
+pub async fn clear_node_options(
+    environment_id: String,
+    mut service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let nodejs_service = NodejsService::global();
+    match nodejs_service.clear_node_options(&environment_id, &mut service_data) {
+        Ok(_) => Ok(CommandResponse::success(
+            "清除 NODE_OPTIONS 成功".to_string(),
+            None,
+        )),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "清除 NODE_OPTIONS 失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 获取当前生效的 NODE_OPTIONS 的 Tauri 命令
+#[tauri::command]
+pub async fn get_current_node_options() -> Result<CommandResponse, String> {
+    let nodejs_service = NodejsService::global();
+    let data = serde_json::json!({
+        "nodeOptions": nodejs_service.get_current_node_options(),
+    });
+    Ok(CommandResponse::success(
+        "获取 NODE_OPTIONS 成功".to_string(),
+        Some(data),
+    ))
+}
+
 /// 获取全局安装的 npm 包列表
 #[tauri::command]
 pub async fn get_global_npm_packages(service_data: ServiceData) -> Result<CommandResponse, String> {
@@ -243,3 +303,133 @@ pub async fn install_global_npm_package(
         Err(e) => Ok(CommandResponse::error(format!("安装全局包失败: {}", e))),
     }
 }
+
+/// 读取 npm 配置项
+#[tauri::command]
+pub async fn get_npm_config(
+    service_data: ServiceData,
+    key: String,
+) -> Result<CommandResponse, String> {
+    let nodejs_service = NodejsService::global();
+    match nodejs_service.get_npm_config(&service_data, &key) {
+        Ok(value) => {
+            let data = serde_json::json!({
+                "key": key,
+                "value": value,
+            });
+            Ok(CommandResponse::success(
+                "获取 npm 配置成功".to_string(),
+                Some(data),
+            ))
+        }
+        Err(e) => Ok(CommandResponse::error(format!("获取 npm 配置失败: {}", e))),
+    }
+}
+
+/// 设置 npm 全局配置项
+#[tauri::command]
+pub async fn set_npm_config_key(
+    service_data: ServiceData,
+    key: String,
+    value: String,
+) -> Result<CommandResponse, String> {
+    let nodejs_service = NodejsService::global();
+    match nodejs_service.set_npm_config_key(&service_data, &key, &value) {
+        Ok(_) => {
+            let data = serde_json::json!({
+                "key": key,
+                "value": value,
+            });
+            Ok(CommandResponse::success(
+                format!("已设置 npm 配置: {}", key),
+                Some(data),
+            ))
+        }
+        Err(e) => Ok(CommandResponse::error(format!("设置 npm 配置失败: {}", e))),
+    }
+}
+
+/// 列出 npm 全部生效配置
+#[tauri::command]
+pub async fn list_npm_config(service_data: ServiceData) -> Result<CommandResponse, String> {
+    let nodejs_service = NodejsService::global();
+    match nodejs_service.list_npm_config(&service_data) {
+        Ok(config) => {
+            let data = serde_json::json!({
+                "config": config,
+            });
+            Ok(CommandResponse::success(
+                "获取 npm 配置列表成功".to_string(),
+                Some(data),
+            ))
+        }
+        Err(e) => Ok(CommandResponse::error(format!(
+            "获取 npm 配置列表失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 执行 package.json 中的脚本，输出通过 npm-script-output 事件推送
+#[tauri::command]
+pub async fn run_npm_script(
+    app_handle: AppHandle,
+    environment_id: String,
+    service_data_id: String,
+    working_dir: String,
+    script_name: String,
+) -> Result<CommandResponse, String> {
+    let nodejs_service = NodejsService::global();
+    let emit_output = {
+        use tauri::Emitter;
+        let handle = app_handle.clone();
+        let script_name = script_name.clone();
+        move |pid: u32, stream: &str, line: &str| {
+            let _ = handle.emit(
+                "npm-script-output",
+                serde_json::json!({
+                    "pid": pid,
+                    "scriptName": script_name,
+                    "stream": stream,
+                    "line": line,
+                }),
+            );
+        }
+    };
+    match nodejs_service.run_npm_script(
+        &environment_id,
+        &service_data_id,
+        &working_dir,
+        &script_name,
+        emit_output,
+    ) {
+        Ok(pid) => Ok(CommandResponse::success(
+            format!("已启动脚本: {}", script_name),
+            Some(serde_json::json!({ "pid": pid })),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("执行 npm 脚本失败: {}", e))),
+    }
+}
+
+/// 停止正在运行的 npm 脚本
+#[tauri::command]
+pub async fn stop_npm_script(pid: u32) -> Result<CommandResponse, String> {
+    let nodejs_service = NodejsService::global();
+    match nodejs_service.stop_npm_script(pid) {
+        Ok(_) => Ok(CommandResponse::success("已停止 npm 脚本".to_string(), None)),
+        Err(e) => Ok(CommandResponse::error(format!("停止 npm 脚本失败: {}", e))),
+    }
+}
+
+/// 获取正在运行的 Node.js 进程列表
+#[tauri::command]
+pub async fn get_node_process_list() -> Result<CommandResponse, String> {
+    let nodejs_service = NodejsService::global();
+    match nodejs_service.get_node_process_list() {
+        Ok(result) => Ok(CommandResponse::success(result.message, result.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "获取 Node.js 进程列表失败: {}",
+            e
+        ))),
+    }
+}
@@ -0,0 +1,206 @@
+use crate::tauri_command::system_info_commands::with_dependency_warning;
+use envis_core::manager::services::opensearch::OpensearchService;
+use envis_core::types::{CommandResponse, ServiceData, ServiceType};
+
+#[tauri::command]
+pub async fn get_opensearch_versions() -> Result<CommandResponse, String> {
+    let service = OpensearchService::global();
+    let versions = service.get_available_versions();
+    let data = serde_json::json!({ "versions": versions });
+    Ok(CommandResponse::success(
+        "获取 OpenSearch 版本列表成功".to_string(),
+        Some(data),
+    ))
+}
+
+#[tauri::command]
+pub async fn download_opensearch(version: String) -> Result<CommandResponse, String> {
+    let service = OpensearchService::global();
+    let response = match service.download_and_install(&version).await {
+        Ok(result) => {
+            let data = serde_json::json!({ "task": result.task });
+            if result.success {
+                Ok(CommandResponse::success(result.message, Some(data)))
+            } else {
+                Ok(CommandResponse::error(result.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!(
+            "下载 OpenSearch 失败: {}",
+            e
+        ))),
+    };
+    response.map(|r| with_dependency_warning(&ServiceType::Opensearch, r))
+}
+
+#[tauri::command]
+pub async fn cancel_download_opensearch(version: String) -> Result<CommandResponse, String> {
+    let service = OpensearchService::global();
+    match service.cancel_download(&version) {
+        Ok(_) => {
+            crate::status_events::emit_download_status(
+                &format!("opensearch-{}", version),
+                "cancelled",
+                0.0,
+            );
+            Ok(CommandResponse::success(
+                "OpenSearch 下载已取消".to_string(),
+                Some(serde_json::json!({ "cancelled": true })),
+            ))
+        }
+        Err(e) => Ok(CommandResponse::error(format!(
+            "取消 OpenSearch 下载失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn check_opensearch_installed(version: String) -> Result<CommandResponse, String> {
+    let service = OpensearchService::global();
+    let installed = service.is_installed(&version);
+    Ok(CommandResponse::success(
+        "检查 OpenSearch 安装状态成功".to_string(),
+        Some(serde_json::json!({ "installed": installed })),
+    ))
+}
+
+#[tauri::command]
+pub async fn get_opensearch_download_progress(version: String) -> Result<CommandResponse, String> {
+    let service = OpensearchService::global();
+    let task = service.get_download_progress(&version);
+    Ok(CommandResponse::success(
+        "获取 OpenSearch 下载进度成功".to_string(),
+        Some(serde_json::json!({ "task": task })),
+    ))
+}
+
+#[tauri::command]
+pub async fn start_opensearch_service(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = OpensearchService::global();
+    match service.start_service(&environment_id, &service_data) {
+        Ok(res) => {
+            if res.success {
+                crate::status_events::emit_service_status(
+                    &environment_id,
+                    &service_data.id,
+                    "running",
+                );
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!(
+            "启动 OpenSearch 失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn stop_opensearch_service(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = OpensearchService::global();
+    match service.stop_service(&environment_id, &service_data) {
+        Ok(res) => {
+            if res.success {
+                crate::status_events::emit_service_status(
+                    &environment_id,
+                    &service_data.id,
+                    "stopped",
+                );
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!(
+            "停止 OpenSearch 失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn restart_opensearch_service(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = OpensearchService::global();
+    match service.restart_service(&environment_id, &service_data) {
+        Ok(res) => {
+            if res.success {
+                crate::status_events::emit_service_status(
+                    &environment_id,
+                    &service_data.id,
+                    "running",
+                );
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!(
+            "重启 OpenSearch 失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn get_opensearch_service_status(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = OpensearchService::global();
+    match service.get_service_status(&environment_id, &service_data) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "获取 OpenSearch 状态失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn list_opensearch_indices(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = OpensearchService::global();
+    match service
+        .list_opensearch_indices(&environment_id, &service_data)
+        .await
+    {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "获取 OpenSearch 索引列表失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_opensearch_index(
+    environment_id: String,
+    service_data: ServiceData,
+    index: String,
+) -> Result<CommandResponse, String> {
+    let service = OpensearchService::global();
+    match service
+        .delete_opensearch_index(&environment_id, &service_data, &index)
+        .await
+    {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "删除 OpenSearch 索引失败: {}",
+            e
+        ))),
+    }
+}
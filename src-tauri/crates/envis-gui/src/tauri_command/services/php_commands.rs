@@ -0,0 +1,258 @@
+use crate::tauri_command::system_info_commands::with_dependency_warning;
+use envis_core::manager::services::php::{ComposerService, PhpService};
+use envis_core::types::{CommandResponse, ServiceData, ServiceType};
+use std::collections::HashMap;
+
+#[tauri::command]
+pub async fn get_php_versions() -> Result<CommandResponse, String> {
+    let service = PhpService::global();
+    let versions = service.get_available_versions();
+    let data = serde_json::json!({ "versions": versions });
+    Ok(CommandResponse::success(
+        "获取 PHP 版本列表成功".to_string(),
+        Some(data),
+    ))
+}
+
+#[tauri::command]
+pub async fn download_php(version: String) -> Result<CommandResponse, String> {
+    let service = PhpService::global();
+    let response = match service.download_and_install(&version).await {
+        Ok(result) => {
+            let data = serde_json::json!({ "task": result.task });
+            if result.success {
+                Ok(CommandResponse::success(result.message, Some(data)))
+            } else {
+                Ok(CommandResponse::error(result.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("下载 PHP 失败: {}", e))),
+    };
+    response.map(|r| with_dependency_warning(&ServiceType::Php, r))
+}
+
+#[tauri::command]
+pub async fn cancel_download_php(version: String) -> Result<CommandResponse, String> {
+    let service = PhpService::global();
+    match service.cancel_download(&version) {
+        Ok(_) => {
+            crate::status_events::emit_download_status(&format!("php-{}", version), "cancelled", 0.0);
+            Ok(CommandResponse::success(
+                "PHP 下载已取消".to_string(),
+                Some(serde_json::json!({ "cancelled": true })),
+            ))
+        }
+        Err(e) => Ok(CommandResponse::error(format!(
+            "取消 PHP 下载失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn check_php_installed(version: String) -> Result<CommandResponse, String> {
+    let service = PhpService::global();
+    let installed = service.is_installed(&version);
+    Ok(CommandResponse::success(
+        "检查 PHP 安装状态成功".to_string(),
+        Some(serde_json::json!({ "installed": installed })),
+    ))
+}
+
+#[tauri::command]
+pub async fn get_php_download_progress(version: String) -> Result<CommandResponse, String> {
+    let service = PhpService::global();
+    let task = service.get_download_progress(&version);
+    Ok(CommandResponse::success(
+        "获取 PHP 下载进度成功".to_string(),
+        Some(serde_json::json!({ "task": task })),
+    ))
+}
+
+#[tauri::command]
+pub async fn start_php_fpm(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = PhpService::global();
+    match service.start_php_fpm(&environment_id, &service_data) {
+        Ok(res) => {
+            if res.success {
+                crate::status_events::emit_service_status(&environment_id, &service_data.id, "running");
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("启动 PHP-FPM 失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn stop_php_fpm(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = PhpService::global();
+    match service.stop_php_fpm(&environment_id, &service_data) {
+        Ok(res) => {
+            if res.success {
+                crate::status_events::emit_service_status(&environment_id, &service_data.id, "stopped");
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("停止 PHP-FPM 失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn get_php_fpm_status(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = PhpService::global();
+    match service.get_php_fpm_status(&environment_id, &service_data) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "获取 PHP-FPM 状态失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn get_php_config(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = PhpService::global();
+    match service.get_php_config(&environment_id, &service_data) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "获取 PHP 配置失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn set_php_config_value(
+    environment_id: String,
+    service_data: ServiceData,
+    key: String,
+    value: String,
+) -> Result<CommandResponse, String> {
+    let service = PhpService::global();
+    match service.set_php_config_value(&environment_id, &service_data, &key, &value) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "修改 PHP 配置失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn set_php_ini_values(
+    environment_id: String,
+    service_data: ServiceData,
+    values: HashMap<String, String>,
+) -> Result<CommandResponse, String> {
+    let service = PhpService::global();
+    match service.set_php_ini_values(&environment_id, &service_data, &values) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "修改 php.ini 失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 为指定 PHP 版本安装 Composer
+#[tauri::command]
+pub async fn install_composer(version: String) -> Result<CommandResponse, String> {
+    let service = ComposerService::global();
+    match service.install_composer(&version).await {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!("安装 Composer 失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn check_composer_installed(version: String) -> Result<CommandResponse, String> {
+    let service = ComposerService::global();
+    let installed = service.is_composer_installed(&version);
+    let composer_version = if installed {
+        service.get_composer_version(&version)
+    } else {
+        None
+    };
+    Ok(CommandResponse::success(
+        "检查 Composer 安装状态成功".to_string(),
+        Some(serde_json::json!({
+            "installed": installed,
+            "version": composer_version,
+        })),
+    ))
+}
+
+/// 设置 Composer 全局镜像，mirror 为预设名称（packagist、aliyun、tencent）或镜像地址
+#[tauri::command]
+pub async fn set_composer_mirror(version: String, mirror: String) -> Result<CommandResponse, String> {
+    let service = ComposerService::global();
+    match service.set_composer_mirror(&version, &mirror) {
+        Ok(res) if res.success => Ok(CommandResponse::success(res.message, res.data)),
+        Ok(res) => Ok(CommandResponse::error(res.message)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "设置 Composer 镜像失败: {}",
+            e
+        ))),
+    }
+}
+
+// 通用服务面板按 `{action}_{type}_service` 调用，以下命令转发到 PHP-FPM
+
+#[tauri::command]
+pub async fn start_php_service(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    start_php_fpm(environment_id, service_data).await
+}
+
+#[tauri::command]
+pub async fn stop_php_service(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    stop_php_fpm(environment_id, service_data).await
+}
+
+#[tauri::command]
+pub async fn restart_php_service(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = PhpService::global();
+    match service.restart_php_fpm(&environment_id, &service_data) {
+        Ok(res) => {
+            if res.success {
+                crate::status_events::emit_service_status(&environment_id, &service_data.id, "running");
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("重启 PHP-FPM 失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn get_php_service_status(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    get_php_fpm_status(environment_id, service_data).await
+}
@@ -1,7 +1,9 @@
+use crate::tauri_command::system_info_commands::with_dependency_warning;
 use chrono::Utc;
 use envis_core::manager::env_serv_data_manager::EnvServDataManager;
-use envis_core::manager::services::postgresql::PostgresqlService;
-use envis_core::types::{CommandResponse, ServiceData};
+use envis_core::manager::services::postgresql::{DumpFormat, PostgresqlService};
+use envis_core::types::{CommandResponse, ServiceData, ServiceType};
+use envis_core::utils::run_blocking;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
@@ -25,9 +27,10 @@ fn persist_postgresql_metadata(
 
     if let Some(super_password) = super_password {
         metadata.insert(
-            "POSTGRESQL_SUPER_PASSWORD".to_string(),
+            "POSTGRESQL_PASSWORD".to_string(),
             serde_json::Value::String(super_password),
         );
+        metadata.remove("POSTGRESQL_SUPER_PASSWORD");
     }
 
     if metadata.is_empty() {
@@ -76,7 +79,7 @@ pub async fn get_postgresql_versions() -> Result<CommandResponse, String> {
 pub async fn download_postgresql(version: String) -> Result<CommandResponse, String> {
     log::info!("tauri::command 开始下载 PostgreSQL {}...", version);
     let postgresql_service = PostgresqlService::global();
-    match postgresql_service.download_and_install(&version).await {
+    let response = match postgresql_service.download_and_install(&version).await {
         Ok(result) => {
             let data = serde_json::json!({
                 "task": result.task
@@ -91,7 +94,8 @@ pub async fn download_postgresql(version: String) -> Result<CommandResponse, Str
             "下载 PostgreSQL 失败: {}",
             e
         ))),
-    }
+    };
+    response.map(|r| with_dependency_warning(&ServiceType::Postgresql, r))
 }
 
 /// 取消 PostgreSQL 下载的 Tauri 命令
@@ -248,11 +252,15 @@ pub async fn start_postgresql_service(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let postgresql_service = PostgresqlService::global();
-    match postgresql_service.start_service(&environment_id, &service_data) {
+    let service_id = service_data.id.clone();
+    let task = run_blocking({
+        let environment_id = environment_id.clone();
+        move || PostgresqlService::global().start_service(&environment_id, &service_data)
+    });
+    match task.await {
         Ok(result) => {
             if result.success {
-                crate::status_events::emit_service_status(&environment_id, &service_data.id, "running");
+                crate::status_events::emit_service_status(&environment_id, &service_id, "running");
                 Ok(CommandResponse::success(result.message, result.data))
             } else {
                 Ok(CommandResponse::error(result.message))
@@ -271,11 +279,15 @@ pub async fn stop_postgresql_service(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let postgresql_service = PostgresqlService::global();
-    match postgresql_service.stop_service(&environment_id, &service_data) {
+    let service_id = service_data.id.clone();
+    let task = run_blocking({
+        let environment_id = environment_id.clone();
+        move || PostgresqlService::global().stop_service(&environment_id, &service_data)
+    });
+    match task.await {
         Ok(result) => {
             if result.success {
-                crate::status_events::emit_service_status(&environment_id, &service_data.id, "stopped");
+                crate::status_events::emit_service_status(&environment_id, &service_id, "stopped");
                 Ok(CommandResponse::success(result.message, result.data))
             } else {
                 Ok(CommandResponse::error(result.message))
@@ -294,11 +306,15 @@ pub async fn restart_postgresql_service(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let postgresql_service = PostgresqlService::global();
-    match postgresql_service.restart_service(&environment_id, &service_data) {
+    let service_id = service_data.id.clone();
+    let task = run_blocking({
+        let environment_id = environment_id.clone();
+        move || PostgresqlService::global().restart_service(&environment_id, &service_data)
+    });
+    match task.await {
         Ok(result) => {
             if result.success {
-                crate::status_events::emit_service_status(&environment_id, &service_data.id, "running");
+                crate::status_events::emit_service_status(&environment_id, &service_id, "running");
                 Ok(CommandResponse::success(result.message, result.data))
             } else {
                 Ok(CommandResponse::error(result.message))
@@ -343,15 +359,21 @@ pub async fn initialize_postgresql(
     bind_address: Option<String>,
     reset: Option<bool>,
 ) -> Result<CommandResponse, String> {
-    let postgresql_service = PostgresqlService::global();
-    match postgresql_service.initialize_service(
-        &environment_id,
-        &service_data,
-        super_password.clone(),
-        port,
-        bind_address,
-        reset.unwrap_or(false),
-    ) {
+    let task = run_blocking({
+        let environment_id = environment_id.clone();
+        let service_data = service_data.clone();
+        move || {
+            PostgresqlService::global().initialize_service(
+                &environment_id,
+                &service_data,
+                super_password,
+                port,
+                bind_address,
+                reset.unwrap_or(false),
+            )
+        }
+    });
+    match task.await {
         Ok(result) => {
             if result.success {
                 if let Some(data) = result.data.as_ref() {
@@ -363,6 +385,17 @@ pub async fn initialize_postgresql(
                         .get("superPassword")
                         .and_then(|v| v.as_str())
                         .map(ToString::to_string);
+                    let metadata = service_data.metadata.get_or_insert_with(HashMap::new);
+                    for (key, field) in [
+                        ("POSTGRESQL_DATA", "dataPath"),
+                        ("POSTGRESQL_LOG", "logPath"),
+                        ("POSTGRESQL_PORT", "port"),
+                        ("POSTGRESQL_SUPERUSER", "superuser"),
+                    ] {
+                        if let Some(value) = data.get(field).cloned() {
+                            metadata.insert(key.to_string(), value);
+                        }
+                    }
                     persist_postgresql_metadata(
                         &environment_id,
                         &mut service_data,
@@ -388,8 +421,10 @@ pub async fn check_postgresql_initialized(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let postgresql_service = PostgresqlService::global();
-    match postgresql_service.check_initialized(&environment_id, &service_data) {
+    let task = run_blocking(move || {
+        PostgresqlService::global().check_initialized(&environment_id, &service_data)
+    });
+    match task.await {
         Ok(result) => Ok(CommandResponse::success(result.message, result.data)),
         Err(e) => Ok(CommandResponse::error(format!(
             "检查 PostgreSQL 初始化状态失败: {}",
@@ -404,8 +439,10 @@ pub async fn list_postgresql_databases(
     environment_id: String,
     service_data: ServiceData,
 ) -> Result<CommandResponse, String> {
-    let postgresql_service = PostgresqlService::global();
-    match postgresql_service.list_databases(&environment_id, &service_data) {
+    let task = run_blocking(move || {
+        PostgresqlService::global().list_databases(&environment_id, &service_data)
+    });
+    match task.await {
         Ok(result) => Ok(CommandResponse::success(result.message, result.data)),
         Err(e) => Ok(CommandResponse::error(format!("列出数据库失败: {}", e))),
     }
@@ -515,3 +552,40 @@ pub async fn update_postgresql_role_grants(
         Err(e) => Ok(CommandResponse::error(format!("更新角色权限失败: {}", e))),
     }
 }
+
+/// 使用 pg_dump 导出 PostgreSQL 数据库
+#[tauri::command]
+pub async fn dump_postgresql_database(
+    environment_id: String,
+    service_data: ServiceData,
+    database_name: String,
+    output_path: String,
+    format: Option<DumpFormat>,
+) -> Result<CommandResponse, String> {
+    let postgresql_service = PostgresqlService::global();
+    match postgresql_service.dump_database(
+        &environment_id,
+        &service_data,
+        database_name,
+        output_path,
+        format.unwrap_or(DumpFormat::Custom),
+    ) {
+        Ok(result) => Ok(CommandResponse::success(result.message, result.data)),
+        Err(e) => Ok(CommandResponse::error(format!("导出数据库失败: {}", e))),
+    }
+}
+
+/// 恢复 PostgreSQL 数据库（自动识别 pg_dump 导出格式）
+#[tauri::command]
+pub async fn restore_postgresql_database(
+    environment_id: String,
+    service_data: ServiceData,
+    database_name: String,
+    backup_path: String,
+) -> Result<CommandResponse, String> {
+    let postgresql_service = PostgresqlService::global();
+    match postgresql_service.restore_database(&environment_id, &service_data, database_name, backup_path) {
+        Ok(result) => Ok(CommandResponse::success(result.message, result.data)),
+        Err(e) => Ok(CommandResponse::error(format!("恢复数据库失败: {}", e))),
+    }
+}
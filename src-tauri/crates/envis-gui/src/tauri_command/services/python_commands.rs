@@ -1,6 +1,9 @@
+use crate::tauri_command::system_info_commands::with_dependency_warning;
 use envis_core::manager::env_serv_data_manager::EnvServDataManager;
 use envis_core::manager::services::python::{PythonInstallMode, PythonService};
-use envis_core::types::{CommandResponse, ServiceData};
+use envis_core::types::{CommandResponse, ServiceData, ServiceType};
+use envis_core::utils::run_blocking;
+use tauri::AppHandle;
 
 /// 检查 Python 是否已安装的 Tauri 命令
 #[tauri::command]
@@ -59,7 +62,7 @@ pub async fn download_python(
     );
 
     let python_service = PythonService::global();
-    match python_service
+    let response = match python_service
         .download_and_install_with_mode(&version, install_mode)
         .await
     {
@@ -74,7 +77,8 @@ pub async fn download_python(
             }
         }
         Err(e) => Ok(CommandResponse::error(format!("下载 Python 失败: {}", e))),
-    }
+    };
+    response.map(|r| with_dependency_warning(&ServiceType::Python, r))
 }
 
 /// 取消 Python 下载的 Tauri 命令
@@ -316,3 +320,112 @@ pub async fn open_python_venv_terminal(
         Err(e) => Ok(CommandResponse::error(format!("打开终端失败: {}", e))),
     }
 }
+
+/// 在 venv 中安装 pip 包，安装输出通过 pip-install-progress 事件推送
+#[tauri::command]
+pub async fn install_python_packages(
+    app_handle: AppHandle,
+    environment_id: String,
+    service_data: ServiceData,
+    venv_name: String,
+    packages: Vec<String>,
+) -> Result<CommandResponse, String> {
+    let python_service = PythonService::global();
+    let emit_progress = {
+        use tauri::Emitter;
+        let handle = app_handle.clone();
+        let venv_name = venv_name.clone();
+        move |stream: &str, line: &str| {
+            let _ = handle.emit(
+                "pip-install-progress",
+                serde_json::json!({
+                    "venvName": venv_name,
+                    "stream": stream,
+                    "line": line,
+                }),
+            );
+        }
+    };
+    match python_service.install_packages(
+        emit_progress,
+        &environment_id,
+        &service_data,
+        &venv_name,
+        packages.clone(),
+    ) {
+        Ok(_) => Ok(CommandResponse::success(
+            "安装包成功".to_string(),
+            Some(serde_json::json!({ "packages": packages })),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("安装包失败: {}", e))),
+    }
+}
+
+/// 获取 venv 中已安装的 pip 包
+#[tauri::command]
+pub async fn list_python_installed_packages(
+    environment_id: String,
+    service_data: ServiceData,
+    venv_name: String,
+) -> Result<CommandResponse, String> {
+    let python_service = PythonService::global();
+    match python_service.list_installed_packages(&environment_id, &service_data, &venv_name) {
+        Ok(packages) => Ok(CommandResponse::success(
+            "获取已安装包成功".to_string(),
+            Some(serde_json::json!({ "packages": packages })),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("获取已安装包失败: {}", e))),
+    }
+}
+
+/// 升级 Python 安装目录中的 pip
+#[tauri::command]
+pub async fn upgrade_python_pip(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let task =
+        run_blocking(move || PythonService::global().upgrade_pip(&environment_id, &service_data));
+    match task.await {
+        Ok(result) => Ok(CommandResponse::success(
+            "pip 升级成功".to_string(),
+            serde_json::to_value(result).ok(),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("升级 pip 失败: {}", e))),
+    }
+}
+
+/// 升级指定 venv 中的 pip
+#[tauri::command]
+pub async fn upgrade_python_pip_in_venv(
+    environment_id: String,
+    service_data: ServiceData,
+    venv_name: String,
+) -> Result<CommandResponse, String> {
+    let task = run_blocking(move || {
+        PythonService::global().upgrade_pip_in_venv(&environment_id, &service_data, &venv_name)
+    });
+    match task.await {
+        Ok(result) => Ok(CommandResponse::success(
+            "pip 升级成功".to_string(),
+            serde_json::to_value(result).ok(),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("升级 pip 失败: {}", e))),
+    }
+}
+
+/// 获取 Python 服务概要信息（版本、pip、OpenSSL、venv 数量）
+#[tauri::command]
+pub async fn get_python_info(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let python_service = PythonService::global();
+    match python_service.get_python_info(&environment_id, &service_data) {
+        Ok(info) => Ok(CommandResponse::success(
+            "获取 Python 信息成功".to_string(),
+            serde_json::to_value(info).ok(),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("获取 Python 信息失败: {}", e))),
+    }
+}
@@ -0,0 +1,211 @@
+use crate::tauri_command::system_info_commands::with_dependency_warning;
+use envis_core::manager::services::rabbitmq::RabbitmqService;
+use envis_core::types::{CommandResponse, ServiceData, ServiceType};
+
+#[tauri::command]
+pub async fn get_rabbitmq_versions() -> Result<CommandResponse, String> {
+    let service = RabbitmqService::global();
+    let versions = service.get_available_versions();
+    let data = serde_json::json!({ "versions": versions });
+    Ok(CommandResponse::success(
+        "获取 RabbitMQ 版本列表成功".to_string(),
+        Some(data),
+    ))
+}
+
+#[tauri::command]
+pub async fn download_rabbitmq(version: String) -> Result<CommandResponse, String> {
+    let service = RabbitmqService::global();
+    let response = match service.download_and_install(&version).await {
+        Ok(result) => {
+            let data = serde_json::json!({ "task": result.task });
+            if result.success {
+                Ok(CommandResponse::success(result.message, Some(data)))
+            } else {
+                Ok(CommandResponse::error(result.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("下载 RabbitMQ 失败: {}", e))),
+    };
+    response.map(|r| with_dependency_warning(&ServiceType::Rabbitmq, r))
+}
+
+#[tauri::command]
+pub async fn cancel_download_rabbitmq(version: String) -> Result<CommandResponse, String> {
+    let service = RabbitmqService::global();
+    match service.cancel_download(&version) {
+        Ok(_) => {
+            crate::status_events::emit_download_status(
+                &format!("rabbitmq-{}", version),
+                "cancelled",
+                0.0,
+            );
+            Ok(CommandResponse::success(
+                "RabbitMQ 下载已取消".to_string(),
+                Some(serde_json::json!({ "cancelled": true })),
+            ))
+        }
+        Err(e) => Ok(CommandResponse::error(format!(
+            "取消 RabbitMQ 下载失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn check_rabbitmq_installed(version: String) -> Result<CommandResponse, String> {
+    let service = RabbitmqService::global();
+    let installed = service.is_installed(&version);
+    Ok(CommandResponse::success(
+        "检查 RabbitMQ 安装状态成功".to_string(),
+        Some(serde_json::json!({ "installed": installed })),
+    ))
+}
+
+#[tauri::command]
+pub async fn get_rabbitmq_download_progress(version: String) -> Result<CommandResponse, String> {
+    let service = RabbitmqService::global();
+    let task = service.get_download_progress(&version);
+    Ok(CommandResponse::success(
+        "获取 RabbitMQ 下载进度成功".to_string(),
+        Some(serde_json::json!({ "task": task })),
+    ))
+}
+
+#[tauri::command]
+pub async fn start_rabbitmq_service(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = RabbitmqService::global();
+    match service.start_service(&environment_id, &service_data) {
+        Ok(res) => {
+            if res.success {
+                crate::status_events::emit_service_status(
+                    &environment_id,
+                    &service_data.id,
+                    "running",
+                );
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("启动 RabbitMQ 失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn stop_rabbitmq_service(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = RabbitmqService::global();
+    match service.stop_service(&environment_id, &service_data) {
+        Ok(res) => {
+            if res.success {
+                crate::status_events::emit_service_status(
+                    &environment_id,
+                    &service_data.id,
+                    "stopped",
+                );
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("停止 RabbitMQ 失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn restart_rabbitmq_service(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = RabbitmqService::global();
+    match service.restart_service(&environment_id, &service_data) {
+        Ok(res) => {
+            if res.success {
+                crate::status_events::emit_service_status(
+                    &environment_id,
+                    &service_data.id,
+                    "running",
+                );
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("重启 RabbitMQ 失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn get_rabbitmq_service_status(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = RabbitmqService::global();
+    match service.get_service_status(&environment_id, &service_data) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "获取 RabbitMQ 状态失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn enable_rabbitmq_management(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = RabbitmqService::global();
+    match service.enable_rabbitmq_management(&environment_id, &service_data) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "启用 RabbitMQ 管理插件失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn list_rabbitmq_queues(
+    environment_id: String,
+    service_data: ServiceData,
+    vhost: Option<String>,
+) -> Result<CommandResponse, String> {
+    let service = RabbitmqService::global();
+    match service
+        .list_rabbitmq_queues(&environment_id, &service_data, vhost.as_deref())
+        .await
+    {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "获取 RabbitMQ 队列列表失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_rabbitmq_queue(
+    environment_id: String,
+    service_data: ServiceData,
+    vhost: String,
+    queue: String,
+) -> Result<CommandResponse, String> {
+    let service = RabbitmqService::global();
+    match service
+        .delete_rabbitmq_queue(&environment_id, &service_data, &vhost, &queue)
+        .await
+    {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "删除 RabbitMQ 队列失败: {}",
+            e
+        ))),
+    }
+}
@@ -1,5 +1,6 @@
+use crate::tauri_command::system_info_commands::with_dependency_warning;
 use envis_core::manager::services::redis::RedisService;
-use envis_core::types::{CommandResponse, ServiceData};
+use envis_core::types::{CommandResponse, ServiceData, ServiceType};
 
 #[tauri::command]
 pub async fn get_redis_versions() -> Result<CommandResponse, String> {
@@ -15,7 +16,7 @@ pub async fn get_redis_versions() -> Result<CommandResponse, String> {
 #[tauri::command]
 pub async fn download_redis(version: String) -> Result<CommandResponse, String> {
     let service = RedisService::global();
-    match service.download_and_install(&version).await {
+    let response = match service.download_and_install(&version).await {
         Ok(result) => {
             let data = serde_json::json!({ "task": result.task });
             if result.success {
@@ -25,7 +26,8 @@ pub async fn download_redis(version: String) -> Result<CommandResponse, String>
             }
         }
         Err(e) => Ok(CommandResponse::error(format!("下载 Redis 失败: {}", e))),
-    }
+    };
+    response.map(|r| with_dependency_warning(&ServiceType::Redis, r))
 }
 
 #[tauri::command]
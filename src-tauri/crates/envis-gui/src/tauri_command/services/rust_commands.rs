@@ -1,6 +1,7 @@
+use crate::tauri_command::system_info_commands::with_dependency_warning;
 use envis_core::manager::env_serv_data_manager::EnvServDataManager;
 use envis_core::manager::services::rust::RustService;
-use envis_core::types::{CommandResponse, ServiceData};
+use envis_core::types::{CommandResponse, ServiceData, ServiceType};
 
 /// 检查 Rust 是否已安装的 Tauri 命令
 #[tauri::command]
@@ -38,7 +39,7 @@ pub async fn download_rust(version: String) -> Result<CommandResponse, String> {
     log::info!("tauri::command 开始下载 Rust {}...", version);
     let rust_service = RustService::global();
 
-    match rust_service.download_and_install(&version).await {
+    let response = match rust_service.download_and_install(&version).await {
         Ok(result) => {
             let data = serde_json::json!({
                 "task": result.task
@@ -50,7 +51,8 @@ pub async fn download_rust(version: String) -> Result<CommandResponse, String> {
             }
         }
         Err(e) => Ok(CommandResponse::error(format!("下载 Rust 失败: {}", e))),
-    }
+    };
+    response.map(|r| with_dependency_warning(&ServiceType::Rust, r))
 }
 
 /// 取消 Rust 下载的 Tauri 命令
@@ -130,3 +132,17 @@ pub async fn set_cargo_home(
         Err(e) => Ok(CommandResponse::error(format!("设置 CARGO_HOME 失败: {}", e))),
     }
 }
+
+/// 设置 crates.io 镜像
+#[tauri::command]
+pub async fn set_cargo_registry_mirror(
+    environment_id: String,
+    service_data: ServiceData,
+    mirror: String,
+) -> Result<CommandResponse, String> {
+    let rust_service = RustService::global();
+    match rust_service.set_cargo_registry_mirror(&environment_id, &service_data, &mirror) {
+        Ok(result) => Ok(CommandResponse::success(result.message, result.data)),
+        Err(e) => Ok(CommandResponse::error(format!("设置 Cargo 镜像失败: {}", e))),
+    }
+}
@@ -1,5 +1,6 @@
 use envis_core::manager::services::ssl::{CAConfig, SslService};
 use envis_core::types::{CommandResponse, ServiceData};
+use envis_core::utils::run_blocking;
 
 /// 检查 CA 是否已初始化
 #[tauri::command]
@@ -47,8 +48,10 @@ pub async fn initialize_ca(
         validity_days,
     };
 
-    let ssl_service = SslService::global();
-    match ssl_service.initialize_ca(&environment_id, &service_data, ca_config) {
+    let task = run_blocking(move || {
+        SslService::global().initialize_ca(&environment_id, &service_data, ca_config)
+    });
+    match task.await {
         Ok(result) => Ok(CommandResponse::success(result.message, result.data)),
         Err(e) => Ok(CommandResponse::error(format!("初始化 CA 失败: {}", e))),
     }
@@ -78,14 +81,16 @@ pub async fn issue_certificate(
 ) -> Result<CommandResponse, String> {
     log::info!("开始签发证书: {}", domain);
 
-    let ssl_service = SslService::global();
-    match ssl_service.issue_certificate(
-        &environment_id,
-        &service_data,
-        domain,
-        subject_alt_names,
-        validity_days,
-    ) {
+    let task = run_blocking(move || {
+        SslService::global().issue_certificate(
+            &environment_id,
+            &service_data,
+            domain,
+            subject_alt_names,
+            validity_days,
+        )
+    });
+    match task.await {
         Ok(result) => Ok(CommandResponse::success(result.message, result.data)),
         Err(e) => Ok(CommandResponse::error(format!("签发证书失败: {}", e))),
     }
@@ -145,3 +150,43 @@ pub async fn check_ca_installed(environment_id: String) -> Result<CommandRespons
         ))),
     }
 }
+
+/// 将 CA 证书安装到系统信任存储（macOS / Linux 需要管理员密码）
+#[tauri::command]
+pub async fn install_ca_to_system_trust(
+    environment_id: String,
+    service_data_id: String,
+    password: Option<String>,
+) -> Result<CommandResponse, String> {
+    let task = run_blocking(move || {
+        SslService::global().install_ca_to_system_trust(
+            &environment_id,
+            &service_data_id,
+            password.as_deref(),
+        )
+    });
+    match task.await {
+        Ok(result) => Ok(CommandResponse::success(result.message, result.data)),
+        Err(e) => Ok(CommandResponse::error(format!("安装 CA 证书失败: {}", e))),
+    }
+}
+
+/// 从系统信任存储中移除 CA 证书
+#[tauri::command]
+pub async fn uninstall_ca_from_system_trust(
+    environment_id: String,
+    service_data_id: String,
+    password: Option<String>,
+) -> Result<CommandResponse, String> {
+    let task = run_blocking(move || {
+        SslService::global().uninstall_ca_from_system_trust(
+            &environment_id,
+            &service_data_id,
+            password.as_deref(),
+        )
+    });
+    match task.await {
+        Ok(result) => Ok(CommandResponse::success(result.message, result.data)),
+        Err(e) => Ok(CommandResponse::error(format!("移除 CA 证书失败: {}", e))),
+    }
+}
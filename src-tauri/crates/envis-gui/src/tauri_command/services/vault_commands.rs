@@ -0,0 +1,239 @@
+use crate::tauri_command::system_info_commands::with_dependency_warning;
+use envis_core::manager::services::vault::VaultService;
+use envis_core::types::{CommandResponse, ServiceData, ServiceType};
+
+#[tauri::command]
+pub async fn get_vault_versions() -> Result<CommandResponse, String> {
+    let service = VaultService::global();
+    let versions = service.get_available_versions();
+    let data = serde_json::json!({ "versions": versions });
+    Ok(CommandResponse::success(
+        "获取 Vault 版本列表成功".to_string(),
+        Some(data),
+    ))
+}
+
+#[tauri::command]
+pub async fn download_vault(version: String) -> Result<CommandResponse, String> {
+    let service = VaultService::global();
+    let response = match service.download_and_install(&version).await {
+        Ok(result) => {
+            let data = serde_json::json!({ "task": result.task });
+            if result.success {
+                Ok(CommandResponse::success(result.message, Some(data)))
+            } else {
+                Ok(CommandResponse::error(result.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("下载 Vault 失败: {}", e))),
+    };
+    response.map(|r| with_dependency_warning(&ServiceType::Vault, r))
+}
+
+#[tauri::command]
+pub async fn cancel_download_vault(version: String) -> Result<CommandResponse, String> {
+    let service = VaultService::global();
+    match service.cancel_download(&version) {
+        Ok(_) => {
+            crate::status_events::emit_download_status(
+                &format!("vault-{}", version),
+                "cancelled",
+                0.0,
+            );
+            Ok(CommandResponse::success(
+                "Vault 下载已取消".to_string(),
+                Some(serde_json::json!({ "cancelled": true })),
+            ))
+        }
+        Err(e) => Ok(CommandResponse::error(format!(
+            "取消 Vault 下载失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn check_vault_installed(version: String) -> Result<CommandResponse, String> {
+    let service = VaultService::global();
+    let installed = service.is_installed(&version);
+    Ok(CommandResponse::success(
+        "检查 Vault 安装状态成功".to_string(),
+        Some(serde_json::json!({ "installed": installed })),
+    ))
+}
+
+#[tauri::command]
+pub async fn get_vault_download_progress(version: String) -> Result<CommandResponse, String> {
+    let service = VaultService::global();
+    let task = service.get_download_progress(&version);
+    Ok(CommandResponse::success(
+        "获取 Vault 下载进度成功".to_string(),
+        Some(serde_json::json!({ "task": task })),
+    ))
+}
+
+#[tauri::command]
+pub async fn start_vault_service(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = VaultService::global();
+    match service.start_service(&environment_id, &service_data) {
+        Ok(res) => {
+            if res.success {
+                crate::status_events::emit_service_status(
+                    &environment_id,
+                    &service_data.id,
+                    "running",
+                );
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("启动 Vault 失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn stop_vault_service(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = VaultService::global();
+    match service.stop_service(&environment_id, &service_data) {
+        Ok(res) => {
+            if res.success {
+                crate::status_events::emit_service_status(
+                    &environment_id,
+                    &service_data.id,
+                    "stopped",
+                );
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("停止 Vault 失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn restart_vault_service(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = VaultService::global();
+    match service.restart_service(&environment_id, &service_data) {
+        Ok(res) => {
+            if res.success {
+                crate::status_events::emit_service_status(
+                    &environment_id,
+                    &service_data.id,
+                    "running",
+                );
+                Ok(CommandResponse::success(res.message, res.data))
+            } else {
+                Ok(CommandResponse::error(res.message))
+            }
+        }
+        Err(e) => Ok(CommandResponse::error(format!("重启 Vault 失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn get_vault_service_status(
+    environment_id: String,
+    service_data: ServiceData,
+) -> Result<CommandResponse, String> {
+    let service = VaultService::global();
+    match service.get_service_status(&environment_id, &service_data) {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "获取 Vault 状态失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn list_vault_secrets(
+    environment_id: String,
+    service_data: ServiceData,
+    path: String,
+) -> Result<CommandResponse, String> {
+    let service = VaultService::global();
+    match service
+        .list_vault_secrets(&environment_id, &service_data, &path)
+        .await
+    {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "获取 Vault 密钥列表失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn read_vault_secret(
+    environment_id: String,
+    service_data: ServiceData,
+    path: String,
+    key: String,
+) -> Result<CommandResponse, String> {
+    let service = VaultService::global();
+    match service
+        .read_vault_secret(&environment_id, &service_data, &path, &key)
+        .await
+    {
+        Ok(res) if res.success => Ok(CommandResponse::success(res.message, res.data)),
+        Ok(res) => Ok(CommandResponse::error(res.message)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "读取 Vault 密钥失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn write_vault_secret(
+    environment_id: String,
+    service_data: ServiceData,
+    path: String,
+    key: String,
+    value: String,
+) -> Result<CommandResponse, String> {
+    let service = VaultService::global();
+    match service
+        .write_vault_secret(&environment_id, &service_data, &path, &key, &value)
+        .await
+    {
+        Ok(res) => Ok(CommandResponse::success(res.message, res.data)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "写入 Vault 密钥失败: {}",
+            e
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_vault_secret(
+    environment_id: String,
+    service_data: ServiceData,
+    path: String,
+    key: String,
+) -> Result<CommandResponse, String> {
+    let service = VaultService::global();
+    match service
+        .delete_vault_secret(&environment_id, &service_data, &path, &key)
+        .await
+    {
+        Ok(res) if res.success => Ok(CommandResponse::success(res.message, res.data)),
+        Ok(res) => Ok(CommandResponse::error(res.message)),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "删除 Vault 密钥失败: {}",
+            e
+        ))),
+    }
+}
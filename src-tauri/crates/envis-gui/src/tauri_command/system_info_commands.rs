@@ -2,7 +2,12 @@ use anyhow::Result;
 use serde_json::Value;
 use tauri::{AppHandle, Manager};
 
+use envis_core::manager::environment_manager::EnvironmentManager;
+use envis_core::manager::service_manager::ServiceManager;
+use envis_core::manager::shell_manamger::ShellManager;
 use envis_core::manager::system_info_manager::SystemInfoManager;
+use envis_core::types::{CommandResponse, ServiceType};
+use envis_core::utils::run_blocking;
 
 /// 获取系统信息
 #[tauri::command]
@@ -22,7 +27,6 @@ pub async fn get_system_info() -> Result<Value, String> {
 #[tauri::command]
 pub async fn open_terminal() -> Result<Value, String> {
     use envis_core::manager::app_config_manager::AppConfigManager;
-    use envis_core::manager::shell_manamger::ShellManager;
 
     // 获取配置的终端类型
     let app_config_manager = AppConfigManager::global();
@@ -44,6 +48,287 @@ pub async fn open_terminal() -> Result<Value, String> {
     }
 }
 
+/// 列出 Envis 管理的 Shell 配置文件及其状态
+#[tauri::command]
+pub async fn list_shell_config_files() -> Result<CommandResponse, String> {
+    let files = match ShellManager::global().lock() {
+        Ok(shell_manager) => shell_manager.get_config_file_info(),
+        Err(e) => {
+            return Ok(CommandResponse::error(format!(
+                "获取 Shell 管理器锁失败: {}",
+                e
+            )))
+        }
+    };
+
+    match serde_json::to_value(files) {
+        Ok(data) => Ok(CommandResponse::success(
+            "获取 Shell 配置文件列表成功".to_string(),
+            Some(data),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "序列化 Shell 配置文件列表失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 检查 Shell 配置文件中的 Envis 环境块，repair 为 true 时备份并重建
+#[tauri::command]
+pub async fn verify_and_repair_shell_config(repair: bool) -> Result<CommandResponse, String> {
+    let result = run_blocking(move || {
+        let manager = EnvironmentManager::global();
+        let manager = manager.lock().unwrap();
+        manager.verify_and_repair_shell_config(repair)
+    })
+    .await;
+
+    match result {
+        Ok(report) => {
+            let message = if report.repaired {
+                "Shell 配置文件已修复"
+            } else if report.healthy {
+                "Shell 配置文件正常"
+            } else {
+                "Shell 配置文件存在问题"
+            };
+            Ok(CommandResponse::success(
+                message.to_string(),
+                serde_json::to_value(report).ok(),
+            ))
+        }
+        Err(e) => Ok(CommandResponse::error(format!(
+            "检查 Shell 配置文件失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 检查 Shell 配置文件中环境块 BEGIN / END 标记的完整性，repair 为 true 时先修复
+#[tauri::command]
+pub async fn verify_shell_integrity(repair: bool) -> Result<CommandResponse, String> {
+    let result = run_blocking(move || {
+        let repaired = if repair {
+            let manager = EnvironmentManager::global();
+            let manager = manager.lock().unwrap();
+            manager.repair_shell_env_blocks()?
+        } else {
+            Vec::new()
+        };
+
+        let shell_manager = ShellManager::global();
+        let shell_manager = shell_manager.lock().unwrap();
+        Ok((shell_manager.verify_env_block_integrity()?, repaired))
+    })
+    .await;
+
+    match result {
+        Ok((files, repaired)) => Ok(CommandResponse::success(
+            if !repaired.is_empty() {
+                "Shell 配置文件环境块已修复".to_string()
+            } else {
+                "Shell 配置文件环境块检查完成".to_string()
+            },
+            Some(serde_json::json!({ "files": files, "repaired": repaired })),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "检查 Shell 配置文件环境块失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 将所有 Shell 配置文件备份为 `<file>.envbak.<tag>`
+#[tauri::command]
+pub async fn backup_all_configs(tag: String) -> Result<CommandResponse, String> {
+    let result = match ShellManager::global().lock() {
+        Ok(shell_manager) => shell_manager.backup_all_configs(tag.trim()),
+        Err(e) => {
+            return Ok(CommandResponse::error(format!(
+                "获取 Shell 管理器锁失败: {}",
+                e
+            )))
+        }
+    };
+
+    match result {
+        Ok(backups) => Ok(CommandResponse::success(
+            "Shell 配置文件备份成功".to_string(),
+            Some(serde_json::json!({ "backups": backups })),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "备份 Shell 配置文件失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 从指定标签的备份恢复所有 Shell 配置文件
+#[tauri::command]
+pub async fn restore_all_configs(tag: String) -> Result<CommandResponse, String> {
+    let result = match ShellManager::global().lock() {
+        Ok(shell_manager) => shell_manager.restore_all_configs(&tag),
+        Err(e) => {
+            return Ok(CommandResponse::error(format!(
+                "获取 Shell 管理器锁失败: {}",
+                e
+            )))
+        }
+    };
+
+    match result {
+        Ok(restored) => Ok(CommandResponse::success(
+            "Shell 配置文件已恢复".to_string(),
+            Some(serde_json::json!({ "restored": restored })),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "恢复 Shell 配置文件失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 从备份恢复单个 Shell 配置文件，恢复前会备份当前内容，恢复后重建 Envis 环境块
+#[tauri::command]
+pub async fn restore_shell_config_backup(
+    config_path: String,
+    tag: String,
+) -> Result<CommandResponse, String> {
+    let result = run_blocking(move || {
+        let manager = EnvironmentManager::global();
+        let manager = manager.lock().unwrap();
+        manager.restore_shell_config_backup(&config_path, &tag)
+    })
+    .await;
+
+    match result {
+        Ok(restored) => Ok(CommandResponse::success(
+            "Shell 配置文件已恢复".to_string(),
+            Some(serde_json::json!({ "restored": restored })),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "恢复 Shell 配置文件失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 列出各 Shell 配置文件的可用备份
+#[tauri::command]
+pub async fn list_available_backups() -> Result<CommandResponse, String> {
+    let backups = match ShellManager::global().lock() {
+        Ok(shell_manager) => shell_manager.list_available_backups(),
+        Err(e) => {
+            return Ok(CommandResponse::error(format!(
+                "获取 Shell 管理器锁失败: {}",
+                e
+            )))
+        }
+    };
+
+    match serde_json::to_value(backups) {
+        Ok(data) => Ok(CommandResponse::success(
+            "获取 Shell 配置备份列表成功".to_string(),
+            Some(data),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "序列化 Shell 配置备份列表失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 统计各服务类型与各环境占用的磁盘空间
+#[tauri::command]
+pub async fn get_disk_usage() -> Result<CommandResponse, String> {
+    use envis_core::manager::app_config_manager::AppConfigManager;
+
+    let (services_folder, envs_folder) = match AppConfigManager::global().lock() {
+        Ok(manager) => (manager.get_services_folder(), manager.get_envs_folder()),
+        Err(e) => {
+            return Ok(CommandResponse::error(format!(
+                "获取应用配置管理器锁失败: {}",
+                e
+            )))
+        }
+    };
+
+    match SystemInfoManager::get_disk_usage(
+        std::path::Path::new(&services_folder),
+        std::path::Path::new(&envs_folder),
+    )
+    .await
+    {
+        Ok(usage) => Ok(CommandResponse::success(
+            "获取磁盘占用成功".to_string(),
+            serde_json::to_value(usage).ok(),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("获取磁盘占用失败: {}", e))),
+    }
+}
+
+/// 统计托管服务进程的 CPU / 内存 / 运行时长 / 监听端口，按环境与服务类型分组
+#[tauri::command]
+pub async fn get_managed_process_stats() -> Result<CommandResponse, String> {
+    let task = run_blocking(|| ServiceManager::global().get_managed_process_stats());
+
+    match task.await {
+        Ok(stats) => Ok(CommandResponse::success(
+            "获取服务进程资源占用成功".to_string(),
+            serde_json::to_value(stats).ok(),
+        )),
+        Err(e) => Ok(CommandResponse::error(format!(
+            "获取服务进程资源占用失败: {}",
+            e
+        ))),
+    }
+}
+
+/// 检查服务类型依赖的系统工具（tar、lsof、pgrep、pkill、openssl 等）是否存在
+#[tauri::command]
+pub async fn check_system_dependencies(
+    service_type: ServiceType,
+) -> Result<CommandResponse, String> {
+    let task = tokio::task::spawn_blocking(move || {
+        SystemInfoManager::global().check_system_dependencies(&service_type)
+    });
+
+    match task.await {
+        Ok(dependencies) => {
+            let missing = dependencies.iter().filter(|d| !d.found).count();
+            let message = if missing == 0 {
+                "系统依赖检查通过".to_string()
+            } else {
+                format!("缺少 {} 个系统依赖", missing)
+            };
+            Ok(CommandResponse::success(
+                message,
+                serde_json::to_value(dependencies).ok(),
+            ))
+        }
+        Err(e) => Ok(CommandResponse::error(format!("检查系统依赖失败: {}", e))),
+    }
+}
+
+/// 下载安装命令的响应中附加缺少系统工具的提示（data.warning）
+pub(crate) fn with_dependency_warning(
+    service_type: &ServiceType,
+    mut response: CommandResponse,
+) -> CommandResponse {
+    let Some(warning) = SystemInfoManager::global().missing_dependencies_warning(service_type)
+    else {
+        return response;
+    };
+    log::warn!("{}", warning);
+    match response.data.as_mut().and_then(|d| d.as_object_mut()) {
+        Some(data) => {
+            data.insert("warning".to_string(), Value::String(warning));
+        }
+        None => response.data = Some(serde_json::json!({ "warning": warning })),
+    }
+    response
+}
+
 /// 切换开发者工具
 #[tauri::command]
 pub async fn toggle_dev_tools(app_handle: AppHandle) -> Result<Value, String> {
@@ -1,19 +1,41 @@
+use envis_core::manager::app_config_manager::AppConfigManager;
+use envis_core::manager::env_serv_data_manager::EnvServDataManager;
+use envis_core::manager::environment_manager::EnvironmentManager;
+use envis_core::manager::service_manager::ServiceManager;
+use envis_core::types::{EnvironmentStatus, ServiceDataStatus};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     Manager, Runtime,
 };
 
+const TRAY_ID: &str = "main";
+/// 合并短时间内的多次重建请求
+const REBUILD_DEBOUNCE_MS: u64 = 200;
+
+/// 服务运行状态缓存（service_id -> status），由 status:service 事件更新
+static SERVICE_STATUS: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+/// 正在从托盘启动 / 重启的服务
+static STARTING_SERVICES: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+static REBUILD_PENDING: AtomicBool = AtomicBool::new(false);
+
+fn service_status() -> &'static Mutex<HashMap<String, String>> {
+    SERVICE_STATUS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn starting_services() -> &'static Mutex<HashSet<String>> {
+    STARTING_SERVICES.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
 /// 设置系统托盘图标
 pub fn setup_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<(), Box<dyn std::error::Error>> {
     // 创建托盘菜单
-    let show_item = MenuItem::with_id(app, "show", "显示主窗口", true, None::<&str>)?;
-    let hide_item = MenuItem::with_id(app, "hide", "隐藏窗口", true, None::<&str>)?;
-    let separator = tauri::menu::PredefinedMenuItem::separator(app)?;
-    let quit_item = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
-
-    let menu = Menu::with_items(app, &[&show_item, &hide_item, &separator, &quit_item])?;
+    let menu = build_tray_menu(app)?;
 
     // 加载自定义托盘图标
     let icon_bytes = include_bytes!("../../../icons/envis.png");
@@ -24,7 +46,7 @@ pub fn setup_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<(), Box<dyn s
     let icon = Image::new_owned(rgba.into_vec(), width, height);
 
     // 创建托盘图标
-    let _tray = TrayIconBuilder::new()
+    let _tray = TrayIconBuilder::with_id(TRAY_ID)
         .icon(icon)
         .icon_as_template(true) // 在 macOS 上启用模板模式，自动适应明暗主题
         .menu(&menu)
@@ -47,7 +69,7 @@ pub fn setup_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<(), Box<dyn s
                 log::info!("从托盘菜单退出应用");
                 app.exit(0);
             }
-            _ => {}
+            id => handle_dynamic_menu_event(app, id),
         })
         .on_tray_icon_event(|tray, event| {
             match event {
@@ -100,6 +122,220 @@ pub fn setup_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<(), Box<dyn s
     Ok(())
 }
 
+/// 构建托盘菜单：窗口控制、环境切换、激活服务的启停
+fn build_tray_menu<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<Menu<R>> {
+    let menu = Menu::new(app)?;
+    menu.append(&MenuItem::with_id(app, "show", "显示主窗口", true, None::<&str>)?)?;
+    menu.append(&MenuItem::with_id(app, "hide", "隐藏窗口", true, None::<&str>)?)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+
+    let environments = EnvironmentManager::global()
+        .lock()
+        .ok()
+        .and_then(|m| m.get_all_environments().ok())
+        .unwrap_or_default();
+
+    // 环境切换
+    let env_menu = Submenu::new(app, "切换环境", !environments.is_empty())?;
+    for env in &environments {
+        let active = env.status == EnvironmentStatus::Active;
+        env_menu.append(&CheckMenuItem::with_id(
+            app,
+            format!("env:{}", env.id),
+            &env.name,
+            true,
+            active,
+            None::<&str>,
+        )?)?;
+    }
+    menu.append(&env_menu)?;
+
+    // 激活环境中的常驻服务
+    let statuses = service_status().lock().unwrap().clone();
+    let starting = starting_services().lock().unwrap().clone();
+    let mut has_services = false;
+    for env in environments
+        .iter()
+        .filter(|e| e.status == EnvironmentStatus::Active)
+    {
+        let service_datas = EnvServDataManager::global()
+            .lock()
+            .ok()
+            .and_then(|m| m.get_environment_all_service_datas(&env.id).ok())
+            .unwrap_or_default();
+
+        for sd in service_datas.iter().filter(|sd| {
+            matches!(sd.status, ServiceDataStatus::Active)
+                && ServiceManager::is_daemon_service(&sd.service_type)
+        }) {
+            if !has_services {
+                menu.append(&PredefinedMenuItem::separator(app)?)?;
+                has_services = true;
+            }
+
+            let is_starting = starting.contains(&sd.id);
+            let status = statuses.get(&sd.id).map(String::as_str).unwrap_or("unknown");
+            let running = matches!(status, "running" | "degraded");
+            let status_label = if is_starting {
+                "启动中…"
+            } else {
+                match status {
+                    "running" => "运行中",
+                    "degraded" => "异常",
+                    "stopped" => "已停止",
+                    _ => "未知",
+                }
+            };
+
+            let submenu = Submenu::new(
+                app,
+                format!("{} {}（{}）", sd.name, sd.version, status_label),
+                true,
+            )?;
+            if is_starting {
+                submenu.append(&MenuItem::new(app, "启动中…", false, None::<&str>)?)?;
+            } else {
+                let id = format!("{}:{}", env.id, sd.id);
+                submenu.append(&MenuItem::with_id(app, format!("svc-start:{}", id), "启动", !running, None::<&str>)?)?;
+                submenu.append(&MenuItem::with_id(app, format!("svc-stop:{}", id), "停止", running, None::<&str>)?)?;
+                submenu.append(&MenuItem::with_id(app, format!("svc-restart:{}", id), "重启", running, None::<&str>)?)?;
+            }
+            menu.append(&submenu)?;
+        }
+    }
+
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?)?;
+    Ok(menu)
+}
+
+/// 处理环境切换和服务启停菜单项
+fn handle_dynamic_menu_event<R: Runtime>(app: &tauri::AppHandle<R>, id: &str) {
+    let Some((action, target)) = id.split_once(':') else {
+        return;
+    };
+
+    match action {
+        "env" => switch_environment(app, target.to_string()),
+        "svc-start" | "svc-stop" | "svc-restart" => {
+            if let Some((env_id, service_id)) = target.split_once(':') {
+                run_service_action(
+                    app,
+                    action.trim_start_matches("svc-").to_string(),
+                    env_id.to_string(),
+                    service_id.to_string(),
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 从托盘切换环境，复用 switch_environment_and_services 命令（含状态事件推送）
+fn switch_environment<R: Runtime>(app: &tauri::AppHandle<R>, environment_id: String) {
+    let already_active = EnvironmentManager::global()
+        .lock()
+        .ok()
+        .and_then(|m| m.get_all_environments().ok())
+        .map(|envs| {
+            envs.iter()
+                .any(|e| e.id == environment_id && e.status == EnvironmentStatus::Active)
+        })
+        .unwrap_or(false);
+    if already_active {
+        // CheckMenuItem 点击会切换勾选状态，重建以恢复
+        update_tray_menu(app);
+        return;
+    }
+
+    let deactivate_others = AppConfigManager::global()
+        .lock()
+        .map(|m| m.get_app_config().deactivate_other_environments_on_activate)
+        .unwrap_or(true);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        log::info!("从托盘切换环境: {}", environment_id);
+        match crate::tauri_command::environment_commands::switch_environment_and_services(
+            environment_id,
+            None,
+            deactivate_others,
+        )
+        .await
+        {
+            Ok(res) if !res.success => log::error!("托盘切换环境失败: {}", res.message),
+            Err(e) => log::error!("托盘切换环境失败: {}", e),
+            _ => {}
+        }
+        update_tray_menu(&app);
+    });
+}
+
+/// 从托盘启动 / 停止 / 重启服务，在后台线程执行避免阻塞托盘
+fn run_service_action<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    action: String,
+    environment_id: String,
+    service_id: String,
+) {
+    let service_data = match EnvServDataManager::global()
+        .lock()
+        .map_err(|e| e.to_string())
+        .and_then(|m| {
+            m.get_service_data(&environment_id, &service_id)
+                .map_err(|e| e.to_string())
+        }) {
+        Ok(sd) => sd,
+        Err(e) => {
+            log::error!("托盘获取服务数据失败: {}", e);
+            return;
+        }
+    };
+
+    let starts = action != "stop";
+    if starts {
+        starting_services().lock().unwrap().insert(service_id.clone());
+        update_tray_menu(app);
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let manager = ServiceManager::global();
+        let result = match action.as_str() {
+            "start" => manager.start_service(&environment_id, &service_data),
+            "stop" => manager.stop_service(&environment_id, &service_data),
+            _ => manager
+                .stop_service(&environment_id, &service_data)
+                .and_then(|_| manager.start_service(&environment_id, &service_data)),
+        };
+        let label = match action.as_str() {
+            "start" => "启动",
+            "stop" => "停止",
+            _ => "重启",
+        };
+        match result {
+            Ok(res) if res.success => log::info!("托盘{}服务 {} 成功", label, service_data.name),
+            Ok(res) => log::error!("托盘{}服务 {} 失败: {}", label, service_data.name, res.message),
+            Err(e) => log::error!("托盘{}服务 {} 失败: {}", label, service_data.name, e),
+        }
+
+        if starts {
+            starting_services().lock().unwrap().remove(&service_id);
+        }
+        update_tray_menu(&app);
+    });
+}
+
+/// 记录服务运行状态变化并重建托盘菜单
+pub fn on_service_status<R: Runtime>(app: &tauri::AppHandle<R>, service_id: &str, status: &str) {
+    service_status()
+        .lock()
+        .unwrap()
+        .insert(service_id.to_string(), status.to_string());
+    starting_services().lock().unwrap().remove(service_id);
+    update_tray_menu(app);
+}
+
 /// 更新托盘图标标题
 #[allow(dead_code)]
 pub fn update_tray_title<R: Runtime>(
@@ -113,12 +349,29 @@ pub fn update_tray_title<R: Runtime>(
 }
 
 /// 更新托盘菜单
-#[allow(dead_code)]
-pub fn update_tray_menu<R: Runtime>(
-    _app: &tauri::AppHandle<R>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // 可以在这里动态更新菜单项
-    // 例如：根据服务状态添加不同的菜单项
-    log::debug!("更新托盘菜单");
-    Ok(())
+///
+/// 在后台线程中合并重建，调用方可能持有管理器锁（如推送服务数据状态时）
+pub fn update_tray_menu<R: Runtime>(app: &tauri::AppHandle<R>) {
+    if REBUILD_PENDING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(REBUILD_DEBOUNCE_MS));
+        REBUILD_PENDING.store(false, Ordering::SeqCst);
+
+        log::debug!("更新托盘菜单");
+        let Some(tray) = app.tray_by_id(TRAY_ID) else {
+            return;
+        };
+        match build_tray_menu(&app) {
+            Ok(menu) => {
+                if let Err(e) = tray.set_menu(Some(menu)) {
+                    log::error!("更新托盘菜单失败: {}", e);
+                }
+            }
+            Err(e) => log::error!("构建托盘菜单失败: {}", e),
+        }
+    });
 }
@@ -1,4 +1,25 @@
-use tauri::{Manager, Runtime};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{Manager, PhysicalPosition, PhysicalSize, Runtime, WebviewWindow};
+
+const WINDOW_STATE_FILE: &str = "window_state.json";
+/// 合并拖动 / 缩放过程中的连续事件
+const SAVE_DEBOUNCE_MS: u64 = 500;
+
+static SAVE_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// 持久化的窗口尺寸与位置（物理像素）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WindowState {
+    width: u32,
+    height: u32,
+    x: i32,
+    y: i32,
+    maximized: bool,
+}
 
 /// 设置窗口事件处理器
 pub fn setup_window_events<R: Runtime>(
@@ -7,9 +28,12 @@ pub fn setup_window_events<R: Runtime>(
     if let Some(window) = app.get_webview_window("main") {
         let app_handle = app.clone();
 
+        // 恢复上次保存的窗口尺寸与位置
+        restore_window_state(&window);
+
         // 处理窗口关闭事件
-        window.on_window_event(move |event| {
-            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+        window.on_window_event(move |event| match event {
+            tauri::WindowEvent::CloseRequested { api, .. } => {
                 // 阻止窗口关闭，改为隐藏到托盘
                 api.prevent_close();
 
@@ -19,6 +43,10 @@ pub fn setup_window_events<R: Runtime>(
                     log::info!("窗口已最小化到系统托盘");
                 }
             }
+            tauri::WindowEvent::Resized(_) | tauri::WindowEvent::Moved(_) => {
+                schedule_save_window_state(&app_handle);
+            }
+            _ => {}
         });
 
         log::info!("窗口事件处理器已设置");
@@ -26,3 +54,122 @@ pub fn setup_window_events<R: Runtime>(
 
     Ok(())
 }
+
+fn window_state_path<R: Runtime>(app: &tauri::AppHandle<R>) -> Option<PathBuf> {
+    match app.path().app_data_dir() {
+        Ok(dir) => Some(dir.join(WINDOW_STATE_FILE)),
+        Err(e) => {
+            log::warn!("获取应用数据目录失败: {}", e);
+            None
+        }
+    }
+}
+
+fn load_window_state<R: Runtime>(app: &tauri::AppHandle<R>) -> Option<WindowState> {
+    let path = window_state_path(app)?;
+    let content = fs::read_to_string(&path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(state) => Some(state),
+        Err(e) => {
+            log::warn!("解析窗口状态文件失败 {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn restore_window_state<R: Runtime>(window: &WebviewWindow<R>) {
+    let Some(state) = load_window_state(window.app_handle()) else {
+        return;
+    };
+
+    if state.width > 0 && state.height > 0 {
+        if let Err(e) = window.set_size(PhysicalSize::new(state.width, state.height)) {
+            log::warn!("恢复窗口尺寸失败: {}", e);
+        }
+    }
+
+    // 显示器布局变化后保存的位置可能已不可见，此时保持默认位置
+    if is_position_visible(window, state.x, state.y) {
+        if let Err(e) = window.set_position(PhysicalPosition::new(state.x, state.y)) {
+            log::warn!("恢复窗口位置失败: {}", e);
+        }
+    }
+
+    if state.maximized {
+        if let Err(e) = window.maximize() {
+            log::warn!("恢复窗口最大化失败: {}", e);
+        }
+    }
+
+    log::debug!("已恢复窗口状态: {:?}", state);
+}
+
+fn is_position_visible<R: Runtime>(window: &WebviewWindow<R>, x: i32, y: i32) -> bool {
+    let Ok(monitors) = window.available_monitors() else {
+        return true;
+    };
+    monitors.iter().any(|monitor| {
+        let position = monitor.position();
+        let size = monitor.size();
+        x >= position.x
+            && y >= position.y
+            && x < position.x + size.width as i32
+            && y < position.y + size.height as i32
+    })
+}
+
+/// 延迟保存窗口状态，期间的多次 Resized / Moved 事件只保存一次
+fn schedule_save_window_state<R: Runtime>(app: &tauri::AppHandle<R>) {
+    if SAVE_PENDING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(SAVE_DEBOUNCE_MS));
+        SAVE_PENDING.store(false, Ordering::SeqCst);
+
+        if let Err(e) = save_window_state(&app) {
+            log::warn!("保存窗口状态失败: {}", e);
+        }
+    });
+}
+
+fn save_window_state<R: Runtime>(app: &tauri::AppHandle<R>) -> anyhow::Result<()> {
+    let Some(window) = app.get_webview_window("main") else {
+        return Ok(());
+    };
+    // 最小化 / 隐藏时的位置没有意义（Windows 下为 -32000），不覆盖已保存的状态
+    if window.is_minimized()? || !window.is_visible()? {
+        return Ok(());
+    }
+    let Some(path) = window_state_path(app) else {
+        return Ok(());
+    };
+
+    let maximized = window.is_maximized()?;
+    let state = match load_window_state(app) {
+        // 最大化时保留还原后的尺寸与位置，只更新最大化标记
+        Some(previous) if maximized => WindowState {
+            maximized,
+            ..previous
+        },
+        _ => {
+            let size = window.inner_size()?;
+            let position = window.outer_position()?;
+            WindowState {
+                width: size.width,
+                height: size.height,
+                x: position.x,
+                y: position.y,
+                maximized,
+            }
+        }
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(&state)?)?;
+    Ok(())
+}
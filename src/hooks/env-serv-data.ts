@@ -1,7 +1,7 @@
 import { AppSettings, CanRunServices, CannotRunServices, Environment, EnvironmentStatus, NeedDownloadServices, ServiceData, ServiceDataStatus, ServiceStatus, ServiceType, SystemSettings, serviceTypeNames } from "@/types/index"
 import { useAtom } from "jotai"
 import { toast } from 'sonner'
-import { ipcActivateServiceData, ipcCreateServiceData, ipcDeactivateServiceData, ipcDeleteServiceData, ipcGetEnvAllServDatas, ipcGetServiceData, ipcRestartServiceData, ipcUpdateServiceData, ipcStartServiceData, ipcStoppedServiceData } from "../ipc/env-serv-data"
+import { ipcActivateServiceData, ipcBulkUpdateServiceData, ipcCreateServiceData, ipcDeactivateServiceData, ipcDeleteServiceData, ipcGetEnvAllServDatas, ipcGetServiceData, ipcRestartServiceData, ipcUpdateServiceData, ipcStartServiceData, ipcStoppedServiceData } from "../ipc/env-serv-data"
 import { ipcGetAllEnvironments, ipcActivateEnvironmentAndServices, ipcDeactivateEnvironmentAndServices, ipcSwitchEnvironmentAndServices } from "../ipc/environment"
 import { isAppLoadingAtom } from "../store/app"
 import { environmentsAtom, selectedEnvironmentIdAtom, selectedServiceDatasAtom, selectedServiceDataIdAtom, envActivationEventAtom } from "../store/environment"
@@ -206,11 +206,11 @@ export function useEnvironmentServiceData() {
         newServiceDatas: ServiceData[]
     }) {
         setSelectedServiceDatas(newServiceDatas)
-        // 保存到文件系统
-        for (const serviceData of newServiceDatas) {
-            // 更新每个服务的排序
-            await ipcUpdateServiceData(environmentId, { id: serviceData.id, sort: serviceData.sort ?? 0 });
-        }
+        // 保存到文件系统，一次性提交所有服务的排序
+        await ipcBulkUpdateServiceData(
+            environmentId,
+            newServiceDatas.map(serviceData => ({ id: serviceData.id, sort: serviceData.sort ?? 0 }))
+        );
     }
 
     // 激活服务数据，这里没有直接更新界面，因为可能不是当前选中的环境的服务数据
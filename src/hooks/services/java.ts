@@ -76,7 +76,7 @@ export function useJavaService() {
     }
 
     async function setJavaHome(environmentId: string, serviceData: ServiceData, javaHome: string) {
-        const ipcRes = await ipcSetJavaHome(environmentId, serviceData, javaHome);
+        const ipcRes = await ipcSetJavaHome(environmentId, serviceData.id, javaHome);
         console.log(`[hooks/java] setJavaHome IPC 响应:`, ipcRes);
         return ipcRes;
     }
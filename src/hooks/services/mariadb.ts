@@ -10,6 +10,7 @@ import {
     ipcRestartMariadbService,
     ipcInitializeMariadb,
     ipcCheckMariadbInitialized,
+    ipcListenMariadbInitProgress,
     ipcListMariadbDatabases,
     ipcCreateMariadbDatabase,
     ipcListMariadbTables,
@@ -19,6 +20,8 @@ import {
     ipcDeleteMariadbUser,
     ipcUpdateMariadbUserGrants,
 } from "../../ipc/services/mariadb";
+import { useEffect, useRef } from "react";
+import { UnlistenFn } from "@tauri-apps/api/event";
 
 // MariaDB 配置接口
 export interface MariaDBConfig {
@@ -261,6 +264,29 @@ export async function initializeMariadb(
     return ipcInitializeMariadb(environmentId, serviceData, rootPassword, port, bindAddress, reset);
 }
 
+/**
+ * 监听 MariaDB 初始化进度
+ */
+export function useMariadbInitProgress(
+    callback: (payload: { step: string; message: string }) => void
+) {
+    const unlistenRef = useRef<UnlistenFn | null>(null);
+
+    useEffect(() => {
+        const setupListener = async () => {
+            unlistenRef.current = await ipcListenMariadbInitProgress(callback);
+        };
+
+        setupListener();
+
+        return () => {
+            if (unlistenRef.current) {
+                unlistenRef.current();
+            }
+        };
+    }, [callback]);
+}
+
 /**
  * 检查 MariaDB 是否已初始化
  */
@@ -1,8 +1,10 @@
-import { ServiceData, ServiceType } from "@/types/index"
+import { Environment, ServiceData, ServiceDataStatus, ServiceType } from "@/types/index"
 import { invokeCommand } from '@/lib/tauri-api'
 import { ipcLogFunc } from '../utils/logger'
 import { IPCResult } from "@/types/ipc";
 import { closeTooManyLogs } from "@/utils/const";
+import { listen, UnlistenFn } from '@tauri-apps/api/event'
+import { ChangeOperation } from "./environment";
 
 export const ipcGetEnvAllServDatas = ipcLogFunc('获取环境所有服务数据', async (environmentId: string): Promise<IPCResult<{ serviceDatas: ServiceData[] }>> => {
     return invokeCommand('get_environment_all_service_datas', { environmentId });
@@ -27,10 +29,22 @@ export const ipcUpdateServiceData = ipcLogFunc('保存服务数据', async (envi
     return invokeCommand('update_service_data', {  environmentId, request });
 })
 
+export const ipcBulkUpdateServiceData = ipcLogFunc('批量保存服务数据', async (environmentId: string, updates: Array<{ id: string } & Partial<ServiceData>>): Promise<IPCResult<{ serviceDatas: ServiceData[] }>> => {
+    return invokeCommand('bulk_update_service_data', { environmentId, updates });
+})
+
 export const ipcDeleteServiceData = ipcLogFunc('删除服务数据', async (environmentId: string, serviceId: string): Promise<IPCResult<undefined>> => {
     return invokeCommand('delete_service_data', { environmentId, serviceId });
 })
 
+export const ipcDuplicateServiceData = ipcLogFunc('复制服务数据', async (srcEnvironmentId: string, srcServiceId: string, destEnvironmentId: string, newName?: string): Promise<IPCResult<{ serviceData: ServiceData }>> => {
+    return invokeCommand('duplicate_service_data', { srcEnvironmentId, srcServiceId, destEnvironmentId, newName });
+})
+
+export const ipcSearchServiceData = ipcLogFunc('搜索服务数据', async (query: string, serviceType?: ServiceType, status?: ServiceDataStatus): Promise<IPCResult<{ results: Array<{ environment: Environment, serviceData: ServiceData }> }>> => {
+    return invokeCommand('search_service_data', { query, serviceType, status });
+})
+
 export const ipcActivateServiceData = ipcLogFunc('激活服务数据', async (environmentId: string, serviceData: ServiceData, password?: string): Promise<IPCResult<undefined>> => {
     return invokeCommand('active_service_data', { environmentId, serviceData, password })
 })
@@ -50,3 +64,22 @@ export const ipcStoppedServiceData = ipcLogFunc('停止服务数据', async (env
 export const ipcRestartServiceData = ipcLogFunc('重启服务数据', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<undefined>> => {
     return invokeCommand(`restart_${serviceData.type}_service`, { environmentId, serviceData });
 })
+
+export interface ServiceDataChangedPayload {
+    operation: ChangeOperation
+    environmentId: string
+    serviceId: string
+    /** 删除时为删除前的记录 */
+    serviceData: ServiceData
+}
+
+/**
+ * 监听服务数据变更事件（创建 / 修改 / 删除 / 激活 / 停用）
+ */
+export async function ipcListenServiceDataChanged(
+    callback: (payload: ServiceDataChangedPayload) => void
+): Promise<UnlistenFn> {
+    return await listen<ServiceDataChangedPayload>('service-data-changed', (event) => {
+        callback(event.payload);
+    });
+}
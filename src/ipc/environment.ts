@@ -1,8 +1,9 @@
-import { Environment } from "@/types/index"
+import { Environment, ServiceData, ServiceType } from "@/types/index"
 import { invokeCommand } from '@/lib/tauri-api'
 import { ipcLogFunc } from '../utils/logger'
 import { IPCResult } from "@/types/ipc"
 import { closeTooManyLogs } from "@/utils/const"
+import { listen, UnlistenFn } from '@tauri-apps/api/event'
 
 export const ipcGetAllEnvironments = ipcLogFunc('获取所有环境', async (): Promise<IPCResult<{ environments: Environment[] }>> => {
     const startAt = new Date();
@@ -17,6 +18,20 @@ export const ipcGetAllEnvironments = ipcLogFunc('获取所有环境', async ():
     return res
 })
 
+export interface EnvironmentServiceTreeNode {
+    environment: Environment
+    serviceDatas: ServiceData[]
+    statusSummary: {
+        total: number
+        active: number
+        installed: number
+    }
+}
+
+export const ipcGetEnvironmentServiceTree = ipcLogFunc('获取环境服务树', async (): Promise<IPCResult<EnvironmentServiceTreeNode[]>> => {
+    return invokeCommand('get_environment_service_tree')
+}, closeTooManyLogs)
+
 export const ipcGetEnvironment = ipcLogFunc('获取单个环境', async (environmentId: string): Promise<IPCResult<{ environment: Environment }>> => {
     return invokeCommand('get_environment', { environmentId })
 }, closeTooManyLogs)
@@ -25,6 +40,68 @@ export const ipcCreateEnvironment = ipcLogFunc('创建环境', async (name: stri
     return invokeCommand('create_environment', { name, description })
 })
 
+export interface EnvironmentTemplate {
+    id: string
+    name: string
+    description: string
+    services: Array<{
+        serviceType: ServiceType
+        version: string | null
+        installed: boolean
+        port: number | null
+    }>
+}
+
+export const ipcListEnvironmentTemplates = ipcLogFunc('获取环境模板', async (): Promise<IPCResult<{ templates: EnvironmentTemplate[] }>> => {
+    return invokeCommand('list_environment_templates')
+})
+
+export const ipcCreateEnvironmentFromTemplate = ipcLogFunc('按模板创建环境', async (templateId: string, name: string): Promise<IPCResult<{
+    environment: Environment
+    serviceDatas: ServiceData[]
+    needsDownload: Array<{ serviceType: ServiceType, version: string }>
+}>> => {
+    return invokeCommand('create_environment_from_template', { templateId, name })
+})
+
+export interface ProjectManifestCheck {
+    path: string
+    format: 'toml' | 'json'
+    entries: Array<{
+        serviceType: ServiceType
+        version: string
+        installed: boolean
+        activeVersions: string[]
+        satisfied: boolean
+    }>
+    satisfied: boolean
+}
+
+export interface ProjectManifestApplyResult {
+    environmentId: string
+    path: string
+    entries: Array<{
+        serviceType: ServiceType
+        version: string
+        action: 'created' | 'updated' | 'unchanged' | 'skipped'
+        fromVersion: string | null
+        serviceData: ServiceData | null
+        installed: boolean
+        downloadTaskId: string | null
+        message: string | null
+    }>
+    downloadTaskIds: string[]
+}
+
+// path 可以是 .envisrc 文件或包含它的项目目录
+export const ipcReadProjectManifest = ipcLogFunc('读取项目清单', async (path: string): Promise<IPCResult<ProjectManifestCheck>> => {
+    return invokeCommand('read_project_manifest', { path })
+})
+
+export const ipcApplyProjectManifest = ipcLogFunc('应用项目清单', async (path: string, environmentId: string, downloadMissing?: boolean): Promise<IPCResult<ProjectManifestApplyResult>> => {
+    return invokeCommand('apply_project_manifest', { path, environmentId, downloadMissing })
+})
+
 export const ipcSaveEnvironment = ipcLogFunc('保存环境', async (environment: Environment): Promise<IPCResult<{ env: Environment }>> => {
     return invokeCommand('save_environment', { environment })
 })
@@ -64,3 +141,108 @@ export const ipcExportEnvironmentData = ipcLogFunc('导出环境数据', async (
 export const ipcImportEnvironmentData = ipcLogFunc('导入环境数据', async (jsonContent: string): Promise<IPCResult<{ environmentId: string; environmentName: string; services: any[] }>> => {
     return invokeCommand('import_environment_data', { jsonContent })
 })
+
+export const ipcGetEnvironmentMetadata = ipcLogFunc('获取环境 metadata', async (environmentId: string, key: string): Promise<IPCResult<{ key: string; value: unknown }>> => {
+    return invokeCommand('get_environment_metadata', { environmentId, key })
+})
+
+export const ipcSetEnvironmentMetadata = ipcLogFunc('设置环境 metadata', async (environmentId: string, key: string, value: unknown): Promise<IPCResult<{ key: string; value: unknown }>> => {
+    return invokeCommand('set_environment_metadata', { environmentId, key, value })
+})
+
+export type ScheduledTaskRun = {
+    startedAt: string
+    finishedAt: string
+    trigger: 'schedule' | 'manual' | 'catchUp'
+    status: 'success' | 'failed'
+    exitCode: number
+    stdout: string
+    stderr: string
+}
+
+export type ScheduledTask = {
+    id: string
+    name: string
+    cronExpr: string
+    command: string
+    enabled: boolean
+    catchUp: boolean
+    lastRun?: string
+    lastStatus?: 'success' | 'failed'
+    history: ScheduledTaskRun[]
+    createdAt: string
+}
+
+export type ScheduledTaskInput = {
+    name: string
+    cronExpr: string
+    command: string
+    enabled?: boolean
+    catchUp?: boolean
+}
+
+export const ipcListScheduledTasks = ipcLogFunc('获取计划任务', async (environmentId: string): Promise<IPCResult<{ tasks: ScheduledTask[] }>> => {
+    return invokeCommand('list_scheduled_tasks', { environmentId })
+})
+
+export const ipcAddScheduledTask = ipcLogFunc('新增计划任务', async (environmentId: string, task: ScheduledTaskInput): Promise<IPCResult<{ task: ScheduledTask }>> => {
+    return invokeCommand('add_scheduled_task', { environmentId, task })
+})
+
+export const ipcUpdateScheduledTask = ipcLogFunc('修改计划任务', async (environmentId: string, taskId: string, task: ScheduledTaskInput): Promise<IPCResult<{ task: ScheduledTask }>> => {
+    return invokeCommand('update_scheduled_task', { environmentId, taskId, task })
+})
+
+export const ipcDeleteScheduledTask = ipcLogFunc('删除计划任务', async (environmentId: string, taskId: string): Promise<IPCResult<{ taskId: string }>> => {
+    return invokeCommand('delete_scheduled_task', { environmentId, taskId })
+})
+
+export const ipcRunScheduledTaskNow = ipcLogFunc('立即执行计划任务', async (environmentId: string, taskId: string): Promise<IPCResult<{ run: ScheduledTaskRun }>> => {
+    return invokeCommand('run_scheduled_task_now', { environmentId, taskId })
+})
+
+export type EnvironmentHooks = {
+    onActivate: string[]
+    onDeactivate: string[]
+    timeoutSecs: number
+}
+
+export type EnvironmentHookResult = {
+    environmentId: string
+    phase: 'activate' | 'deactivate'
+    command: string
+    success: boolean
+    exitCode?: number
+    stdout: string
+    stderr: string
+    error?: string
+    durationMs: number
+}
+
+export const ipcGetEnvironmentHooks = ipcLogFunc('获取环境钩子', async (environmentId: string): Promise<IPCResult<{ hooks: EnvironmentHooks }>> => {
+    return invokeCommand('get_environment_hooks', { environmentId })
+})
+
+export const ipcSetEnvironmentHooks = ipcLogFunc('设置环境钩子', async (environmentId: string, hooks: EnvironmentHooks): Promise<IPCResult<{ hooks: EnvironmentHooks }>> => {
+    return invokeCommand('set_environment_hooks', { environmentId, hooks })
+})
+
+export type ChangeOperation = 'created' | 'updated' | 'deleted' | 'activated' | 'deactivated'
+
+export interface EnvironmentChangedPayload {
+    operation: ChangeOperation
+    environmentId: string
+    /** 删除时为删除前的记录 */
+    environment: Environment
+}
+
+/**
+ * 监听环境变更事件（创建 / 修改 / 删除 / 激活 / 停用）
+ */
+export async function ipcListenEnvironmentChanged(
+    callback: (payload: EnvironmentChangedPayload) => void
+): Promise<UnlistenFn> {
+    return await listen<EnvironmentChangedPayload>('environment-changed', (event) => {
+        callback(event.payload);
+    });
+}
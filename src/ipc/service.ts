@@ -1,8 +1,9 @@
-import { Service, DownloadStatus, ServiceStatus, ServiceData } from "@/types/index"
+import { Service, DownloadStatus, ServiceStatus, ServiceData, ServiceType } from "@/types/index"
 import { invokeCommand } from '@/lib/tauri-api'
 import { ipcLogFunc } from '../utils/logger'
 import { IPCResult } from "@/types/ipc"
 import { closeTooManyLogs } from "@/utils/const"
+import { listen, UnlistenFn } from '@tauri-apps/api/event'
 
 export interface DownloadTask {
     id: string
@@ -42,6 +43,15 @@ export const ipcDownloadService = ipcLogFunc('下载服务', async (
     return invokeCommand(`download_${serviceType}`, { version, buildMethod, ...options })
 })
 
+export const ipcInstallServiceFromFile = ipcLogFunc('离线安装服务', async (
+    serviceType: ServiceType,
+    version: string,
+    archivePath: string,
+    sha256?: string
+): Promise<IPCResult<{task: DownloadTask | null}>> => {
+    return invokeCommand('install_service_from_file', { serviceType, version, archivePath, sha256 })
+})
+
 export const ipcCancelServiceDownload = ipcLogFunc('取消服务下载', async (serviceType: string, version: string): Promise<IPCResult<undefined>> => {
     return invokeCommand(`cancel_download_${serviceType}`, { version })
 })
@@ -56,8 +66,67 @@ export const ipcGetServiceDownloadProgress = ipcLogFunc('获取服务下载进
     return invokeCommand(`get_${serviceType}_download_progress`, { version })
 }, closeTooManyLogs)
 
-export const ipcDeleteService = ipcLogFunc('删除服务', async (serviceType: string, version: string): Promise<IPCResult<undefined>> => {
-    return invokeCommand('delete_service', { serviceType, version })
+export interface DownloadHistoryRecord {
+    id: string
+    url: string
+    filename: string
+    total_size: number
+    downloaded_size: number
+    status: DownloadStatus
+    error_message: string | null
+    failed_urls: string[]
+    finished_at: string | null
+}
+
+export const ipcGetDownloadHistory = ipcLogFunc('获取下载历史', async (): Promise<IPCResult<{history: DownloadHistoryRecord[]}>> => {
+    return invokeCommand('get_download_history')
+})
+
+export const ipcClearDownloadHistory = ipcLogFunc('清空下载历史', async (): Promise<IPCResult<undefined>> => {
+    return invokeCommand('clear_download_history')
+})
+
+export const ipcGetConcurrentDownloadLimit = ipcLogFunc('获取并发下载数', async (): Promise<IPCResult<{limit: number}>> => {
+    return invokeCommand('get_concurrent_download_limit')
+})
+
+export const ipcSetConcurrentDownloadLimit = ipcLogFunc('设置并发下载数', async (limit: number): Promise<IPCResult<{limit: number}>> => {
+    return invokeCommand('set_concurrent_download_limit', { limit })
+})
+
+export interface ServiceReference {
+    environmentId: string
+    environmentName: string
+    serviceDataId: string
+    serviceDataName: string
+}
+
+export interface RunningProcess {
+    pid: number
+    name: string
+    exe: string
+}
+
+export const ipcDeleteService = ipcLogFunc('删除服务', async (serviceType: string, version: string, force: boolean = false, stopRunning: boolean = false): Promise<IPCResult<{
+    stopped?: RunningProcess[]
+    unlinked?: ServiceReference[]
+    references?: ServiceReference[]
+    processes?: RunningProcess[]
+}>> => {
+    return invokeCommand('delete_service', { serviceType, version, force, stopRunning })
+})
+
+export const ipcUpgradeServiceData = ipcLogFunc('升级服务数据版本', async (environmentId: string, serviceDataId: string, targetVersion: string, allowMajor: boolean = false): Promise<IPCResult<{
+    serviceData?: ServiceData
+    fromVersion: string
+    toVersion: string
+    reactivated?: boolean
+    wasRunning?: boolean
+    restarted?: boolean
+    requiresAllowMajor?: boolean
+    caveat?: string | null
+}>> => {
+    return invokeCommand('upgrade_service_data', { environmentId, serviceDataId, targetVersion, allowMajor })
 })
 
 export const ipcGetServiceStatus = ipcLogFunc('获取服务状态', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{
@@ -78,3 +147,79 @@ export interface ProcessStatData {
 export const ipcGetServicesProcessStats = ipcLogFunc('获取服务进程统计', async (serviceTypes: string[]): Promise<IPCResult<ProcessStatData[]>> => {
     return invokeCommand('get_services_process_stats', { serviceTypes })
 }, true)
+
+export interface ServiceResourceUsage {
+    pid: number | null
+    cpuPercent: number
+    memoryRssKb: number
+}
+
+export const ipcGetServiceResourceUsage = ipcLogFunc('获取服务资源占用', async (environmentId: string, serviceDataId: string): Promise<IPCResult<ServiceResourceUsage>> => {
+    return invokeCommand('get_service_resource_usage', { environmentId, serviceDataId })
+}, true)
+
+export interface ServiceStatusSummary {
+    serviceDataId: string
+    type: ServiceType
+    version: string
+    status: ServiceStatus
+    detail: Record<string, any>
+}
+
+export const ipcGetEnvironmentServicesStatus = ipcLogFunc('获取环境服务状态', async (environmentId: string): Promise<IPCResult<ServiceStatusSummary[]>> => {
+    return invokeCommand('get_environment_services_status', { environmentId })
+}, true)
+
+export interface ServiceDataIntegrity {
+    serviceDataId: string
+    serviceType: ServiceType
+    version: string
+    issues: string[]
+}
+
+export const ipcValidateServiceDataIntegrity = ipcLogFunc('检查服务数据完整性', async (environmentId: string): Promise<IPCResult<{ results: ServiceDataIntegrity[] }>> => {
+    return invokeCommand('validate_service_data_integrity', { environmentId })
+})
+
+export type ServicesFolderEntryKind = 'installed' | 'orphaned' | 'archive' | 'temp'
+
+export interface ServicesFolderEntry {
+    kind: ServicesFolderEntryKind
+    serviceType: ServiceType
+    version?: string
+    path: string
+    size: number
+    sizeFormatted: string
+    referenced: boolean
+}
+
+export const ipcScanServicesFolder = ipcLogFunc('扫描服务文件夹', async (): Promise<IPCResult<{ entries: ServicesFolderEntry[] }>> => {
+    return invokeCommand('scan_services_folder')
+})
+
+export const ipcCleanupServicesFolder = ipcLogFunc('清理服务文件夹', async (kinds: ServicesFolderEntryKind[]): Promise<IPCResult<{
+    removed: { orphaned: number, archive: number, temp: number }
+    reclaimedBytes: number
+    reclaimedFormatted: string
+    failed: string[]
+}>> => {
+    return invokeCommand('cleanup_services_folder', { kinds })
+})
+
+export interface ServiceStatusChangedPayload {
+    environmentId: string
+    serviceId: string
+    previousStatus: ServiceStatus
+    status: ServiceStatus
+}
+
+/**
+ * 监听状态检测发现的服务启动 / 停止事件
+ */
+export async function ipcListenServiceStatusChanged(
+    callback: (payload: ServiceStatusChangedPayload) => void
+): Promise<UnlistenFn> {
+    return await listen<ServiceStatusChangedPayload>('service-status-changed', (event) => {
+        callback(event.payload);
+    });
+}
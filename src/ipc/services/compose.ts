@@ -0,0 +1,55 @@
+import { ServiceData } from "@/types/index";
+import { invokeCommand } from '@/lib/tauri-api'
+import { ipcLogFunc } from '../../utils/logger'
+import { IPCResult } from "@/types/ipc";
+
+export type ContainerRuntime = {
+    binary: 'docker' | 'podman'
+    composeVersion: string
+}
+
+export type ComposeContainer = {
+    name: string
+    service: string
+    state: string
+    status: string
+    health: string | null
+    exitCode: number | null
+}
+
+export const ipcDetectContainerRuntime = ipcLogFunc('检测容器运行时', async (): Promise<IPCResult<{
+    runtime: ContainerRuntime
+}>> => {
+    return invokeCommand('detect_container_runtime')
+})
+
+export const ipcSetComposeFile = ipcLogFunc('设置 compose 文件', async (environmentId: string, serviceData: ServiceData, composeFile: string, projectName?: string): Promise<IPCResult<{
+    composeFile: string
+    projectName: string
+}>> => {
+    return invokeCommand('set_compose_file', { environmentId, serviceData, composeFile, projectName })
+})
+
+export const ipcStartComposeService = ipcLogFunc('启动 Compose 项目', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{
+    projectName: string
+    runtime: ContainerRuntime
+}>> => {
+    return invokeCommand('start_compose_service', { environmentId, serviceData })
+})
+
+export const ipcStopComposeService = ipcLogFunc('停止 Compose 项目', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult> => {
+    return invokeCommand('stop_compose_service', { environmentId, serviceData })
+})
+
+export const ipcRestartComposeService = ipcLogFunc('重启 Compose 项目', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult> => {
+    return invokeCommand('restart_compose_service', { environmentId, serviceData })
+})
+
+export const ipcGetComposeServiceStatus = ipcLogFunc('获取 Compose 项目状态', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{
+    status: string
+    isRunning: boolean
+    projectName: string
+    containers: ComposeContainer[]
+}>> => {
+    return invokeCommand('get_compose_service_status', { environmentId, serviceData })
+})
@@ -46,6 +46,107 @@ export const ipcExecuteCustomServiceAlias = ipcLogFunc('执行自定义服务 Al
     return invokeCommand(`execute_custom_service_alias`, { aliasName, command })
 })
 
+export interface CustomServiceCommand {
+    name: string
+    program: string
+    args?: string[]
+    cwd?: string | null
+    env?: Record<string, string>
+}
+
+export const ipcListCustomServiceCommands = ipcLogFunc('获取自定义服务命令列表', async (
+    environmentId: string,
+    serviceData: ServiceData
+): Promise<IPCResult<{ commands: CustomServiceCommand[] }>> => {
+    return invokeCommand(`list_custom_service_commands`, { environmentId, serviceData })
+})
+
+export const ipcAddCustomServiceCommand = ipcLogFunc('新增自定义服务命令', async (
+    environmentId: string,
+    serviceData: ServiceData,
+    command: CustomServiceCommand
+): Promise<IPCResult<{ commands: CustomServiceCommand[] }>> => {
+    return invokeCommand(`add_custom_service_command`, { environmentId, serviceData, command })
+})
+
+export const ipcUpdateCustomServiceCommand = ipcLogFunc('更新自定义服务命令', async (
+    environmentId: string,
+    serviceData: ServiceData,
+    name: string,
+    command: CustomServiceCommand
+): Promise<IPCResult<{ commands: CustomServiceCommand[] }>> => {
+    return invokeCommand(`update_custom_service_command`, { environmentId, serviceData, name, command })
+})
+
+export const ipcRemoveCustomServiceCommand = ipcLogFunc('删除自定义服务命令', async (
+    environmentId: string,
+    serviceData: ServiceData,
+    name: string
+): Promise<IPCResult<{ commands: CustomServiceCommand[] }>> => {
+    return invokeCommand(`remove_custom_service_command`, { environmentId, serviceData, name })
+})
+
+// stream 为 true 时仅返回 runId，输出通过 custom-command:output / custom-command:exit 事件推送
+export const ipcExecuteCustomServiceCommand = ipcLogFunc('执行自定义服务命令', async (
+    environmentId: string,
+    serviceData: ServiceData,
+    name: string,
+    timeoutSecs?: number,
+    stream?: boolean
+): Promise<IPCResult<{ runId?: string; stdout?: string; stderr?: string; exitCode?: number; timedOut?: boolean; durationMs?: number }>> => {
+    return invokeCommand(`execute_custom_service_command`, { environmentId, serviceData, name, timeoutSecs, stream })
+})
+
+export const ipcStopCustomServiceCommand = ipcLogFunc('停止自定义服务命令', async (
+    runId: string
+): Promise<IPCResult<{ runId: string }>> => {
+    return invokeCommand(`stop_custom_service_command`, { runId })
+})
+
+export interface CustomBinary {
+    name: string
+    version: string
+    folder: string
+    binDir?: string | null
+    active: boolean
+}
+
+export const ipcListCustomBinaries = ipcLogFunc('获取已注册工具目录', async (
+    environmentId: string,
+    serviceData: ServiceData
+): Promise<IPCResult<{ binaries: CustomBinary[] }>> => {
+    return invokeCommand(`list_custom_binaries`, { environmentId, serviceData })
+})
+
+export const ipcRegisterCustomBinary = ipcLogFunc('注册工具目录', async (
+    environmentId: string,
+    serviceData: ServiceData,
+    name: string,
+    version: string,
+    folder: string,
+    binDir?: string | null
+): Promise<IPCResult<{ binaries: CustomBinary[] }>> => {
+    return invokeCommand(`register_custom_binary`, { environmentId, serviceData, name, version, folder, binDir })
+})
+
+export const ipcUnregisterCustomBinary = ipcLogFunc('取消注册工具目录', async (
+    environmentId: string,
+    serviceData: ServiceData,
+    name: string,
+    version: string
+): Promise<IPCResult<{ binaries: CustomBinary[] }>> => {
+    return invokeCommand(`unregister_custom_binary`, { environmentId, serviceData, name, version })
+})
+
+export const ipcSwitchCustomBinary = ipcLogFunc('切换工具版本', async (
+    environmentId: string,
+    serviceData: ServiceData,
+    name: string,
+    version: string
+): Promise<IPCResult<{ binaries: CustomBinary[] }>> => {
+    return invokeCommand(`switch_custom_binary`, { environmentId, serviceData, name, version })
+})
+
 export const ipcOpenProjectInVSCode = ipcLogFunc('用 VSCode 打开项目', async (
     path: string,
     environmentId: string
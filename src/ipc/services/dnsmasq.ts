@@ -9,3 +9,24 @@ export const ipcGetDnsmasqConfig = ipcLogFunc('获取 Dnsmasq 配置', async (en
 }>> => {
     return invokeCommand(`get_dnsmasq_config`, { environmentId, serviceData })
 })
+
+export const ipcGetDnsmasqUpstreamDns = ipcLogFunc('获取 Dnsmasq 上游 DNS', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{
+  servers: string[]
+}>> => {
+    return invokeCommand(`get_dnsmasq_upstream_dns`, { environmentId, serviceData })
+})
+
+export const ipcSetDnsmasqUpstreamDns = ipcLogFunc('设置 Dnsmasq 上游 DNS', async (environmentId: string, serviceData: ServiceData, servers: string[]): Promise<IPCResult<{
+  configPath: string
+  servers: string[]
+  restarted: boolean
+}>> => {
+    return invokeCommand(`set_dnsmasq_upstream_dns`, { environmentId, serviceData, servers })
+})
+
+export const ipcResetDnsmasqUpstreamDns = ipcLogFunc('重置 Dnsmasq 上游 DNS', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{
+  configPath: string
+  restarted: boolean
+}>> => {
+    return invokeCommand(`reset_dnsmasq_upstream_dns`, { environmentId, serviceData })
+})
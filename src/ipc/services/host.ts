@@ -42,6 +42,18 @@ export const ipcClearHosts = ipcLogFunc('清空所有 hosts', async (password: s
     return invokeCommand(`clear_hosts`, { password })
 })
 
+export const ipcBulkImportHosts = ipcLogFunc('批量导入 hosts', async (
+    filePath: string,
+    overwrite: boolean,
+    password: string
+): Promise<IPCResult<{ imported: number, skipped: number, errors: string[], entries: HostEntry[] }>> => {
+    return invokeCommand(`bulk_import_hosts`, { filePath, overwrite, password })
+})
+
+export const ipcExportHostsAsEtcFormat = ipcLogFunc('导出 hosts 文件', async (destPath: string): Promise<IPCResult<{ count: number, path: string }>> => {
+    return invokeCommand(`export_hosts_as_etc_format`, { destPath })
+})
+
 export const ipcOpenHostsFile = ipcLogFunc('打开 hosts 文件所在文件夹', async (): Promise<IPCResult> => {
     return invokeCommand(`open_hosts_file`, {})
 })
@@ -7,10 +7,24 @@ export const ipcCheckJavaInstalled = ipcLogFunc('检查 Java 是否已安装', a
     return invokeCommand('check_java_installed', { version })
 })
 
-export const ipcCheckMavenInstalled = ipcLogFunc('检查 Maven 是否已安装', async (version: string): Promise<IPCResult<{ installed: boolean, home?: string }>> => {
+export const ipcCheckMavenInstalled = ipcLogFunc('检查 Maven 是否已安装', async (version: string): Promise<IPCResult<{ installed: boolean, home?: string, version: string }>> => {
     return invokeCommand('check_maven_installed', { version })
 })
 
+export const ipcGetMavenVersions = ipcLogFunc('获取 Maven 版本列表', async (): Promise<IPCResult<{
+    versions: Array<{
+        javaVersion: string
+        version: string
+        installed: boolean
+    }>
+}>> => {
+    return invokeCommand('get_maven_versions')
+})
+
+export const ipcDownloadMaven = ipcLogFunc('下载 Maven', async (version: string): Promise<IPCResult<{ task: any }>> => {
+    return invokeCommand('download_maven', { version })
+})
+
 export const ipcGetJavaVersions = ipcLogFunc('获取 Java 版本列表', async (): Promise<IPCResult<{ 
     versions: Array<{
         version: string
@@ -51,8 +65,30 @@ export const ipcGetJavaInfo = ipcLogFunc('获取 Java 信息', async (serviceDat
     return invokeCommand('get_java_info', { serviceData })
 })
 
-export const ipcSetJavaHome = ipcLogFunc('设置 JAVA_HOME', async (environmentId: string, serviceData: ServiceData, javaHome: string): Promise<IPCResult> => {
-    return invokeCommand('set_java_home', { environmentId, serviceData, javaHome })
+export type SystemJavaInstallation = {
+    home: string
+    version: string | null
+}
+
+export const ipcGetJavaHome = ipcLogFunc('获取 JAVA_HOME', async (environmentId: string, serviceDataId: string): Promise<IPCResult<{
+    javaHome: string
+    external: boolean
+    installed: boolean
+}>> => {
+    return invokeCommand('get_java_home', { environmentId, serviceDataId })
+})
+
+export const ipcSetJavaHome = ipcLogFunc('设置 JAVA_HOME', async (environmentId: string, serviceDataId: string, javaHomePath: string): Promise<IPCResult<{
+    javaHome: string
+    version: string | null
+}>> => {
+    return invokeCommand('set_java_home', { environmentId, serviceDataId, javaHomePath })
+})
+
+export const ipcDetectSystemJavaInstallations = ipcLogFunc('检测系统已安装的 JDK', async (): Promise<IPCResult<{
+    installations: SystemJavaInstallation[]
+}>> => {
+    return invokeCommand('detect_system_java_installations')
 })
 
 export const ipcSetJavaOpts = ipcLogFunc('设置 JAVA_OPTS', async (environmentId: string, serviceData: ServiceData, javaOpts: string): Promise<IPCResult> => {
@@ -86,3 +122,11 @@ export const ipcGetGradleDownloadProgress = ipcLogFunc('获取 Gradle 下载进
 export const ipcSetMavenLocalRepository = ipcLogFunc('设置 Maven 本地仓库', async (environmentId: string, serviceData: ServiceData, localRepo: string): Promise<IPCResult> => {
     return invokeCommand('set_maven_local_repository', { environmentId, serviceData, localRepo })
 })
+
+export const ipcGetMavenRepository = ipcLogFunc('获取 Maven 仓库地址', async (serviceData: ServiceData): Promise<IPCResult<{ repositoryUrl: string, localRepo: string | null }>> => {
+    return invokeCommand('get_maven_repository', { serviceData })
+})
+
+export const ipcSetMavenRepository = ipcLogFunc('设置 Maven 仓库地址', async (environmentId: string, serviceData: ServiceData, repositoryUrl: string): Promise<IPCResult<{ repositoryUrl: string }>> => {
+    return invokeCommand('set_maven_repository', { environmentId, serviceData, repositoryUrl })
+})
@@ -0,0 +1,46 @@
+import { ServiceData } from "@/types/index";
+import { invokeCommand } from '@/lib/tauri-api'
+import { ipcLogFunc } from '../../utils/logger'
+import { IPCResult } from "@/types/ipc";
+
+export type KafkaPartition = {
+    partition: number
+    leader: number | null
+    replicas: number[]
+    isr: number[]
+}
+
+export type KafkaTopic = {
+    topic: string
+    topicId: string | null
+    partitionCount: number | null
+    replicationFactor: number | null
+    configs: string
+    partitions: KafkaPartition[]
+}
+
+export const ipcCreateKafkaTopic = ipcLogFunc('创建 Kafka topic', async (environmentId: string, serviceData: ServiceData, topic: string, partitions: number, replicationFactor: number): Promise<IPCResult<{
+    topic: string
+    partitions: number
+    replicationFactor: number
+}>> => {
+    return invokeCommand('create_kafka_topic', { environmentId, serviceData, topic, partitions, replicationFactor })
+})
+
+export const ipcListKafkaTopics = ipcLogFunc('获取 Kafka topic 列表', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{
+    topics: string[]
+}>> => {
+    return invokeCommand('list_kafka_topics', { environmentId, serviceData })
+})
+
+export const ipcDeleteKafkaTopic = ipcLogFunc('删除 Kafka topic', async (environmentId: string, serviceData: ServiceData, topic: string): Promise<IPCResult<{
+    topic: string
+}>> => {
+    return invokeCommand('delete_kafka_topic', { environmentId, serviceData, topic })
+})
+
+export const ipcDescribeKafkaTopic = ipcLogFunc('获取 Kafka topic 详情', async (environmentId: string, serviceData: ServiceData, topic: string): Promise<IPCResult<{
+    topic: KafkaTopic
+}>> => {
+    return invokeCommand('describe_kafka_topic', { environmentId, serviceData, topic })
+})
@@ -1,7 +1,9 @@
 import { ServiceData } from "@/types/index";
 import { IPCResult } from "@/types/ipc";
+import { DatabaseInitializationCheck, MariaDBMetadata } from "@/types/service";
 import { invokeCommand } from '@/lib/tauri-api'
 import { ipcLogFunc } from '../../utils/logger'
+import { listen, UnlistenFn } from '@tauri-apps/api/event'
 
 export const ipcGetMariadbVersions = ipcLogFunc('获取 MariaDB 版本列表', async (): Promise<IPCResult> => {
     return invokeCommand('get_mariadb_versions')
@@ -77,11 +79,23 @@ export const ipcInitializeMariadb = ipcLogFunc('初始化 MariaDB', async (
     rootPassword: string;
     port: string;
     bindAddress: string;
+    metadata: MariaDBMetadata;
 }>> => {
     return invokeCommand('initialize_mariadb', { environmentId, serviceData, rootPassword, port, bindAddress, reset })
 })
 
-export const ipcCheckMariadbInitialized = ipcLogFunc('检查 MariaDB 是否已初始化', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{ initialized: boolean }>> => {
+/**
+ * 监听 MariaDB 初始化进度事件
+ */
+export async function ipcListenMariadbInitProgress(
+    callback: (payload: { step: string; message: string }) => void
+): Promise<UnlistenFn> {
+    return await listen<{ step: string; message: string }>('mariadb-init-progress', (event) => {
+        callback(event.payload);
+    });
+}
+
+export const ipcCheckMariadbInitialized = ipcLogFunc('检查 MariaDB 是否已初始化', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<DatabaseInitializationCheck>> => {
     return invokeCommand('check_mariadb_initialized', { environmentId, serviceData })
 })
 
@@ -105,7 +119,7 @@ export const ipcListMariadbUsers = ipcLogFunc('列出 MariaDB 用户', async (en
     users: Array<{
         username: string
         host: string
-        grants: Array<{ database: string; privilege: string }>
+        grants: Array<{ database: string; privilege: string; privileges?: string[] }>
     }>
 }>> => {
     return invokeCommand('list_mariadb_users', { environmentId, serviceData })
@@ -116,7 +130,7 @@ export const ipcCreateMariadbUser = ipcLogFunc('创建 MariaDB 用户', async (
     serviceData: ServiceData,
     username: string,
     password: string,
-    grants: Array<{ database: string; privilege: string }>
+    grants: Array<{ database: string; privilege: string; privileges?: string[] }>
 ): Promise<IPCResult<{ username: string }>> => {
     return invokeCommand('create_mariadb_user', { environmentId, serviceData, username, password, grants })
 })
@@ -133,7 +147,44 @@ export const ipcUpdateMariadbUserGrants = ipcLogFunc('更新 MariaDB 用户权
     environmentId: string,
     serviceData: ServiceData,
     username: string,
-    grants: Array<{ database: string; privilege: string }>
+    grants: Array<{ database: string; privilege: string; privileges?: string[] }>
 ): Promise<IPCResult<{ username: string }>> => {
     return invokeCommand('update_mariadb_user_grants', { environmentId, serviceData, username, grants })
 })
+
+// 进度通过 mariadb-dump-progress 事件推送
+export const ipcDumpMariadbDatabase = ipcLogFunc('导出 MariaDB 数据库', async (
+    environmentId: string,
+    serviceData: ServiceData,
+    databaseName: string,
+    outputPath: string
+): Promise<IPCResult<{ database: string; outputPath: string; size: number }>> => {
+    return invokeCommand('dump_mariadb_database', { environmentId, serviceData, databaseName, outputPath })
+})
+
+// 进度通过 mariadb-restore-progress 事件推送
+export const ipcRestoreMariadbDatabase = ipcLogFunc('导入 MariaDB 数据库', async (
+    environmentId: string,
+    serviceData: ServiceData,
+    databaseName: string,
+    sqlPath: string
+): Promise<IPCResult<{ database: string; sqlPath: string; size: number }>> => {
+    return invokeCommand('restore_mariadb_database', { environmentId, serviceData, databaseName, sqlPath })
+})
+
+export const ipcChangeMariadbRootPassword = ipcLogFunc('修改 MariaDB root 密码', async (
+    environmentId: string,
+    serviceData: ServiceData,
+    newPassword: string
+): Promise<IPCResult<undefined>> => {
+    return invokeCommand('change_mariadb_root_password', { environmentId, serviceData, newPassword })
+})
+
+// 不包含 root、系统账号与角色
+export const ipcExportMariadbUsersSql = ipcLogFunc('导出 MariaDB 用户权限', async (
+    environmentId: string,
+    serviceData: ServiceData,
+    outputPath: string
+): Promise<IPCResult<{ outputPath: string; size: number; users: string[] }>> => {
+    return invokeCommand('export_mariadb_users_sql', { environmentId, serviceData, outputPath })
+})
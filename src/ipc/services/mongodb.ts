@@ -2,6 +2,7 @@ import { ServiceData } from '@/types'
 import { invokeCommand } from '@/lib/tauri-api'
 import { ipcLogFunc } from '@/utils/logger'
 import { IPCResult } from '@/types/ipc'
+import { DatabaseInitializationCheck, MongoDBMetadata } from '@/types/service'
 import { listen, UnlistenFn } from '@tauri-apps/api/event'
 
 export const ipcGetMongoConfig = ipcLogFunc('获取 MongoDB 配置', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{ content: string }>> => {
@@ -35,11 +36,12 @@ export const ipcInitializeMongoDB = ipcLogFunc('初始化 MongoDB', async (
     port: string;
     bindIp: string;
     replicaSetInitialized: boolean
+    metadata: MongoDBMetadata
 }>> => {
     return invokeCommand('initialize_mongodb', { environmentId, serviceData, adminUsername, adminPassword, port, bindIp, enableReplicaSet, reset })
 })
 
-export const ipcCheckMongoDBInitialized = ipcLogFunc('检查 MongoDB 是否已初始化', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{ initialized: boolean }>> => {
+export const ipcCheckMongoDBInitialized = ipcLogFunc('检查 MongoDB 是否已初始化', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<DatabaseInitializationCheck>> => {
     return invokeCommand('check_mongodb_initialized', { environmentId, serviceData })
 })
 
@@ -51,6 +53,48 @@ export const ipcListMongoDBCollections = ipcLogFunc('列出 MongoDB 集合', asy
     return invokeCommand('list_mongodb_collections', { environmentId, serviceData, databaseName })
 })
 
+export const ipcCreateMongoDBCollection = ipcLogFunc('创建 MongoDB 集合', async (environmentId: string, serviceData: ServiceData, databaseName: string, collectionName: string): Promise<IPCResult<{ database: string, collection: string }>> => {
+    return invokeCommand('create_mongodb_collection', { environmentId, serviceData, databaseName, collectionName })
+})
+
+export const ipcDropMongoDBCollection = ipcLogFunc('删除 MongoDB 集合', async (environmentId: string, serviceData: ServiceData, databaseName: string, collectionName: string): Promise<IPCResult<{ database: string, collection: string }>> => {
+    return invokeCommand('drop_mongodb_collection', { environmentId, serviceData, databaseName, collectionName })
+})
+
+export const ipcGetMongoDBCollectionStats = ipcLogFunc('获取 MongoDB 集合统计信息', async (environmentId: string, serviceData: ServiceData, databaseName: string, collectionName: string): Promise<IPCResult<{
+    database: string,
+    collection: string,
+    count: number,
+    size: number,
+    avgObjSize: number,
+    storageSize: number,
+    totalIndexSize: number,
+    totalSize: number,
+    nindexes: number,
+    indexSizes: Record<string, number>,
+    capped: boolean,
+}>> => {
+    return invokeCommand('get_mongodb_collection_stats', { environmentId, serviceData, databaseName, collectionName })
+})
+
+export const ipcListMongoDBIndexes = ipcLogFunc('列出 MongoDB 索引', async (environmentId: string, serviceData: ServiceData, databaseName: string, collectionName: string): Promise<IPCResult<{
+    indexes: Array<{ name: string, key: Record<string, number | string>, unique: boolean, sparse: boolean, expireAfterSeconds: number | null }>
+}>> => {
+    return invokeCommand('list_mongodb_indexes', { environmentId, serviceData, databaseName, collectionName })
+})
+
+export type MongoDBExplainVerbosity = 'queryPlanner' | 'executionStats' | 'allPlansExecution'
+
+// queryJson 为 find 的查询条件（Extended JSON），返回 MongoDB 原始的 explain 结果
+export const ipcExplainMongoDBQuery = ipcLogFunc('获取 MongoDB 查询计划', async (environmentId: string, serviceData: ServiceData, databaseName: string, collectionName: string, queryJson: string, verbosity: MongoDBExplainVerbosity): Promise<IPCResult<Record<string, any>>> => {
+    return invokeCommand('explain_mongodb_query', { environmentId, serviceData, databaseName, collectionName, queryJson, verbosity })
+})
+
+// keys 为对象或 [字段名, 方向] 数组；复合索引需要保证字段顺序时使用数组形式
+export const ipcCreateMongoDBIndex = ipcLogFunc('创建 MongoDB 索引', async (environmentId: string, serviceData: ServiceData, databaseName: string, collectionName: string, keys: Record<string, number | string> | Array<[string, number | string]>, options?: Record<string, unknown>): Promise<IPCResult<{ database: string, collection: string, name: string }>> => {
+    return invokeCommand('create_mongodb_index', { environmentId, serviceData, databaseName, collectionName, keys, options })
+})
+
 export const ipcCreateMongoDBDatabase = ipcLogFunc('创建 MongoDB 数据库', async (environmentId: string, serviceData: ServiceData, databaseName: string): Promise<IPCResult<{ database: string }>> => {
     return invokeCommand('create_mongodb_database', { environmentId, serviceData, databaseName })
 })
@@ -110,3 +154,44 @@ export async function ipcListenMongoDBInitProgress(
     });
 }
 
+
+export const ipcChangeMongoDBAdminPassword = ipcLogFunc('修改 MongoDB 管理员密码', async (environmentId: string, serviceData: ServiceData, newPassword: string): Promise<IPCResult<{ username: string }>> => {
+    return invokeCommand('change_mongodb_admin_password', { environmentId, serviceData, newPassword })
+})
+
+export const ipcRotateMongoDBKeyfile = ipcLogFunc('轮换 MongoDB keyfile', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{ keyfilePath: string, restarted: boolean }>> => {
+    return invokeCommand('rotate_mongodb_keyfile', { environmentId, serviceData })
+})
+
+export interface MongoDBReplicaMember {
+    name: string
+    state: string
+    health: boolean
+    /** 相对主节点的复制延迟（秒），没有主节点时为 null */
+    lagSeconds: number | null
+}
+
+export const ipcGetMongoDBReplicationStatus = ipcLogFunc('获取 MongoDB 副本集状态', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{ set: string, members: MongoDBReplicaMember[], ok: boolean }>> => {
+    return invokeCommand('get_mongodb_replication_status', { environmentId, serviceData })
+})
+
+export interface MongoDBBackupSchedule {
+    id: string
+    cronExpression: string
+    outputDir: string
+    command: string
+    createdAt: string
+    installed?: boolean
+}
+
+export const ipcCreateMongoDBBackupSchedule = ipcLogFunc('创建 MongoDB 定时备份', async (environmentId: string, serviceData: ServiceData, cronExpression: string, outputDir: string): Promise<IPCResult<{ schedule: MongoDBBackupSchedule }>> => {
+    return invokeCommand('create_mongodb_backup_schedule', { environmentId, serviceData, cronExpression, outputDir })
+})
+
+export const ipcDeleteMongoDBBackupSchedule = ipcLogFunc('删除 MongoDB 定时备份', async (environmentId: string, serviceData: ServiceData, scheduleId: string): Promise<IPCResult<void>> => {
+    return invokeCommand('delete_mongodb_backup_schedule', { environmentId, serviceData, scheduleId })
+})
+
+export const ipcListMongoDBBackupSchedules = ipcLogFunc('获取 MongoDB 定时备份列表', async (serviceData: ServiceData): Promise<IPCResult<{ schedules: MongoDBBackupSchedule[] }>> => {
+    return invokeCommand('list_mongodb_backup_schedules', { serviceData })
+})
@@ -1,7 +1,9 @@
 import { ServiceData } from "@/types/index";
 import { IPCResult } from "@/types/ipc";
+import { DatabaseInitializationCheck, MySQLMetadata } from "@/types/service";
 import { invokeCommand } from '@/lib/tauri-api'
 import { ipcLogFunc } from '../../utils/logger'
+import { listen, UnlistenFn } from '@tauri-apps/api/event'
 
 export const ipcGetMysqlVersions = ipcLogFunc('获取 MySQL 版本列表', async (): Promise<IPCResult> => {
     return invokeCommand('get_mysql_versions')
@@ -69,11 +71,23 @@ export const ipcInitializeMysql = ipcLogFunc('初始化 MySQL', async (
     rootPassword: string;
     port: string;
     bindAddress: string;
+    metadata: MySQLMetadata;
 }>> => {
     return invokeCommand('initialize_mysql', { environmentId, serviceData, rootPassword, port, bindAddress, reset })
 })
 
-export const ipcCheckMysqlInitialized = ipcLogFunc('检查 MySQL 是否已初始化', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{ initialized: boolean }>> => {
+/**
+ * 监听 MySQL 初始化进度事件
+ */
+export async function ipcListenMysqlInitProgress(
+    callback: (payload: { step: string; message: string }) => void
+): Promise<UnlistenFn> {
+    return await listen<{ step: string; message: string }>('mysql-init-progress', (event) => {
+        callback(event.payload);
+    });
+}
+
+export const ipcCheckMysqlInitialized = ipcLogFunc('检查 MySQL 是否已初始化', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<DatabaseInitializationCheck>> => {
     return invokeCommand('check_mysql_initialized', { environmentId, serviceData })
 })
 
@@ -97,7 +111,7 @@ export const ipcListMysqlUsers = ipcLogFunc('列出 MySQL 用户', async (enviro
     users: Array<{
         username: string
         host: string
-        grants: Array<{ database: string; privilege: string }>
+        grants: Array<{ database: string; privilege: string; privileges?: string[] }>
     }>
 }>> => {
     return invokeCommand('list_mysql_users', { environmentId, serviceData })
@@ -108,7 +122,7 @@ export const ipcCreateMysqlUser = ipcLogFunc('创建 MySQL 用户', async (
     serviceData: ServiceData,
     username: string,
     password: string,
-    grants: Array<{ database: string; privilege: string }>
+    grants: Array<{ database: string; privilege: string; privileges?: string[] }>
 ): Promise<IPCResult<{ username: string }>> => {
     return invokeCommand('create_mysql_user', { environmentId, serviceData, username, password, grants })
 })
@@ -125,7 +139,15 @@ export const ipcUpdateMysqlUserGrants = ipcLogFunc('更新 MySQL 用户权限',
     environmentId: string,
     serviceData: ServiceData,
     username: string,
-    grants: Array<{ database: string; privilege: string }>
+    grants: Array<{ database: string; privilege: string; privileges?: string[] }>
 ): Promise<IPCResult<{ username: string }>> => {
     return invokeCommand('update_mysql_user_grants', { environmentId, serviceData, username, grants })
 })
+
+export const ipcChangeMysqlRootPassword = ipcLogFunc('修改 MySQL root 密码', async (
+    environmentId: string,
+    serviceData: ServiceData,
+    newPassword: string
+): Promise<IPCResult<undefined>> => {
+    return invokeCommand('change_mysql_root_password', { environmentId, serviceData, newPassword })
+})
@@ -8,3 +8,99 @@ export const ipcGetNginxConfig = ipcLogFunc('获取 Nginx 配置', async (enviro
 }>> => {
     return invokeCommand(`get_nginx_config`, { environmentId, serviceData })
 })
+
+export type NginxBalanceMethod = 'roundRobin' | 'leastConn' | 'ipHash'
+
+export interface NginxUpstream {
+  name: string
+  servers: string[]
+  method: NginxBalanceMethod
+}
+
+export const ipcValidateNginxConfig = ipcLogFunc('检查 Nginx 配置', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{
+  configPath: string
+  output: string
+}>> => {
+    return invokeCommand('validate_nginx_config', { environmentId, serviceData })
+})
+
+export const ipcListNginxUpstreams = ipcLogFunc('获取 Nginx upstream 列表', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{
+  upstreams: NginxUpstream[]
+}>> => {
+    return invokeCommand('list_nginx_upstreams', { environmentId, serviceData })
+})
+
+export const ipcAddNginxUpstream = ipcLogFunc('添加 Nginx upstream', async (environmentId: string, serviceData: ServiceData, upstreamName: string, servers: string[], method: NginxBalanceMethod = 'roundRobin'): Promise<IPCResult<{
+  upstream: NginxUpstream
+  path: string
+}>> => {
+    return invokeCommand('add_nginx_upstream', { environmentId, serviceData, upstreamName, servers, method })
+})
+
+export const ipcRemoveNginxUpstream = ipcLogFunc('删除 Nginx upstream', async (environmentId: string, serviceData: ServiceData, upstreamName: string): Promise<IPCResult<void>> => {
+    return invokeCommand('remove_nginx_upstream', { environmentId, serviceData, upstreamName })
+})
+
+// worker_processes 取值：'auto' 或 { count: n }
+export type NginxWorkers = 'auto' | { count: number }
+
+export const ipcSetNginxWorkerProcesses = ipcLogFunc('设置 Nginx worker_processes', async (environmentId: string, serviceData: ServiceData, value: NginxWorkers): Promise<IPCResult<{
+  configPath: string
+  directive: string
+  value: string
+}>> => {
+    return invokeCommand('set_nginx_worker_processes', { environmentId, serviceData, value })
+})
+
+export const ipcSetNginxWorkerConnections = ipcLogFunc('设置 Nginx worker_connections', async (environmentId: string, serviceData: ServiceData, connections: number): Promise<IPCResult<{
+  configPath: string
+  directive: string
+  value: string
+}>> => {
+    return invokeCommand('set_nginx_worker_connections', { environmentId, serviceData, connections })
+})
+
+export const ipcWriteNginxPhpFastcgiConf = ipcLogFunc('生成 Nginx PHP FastCGI 配置', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{
+  path: string
+  fastcgiPass: string
+  phpVersion: string
+  include: string
+}>> => {
+    return invokeCommand('write_nginx_php_fastcgi_conf', { environmentId, serviceData })
+})
+
+export const ipcRotateNginxLogs = ipcLogFunc('轮转 Nginx 日志', async (environmentId: string, serviceData: ServiceData, compress: boolean = true, retainDays?: number): Promise<IPCResult<{
+  rotated: string[]
+  removed: string[]
+  failed: string[]
+  running: boolean
+  reopened: boolean
+}>> => {
+    return invokeCommand('rotate_nginx_logs', { environmentId, serviceData, compress, retainDays })
+})
+
+export const ipcGetNginxAccessLogStats = ipcLogFunc('统计 Nginx 访问日志', async (environmentId: string, serviceData: ServiceData, hours: number = 24): Promise<IPCResult<{
+  totalRequests: number
+  statusCounts: Record<string, number>
+  topPaths: Array<{ path: string, count: number }>
+  unparsed: number
+  logPaths: string[]
+}>> => {
+    return invokeCommand('get_nginx_access_log_stats', { environmentId, serviceData, hours })
+})
+
+export const ipcGetNginxActiveConnections = ipcLogFunc('获取 Nginx 实时连接数', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{
+  connections: {
+    active: number
+    accepts: number
+    handled: number
+    requests: number
+    reading: number
+    writing: number
+    waiting: number
+  }
+  statusUrl: string
+  configAdded: boolean
+}>> => {
+    return invokeCommand('get_nginx_active_connections', { environmentId, serviceData })
+})
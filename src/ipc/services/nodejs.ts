@@ -36,6 +36,18 @@ export const ipcSetPnpmHome = ipcLogFunc('设置 PNPM_HOME', async (environmentI
   return invokeCommand(`set_pnpm_home`, { environmentId, serviceData, pnpmHome })
 })
 
+export const ipcSetNodeOptions = ipcLogFunc('设置 NODE_OPTIONS', async (environmentId: string, serviceData: ServiceData, options: string[]): Promise<IPCResult<{ nodeOptions: string | null }>> => {
+  return invokeCommand(`set_node_options`, { environmentId, serviceData, options })
+})
+
+export const ipcClearNodeOptions = ipcLogFunc('清除 NODE_OPTIONS', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<undefined>> => {
+  return invokeCommand(`clear_node_options`, { environmentId, serviceData })
+})
+
+export const ipcGetCurrentNodeOptions = ipcLogFunc('获取当前 NODE_OPTIONS', async (): Promise<IPCResult<{ nodeOptions: string | null }>> => {
+  return invokeCommand(`get_current_node_options`)
+})
+
 export const ipcGetGlobalNpmPackages = ipcLogFunc('获取全局 npm 包', async (serviceData: ServiceData): Promise<IPCResult<{ packages: Array<{ name: string, version: string }> }>> => {
   return invokeCommand(`get_global_npm_packages`, { serviceData })
 })
@@ -43,3 +55,29 @@ export const ipcGetGlobalNpmPackages = ipcLogFunc('获取全局 npm 包', async
 export const ipcInstallGlobalNpmPackage = ipcLogFunc('安装全局 npm 包', async (serviceData: ServiceData, packageName: string): Promise<IPCResult> => {
   return invokeCommand(`install_global_npm_package`, { serviceData, package: packageName })
 })
+
+export const ipcGetNpmConfigValue = ipcLogFunc('获取 npm 配置项', async (serviceData: ServiceData, key: string): Promise<IPCResult<{ key: string, value: string | null }>> => {
+  return invokeCommand(`get_npm_config`, { serviceData, key })
+})
+
+export const ipcSetNpmConfigKey = ipcLogFunc('设置 npm 配置项', async (serviceData: ServiceData, key: string, value: string): Promise<IPCResult<{ key: string, value: string }>> => {
+  return invokeCommand(`set_npm_config_key`, { serviceData, key, value })
+})
+
+export const ipcListNpmConfig = ipcLogFunc('获取 npm 配置列表', async (serviceData: ServiceData): Promise<IPCResult<{ config: Record<string, unknown> }>> => {
+  return invokeCommand(`list_npm_config`, { serviceData })
+})
+
+export type NodeProcess = {
+  pid: number
+  workingDir: string
+  args: string
+  memoryKb: number
+  exe: string
+  version: string | null
+  environments: Array<{ environmentId: string, environmentName: string, serviceDataId: string }>
+}
+
+export const ipcGetNodeProcessList = ipcLogFunc('获取 Node.js 进程列表', async (): Promise<IPCResult<{ processes: NodeProcess[] }>> => {
+  return invokeCommand(`get_node_process_list`)
+})
@@ -0,0 +1,24 @@
+import { ServiceData } from "@/types/index";
+import { invokeCommand } from '@/lib/tauri-api'
+import { ipcLogFunc } from '../../utils/logger'
+import { IPCResult } from "@/types/ipc";
+
+export type OpensearchIndex = {
+    index: string
+    health: string
+    status: string
+    docsCount: number | null
+    storeSizeBytes: number | null
+}
+
+export const ipcListOpensearchIndices = ipcLogFunc('获取 OpenSearch 索引列表', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{
+    indices: OpensearchIndex[]
+}>> => {
+    return invokeCommand('list_opensearch_indices', { environmentId, serviceData })
+})
+
+export const ipcDeleteOpensearchIndex = ipcLogFunc('删除 OpenSearch 索引', async (environmentId: string, serviceData: ServiceData, index: string): Promise<IPCResult<{
+    index: string
+}>> => {
+    return invokeCommand('delete_opensearch_index', { environmentId, serviceData, index })
+})
@@ -67,6 +67,7 @@ export const ipcInitializePostgresql = ipcLogFunc('初始化 PostgreSQL', async
     dataPath: string;
     logPath: string;
     superPassword: string;
+    superuser: string;
     port: string;
     bindAddress: string;
 }>> => {
@@ -131,3 +132,24 @@ export const ipcUpdatePostgresqlRoleGrants = ipcLogFunc('更新 PostgreSQL 角
 ): Promise<IPCResult<{ roleName: string }>> => {
     return invokeCommand('update_postgresql_role_grants', { environmentId, serviceData, roleName, grants })
 })
+
+export type PostgresqlDumpFormat = 'plain' | 'custom' | 'directory' | 'tar'
+
+export const ipcDumpPostgresqlDatabase = ipcLogFunc('导出 PostgreSQL 数据库', async (
+    environmentId: string,
+    serviceData: ServiceData,
+    databaseName: string,
+    outputPath: string,
+    format: PostgresqlDumpFormat = 'custom'
+): Promise<IPCResult<{ database: string; outputPath: string; format: PostgresqlDumpFormat; size: number }>> => {
+    return invokeCommand('dump_postgresql_database', { environmentId, serviceData, databaseName, outputPath, format })
+})
+
+export const ipcRestorePostgresqlDatabase = ipcLogFunc('恢复 PostgreSQL 数据库', async (
+    environmentId: string,
+    serviceData: ServiceData,
+    databaseName: string,
+    backupPath: string
+): Promise<IPCResult<{ database: string; backupPath: string; format: PostgresqlDumpFormat }>> => {
+    return invokeCommand('restore_postgresql_database', { environmentId, serviceData, databaseName, backupPath })
+})
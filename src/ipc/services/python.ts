@@ -45,3 +45,28 @@ export const ipcRemovePythonVenv = ipcLogFunc('删除 venv', async (environmentI
 export const ipcOpenPythonVenvTerminal = ipcLogFunc('打开终端并激活 venv', async (environmentId: string, serviceData: ServiceData, venvName: string): Promise<IPCResult> => {
     return invokeCommand(`open_python_venv_terminal`, { environmentId, serviceData, venvName })
 })
+
+export const ipcGetPythonInfo = ipcLogFunc('获取 Python 信息', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{
+  pythonPath: string
+  pythonVersion: string | null
+  pipVersion: string | null
+  executable: string | null
+  opensslVersion: string | null
+  venvCount: number
+}>> => {
+    return invokeCommand(`get_python_info`, { environmentId, serviceData })
+})
+
+export const ipcUpgradePythonPip = ipcLogFunc('升级 pip', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{
+  pipVersion: string | null
+  output: string
+}>> => {
+    return invokeCommand(`upgrade_python_pip`, { environmentId, serviceData })
+})
+
+export const ipcUpgradePythonPipInVenv = ipcLogFunc('升级 venv 中的 pip', async (environmentId: string, serviceData: ServiceData, venvName: string): Promise<IPCResult<{
+  pipVersion: string | null
+  output: string
+}>> => {
+    return invokeCommand(`upgrade_python_pip_in_venv`, { environmentId, serviceData, venvName })
+})
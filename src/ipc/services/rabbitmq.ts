@@ -0,0 +1,36 @@
+import { ServiceData } from "@/types/index";
+import { invokeCommand } from '@/lib/tauri-api'
+import { ipcLogFunc } from '../../utils/logger'
+import { IPCResult } from "@/types/ipc";
+
+export type RabbitmqQueue = {
+    name: string
+    vhost: string
+    durable: boolean
+    autoDelete: boolean
+    state: string | null
+    messages: number
+    messagesReady: number
+    messagesUnacknowledged: number
+    consumers: number
+}
+
+export const ipcEnableRabbitmqManagement = ipcLogFunc('启用 RabbitMQ 管理插件', async (environmentId: string, serviceData: ServiceData): Promise<IPCResult<{
+    managementUrl: string
+    applied: boolean
+}>> => {
+    return invokeCommand('enable_rabbitmq_management', { environmentId, serviceData })
+})
+
+export const ipcListRabbitmqQueues = ipcLogFunc('获取 RabbitMQ 队列列表', async (environmentId: string, serviceData: ServiceData, vhost?: string): Promise<IPCResult<{
+    queues: RabbitmqQueue[]
+}>> => {
+    return invokeCommand('list_rabbitmq_queues', { environmentId, serviceData, vhost })
+})
+
+export const ipcDeleteRabbitmqQueue = ipcLogFunc('删除 RabbitMQ 队列', async (environmentId: string, serviceData: ServiceData, vhost: string, queue: string): Promise<IPCResult<{
+    vhost: string
+    queue: string
+}>> => {
+    return invokeCommand('delete_rabbitmq_queue', { environmentId, serviceData, vhost, queue })
+})
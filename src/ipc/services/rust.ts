@@ -40,3 +40,11 @@ export const ipcGetRustInfo = ipcLogFunc('获取 Rust 信息', async (serviceDat
 export const ipcSetCargoHome = ipcLogFunc('设置 CARGO_HOME', async (environmentId: string, serviceData: ServiceData, cargoHome: string): Promise<IPCResult> => {
     return invokeCommand('set_cargo_home', { environmentId, serviceData, cargoHome })
 })
+
+// mirror 可以是预设名称（crates-io、rsproxy、tuna、ustc）或镜像地址
+export const ipcSetCargoRegistryMirror = ipcLogFunc('设置 Cargo 镜像', async (environmentId: string, serviceData: ServiceData, mirror: string): Promise<IPCResult<{
+    configPath: string
+    registry: string
+}>> => {
+    return invokeCommand('set_cargo_registry_mirror', { environmentId, serviceData, mirror })
+})
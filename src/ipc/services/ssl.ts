@@ -137,3 +137,31 @@ export const ipcCheckCAInstalled = ipcLogFunc(
         return invokeCommand('check_ca_installed', { environmentId })
     }
 )
+
+/**
+ * 将 CA 证书安装到系统信任存储（macOS / Linux 需要管理员密码）
+ */
+export const ipcInstallCAToSystemTrust = ipcLogFunc(
+    '安装 CA 证书到系统信任存储',
+    async (
+        environmentId: string,
+        serviceDataId: string,
+        password?: string
+    ): Promise<IPCResult<{ installed: boolean; certPath: string }>> => {
+        return invokeCommand('install_ca_to_system_trust', { environmentId, serviceDataId, password })
+    }
+)
+
+/**
+ * 从系统信任存储中移除 CA 证书
+ */
+export const ipcUninstallCAFromSystemTrust = ipcLogFunc(
+    '从系统信任存储移除 CA 证书',
+    async (
+        environmentId: string,
+        serviceDataId: string,
+        password?: string
+    ): Promise<IPCResult<{ installed: boolean }>> => {
+        return invokeCommand('uninstall_ca_from_system_trust', { environmentId, serviceDataId, password })
+    }
+)
@@ -0,0 +1,35 @@
+import { ServiceData } from "@/types/index";
+import { invokeCommand } from '@/lib/tauri-api'
+import { ipcLogFunc } from '../../utils/logger'
+import { IPCResult } from "@/types/ipc";
+
+export const ipcListVaultSecrets = ipcLogFunc('获取 Vault 密钥列表', async (environmentId: string, serviceData: ServiceData, path: string): Promise<IPCResult<{
+    path: string
+    keys: string[]
+}>> => {
+    return invokeCommand('list_vault_secrets', { environmentId, serviceData, path })
+})
+
+export const ipcReadVaultSecret = ipcLogFunc('读取 Vault 密钥', async (environmentId: string, serviceData: ServiceData, path: string, key: string): Promise<IPCResult<{
+    path: string
+    key: string
+    value: unknown
+}>> => {
+    return invokeCommand('read_vault_secret', { environmentId, serviceData, path, key })
+})
+
+export const ipcWriteVaultSecret = ipcLogFunc('写入 Vault 密钥', async (environmentId: string, serviceData: ServiceData, path: string, key: string, value: string): Promise<IPCResult<{
+    path: string
+    key: string
+    version: number | null
+}>> => {
+    return invokeCommand('write_vault_secret', { environmentId, serviceData, path, key, value })
+})
+
+export const ipcDeleteVaultSecret = ipcLogFunc('删除 Vault 密钥', async (environmentId: string, serviceData: ServiceData, path: string, key: string): Promise<IPCResult<{
+    path: string
+    key: string
+    removedPath: boolean
+}>> => {
+    return invokeCommand('delete_vault_secret', { environmentId, serviceData, path, key })
+})
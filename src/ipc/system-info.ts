@@ -1,4 +1,5 @@
 import { IPCResult } from "@/types/ipc"
+import { ServiceType } from "@/types/index"
 import { invokeCommand } from '@/lib/tauri-api'
 import { ipcLogFunc } from '../utils/logger'
 import { closeTooManyLogs } from "@/utils/const"
@@ -52,3 +53,105 @@ export const ipcQuitApp = ipcLogFunc('退出应用程序', async (): Promise<IPC
 export const ipcOpenSystemEnvSettings = ipcLogFunc('打开系统环境变量设置', async (): Promise<IPCResult> => {
     return invokeCommand('open_system_env_settings');
 })
+
+export const ipcGetDiskUsage = ipcLogFunc('获取磁盘占用', async (): Promise<IPCResult<{
+    services: Record<string, {
+        size_bytes: number,
+        versions: Array<{ version: string, size_bytes: number }>,
+    }>,
+    environments: Record<string, number>,
+}>> => {
+    return invokeCommand('get_disk_usage')
+})
+
+export interface ManagedProcessStat {
+    environmentId: string,
+    serviceId: string,
+    serviceName: string,
+    serviceType: string,
+    version: string,
+    pid: number,
+    pids: number[],
+    cpuUsage: number,
+    memoryBytes: number,
+    uptimeSecs: number,
+    ports: number[],
+}
+
+// 环境 ID -> 服务类型 -> 进程统计
+export const ipcGetManagedProcessStats = ipcLogFunc('获取服务进程资源占用', async (): Promise<IPCResult<
+    Record<string, Record<string, ManagedProcessStat[]>>
+>> => {
+    return invokeCommand('get_managed_process_stats')
+}, closeTooManyLogs)
+
+export interface SystemDependency {
+    tool: string,
+    found: boolean,
+    path: string | null,
+}
+
+// 检查服务所需的系统工具（tar、lsof 等）是否可用
+export const ipcCheckSystemDependencies = ipcLogFunc('检查系统依赖', async (serviceType: ServiceType): Promise<IPCResult<SystemDependency[]>> => {
+    return invokeCommand('check_system_dependencies', { serviceType })
+})
+
+export type ShellConfigIssueKind = 'missingBlock' | 'duplicateBlockStart' | 'duplicateBlock' | 'missingBlockEnd' | 'orphanBlockEnd' | 'duplicateLine' | 'unknownLine'
+
+export interface ShellConfigDiagnostic {
+    path: string,
+    shellType: string,
+    exists: boolean,
+    issues: Array<{ kind: ShellConfigIssueKind, line: number | null, content: string | null }>,
+    backupPath: string | null,
+}
+
+// repair 为 true 时备份并按当前激活状态重建环境块，afterRepair 为修复后重新检查的结果
+export const ipcVerifyAndRepairShellConfig = ipcLogFunc('检查并修复 Shell 配置文件', async (repair: boolean): Promise<IPCResult<{
+    healthy: boolean,
+    repaired: boolean,
+    files: ShellConfigDiagnostic[],
+    afterRepair: ShellConfigDiagnostic[] | null,
+}>> => {
+    return invokeCommand('verify_and_repair_shell_config', { repair })
+})
+
+export type EnvBlockIntegrityStatus = 'ok' | 'missingStart' | 'missingEnd' | 'duplicated' | 'unpaired'
+
+export interface EnvBlockIntegrity {
+    path: string,
+    status: EnvBlockIntegrityStatus,
+    details: string,
+}
+
+// repair 为 true 时先修复，repaired 为修复前存在问题的文件
+export const ipcVerifyShellIntegrity = ipcLogFunc('检查 Shell 配置文件环境块', async (repair: boolean): Promise<IPCResult<{ files: EnvBlockIntegrity[], repaired: EnvBlockIntegrity[] }>> => {
+    return invokeCommand('verify_shell_integrity', { repair })
+})
+
+export interface ShellConfigBackups {
+    configPath: string,
+    backups: Array<{
+        tag: string,
+        timestamp: number,
+        sizeBytes: number,
+        automatic: boolean,
+    }>,
+}
+
+export const ipcBackupAllShellConfigs = ipcLogFunc('备份 Shell 配置文件', async (tag: string): Promise<IPCResult<{ backups: string[] }>> => {
+    return invokeCommand('backup_all_configs', { tag })
+})
+
+export const ipcRestoreAllShellConfigs = ipcLogFunc('恢复 Shell 配置文件', async (tag: string): Promise<IPCResult<{ restored: string[] }>> => {
+    return invokeCommand('restore_all_configs', { tag })
+})
+
+// 恢复后会重建 Envis 环境块并重新写入已激活环境
+export const ipcRestoreShellConfigBackup = ipcLogFunc('恢复单个 Shell 配置文件', async (configPath: string, tag: string): Promise<IPCResult<{ restored: string }>> => {
+    return invokeCommand('restore_shell_config_backup', { configPath, tag })
+})
+
+export const ipcListShellConfigBackups = ipcLogFunc('获取 Shell 配置备份列表', async (): Promise<IPCResult<ShellConfigBackups[]>> => {
+    return invokeCommand('list_available_backups')
+})
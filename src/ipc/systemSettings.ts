@@ -3,7 +3,14 @@ import { invokeCommand } from '@/lib/tauri-api'
 import { ipcLogFunc } from '../utils/logger'
 import { IPCResult } from "@/types/ipc"
 
-export const ipcGetSystemSettings = ipcLogFunc('获取系统设置', async (): Promise<IPCResult<{appConfig: SystemSettings}>> => {
+export const ipcGetSystemSettings = ipcLogFunc('获取系统设置', async (): Promise<IPCResult<{
+    appConfig: SystemSettings,
+    resolvedEnvsFolder: string,
+    resolvedServicesFolder: string,
+    appVersion: string,
+    configFilePath: string,
+    availableDiskSpaceGb: number | null,
+}>> => {
     return invokeCommand('get_app_config')
 })
 
@@ -11,6 +18,16 @@ export const ipcUpdateSystemSettings = ipcLogFunc('更新系统设置', async (s
     return invokeCommand('set_app_config', { appConfig: systemSettings })
 })
 
+export const ipcTestProxyConnection = ipcLogFunc('测试代理连接', async (targetUrl?: string): Promise<IPCResult<{
+    success: boolean,
+    url: string,
+    status: number | null,
+    latencyMs: number | null,
+    error: string | null,
+}>> => {
+    return invokeCommand('test_proxy_connection', { targetUrl })
+})
+
 export const ipcOpenAppConfigFolder = ipcLogFunc('打开应用配置文件夹', async (): Promise<IPCResult> => {
     return invokeCommand('open_app_config_folder')
 })
@@ -111,6 +111,30 @@ export function AddServiceMenu({ buttonType = "icon" }: {
             isLoading: false,
             availableVersions: [],
         },
+        [ServiceType.Php]: {
+            isLoading: false,
+            availableVersions: [],
+        },
+        [ServiceType.Vault]: {
+            isLoading: false,
+            availableVersions: [],
+        },
+        [ServiceType.Opensearch]: {
+            isLoading: false,
+            availableVersions: [],
+        },
+        [ServiceType.Kafka]: {
+            isLoading: false,
+            availableVersions: [],
+        },
+        [ServiceType.Rabbitmq]: {
+            isLoading: false,
+            availableVersions: [],
+        },
+        [ServiceType.Compose]: {
+            isLoading: false,
+            availableVersions: [],
+        },
     })
 
     // 用于下载时的服务数据缓存，包含对话框状态
@@ -89,6 +89,12 @@ const SERVICE_TYPE_ICONS: Record<ServiceType, string> = {
   [ServiceType.Dnsmasq]: 'linux', // 使用 linux 作为 Dnsmasq 图标
   [ServiceType.Java]: 'java', // 使用 java 作为 Java 图标
   [ServiceType.Rust]: 'rust', // 使用 rust 作为 Rust 图标
+  [ServiceType.Php]: 'php',
+  [ServiceType.Vault]: 'vault',
+  [ServiceType.Opensearch]: 'opensearch',
+  [ServiceType.Kafka]: 'apachekafka',
+  [ServiceType.Rabbitmq]: 'rabbitmq',
+  [ServiceType.Compose]: 'docker',
 }
 
 export function SortableServiceItem({
@@ -503,8 +503,8 @@ export function MariaDBService({ serviceData }: MariaDBServiceProps) {
       if (result.success && result.data) {
         const data = result.data
         const newMetadata: MariaDBMetadata = { ...(serviceData.metadata || {}) }
-        newMetadata['MARIADB_CONFIG'] = data.configPath
-        newMetadata['MARIADB_ROOT_PASSWORD'] = data.rootPassword
+        // 后端已保存配置、端口、凭据与初始化标记，这里合并以免覆盖
+        Object.assign(newMetadata, data.metadata)
         await updateServiceData({
           environmentId: selectedEnvironmentId,
           serviceId: serviceData.id,
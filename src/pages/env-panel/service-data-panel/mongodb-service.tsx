@@ -634,10 +634,8 @@ export function MongoDBService({ serviceData }: MongoDBServiceProps) {
           }
         }
 
-        newMetadata['MONGODB_CONFIG'] = data.configPath
-        newMetadata['MONGODB_KEYFILE_PATH'] = data.keyfilePath
-        newMetadata['MONGODB_ADMIN_USERNAME'] = data.adminUsername
-        newMetadata['MONGODB_ADMIN_PASSWORD'] = data.adminPassword
+        // 后端已保存配置、端口、凭据与初始化标记，这里合并以免覆盖
+        Object.assign(newMetadata, data.metadata)
         const updatedServiceData = await updateServiceData({
           environmentId: selectedEnvironmentId,
           serviceId: serviceData.id,
@@ -507,8 +507,8 @@ export function MySQLService({ serviceData }: MySQLServiceProps) {
       if (result.success && result.data) {
         const data = result.data
         const newMetadata: MySQLMetadata = { ...(serviceData.metadata || {}) }
-        newMetadata['MYSQL_CONFIG'] = data.configPath
-        newMetadata['MYSQL_ROOT_PASSWORD'] = data.rootPassword
+        // 后端已保存配置、端口、凭据与初始化标记，这里合并以免覆盖
+        Object.assign(newMetadata, data.metadata)
         await updateServiceData({
           environmentId: selectedEnvironmentId,
           serviceId: serviceData.id,
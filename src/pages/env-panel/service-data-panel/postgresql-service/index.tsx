@@ -72,6 +72,8 @@ export function PostgreSQLService({ serviceData }: PostgreSQLServiceProps) {
   })
 
   const [showPassword, setShowPassword] = useState(false)
+  // 兼容旧版本保存的 POSTGRESQL_SUPER_PASSWORD
+  const superPassword = serviceData.metadata?.POSTGRESQL_PASSWORD || serviceData.metadata?.POSTGRESQL_SUPER_PASSWORD
   const [postgresqlConfig, setPostgresqlConfig] = useState<PostgreSQLConfig | null>(null)
 
   const [isStarting, setIsStarting] = useState(false)
@@ -499,7 +501,12 @@ export function PostgreSQLService({ serviceData }: PostgreSQLServiceProps) {
         const data = result.data
         const newMetadata: PostgreSQLMetadata = { ...(serviceData.metadata || {}) }
         newMetadata.POSTGRESQL_CONFIG = data.configPath
-        newMetadata.POSTGRESQL_SUPER_PASSWORD = data.superPassword
+        newMetadata.POSTGRESQL_DATA = data.dataPath
+        newMetadata.POSTGRESQL_LOG = data.logPath
+        newMetadata.POSTGRESQL_PORT = data.port
+        newMetadata.POSTGRESQL_SUPERUSER = data.superuser
+        newMetadata.POSTGRESQL_PASSWORD = data.superPassword
+        delete newMetadata.POSTGRESQL_SUPER_PASSWORD
 
         await updateServiceData({
           environmentId: selectedEnvironmentId,
@@ -1416,13 +1423,13 @@ export function PostgreSQLService({ serviceData }: PostgreSQLServiceProps) {
                 </div>
                 <div className="flex items-center gap-1.5">
                   <span className="text-[10px] text-muted-foreground font-mono">
-                    {serviceData.metadata?.POSTGRESQL_SUPER_PASSWORD
+                    {superPassword
                       ? showPassword
-                        ? serviceData.metadata.POSTGRESQL_SUPER_PASSWORD
+                        ? superPassword
                         : '••••••••'
                       : '—'}
                   </span>
-                  {serviceData.metadata?.POSTGRESQL_SUPER_PASSWORD && (
+                  {superPassword && (
                     <Button
                       size="sm"
                       variant="ghost"
@@ -24,6 +24,9 @@ function getServiceProcessNames(type: ServiceType): string[] {
     case ServiceType.Mysql: return ['mysqld']
     case ServiceType.Postgresql: return ['postgres']
     case ServiceType.Dnsmasq: return ['dnsmasq']
+    case ServiceType.Php: return ['php-fpm', 'php-cgi']
+    case ServiceType.Vault: return ['vault']
+    // OpenSearch、Kafka 以 java 进程运行，RabbitMQ 以 Erlang 虚拟机运行，无法与其他应用区分
     default: return []
   }
 }
@@ -18,8 +18,19 @@ export type SystemSettings = {
   terminalTool?: string // 终端程序路径或程序名（为空时使用系统默认）
   showEnvironmentNameOnTerminalOpen?: boolean // 打开终端时显示环境名称
   showServiceInfoOnTerminalOpen?: boolean // 打开终端时显示服务信息
+  autoStartServices?: boolean // 启动应用时自动启动已激活环境中的服务
+  serviceSupervisionIntervalSecs?: number // 自动重启服务的健康检查间隔（秒）
+  serviceAutoRestartMaxRetries?: number // 服务崩溃后的最大自动重启次数
+  windowsUseUserEnvironment?: boolean // Windows 下同时写入用户环境变量（注册表），供 GUI 程序读取
+  shellConfigBackupRetention?: number // 每个 Shell 配置文件保留的自动备份数量
+  proxyMode?: ProxyMode // 下载代理模式
+  proxyUrl?: string // 手动代理地址（http / https / socks5 / socks5h，可包含 user:password@）
+  noProxy?: string[] // 不使用代理的主机列表
+  maxConcurrentDownloads?: number // 同时进行的最大下载任务数
 }
 
+export type ProxyMode = 'none' | 'system' | 'manual';
+
 export enum EnvironmentStatus {
   Unknown = "unknown",
   Active = "active",
@@ -31,6 +42,7 @@ export enum ServiceStatus {
   Error = "error",
   Running = "running",
   Stopped = "stopped",
+  Degraded = "degraded",
 }
 
 export enum DownloadStatus {
@@ -43,6 +55,8 @@ export enum DownloadStatus {
   Installed = "installed",
   Failed = "failed",
   Cancelled = "cancelled",
+  Paused = "paused",
+  Resuming = "resuming",
 }
 
 export enum ServiceDataStatus {
@@ -67,6 +81,12 @@ export enum ServiceType {
   Host = "host",
   SSL = "ssl",
   Dnsmasq = "dnsmasq",
+  Php = "php",
+  Vault = "vault",
+  Opensearch = "opensearch",
+  Kafka = "kafka",
+  Rabbitmq = "rabbitmq",
+  Compose = "compose",
 }
 
 // 服务类型标签
@@ -86,6 +106,12 @@ export const serviceTypeNames: Record<ServiceType, string> = {
   [ServiceType.Host]: 'Hosts 管理',
   [ServiceType.SSL]: 'SSL 证书',
   [ServiceType.Dnsmasq]: 'Dnsmasq',
+  [ServiceType.Php]: 'PHP',
+  [ServiceType.Vault]: 'Vault',
+  [ServiceType.Opensearch]: 'OpenSearch',
+  [ServiceType.Kafka]: 'Kafka',
+  [ServiceType.Rabbitmq]: 'RabbitMQ',
+  [ServiceType.Compose]: 'Docker Compose',
 }
 
 // 服务分类配置
@@ -102,7 +128,7 @@ export const serviceCategories = {
     rust: 'Rust',
     nasm: 'NASM',
     // go: 'Go',
-    // php: 'PHP'
+    php: 'PHP',
   },
   'databases': {
     mysql: 'MySQL',
@@ -110,10 +136,15 @@ export const serviceCategories = {
     redis: 'Redis',
     mongodb: 'MongoDB',
     postgresql: 'PostgreSQL',
+    opensearch: 'OpenSearch',
     // redis: 'Redis'
   },
   'servers': {
     nginx: 'Nginx',
+    vault: 'Vault',
+    kafka: 'Kafka',
+    rabbitmq: 'RabbitMQ',
+    compose: 'Docker Compose',
     // dnsmasq: 'Dnsmasq',
   },
 }
@@ -131,12 +162,18 @@ export const NeedDownloadServices: ServiceType[] = [
   ServiceType.Rust,
   ServiceType.Nasm,
   ServiceType.Dnsmasq,
+  ServiceType.Php,
+  ServiceType.Vault,
+  ServiceType.Opensearch,
+  ServiceType.Kafka,
+  ServiceType.Rabbitmq,
 ];
 
 export const NoNeedDownloadServices: ServiceType[] = [
   ServiceType.Custom,
   ServiceType.Host,
   ServiceType.SSL,
+  ServiceType.Compose,
 ];
 // 一般用这个
 export const CanRunServices: ServiceType[] = [
@@ -147,6 +184,12 @@ export const CanRunServices: ServiceType[] = [
   ServiceType.Mysql,
   ServiceType.Postgresql,
   ServiceType.Dnsmasq,
+  ServiceType.Php,
+  ServiceType.Vault,
+  ServiceType.Opensearch,
+  ServiceType.Kafka,
+  ServiceType.Rabbitmq,
+  ServiceType.Compose,
 ];
 
 export const CannotRunServices: ServiceType[] = [
@@ -164,6 +207,7 @@ export const NoNeedVersionServices: ServiceType[] = [
   ServiceType.Host,
   ServiceType.SSL,
   ServiceType.Custom,
+  ServiceType.Compose,
 ]
 
 export type Environment = {
@@ -12,6 +12,8 @@ export interface MongoDBMetadata {
     "MONGODB_KEYFILE_PATH"?: string
     "MONGODB_ADMIN_USERNAME"?: string
     "MONGODB_ADMIN_PASSWORD"?: string
+    "MONGODB_PORT"?: string
+    "MONGODB_INITIALIZED"?: boolean
 }
 
 export interface RedisConfig {
@@ -34,6 +36,8 @@ export interface RedisMetadata {
 export interface MariaDBMetadata {
     "MARIADB_CONFIG"?: string
     "MARIADB_ROOT_PASSWORD"?: string
+    "MARIADB_PORT"?: string
+    "MARIADB_INITIALIZED"?: boolean
 }
 
 export interface MariaDBGrant {
@@ -50,6 +54,18 @@ export interface MariaDBUser {
 export interface MySQLMetadata {
     "MYSQL_CONFIG"?: string
     "MYSQL_ROOT_PASSWORD"?: string
+    "MYSQL_PORT"?: string
+    "MYSQL_INITIALIZED"?: boolean
+}
+
+// MySQL / MariaDB / MongoDB 初始化状态
+export type DatabaseInitializationState = 'initialized' | 'partially_initialized' | 'not_initialized'
+
+export interface DatabaseInitializationCheck {
+    initialized: boolean                    // 配置文件与数据目录是否存在
+    state: DatabaseInitializationState
+    reason: string
+    credentialsVerified: boolean            // 服务运行中且已用保存的凭据登录成功
 }
 
 export interface MySQLGrant {
@@ -65,6 +81,12 @@ export interface MySQLUser {
 
 export interface PostgreSQLMetadata {
     "POSTGRESQL_CONFIG"?: string
+    "POSTGRESQL_DATA"?: string
+    "POSTGRESQL_LOG"?: string
+    "POSTGRESQL_PORT"?: string
+    "POSTGRESQL_SUPERUSER"?: string
+    "POSTGRESQL_PASSWORD"?: string
+    /** 旧版本保存的超级用户密码 */
     "POSTGRESQL_SUPER_PASSWORD"?: string
 }
 